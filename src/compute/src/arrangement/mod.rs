@@ -10,3 +10,25 @@
 //! Types related to the arrangement and management of collections.
 
 pub mod manager;
+pub mod spill;
+
+use differential_dataflow::operators::arrange::TraceAgent;
+use differential_dataflow::trace::implementations::ord::{OrdKeyBatch, OrdValBatch};
+use differential_dataflow::trace::implementations::spine_fueled::Spine;
+
+use mz_dataflow_types::DataflowError;
+use mz_repr::{Diff, Row, Timestamp};
+
+use crate::arrangement::spill::SpillBatch;
+
+// Arrangements hold their batches in memory until the worker exceeds its memory
+// budget, after which new batches are spilled to disk. See [`spill`].
+pub type RowSpine<K, V, T, R, O = usize> =
+    Spine<K, V, T, R, SpillBatch<K, T, OrdValBatch<K, V, T, R, O>>>;
+pub type ErrSpine<K, T, R, O = usize> =
+    Spine<K, (), T, R, SpillBatch<K, T, OrdKeyBatch<K, T, R, O>>>;
+
+pub type TraceRowHandle<K, V, T, R> = TraceAgent<RowSpine<K, V, T, R>>;
+pub type TraceErrHandle<K, T, R> = TraceAgent<ErrSpine<K, T, R>>;
+pub type KeysValsHandle = TraceRowHandle<Row, Row, Timestamp, Diff>;
+pub type ErrsHandle = TraceErrHandle<DataflowError, Timestamp, Diff>;
@@ -0,0 +1,1312 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Arrangement batches that move to local disk under memory pressure.
+//!
+//! A [`SpillBatch`] wraps an in-memory batch implementation. Each worker
+//! tracks the size of the batches it holds in memory, including the heap
+//! allocations of their keys and values, and once that exceeds the worker's
+//! share of the replica's memory budget, newly built batches are written to an
+//! anonymous file in the spill directory instead of being retained in memory.
+//!
+//! A spilled batch is written as a sequence of pages, each holding the updates
+//! of a run of whole keys, and keeps the first key of each page in memory.
+//! Cursors read in only the pages they visit, using those keys to skip
+//! directly to the page that holds a sought key, and merges involving a
+//! spilled batch read their inputs and write their output a page at a time.
+//!
+//! Spilling is disabled unless a budget is configured with [`configure`].
+//!
+//! Failing to write to the spill directory is not fatal: batches that cannot
+//! be written out stay in memory, and merges that cannot write their output
+//! start over in memory. Failing to read a spilled page back in loses the
+//! updates it held, and the cursor and merge interfaces that read pages cannot
+//! report errors, so the page is read as empty instead and the error is
+//! recorded for the worker. [`read_error`] reports it, and peeks fail with it
+//! from then on.
+
+use std::cell::{Ref, RefCell};
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::Write;
+use std::marker::PhantomData;
+use std::mem;
+use std::os::unix::fs::FileExt;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use differential_dataflow::consolidation::consolidate_updates;
+use differential_dataflow::difference::Semigroup;
+use differential_dataflow::lattice::Lattice;
+use differential_dataflow::trace::{
+    Batch, BatchReader, Batcher, Builder, Cursor, Description, Merger,
+};
+use fail::fail_point;
+use once_cell::unsync::OnceCell;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use timely::communication::message::RefOrMut;
+use timely::progress::frontier::AntichainRef;
+use timely::progress::Antichain;
+use tracing::{error, warn};
+
+use mz_dataflow_types::DataflowError;
+use mz_ore::cast::CastFrom;
+use mz_repr::Row;
+
+/// The smallest batch, in records, that is worth writing to disk.
+const MIN_SPILL_RECORDS: usize = 1024;
+
+/// The number of updates after which a page of a spilled batch is closed at
+/// the end of the current key.
+const PAGE_RECORDS: usize = 4096;
+
+/// Configuration for spilling arrangement batches to disk.
+#[derive(Clone, Debug)]
+pub struct SpillConfig {
+    /// The directory in which to create spill files.
+    pub directory: PathBuf,
+    /// The number of bytes of arrangement batches to hold in memory before
+    /// spilling new batches to disk.
+    pub memory_budget: usize,
+}
+
+/// Counts of the arrangement batches a worker currently has on disk.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SpillStatistics {
+    /// The number of spilled batches.
+    pub batches: usize,
+    /// The number of updates in spilled batches.
+    pub records: usize,
+    /// The size of the spill files, in bytes.
+    pub bytes: u64,
+}
+
+#[derive(Default)]
+struct SpillState {
+    config: Option<SpillConfig>,
+    /// The size of the batches and pages held in memory.
+    resident_bytes: usize,
+    statistics: SpillStatistics,
+    /// The error of the first spilled page that could not be read back in.
+    read_error: Option<String>,
+}
+
+thread_local! {
+    static STATE: RefCell<SpillState> = RefCell::new(SpillState::default());
+}
+
+/// Configures spilling for batches built on the current worker thread.
+///
+/// `None` disables spilling; batches already on disk remain there.
+pub fn configure(config: Option<SpillConfig>) {
+    STATE.with(|state| state.borrow_mut().config = config);
+}
+
+/// Reports the batches the current worker thread has on disk.
+pub fn statistics() -> SpillStatistics {
+    STATE.with(|state| state.borrow().statistics)
+}
+
+/// Reports the error of the first spilled page that the current worker thread
+/// failed to read back in, if any.
+///
+/// The updates of that page are missing from the arrangement that held it, so
+/// the worker's arrangements are no longer trustworthy once this returns an
+/// error.
+pub fn read_error() -> Option<String> {
+    STATE.with(|state| state.borrow().read_error.clone())
+}
+
+/// Records that a spilled page could not be read back in.
+fn record_read_error(e: &anyhow::Error) {
+    error!("failed to read spilled arrangement batch: {:#}", e);
+    STATE.with(|state| {
+        state
+            .borrow_mut()
+            .read_error
+            .get_or_insert_with(|| format!("failed to read spilled arrangement batch: {:#}", e));
+    });
+}
+
+/// Returns the spill directory, if spilling is enabled on the current worker
+/// thread.
+fn directory() -> Option<PathBuf> {
+    STATE.with(|state| {
+        let state = state.borrow();
+        state.config.as_ref().map(|config| config.directory.clone())
+    })
+}
+
+/// The number of bytes a key or value owns on the heap.
+///
+/// Batches count these allocations in addition to the inline size of their
+/// contents against the worker's memory budget.
+pub trait HeapSize {
+    /// Returns the number of bytes owned by `self` on the heap.
+    fn heap_size(&self) -> usize;
+}
+
+macro_rules! impl_heap_size_inline {
+    ($($ty:ty),*) => {
+        $(
+            impl HeapSize for $ty {
+                fn heap_size(&self) -> usize {
+                    0
+                }
+            }
+        )*
+    };
+}
+
+impl_heap_size_inline!(
+    (),
+    bool,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize
+);
+
+macro_rules! impl_heap_size_tuple {
+    ($($name:ident)+) => {
+        impl<$($name: HeapSize),+> HeapSize for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn heap_size(&self) -> usize {
+                let ($($name,)+) = self;
+                0 $(+ $name.heap_size())+
+            }
+        }
+    };
+}
+
+impl_heap_size_tuple!(A B);
+impl_heap_size_tuple!(A B C);
+impl_heap_size_tuple!(A B C D);
+impl_heap_size_tuple!(A B C D E);
+impl_heap_size_tuple!(A B C D E F);
+
+impl<T: HeapSize> HeapSize for Option<T> {
+    fn heap_size(&self) -> usize {
+        self.as_ref().map_or(0, HeapSize::heap_size)
+    }
+}
+
+impl<T: HeapSize> HeapSize for Vec<T> {
+    fn heap_size(&self) -> usize {
+        self.capacity() * mem::size_of::<T>() + self.iter().map(HeapSize::heap_size).sum::<usize>()
+    }
+}
+
+impl HeapSize for String {
+    fn heap_size(&self) -> usize {
+        self.capacity()
+    }
+}
+
+impl HeapSize for Row {
+    fn heap_size(&self) -> usize {
+        Row::heap_size(self)
+    }
+}
+
+impl HeapSize for DataflowError {
+    fn heap_size(&self) -> usize {
+        // Errors are rare, and their allocations are dominated by their
+        // messages, which they encode more or less verbatim.
+        bincode::serialized_size(self).map_or(0, usize::cast_from)
+    }
+}
+
+/// Returns the number of bytes `batch` occupies in memory.
+///
+/// Ordered batches store each distinct key and each distinct value of a key
+/// once, along with an offset into the next layer, and each update's time and
+/// difference.
+fn memory_size<K, V, T, R, B>(batch: &B) -> usize
+where
+    B: BatchReader<K, V, T, R>,
+    K: HeapSize,
+    V: HeapSize,
+{
+    let mut bytes = 0;
+    let mut cursor = batch.cursor();
+    while cursor.key_valid(batch) {
+        bytes += mem::size_of::<(K, usize)>() + cursor.key(batch).heap_size();
+        while cursor.val_valid(batch) {
+            bytes += mem::size_of::<(V, usize)>() + cursor.val(batch).heap_size();
+            cursor.map_times(batch, |_, _| bytes += mem::size_of::<(T, R)>());
+            cursor.step_val(batch);
+        }
+        cursor.step_key(batch);
+    }
+    bytes
+}
+
+/// Appends the updates of the cursor's current key to `updates`, leaving the
+/// cursor past the key's last value.
+fn collect_updates<K, V, T, R, C>(
+    cursor: &mut C,
+    storage: &C::Storage,
+    updates: &mut Vec<(V, T, R)>,
+) where
+    C: Cursor<K, V, T, R>,
+    V: Clone,
+    T: Clone,
+    R: Clone,
+{
+    while cursor.val_valid(storage) {
+        let val = cursor.val(storage);
+        cursor.map_times(storage, |time, diff| {
+            updates.push((val.clone(), time.clone(), diff.clone()))
+        });
+        cursor.step_val(storage);
+    }
+}
+
+/// An arrangement batch that is either held in memory or spilled to disk.
+pub struct SpillBatch<K, T, B> {
+    desc: Description<T>,
+    len: usize,
+    contents: Contents<K, T, B>,
+    /// The pages of a spilled batch read back in through this handle.
+    ///
+    /// Clones do not share the pages, so that they are released along with
+    /// the handle that needed them, which is typically the storage of a
+    /// cursor.
+    pages: Vec<OnceCell<Rc<Resident<B>>>>,
+}
+
+enum Contents<K, T, B> {
+    Memory(Rc<Resident<B>>),
+    Disk(Rc<SpillFile<K, T, B>>),
+}
+
+impl<K, T: Clone, B> Clone for SpillBatch<K, T, B> {
+    fn clone(&self) -> Self {
+        let contents = match &self.contents {
+            Contents::Memory(resident) => Contents::Memory(Rc::clone(resident)),
+            Contents::Disk(file) => Contents::Disk(Rc::clone(file)),
+        };
+        SpillBatch::new(self.desc.clone(), self.len, contents)
+    }
+}
+
+impl<K, T, B> SpillBatch<K, T, B> {
+    fn new(desc: Description<T>, len: usize, contents: Contents<K, T, B>) -> Self {
+        let pages = match &contents {
+            Contents::Memory(_) => Vec::new(),
+            Contents::Disk(file) => file.pages.iter().map(|_| OnceCell::new()).collect(),
+        };
+        SpillBatch {
+            desc,
+            len,
+            contents,
+            pages,
+        }
+    }
+
+    /// Wraps a newly built batch, spilling it to disk if the worker is over
+    /// its memory budget.
+    fn seal<V, R>(batch: B) -> Self
+    where
+        B: Batch<K, V, T, R>,
+        K: Clone + Serialize + DeserializeOwned + HeapSize,
+        V: Serialize + DeserializeOwned + HeapSize,
+        T: Serialize + DeserializeOwned + Clone,
+        R: Serialize + DeserializeOwned + Clone,
+    {
+        let len = batch.len();
+        let desc = batch.description().clone();
+        let memory_bytes = memory_size::<K, V, T, R, B>(&batch);
+
+        let directory = STATE.with(|state| {
+            let state = state.borrow();
+            match &state.config {
+                Some(config)
+                    if len >= MIN_SPILL_RECORDS
+                        && state.resident_bytes + memory_bytes > config.memory_budget =>
+                {
+                    Some(config.directory.clone())
+                }
+                _ => None,
+            }
+        });
+        let contents = match directory {
+            Some(directory) => match SpillFile::write::<V, R>(&directory, &batch) {
+                Ok(file) => Contents::Disk(Rc::new(file)),
+                Err(e) => {
+                    warn!("failed to spill arrangement batch: {:#}", e);
+                    Contents::Memory(Rc::new(Resident::new(batch, memory_bytes)))
+                }
+            },
+            None => Contents::Memory(Rc::new(Resident::new(batch, memory_bytes))),
+        };
+        SpillBatch::new(desc, len, contents)
+    }
+
+    /// Returns the number of distinct keys in the batch.
+    ///
+    /// `count` is applied to batches that are in memory; for spilled batches
+    /// the count recorded while writing them out is returned instead.
+    pub fn key_count(&self, count: impl FnOnce(&B) -> usize) -> usize {
+        match &self.contents {
+            Contents::Memory(resident) => count(&resident.batch),
+            Contents::Disk(file) => file.keys,
+        }
+    }
+
+    fn is_spilled(&self) -> bool {
+        matches!(self.contents, Contents::Disk(_))
+    }
+
+    /// Returns the number of pages in the batch. A batch held in memory is a
+    /// single page.
+    fn page_count(&self) -> usize {
+        match &self.contents {
+            Contents::Memory(_) => 1,
+            Contents::Disk(file) => file.pages.len(),
+        }
+    }
+
+    /// Returns the index of the page that holds `key`, if the batch holds it.
+    fn seek_page(&self, key: &K) -> usize
+    where
+        K: Ord,
+    {
+        match &self.contents {
+            Contents::Memory(_) => 0,
+            Contents::Disk(file) => file
+                .pages
+                .partition_point(|page| page.key <= *key)
+                .saturating_sub(1),
+        }
+    }
+
+    /// Returns a page of the batch, reading it back in through this handle if
+    /// the batch was spilled.
+    fn page(&self, index: usize) -> &B {
+        match &self.contents {
+            Contents::Memory(resident) => &resident.batch,
+            Contents::Disk(file) => {
+                &self.pages[index]
+                    .get_or_init(|| Rc::new(file.page_or_empty(index, &self.desc)))
+                    .batch
+            }
+        }
+    }
+
+    /// Returns a page of the batch without retaining a copy of a spilled page
+    /// in this handle.
+    fn load_page(&self, index: usize) -> Rc<Resident<B>> {
+        match &self.contents {
+            Contents::Memory(resident) => Rc::clone(resident),
+            Contents::Disk(file) => match self.pages[index].get() {
+                Some(resident) => Rc::clone(resident),
+                None => Rc::new(file.page_or_empty(index, &self.desc)),
+            },
+        }
+    }
+}
+
+impl<K, V, T, R, B> BatchReader<K, V, T, R> for SpillBatch<K, T, B>
+where
+    B: Batch<K, V, T, R>,
+    K: Ord + Clone + Serialize + DeserializeOwned + HeapSize,
+    V: Serialize + DeserializeOwned + HeapSize,
+    T: Serialize + DeserializeOwned + Clone,
+    R: Serialize + DeserializeOwned + Clone,
+{
+    type Cursor = SpillCursor<K, T, B, B::Cursor>;
+
+    fn cursor(&self) -> Self::Cursor {
+        // The inner cursor is created on first use, as cursors are usually
+        // handed storage that is a clone of this batch, and reading a page in
+        // here would leave a copy attached to this handle.
+        SpillCursor {
+            page: 0,
+            cursor: RefCell::new(None),
+            _marker: PhantomData,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn description(&self) -> &Description<T> {
+        &self.desc
+    }
+}
+
+impl<K, V, T, R, B> Batch<K, V, T, R> for SpillBatch<K, T, B>
+where
+    B: Batch<K, V, T, R>,
+    K: Ord + Clone + Serialize + DeserializeOwned + HeapSize,
+    V: Ord + Clone + Serialize + DeserializeOwned + HeapSize,
+    T: Lattice + Ord + Serialize + DeserializeOwned + Clone,
+    R: Semigroup + Serialize + DeserializeOwned,
+{
+    type Batcher = SpillBatcher<B::Batcher>;
+    type Builder = SpillBuilder<B::Builder>;
+    type Merger = SpillMerger<K, V, T, R, B, B::Merger>;
+
+    fn begin_merge(
+        &self,
+        other: &Self,
+        compaction_frontier: Option<AntichainRef<T>>,
+    ) -> Self::Merger {
+        SpillMerger::new(self, other, compaction_frontier)
+    }
+}
+
+/// A batch held in memory, counted against the worker's memory budget for as
+/// long as it lives.
+struct Resident<B> {
+    batch: B,
+    bytes: usize,
+}
+
+impl<B> Resident<B> {
+    fn new(batch: B, bytes: usize) -> Self {
+        STATE.with(|state| state.borrow_mut().resident_bytes += bytes);
+        Resident { batch, bytes }
+    }
+}
+
+impl<B> Drop for Resident<B> {
+    fn drop(&mut self) {
+        // The state is gone if the worker thread is already shutting down.
+        let _ = STATE.try_with(|state| state.borrow_mut().resident_bytes -= self.bytes);
+    }
+}
+
+/// The location of a page of a spilled batch.
+struct Page<K> {
+    /// The first key of the page.
+    key: K,
+    offset: u64,
+    bytes: usize,
+    records: usize,
+}
+
+/// Writes updates to a new spill file a page at a time.
+struct SpillWriter<K, V, T, R> {
+    file: File,
+    /// The updates of the page being written, grouped by key.
+    page: Vec<(K, Vec<(V, T, R)>)>,
+    page_records: usize,
+    pages: Vec<Page<K>>,
+    offset: u64,
+    keys: usize,
+    records: usize,
+}
+
+impl<K, V, T, R> SpillWriter<K, V, T, R>
+where
+    K: Clone + Serialize,
+    V: Serialize,
+    T: Serialize,
+    R: Serialize,
+{
+    fn new(directory: &Path) -> Result<Self, anyhow::Error> {
+        Ok(SpillWriter {
+            file: tempfile::tempfile_in(directory)?,
+            page: Vec::new(),
+            page_records: 0,
+            pages: Vec::new(),
+            offset: 0,
+            keys: 0,
+            records: 0,
+        })
+    }
+
+    /// Appends the updates of a key, which must follow all keys pushed so
+    /// far.
+    fn push(&mut self, key: K, updates: Vec<(V, T, R)>) -> Result<(), anyhow::Error> {
+        self.keys += 1;
+        self.records += updates.len();
+        self.page_records += updates.len();
+        self.page.push((key, updates));
+        if self.page_records >= PAGE_RECORDS {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), anyhow::Error> {
+        fail_point!("spill_write", |_| Err(anyhow::anyhow!(
+            "injected failure writing spill file"
+        )));
+        if let Some((key, _)) = self.page.first() {
+            let bytes = bincode::serialize(&self.page)?;
+            self.file.write_all(&bytes)?;
+            self.pages.push(Page {
+                key: key.clone(),
+                offset: self.offset,
+                bytes: bytes.len(),
+                records: self.page_records,
+            });
+            self.offset += u64::cast_from(bytes.len());
+            self.page.clear();
+            self.page_records = 0;
+        }
+        Ok(())
+    }
+
+    fn finish<B>(mut self) -> Result<SpillFile<K, T, B>, anyhow::Error>
+    where
+        B: Batch<K, V, T, R>,
+        K: DeserializeOwned + HeapSize,
+        V: DeserializeOwned + HeapSize,
+        T: DeserializeOwned + Clone,
+        R: DeserializeOwned,
+    {
+        self.flush()?;
+        let SpillWriter {
+            file,
+            pages,
+            offset,
+            keys,
+            records,
+            ..
+        } = self;
+        STATE.with(|state| {
+            let statistics = &mut state.borrow_mut().statistics;
+            statistics.batches += 1;
+            statistics.records += records;
+            statistics.bytes += offset;
+        });
+        Ok(SpillFile {
+            file,
+            pages,
+            keys,
+            records,
+            bytes: offset,
+            read_page: SpillFile::<K, T, B>::read_page::<V, R>,
+            empty_page: SpillFile::<K, T, B>::empty_page::<V, R>,
+        })
+    }
+}
+
+/// The contents of a spilled batch.
+///
+/// The file is anonymous, so its space is reclaimed once it is closed, even if
+/// the process exits abnormally.
+struct SpillFile<K, T, B> {
+    file: File,
+    /// The pages of the file, in key order.
+    pages: Vec<Page<K>>,
+    keys: usize,
+    records: usize,
+    bytes: u64,
+    /// Reads a page of the file back in.
+    read_page: fn(&File, &Page<K>, &Description<T>) -> Result<Resident<B>, anyhow::Error>,
+    /// Builds an empty page, which stands in for a page that cannot be read.
+    empty_page: fn(&Description<T>) -> Resident<B>,
+}
+
+impl<K, T, B> SpillFile<K, T, B> {
+    /// Writes the updates of `batch` to a new file in `directory`.
+    fn write<V, R>(directory: &Path, batch: &B) -> Result<Self, anyhow::Error>
+    where
+        B: Batch<K, V, T, R>,
+        K: Clone + Serialize + DeserializeOwned + HeapSize,
+        V: Serialize + DeserializeOwned + HeapSize,
+        T: Serialize + DeserializeOwned + Clone,
+        R: Serialize + DeserializeOwned + Clone,
+    {
+        let mut writer = SpillWriter::new(directory)?;
+        let mut cursor = batch.cursor();
+        while cursor.key_valid(batch) {
+            let key = cursor.key(batch).clone();
+            let mut updates = Vec::new();
+            collect_updates::<K, V, T, R, _>(&mut cursor, batch, &mut updates);
+            writer.push(key, updates)?;
+            cursor.step_key(batch);
+        }
+        writer.finish()
+    }
+
+    /// Reads a page back in.
+    fn page(&self, index: usize, desc: &Description<T>) -> Result<Resident<B>, anyhow::Error> {
+        (self.read_page)(&self.file, &self.pages[index], desc)
+    }
+
+    /// Reads a page back in, or records the error and returns an empty page
+    /// if it cannot be read.
+    fn page_or_empty(&self, index: usize, desc: &Description<T>) -> Resident<B> {
+        self.page(index, desc).unwrap_or_else(|e| {
+            record_read_error(&e);
+            (self.empty_page)(desc)
+        })
+    }
+
+    fn read_page<V, R>(
+        file: &File,
+        page: &Page<K>,
+        desc: &Description<T>,
+    ) -> Result<Resident<B>, anyhow::Error>
+    where
+        B: Batch<K, V, T, R>,
+        K: Clone + DeserializeOwned + HeapSize,
+        V: DeserializeOwned + HeapSize,
+        T: DeserializeOwned + Clone,
+        R: DeserializeOwned,
+    {
+        fail_point!("spill_read", |_| Err(anyhow::anyhow!(
+            "injected failure reading spill file"
+        )));
+        let mut bytes = vec![0; page.bytes];
+        file.read_exact_at(&mut bytes, page.offset)?;
+        let updates: Vec<(K, Vec<(V, T, R)>)> = bincode::deserialize(&bytes)?;
+        let mut builder = B::Builder::with_capacity(page.records);
+        for (key, updates) in updates {
+            for (val, time, diff) in updates {
+                builder.push((key.clone(), val, time, diff));
+            }
+        }
+        let batch = builder.done(
+            desc.lower().clone(),
+            desc.upper().clone(),
+            desc.since().clone(),
+        );
+        let bytes = memory_size::<K, V, T, R, B>(&batch);
+        Ok(Resident::new(batch, bytes))
+    }
+
+    fn empty_page<V, R>(desc: &Description<T>) -> Resident<B>
+    where
+        B: Batch<K, V, T, R>,
+        T: Clone,
+    {
+        let batch = B::Builder::new().done(
+            desc.lower().clone(),
+            desc.upper().clone(),
+            desc.since().clone(),
+        );
+        Resident::new(batch, 0)
+    }
+}
+
+impl<K, T, B> Drop for SpillFile<K, T, B> {
+    fn drop(&mut self) {
+        let _ = STATE.try_with(|state| {
+            let statistics = &mut state.borrow_mut().statistics;
+            statistics.batches -= 1;
+            statistics.records -= self.records;
+            statistics.bytes -= self.bytes;
+        });
+    }
+}
+
+/// A cursor over a [`SpillBatch`].
+///
+/// The cursor moves through the batch a page at a time, so that seeking to a
+/// key reads in only the page that holds it.
+pub struct SpillCursor<K, T, B, C> {
+    /// The page of the batch that the cursor is on.
+    page: usize,
+    /// A cursor over the page, created on first use.
+    cursor: RefCell<Option<C>>,
+    _marker: PhantomData<(K, T, B)>,
+}
+
+impl<K, T, B, C> SpillCursor<K, T, B, C> {
+    fn inner(&self, init: impl FnOnce() -> C) -> Ref<'_, C> {
+        if self.cursor.borrow().is_none() {
+            *self.cursor.borrow_mut() = Some(init());
+        }
+        Ref::map(self.cursor.borrow(), |cursor| {
+            cursor.as_ref().expect("cursor initialized")
+        })
+    }
+
+    fn inner_mut(&mut self, init: impl FnOnce() -> C) -> &mut C {
+        self.cursor.get_mut().get_or_insert_with(init)
+    }
+
+    /// Moves to the first key of the page after the current one, if there is
+    /// one. Pages are never empty.
+    fn next_page(&mut self, storage: &SpillBatch<K, T, B>) {
+        if self.page + 1 < storage.page_count() {
+            self.page += 1;
+            *self.cursor.get_mut() = None;
+        }
+    }
+}
+
+impl<K, V, T, R, B, C> Cursor<K, V, T, R> for SpillCursor<K, T, B, C>
+where
+    B: BatchReader<K, V, T, R, Cursor = C>,
+    C: Cursor<K, V, T, R, Storage = B>,
+    K: Ord,
+{
+    type Storage = SpillBatch<K, T, B>;
+
+    fn key_valid(&self, storage: &Self::Storage) -> bool {
+        let batch = storage.page(self.page);
+        self.inner(|| batch.cursor()).key_valid(batch)
+    }
+
+    fn val_valid(&self, storage: &Self::Storage) -> bool {
+        let batch = storage.page(self.page);
+        self.inner(|| batch.cursor()).val_valid(batch)
+    }
+
+    fn key<'a>(&self, storage: &'a Self::Storage) -> &'a K {
+        let batch = storage.page(self.page);
+        self.inner(|| batch.cursor()).key(batch)
+    }
+
+    fn val<'a>(&self, storage: &'a Self::Storage) -> &'a V {
+        let batch = storage.page(self.page);
+        self.inner(|| batch.cursor()).val(batch)
+    }
+
+    fn map_times<L: FnMut(&T, &R)>(&mut self, storage: &Self::Storage, logic: L) {
+        let batch = storage.page(self.page);
+        self.inner_mut(|| batch.cursor()).map_times(batch, logic)
+    }
+
+    fn step_key(&mut self, storage: &Self::Storage) {
+        let batch = storage.page(self.page);
+        let cursor = self.inner_mut(|| batch.cursor());
+        cursor.step_key(batch);
+        if !cursor.key_valid(batch) {
+            self.next_page(storage);
+        }
+    }
+
+    fn seek_key(&mut self, storage: &Self::Storage, key: &K) {
+        // Cursors never move backwards, so the key is either on the current
+        // page or on a later one.
+        let page = storage.seek_page(key);
+        if page > self.page {
+            self.page = page;
+            *self.cursor.get_mut() = None;
+        }
+        let batch = storage.page(self.page);
+        let cursor = self.inner_mut(|| batch.cursor());
+        cursor.seek_key(batch, key);
+        if !cursor.key_valid(batch) {
+            self.next_page(storage);
+        }
+    }
+
+    fn step_val(&mut self, storage: &Self::Storage) {
+        let batch = storage.page(self.page);
+        self.inner_mut(|| batch.cursor()).step_val(batch)
+    }
+
+    fn seek_val(&mut self, storage: &Self::Storage, val: &V) {
+        let batch = storage.page(self.page);
+        self.inner_mut(|| batch.cursor()).seek_val(batch, val)
+    }
+
+    fn rewind_keys(&mut self, _storage: &Self::Storage) {
+        self.page = 0;
+        *self.cursor.get_mut() = None;
+    }
+
+    fn rewind_vals(&mut self, storage: &Self::Storage) {
+        let batch = storage.page(self.page);
+        self.inner_mut(|| batch.cursor()).rewind_vals(batch)
+    }
+}
+
+/// Batches updates for a [`SpillBatch`].
+pub struct SpillBatcher<I> {
+    batcher: I,
+}
+
+impl<K, V, T, R, B, I> Batcher<K, V, T, R, SpillBatch<K, T, B>> for SpillBatcher<I>
+where
+    I: Batcher<K, V, T, R, B>,
+    B: Batch<K, V, T, R>,
+    K: Ord + Clone + Serialize + DeserializeOwned + HeapSize,
+    V: Ord + Clone + Serialize + DeserializeOwned + HeapSize,
+    T: Lattice + Ord + Serialize + DeserializeOwned + Clone,
+    R: Semigroup + Serialize + DeserializeOwned,
+{
+    fn new() -> Self {
+        SpillBatcher { batcher: I::new() }
+    }
+
+    fn push_batch(&mut self, batch: RefOrMut<Vec<((K, V), T, R)>>) {
+        self.batcher.push_batch(batch)
+    }
+
+    fn seal(&mut self, upper: Antichain<T>) -> SpillBatch<K, T, B> {
+        SpillBatch::seal::<V, R>(self.batcher.seal(upper))
+    }
+
+    fn frontier(&mut self) -> AntichainRef<T> {
+        self.batcher.frontier()
+    }
+}
+
+/// Builds a [`SpillBatch`].
+pub struct SpillBuilder<I> {
+    builder: I,
+}
+
+impl<K, V, T, R, B, I> Builder<K, V, T, R, SpillBatch<K, T, B>> for SpillBuilder<I>
+where
+    I: Builder<K, V, T, R, B>,
+    B: Batch<K, V, T, R>,
+    K: Ord + Clone + Serialize + DeserializeOwned + HeapSize,
+    V: Ord + Clone + Serialize + DeserializeOwned + HeapSize,
+    T: Lattice + Ord + Serialize + DeserializeOwned + Clone,
+    R: Semigroup + Serialize + DeserializeOwned,
+{
+    fn new() -> Self {
+        SpillBuilder { builder: I::new() }
+    }
+
+    fn with_capacity(cap: usize) -> Self {
+        SpillBuilder {
+            builder: I::with_capacity(cap),
+        }
+    }
+
+    fn push(&mut self, element: (K, V, T, R)) {
+        self.builder.push(element)
+    }
+
+    fn done(
+        self,
+        lower: Antichain<T>,
+        upper: Antichain<T>,
+        since: Antichain<T>,
+    ) -> SpillBatch<K, T, B> {
+        SpillBatch::seal::<V, R>(self.builder.done(lower, upper, since))
+    }
+}
+
+/// Merges two [`SpillBatch`]es.
+///
+/// Batches held in memory are merged by the inner batch's merger. If either
+/// input was spilled, the inputs are instead read in a page at a time, and
+/// the output is written to disk as it is produced if spilling is still
+/// enabled. If the output cannot be written, the merge starts over with its
+/// output held in memory.
+pub struct SpillMerger<K, V, T, R, B: Batch<K, V, T, R>, M> {
+    merge: Merge<K, V, T, R, B, M>,
+}
+
+enum Merge<K, V, T, R, B: Batch<K, V, T, R>, M> {
+    Memory {
+        sources: (Rc<Resident<B>>, Rc<Resident<B>>),
+        merger: M,
+    },
+    Paged(Box<PagedMerge<K, V, T, R, B>>),
+}
+
+impl<K, V, T, R, B, M> Merger<K, V, T, R, SpillBatch<K, T, B>> for SpillMerger<K, V, T, R, B, M>
+where
+    M: Merger<K, V, T, R, B>,
+    B: Batch<K, V, T, R>,
+    K: Ord + Clone + Serialize + DeserializeOwned + HeapSize,
+    V: Ord + Clone + Serialize + DeserializeOwned + HeapSize,
+    T: Lattice + Ord + Serialize + DeserializeOwned + Clone,
+    R: Semigroup + Serialize + DeserializeOwned,
+{
+    fn new(
+        source1: &SpillBatch<K, T, B>,
+        source2: &SpillBatch<K, T, B>,
+        compaction_frontier: Option<AntichainRef<T>>,
+    ) -> Self {
+        let merge = if source1.is_spilled() || source2.is_spilled() {
+            Merge::Paged(Box::new(PagedMerge::new(
+                source1,
+                source2,
+                compaction_frontier,
+            )))
+        } else {
+            let sources = (source1.load_page(0), source2.load_page(0));
+            let merger = M::new(&sources.0.batch, &sources.1.batch, compaction_frontier);
+            Merge::Memory { sources, merger }
+        };
+        SpillMerger { merge }
+    }
+
+    fn work(
+        &mut self,
+        _source1: &SpillBatch<K, T, B>,
+        _source2: &SpillBatch<K, T, B>,
+        fuel: &mut isize,
+    ) {
+        match &mut self.merge {
+            Merge::Memory { sources, merger } => {
+                merger.work(&sources.0.batch, &sources.1.batch, fuel)
+            }
+            Merge::Paged(merge) => merge.work(fuel),
+        }
+    }
+
+    fn done(self) -> SpillBatch<K, T, B> {
+        match self.merge {
+            Merge::Memory { sources, merger } => {
+                let batch = merger.done();
+                // Release the inputs before deciding whether the output fits
+                // in memory.
+                drop(sources);
+                SpillBatch::seal::<V, R>(batch)
+            }
+            Merge::Paged(merge) => merge.done(),
+        }
+    }
+}
+
+/// A merge that holds a single page of each input in memory at a time.
+struct PagedMerge<K, V, T, R, B: Batch<K, V, T, R>> {
+    sources: (PageReader<K, V, T, R, B>, PageReader<K, V, T, R, B>),
+    /// The frontier to which the times of merged updates are advanced.
+    frontier: Option<Antichain<T>>,
+    desc: Description<T>,
+    output: MergeOutput<K, V, T, R, B>,
+}
+
+/// The destination of the updates produced by a [`PagedMerge`].
+enum MergeOutput<K, V, T, R, B: Batch<K, V, T, R>> {
+    Disk(SpillWriter<K, V, T, R>),
+    Memory(B::Builder),
+}
+
+impl<K, V, T, R, B> PagedMerge<K, V, T, R, B>
+where
+    B: Batch<K, V, T, R>,
+    K: Ord + Clone + Serialize + DeserializeOwned + HeapSize,
+    V: Ord + Clone + Serialize + DeserializeOwned + HeapSize,
+    T: Lattice + Ord + Serialize + DeserializeOwned + Clone,
+    R: Semigroup + Serialize + DeserializeOwned,
+{
+    fn new(
+        source1: &SpillBatch<K, T, B>,
+        source2: &SpillBatch<K, T, B>,
+        compaction_frontier: Option<AntichainRef<T>>,
+    ) -> Self {
+        let mut since = source1.desc.since().join(source2.desc.since());
+        if let Some(compaction_frontier) = compaction_frontier {
+            since = since.join(&compaction_frontier.to_owned());
+        }
+        let desc = Description::new(
+            source1.desc.lower().clone(),
+            source2.desc.upper().clone(),
+            since,
+        );
+        let output = match directory().map(|directory| SpillWriter::new(&directory)) {
+            Some(Ok(writer)) => MergeOutput::Disk(writer),
+            Some(Err(e)) => {
+                warn!("failed to spill merged arrangement batch: {:#}", e);
+                MergeOutput::Memory(B::Builder::new())
+            }
+            None => MergeOutput::Memory(B::Builder::new()),
+        };
+        PagedMerge {
+            sources: (PageReader::new(source1), PageReader::new(source2)),
+            frontier: compaction_frontier.map(|frontier| frontier.to_owned()),
+            desc,
+            output,
+        }
+    }
+
+    /// Merges the inputs a key at a time until they are exhausted or `fuel`
+    /// runs out.
+    fn work(&mut self, fuel: &mut isize) {
+        let mut updates = Vec::new();
+        while *fuel > 0 {
+            let ordering = match (self.sources.0.key(), self.sources.1.key()) {
+                (None, None) => return,
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(key1), Some(key2)) => key1.cmp(key2),
+            };
+            let key = match ordering {
+                Ordering::Less => self.sources.0.take_key(&mut updates),
+                Ordering::Greater => self.sources.1.take_key(&mut updates),
+                Ordering::Equal => {
+                    self.sources.0.take_key(&mut updates);
+                    self.sources.1.take_key(&mut updates)
+                }
+            };
+            *fuel -= isize::try_from(updates.len()).unwrap_or(isize::MAX);
+            if let Some(frontier) = &self.frontier {
+                for (_, time, _) in updates.iter_mut() {
+                    time.advance_by(frontier.borrow());
+                }
+            }
+            consolidate_updates(&mut updates);
+            if !updates.is_empty() {
+                if let Err(e) = self.output.push(key, mem::take(&mut updates)) {
+                    warn!(
+                        "failed to spill merged arrangement batch, merging in memory instead: {:#}",
+                        e
+                    );
+                    self.restart_in_memory();
+                }
+            }
+        }
+    }
+
+    /// Discards the output produced so far and rewinds the inputs, so that
+    /// the merge starts over with its output held in memory.
+    fn restart_in_memory(&mut self) {
+        self.sources = (
+            PageReader::new(&self.sources.0.batch),
+            PageReader::new(&self.sources.1.batch),
+        );
+        self.output = MergeOutput::Memory(B::Builder::new());
+    }
+
+    fn done(mut self) -> SpillBatch<K, T, B> {
+        let output = mem::replace(&mut self.output, MergeOutput::Memory(B::Builder::new()));
+        let builder = match output {
+            MergeOutput::Disk(writer) if writer.keys > 0 => match writer.finish() {
+                Ok(file) => {
+                    return SpillBatch::new(self.desc, file.records, Contents::Disk(Rc::new(file)))
+                }
+                Err(e) => {
+                    warn!(
+                        "failed to spill merged arrangement batch, merging in memory instead: {:#}",
+                        e
+                    );
+                    self.restart_in_memory();
+                    let mut fuel = isize::MAX;
+                    self.work(&mut fuel);
+                    match mem::replace(&mut self.output, MergeOutput::Memory(B::Builder::new())) {
+                        MergeOutput::Memory(builder) => builder,
+                        MergeOutput::Disk(_) => unreachable!("restarted merges write to memory"),
+                    }
+                }
+            },
+            MergeOutput::Disk(_) => B::Builder::new(),
+            MergeOutput::Memory(builder) => builder,
+        };
+        let PagedMerge { sources, desc, .. } = self;
+        // Release the inputs before deciding whether the output fits in
+        // memory.
+        drop(sources);
+        let batch = builder.done(
+            desc.lower().clone(),
+            desc.upper().clone(),
+            desc.since().clone(),
+        );
+        SpillBatch::seal::<V, R>(batch)
+    }
+}
+
+impl<K, V, T, R, B> MergeOutput<K, V, T, R, B>
+where
+    B: Batch<K, V, T, R>,
+    K: Clone + Serialize,
+    V: Serialize,
+    T: Serialize,
+    R: Serialize,
+{
+    fn push(&mut self, key: K, updates: Vec<(V, T, R)>) -> Result<(), anyhow::Error> {
+        match self {
+            MergeOutput::Disk(writer) => writer.push(key, updates),
+            MergeOutput::Memory(builder) => {
+                for (val, time, diff) in updates {
+                    builder.push((key.clone(), val, time, diff));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Reads the keys of a [`SpillBatch`] in order, holding only the page of the
+/// current key in memory.
+struct PageReader<K, V, T, R, B: BatchReader<K, V, T, R>> {
+    batch: SpillBatch<K, T, B>,
+    page: usize,
+    resident: Rc<Resident<B>>,
+    cursor: B::Cursor,
+    _marker: PhantomData<(V, R)>,
+}
+
+impl<K, V, T, R, B> PageReader<K, V, T, R, B>
+where
+    B: BatchReader<K, V, T, R>,
+    K: Clone,
+    V: Clone,
+    T: Clone,
+    R: Clone,
+{
+    fn new(batch: &SpillBatch<K, T, B>) -> Self {
+        let resident = batch.load_page(0);
+        let cursor = resident.batch.cursor();
+        PageReader {
+            batch: batch.clone(),
+            page: 0,
+            resident,
+            cursor,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the current key, unless the batch is exhausted.
+    fn key(&self) -> Option<&K> {
+        let batch = &self.resident.batch;
+        if self.cursor.key_valid(batch) {
+            Some(self.cursor.key(batch))
+        } else {
+            None
+        }
+    }
+
+    /// Appends the updates of the current key to `updates` and moves to the
+    /// next key, returning the current one.
+    fn take_key(&mut self, updates: &mut Vec<(V, T, R)>) -> K {
+        let batch = &self.resident.batch;
+        let key = self.cursor.key(batch).clone();
+        collect_updates::<K, V, T, R, _>(&mut self.cursor, batch, updates);
+        self.cursor.step_key(batch);
+        if !self.cursor.key_valid(batch) && self.page + 1 < self.batch.page_count() {
+            self.page += 1;
+            self.resident = self.batch.load_page(self.page);
+            self.cursor = self.resident.batch.cursor();
+        }
+        key
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use differential_dataflow::trace::implementations::ord::OrdValBatch;
+    use differential_dataflow::trace::{Batch, BatchReader, Builder, Cursor, Merger};
+    use fail::FailScenario;
+    use mz_ore::cast::CastFrom;
+    use timely::progress::Antichain;
+
+    use super::{configure, read_error, SpillBatch, SpillConfig, MIN_SPILL_RECORDS};
+
+    type TestBatch = SpillBatch<u64, u64, OrdValBatch<u64, u64, u64, i64>>;
+
+    /// Builds a batch of `len` updates whose keys start at `start`.
+    fn build(start: u64, len: u64, lower: u64, upper: u64) -> TestBatch {
+        let mut builder = <TestBatch as Batch<u64, u64, u64, i64>>::Builder::new();
+        for key in start..start + len {
+            builder.push((key, key * 2, lower, 1));
+        }
+        builder.done(
+            Antichain::from_elem(lower),
+            Antichain::from_elem(upper),
+            Antichain::from_elem(0),
+        )
+    }
+
+    /// Reads the keys and values of `batch` back out.
+    fn contents(batch: &TestBatch) -> Vec<(u64, u64)> {
+        let mut contents = Vec::new();
+        let mut cursor = batch.cursor();
+        while cursor.key_valid(batch) {
+            while cursor.val_valid(batch) {
+                contents.push((*cursor.key(batch), *cursor.val(batch)));
+                cursor.step_val(batch);
+            }
+            cursor.step_key(batch);
+        }
+        contents
+    }
+
+    fn merge(batch1: &TestBatch, batch2: &TestBatch) -> TestBatch {
+        let mut merger = batch1.begin_merge(batch2, None);
+        let mut fuel = isize::MAX;
+        merger.work(batch1, batch2, &mut fuel);
+        merger.done()
+    }
+
+    fn expected(len: u64) -> Vec<(u64, u64)> {
+        (0..len).map(|key| (key, key * 2)).collect()
+    }
+
+    /// Spills every batch that is large enough into `directory`.
+    fn configure_spill(directory: &std::path::Path) {
+        configure(Some(SpillConfig {
+            directory: directory.to_owned(),
+            memory_budget: 0,
+        }));
+    }
+
+    #[test]
+    fn test_missing_spill_directory() {
+        let directory = tempfile::tempdir().unwrap();
+        configure_spill(&directory.path().join("missing"));
+
+        let len = u64::cast_from(MIN_SPILL_RECORDS) * 2;
+        let batch1 = build(0, len, 0, 1);
+        let batch2 = build(len, len, 1, 2);
+        assert!(!batch1.is_spilled());
+        assert!(!batch2.is_spilled());
+
+        let merged = merge(&batch1, &batch2);
+        assert!(!merged.is_spilled());
+        assert_eq!(contents(&merged), expected(len * 2));
+    }
+
+    #[test]
+    fn test_failing_spill_writes() {
+        let scenario = FailScenario::setup();
+        let directory = tempfile::tempdir().unwrap();
+        configure_spill(directory.path());
+
+        let len = u64::cast_from(MIN_SPILL_RECORDS) * 8;
+        let batch1 = build(0, len, 0, 1);
+        let batch2 = build(len, len, 1, 2);
+        assert!(batch1.is_spilled());
+        assert!(batch2.is_spilled());
+
+        // Merges of spilled batches write their output as they go, and start
+        // over in memory when a write fails.
+        fail::cfg("spill_write", "return").unwrap();
+        let merged = merge(&batch1, &batch2);
+        assert!(!merged.is_spilled());
+        assert_eq!(contents(&merged), expected(len * 2));
+
+        // New batches that cannot be written stay in memory.
+        let batch3 = build(0, len, 2, 3);
+        assert!(!batch3.is_spilled());
+        assert_eq!(contents(&batch3), expected(len));
+        assert_eq!(read_error(), None);
+
+        scenario.teardown();
+    }
+
+    #[test]
+    fn test_failing_spill_reads() {
+        let scenario = FailScenario::setup();
+        let directory = tempfile::tempdir().unwrap();
+        configure_spill(directory.path());
+
+        let len = u64::cast_from(MIN_SPILL_RECORDS) * 2;
+        let batch = build(0, len, 0, 1);
+        assert!(batch.is_spilled());
+        assert_eq!(read_error(), None);
+
+        // Pages that cannot be read back in are read as empty, and the error
+        // is recorded for the worker to report.
+        fail::cfg("spill_read", "return").unwrap();
+        assert_eq!(contents(&batch), vec![]);
+        assert!(read_error().unwrap().contains("injected failure"));
+
+        scenario.teardown();
+    }
+}
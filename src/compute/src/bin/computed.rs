@@ -29,6 +29,7 @@ use mz_ore::metrics::MetricsRegistry;
 use mz_ore::now::SYSTEM_TIME;
 
 use mz_compute::server::Server;
+use mz_compute::SpillConfig;
 use mz_pid_file::PidFile;
 
 // Disable jemalloc on macOS, as it is not well supported [0][1][2].
@@ -108,6 +109,24 @@ struct Args {
     #[clap(long, value_name = "PATH")]
     pid_file_location: Option<PathBuf>,
 
+    // === Arrangement options. ===
+    /// The number of bytes of arrangement batches the process may hold in
+    /// memory. Once the budget is exceeded, new batches are spilled to disk.
+    ///
+    /// If unset, arrangements are held entirely in memory.
+    #[clap(long, env = "COMPUTED_ARRANGEMENT_MEMORY_BUDGET", value_name = "BYTES")]
+    arrangement_memory_budget: Option<usize>,
+    /// The directory in which to create arrangement spill files.
+    ///
+    /// Defaults to the system's temporary directory.
+    #[clap(
+        long,
+        env = "COMPUTED_ARRANGEMENT_SPILL_DIRECTORY",
+        value_name = "PATH",
+        requires = "arrangement-memory-budget"
+    )]
+    arrangement_spill_directory: Option<PathBuf>,
+
     // === Logging options. ===
     /// Which log messages to emit. See `materialized`'s help for more
     /// info.
@@ -256,6 +275,14 @@ async fn run(args: Args) -> Result<(), anyhow::Error> {
             .aws_external_id
             .map(AwsExternalId::ISwearThisCameFromACliArgOrEnvVariable)
             .unwrap_or(AwsExternalId::NotProvided),
+        arrangement_spill: args
+            .arrangement_memory_budget
+            .map(|memory_budget| SpillConfig {
+                directory: args
+                    .arrangement_spill_directory
+                    .unwrap_or_else(std::env::temp_dir),
+                memory_budget,
+            }),
     };
 
     let serve_config = ServeConfig {
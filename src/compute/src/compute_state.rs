@@ -13,7 +13,8 @@ use std::rc::Rc;
 use std::time::{Duration, Instant};
 
 use differential_dataflow::operators::arrange::arrangement::Arrange;
-use differential_dataflow::trace::TraceReader;
+use differential_dataflow::trace::layers::Trie;
+use differential_dataflow::trace::{BatchReader, TraceReader};
 use differential_dataflow::Collection;
 use timely::communication::Allocate;
 use timely::logging::Logger;
@@ -23,20 +24,27 @@ use timely::progress::reachability::logging::TrackerEvent;
 use timely::progress::ChangeBatch;
 use timely::worker::Worker as TimelyWorker;
 use tokio::sync::mpsc;
+use uuid::Uuid;
 
-use mz_dataflow_types::client::{ComputeCommand, ComputeResponse};
+use mz_dataflow_types::client::{ArrangementStatistics, ComputeCommand, ComputeResponse};
 use mz_dataflow_types::logging::LoggingConfig;
 use mz_dataflow_types::{DataflowError, PeekResponse, TailResponse};
+use mz_ore::cast::CastFrom;
 use mz_repr::{Diff, GlobalId, Row, Timestamp};
 use mz_storage::boundary::ComputeReplay;
 use mz_timely_util::activator::RcActivator;
 use mz_timely_util::operator::CollectionExt;
 
 use crate::arrangement::manager::{TraceBundle, TraceManager};
+use crate::arrangement::spill::{self, SpillStatistics};
 use crate::logging;
 use crate::logging::materialized::ComputeEvent;
+use crate::logging::plan::{DataflowPlan, StatisticsTracker};
 use crate::sink::SinkBaseMetrics;
 
+/// The minimum interval between reports of arrangement statistics.
+const STATISTICS_INTERVAL: Duration = Duration::from_secs(1);
+
 /// Worker-local state that is maintained across dataflows.
 ///
 /// This state is restricted to the COMPUTE state, the deterministic, idempotent work
@@ -59,10 +67,21 @@ pub struct ComputeState {
     pub pending_peeks: Vec<PendingPeek>,
     /// Tracks the frontier information that has been sent over `response_tx`.
     pub reported_frontiers: HashMap<GlobalId, Antichain<Timestamp>>,
+    /// Tracks the arrangement statistics that have been sent over `response_tx`.
+    pub reported_statistics: HashMap<GlobalId, ArrangementStatistics>,
+    /// The last time arrangement statistics were reported.
+    pub statistics_reported_at: Instant,
+    /// Tracks the spilled arrangement batches that have been logged.
+    pub logged_spills: SpillStatistics,
     /// Undocumented
     pub sink_metrics: SinkBaseMetrics,
     /// The logger, from Timely's logging framework, if logs are enabled.
     pub materialized_logger: Option<logging::materialized::Logger>,
+    /// Runtime statistics of the operators of live dataflows, fed by the
+    /// logging framework if logs are enabled.
+    pub statistics_tracker: Rc<RefCell<StatisticsTracker>>,
+    /// The plan nodes of the dataflow that maintains each index.
+    pub dataflow_plans: HashMap<GlobalId, Rc<DataflowPlan>>,
 }
 
 /// A wrapper around [ComputeState] with a live timely worker and response channel.
@@ -139,6 +158,7 @@ impl<'a, A: Allocate, B: ComputeReplay> ActiveComputeState<'a, A, B> {
                         self.compute_state.dataflow_tokens.remove(&id);
                         // Index-specific work:
                         self.compute_state.traces.del_trace(&id);
+                        self.compute_state.dataflow_plans.remove(&id);
 
                         // Work common to sinks and indexes (removing frontier tracking and cleaning up logging).
                         let frontier = self
@@ -177,17 +197,44 @@ impl<'a, A: Allocate, B: ComputeReplay> ActiveComputeState<'a, A, B> {
                 trace_bundle
                     .errs_mut()
                     .set_physical_compaction(empty_frontier.borrow());
+                // Analyzing the dataflow requires the statistics gathered by
+                // the logging framework.
+                let mut error = None;
+                let plan = if !peek.analyze {
+                    None
+                } else if self.compute_state.materialized_logger.is_none() {
+                    error = Some("cannot analyze dataflows when logging is disabled");
+                    None
+                } else {
+                    let plan = self.compute_state.dataflow_plans.get(&peek.id).cloned();
+                    if plan.is_none() {
+                        error = Some("cannot analyze a dataflow that was not rendered from a plan");
+                    }
+                    plan
+                };
                 // Prepare a description of the peek work to do.
-                let mut peek = PendingPeek { peek, trace_bundle };
+                let mut peek = PendingPeek {
+                    peek,
+                    trace_bundle,
+                    plan,
+                    resume: None,
+                };
                 // Log the receipt of the peek.
                 if let Some(logger) = self.compute_state.materialized_logger.as_mut() {
                     logger.log(ComputeEvent::Peek(peek.as_log_event(), true));
                 }
                 // Attempt to fulfill the peek.
-                if let Some(response) = peek.seek_fulfillment(&mut Antichain::new()) {
-                    self.send_peek_response(peek, response);
+                if let Some(error) = error {
+                    self.send_peek_response(peek, PeekResponse::Error(error.into()));
                 } else {
-                    self.compute_state.pending_peeks.push(peek);
+                    let uuid = peek.peek.uuid;
+                    let response = peek.seek_fulfillment(&mut Antichain::new(), &mut |rows| {
+                        self.send_peek_batch(uuid, rows)
+                    });
+                    match response {
+                        Some(response) => self.send_peek_response(peek, response),
+                        None => self.compute_state.pending_peeks.push(peek),
+                    }
                 }
             }
             ComputeCommand::CancelPeeks { uuids } => {
@@ -204,6 +251,15 @@ impl<'a, A: Allocate, B: ComputeReplay> ActiveComputeState<'a, A, B> {
                     }
                 }
             }
+            ComputeCommand::ContinuePeeks { uuids } => {
+                for peek in self.compute_state.pending_peeks.iter_mut() {
+                    if let Some(resume) = &mut peek.resume {
+                        if uuids.contains(&peek.peek.uuid) {
+                            resume.continued = true;
+                        }
+                    }
+                }
+            }
         }
     }
 
@@ -278,6 +334,7 @@ impl<'a, A: Allocate, B: ComputeReplay> ActiveComputeState<'a, A, B> {
 
         // Register each logger endpoint.
         let activator = t_activator.clone();
+        let statistics_tracker = Rc::clone(&self.compute_state.statistics_tracker);
         self.timely_worker.log_register().insert_logger(
             "timely",
             Logger::new(
@@ -285,6 +342,11 @@ impl<'a, A: Allocate, B: ComputeReplay> ActiveComputeState<'a, A, B> {
                 start_offset,
                 self.timely_worker.index(),
                 move |time, data| {
+                    let mut tracker = statistics_tracker.borrow_mut();
+                    for (time, _worker, event) in data.iter() {
+                        tracker.observe_timely(*time, event);
+                    }
+                    drop(tracker);
                     t_logger.publish_batch(time, data);
                     activator.activate();
                 },
@@ -343,6 +405,7 @@ impl<'a, A: Allocate, B: ComputeReplay> ActiveComputeState<'a, A, B> {
         );
 
         let activator = d_activator.clone();
+        let statistics_tracker = Rc::clone(&self.compute_state.statistics_tracker);
         self.timely_worker.log_register().insert_logger(
             "differential/arrange",
             Logger::new(
@@ -350,6 +413,11 @@ impl<'a, A: Allocate, B: ComputeReplay> ActiveComputeState<'a, A, B> {
                 start_offset,
                 self.timely_worker.index(),
                 move |time, data| {
+                    let mut tracker = statistics_tracker.borrow_mut();
+                    for (_time, _worker, event) in data.iter() {
+                        tracker.observe_differential(event);
+                    }
+                    drop(tracker);
                     d_logger.publish_batch(time, data);
                     activator.activate();
                 },
@@ -546,6 +614,66 @@ impl<'a, A: Allocate, B: ComputeReplay> ActiveComputeState<'a, A, B> {
         }
     }
 
+    /// Send statistics about the contents of arrangements to the coordinator.
+    ///
+    /// Statistics are reported at most once per [`STATISTICS_INTERVAL`], and
+    /// only for arrangements whose statistics have changed.
+    pub fn report_arrangement_statistics(&mut self) {
+        if self.compute_state.statistics_reported_at.elapsed() < STATISTICS_INTERVAL {
+            return;
+        }
+        self.compute_state.statistics_reported_at = Instant::now();
+
+        let mut updates = Vec::new();
+        for (id, traces) in self.compute_state.traces.traces.iter_mut() {
+            // Counting the updates in each batch is cheap, but overestimates
+            // the number of records: batches that have not yet been merged
+            // may hold updates that cancel out, and records that changed
+            // appear once per version. Keys may likewise appear in more than
+            // one batch. The largest batch is usually the result of merging
+            // most of the others, so its key count is a reasonable estimate.
+            let mut statistics = ArrangementStatistics::default();
+            traces.oks_mut().map_batches(|batch| {
+                statistics.records += u64::cast_from(batch.len());
+                let keys = batch.key_count(|batch| batch.layer.keys());
+                statistics.keys = std::cmp::max(statistics.keys, u64::cast_from(keys));
+            });
+            let prev = self
+                .compute_state
+                .reported_statistics
+                .insert(*id, statistics);
+            if prev != Some(statistics) {
+                updates.push((*id, statistics));
+            }
+        }
+        let traces = &self.compute_state.traces.traces;
+        self.compute_state
+            .reported_statistics
+            .retain(|id, _| traces.contains_key(id));
+
+        if !updates.is_empty() {
+            self.send_compute_response(ComputeResponse::ArrangementStatistics(updates));
+        }
+    }
+
+    /// Log changes to the arrangement batches this worker has spilled to disk.
+    pub fn log_spill_statistics(&mut self) {
+        let statistics = spill::statistics();
+        let logged = &mut self.compute_state.logged_spills;
+        if *logged == statistics {
+            return;
+        }
+        if let Some(logger) = self.compute_state.materialized_logger.as_mut() {
+            if *logged != SpillStatistics::default() {
+                logger.log(ComputeEvent::ArrangementSpills(*logged, -1));
+            }
+            if statistics != SpillStatistics::default() {
+                logger.log(ComputeEvent::ArrangementSpills(statistics, 1));
+            }
+        }
+        *logged = statistics;
+    }
+
     /// Scan pending peeks and attempt to retire each.
     pub fn process_peeks(&mut self) {
         let mut upper = Antichain::new();
@@ -555,14 +683,25 @@ impl<'a, A: Allocate, B: ComputeReplay> ActiveComputeState<'a, A, B> {
             Vec::with_capacity(pending_peeks_len),
         );
         for mut peek in pending_peeks.drain(..) {
-            if let Some(response) = peek.seek_fulfillment(&mut upper) {
-                self.send_peek_response(peek, response);
-            } else {
-                self.compute_state.pending_peeks.push(peek);
+            let uuid = peek.peek.uuid;
+            let response =
+                peek.seek_fulfillment(&mut upper, &mut |rows| self.send_peek_batch(uuid, rows));
+            match response {
+                Some(response) => self.send_peek_response(peek, response),
+                None => self.compute_state.pending_peeks.push(peek),
             }
         }
     }
 
+    /// Sends some of the rows of a peek to the coordinator ahead of the peek's
+    /// final response.
+    fn send_peek_batch(&self, uuid: Uuid, rows: Vec<(Row, NonZeroUsize)>) {
+        self.send_compute_response(ComputeResponse::PeekResponse(
+            uuid,
+            PeekResponse::Batch(rows),
+        ));
+    }
+
     /// Sends a response for this peek's resolution to the coordinator.
     ///
     /// Note that this function takes ownership of the `PendingPeek`, which is
@@ -593,6 +732,22 @@ impl<'a, A: Allocate, B: ComputeReplay> ActiveComputeState<'a, A, B> {
     }
 }
 
+/// The number of rows in each batch of rows a peek sends ahead of its response.
+const PEEK_BATCH_SIZE: usize = 1024;
+
+/// The position from which a peek that has sent a batch of rows resumes
+/// reading its trace.
+struct PeekResume {
+    /// The key of the last record the peek read.
+    key: Row,
+    /// The value of the last record the peek read.
+    val: Row,
+    /// The number of results the peek has sent in batches.
+    sent: usize,
+    /// Whether the last batch the peek sent has been continued.
+    continued: bool,
+}
+
 /// An in-progress peek, and data to eventually fulfill it.
 ///
 /// Note that `PendingPeek` intentionally does not implement or derive `Clone`,
@@ -601,6 +756,11 @@ pub struct PendingPeek {
     peek: mz_dataflow_types::client::Peek,
     /// The data from which the trace derives.
     trace_bundle: TraceBundle,
+    /// The plan nodes of the dataflow that maintains the trace, if the peek
+    /// analyzes the dataflow rather than reading the trace.
+    plan: Option<Rc<DataflowPlan>>,
+    /// Where to resume reading the trace, if the peek has sent a batch of rows.
+    resume: Option<PeekResume>,
 }
 
 impl PendingPeek {
@@ -621,7 +781,18 @@ impl PendingPeek {
     /// then for any time `t` less or equal to `peek.timestamp` it is
     /// not the case that `upper` is less or equal to that timestamp,
     /// and so the result cannot further evolve.
-    fn seek_fulfillment(&mut self, upper: &mut Antichain<Timestamp>) -> Option<PeekResponse> {
+    ///
+    /// Rows may be sent ahead of the response in batches, through `send_batch`.
+    /// Having sent a batch, the peek reads no further until the batch has been
+    /// continued.
+    fn seek_fulfillment(
+        &mut self,
+        upper: &mut Antichain<Timestamp>,
+        send_batch: &mut dyn FnMut(Vec<(Row, NonZeroUsize)>),
+    ) -> Option<PeekResponse> {
+        if matches!(&self.resume, Some(resume) if !resume.continued) {
+            return None;
+        }
         self.trace_bundle.oks_mut().read_upper(upper);
         if upper.less_equal(&self.peek.timestamp) {
             return None;
@@ -630,19 +801,45 @@ impl PendingPeek {
         if upper.less_equal(&self.peek.timestamp) {
             return None;
         }
-        let response = match self.collect_finished_data() {
-            Ok(rows) => PeekResponse::Rows(rows),
-            Err(text) => PeekResponse::Error(text),
-        };
-        Some(response)
+        if let Some(plan) = &self.plan {
+            let one = NonZeroUsize::new(1).unwrap();
+            let rows = plan
+                .analyze()
+                .iter()
+                .map(|statistics| (statistics.to_row(), one))
+                .collect();
+            return Some(PeekResponse::Rows(rows));
+        }
+        let result = self.collect_finished_data(send_batch);
+        // Spilled pages that could not be read back in were read as empty, so
+        // the data may be incomplete.
+        if let Some(error) = spill::read_error() {
+            return Some(PeekResponse::Error(error));
+        }
+        match result {
+            Ok(Some(rows)) => Some(PeekResponse::Rows(rows)),
+            Ok(None) => None,
+            Err(text) => Some(PeekResponse::Error(text)),
+        }
     }
 
     /// Collects data for a known-complete peek.
-    fn collect_finished_data(&mut self) -> Result<Vec<(Row, NonZeroUsize)>, String> {
+    ///
+    /// Unless the results must be thinned using the finishing's ordering, they
+    /// are sent through `send_batch` once `PEEK_BATCH_SIZE` of them have
+    /// accumulated, and only those not yet sent are returned. Having sent a
+    /// batch, this returns `None`, and the next call resumes reading after the
+    /// last record read.
+    fn collect_finished_data(
+        &mut self,
+        send_batch: &mut dyn FnMut(Vec<(Row, NonZeroUsize)>),
+    ) -> Result<Option<Vec<(Row, NonZeroUsize)>>, String> {
+        let resume = self.resume.take();
+
         // Check if there exist any errors and, if so, return whatever one we
-        // find first.
+        // find first. A resumed peek has already checked.
         let (mut cursor, storage) = self.trace_bundle.errs_mut().cursor();
-        while cursor.key_valid(&storage) {
+        while resume.is_none() && cursor.key_valid(&storage) {
             let mut copies = 0;
             cursor.map_times(&storage, |time, diff| {
                 if time.less_equal(&self.peek.timestamp) {
@@ -677,12 +874,47 @@ impl PendingPeek {
             .finishing
             .limit
             .map(|l| l + self.peek.finishing.offset);
+        // Whether we may send results before we have seen all of them. We
+        // cannot when we must retain the first `max_results` records in the
+        // finishing's ordering, which may be among those we have yet to see.
+        let send_batches = max_results.is_none() || self.peek.finishing.order_by.is_empty();
+        // The number of results already sent in batches.
+        let mut sent = resume.as_ref().map_or(0, |resume| resume.sent);
 
-        if let Some(literal) = &self.peek.key {
-            cursor.seek_key(&storage, literal);
+        // The keys to read, in the order they appear in the arrangement, if we
+        // are not to read every key.
+        let mut literals = self.peek.literal_constraints.clone().map(|mut literals| {
+            literals.sort();
+            literals.dedup();
+            if let Some(resume) = &resume {
+                literals.retain(|literal| literal >= &resume.key);
+            }
+            literals.into_iter()
+        });
+        let mut literal = None;
+        if let Some(literals) = &mut literals {
+            literal = literals.next();
+            match &literal {
+                Some(literal) => cursor.seek_key(&storage, literal),
+                None => return Ok(Some(results)),
+            }
         }
 
         use differential_dataflow::trace::Cursor;
+
+        // Skip the records up to and including the last one read.
+        if let Some(resume) = &resume {
+            if literals.is_none() {
+                cursor.seek_key(&storage, &resume.key);
+            }
+            if cursor.key_valid(&storage) && cursor.key(&storage) == &resume.key {
+                cursor.seek_val(&storage, &resume.val);
+                if cursor.val_valid(&storage) && cursor.val(&storage) == &resume.val {
+                    cursor.step_val(&storage);
+                }
+            }
+        }
+
         use mz_ore::result::ResultExt;
         use mz_repr::{DatumVec, RowArena};
 
@@ -692,7 +924,12 @@ impl PendingPeek {
         let mut r_datum_vec = DatumVec::new();
 
         while cursor.key_valid(&storage) {
-            while cursor.val_valid(&storage) {
+            // Seeking a key that is absent from the arrangement leaves the
+            // cursor at the next key, whose values we must skip.
+            let key_matches = literal
+                .as_ref()
+                .map_or(true, |literal| cursor.key(&storage) == literal);
+            while key_matches && cursor.val_valid(&storage) {
                 // TODO: This arena could be maintained and reuse for longer
                 // but it wasn't clear at what granularity we should flush
                 // it to ensure we don't accidentally spike our memory use.
@@ -734,47 +971,68 @@ impl PendingPeek {
                     // If we hold many more than `max_results` records, we can thin down
                     // `results` using `self.finishing.ordering`.
                     if let Some(max_results) = max_results {
-                        // We use a threshold twice what we intend, to amortize the work
-                        // across all of the insertions. We could tighten this, but it
-                        // works for the moment.
-                        if results.len() >= 2 * max_results {
-                            if self.peek.finishing.order_by.is_empty() {
-                                results.truncate(max_results);
-                                return Ok(results);
-                            } else {
-                                // We can sort `results` and then truncate to `max_results`.
-                                // This has an effect similar to a priority queue, without
-                                // its interactive dequeueing properties.
-                                // TODO: Had we left these as `Vec<Datum>` we would avoid
-                                // the unpacking; we should consider doing that, although
-                                // it will require a re-pivot of the code to branch on this
-                                // inner test (as we prefer not to maintain `Vec<Datum>`
-                                // in the other case).
-                                results.sort_by(|left, right| {
-                                    let left_datums = l_datum_vec.borrow_with(&left.0);
-                                    let right_datums = r_datum_vec.borrow_with(&right.0);
-                                    mz_expr::compare_columns(
-                                        &self.peek.finishing.order_by,
-                                        &left_datums,
-                                        &right_datums,
-                                        || left.0.cmp(&right.0),
-                                    )
-                                });
-                                results.truncate(max_results);
+                        if self.peek.finishing.order_by.is_empty() {
+                            // Any `max_results` records will do, and we need no more.
+                            if sent + results.len() >= max_results {
+                                results.truncate(max_results - sent);
+                                return Ok(Some(results));
                             }
+                        } else if results.len() >= 2 * max_results {
+                            // We use a threshold twice what we intend, to amortize the work
+                            // across all of the insertions. We could tighten this, but it
+                            // works for the moment.
+                            //
+                            // We can sort `results` and then truncate to `max_results`.
+                            // This has an effect similar to a priority queue, without
+                            // its interactive dequeueing properties.
+                            // TODO: Had we left these as `Vec<Datum>` we would avoid
+                            // the unpacking; we should consider doing that, although
+                            // it will require a re-pivot of the code to branch on this
+                            // inner test (as we prefer not to maintain `Vec<Datum>`
+                            // in the other case).
+                            results.sort_by(|left, right| {
+                                let left_datums = l_datum_vec.borrow_with(&left.0);
+                                let right_datums = r_datum_vec.borrow_with(&right.0);
+                                mz_expr::compare_columns(
+                                    &self.peek.finishing.order_by,
+                                    &left_datums,
+                                    &right_datums,
+                                    || left.0.cmp(&right.0),
+                                )
+                            });
+                            results.truncate(max_results);
                         }
                     }
+
+                    // Send the results we hold, if there are enough of them, and
+                    // read no further until they have been consumed.
+                    if send_batches && results.len() >= PEEK_BATCH_SIZE {
+                        sent += results.len();
+                        send_batch(std::mem::take(&mut results));
+                        self.resume = Some(PeekResume {
+                            key: key.clone(),
+                            val: row.clone(),
+                            sent,
+                            continued: false,
+                        });
+                        return Ok(None);
+                    }
                 }
                 cursor.step_val(&storage);
             }
-            // If we had a key, we are now done and can return.
-            if self.peek.key.is_some() {
-                return Ok(results);
-            } else {
-                cursor.step_key(&storage);
+            // Move on to the next key to read, if any.
+            match &mut literals {
+                Some(literals) => match literals.next() {
+                    Some(next) => {
+                        cursor.seek_key(&storage, &next);
+                        literal = Some(next);
+                    }
+                    None => return Ok(Some(results)),
+                },
+                None => cursor.step_key(&storage),
             }
         }
 
-        Ok(results)
+        Ok(Some(results))
     }
 }
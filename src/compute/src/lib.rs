@@ -19,4 +19,5 @@ pub mod server;
 pub(crate) mod sink;
 
 pub use arrangement::manager::{TraceManager, TraceMetrics};
+pub use arrangement::spill::SpillConfig;
 pub use sink::SinkBaseMetrics;
@@ -25,9 +25,9 @@ use timely::dataflow::operators::generic::builder_rc::OperatorBuilder;
 use timely::logging::WorkerIdentifier;
 
 use super::{DifferentialLog, LogVariant};
+use crate::arrangement::KeysValsHandle;
+use crate::arrangement::RowSpine;
 use crate::logging::ConsolidateBuffer;
-use mz_dataflow_types::KeysValsHandle;
-use mz_dataflow_types::RowSpine;
 use mz_repr::{Datum, DatumVec, Diff, Row, Timestamp};
 use mz_timely_util::activator::RcActivator;
 use mz_timely_util::replay::MzReplay;
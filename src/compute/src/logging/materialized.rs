@@ -24,14 +24,14 @@ use timely::logging::WorkerIdentifier;
 use tracing::error;
 use uuid::Uuid;
 
-use mz_dataflow_types::KeysValsHandle;
-use mz_dataflow_types::RowSpine;
 use mz_expr::{permutation_for_arrangement, MirScalarExpr};
 use mz_repr::{Datum, DatumVec, GlobalId, Row, Timestamp};
 use mz_timely_util::activator::RcActivator;
 use mz_timely_util::replay::MzReplay;
 
 use super::{LogVariant, MaterializedLog};
+use crate::arrangement::spill::SpillStatistics;
+use crate::arrangement::{KeysValsHandle, RowSpine};
 
 /// Type alias for logging of materialized events.
 pub type Logger = timely::logging_core::Logger<ComputeEvent, WorkerIdentifier>;
@@ -52,6 +52,8 @@ pub enum ComputeEvent {
     Peek(Peek, bool),
     /// Available frontier information for views.
     Frontier(GlobalId, Timestamp, i64),
+    /// Arrangement batches the worker has spilled to disk.
+    ArrangementSpills(SpillStatistics, i64),
 }
 
 /// A logged peek event.
@@ -111,6 +113,7 @@ pub fn construct<A: Allocate>(
         let (mut frontier_out, frontier) = demux.new_output();
         let (mut peek_out, peek) = demux.new_output();
         let (mut peek_duration_out, peek_duration) = demux.new_output();
+        let (mut spills_out, spills) = demux.new_output();
 
         let mut demux_buffer = Vec::new();
         demux.build(move |_capability| {
@@ -122,6 +125,7 @@ pub fn construct<A: Allocate>(
                 let mut frontier = frontier_out.activate();
                 let mut peek = peek_out.activate();
                 let mut peek_duration = peek_duration_out.activate();
+                let mut spills = spills_out.activate();
 
                 input.for_each(|time, data| {
                     data.swap(&mut demux_buffer);
@@ -131,6 +135,7 @@ pub fn construct<A: Allocate>(
                     let mut frontier_session = frontier.session(&time);
                     let mut peek_session = peek.session(&time);
                     let mut peek_duration_session = peek_duration.session(&time);
+                    let mut spills_session = spills.session(&time);
 
                     for (time, worker, datum) in demux_buffer.drain(..) {
                         let time_ms = (((time.as_millis() as Timestamp / granularity_ms) + 1)
@@ -194,6 +199,18 @@ pub fn construct<A: Allocate>(
                                     delta,
                                 ));
                             }
+                            ComputeEvent::ArrangementSpills(statistics, delta) => {
+                                spills_session.give((
+                                    Row::pack_slice(&[
+                                        Datum::Int64(worker as i64),
+                                        Datum::Int64(statistics.batches as i64),
+                                        Datum::Int64(statistics.records as i64),
+                                        Datum::Int64(statistics.bytes as i64),
+                                    ]),
+                                    time_ms,
+                                    delta,
+                                ));
+                            }
                             ComputeEvent::Peek(peek, is_install) => {
                                 let key = (worker, peek.uuid);
                                 if is_install {
@@ -251,6 +268,8 @@ pub fn construct<A: Allocate>(
 
         let frontier_current = frontier.as_collection();
 
+        let arrangement_spills = spills.as_collection();
+
         let peek_current = peek.as_collection().map({
             move |(peek, worker)| {
                 Row::pack_slice(&[
@@ -294,6 +313,10 @@ pub fn construct<A: Allocate>(
                 LogVariant::Materialized(MaterializedLog::PeekDuration),
                 peek_duration,
             ),
+            (
+                LogVariant::Materialized(MaterializedLog::ArrangementSpills),
+                arrangement_spills,
+            ),
         ];
 
         let mut result = std::collections::HashMap::new();
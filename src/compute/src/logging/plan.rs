@@ -0,0 +1,219 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Attribution of runtime statistics to the nodes of rendered plans.
+//!
+//! Timely operators and channels draw their identifiers from a single
+//! worker-local sequence. The identifiers allocated while rendering a plan
+//! node, including those allocated while rendering its inputs, therefore form
+//! a contiguous range, and each operator or channel belongs to the innermost
+//! node whose range contains its identifier.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use std::rc::Rc;
+use std::time::Duration;
+
+use differential_dataflow::logging::DifferentialEvent;
+use timely::logging::{StartStop, TimelyEvent};
+
+use mz_dataflow_types::PlanNodeStatistics;
+use mz_ore::cast::CastFrom;
+use mz_repr::GlobalId;
+
+/// A node of a rendered plan.
+#[derive(Clone, Debug)]
+pub struct PlanNode {
+    /// The object whose plan contains the node.
+    pub object: GlobalId,
+    /// The depth of the node within the plan of `object`.
+    pub depth: usize,
+    /// A short description of the node, like `Join`.
+    pub operator: &'static str,
+    /// The collection that the node reads, if the node is a `Get` of a
+    /// collection outside of the dataflow.
+    pub id: Option<GlobalId>,
+    /// The identifiers allocated while rendering the node and its inputs.
+    pub identifiers: Range<usize>,
+}
+
+/// The plan nodes of a rendered dataflow.
+///
+/// The identifiers of the dataflow are tracked by a [`StatisticsTracker`]
+/// for as long as this value is live.
+#[derive(Debug)]
+pub struct DataflowPlan {
+    nodes: Vec<PlanNode>,
+    identifiers: Range<usize>,
+    tracker: Rc<RefCell<StatisticsTracker>>,
+}
+
+impl DataflowPlan {
+    /// Starts tracking statistics for the dataflow rendered with the
+    /// identifiers in `identifiers`.
+    pub fn new(
+        nodes: Vec<PlanNode>,
+        identifiers: Range<usize>,
+        tracker: Rc<RefCell<StatisticsTracker>>,
+    ) -> Self {
+        tracker.borrow_mut().register(identifiers.clone());
+        DataflowPlan {
+            nodes,
+            identifiers,
+            tracker,
+        }
+    }
+
+    /// Reports the statistics gathered so far for each plan node.
+    pub fn analyze(&self) -> Vec<PlanNodeStatistics> {
+        let mut statistics: Vec<_> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| PlanNodeStatistics {
+                object: node.object,
+                node: u64::cast_from(index),
+                depth: u64::cast_from(node.depth),
+                operator: node.operator.to_owned(),
+                id: node.id,
+                elapsed: Duration::ZERO,
+                updates: 0,
+                arrangement_records: 0,
+                arrangement_batches: 0,
+            })
+            .collect();
+        let tracker = self.tracker.borrow();
+        for (identifier, operator) in tracker.statistics.range(self.identifiers.clone()) {
+            // Nodes are listed in the order they began rendering, so the last
+            // node containing the identifier is the innermost one.
+            let node = self
+                .nodes
+                .iter()
+                .rposition(|node| node.identifiers.contains(identifier));
+            if let Some(node) = node {
+                let node = &mut statistics[node];
+                node.elapsed += operator.elapsed;
+                node.updates += operator.updates;
+                node.arrangement_records +=
+                    u64::try_from(operator.arrangement_records).unwrap_or(0);
+                node.arrangement_batches +=
+                    u64::try_from(operator.arrangement_batches).unwrap_or(0);
+            }
+        }
+        statistics
+    }
+}
+
+impl Drop for DataflowPlan {
+    fn drop(&mut self) {
+        self.tracker.borrow_mut().retire(self.identifiers.clone());
+    }
+}
+
+/// Statistics about a single operator or channel.
+#[derive(Clone, Copy, Debug, Default)]
+struct OperatorStatistics {
+    /// The time spent running the operator.
+    elapsed: Duration,
+    /// The number of updates received over the channel.
+    updates: u64,
+    /// The number of records in the operator's arrangement.
+    arrangement_records: i64,
+    /// The number of batches in the operator's arrangement.
+    arrangement_batches: i64,
+}
+
+/// Accumulates statistics from timely and differential logging events for the
+/// operators and channels of registered dataflows.
+#[derive(Debug, Default)]
+pub struct StatisticsTracker {
+    /// The registered ranges of identifiers, as a map from start to end.
+    ranges: BTreeMap<usize, usize>,
+    /// The statistics of each tracked operator and channel.
+    statistics: BTreeMap<usize, OperatorStatistics>,
+    /// The times at which currently scheduled operators were scheduled.
+    schedules: HashMap<usize, Duration>,
+}
+
+impl StatisticsTracker {
+    /// Starts tracking the operators and channels with identifiers in
+    /// `identifiers`.
+    fn register(&mut self, identifiers: Range<usize>) {
+        self.ranges.insert(identifiers.start, identifiers.end);
+    }
+
+    /// Stops tracking, and forgets the statistics of, the operators and
+    /// channels with identifiers in `identifiers`.
+    fn retire(&mut self, identifiers: Range<usize>) {
+        self.ranges.remove(&identifiers.start);
+        let mut retired = self.statistics.split_off(&identifiers.start);
+        self.statistics
+            .append(&mut retired.split_off(&identifiers.end));
+        self.schedules
+            .retain(|identifier, _| !identifiers.contains(identifier));
+    }
+
+    /// Returns the statistics for `identifier`, if it is tracked.
+    fn get_mut(&mut self, identifier: usize) -> Option<&mut OperatorStatistics> {
+        match self.ranges.range(..=identifier).next_back() {
+            Some((_, end)) if identifier < *end => {
+                Some(self.statistics.entry(identifier).or_default())
+            }
+            _ => None,
+        }
+    }
+
+    /// Observes an event from the timely logger, which occurred at `time`.
+    pub fn observe_timely(&mut self, time: Duration, event: &TimelyEvent) {
+        match event {
+            TimelyEvent::Schedule(event) => match event.start_stop {
+                StartStop::Start => {
+                    if self.get_mut(event.id).is_some() {
+                        self.schedules.insert(event.id, time);
+                    }
+                }
+                StartStop::Stop => {
+                    if let Some(start) = self.schedules.remove(&event.id) {
+                        if let Some(operator) = self.get_mut(event.id) {
+                            operator.elapsed += time.saturating_sub(start);
+                        }
+                    }
+                }
+            },
+            TimelyEvent::Messages(event) if !event.is_send => {
+                if let Some(channel) = self.get_mut(event.channel) {
+                    channel.updates += u64::cast_from(event.length);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Observes an event from the differential arrangement logger.
+    pub fn observe_differential(&mut self, event: &DifferentialEvent) {
+        let (operator, records, batches) = match event {
+            DifferentialEvent::Batch(event) => (event.operator, event.length as i64, 1),
+            DifferentialEvent::Merge(event) => match event.complete {
+                Some(done) => (
+                    event.operator,
+                    done as i64 - (event.length1 + event.length2) as i64,
+                    -1,
+                ),
+                None => return,
+            },
+            DifferentialEvent::Drop(event) => (event.operator, -(event.length as i64), -1),
+            DifferentialEvent::MergeShortfall(_) | DifferentialEvent::TraceShare(_) => return,
+        };
+        if let Some(operator) = self.get_mut(operator) {
+            operator.arrangement_records += records;
+            operator.arrangement_batches += batches;
+        }
+    }
+}
@@ -22,9 +22,9 @@ use timely::dataflow::channels::pact::Exchange;
 use timely::dataflow::operators::capture::EventLink;
 use timely::logging::{ParkEvent, TimelyEvent, WorkerIdentifier};
 
+use crate::arrangement::KeysValsHandle;
+use crate::arrangement::RowSpine;
 use mz_dataflow_types::logging::LoggingConfig;
-use mz_dataflow_types::KeysValsHandle;
-use mz_dataflow_types::RowSpine;
 use mz_repr::{datum_list_size, datum_size, Datum, DatumVec, Diff, Row, Timestamp};
 
 use super::{LogVariant, TimelyLog};
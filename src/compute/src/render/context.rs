@@ -30,11 +30,13 @@ use timely::progress::{Antichain, Timestamp};
 
 use mz_dataflow_types::client::controller::storage::CollectionMetadata;
 use mz_dataflow_types::{DataflowDescription, DataflowError};
-use mz_dataflow_types::{ErrSpine, RowSpine, TraceErrHandle, TraceRowHandle};
 use mz_expr::{Id, MapFilterProject, MirScalarExpr};
 use mz_repr::{DatumVec, Diff, GlobalId, Row, RowArena};
 use mz_timely_util::operator::CollectionExt;
 
+use crate::arrangement::{ErrSpine, RowSpine, TraceErrHandle, TraceRowHandle};
+use crate::logging::plan::PlanNode;
+
 // Local type definition to avoid the horror in signatures.
 pub(crate) type Arrangement<S, V> =
     Arranged<S, TraceRowHandle<V, V, <S as ScopeParent>::Timestamp, Diff>>;
@@ -78,6 +80,11 @@ where
     pub as_of_frontier: Antichain<T>,
     /// Bindings of identifiers to collections.
     pub bindings: BTreeMap<Id, CollectionBundle<S, V, T>>,
+    /// The plan nodes rendered so far, in the order they began rendering.
+    pub plan_nodes: Vec<PlanNode>,
+    /// The object whose plan is being rendered, and the depth of the next
+    /// plan node within it.
+    pub(crate) rendering: Option<(GlobalId, usize)>,
 }
 
 impl<S: Scope, V: Data> Context<S, V>
@@ -99,6 +106,8 @@ where
             dataflow_id,
             as_of_frontier,
             bindings: BTreeMap::new(),
+            plan_nodes: Vec::new(),
+            rendering: None,
         }
     }
 }
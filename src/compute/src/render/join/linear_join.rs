@@ -203,7 +203,7 @@ where
             });
 
             errors.push(errs);
-            use mz_dataflow_types::RowSpine;
+            use crate::arrangement::RowSpine;
             let arranged = keyed.arrange_named::<RowSpine<_, _, _, _>>(&format!("JoinStage"));
             joined = JoinedFlavor::Local(arranged);
         }
@@ -7,9 +7,11 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-//! Rendering of `MirRelationExpr::Join` operators, and supporting types.
+//! Rendering of `MirRelationExpr::Join` and `MirRelationExpr::OuterJoin`
+//! operators, and supporting types.
 //!
 //! Consult [mz_dataflow_types::plan::join::JoinPlan] documentation for details.
 
 mod delta_join;
 mod linear_join;
+mod outer_join;
@@ -0,0 +1,220 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Rendering of outer join plans.
+//!
+//! Consult [OuterJoinPlan] documentation for details.
+
+use differential_dataflow::lattice::Lattice;
+use differential_dataflow::operators::arrange::arrangement::Arranged;
+use differential_dataflow::operators::join::JoinCore;
+use differential_dataflow::operators::reduce::ReduceCore;
+use differential_dataflow::trace::BatchReader;
+use differential_dataflow::trace::Cursor;
+use differential_dataflow::trace::TraceReader;
+use differential_dataflow::Collection;
+use timely::dataflow::Scope;
+use timely::progress::{timestamp::Refines, Timestamp};
+
+use mz_dataflow_types::plan::join::OuterJoinPlan;
+use mz_repr::{Datum, DatumVec, Diff, Row};
+
+use crate::render::context::CollectionBundle;
+use crate::render::context::{Arrangement, ArrangementFlavor, Context};
+
+impl<G, T> Context<G, Row, T>
+where
+    G: Scope,
+    G::Timestamp: Lattice + Refines<T>,
+    T: Timestamp + Lattice,
+{
+    pub(crate) fn render_outer_join(
+        &mut self,
+        left: CollectionBundle<G, Row, T>,
+        right: CollectionBundle<G, Row, T>,
+        plan: OuterJoinPlan,
+        scope: &mut G,
+    ) -> CollectionBundle<G, Row, T> {
+        let OuterJoinPlan {
+            left_key,
+            right_key,
+            left_permutation,
+            right_permutation,
+            kind,
+        } = plan;
+        let left_arity = left_permutation.len();
+        let right_arity = right_permutation.len();
+
+        let left = left
+            .arrangement(&left_key)
+            .expect("Arrangement absent despite explicit construction");
+        let right = right
+            .arrangement(&right_key)
+            .expect("Arrangement absent despite explicit construction");
+
+        let mut oks = Vec::new();
+        let mut errors = Vec::new();
+        for arrangement in [&left, &right] {
+            errors.push(match arrangement {
+                ArrangementFlavor::Local(_, errs) => errs.as_collection(|k, _v| k.clone()),
+                ArrangementFlavor::Trace(_, _, errs) => errs.as_collection(|k, _v| k.clone()),
+            });
+        }
+
+        // Demultiplex the four different cross products of arrangement types we might have.
+        oks.push(match (&left, &right) {
+            (ArrangementFlavor::Local(l, _), ArrangementFlavor::Local(r, _)) => {
+                join_matched(l, r, left_permutation.clone(), right_permutation.clone())
+            }
+            (ArrangementFlavor::Local(l, _), ArrangementFlavor::Trace(_, r, _)) => {
+                join_matched(l, r, left_permutation.clone(), right_permutation.clone())
+            }
+            (ArrangementFlavor::Trace(_, l, _), ArrangementFlavor::Local(r, _)) => {
+                join_matched(l, r, left_permutation.clone(), right_permutation.clone())
+            }
+            (ArrangementFlavor::Trace(_, l, _), ArrangementFlavor::Trace(_, r, _)) => {
+                join_matched(l, r, left_permutation.clone(), right_permutation.clone())
+            }
+        });
+
+        if kind.preserves_left() {
+            let right_keys = match &right {
+                ArrangementFlavor::Local(r, _) => distinct_keys(r, "OuterJoinRightKeys"),
+                ArrangementFlavor::Trace(_, r, _) => distinct_keys(r, "OuterJoinRightKeys"),
+            };
+            let padding = (0, right_arity);
+            oks.push(match &left {
+                ArrangementFlavor::Local(l, _) => {
+                    unmatched(l, &right_keys, left_permutation, padding)
+                }
+                ArrangementFlavor::Trace(_, l, _) => {
+                    unmatched(l, &right_keys, left_permutation, padding)
+                }
+            });
+        }
+        if kind.preserves_right() {
+            let left_keys = match &left {
+                ArrangementFlavor::Local(l, _) => distinct_keys(l, "OuterJoinLeftKeys"),
+                ArrangementFlavor::Trace(_, l, _) => distinct_keys(l, "OuterJoinLeftKeys"),
+            };
+            let padding = (left_arity, 0);
+            oks.push(match &right {
+                ArrangementFlavor::Local(r, _) => {
+                    unmatched(r, &left_keys, right_permutation, padding)
+                }
+                ArrangementFlavor::Trace(_, r, _) => {
+                    unmatched(r, &left_keys, right_permutation, padding)
+                }
+            });
+        }
+
+        CollectionBundle::from_collections(
+            differential_dataflow::collection::concatenate(scope, oks),
+            differential_dataflow::collection::concatenate(scope, errors),
+        )
+    }
+}
+
+/// Joins the rows of `left` and `right` whose keys match and contain no nulls.
+///
+/// The output rows are the columns of the left row followed by the columns of
+/// the right row, each reassembled from the key and value of its arrangement
+/// by its `permutation`.
+fn join_matched<G, Tr1, Tr2>(
+    left: &Arranged<G, Tr1>,
+    right: &Arranged<G, Tr2>,
+    left_permutation: Vec<usize>,
+    right_permutation: Vec<usize>,
+) -> Collection<G, Row, Diff>
+where
+    G: Scope,
+    G::Timestamp: Lattice,
+    Tr1: TraceReader<Key = Row, Val = Row, Time = G::Timestamp, R = Diff> + Clone + 'static,
+    Tr1::Batch: BatchReader<Row, Row, G::Timestamp, Diff> + 'static,
+    Tr1::Cursor: Cursor<Row, Row, G::Timestamp, Diff> + 'static,
+    Tr2: TraceReader<Key = Row, Val = Row, Time = G::Timestamp, R = Diff> + Clone + 'static,
+    Tr2::Batch: BatchReader<Row, Row, G::Timestamp, Diff> + 'static,
+    Tr2::Cursor: Cursor<Row, Row, G::Timestamp, Diff> + 'static,
+{
+    // Reuseable allocations for unpacking.
+    let mut datums = DatumVec::new();
+    let mut row_builder = Row::default();
+
+    left.join_core(right, move |key, left_val, right_val| {
+        // Null keys do not match, not even other null keys.
+        if key.iter().any(|datum| datum.is_null()) {
+            return None;
+        }
+        let mut packer = row_builder.packer();
+        let datums_local = datums.borrow_with_many(&[key, left_val]);
+        packer.extend(left_permutation.iter().map(|c| datums_local[*c]));
+        drop(datums_local);
+        let datums_local = datums.borrow_with_many(&[key, right_val]);
+        packer.extend(right_permutation.iter().map(|c| datums_local[*c]));
+        drop(datums_local);
+        Some(row_builder.clone())
+    })
+}
+
+/// Reduces an arrangement to the set of its keys that contain no nulls.
+fn distinct_keys<G, R>(arrangement: &R, name: &str) -> Arrangement<G, Row>
+where
+    G: Scope,
+    G::Timestamp: Lattice,
+    R: ReduceCore<G, Row, Row, Diff>,
+{
+    arrangement.reduce_abelian(name, |key, _s, t| {
+        if !key.iter().any(|datum| datum.is_null()) {
+            t.push((Row::default(), 1));
+        }
+    })
+}
+
+/// Produces the rows of `arrangement` whose keys are absent from `keys`,
+/// padded with the given numbers of nulls before and after their columns.
+///
+/// The rows are reassembled from the key and value of `arrangement` by
+/// `permutation`.
+fn unmatched<G, Tr>(
+    arrangement: &Arranged<G, Tr>,
+    keys: &Arrangement<G, Row>,
+    permutation: Vec<usize>,
+    (nulls_before, nulls_after): (usize, usize),
+) -> Collection<G, Row, Diff>
+where
+    G: Scope,
+    G::Timestamp: Lattice,
+    Tr: TraceReader<Key = Row, Val = Row, Time = G::Timestamp, R = Diff> + Clone + 'static,
+    Tr::Batch: BatchReader<Row, Row, G::Timestamp, Diff> + 'static,
+    Tr::Cursor: Cursor<Row, Row, G::Timestamp, Diff> + 'static,
+{
+    let pad = move |key: &Row, val: &Row, datums: &mut DatumVec, row_builder: &mut Row| {
+        let datums_local = datums.borrow_with_many(&[key, val]);
+        row_builder.packer().extend(
+            std::iter::repeat(Datum::Null)
+                .take(nulls_before)
+                .chain(permutation.iter().map(|c| datums_local[*c]))
+                .chain(std::iter::repeat(Datum::Null).take(nulls_after)),
+        );
+        row_builder.clone()
+    };
+
+    let matched = arrangement.join_core(keys, {
+        let pad = pad.clone();
+        let mut datums = DatumVec::new();
+        let mut row_builder = Row::default();
+        move |key, val, _| Some(pad(key, val, &mut datums, &mut row_builder))
+    });
+    let all = arrangement.as_collection({
+        let mut datums = DatumVec::new();
+        let mut row_builder = Row::default();
+        move |key, val| pad(key, val, &mut datums, &mut row_builder)
+    });
+    all.concat(&matched.negate())
+}
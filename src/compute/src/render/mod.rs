@@ -111,7 +111,7 @@ use timely::dataflow::operators::to_stream::ToStream;
 use timely::dataflow::scopes::Child;
 use timely::dataflow::Scope;
 use timely::progress::Timestamp;
-use timely::worker::Worker as TimelyWorker;
+use timely::worker::{AsWorker, Worker as TimelyWorker};
 
 use mz_dataflow_types::client::controller::storage::CollectionMetadata;
 use mz_dataflow_types::*;
@@ -121,6 +121,7 @@ use mz_repr::{GlobalId, Row};
 
 use crate::arrangement::manager::TraceBundle;
 use crate::compute_state::ComputeState;
+use crate::logging::plan::{DataflowPlan, PlanNode};
 pub use context::CollectionBundle;
 use context::{ArrangementFlavor, Context};
 use mz_storage::boundary::ComputeReplay;
@@ -146,6 +147,9 @@ pub fn build_compute_dataflow<A: Allocate, B: ComputeReplay>(
 ) {
     let worker_logging = timely_worker.log_register().get("timely");
     let name = format!("Dataflow: {}", &dataflow.debug_name);
+    let index_ids: Vec<_> = dataflow.index_exports.keys().copied().collect();
+    let start = timely_worker.peek_identifier();
+    let mut plan_nodes = Vec::new();
 
     timely_worker.dataflow_core(&name, worker_logging, Box::new(()), |_, scope| {
         // The scope.clone() occurs to allow import in the region.
@@ -250,8 +254,23 @@ pub fn build_compute_dataflow<A: Allocate, B: ComputeReplay>(
             for (sink_id, imports, sink) in sinks {
                 context.export_sink(compute_state, &mut tokens, imports, sink_id, &sink);
             }
+
+            plan_nodes = context.plan_nodes;
         });
-    })
+    });
+
+    // Track runtime statistics for the plan nodes, so that peeks at the
+    // exported indexes can analyze them.
+    let plan = Rc::new(DataflowPlan::new(
+        plan_nodes,
+        start..timely_worker.peek_identifier(),
+        Rc::clone(&compute_state.statistics_tracker),
+    ));
+    for idx_id in index_ids {
+        compute_state
+            .dataflow_plans
+            .insert(idx_id, Rc::clone(&plan));
+    }
 }
 
 // This implementation block allows child timestamps to vary from parent timestamps,
@@ -312,7 +331,9 @@ where
 {
     pub(crate) fn build_object(&mut self, scope: &mut G, object: BuildDesc<plan::Plan>) {
         // First, transform the relation expression into a render plan.
+        self.rendering = Some((object.id, 0));
         let bundle = self.render_plan(object.plan, scope, scope.index());
+        self.rendering = None;
         self.insert_id(Id::Global(object.id), bundle);
     }
 }
@@ -382,11 +403,46 @@ where
     ///
     /// The return type reflects the uncertainty about the data representation, perhaps
     /// as a stream of data, perhaps as an arrangement, perhaps as a stream of batches.
+    ///
+    /// While rendering the plan of an object, each plan node is recorded in
+    /// `self.plan_nodes` along with the identifiers allocated to render it.
     pub fn render_plan(
         &mut self,
         plan: plan::Plan,
         scope: &mut G,
         worker_index: usize,
+    ) -> CollectionBundle<G, Row> {
+        let (object, depth) = match self.rendering {
+            Some(rendering) => rendering,
+            None => return self.render_plan_node(plan, scope, worker_index),
+        };
+        let start = scope.peek_identifier();
+        let node = self.plan_nodes.len();
+        self.plan_nodes.push(PlanNode {
+            object,
+            depth,
+            operator: plan_operator(&plan),
+            id: match &plan {
+                Plan::Get {
+                    id: Id::Global(id), ..
+                } => Some(*id),
+                _ => None,
+            },
+            identifiers: start..start,
+        });
+        self.rendering = Some((object, depth + 1));
+        let bundle = self.render_plan_node(plan, scope, worker_index);
+        self.rendering = Some((object, depth));
+        self.plan_nodes[node].identifiers.end = scope.peek_identifier();
+        bundle
+    }
+
+    /// Renders a single plan node, and recursively its inputs.
+    fn render_plan_node(
+        &mut self,
+        plan: plan::Plan,
+        scope: &mut G,
+        worker_index: usize,
     ) -> CollectionBundle<G, Row> {
         match plan {
             Plan::Constant { rows } => {
@@ -505,6 +561,11 @@ where
                     }
                 }
             }
+            Plan::OuterJoin { left, right, plan } => {
+                let left = self.render_plan(*left, scope, worker_index);
+                let right = self.render_plan(*right, scope, worker_index);
+                self.render_outer_join(left, right, plan, scope)
+            }
             Plan::Reduce {
                 input,
                 key_val_plan,
@@ -557,6 +618,32 @@ where
     }
 }
 
+/// A short description of the root node of `plan`.
+fn plan_operator(plan: &Plan) -> &'static str {
+    match plan {
+        Plan::Constant { .. } => "Constant",
+        Plan::Get { .. } => "Get",
+        Plan::Let { .. } => "Let",
+        Plan::Mfp { .. } => "Mfp",
+        Plan::FlatMap { .. } => "FlatMap",
+        Plan::Join {
+            plan: mz_dataflow_types::plan::join::JoinPlan::Linear(_),
+            ..
+        } => "Join (linear)",
+        Plan::Join {
+            plan: mz_dataflow_types::plan::join::JoinPlan::Delta(_),
+            ..
+        } => "Join (delta)",
+        Plan::OuterJoin { .. } => "OuterJoin",
+        Plan::Reduce { .. } => "Reduce",
+        Plan::TopK { .. } => "TopK",
+        Plan::Negate { .. } => "Negate",
+        Plan::Threshold { .. } => "Threshold",
+        Plan::Union { .. } => "Union",
+        Plan::ArrangeBy { .. } => "ArrangeBy",
+    }
+}
+
 use differential_dataflow::lattice::Lattice;
 use timely::progress::timestamp::Refines;
 
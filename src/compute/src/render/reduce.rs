@@ -46,7 +46,7 @@ use super::context::Context;
 use super::ArrangementFlavor;
 use mz_repr::DatumVec;
 
-use mz_dataflow_types::RowSpine;
+use crate::arrangement::RowSpine;
 
 /// Render a dataflow based on the provided plan.
 ///
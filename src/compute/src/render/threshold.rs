@@ -21,12 +21,12 @@ use timely::progress::{timestamp::Refines, Timestamp};
 
 use mz_repr::{Diff, Row};
 
+use crate::arrangement::RowSpine;
 use crate::render::context::CollectionBundle;
 use crate::render::context::{ArrangementFlavor, Context};
 use mz_dataflow_types::plan::threshold::{
     BasicThresholdPlan, RetractionsThresholdPlan, ThresholdPlan,
 };
-use mz_dataflow_types::RowSpine;
 
 /// Shared function to compute an arrangement of values matching `logic`.
 fn threshold_arrangement<G, T, R, L>(
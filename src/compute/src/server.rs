@@ -11,6 +11,7 @@
 
 use std::collections::HashMap;
 use std::sync::Mutex;
+use std::time::Instant;
 
 use anyhow::anyhow;
 use crossbeam_channel::TryRecvError;
@@ -25,6 +26,7 @@ use mz_ore::metrics::MetricsRegistry;
 use mz_ore::now::NowFn;
 use mz_storage::boundary::ComputeReplay;
 
+use crate::arrangement::spill::{self, SpillConfig};
 use crate::compute_state::ActiveComputeState;
 use crate::compute_state::ComputeState;
 use crate::SinkBaseMetrics;
@@ -44,6 +46,10 @@ pub struct Config {
     pub metrics_registry: MetricsRegistry,
     /// An external ID to use for all AWS AssumeRole operations.
     pub aws_external_id: AwsExternalId,
+    /// Configuration for spilling arrangement batches to disk, if enabled.
+    ///
+    /// The memory budget is shared by all workers of the process.
+    pub arrangement_spill: Option<SpillConfig>,
 }
 
 /// A handle to a running dataflow server.
@@ -87,10 +93,17 @@ pub fn serve_boundary<CR: ComputeReplay, B: Fn(usize) -> CR + Send + Sync + 'sta
 
     let tokio_executor = tokio::runtime::Handle::current();
 
+    // Each worker holds an equal share of the arrangement memory budget.
+    let arrangement_spill = config.arrangement_spill.map(|spill| SpillConfig {
+        memory_budget: spill.memory_budget / config.workers,
+        ..spill
+    });
+
     let worker_guards = timely::execute::execute(config.timely_config, move |timely_worker| {
         let timely_worker_index = timely_worker.index();
         let compute_boundary = create_boundary(timely_worker_index);
         let _tokio_guard = tokio_executor.enter();
+        spill::configure(arrangement_spill.clone());
         let command_rx = command_channels.lock().unwrap()[timely_worker_index % config.workers]
             .take()
             .unwrap();
@@ -168,6 +181,8 @@ where
             // Report frontier information back the coordinator.
             if let Some(mut compute_state) = self.activate_compute() {
                 compute_state.report_compute_frontiers();
+                compute_state.report_arrangement_statistics();
+                compute_state.log_spill_statistics();
             }
 
             // Handle any received commands.
@@ -199,8 +214,13 @@ where
                             sink_write_frontiers: HashMap::new(),
                             pending_peeks: Vec::new(),
                             reported_frontiers: HashMap::new(),
+                            reported_statistics: HashMap::new(),
+                            statistics_reported_at: Instant::now(),
+                            logged_spills: Default::default(),
                             sink_metrics: self.metrics_bundle.0.clone(),
                             materialized_logger: None,
+                            statistics_tracker: Default::default(),
+                            dataflow_plans: HashMap::new(),
                         });
                     }
                     ComputeCommand::DropInstance => {
@@ -9,7 +9,7 @@
 
 //! Persistent metadata storage for the coordinator.
 
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::path::Path;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -21,6 +21,7 @@ use lazy_static::lazy_static;
 use mz_stash::{Append, Postgres, Sqlite};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use tokio::sync::{Mutex, MutexGuard};
 use tracing::{info, trace};
 
@@ -40,16 +41,17 @@ use mz_ore::now::{to_datetime, EpochMillis, NowFn};
 use mz_pgrepr::oid::FIRST_USER_OID;
 use mz_repr::{GlobalId, RelationDesc, ScalarType};
 use mz_sql::ast::display::AstDisplay;
-use mz_sql::ast::Expr;
+use mz_sql::ast::{Expr, Privilege};
 use mz_sql::catalog::{
     CatalogConnector, CatalogDatabase, CatalogError as SqlCatalogError,
     CatalogItem as SqlCatalogItem, CatalogItemType as SqlCatalogItemType, CatalogSchema,
     CatalogType, CatalogTypeDetails, IdReference, NameReference, SessionCatalog, TypeReference,
+    PUBLIC_ROLE_ID,
 };
 use mz_sql::names::{
-    Aug, DatabaseId, FullObjectName, ObjectQualifiers, PartialObjectName, QualifiedObjectName,
-    QualifiedSchemaName, RawDatabaseSpecifier, ResolvedDatabaseSpecifier, SchemaId,
-    SchemaSpecifier,
+    Aug, DatabaseId, FullObjectName, ObjectId, ObjectQualifiers, PartialObjectName,
+    QualifiedObjectName, QualifiedSchemaName, RawDatabaseSpecifier, ResolvedDatabaseSpecifier,
+    SchemaId, SchemaSpecifier,
 };
 use mz_sql::plan::{
     ComputeInstanceIntrospectionConfig, CreateConnectorPlan, CreateIndexPlan, CreateSecretPlan,
@@ -60,13 +62,17 @@ use mz_sql::DEFAULT_SCHEMA;
 use mz_transform::Optimizer;
 use uuid::Uuid;
 
+use crate::catalog::audit::{EventType, ObjectType};
 use crate::catalog::builtin::{
     Builtin, BuiltinLog, BuiltinTable, BuiltinType, Fingerprint, BUILTINS, BUILTIN_ROLES,
-    INFORMATION_SCHEMA, MZ_CATALOG_SCHEMA, MZ_INTERNAL_SCHEMA, MZ_TEMP_SCHEMA, PG_CATALOG_SCHEMA,
+    INFORMATION_SCHEMA, MZ_CATALOG_SCHEMA, MZ_INTERNAL_SCHEMA, MZ_SYSTEM, MZ_TEMP_SCHEMA,
+    PG_CATALOG_SCHEMA,
 };
+use crate::scram::ScramVerifier;
 use crate::session::{PreparedStatement, Session, DEFAULT_DATABASE_NAME};
 use crate::CoordError;
 
+mod audit;
 mod builtin_table_updates;
 mod config;
 mod error;
@@ -136,6 +142,8 @@ pub struct CatalogState {
     compute_instances_by_id: HashMap<ComputeInstanceId, ComputeInstance>,
     compute_instances_by_name: HashMap<String, ComputeInstanceId>,
     roles: HashMap<String, Role>,
+    /// The privileges granted on each object, by grantee.
+    privileges: BTreeMap<ObjectId, BTreeMap<i64, BTreeSet<Privilege>>>,
     config: mz_sql::catalog::CatalogConfig,
     oid_counter: u32,
 }
@@ -326,6 +334,7 @@ impl CatalogState {
         oid: u32,
         name: QualifiedObjectName,
         item: CatalogItem,
+        owner_id: i64,
     ) {
         if !id.is_system() && !item.is_placeholder() {
             info!(
@@ -358,6 +367,7 @@ impl CatalogState {
             id,
             oid,
             used_by: Vec::new(),
+            owner_id,
         };
         for u in entry.uses() {
             match self.entry_by_id.get_mut(&u) {
@@ -388,12 +398,122 @@ impl CatalogState {
         &self.database_by_id[database_id]
     }
 
+    /// Reports whether the role identified by `role_id` owns any object other
+    /// than those in `ignore`.
+    fn role_owns_objects(&self, role_id: i64, ignore: &HashSet<ObjectId>) -> bool {
+        let databases = self
+            .database_by_id
+            .values()
+            .filter(|db| db.owner_id == role_id)
+            .map(|db| ObjectId::Database(db.id));
+        let schemas = self.database_by_id.values().flat_map(|db| {
+            db.schemas_by_id
+                .values()
+                .filter(|schema| schema.owner_id == role_id)
+                .map(|schema| ObjectId::Schema(schema.id.clone().into()))
+        });
+        let items = self
+            .entry_by_id
+            .values()
+            .filter(|entry| entry.owner_id == role_id)
+            .map(|entry| ObjectId::Item(entry.id));
+        let compute_instances = self
+            .compute_instances_by_id
+            .values()
+            .filter(|instance| instance.owner_id == role_id)
+            .map(|instance| ObjectId::ComputeInstance(instance.id));
+        databases
+            .chain(schemas)
+            .chain(items)
+            .chain(compute_instances)
+            .any(|object| !ignore.contains(&object))
+    }
+
+    /// Returns the non-temporary schema identified by `id`.
+    pub fn get_schema_by_id(&self, id: &SchemaId) -> &Schema {
+        self.ambient_schemas_by_id
+            .get(id)
+            .or_else(|| {
+                self.database_by_id
+                    .values()
+                    .find_map(|db| db.schemas_by_id.get(id))
+            })
+            .expect("catalog out of sync")
+    }
+
+    /// Returns the ID of the role that owns `object`.
+    pub fn owner_id(&self, object: ObjectId) -> i64 {
+        match object {
+            ObjectId::Database(id) => self.database_by_id[&id].owner_id,
+            ObjectId::Schema(id) => self.get_schema_by_id(&id).owner_id,
+            ObjectId::Item(id) => self.get_entry(&id).owner_id,
+            ObjectId::ComputeInstance(id) => self.compute_instances_by_id[&id].owner_id,
+        }
+    }
+
+    /// Describes `object` for the audit log, returning its type and details
+    /// that identify it.
+    fn audit_object(&self, object: ObjectId) -> (ObjectType, serde_json::Value) {
+        match object {
+            ObjectId::Database(id) => (
+                ObjectType::Database,
+                json!({"id": id.to_string(), "name": self.database_by_id[&id].name}),
+            ),
+            ObjectId::Schema(id) => {
+                let schema = self.get_schema_by_id(&id);
+                let name = match &schema.name.database {
+                    ResolvedDatabaseSpecifier::Id(database_id) => format!(
+                        "{}.{}",
+                        self.database_by_id[database_id].name, schema.name.schema
+                    ),
+                    ResolvedDatabaseSpecifier::Ambient => schema.name.schema.clone(),
+                };
+                (
+                    ObjectType::Schema,
+                    json!({"id": id.to_string(), "name": name}),
+                )
+            }
+            ObjectId::Item(id) => {
+                let entry = self.get_entry(&id);
+                let name = self.resolve_full_name(entry.name(), entry.conn_id());
+                (
+                    entry.item().typ().into(),
+                    json!({"id": id.to_string(), "name": name.to_string()}),
+                )
+            }
+            ObjectId::ComputeInstance(id) => (
+                ObjectType::Cluster,
+                json!({"id": id.to_string(), "name": self.compute_instances_by_id[&id].name}),
+            ),
+        }
+    }
+
+    /// Reports whether the role identified by `role_id` holds `privilege` on
+    /// `object`, either through ownership or through a grant to the role or to
+    /// `PUBLIC`.
+    ///
+    /// Superuser status is not considered.
+    pub fn has_privilege(&self, role_id: i64, object: ObjectId, privilege: Privilege) -> bool {
+        if self.owner_id(object) == role_id {
+            return true;
+        }
+        match self.privileges.get(&object) {
+            None => false,
+            Some(grants) => [role_id, PUBLIC_ROLE_ID].iter().any(|grantee_id| {
+                grants
+                    .get(grantee_id)
+                    .map_or(false, |privileges| privileges.contains(&privilege))
+            }),
+        }
+    }
+
     async fn insert_compute_instance(
         &mut self,
         id: ComputeInstanceId,
         name: String,
         introspection: Option<ComputeInstanceIntrospectionConfig>,
         introspection_sources: Vec<(&'static BuiltinLog, GlobalId)>,
+        owner_id: i64,
     ) {
         let logging = match introspection {
             None => None,
@@ -448,6 +568,7 @@ impl CatalogState {
                             depends_on: vec![log_id],
                             compute_instance: id,
                         }),
+                        MZ_SYSTEM.id,
                     );
                     active_logs.insert(log.variant.clone(), index_id);
                 }
@@ -468,6 +589,7 @@ impl CatalogState {
                 logging,
                 replica_id_by_name: HashMap::new(),
                 replicas_by_id: HashMap::new(),
+                owner_id,
             },
         );
         assert!(self.compute_instances_by_name.insert(name, id).is_none());
@@ -622,6 +744,7 @@ impl CatalogState {
                 SourceConnector::External { connector, .. } => match &connector {
                     ExternalSourceConnector::PubNub(_) => Volatile,
                     ExternalSourceConnector::Kinesis(_) => Volatile,
+                    ExternalSourceConnector::LoadGenerator(_) => Nonvolatile,
                     _ => Unknown,
                 },
                 SourceConnector::Local { .. } => Volatile,
@@ -869,6 +992,7 @@ pub struct Database {
     pub oid: u32,
     pub schemas_by_id: BTreeMap<SchemaId, Schema>,
     pub schemas_by_name: BTreeMap<String, SchemaId>,
+    pub owner_id: i64,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -879,6 +1003,7 @@ pub struct Schema {
     pub oid: u32,
     pub items: BTreeMap<String, GlobalId>,
     pub functions: BTreeMap<String, GlobalId>,
+    pub owner_id: i64,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -887,6 +1012,9 @@ pub struct Role {
     pub id: i64,
     #[serde(skip)]
     pub oid: u32,
+    #[serde(skip)]
+    pub password_verifier: Option<ScramVerifier>,
+    pub superuser: bool,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -898,6 +1026,7 @@ pub struct ComputeInstance {
     pub indexes: HashSet<GlobalId>,
     pub replica_id_by_name: HashMap<String, ReplicaId>,
     pub replicas_by_id: HashMap<ReplicaId, ConcreteComputeInstanceReplicaConfig>,
+    pub owner_id: i64,
 }
 
 #[derive(Clone, Debug)]
@@ -907,6 +1036,7 @@ pub struct CatalogEntry {
     id: GlobalId,
     oid: u32,
     name: QualifiedObjectName,
+    owner_id: i64,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -978,6 +1108,8 @@ pub struct View {
     pub desc: RelationDesc,
     pub conn_id: Option<u32>,
     pub depends_on: Vec<GlobalId>,
+    /// Whether the view was planned with `native_outer_joins_experimental`.
+    pub native_outer_joins: bool,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -1216,6 +1348,11 @@ impl CatalogItem {
 }
 
 impl CatalogEntry {
+    /// Returns the ID of the role that owns this catalog item.
+    pub fn owner_id(&self) -> i64 {
+        self.owner_id
+    }
+
     /// Reports the description of the datums produced by this catalog item.
     pub fn desc(&self, name: &FullObjectName) -> Result<&RelationDesc, SqlCatalogError> {
         self.item.desc(name)
@@ -1369,6 +1506,7 @@ impl<S: Append> Catalog<S> {
                 compute_instances_by_id: HashMap::new(),
                 compute_instances_by_name: HashMap::new(),
                 roles: HashMap::new(),
+                privileges: BTreeMap::new(),
                 config: mz_sql::catalog::CatalogConfig {
                     start_time: to_datetime((config.now)()),
                     start_instant: Instant::now(),
@@ -1390,7 +1528,7 @@ impl<S: Append> Catalog<S> {
         catalog.create_temporary_schema(SYSTEM_CONN_ID).await?;
 
         let databases = catalog.storage().await.load_databases().await?;
-        for (id, name) in databases {
+        for (id, name, owner_id) in databases {
             let oid = catalog.allocate_oid().await?;
             catalog.state.database_by_id.insert(
                 id.clone(),
@@ -1400,6 +1538,7 @@ impl<S: Append> Catalog<S> {
                     oid,
                     schemas_by_id: BTreeMap::new(),
                     schemas_by_name: BTreeMap::new(),
+                    owner_id,
                 },
             );
             catalog
@@ -1409,7 +1548,7 @@ impl<S: Append> Catalog<S> {
         }
 
         let schemas = catalog.storage().await.load_schemas().await?;
-        for (schema_id, schema_name, database_id) in schemas {
+        for (schema_id, schema_name, database_id, owner_id) in schemas {
             let oid = catalog.allocate_oid().await?;
             let (schemas_by_id, schemas_by_name, database_spec) = match &database_id {
                 Some(database_id) => {
@@ -1441,25 +1580,49 @@ impl<S: Append> Catalog<S> {
                     oid,
                     items: BTreeMap::new(),
                     functions: BTreeMap::new(),
+                    owner_id,
                 },
             );
             schemas_by_name.insert(schema_name.clone(), schema_id);
         }
 
         let roles = catalog.storage().await.load_roles().await?;
-        let builtin_roles = BUILTIN_ROLES.iter().map(|b| (b.id, b.name.to_owned()));
-        for (id, name) in roles.into_iter().chain(builtin_roles) {
+        let builtin_roles = BUILTIN_ROLES
+            .iter()
+            .map(|b| (b.id, b.name.to_owned(), None, true));
+        for (id, name, password_verifier, superuser) in roles.into_iter().chain(builtin_roles) {
             let oid = catalog.allocate_oid().await?;
+            let password_verifier = match password_verifier.map(|v| v.parse()).transpose() {
+                Ok(password_verifier) => password_verifier,
+                Err(e) => {
+                    return Err(Error::new(ErrorKind::Corruption {
+                        detail: format!("failed to parse password of role {}: {}", name, e),
+                    }))
+                }
+            };
             catalog.state.roles.insert(
                 name.clone(),
                 Role {
                     name: name.clone(),
                     id,
                     oid,
+                    password_verifier,
+                    superuser,
                 },
             );
         }
 
+        for (object, grantee_id, privilege) in catalog.storage().await.load_privileges().await? {
+            catalog
+                .state
+                .privileges
+                .entry(object)
+                .or_default()
+                .entry(grantee_id)
+                .or_default()
+                .insert(privilege);
+        }
+
         catalog.load_builtin_types().await?;
 
         let persisted_builtin_ids = catalog.storage().await.load_system_gids().await?;
@@ -1505,6 +1668,7 @@ impl<S: Append> Catalog<S> {
                             desc: log.variant.desc(),
                             depends_on: vec![],
                         }),
+                        MZ_SYSTEM.id,
                     );
                 }
 
@@ -1521,6 +1685,7 @@ impl<S: Append> Catalog<S> {
                             conn_id: None,
                             depends_on: vec![],
                         }),
+                        MZ_SYSTEM.id,
                     );
                 }
 
@@ -1541,7 +1706,7 @@ impl<S: Append> Catalog<S> {
                             )
                         });
                     let oid = catalog.allocate_oid().await?;
-                    catalog.state.insert_item(id, oid, name, item);
+                    catalog.state.insert_item(id, oid, name, item, MZ_SYSTEM.id);
                 }
 
                 Builtin::Type(_) => unreachable!("loaded separately"),
@@ -1553,6 +1718,7 @@ impl<S: Append> Catalog<S> {
                         oid,
                         name.clone(),
                         CatalogItem::Func(Func { inner: func.inner }),
+                        MZ_SYSTEM.id,
                     );
                 }
             }
@@ -1579,7 +1745,7 @@ impl<S: Append> Catalog<S> {
             .await?;
 
         let compute_instances = catalog.storage().await.load_compute_instances().await?;
-        for (id, name, introspection) in compute_instances {
+        for (id, name, introspection, owner_id) in compute_instances {
             let introspection_sources = if introspection.is_some() {
                 let introspection_source_index_gids = catalog
                     .storage()
@@ -1618,7 +1784,7 @@ impl<S: Append> Catalog<S> {
             };
             catalog
                 .state
-                .insert_compute_instance(id, name, introspection, introspection_sources)
+                .insert_compute_instance(id, name, introspection, introspection_sources, owner_id)
                 .await;
         }
 
@@ -1664,6 +1830,11 @@ impl<S: Append> Catalog<S> {
         for (schema_id, schema) in &catalog.state.ambient_schemas_by_id {
             let db_spec = ResolvedDatabaseSpecifier::Ambient;
             builtin_table_updates.push(catalog.state.pack_schema_update(&db_spec, schema_id, 1));
+            builtin_table_updates.push(
+                catalog
+                    .state
+                    .pack_object_owner_update(ObjectId::Schema(*schema_id), 1),
+            );
             for (_item_name, item_id) in &schema.items {
                 builtin_table_updates.extend(catalog.state.pack_item_update(*item_id, 1));
             }
@@ -1673,10 +1844,20 @@ impl<S: Append> Catalog<S> {
         }
         for (db_id, db) in &catalog.state.database_by_id {
             builtin_table_updates.push(catalog.state.pack_database_update(db_id, 1));
+            builtin_table_updates.push(
+                catalog
+                    .state
+                    .pack_object_owner_update(ObjectId::Database(*db_id), 1),
+            );
             let db_spec = ResolvedDatabaseSpecifier::Id(db.id.clone());
             for (schema_id, schema) in &db.schemas_by_id {
                 builtin_table_updates
                     .push(catalog.state.pack_schema_update(&db_spec, schema_id, 1));
+                builtin_table_updates.push(
+                    catalog
+                        .state
+                        .pack_object_owner_update(ObjectId::Schema(*schema_id), 1),
+                );
                 for (_item_name, item_id) in &schema.items {
                     builtin_table_updates.extend(catalog.state.pack_item_update(*item_id, 1));
                 }
@@ -1688,8 +1869,19 @@ impl<S: Append> Catalog<S> {
         for (role_name, _role) in &catalog.state.roles {
             builtin_table_updates.push(catalog.state.pack_role_update(role_name, 1));
         }
+        for (object, _grants) in &catalog.state.privileges {
+            builtin_table_updates.extend(catalog.state.pack_privileges_update(*object, 1));
+        }
+        for event in catalog.storage().await.load_audit_events().await? {
+            builtin_table_updates.push(catalog.state.pack_audit_event_update(&event, 1));
+        }
         for (name, id) in &catalog.state.compute_instances_by_name {
             builtin_table_updates.push(catalog.state.pack_compute_instance_update(name, 1));
+            builtin_table_updates.push(
+                catalog
+                    .state
+                    .pack_object_owner_update(ObjectId::ComputeInstance(*id), 1),
+            );
             let instance = &catalog.state.compute_instances_by_id[id];
             for (replica_name, _replica_id) in &instance.replica_id_by_name {
                 builtin_table_updates.push(catalog.state.pack_compute_instance_replica_update(
@@ -1769,6 +1961,7 @@ impl<S: Append> Catalog<S> {
                     details: typ.details.clone(),
                     depends_on: vec![],
                 }),
+                MZ_SYSTEM.id,
             );
         }
 
@@ -1870,7 +2063,7 @@ impl<S: Append> Catalog<S> {
     ) -> Result<Catalog<S>, Error> {
         let mut c = c.clone();
         let items = tx.loaded_items();
-        for (id, name, def) in items {
+        for (id, name, def, owner_id) in items {
             // TODO(benesch): a better way of detecting when a view has depended
             // upon a non-existent logging view. This is fine for now because
             // the only goal is to produce a nicer error message; we'll bail out
@@ -1893,7 +2086,7 @@ impl<S: Append> Catalog<S> {
                 }
             };
             let oid = c.allocate_oid().await?;
-            c.state.insert_item(id, oid, name, item);
+            c.state.insert_item(id, oid, name, item, owner_id);
         }
         c.transient_revision = 1;
         Ok(c)
@@ -2038,6 +2231,33 @@ impl<S: Append> Catalog<S> {
         self.state.allocate_oid()
     }
 
+    /// Loads the durable replication slots and the latest LSN that the
+    /// consumer of each has confirmed flushing.
+    pub async fn load_replication_slots(&self) -> Result<Vec<(String, u64)>, Error> {
+        self.storage().await.load_replication_slots().await
+    }
+
+    /// Durably records the latest LSN that the consumer of the replication
+    /// slot `name` has confirmed flushing, creating the slot if necessary.
+    ///
+    /// Replication slots are not catalog items, so this does not bump the
+    /// catalog's revision.
+    pub async fn set_replication_slot(
+        &self,
+        name: &str,
+        confirmed_flush: u64,
+    ) -> Result<(), Error> {
+        self.storage()
+            .await
+            .set_replication_slot(name, confirmed_flush)
+            .await
+    }
+
+    /// Durably removes the replication slot `name`.
+    pub async fn remove_replication_slot(&self, name: &str) -> Result<(), Error> {
+        self.storage().await.remove_replication_slot(name).await
+    }
+
     pub fn resolve_database(&self, database_name: &str) -> Result<&Database, SqlCatalogError> {
         self.state.resolve_database(database_name)
     }
@@ -2104,10 +2324,28 @@ impl<S: Append> Catalog<S> {
         self.state.resolve_compute_instance(name)
     }
 
+    pub fn get_compute_instance(&self, id: ComputeInstanceId) -> &ComputeInstance {
+        &self.state.compute_instances_by_id[&id]
+    }
+
     pub fn state(&self) -> &CatalogState {
         &self.state
     }
 
+    /// Returns the named role, if it exists.
+    pub fn get_role(&self, role_name: &str) -> Option<&Role> {
+        self.state.roles.get(role_name)
+    }
+
+    /// Returns the password verifier of the named role, if the role exists
+    /// and has a password.
+    pub fn password_verifier(&self, role_name: &str) -> Option<&ScramVerifier> {
+        self.state
+            .roles
+            .get(role_name)
+            .and_then(|role| role.password_verifier.as_ref())
+    }
+
     pub fn resolve_full_name(
         &self,
         name: &QualifiedObjectName,
@@ -2177,6 +2415,7 @@ impl<S: Append> Catalog<S> {
                 oid,
                 items: BTreeMap::new(),
                 functions: BTreeMap::new(),
+                owner_id: MZ_SYSTEM.id,
             },
         );
         Ok(())
@@ -2188,6 +2427,14 @@ impl<S: Append> Catalog<S> {
             .contains_key(item_name)
     }
 
+    /// Reports whether the connection has created any temporary items.
+    pub fn has_temporary_items(&self, conn_id: u32) -> bool {
+        self.state
+            .temporary_schemas
+            .get(&conn_id)
+            .map_or(false, |schema| !schema.items.is_empty())
+    }
+
     pub fn drop_temp_item_ops(&mut self, conn_id: u32) -> Vec<Op> {
         let ids: Vec<GlobalId> = self.state.temporary_schemas[&conn_id]
             .items
@@ -2282,13 +2529,7 @@ impl<S: Append> Catalog<S> {
         let mut creating = HashSet::with_capacity(ops.len());
         let mut temporary_ids = Vec::with_capacity(ops.len());
         for op in ops.iter() {
-            if let Op::CreateItem {
-                id,
-                oid: _,
-                name,
-                item,
-            } = op
-            {
+            if let Op::CreateItem { id, name, item, .. } = op {
                 if let Some(conn_id) = item.conn_id() {
                     if self.item_exists_in_temp_schemas(conn_id, &name.item)
                         && !temporary_drops.contains(&(conn_id, name.item.clone()))
@@ -2305,8 +2546,13 @@ impl<S: Append> Catalog<S> {
         Ok(temporary_ids)
     }
 
+    /// Applies `ops` to the catalog.
+    ///
+    /// If the transaction was initiated by `session`, an event describing each
+    /// change to a non-temporary object is appended to the audit log.
     pub async fn transact<F, T>(
         &mut self,
+        session: Option<&Session>,
         ops: Vec<Op>,
         f: F,
     ) -> Result<(Vec<BuiltinTableUpdate>, T), CoordError>
@@ -2321,23 +2567,28 @@ impl<S: Append> Catalog<S> {
                 id: DatabaseId,
                 oid: u32,
                 name: String,
+                owner_id: i64,
             },
             CreateSchema {
                 id: SchemaId,
                 oid: u32,
                 database_id: DatabaseId,
                 schema_name: String,
+                owner_id: i64,
             },
             CreateRole {
                 id: i64,
                 oid: u32,
                 name: String,
+                password_verifier: Option<ScramVerifier>,
+                superuser: bool,
             },
             CreateComputeInstance {
                 id: ComputeInstanceId,
                 name: String,
                 config: Option<ComputeInstanceIntrospectionConfig>,
                 introspection_sources: Vec<(&'static BuiltinLog, GlobalId)>,
+                owner_id: i64,
             },
             CreateComputeInstanceReplica {
                 id: ReplicaId,
@@ -2350,6 +2601,7 @@ impl<S: Append> Catalog<S> {
                 oid: u32,
                 name: QualifiedObjectName,
                 item: CatalogItem,
+                owner_id: i64,
             },
 
             DropDatabase {
@@ -2375,6 +2627,16 @@ impl<S: Append> Catalog<S> {
                 to_name: QualifiedObjectName,
                 to_item: CatalogItem,
             },
+            GrantPrivilege {
+                object: ObjectId,
+                grantee_id: i64,
+                privilege: Privilege,
+            },
+            RevokePrivilege {
+                object: ObjectId,
+                grantee_id: i64,
+                privilege: Privilege,
+            },
         }
 
         let drop_ids: HashSet<_> = ops
@@ -2395,7 +2657,24 @@ impl<S: Append> Catalog<S> {
             })
             .collect();
         let temporary_ids = self.temporary_ids(&ops, temporary_drops)?;
+        // Objects dropped in this transaction no longer prevent their owner
+        // from being dropped.
+        let drop_objects: HashSet<_> = ops
+            .iter()
+            .filter_map(|op| match op {
+                Op::DropDatabase { id } => Some(ObjectId::Database(*id)),
+                Op::DropSchema { schema_id, .. } => Some(ObjectId::Schema(*schema_id)),
+                Op::DropComputeInstance { name } => self
+                    .state
+                    .compute_instances_by_name
+                    .get(name)
+                    .map(|id| ObjectId::ComputeInstance(*id)),
+                Op::DropItem(id) => Some(ObjectId::Item(*id)),
+                _ => None,
+            })
+            .collect();
         let mut builtin_table_updates = vec![];
+        let mut audit_events = vec![];
         let mut actions = Vec::with_capacity(ops.len());
         let mut storage = self.storage().await;
         let mut tx = storage.transaction().await?;
@@ -2405,19 +2684,22 @@ impl<S: Append> Catalog<S> {
                     name,
                     oid,
                     public_schema_oid,
+                    owner_id,
                 } => {
-                    let database_id = tx.insert_database(&name)?;
+                    let database_id = tx.insert_database(&name, owner_id)?;
                     vec![
                         Action::CreateDatabase {
                             id: database_id,
                             oid,
                             name,
+                            owner_id,
                         },
                         Action::CreateSchema {
-                            id: tx.insert_schema(database_id, DEFAULT_SCHEMA)?,
+                            id: tx.insert_schema(database_id, DEFAULT_SCHEMA, owner_id)?,
                             oid: public_schema_oid,
                             database_id,
                             schema_name: DEFAULT_SCHEMA.to_string(),
+                            owner_id,
                         },
                     ]
                 }
@@ -2425,6 +2707,7 @@ impl<S: Append> Catalog<S> {
                     database_id,
                     schema_name,
                     oid,
+                    owner_id,
                 } => {
                     if is_reserved_name(&schema_name) {
                         return Err(CoordError::Catalog(Error::new(
@@ -2440,28 +2723,42 @@ impl<S: Append> Catalog<S> {
                         }
                     };
                     vec![Action::CreateSchema {
-                        id: tx.insert_schema(database_id, &schema_name)?,
+                        id: tx.insert_schema(database_id, &schema_name, owner_id)?,
                         oid,
                         database_id,
                         schema_name,
+                        owner_id,
                     }]
                 }
-                Op::CreateRole { name, oid } => {
+                Op::CreateRole {
+                    name,
+                    oid,
+                    password_verifier,
+                    superuser,
+                } => {
                     if is_reserved_name(&name) {
                         return Err(CoordError::Catalog(Error::new(
                             ErrorKind::ReservedRoleName(name),
                         )));
                     }
+                    let id = tx.insert_role(
+                        &name,
+                        password_verifier.as_ref().map(|v| v.to_string()),
+                        superuser,
+                    )?;
                     vec![Action::CreateRole {
-                        id: tx.insert_role(&name)?,
+                        id,
                         oid,
                         name,
+                        password_verifier,
+                        superuser,
                     }]
                 }
                 Op::CreateComputeInstance {
                     name,
                     config,
                     introspection_sources,
+                    owner_id,
                 } => {
                     if is_reserved_name(&name) {
                         return Err(CoordError::Catalog(Error::new(
@@ -2469,10 +2766,16 @@ impl<S: Append> Catalog<S> {
                         )));
                     }
                     vec![Action::CreateComputeInstance {
-                        id: tx.insert_compute_instance(&name, &config, &introspection_sources)?,
+                        id: tx.insert_compute_instance(
+                            &name,
+                            &config,
+                            &introspection_sources,
+                            owner_id,
+                        )?,
                         name,
                         config,
                         introspection_sources,
+                        owner_id,
                     }]
                 }
                 Op::CreateComputeInstanceReplica {
@@ -2497,6 +2800,7 @@ impl<S: Append> Catalog<S> {
                     oid,
                     name,
                     item,
+                    owner_id,
                 } => {
                     if item.is_temporary() {
                         if name.qualifiers.database_spec != ResolvedDatabaseSpecifier::Ambient
@@ -2527,7 +2831,7 @@ impl<S: Append> Catalog<S> {
                         }
                         let schema_id = name.qualifiers.schema_spec.clone().into();
                         let serialized_item = self.serialize_item(&item);
-                        tx.insert_item(id, schema_id, &name.item, &serialized_item)?;
+                        tx.insert_item(id, schema_id, &name.item, &serialized_item, owner_id)?;
                     }
 
                     vec![Action::CreateItem {
@@ -2535,11 +2839,18 @@ impl<S: Append> Catalog<S> {
                         oid,
                         name,
                         item,
+                        owner_id,
                     }]
                 }
                 Op::DropDatabase { id } => {
                     tx.remove_database(&id)?;
                     builtin_table_updates.push(self.state.pack_database_update(&id, -1));
+                    let object = ObjectId::Database(id);
+                    let (object_type, details) = self.state.audit_object(object);
+                    audit_events.push((EventType::Drop, object_type, details));
+                    tx.remove_privileges_on(object);
+                    builtin_table_updates.push(self.state.pack_object_owner_update(object, -1));
+                    builtin_table_updates.extend(self.state.pack_privileges_update(object, -1));
                     vec![Action::DropDatabase { id }]
                 }
                 Op::DropSchema {
@@ -2552,19 +2863,46 @@ impl<S: Append> Catalog<S> {
                         &schema_id,
                         -1,
                     ));
+                    let object = ObjectId::Schema(schema_id);
+                    let (object_type, details) = self.state.audit_object(object);
+                    audit_events.push((EventType::Drop, object_type, details));
+                    tx.remove_privileges_on(object);
+                    builtin_table_updates.push(self.state.pack_object_owner_update(object, -1));
+                    builtin_table_updates.extend(self.state.pack_privileges_update(object, -1));
                     vec![Action::DropSchema {
                         database_id,
                         schema_id,
                     }]
                 }
                 Op::DropRole { name } => {
+                    let role_id = self.state.roles[&name].id;
+                    if self.state.role_owns_objects(role_id, &drop_objects) {
+                        return Err(CoordError::Catalog(Error::new(ErrorKind::RoleOwnsObjects(
+                            name,
+                        ))));
+                    }
                     tx.remove_role(&name)?;
+                    tx.remove_privileges_of(role_id);
+                    audit_events.push((
+                        EventType::Drop,
+                        ObjectType::Role,
+                        json!({"id": role_id.to_string(), "name": name}),
+                    ));
                     builtin_table_updates.push(self.state.pack_role_update(&name, -1));
+                    builtin_table_updates
+                        .extend(self.state.pack_grantee_privileges_update(role_id, -1));
                     vec![Action::DropRole { name }]
                 }
                 Op::DropComputeInstance { name } => {
+                    let object =
+                        ObjectId::ComputeInstance(self.state.compute_instances_by_name[&name]);
+                    let (object_type, details) = self.state.audit_object(object);
+                    audit_events.push((EventType::Drop, object_type, details));
                     let introspection_source_index_ids = tx.remove_compute_instance(&name)?;
                     builtin_table_updates.push(self.state.pack_compute_instance_update(&name, -1));
+                    tx.remove_privileges_on(object);
+                    builtin_table_updates.push(self.state.pack_object_owner_update(object, -1));
+                    builtin_table_updates.extend(self.state.pack_privileges_update(object, -1));
                     for id in introspection_source_index_ids {
                         builtin_table_updates.extend(self.state.pack_item_update(id, -1));
                     }
@@ -2572,6 +2910,16 @@ impl<S: Append> Catalog<S> {
                 }
                 Op::DropComputeInstanceReplica { name, compute_id } => {
                     tx.remove_compute_instance_replica(&name, compute_id)?;
+                    let instance = &self.state.compute_instances_by_id[&compute_id];
+                    audit_events.push((
+                        EventType::Drop,
+                        ObjectType::ClusterReplica,
+                        json!({
+                            "id": instance.replica_id_by_name[&name].to_string(),
+                            "name": name,
+                            "cluster_name": instance.name,
+                        }),
+                    ));
                     builtin_table_updates.push(
                         self.state
                             .pack_compute_instance_replica_update(compute_id, &name, -1),
@@ -2581,8 +2929,13 @@ impl<S: Append> Catalog<S> {
                 Op::DropItem(id) => {
                     if !self.get_entry(&id).item().is_temporary() {
                         tx.remove_item(id)?;
+                        let (object_type, details) = self.state.audit_object(ObjectId::Item(id));
+                        audit_events.push((EventType::Drop, object_type, details));
                     }
+                    let object = ObjectId::Item(id);
+                    tx.remove_privileges_on(object);
                     builtin_table_updates.extend(self.state.pack_item_update(id, -1));
+                    builtin_table_updates.extend(self.state.pack_privileges_update(object, -1));
                     vec![Action::DropItem(id)]
                 }
                 Op::RenameItem {
@@ -2664,6 +3017,15 @@ impl<S: Append> Catalog<S> {
                     }
                     if !item.is_temporary() {
                         tx.update_item(id, &to_full_name.item, &serialized_item)?;
+                        audit_events.push((
+                            EventType::Alter,
+                            item.typ().into(),
+                            json!({
+                                "id": id.to_string(),
+                                "previous_name": current_full_name.to_string(),
+                                "new_name": to_full_name.to_string(),
+                            }),
+                        ));
                     }
                     builtin_table_updates.extend(self.state.pack_item_update(id, -1));
                     actions.push(Action::UpdateItem {
@@ -2689,6 +3051,46 @@ impl<S: Append> Catalog<S> {
                         to_item,
                     }]
                 }
+                Op::GrantPrivilege {
+                    object,
+                    grantee_id,
+                    privilege,
+                } => {
+                    if let ObjectId::Item(id) = object {
+                        let entry = self.get_entry(&id);
+                        if entry.item().is_temporary() {
+                            return Err(CoordError::Catalog(Error::new(
+                                ErrorKind::TemporaryPrivilegeObject(entry.name().item.clone()),
+                            )));
+                        }
+                    }
+                    tx.insert_privilege(object, grantee_id, privilege);
+                    let (object_type, mut details) = self.state.audit_object(object);
+                    details["grantee_id"] = json!(grantee_id.to_string());
+                    details["privilege"] = json!(privilege.to_string());
+                    audit_events.push((EventType::Grant, object_type, details));
+                    vec![Action::GrantPrivilege {
+                        object,
+                        grantee_id,
+                        privilege,
+                    }]
+                }
+                Op::RevokePrivilege {
+                    object,
+                    grantee_id,
+                    privilege,
+                } => {
+                    tx.remove_privilege(object, grantee_id, privilege);
+                    let (object_type, mut details) = self.state.audit_object(object);
+                    details["grantee_id"] = json!(grantee_id.to_string());
+                    details["privilege"] = json!(privilege.to_string());
+                    audit_events.push((EventType::Revoke, object_type, details));
+                    vec![Action::RevokePrivilege {
+                        object,
+                        grantee_id,
+                        privilege,
+                    }]
+                }
             });
         }
 
@@ -2697,7 +3099,12 @@ impl<S: Append> Catalog<S> {
 
         for action in actions {
             match action {
-                Action::CreateDatabase { id, oid, name } => {
+                Action::CreateDatabase {
+                    id,
+                    oid,
+                    name,
+                    owner_id,
+                } => {
                     info!("create database {}", name);
                     state.database_by_id.insert(
                         id.clone(),
@@ -2707,10 +3114,15 @@ impl<S: Append> Catalog<S> {
                             oid,
                             schemas_by_id: BTreeMap::new(),
                             schemas_by_name: BTreeMap::new(),
+                            owner_id,
                         },
                     );
                     state.database_by_name.insert(name.clone(), id.clone());
                     builtin_table_updates.push(state.pack_database_update(&id, 1));
+                    let (object_type, details) = state.audit_object(ObjectId::Database(id));
+                    audit_events.push((EventType::Create, object_type, details));
+                    builtin_table_updates
+                        .push(state.pack_object_owner_update(ObjectId::Database(id), 1));
                 }
 
                 Action::CreateSchema {
@@ -2718,6 +3130,7 @@ impl<S: Append> Catalog<S> {
                     oid,
                     database_id,
                     schema_name,
+                    owner_id,
                 } => {
                     info!(
                         "create schema {}.{}",
@@ -2736,17 +3149,28 @@ impl<S: Append> Catalog<S> {
                             oid,
                             items: BTreeMap::new(),
                             functions: BTreeMap::new(),
+                            owner_id,
                         },
                     );
                     db.schemas_by_name.insert(schema_name.clone(), id.clone());
+                    let (object_type, details) = state.audit_object(ObjectId::Schema(id));
+                    audit_events.push((EventType::Create, object_type, details));
                     builtin_table_updates.push(state.pack_schema_update(
                         &ResolvedDatabaseSpecifier::Id(database_id.clone()),
                         &id,
                         1,
                     ));
+                    builtin_table_updates
+                        .push(state.pack_object_owner_update(ObjectId::Schema(id), 1));
                 }
 
-                Action::CreateRole { id, oid, name } => {
+                Action::CreateRole {
+                    id,
+                    oid,
+                    name,
+                    password_verifier,
+                    superuser,
+                } => {
                     info!("create role {}", name);
                     state.roles.insert(
                         name.clone(),
@@ -2754,9 +3178,16 @@ impl<S: Append> Catalog<S> {
                             name: name.clone(),
                             id,
                             oid,
+                            password_verifier,
+                            superuser,
                         },
                     );
                     builtin_table_updates.push(state.pack_role_update(&name, 1));
+                    audit_events.push((
+                        EventType::Create,
+                        ObjectType::Role,
+                        json!({"id": id.to_string(), "name": name, "superuser": superuser}),
+                    ));
                 }
 
                 Action::CreateComputeInstance {
@@ -2764,14 +3195,25 @@ impl<S: Append> Catalog<S> {
                     name,
                     config,
                     introspection_sources,
+                    owner_id,
                 } => {
                     info!("create cluster {}", name);
                     let introspection_source_index_ids: Vec<GlobalId> =
                         introspection_sources.iter().map(|(_, id)| *id).collect();
                     state
-                        .insert_compute_instance(id, name.clone(), config, introspection_sources)
+                        .insert_compute_instance(
+                            id,
+                            name.clone(),
+                            config,
+                            introspection_sources,
+                            owner_id,
+                        )
                         .await;
                     builtin_table_updates.push(state.pack_compute_instance_update(&name, 1));
+                    builtin_table_updates
+                        .push(state.pack_object_owner_update(ObjectId::ComputeInstance(id), 1));
+                    let (object_type, details) = state.audit_object(ObjectId::ComputeInstance(id));
+                    audit_events.push((EventType::Create, object_type, details));
                     for id in introspection_source_index_ids {
                         builtin_table_updates.extend(state.pack_item_update(id, 1));
                     }
@@ -2796,6 +3238,15 @@ impl<S: Append> Catalog<S> {
                         &name,
                         1,
                     ));
+                    audit_events.push((
+                        EventType::Create,
+                        ObjectType::ClusterReplica,
+                        json!({
+                            "id": id.to_string(),
+                            "name": name,
+                            "cluster_name": on_cluster_name,
+                        }),
+                    ));
                 }
 
                 Action::CreateItem {
@@ -2803,15 +3254,22 @@ impl<S: Append> Catalog<S> {
                     oid,
                     name,
                     item,
+                    owner_id,
                 } => {
-                    state.insert_item(id, oid, name, item);
+                    let is_temporary = item.is_temporary();
+                    state.insert_item(id, oid, name, item, owner_id);
                     builtin_table_updates.extend(state.pack_item_update(id, 1));
+                    if !is_temporary {
+                        let (object_type, details) = state.audit_object(ObjectId::Item(id));
+                        audit_events.push((EventType::Create, object_type, details));
+                    }
                 }
 
                 Action::DropDatabase { id } => {
                     let db = state.database_by_id.get(&id).unwrap();
                     state.database_by_name.remove(db.name());
                     state.database_by_id.remove(&id);
+                    state.privileges.remove(&ObjectId::Database(id));
                 }
 
                 Action::DropSchema {
@@ -2822,11 +3280,16 @@ impl<S: Append> Catalog<S> {
                     let schema = db.schemas_by_id.get(&schema_id).unwrap();
                     db.schemas_by_name.remove(&schema.name.schema);
                     db.schemas_by_id.remove(&schema_id);
+                    state.privileges.remove(&ObjectId::Schema(schema_id));
                 }
 
                 Action::DropRole { name } => {
-                    if state.roles.remove(&name).is_some() {
+                    if let Some(role) = state.roles.remove(&name) {
                         info!("drop role {}", name);
+                        for grants in state.privileges.values_mut() {
+                            grants.remove(&role.id);
+                        }
+                        state.privileges.retain(|_, grants| !grants.is_empty());
                     }
                 }
 
@@ -2845,6 +3308,7 @@ impl<S: Append> Catalog<S> {
                         instance.indexes.is_empty() && instance.replicas_by_id.is_empty(),
                         "not all items dropped before compute instance"
                     );
+                    state.privileges.remove(&ObjectId::ComputeInstance(id));
                 }
 
                 Action::DropComputeInstanceReplica { name, compute_id } => {
@@ -2858,6 +3322,7 @@ impl<S: Append> Catalog<S> {
                 }
 
                 Action::DropItem(id) => {
+                    state.privileges.remove(&ObjectId::Item(id));
                     let metadata = state.entry_by_id.remove(&id).unwrap();
                     if !metadata.item.is_placeholder() {
                         info!(
@@ -2930,6 +3395,62 @@ impl<S: Append> Catalog<S> {
                     state.entry_by_id.insert(id, new_entry.clone());
                     builtin_table_updates.extend(state.pack_item_update(id, 1));
                 }
+
+                Action::GrantPrivilege {
+                    object,
+                    grantee_id,
+                    privilege,
+                } => {
+                    let inserted = state
+                        .privileges
+                        .entry(object)
+                        .or_default()
+                        .entry(grantee_id)
+                        .or_default()
+                        .insert(privilege);
+                    if inserted {
+                        builtin_table_updates
+                            .push(state.pack_privilege_update(object, grantee_id, privilege, 1));
+                    }
+                }
+
+                Action::RevokePrivilege {
+                    object,
+                    grantee_id,
+                    privilege,
+                } => {
+                    let removed = state
+                        .privileges
+                        .get_mut(&object)
+                        .and_then(|grants| grants.get_mut(&grantee_id))
+                        .map_or(false, |privileges| privileges.remove(&privilege));
+                    if let Some(grants) = state.privileges.get_mut(&object) {
+                        grants.retain(|_, privileges| !privileges.is_empty());
+                        if grants.is_empty() {
+                            state.privileges.remove(&object);
+                        }
+                    }
+                    if removed {
+                        builtin_table_updates
+                            .push(state.pack_privilege_update(object, grantee_id, privilege, -1));
+                    }
+                }
+            }
+        }
+
+        // Changes that the system makes on its own behalf, like those made when
+        // a sink's connector becomes ready, are not audited.
+        if let Some(session) = session {
+            let occurred_at = (state.config.now)();
+            for (event_type, object_type, details) in audit_events {
+                let event = tx.insert_audit_event(
+                    event_type,
+                    object_type,
+                    details,
+                    session.user().into(),
+                    occurred_at,
+                );
+                builtin_table_updates.push(state.pack_audit_event_update(&event, 1));
             }
         }
 
@@ -2958,7 +3479,11 @@ impl<S: Append> Catalog<S> {
             },
             CatalogItem::View(view) => SerializedCatalogItem::V1 {
                 create_sql: view.create_sql.clone(),
-                eval_env: None,
+                eval_env: Some(SerializedPlanContext {
+                    logical_time: None,
+                    wall_time: None,
+                    native_outer_joins: view.native_outer_joins,
+                }),
             },
             CatalogItem::Index(index) => SerializedCatalogItem::V1 {
                 create_sql: index.create_sql.clone(),
@@ -2988,9 +3513,12 @@ impl<S: Append> Catalog<S> {
     fn deserialize_item(&self, bytes: Vec<u8>) -> Result<CatalogItem, anyhow::Error> {
         let SerializedCatalogItem::V1 {
             create_sql,
-            eval_env: _,
+            eval_env,
         } = serde_json::from_slice(&bytes)?;
-        self.parse_item(create_sql, Some(&PlanContext::zero()))
+        let pcx = eval_env
+            .map(PlanContext::from)
+            .unwrap_or_else(PlanContext::zero);
+        self.parse_item(create_sql, Some(&pcx))
     }
 
     // Parses the given SQL string into a `CatalogItem`.
@@ -3025,6 +3553,7 @@ impl<S: Append> Catalog<S> {
                     desc,
                     conn_id: None,
                     depends_on: view.depends_on,
+                    native_outer_joins: pcx.map_or(false, |pcx| pcx.native_outer_joins),
                 })
             }
             Plan::CreateIndex(CreateIndexPlan { index, .. }) => CatalogItem::Index(Index {
@@ -3122,20 +3651,25 @@ pub enum Op {
         name: String,
         oid: u32,
         public_schema_oid: u32,
+        owner_id: i64,
     },
     CreateSchema {
         database_id: ResolvedDatabaseSpecifier,
         schema_name: String,
         oid: u32,
+        owner_id: i64,
     },
     CreateRole {
         name: String,
         oid: u32,
+        password_verifier: Option<ScramVerifier>,
+        superuser: bool,
     },
     CreateComputeInstance {
         name: String,
         config: Option<ComputeInstanceIntrospectionConfig>,
         introspection_sources: Vec<(&'static BuiltinLog, GlobalId)>,
+        owner_id: i64,
     },
     CreateComputeInstanceReplica {
         name: String,
@@ -3147,6 +3681,7 @@ pub enum Op {
         oid: u32,
         name: QualifiedObjectName,
         item: CatalogItem,
+        owner_id: i64,
     },
     DropDatabase {
         id: DatabaseId,
@@ -3178,6 +3713,20 @@ pub enum Op {
         id: GlobalId,
         to_item: CatalogItem,
     },
+    /// Grants `privilege` on `object` to the role identified by `grantee_id`.
+    /// Granting a privilege that is already held is a no-op.
+    GrantPrivilege {
+        object: ObjectId,
+        grantee_id: i64,
+        privilege: Privilege,
+    },
+    /// Revokes `privilege` on `object` from the role identified by
+    /// `grantee_id`. Revoking a privilege that is not held is a no-op.
+    RevokePrivilege {
+        object: ObjectId,
+        grantee_id: i64,
+        privilege: Privilege,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -3193,6 +3742,10 @@ enum SerializedCatalogItem {
 struct SerializedPlanContext {
     pub logical_time: Option<u64>,
     pub wall_time: Option<DateTime<Utc>>,
+    /// Absent from items serialized before native outer joins existed, which
+    /// were planned without them.
+    #[serde(default)]
+    pub native_outer_joins: bool,
 }
 
 impl From<SerializedPlanContext> for PlanContext {
@@ -3200,6 +3753,7 @@ impl From<SerializedPlanContext> for PlanContext {
         PlanContext {
             wall_time: cx.wall_time.unwrap_or_else(|| Utc.timestamp(0, 0)),
             qgm_optimizations: false,
+            native_outer_joins: cx.native_outer_joins,
         }
     }
 }
@@ -3209,6 +3763,7 @@ impl From<PlanContext> for SerializedPlanContext {
         SerializedPlanContext {
             logical_time: None,
             wall_time: Some(cx.wall_time),
+            native_outer_joins: cx.native_outer_joins,
         }
     }
 }
@@ -3647,7 +4202,10 @@ mod tests {
         SchemaSpecifier,
     };
 
-    use crate::catalog::{Catalog, Op};
+    use mz_sql::plan::PlanContext;
+
+    use crate::catalog::builtin::MZ_SYSTEM;
+    use crate::catalog::{Catalog, CatalogItem, Op, SerializedCatalogItem};
     use crate::session::Session;
 
     /// System sessions have an empty `search_path` so it's necessary to
@@ -3736,10 +4294,12 @@ mod tests {
         assert_eq!(catalog.transient_revision(), 1);
         catalog
             .transact(
+                None,
                 vec![Op::CreateDatabase {
                     name: "test".to_string(),
                     oid: 1,
                     public_schema_oid: 2,
+                    owner_id: MZ_SYSTEM.id,
                 }],
                 |_catalog| Ok(()),
             )
@@ -3875,4 +4435,37 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_view_native_outer_joins_round_trip() -> Result<(), anyhow::Error> {
+        let data_dir = TempDir::new()?;
+        let catalog = Catalog::open_debug_sqlite(data_dir.path(), NOW_ZERO.clone()).await?;
+        let create_sql = "CREATE VIEW materialize.public.v AS SELECT 1".to_string();
+
+        for native_outer_joins in [false, true] {
+            let pcx = PlanContext {
+                native_outer_joins,
+                ..PlanContext::zero()
+            };
+            let item = catalog.parse_item(create_sql.clone(), Some(&pcx))?;
+            let item = catalog.deserialize_item(catalog.serialize_item(&item))?;
+            match item {
+                CatalogItem::View(view) => assert_eq!(view.native_outer_joins, native_outer_joins),
+                _ => panic!("expected a view"),
+            }
+        }
+
+        // Views serialized without a plan context were planned without
+        // native outer joins.
+        let bytes = serde_json::to_vec(&SerializedCatalogItem::V1 {
+            create_sql,
+            eval_env: None,
+        })?;
+        match catalog.deserialize_item(bytes)? {
+            CatalogItem::View(view) => assert!(!view.native_outer_joins),
+            _ => panic!("expected a view"),
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,148 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Audit events for changes to the catalog.
+//!
+//! Every catalog transaction initiated by a user appends one event per
+//! affected object to the durable audit log. Events are never removed, and
+//! are exposed via the `mz_catalog.mz_audit_events` table.
+
+use mz_ore::now::EpochMillis;
+use mz_sql::catalog::CatalogItemType;
+
+/// An entry in the audit log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditEvent {
+    /// The ID of the event. IDs are assigned in increasing order.
+    pub id: u64,
+    /// The kind of change.
+    pub event_type: EventType,
+    /// The kind of object that was changed.
+    pub object_type: ObjectType,
+    /// Details about the change, like the ID and name of the object before
+    /// and after the change.
+    pub details: serde_json::Value,
+    /// The user that made the change.
+    pub user: String,
+    /// The time at which the change was made.
+    pub occurred_at: EpochMillis,
+}
+
+/// The kind of change recorded by an [`AuditEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventType {
+    Create,
+    Drop,
+    Alter,
+    Grant,
+    Revoke,
+}
+
+impl EventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventType::Create => "create",
+            EventType::Drop => "drop",
+            EventType::Alter => "alter",
+            EventType::Grant => "grant",
+            EventType::Revoke => "revoke",
+        }
+    }
+
+    /// Parses an event type produced by [`EventType::as_str`].
+    pub fn parse(s: &str) -> Option<EventType> {
+        [
+            EventType::Create,
+            EventType::Drop,
+            EventType::Alter,
+            EventType::Grant,
+            EventType::Revoke,
+        ]
+        .into_iter()
+        .find(|t| t.as_str() == s)
+    }
+}
+
+/// The kind of object changed by an [`AuditEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectType {
+    Cluster,
+    ClusterReplica,
+    Connector,
+    Database,
+    Func,
+    Index,
+    Role,
+    Schema,
+    Secret,
+    Sink,
+    Source,
+    Table,
+    Type,
+    View,
+}
+
+impl ObjectType {
+    const ALL: [ObjectType; 14] = [
+        ObjectType::Cluster,
+        ObjectType::ClusterReplica,
+        ObjectType::Connector,
+        ObjectType::Database,
+        ObjectType::Func,
+        ObjectType::Index,
+        ObjectType::Role,
+        ObjectType::Schema,
+        ObjectType::Secret,
+        ObjectType::Sink,
+        ObjectType::Source,
+        ObjectType::Table,
+        ObjectType::Type,
+        ObjectType::View,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ObjectType::Cluster => "cluster",
+            ObjectType::ClusterReplica => "cluster-replica",
+            ObjectType::Connector => "connector",
+            ObjectType::Database => "database",
+            ObjectType::Func => "func",
+            ObjectType::Index => "index",
+            ObjectType::Role => "role",
+            ObjectType::Schema => "schema",
+            ObjectType::Secret => "secret",
+            ObjectType::Sink => "sink",
+            ObjectType::Source => "source",
+            ObjectType::Table => "table",
+            ObjectType::Type => "type",
+            ObjectType::View => "view",
+        }
+    }
+
+    /// Parses an object type produced by [`ObjectType::as_str`].
+    pub fn parse(s: &str) -> Option<ObjectType> {
+        ObjectType::ALL.into_iter().find(|t| t.as_str() == s)
+    }
+}
+
+impl From<CatalogItemType> for ObjectType {
+    fn from(typ: CatalogItemType) -> ObjectType {
+        match typ {
+            CatalogItemType::Table => ObjectType::Table,
+            CatalogItemType::Source => ObjectType::Source,
+            CatalogItemType::Sink => ObjectType::Sink,
+            CatalogItemType::View => ObjectType::View,
+            CatalogItemType::Index => ObjectType::Index,
+            CatalogItemType::Type => ObjectType::Type,
+            CatalogItemType::Func => ObjectType::Func,
+            CatalogItemType::Secret => ObjectType::Secret,
+            CatalogItemType::Connector => ObjectType::Connector,
+        }
+    }
+}
@@ -27,6 +27,7 @@ use lazy_static::lazy_static;
 use std::hash::Hash;
 
 use mz_dataflow_types::logging::{DifferentialLog, LogVariant, MaterializedLog, TimelyLog};
+use mz_repr::adt::numeric::NumericMaxScale;
 use mz_repr::{RelationDesc, ScalarType};
 use mz_sql::catalog::{CatalogType, CatalogTypeDetails, NameReference, TypeReference};
 
@@ -896,6 +897,12 @@ pub const MZ_PEEK_DURATIONS: BuiltinLog = BuiltinLog {
     variant: LogVariant::Materialized(MaterializedLog::PeekDuration),
 };
 
+pub const MZ_ARRANGEMENT_SPILLS: BuiltinLog = BuiltinLog {
+    name: "mz_arrangement_spills",
+    schema: MZ_CATALOG_SCHEMA,
+    variant: LogVariant::Materialized(MaterializedLog::ArrangementSpills),
+};
+
 pub const MZ_MESSAGE_COUNTS_RECEIVED_INTERNAL: BuiltinLog = BuiltinLog {
     name: "mz_message_counts_received_internal",
     schema: MZ_CATALOG_SCHEMA,
@@ -1096,7 +1103,71 @@ lazy_static! {
         desc: RelationDesc::empty()
             .with_column("id", ScalarType::Int64.nullable(false))
             .with_column("oid", ScalarType::Oid.nullable(false))
-            .with_column("name", ScalarType::String.nullable(false)),
+            .with_column("name", ScalarType::String.nullable(false))
+            .with_column("superuser", ScalarType::Bool.nullable(false)),
+    };
+    pub static ref MZ_OBJECT_OWNERS: BuiltinTable = BuiltinTable {
+        name: "mz_object_owners",
+        schema: MZ_CATALOG_SCHEMA,
+        desc: RelationDesc::empty()
+            .with_column("object_type", ScalarType::String.nullable(false))
+            .with_column("object_id", ScalarType::String.nullable(false))
+            .with_column("owner_id", ScalarType::Int64.nullable(false)),
+    };
+    pub static ref MZ_PRIVILEGES: BuiltinTable = BuiltinTable {
+        name: "mz_privileges",
+        schema: MZ_CATALOG_SCHEMA,
+        desc: RelationDesc::empty()
+            .with_column("object_type", ScalarType::String.nullable(false))
+            .with_column("object_id", ScalarType::String.nullable(false))
+            .with_column("grantee_id", ScalarType::Int64.nullable(false))
+            .with_column("privilege", ScalarType::String.nullable(false)),
+    };
+    pub static ref MZ_AUDIT_EVENTS: BuiltinTable = BuiltinTable {
+        name: "mz_audit_events",
+        schema: MZ_CATALOG_SCHEMA,
+        desc: RelationDesc::empty()
+            .with_column("id", ScalarType::Int64.nullable(false))
+            .with_column("event_type", ScalarType::String.nullable(false))
+            .with_column("object_type", ScalarType::String.nullable(false))
+            .with_column("event_details", ScalarType::Jsonb.nullable(false))
+            .with_column("user", ScalarType::String.nullable(false))
+            .with_column("occurred_at", ScalarType::TimestampTz.nullable(false)),
+    };
+    pub static ref MZ_ALL_STATEMENT_LOG: BuiltinTable = BuiltinTable {
+        name: "mz_all_statement_log",
+        schema: MZ_CATALOG_SCHEMA,
+        desc: RelationDesc::empty()
+            .with_column("id", ScalarType::Int64.nullable(false))
+            .with_column("connection_id", ScalarType::Int32.nullable(false))
+            .with_column("user", ScalarType::String.nullable(false))
+            .with_column("sql", ScalarType::String.nullable(false))
+            .with_column(
+                "timestamp",
+                ScalarType::Numeric {
+                    max_scale: Some(NumericMaxScale::ZERO),
+                }
+                .nullable(true),
+            )
+            .with_column("plan_kind", ScalarType::String.nullable(true))
+            .with_column("rows_returned", ScalarType::Int64.nullable(true))
+            .with_column("error", ScalarType::String.nullable(true))
+            .with_column("began_at", ScalarType::TimestampTz.nullable(false))
+            .with_column("duration", ScalarType::Interval.nullable(false)),
+    };
+    pub static ref MZ_ALL_INDEX_ADVICE: BuiltinTable = BuiltinTable {
+        name: "mz_all_index_advice",
+        schema: MZ_CATALOG_SCHEMA,
+        desc: RelationDesc::empty()
+            .with_column("object_id", ScalarType::String.nullable(false))
+            .with_column("cluster", ScalarType::String.nullable(false))
+            .with_column("role", ScalarType::String.nullable(false))
+            .with_column("create_sql", ScalarType::String.nullable(false))
+            .with_column("peeks", ScalarType::Int64.nullable(false))
+            .with_column("fast_path", ScalarType::Bool.nullable(false))
+            .with_column("estimated_record_bytes", ScalarType::Int64.nullable(false))
+            .with_column("estimated_bytes", ScalarType::Int64.nullable(true))
+            .with_column("last_seen", ScalarType::TimestampTz.nullable(false)),
     };
     pub static ref MZ_PSEUDO_TYPES: BuiltinTable = BuiltinTable {
         name: "mz_pseudo_types",
@@ -1153,6 +1224,32 @@ UNION SELECT id, oid, schema_id, name, 'source' FROM mz_catalog.mz_sources
 UNION SELECT id, oid, schema_id, name, 'view' FROM mz_catalog.mz_views",
 };
 
+// The advice describes the queries of every role, so roles other than
+// superusers may read only the advice drawn from their own queries.
+pub const MZ_INDEX_ADVICE: BuiltinView = BuiltinView {
+    name: "mz_index_advice",
+    schema: MZ_CATALOG_SCHEMA,
+    sql: "CREATE VIEW mz_catalog.mz_index_advice AS SELECT
+    object_id, cluster, role, create_sql, peeks, fast_path,
+    estimated_record_bytes, estimated_bytes, last_seen
+FROM mz_catalog.mz_all_index_advice
+WHERE role = current_user()
+    OR (SELECT superuser FROM mz_catalog.mz_roles WHERE name = current_user())",
+};
+
+// The log records the statements of every role, so roles other than
+// superusers may read only their own statements.
+pub const MZ_STATEMENT_LOG: BuiltinView = BuiltinView {
+    name: "mz_statement_log",
+    schema: MZ_CATALOG_SCHEMA,
+    sql: "CREATE VIEW mz_catalog.mz_statement_log AS SELECT
+    id, connection_id, \"user\", sql, \"timestamp\", plan_kind, rows_returned,
+    error, began_at, duration
+FROM mz_catalog.mz_all_statement_log
+WHERE \"user\" = current_user()
+    OR (SELECT superuser FROM mz_catalog.mz_roles WHERE name = current_user())",
+};
+
 pub const MZ_OBJECTS: BuiltinView = BuiltinView {
     name: "mz_objects",
     schema: MZ_CATALOG_SCHEMA,
@@ -1357,10 +1454,12 @@ pub const PG_NAMESPACE: BuiltinView = BuiltinView {
     sql: "CREATE VIEW pg_catalog.pg_namespace AS SELECT
 s.oid AS oid,
 s.name AS nspname,
-NULL::pg_catalog.oid AS nspowner,
+r.oid AS nspowner,
 NULL::pg_catalog.text[] AS nspacl
 FROM mz_catalog.mz_schemas s
-JOIN mz_catalog.mz_databases d ON (d.id IS NULL OR d.name = pg_catalog.current_database())",
+JOIN mz_catalog.mz_databases d ON (d.id IS NULL OR d.name = pg_catalog.current_database())
+LEFT JOIN mz_catalog.mz_object_owners o ON o.object_type = 'schema' AND o.object_id = s.id::pg_catalog.text
+LEFT JOIN mz_catalog.mz_roles r ON r.id = o.owner_id",
 };
 
 pub const PG_CLASS: BuiltinView = BuiltinView {
@@ -1372,7 +1471,7 @@ pub const PG_CLASS: BuiltinView = BuiltinView {
     mz_schemas.oid AS relnamespace,
     -- MZ doesn't support typed tables so reloftype is filled with 0
     0::pg_catalog.oid AS reloftype,
-    NULL::pg_catalog.oid AS relowner,
+    mz_roles.oid AS relowner,
     0::pg_catalog.oid AS relam,
     -- MZ doesn't have tablespaces so reltablespace is filled in with 0 implying the default tablespace
     0::pg_catalog.oid AS reltablespace,
@@ -1408,21 +1507,25 @@ pub const PG_CLASS: BuiltinView = BuiltinView {
     NULL::pg_catalog.text[] as reloptions
 FROM mz_catalog.mz_objects
 JOIN mz_catalog.mz_schemas ON mz_schemas.id = mz_objects.schema_id
-JOIN mz_catalog.mz_databases d ON (d.id IS NULL OR d.name = pg_catalog.current_database())",
+JOIN mz_catalog.mz_databases d ON (d.id IS NULL OR d.name = pg_catalog.current_database())
+LEFT JOIN mz_catalog.mz_object_owners ON mz_object_owners.object_type = 'item' AND mz_object_owners.object_id = mz_objects.id
+LEFT JOIN mz_catalog.mz_roles ON mz_roles.id = mz_object_owners.owner_id",
 };
 
 pub const PG_DATABASE: BuiltinView = BuiltinView {
     name: "pg_database",
     schema: PG_CATALOG_SCHEMA,
     sql: "CREATE VIEW pg_catalog.pg_database AS SELECT
-    oid,
-    name as datname,
-    NULL::pg_catalog.oid AS datdba,
+    d.oid,
+    d.name as datname,
+    r.oid AS datdba,
     6 as encoding,
     'C' as datcollate,
     'C' as datctype,
     NULL::pg_catalog.text[] as datacl
-FROM mz_catalog.mz_databases d",
+FROM mz_catalog.mz_databases d
+LEFT JOIN mz_catalog.mz_object_owners o ON o.object_type = 'database' AND o.object_id = d.id::pg_catalog.text
+LEFT JOIN mz_catalog.mz_roles r ON r.id = o.owner_id",
 };
 
 pub const PG_INDEX: BuiltinView = BuiltinView {
@@ -1811,6 +1914,11 @@ pub const PG_ROLES: BuiltinView = BuiltinView {
     schema: PG_CATALOG_SCHEMA,
     sql: "CREATE VIEW pg_catalog.pg_roles AS SELECT
     r.name AS rolname,
+    r.superuser AS rolsuper,
+    true AS rolinherit,
+    r.superuser AS rolcreaterole,
+    r.superuser AS rolcreatedb,
+    true AS rolcanlogin,
     '********'::pg_catalog.text AS rolpassword,
     r.oid AS oid
 FROM mz_catalog.mz_roles r
@@ -1823,11 +1931,13 @@ pub const PG_VIEWS: BuiltinView = BuiltinView {
     sql: "CREATE VIEW pg_catalog.pg_views AS SELECT
     s.name AS schemaname,
     v.name AS viewname,
-    NULL::pg_catalog.oid AS viewowner,
+    r.oid AS viewowner,
     v.definition AS definition
 FROM mz_catalog.mz_views v
 LEFT JOIN mz_catalog.mz_schemas s ON s.id = v.schema_id
 LEFT JOIN mz_catalog.mz_databases d ON d.id = s.database_id
+LEFT JOIN mz_catalog.mz_object_owners o ON o.object_type = 'item' AND o.object_id = v.id
+LEFT JOIN mz_catalog.mz_roles r ON r.id = o.owner_id
 WHERE d.name = pg_catalog.current_database()",
 };
 
@@ -1867,6 +1977,42 @@ JOIN mz_catalog.mz_schemas s ON s.id = r.schema_id
 JOIN mz_catalog.mz_databases d on s.database_id = d.id",
 };
 
+// Owners implicitly hold every privilege that applies to their relations, so
+// they are listed alongside the explicitly granted privileges, as in
+// PostgreSQL.
+pub const INFORMATION_SCHEMA_TABLE_PRIVILEGES: BuiltinView = BuiltinView {
+    name: "table_privileges",
+    schema: INFORMATION_SCHEMA,
+    sql: "CREATE VIEW information_schema.table_privileges AS SELECT
+    owner.name AS grantor,
+    CASE WHEN p.grantee_id = 0 THEN 'PUBLIC' ELSE grantee.name END AS grantee,
+    d.name AS table_catalog,
+    s.name AS table_schema,
+    r.name AS table_name,
+    p.privilege AS privilege_type,
+    'NO' AS is_grantable,
+    'NO' AS with_hierarchy
+FROM (
+    SELECT object_id, grantee_id, privilege FROM mz_catalog.mz_privileges
+    WHERE object_type = 'item'
+    UNION
+    SELECT o.object_id, o.owner_id, t.privilege
+    FROM mz_catalog.mz_object_owners o
+    JOIN mz_catalog.mz_relations r ON r.id = o.object_id
+    JOIN (VALUES
+        ('table', 'SELECT'), ('table', 'INSERT'), ('table', 'UPDATE'), ('table', 'DELETE'),
+        ('source', 'SELECT'), ('view', 'SELECT')
+    ) AS t (type, privilege) ON t.type = r.type
+    WHERE o.object_type = 'item'
+) p
+JOIN mz_catalog.mz_relations r ON r.id = p.object_id
+JOIN mz_catalog.mz_schemas s ON s.id = r.schema_id
+JOIN mz_catalog.mz_databases d ON s.database_id = d.id
+LEFT JOIN mz_catalog.mz_object_owners o ON o.object_type = 'item' AND o.object_id = r.id
+LEFT JOIN mz_catalog.mz_roles owner ON owner.id = o.owner_id
+LEFT JOIN mz_catalog.mz_roles grantee ON grantee.id = p.grantee_id",
+};
+
 // MZ doesn't support COLLATE so the table is filled with NULLs and made empty. pg_database hard
 // codes a collation of 'C' for every database, so we could copy that here.
 pub const PG_COLLATION: BuiltinView = BuiltinView {
@@ -2008,6 +2154,7 @@ lazy_static! {
             Builtin::Log(&MZ_ARRANGEMENT_SHARING_INTERNAL),
             Builtin::Log(&MZ_ARRANGEMENT_BATCHES_INTERNAL),
             Builtin::Log(&MZ_ARRANGEMENT_RECORDS_INTERNAL),
+            Builtin::Log(&MZ_ARRANGEMENT_SPILLS),
             Builtin::Log(&MZ_DATAFLOW_CHANNELS),
             Builtin::Log(&MZ_DATAFLOW_OPERATORS),
             Builtin::Log(&MZ_DATAFLOW_OPERATORS_ADDRESSES),
@@ -2040,6 +2187,11 @@ lazy_static! {
             Builtin::Table(&MZ_LIST_TYPES),
             Builtin::Table(&MZ_MAP_TYPES),
             Builtin::Table(&MZ_ROLES),
+            Builtin::Table(&MZ_OBJECT_OWNERS),
+            Builtin::Table(&MZ_PRIVILEGES),
+            Builtin::Table(&MZ_AUDIT_EVENTS),
+            Builtin::Table(&MZ_ALL_STATEMENT_LOG),
+            Builtin::Table(&MZ_ALL_INDEX_ADVICE),
             Builtin::Table(&MZ_PSEUDO_TYPES),
             Builtin::Table(&MZ_FUNCTIONS),
             Builtin::Table(&MZ_CLUSTERS),
@@ -2048,6 +2200,8 @@ lazy_static! {
             Builtin::Table(&MZ_CLUSTER_REPLICAS),
             Builtin::View(&MZ_RELATIONS),
             Builtin::View(&MZ_OBJECTS),
+            Builtin::View(&MZ_INDEX_ADVICE),
+            Builtin::View(&MZ_STATEMENT_LOG),
             Builtin::View(&MZ_CATALOG_NAMES),
             Builtin::View(&MZ_ARRANGEMENT_SHARING),
             Builtin::View(&MZ_ARRANGEMENT_SIZES),
@@ -2088,6 +2242,7 @@ lazy_static! {
             Builtin::View(&PG_INHERITS),
             Builtin::View(&INFORMATION_SCHEMA_COLUMNS),
             Builtin::View(&INFORMATION_SCHEMA_TABLES),
+            Builtin::View(&INFORMATION_SCHEMA_TABLE_PRIVILEGES),
         ]);
 
         builtins
@@ -12,18 +12,21 @@ use mz_dataflow_types::sinks::KafkaSinkConnector;
 use mz_dataflow_types::sources::ConnectorInner;
 use mz_expr::MirScalarExpr;
 use mz_ore::collections::CollectionExt;
+use mz_ore::now::to_datetime;
 use mz_repr::adt::array::ArrayDimension;
+use mz_repr::adt::jsonb::JsonbPacker;
 use mz_repr::{Datum, Diff, GlobalId, Row};
-use mz_sql::ast::{CreateIndexStatement, Statement};
+use mz_sql::ast::{CreateIndexStatement, Privilege, Statement};
 use mz_sql::catalog::{CatalogDatabase, CatalogType, TypeCategory};
-use mz_sql::names::{DatabaseId, ResolvedDatabaseSpecifier, SchemaId, SchemaSpecifier};
+use mz_sql::names::{DatabaseId, ObjectId, ResolvedDatabaseSpecifier, SchemaId, SchemaSpecifier};
 use mz_sql_parser::ast::display::AstDisplay;
 
+use crate::catalog::audit::AuditEvent;
 use crate::catalog::builtin::{
-    MZ_ARRAY_TYPES, MZ_BASE_TYPES, MZ_CLUSTERS, MZ_CLUSTER_REPLICAS, MZ_COLUMNS, MZ_CONNECTORS,
-    MZ_DATABASES, MZ_FUNCTIONS, MZ_INDEXES, MZ_INDEX_COLUMNS, MZ_KAFKA_SINKS, MZ_LIST_TYPES,
-    MZ_MAP_TYPES, MZ_PSEUDO_TYPES, MZ_ROLES, MZ_SCHEMAS, MZ_SECRETS, MZ_SINKS, MZ_SOURCES,
-    MZ_TABLES, MZ_TYPES, MZ_VIEWS,
+    MZ_ARRAY_TYPES, MZ_AUDIT_EVENTS, MZ_BASE_TYPES, MZ_CLUSTERS, MZ_CLUSTER_REPLICAS, MZ_COLUMNS,
+    MZ_CONNECTORS, MZ_DATABASES, MZ_FUNCTIONS, MZ_INDEXES, MZ_INDEX_COLUMNS, MZ_KAFKA_SINKS,
+    MZ_LIST_TYPES, MZ_MAP_TYPES, MZ_OBJECT_OWNERS, MZ_PRIVILEGES, MZ_PSEUDO_TYPES, MZ_ROLES,
+    MZ_SCHEMAS, MZ_SECRETS, MZ_SINKS, MZ_SOURCES, MZ_TABLES, MZ_TYPES, MZ_VIEWS,
 };
 use crate::catalog::{
     CatalogItem, CatalogState, Connector, Func, Index, Sink, SinkConnector, SinkConnectorState,
@@ -88,6 +91,7 @@ impl CatalogState {
                 Datum::Int64(role.id),
                 Datum::UInt32(role.oid),
                 Datum::String(&name),
+                Datum::from(role.superuser),
             ]),
             diff,
         }
@@ -106,6 +110,98 @@ impl CatalogState {
         }
     }
 
+    pub(super) fn pack_object_owner_update(
+        &self,
+        object: ObjectId,
+        diff: Diff,
+    ) -> BuiltinTableUpdate {
+        let owner_id = self.owner_id(object);
+        let (object_type, object_id) = object_datums(object);
+        BuiltinTableUpdate {
+            id: self.resolve_builtin_table(&MZ_OBJECT_OWNERS),
+            row: Row::pack_slice(&[
+                Datum::String(object_type),
+                Datum::String(&object_id),
+                Datum::Int64(owner_id),
+            ]),
+            diff,
+        }
+    }
+
+    pub(super) fn pack_privilege_update(
+        &self,
+        object: ObjectId,
+        grantee_id: i64,
+        privilege: Privilege,
+        diff: Diff,
+    ) -> BuiltinTableUpdate {
+        let (object_type, object_id) = object_datums(object);
+        BuiltinTableUpdate {
+            id: self.resolve_builtin_table(&MZ_PRIVILEGES),
+            row: Row::pack_slice(&[
+                Datum::String(object_type),
+                Datum::String(&object_id),
+                Datum::Int64(grantee_id),
+                Datum::String(&privilege.to_string()),
+            ]),
+            diff,
+        }
+    }
+
+    pub(super) fn pack_audit_event_update(
+        &self,
+        event: &AuditEvent,
+        diff: Diff,
+    ) -> BuiltinTableUpdate {
+        let mut row = Row::default();
+        let mut packer = row.packer();
+        packer.push(Datum::Int64(
+            i64::try_from(event.id).expect("audit event IDs fit in an i64"),
+        ));
+        packer.push(Datum::String(event.event_type.as_str()));
+        packer.push(Datum::String(event.object_type.as_str()));
+        JsonbPacker::new(&mut packer)
+            .pack_serde_json(event.details.clone())
+            .expect("audit event details must be valid JSON");
+        packer.push(Datum::String(&event.user));
+        packer.push(Datum::TimestampTz(to_datetime(event.occurred_at)));
+        BuiltinTableUpdate {
+            id: self.resolve_builtin_table(&MZ_AUDIT_EVENTS),
+            row,
+            diff,
+        }
+    }
+
+    /// Packs all privileges granted on `object`.
+    pub(super) fn pack_privileges_update(
+        &self,
+        object: ObjectId,
+        diff: Diff,
+    ) -> Vec<BuiltinTableUpdate> {
+        let mut updates = vec![];
+        for (grantee_id, privileges) in self.privileges.get(&object).into_iter().flatten() {
+            for privilege in privileges {
+                updates.push(self.pack_privilege_update(object, *grantee_id, *privilege, diff));
+            }
+        }
+        updates
+    }
+
+    /// Packs all privileges granted to the role identified by `grantee_id`.
+    pub(super) fn pack_grantee_privileges_update(
+        &self,
+        grantee_id: i64,
+        diff: Diff,
+    ) -> Vec<BuiltinTableUpdate> {
+        let mut updates = vec![];
+        for (object, grants) in &self.privileges {
+            for privilege in grants.get(&grantee_id).into_iter().flatten() {
+                updates.push(self.pack_privilege_update(*object, grantee_id, *privilege, diff));
+            }
+        }
+        updates
+    }
+
     pub(super) fn pack_compute_instance_replica_update(
         &self,
         compute_instance_id: ComputeInstanceId,
@@ -197,6 +293,8 @@ impl CatalogState {
             }
         }
 
+        updates.push(self.pack_object_owner_update(ObjectId::Item(id), diff));
+
         updates
     }
 
@@ -559,3 +657,14 @@ impl CatalogState {
         }]
     }
 }
+
+/// Splits `object` into the values of the `object_type` and `object_id`
+/// columns of `mz_object_owners` and `mz_privileges`.
+fn object_datums(object: ObjectId) -> (&'static str, String) {
+    match object {
+        ObjectId::Database(id) => ("database", id.to_string()),
+        ObjectId::Schema(id) => ("schema", id.to_string()),
+        ObjectId::Item(id) => ("item", id.to_string()),
+        ObjectId::ComputeInstance(id) => ("cluster", id.to_string()),
+    }
+}
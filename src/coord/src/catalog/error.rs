@@ -55,6 +55,10 @@ pub enum ErrorKind {
     ReadOnlyItem(String),
     #[error("cannot drop non-empty schema '{0}'")]
     SchemaNotEmpty(String),
+    #[error("role {} cannot be dropped because some objects depend on it", .0.quoted())]
+    RoleOwnsObjects(String),
+    #[error("cannot grant privileges on temporary object '{0}'")]
+    TemporaryPrivilegeObject(String),
     #[error("non-temporary items cannot depend on temporary item '{0}'")]
     InvalidTemporaryDependency(String),
     #[error("cannot create temporary item in non-temporary schema")]
@@ -23,7 +23,7 @@ where
     F: FnMut(&mut mz_sql::ast::Statement<Raw>) -> Result<(), anyhow::Error>,
 {
     let items = tx.loaded_items();
-    for (id, name, def) in items {
+    for (id, name, def, _owner_id) in items {
         let SerializedCatalogItem::V1 {
             create_sql,
             eval_env,
@@ -21,22 +21,28 @@ use mz_dataflow_types::client::{
 };
 use mz_ore::cast::CastFrom;
 use mz_ore::collections::CollectionExt;
+use mz_ore::now::EpochMillis;
 use mz_persist_types::Codec;
 use mz_repr::global_id::ProtoGlobalId;
 use mz_repr::GlobalId;
-use mz_sql::catalog::CatalogError as SqlCatalogError;
+use mz_sql::ast::Privilege;
+use mz_sql::catalog::{CatalogError as SqlCatalogError, PUBLIC_ROLE_ID};
 use mz_sql::names::{
-    DatabaseId, ObjectQualifiers, QualifiedObjectName, ResolvedDatabaseSpecifier, SchemaId,
-    SchemaSpecifier,
+    DatabaseId, ObjectId, ObjectQualifiers, QualifiedObjectName, ResolvedDatabaseSpecifier,
+    SchemaId, SchemaSpecifier,
 };
 use mz_sql::plan::ComputeInstanceIntrospectionConfig;
 use mz_stash::{Append, AppendBatch, Stash, StashError, TableTransaction, TypedCollection};
 
-use crate::catalog::builtin::BuiltinLog;
+use crate::catalog::audit::{AuditEvent, EventType, ObjectType};
+use crate::catalog::builtin::{BuiltinLog, MZ_SYSTEM};
 use crate::catalog::error::{Error, ErrorKind};
 
 const USER_VERSION: &str = "user_version";
 
+/// The name of the ID allocator for audit log events.
+const AUDIT_LOG_ID_ALLOC: &str = "audit_log";
+
 async fn migrate<S: Append>(stash: &mut S, version: u64) -> Result<(), StashError> {
     // Initial state.
     let migrations: &[fn(&mut S) -> BoxFuture<Result<(), StashError>>] = &[
@@ -76,6 +82,7 @@ async fn migrate<S: Append>(stash: &mut S, version: u64) -> Result<(), StashErro
                             DatabaseKey { id: 1 },
                             DatabaseValue {
                                 name: "materialize".into(),
+                                owner_id: None,
                             },
                         )],
                     )
@@ -89,6 +96,7 @@ async fn migrate<S: Append>(stash: &mut S, version: u64) -> Result<(), StashErro
                                 SchemaValue {
                                     database_id: None,
                                     name: "mz_catalog".into(),
+                                    owner_id: None,
                                 },
                             ),
                             (
@@ -96,6 +104,7 @@ async fn migrate<S: Append>(stash: &mut S, version: u64) -> Result<(), StashErro
                                 SchemaValue {
                                     database_id: None,
                                     name: "pg_catalog".into(),
+                                    owner_id: None,
                                 },
                             ),
                             (
@@ -103,6 +112,7 @@ async fn migrate<S: Append>(stash: &mut S, version: u64) -> Result<(), StashErro
                                 SchemaValue {
                                     database_id: Some(1),
                                     name: "public".into(),
+                                    owner_id: None,
                                 },
                             ),
                             (
@@ -110,6 +120,7 @@ async fn migrate<S: Append>(stash: &mut S, version: u64) -> Result<(), StashErro
                                 SchemaValue {
                                     database_id: None,
                                     name: "mz_internal".into(),
+                                    owner_id: None,
                                 },
                             ),
                             (
@@ -117,6 +128,7 @@ async fn migrate<S: Append>(stash: &mut S, version: u64) -> Result<(), StashErro
                                 SchemaValue {
                                     database_id: None,
                                     name: "information_schema".into(),
+                                    owner_id: None,
                                 },
                             ),
                         ],
@@ -129,6 +141,8 @@ async fn migrate<S: Append>(stash: &mut S, version: u64) -> Result<(), StashErro
                             RoleKey { id: 1 },
                             RoleValue {
                                 name: "materialize".into(),
+                                password_verifier: None,
+                                superuser: None,
                             },
                         )],
                     )
@@ -143,6 +157,7 @@ async fn migrate<S: Append>(stash: &mut S, version: u64) -> Result<(), StashErro
                         config: Some(
                             "{\"debugging\":false,\"granularity\":{\"secs\":1,\"nanos\":0}}".into(),
                         ),
+                        owner_id: None,
                     },
                 )],
                     )
@@ -163,6 +178,77 @@ async fn migrate<S: Append>(stash: &mut S, version: u64) -> Result<(), StashErro
                 Ok(())
             })
         },
+        // > Record privileges granted on catalog objects, and let every role use
+        // > the default cluster and the `materialize.public` schema.
+        // >
+        // > Introduced in v0.26.1.
+        // >
+        // > Objects created before this version have no recorded owner and are
+        // > treated as owned by the `mz_system` role.
+        |stash| {
+            Box::pin(async {
+                let mut grants = vec![];
+                if let Some(cluster) = COLLECTION_COMPUTE_INSTANCES
+                    .peek_key_one(stash, &ComputeInstanceKey { id: 1 })
+                    .await?
+                {
+                    if cluster.name == "default" {
+                        grants.push(PrivilegeKey {
+                            object: ObjectId::ComputeInstance(1).to_string(),
+                            grantee_id: PUBLIC_ROLE_ID,
+                            privilege: Privilege::Usage.to_string(),
+                        });
+                    }
+                }
+                if let Some(schema) = COLLECTION_SCHEMA
+                    .peek_key_one(stash, &SchemaKey { id: 3 })
+                    .await?
+                {
+                    if schema.database_id == Some(1) && schema.name == "public" {
+                        grants.push(PrivilegeKey {
+                            object: ObjectId::Schema(SchemaId::new(3)).to_string(),
+                            grantee_id: PUBLIC_ROLE_ID,
+                            privilege: Privilege::Usage.to_string(),
+                        });
+                    }
+                }
+                COLLECTION_PRIVILEGE
+                    .upsert(stash, grants.into_iter().map(|k| (k, PrivilegeValue {})))
+                    .await?;
+                Ok(())
+            })
+        },
+        // > Record an audit log of changes to the catalog.
+        // >
+        // > Introduced in v0.26.1.
+        // >
+        // > Changes made before this version are not recorded.
+        |stash| {
+            Box::pin(async {
+                // Bump the upper so peek works.
+                COLLECTION_AUDIT_LOG.upsert(stash, vec![]).await?;
+                COLLECTION_GID_ALLOC
+                    .upsert_key(
+                        stash,
+                        &GidAllocKey {
+                            name: AUDIT_LOG_ID_ALLOC.into(),
+                        },
+                        &GidAllocValue { next_gid: 1 },
+                    )
+                    .await?;
+                Ok(())
+            })
+        },
+        // > Record the replication slots of logical replication clients.
+        // >
+        // > Introduced in v0.26.1.
+        |stash| {
+            Box::pin(async {
+                // Bump the upper so peek works.
+                COLLECTION_REPLICATION_SLOT.upsert(stash, vec![]).await?;
+                Ok(())
+            })
+        },
         // Add new migrations here.
         //
         // Migrations should be preceded with a comment of the following form:
@@ -361,18 +447,18 @@ impl<S: Append> Connection<S> {
             .await
     }
 
-    pub async fn load_databases(&mut self) -> Result<Vec<(DatabaseId, String)>, Error> {
+    pub async fn load_databases(&mut self) -> Result<Vec<(DatabaseId, String, i64)>, Error> {
         Ok(COLLECTION_DATABASE
             .peek_one(&mut self.stash)
             .await?
             .into_iter()
-            .map(|(k, v)| (DatabaseId::new(k.id), v.name))
+            .map(|(k, v)| (DatabaseId::new(k.id), v.name, owner_id(v.owner_id)))
             .collect())
     }
 
     pub async fn load_schemas(
         &mut self,
-    ) -> Result<Vec<(SchemaId, String, Option<DatabaseId>)>, Error> {
+    ) -> Result<Vec<(SchemaId, String, Option<DatabaseId>, i64)>, Error> {
         Ok(COLLECTION_SCHEMA
             .peek_one(&mut self.stash)
             .await?
@@ -382,20 +468,106 @@ impl<S: Append> Connection<S> {
                     SchemaId::new(k.id),
                     v.name,
                     v.database_id.map(DatabaseId::new),
+                    owner_id(v.owner_id),
                 )
             })
             .collect())
     }
 
-    pub async fn load_roles(&mut self) -> Result<Vec<(i64, String)>, Error> {
+    pub async fn load_roles(&mut self) -> Result<Vec<(i64, String, Option<String>, bool)>, Error> {
         Ok(COLLECTION_ROLE
             .peek_one(&mut self.stash)
             .await?
             .into_iter()
-            .map(|(k, v)| (k.id, v.name))
+            // Roles created before non-superusers were supported have no
+            // recorded superuser attribute and are all superusers.
+            .map(|(k, v)| {
+                (
+                    k.id,
+                    v.name,
+                    v.password_verifier,
+                    v.superuser.unwrap_or(true),
+                )
+            })
             .collect())
     }
 
+    pub async fn load_privileges(&mut self) -> Result<Vec<(ObjectId, i64, Privilege)>, Error> {
+        COLLECTION_PRIVILEGE
+            .peek_one(&mut self.stash)
+            .await?
+            .into_iter()
+            .map(|(k, _v)| {
+                let object = k.object.parse().map_err(|e| {
+                    Error::new(ErrorKind::Corruption {
+                        detail: format!("invalid privilege object {}: {}", k.object, e),
+                    })
+                })?;
+                Ok((object, k.grantee_id, parse_privilege(&k.privilege)?))
+            })
+            .collect()
+    }
+
+    pub async fn load_audit_events(&mut self) -> Result<Vec<AuditEvent>, Error> {
+        COLLECTION_AUDIT_LOG
+            .peek_one(&mut self.stash)
+            .await?
+            .into_iter()
+            .map(|(k, _v)| {
+                let corruption = |detail| Error::new(ErrorKind::Corruption { detail });
+                Ok(AuditEvent {
+                    id: k.id,
+                    event_type: EventType::parse(&k.event_type).ok_or_else(|| {
+                        corruption(format!("invalid audit event type {}", k.event_type))
+                    })?,
+                    object_type: ObjectType::parse(&k.object_type).ok_or_else(|| {
+                        corruption(format!("invalid audit object type {}", k.object_type))
+                    })?,
+                    details: serde_json::from_str(&k.details).map_err(|e| {
+                        corruption(format!("invalid audit event details {}: {}", k.details, e))
+                    })?,
+                    user: k.user,
+                    occurred_at: k.occurred_at,
+                })
+            })
+            .collect()
+    }
+
+    /// Loads the durable replication slots and the latest LSN that the
+    /// consumer of each has confirmed flushing.
+    pub async fn load_replication_slots(&mut self) -> Result<Vec<(String, u64)>, Error> {
+        Ok(COLLECTION_REPLICATION_SLOT
+            .peek_one(&mut self.stash)
+            .await?
+            .into_iter()
+            .map(|(k, v)| (k.name, v.confirmed_flush))
+            .collect())
+    }
+
+    /// Records the latest LSN that the consumer of the replication slot `name`
+    /// has confirmed flushing, creating the slot if it does not exist.
+    pub async fn set_replication_slot(
+        &mut self,
+        name: &str,
+        confirmed_flush: u64,
+    ) -> Result<(), Error> {
+        COLLECTION_REPLICATION_SLOT
+            .upsert_key(
+                &mut self.stash,
+                &ReplicationSlotKey { name: name.into() },
+                &ReplicationSlotValue { confirmed_flush },
+            )
+            .await
+            .map_err(|e| e.into())
+    }
+
+    pub async fn remove_replication_slot(&mut self, name: &str) -> Result<(), Error> {
+        COLLECTION_REPLICATION_SLOT
+            .delete_key(&mut self.stash, &ReplicationSlotKey { name: name.into() })
+            .await?;
+        Ok(())
+    }
+
     pub async fn load_compute_instances(
         &mut self,
     ) -> Result<
@@ -403,6 +575,7 @@ impl<S: Append> Connection<S> {
             ComputeInstanceId,
             String,
             Option<ComputeInstanceIntrospectionConfig>,
+            i64,
         )>,
         Error,
     > {
@@ -416,7 +589,7 @@ impl<S: Append> Connection<S> {
                     Some(config) => serde_json::from_str(&config)
                         .map_err(|err| Error::from(StashError::from(err.to_string())))?,
                 };
-                Ok((k.id, v.name, config))
+                Ok((k.id, v.name, config, owner_id(v.owner_id)))
             })
             .collect()
     }
@@ -586,6 +759,17 @@ impl<S: Append> Connection<S> {
         let introspection_sources = COLLECTION_COMPUTE_INTROSPECTION_SOURCE_INDEX
             .peek_one(&mut self.stash)
             .await?;
+        let privileges = COLLECTION_PRIVILEGE.peek_one(&mut self.stash).await?;
+        let audit_log_next_id = COLLECTION_GID_ALLOC
+            .peek_key_one(
+                &mut self.stash,
+                &GidAllocKey {
+                    name: AUDIT_LOG_ID_ALLOC.into(),
+                },
+            )
+            .await?
+            .expect("audit log ID allocator must exist")
+            .next_gid;
 
         Ok(Transaction {
             stash: &mut self.stash,
@@ -608,6 +792,9 @@ impl<S: Append> Connection<S> {
             introspection_sources: TableTransaction::new(introspection_sources, None, |_a, _b| {
                 false
             }),
+            privileges: TableTransaction::new(privileges, None, |_a, _b| false),
+            audit_log_next_id,
+            audit_events: Vec::new(),
         })
     }
 
@@ -634,10 +821,15 @@ pub struct Transaction<'a, S> {
         ComputeIntrospectionSourceIndexValue,
         i64,
     >,
+    privileges: TableTransaction<PrivilegeKey, PrivilegeValue, i64>,
+    /// The ID to assign to the next audit event, as of the start of the
+    /// transaction.
+    audit_log_next_id: u64,
+    audit_events: Vec<AuditEvent>,
 }
 
 impl<'a, S: Append> Transaction<'a, S> {
-    pub fn loaded_items(&self) -> Vec<(GlobalId, QualifiedObjectName, Vec<u8>)> {
+    pub fn loaded_items(&self) -> Vec<(GlobalId, QualifiedObjectName, Vec<u8>, i64)> {
         let databases = self.databases.items();
         let schemas = self.schemas.items();
         let mut items = Vec::new();
@@ -664,17 +856,23 @@ impl<'a, S: Append> Transaction<'a, S> {
                     item: v.name.clone(),
                 },
                 v.definition.clone(),
+                owner_id(v.owner_id),
             ));
         });
-        items.sort_by_key(|(id, _, _)| *id);
+        items.sort_by_key(|(id, _, _, _)| *id);
         items
     }
 
-    pub fn insert_database(&mut self, database_name: &str) -> Result<DatabaseId, Error> {
+    pub fn insert_database(
+        &mut self,
+        database_name: &str,
+        owner_id: i64,
+    ) -> Result<DatabaseId, Error> {
         match self.databases.insert(
             |id| DatabaseKey { id: id.unwrap() },
             DatabaseValue {
                 name: database_name.to_string(),
+                owner_id: Some(owner_id),
             },
         ) {
             Ok(id) => Ok(DatabaseId::new(id.unwrap())),
@@ -688,12 +886,14 @@ impl<'a, S: Append> Transaction<'a, S> {
         &mut self,
         database_id: DatabaseId,
         schema_name: &str,
+        owner_id: i64,
     ) -> Result<SchemaId, Error> {
         match self.schemas.insert(
             |id| SchemaKey { id: id.unwrap() },
             SchemaValue {
                 database_id: Some(database_id.0),
                 name: schema_name.to_string(),
+                owner_id: Some(owner_id),
             },
         ) {
             Ok(id) => Ok(SchemaId::new(id.unwrap())),
@@ -703,11 +903,18 @@ impl<'a, S: Append> Transaction<'a, S> {
         }
     }
 
-    pub fn insert_role(&mut self, role_name: &str) -> Result<i64, Error> {
+    pub fn insert_role(
+        &mut self,
+        role_name: &str,
+        password_verifier: Option<String>,
+        superuser: bool,
+    ) -> Result<i64, Error> {
         match self.roles.insert(
             |id| RoleKey { id: id.unwrap() },
             RoleValue {
                 name: role_name.to_string(),
+                password_verifier,
+                superuser: Some(superuser),
             },
         ) {
             Ok(id) => Ok(id.unwrap()),
@@ -723,6 +930,7 @@ impl<'a, S: Append> Transaction<'a, S> {
         cluster_name: &str,
         config: &Option<ComputeInstanceIntrospectionConfig>,
         introspection_sources: &Vec<(&'static BuiltinLog, GlobalId)>,
+        owner_id: i64,
     ) -> Result<ComputeInstanceId, Error> {
         let config = serde_json::to_string(config)
             .map_err(|err| Error::from(StashError::from(err.to_string())))?;
@@ -731,6 +939,7 @@ impl<'a, S: Append> Transaction<'a, S> {
             ComputeInstanceValue {
                 name: cluster_name.to_string(),
                 config: Some(config),
+                owner_id: Some(owner_id),
             },
         ) {
             Ok(id) => id.unwrap(),
@@ -770,13 +979,8 @@ impl<'a, S: Append> Transaction<'a, S> {
         let config = serde_json::to_string(config)
             .map_err(|err| Error::from(StashError::from(err.to_string())))?;
         let mut compute_instance_id = None;
-        for (
-            ComputeInstanceKey { id },
-            ComputeInstanceValue {
-                name,
-                config: _config,
-            },
-        ) in self.compute_instances.items()
+        for (ComputeInstanceKey { id }, ComputeInstanceValue { name, .. }) in
+            self.compute_instances.items()
         {
             if &name == compute_name {
                 compute_instance_id = Some(id);
@@ -808,6 +1012,7 @@ impl<'a, S: Append> Transaction<'a, S> {
         schema_id: SchemaId,
         item_name: &str,
         item: &[u8],
+        owner_id: i64,
     ) -> Result<(), Error> {
         match self.items.insert(
             |_| ItemKey { gid: id },
@@ -815,6 +1020,7 @@ impl<'a, S: Append> Transaction<'a, S> {
                 schema_id: schema_id.0,
                 name: item_name.to_string(),
                 definition: item.to_vec(),
+                owner_id: Some(owner_id),
             },
         ) {
             Ok(_) => Ok(()),
@@ -911,6 +1117,7 @@ impl<'a, S: Append> Transaction<'a, S> {
                     schema_id: v.schema_id,
                     name: item_name.to_string(),
                     definition: item.to_vec(),
+                    owner_id: v.owner_id,
                 })
             } else {
                 None
@@ -924,6 +1131,61 @@ impl<'a, S: Append> Transaction<'a, S> {
         }
     }
 
+    /// Records that `grantee_id` holds `privilege` on `object`. Granting a
+    /// privilege that is already held is a no-op.
+    pub fn insert_privilege(&mut self, object: ObjectId, grantee_id: i64, privilege: Privilege) {
+        let key = PrivilegeKey {
+            object: object.to_string(),
+            grantee_id,
+            privilege: privilege.to_string(),
+        };
+        // A uniqueness violation means the privilege was already granted.
+        let _ = self.privileges.insert(|_| key, PrivilegeValue {});
+    }
+
+    /// Removes the record that `grantee_id` holds `privilege` on `object`, if
+    /// any.
+    pub fn remove_privilege(&mut self, object: ObjectId, grantee_id: i64, privilege: Privilege) {
+        let object = object.to_string();
+        let privilege = privilege.to_string();
+        self.privileges.delete(|k, _v| {
+            k.object == object && k.grantee_id == grantee_id && k.privilege == privilege
+        });
+    }
+
+    /// Removes all privileges granted on `object`.
+    pub fn remove_privileges_on(&mut self, object: ObjectId) {
+        let object = object.to_string();
+        self.privileges.delete(|k, _v| k.object == object);
+    }
+
+    /// Removes all privileges granted to `grantee_id`.
+    pub fn remove_privileges_of(&mut self, grantee_id: i64) {
+        self.privileges.delete(|k, _v| k.grantee_id == grantee_id);
+    }
+
+    /// Appends an event to the audit log, assigning it the next available ID.
+    pub fn insert_audit_event(
+        &mut self,
+        event_type: EventType,
+        object_type: ObjectType,
+        details: serde_json::Value,
+        user: String,
+        occurred_at: EpochMillis,
+    ) -> AuditEvent {
+        let id = self.audit_log_next_id + u64::cast_from(self.audit_events.len());
+        let event = AuditEvent {
+            id,
+            event_type,
+            object_type,
+            details,
+            user,
+            occurred_at,
+        };
+        self.audit_events.push(event.clone());
+        event
+    }
+
     pub async fn commit(self) -> Result<(), Error> {
         let mut batches = Vec::new();
         async fn add_batch<K, V, S, I>(
@@ -999,6 +1261,52 @@ impl<'a, S: Append> Transaction<'a, S> {
             self.introspection_sources.pending(),
         )
         .await?;
+        add_batch(
+            self.stash,
+            &mut batches,
+            &COLLECTION_PRIVILEGE,
+            self.privileges.pending(),
+        )
+        .await?;
+        if !self.audit_events.is_empty() {
+            let key = GidAllocKey {
+                name: AUDIT_LOG_ID_ALLOC.into(),
+            };
+            let next_id = self.audit_log_next_id + u64::cast_from(self.audit_events.len());
+            add_batch(
+                self.stash,
+                &mut batches,
+                &COLLECTION_GID_ALLOC,
+                [
+                    (
+                        key.clone(),
+                        GidAllocValue {
+                            next_gid: self.audit_log_next_id,
+                        },
+                        -1,
+                    ),
+                    (key, GidAllocValue { next_gid: next_id }, 1),
+                ],
+            )
+            .await?;
+        }
+        add_batch(
+            self.stash,
+            &mut batches,
+            &COLLECTION_AUDIT_LOG,
+            self.audit_events.into_iter().map(|event| {
+                let key = AuditLogKey {
+                    id: event.id,
+                    event_type: event.event_type.as_str().into(),
+                    object_type: event.object_type.as_str().into(),
+                    details: event.details.to_string(),
+                    user: event.user,
+                    occurred_at: event.occurred_at,
+                };
+                (key, AuditLogValue {}, 1)
+            }),
+        )
+        .await?;
         if batches.is_empty() {
             return Ok(());
         }
@@ -1083,6 +1391,8 @@ struct ComputeInstanceValue {
     name: String,
     #[prost(string, optional)]
     config: Option<String>,
+    #[prost(int64, optional)]
+    owner_id: Option<i64>,
 }
 impl_codec!(ComputeInstanceValue);
 
@@ -1131,6 +1441,8 @@ impl_codec!(ComputeInstanceReplicaValue);
 struct DatabaseValue {
     #[prost(string)]
     name: String,
+    #[prost(int64, optional)]
+    owner_id: Option<i64>,
 }
 impl_codec!(DatabaseValue);
 
@@ -1147,6 +1459,8 @@ struct SchemaValue {
     database_id: Option<i64>,
     #[prost(string)]
     name: String,
+    #[prost(int64, optional)]
+    owner_id: Option<i64>,
 }
 impl_codec!(SchemaValue);
 
@@ -1191,6 +1505,8 @@ struct ItemValue {
     name: String,
     #[prost(bytes)]
     definition: Vec<u8>,
+    #[prost(int64, optional)]
+    owner_id: Option<i64>,
 }
 impl_codec!(ItemValue);
 
@@ -1205,9 +1521,87 @@ impl_codec!(RoleKey);
 struct RoleValue {
     #[prost(string)]
     name: String,
+    /// A SCRAM-SHA-256 verifier for the role's password, if it has one.
+    #[prost(string, optional)]
+    password_verifier: Option<String>,
+    #[prost(bool, optional)]
+    superuser: Option<bool>,
 }
 impl_codec!(RoleValue);
 
+#[derive(Clone, Message, PartialOrd, PartialEq, Eq, Ord, Hash)]
+struct PrivilegeKey {
+    /// The object on which the privilege is granted, as formatted by
+    /// [`ObjectId`]'s `Display` implementation.
+    #[prost(string)]
+    object: String,
+    #[prost(int64)]
+    grantee_id: i64,
+    #[prost(string)]
+    privilege: String,
+}
+impl_codec!(PrivilegeKey);
+
+#[derive(Clone, Message, PartialOrd, PartialEq, Eq, Ord)]
+struct PrivilegeValue {}
+impl_codec!(PrivilegeValue);
+
+#[derive(Clone, Message, PartialOrd, PartialEq, Eq, Ord, Hash)]
+struct AuditLogKey {
+    #[prost(uint64)]
+    id: u64,
+    #[prost(string)]
+    event_type: String,
+    #[prost(string)]
+    object_type: String,
+    /// The event's details, encoded as JSON.
+    #[prost(string)]
+    details: String,
+    #[prost(string)]
+    user: String,
+    #[prost(uint64)]
+    occurred_at: u64,
+}
+impl_codec!(AuditLogKey);
+
+#[derive(Clone, Message, PartialOrd, PartialEq, Eq, Ord)]
+struct AuditLogValue {}
+impl_codec!(AuditLogValue);
+
+#[derive(Clone, Message, PartialOrd, PartialEq, Eq, Ord, Hash)]
+struct ReplicationSlotKey {
+    #[prost(string)]
+    name: String,
+}
+impl_codec!(ReplicationSlotKey);
+
+#[derive(Clone, Message, PartialOrd, PartialEq, Eq, Ord)]
+struct ReplicationSlotValue {
+    #[prost(uint64)]
+    confirmed_flush: u64,
+}
+impl_codec!(ReplicationSlotValue);
+
+/// Resolves the owner recorded for an object. Objects created before
+/// ownership was recorded are owned by the `mz_system` role.
+fn owner_id(owner_id: Option<i64>) -> i64 {
+    owner_id.unwrap_or(MZ_SYSTEM.id)
+}
+
+fn parse_privilege(privilege: &str) -> Result<Privilege, Error> {
+    match privilege {
+        "SELECT" => Ok(Privilege::Select),
+        "INSERT" => Ok(Privilege::Insert),
+        "UPDATE" => Ok(Privilege::Update),
+        "DELETE" => Ok(Privilege::Delete),
+        "USAGE" => Ok(Privilege::Usage),
+        "CREATE" => Ok(Privilege::Create),
+        _ => Err(Error::new(ErrorKind::Corruption {
+            detail: format!("invalid privilege {}", privilege),
+        })),
+    }
+}
+
 #[derive(Clone, Message, PartialOrd, PartialEq, Eq, Ord, Hash)]
 struct ConfigValue {
     #[prost(uint64)]
@@ -1237,3 +1631,9 @@ static COLLECTION_DATABASE: TypedCollection<DatabaseKey, DatabaseValue> =
 static COLLECTION_SCHEMA: TypedCollection<SchemaKey, SchemaValue> = TypedCollection::new("schema");
 static COLLECTION_ITEM: TypedCollection<ItemKey, ItemValue> = TypedCollection::new("item");
 static COLLECTION_ROLE: TypedCollection<RoleKey, RoleValue> = TypedCollection::new("role");
+static COLLECTION_PRIVILEGE: TypedCollection<PrivilegeKey, PrivilegeValue> =
+    TypedCollection::new("privilege");
+static COLLECTION_AUDIT_LOG: TypedCollection<AuditLogKey, AuditLogValue> =
+    TypedCollection::new("audit_log");
+static COLLECTION_REPLICATION_SLOT: TypedCollection<ReplicationSlotKey, ReplicationSlotValue> =
+    TypedCollection::new("replication_slot");
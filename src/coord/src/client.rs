@@ -11,14 +11,16 @@ use std::future::Future;
 use std::sync::Arc;
 use std::time::Instant;
 
+use anyhow::anyhow;
 use tokio::sync::{mpsc, oneshot, watch};
 use uuid::Uuid;
 
 use mz_ore::collections::CollectionExt;
 use mz_ore::id_gen::IdAllocator;
 use mz_ore::thread::JoinOnDropHandle;
-use mz_repr::{Datum, GlobalId, Row, ScalarType};
+use mz_repr::{Datum, GlobalId, Row, RowArena, ScalarType};
 use mz_sql::ast::{Raw, Statement};
+use mz_sql::plan::Params;
 
 use crate::command::{
     Canceled, Command, ExecuteResponse, Response, SimpleExecuteResponse, SimpleResult,
@@ -26,7 +28,8 @@ use crate::command::{
 };
 use crate::coord::PeekResponseUnary;
 use crate::error::CoordError;
-use crate::session::{EndTransactionAction, PreparedStatement, Session};
+use crate::scram::ScramVerifier;
+use crate::session::{collect_row_batches, EndTransactionAction, PreparedStatement, Session};
 
 /// A handle to a running coordinator.
 ///
@@ -102,6 +105,20 @@ impl Client {
         session_client.simple_execute(stmts).await
     }
 
+    /// Returns the password verifier of the named role.
+    ///
+    /// Returns `None` if the role does not exist or does not have a password.
+    pub async fn password_verifier(&self, role_name: &str) -> Option<ScramVerifier> {
+        let (tx, rx) = oneshot::channel();
+        self.cmd_tx
+            .send(Command::PasswordVerifier {
+                role_name: role_name.into(),
+                tx,
+            })
+            .expect("coordinator unexpectedly gone");
+        rx.await.expect("coordinator unexpectedly canceled request")
+    }
+
     /// Like [`Client::system_execute`], but for cases when `stmt` is known to
     /// contain just one statement.
     ///
@@ -158,6 +175,7 @@ impl ConnClient {
             session: Some(session),
             cancel_tx: Arc::clone(&cancel_tx),
             cancel_rx,
+            secret_key: 0,
         };
         let response = client
             .send(|tx, session| Command::Startup {
@@ -168,7 +186,10 @@ impl ConnClient {
             })
             .await;
         match response {
-            Ok(response) => Ok((client, response)),
+            Ok(response) => {
+                client.secret_key = response.secret_key;
+                Ok((client, response))
+            }
             Err(e) => {
                 // When startup fails, no need to call terminate. Remove the
                 // session from the client to sidestep the panic in the `Drop`
@@ -179,6 +200,13 @@ impl ConnClient {
         }
     }
 
+    /// Returns the password verifier of the named role.
+    ///
+    /// See [`Client::password_verifier`].
+    pub async fn password_verifier(&self, role_name: &str) -> Option<ScramVerifier> {
+        self.inner.password_verifier(role_name).await
+    }
+
     /// Cancels the query currently running on another connection.
     pub async fn cancel_request(&mut self, conn_id: u32, secret_key: u32) {
         self.inner
@@ -219,6 +247,7 @@ pub struct SessionClient {
     session: Option<Session>,
     cancel_tx: Arc<watch::Sender<Canceled>>,
     cancel_rx: watch::Receiver<Canceled>,
+    secret_key: u32,
 }
 
 impl SessionClient {
@@ -227,13 +256,18 @@ impl SessionClient {
         async move {
             loop {
                 let _ = cancel_rx.changed().await;
-                if let Canceled::Canceled = *cancel_rx.borrow() {
+                if *cancel_rx.borrow() != Canceled::NotCanceled {
                     return;
                 }
             }
         }
     }
 
+    /// Returns the reason the current operation was canceled, if it was.
+    pub fn cancel_reason(&self) -> Canceled {
+        *self.cancel_rx.borrow()
+    }
+
     pub fn reset_canceled(&mut self) {
         // Clear any cancellation message.
         // TODO(mjibson): This makes the use of .changed annoying since it will
@@ -298,6 +332,50 @@ impl SessionClient {
         .await
     }
 
+    /// Binds a statement to a portal, like [`SessionClient::declare`], along
+    /// with values for its parameters in the PostgreSQL text format.
+    ///
+    /// `None` values bind `NULL`.
+    pub async fn declare_with_params(
+        &mut self,
+        name: String,
+        stmt: Statement<Raw>,
+        params: Vec<Option<String>>,
+    ) -> Result<(), CoordError> {
+        self.declare(name.clone(), stmt, vec![]).await?;
+        let portal = self
+            .session()
+            .get_portal_unverified_mut(&name)
+            .expect("portal was just declared");
+        let types = portal.desc.param_types.clone();
+        if types.len() != params.len() {
+            return Err(CoordError::Unstructured(anyhow!(
+                "request supplies {} parameters, but the statement requires {}",
+                params.len(),
+                types.len()
+            )));
+        }
+        let buf = RowArena::new();
+        let mut datums = vec![];
+        for (param, typ) in params.iter().zip(&types) {
+            let pg_typ = mz_pgrepr::Type::from(typ);
+            let datum = match param {
+                None => Datum::Null,
+                Some(text) => mz_pgrepr::Value::decode_text(&pg_typ, text.as_bytes())
+                    .map_err(|e| {
+                        CoordError::Unstructured(anyhow!("unable to decode parameter: {}", e))
+                    })?
+                    .into_datum(&buf, &pg_typ),
+            };
+            datums.push(datum);
+        }
+        portal.parameters = Params {
+            datums: Row::pack(datums),
+            types,
+        };
+        Ok(())
+    }
+
     /// Executes a previously-bound portal.
     pub async fn execute(&mut self, portal_name: String) -> Result<ExecuteResponse, CoordError> {
         self.send(|tx, session| Command::Execute {
@@ -327,6 +405,15 @@ impl SessionClient {
         self.inner.cancel_request(conn_id, secret_key).await
     }
 
+    /// Cancels the query currently running on this connection.
+    ///
+    /// This is useful for clients that can receive a cancellation request on
+    /// the same connection that is running the query.
+    pub async fn cancel(&mut self) {
+        let (conn_id, secret_key) = (self.inner.conn_id, self.secret_key);
+        self.inner.cancel_request(conn_id, secret_key).await
+    }
+
     /// Ends a transaction.
     pub async fn end_transaction(
         &mut self,
@@ -353,6 +440,57 @@ impl SessionClient {
             .await
     }
 
+    /// Creates a replication slot. A temporary slot is dropped when this
+    /// session terminates.
+    pub async fn create_replication_slot(
+        &mut self,
+        name: String,
+        temporary: bool,
+    ) -> Result<(), CoordError> {
+        self.send(|tx, session| Command::CreateReplicationSlot {
+            name,
+            temporary,
+            session,
+            tx,
+        })
+        .await
+    }
+
+    /// Drops a replication slot that is not in use.
+    pub async fn drop_replication_slot(&mut self, name: String) -> Result<(), CoordError> {
+        self.send(|tx, session| Command::DropReplicationSlot { name, session, tx })
+            .await
+    }
+
+    /// Marks a replication slot as in use by this session, and returns the
+    /// latest LSN that a consumer of the slot has confirmed flushing.
+    pub async fn acquire_replication_slot(&mut self, name: String) -> Result<u64, CoordError> {
+        self.send(|tx, session| Command::AcquireReplicationSlot { name, session, tx })
+            .await
+    }
+
+    /// Records that the consumer of a replication slot has flushed all
+    /// changes up to and including `lsn`.
+    pub async fn confirm_replication_slot(
+        &mut self,
+        name: String,
+        lsn: u64,
+    ) -> Result<(), CoordError> {
+        self.send(|tx, session| Command::ConfirmReplicationSlot {
+            name,
+            lsn,
+            session,
+            tx,
+        })
+        .await
+    }
+
+    /// Marks a replication slot as no longer in use by this session.
+    pub async fn release_replication_slot(&mut self, name: String) -> Result<(), CoordError> {
+        self.send(|tx, session| Command::ReleaseReplicationSlot { name, session, tx })
+            .await
+    }
+
     /// Inserts a set of rows into the given table.
     ///
     /// The rows only contain the columns positions in `columns`, so they
@@ -387,51 +525,6 @@ impl SessionClient {
         &mut self,
         stmts: &str,
     ) -> Result<SimpleExecuteResponse, CoordError> {
-        // Convert most floats to a JSON Number. JSON Numbers don't support NaN or
-        // Infinity, so those will still be rendered as strings.
-        fn float_to_json(f: f64) -> serde_json::Value {
-            match serde_json::Number::from_f64(f) {
-                Some(n) => serde_json::Value::Number(n),
-                None => serde_json::Value::String(f.to_string()),
-            }
-        }
-
-        fn datum_to_json(datum: &Datum) -> serde_json::Value {
-            match datum {
-                // Convert some common things to a native JSON value. This doesn't need to be
-                // too exhaustive because the SQL-over-HTTP interface is currently not hooked
-                // up to arbitrary external user queries.
-                Datum::Null | Datum::JsonNull => serde_json::Value::Null,
-                Datum::False => serde_json::Value::Bool(false),
-                Datum::True => serde_json::Value::Bool(true),
-                Datum::Int16(n) => serde_json::Value::Number(serde_json::Number::from(*n)),
-                Datum::Int32(n) => serde_json::Value::Number(serde_json::Number::from(*n)),
-                Datum::Int64(n) => serde_json::Value::Number(serde_json::Number::from(*n)),
-                Datum::Float32(n) => float_to_json(n.into_inner() as f64),
-                Datum::Float64(n) => float_to_json(n.into_inner()),
-                Datum::Numeric(d) => {
-                    // serde_json requires floats to be finite
-                    if d.0.is_infinite() {
-                        serde_json::Value::String(d.0.to_string())
-                    } else {
-                        serde_json::Value::Number(
-                            serde_json::Number::from_f64(f64::try_from(d.0).unwrap()).unwrap(),
-                        )
-                    }
-                }
-                Datum::String(s) => serde_json::Value::String(s.to_string()),
-                Datum::List(list) => serde_json::Value::Array(
-                    list.iter().map(|entry| datum_to_json(&entry)).collect(),
-                ),
-                Datum::Map(map) => serde_json::Value::Object(
-                    map.iter()
-                        .map(|(k, v)| (k.to_owned(), datum_to_json(&v)))
-                        .collect(),
-                ),
-                _ => serde_json::Value::String(datum.to_string()),
-            }
-        }
-
         let stmts = mz_sql::parse::parse(&stmts).map_err(|e| CoordError::Unstructured(e.into()))?;
         let num_stmts = stmts.len();
         const EMPTY_PORTAL: &str = "";
@@ -500,6 +593,8 @@ impl SessionClient {
                 | ExecuteResponse::DroppedSecret
                 | ExecuteResponse::DroppedConnector
                 | ExecuteResponse::EmptyQuery
+                | ExecuteResponse::GrantedPrivilege
+                | ExecuteResponse::RevokedPrivilege
                 | ExecuteResponse::Inserted(_)
                 | ExecuteResponse::StartedTransaction { duplicated: _ }
                 | ExecuteResponse::TransactionExited {
@@ -514,7 +609,7 @@ impl SessionClient {
                     results.push(SimpleResult::Ok);
                 }
                 ExecuteResponse::SendingRows(rows) => {
-                    let rows = match rows.await {
+                    let rows = match collect_row_batches(rows).await {
                         PeekResponseUnary::Rows(rows) => rows,
                         PeekResponseUnary::Error(e) => {
                             results.push(SimpleResult::err(e.to_string()));
@@ -594,3 +689,50 @@ impl Drop for SessionClient {
         }
     }
 }
+
+/// Converts a datum to a JSON value.
+///
+/// Most floats are converted to a JSON Number. JSON Numbers don't support NaN
+/// or Infinity, so those are rendered as strings.
+pub fn datum_to_json(datum: &Datum) -> serde_json::Value {
+    fn float_to_json(f: f64) -> serde_json::Value {
+        match serde_json::Number::from_f64(f) {
+            Some(n) => serde_json::Value::Number(n),
+            None => serde_json::Value::String(f.to_string()),
+        }
+    }
+
+    match datum {
+        // Convert some common things to a native JSON value. Everything else is
+        // rendered as its text representation.
+        Datum::Null | Datum::JsonNull => serde_json::Value::Null,
+        Datum::False => serde_json::Value::Bool(false),
+        Datum::True => serde_json::Value::Bool(true),
+        Datum::Int16(n) => serde_json::Value::Number(serde_json::Number::from(*n)),
+        Datum::Int32(n) => serde_json::Value::Number(serde_json::Number::from(*n)),
+        Datum::Int64(n) => serde_json::Value::Number(serde_json::Number::from(*n)),
+        Datum::Float32(n) => float_to_json(n.into_inner() as f64),
+        Datum::Float64(n) => float_to_json(n.into_inner()),
+        Datum::Numeric(d) => {
+            // serde_json requires floats to be finite, so NaN and Infinity are
+            // rendered as strings, as for floats.
+            match f64::try_from(d.0)
+                .ok()
+                .and_then(serde_json::Number::from_f64)
+            {
+                Some(n) => serde_json::Value::Number(n),
+                None => serde_json::Value::String(d.0.to_string()),
+            }
+        }
+        Datum::String(s) => serde_json::Value::String(s.to_string()),
+        Datum::List(list) => {
+            serde_json::Value::Array(list.iter().map(|entry| datum_to_json(&entry)).collect())
+        }
+        Datum::Map(map) => serde_json::Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.to_owned(), datum_to_json(&v)))
+                .collect(),
+        ),
+        _ => serde_json::Value::String(datum.to_string()),
+    }
+}
@@ -8,11 +8,8 @@
 // by the Apache License, Version 2.0.
 
 use std::fmt;
-use std::future::Future;
-use std::pin::Pin;
 use std::sync::Arc;
 
-use derivative::Derivative;
 use serde::Serialize;
 use tokio::sync::oneshot;
 
@@ -22,8 +19,8 @@ use mz_sql::ast::{FetchDirection, NoticeSeverity, ObjectType, Raw, Statement};
 use mz_sql::plan::ExecuteTimeout;
 use tokio::sync::watch;
 
-use crate::coord::PeekResponseUnary;
 use crate::error::CoordError;
+use crate::scram::ScramVerifier;
 use crate::session::{EndTransactionAction, RowBatchStream, Session};
 
 #[derive(Debug)]
@@ -80,11 +77,48 @@ pub enum Command {
         secret_key: u32,
     },
 
+    PasswordVerifier {
+        role_name: String,
+        tx: oneshot::Sender<Option<ScramVerifier>>,
+    },
+
     DumpCatalog {
         session: Session,
         tx: oneshot::Sender<Response<String>>,
     },
 
+    CreateReplicationSlot {
+        name: String,
+        temporary: bool,
+        session: Session,
+        tx: oneshot::Sender<Response<()>>,
+    },
+
+    DropReplicationSlot {
+        name: String,
+        session: Session,
+        tx: oneshot::Sender<Response<()>>,
+    },
+
+    AcquireReplicationSlot {
+        name: String,
+        session: Session,
+        tx: oneshot::Sender<Response<u64>>,
+    },
+
+    ConfirmReplicationSlot {
+        name: String,
+        lsn: u64,
+        session: Session,
+        tx: oneshot::Sender<Response<()>>,
+    },
+
+    ReleaseReplicationSlot {
+        name: String,
+        session: Session,
+        tx: oneshot::Sender<Response<()>>,
+    },
+
     CopyRows {
         id: GlobalId,
         columns: Vec<usize>,
@@ -104,8 +138,6 @@ pub struct Response<T> {
     pub session: Session,
 }
 
-pub type RowsFuture = Pin<Box<dyn Future<Output = PeekResponseUnary> + Send>>;
-
 /// The response to [`ConnClient::startup`](crate::ConnClient::startup).
 #[derive(Debug)]
 pub struct StartupResponse {
@@ -152,8 +184,7 @@ impl fmt::Display for StartupMessage {
 }
 
 /// The response to [`SessionClient::execute`](crate::SessionClient::execute).
-#[derive(Derivative)]
-#[derivative(Debug)]
+#[derive(Debug)]
 pub enum ExecuteResponse {
     /// The active transaction was exited.
     TransactionExited {
@@ -267,6 +298,8 @@ pub enum ExecuteResponse {
     DroppedSecret,
     /// The provided query was empty.
     EmptyQuery,
+    /// The requested privileges were granted.
+    GrantedPrivilege,
     /// Fetch results from a cursor.
     Fetch {
         /// The name of the cursor from which to fetch results.
@@ -280,8 +313,10 @@ pub enum ExecuteResponse {
     Inserted(usize),
     /// The specified prepared statement was created.
     Prepare,
-    /// Rows will be delivered via the specified future.
-    SendingRows(#[derivative(Debug = "ignore")] RowsFuture),
+    /// The requested privileges were revoked.
+    RevokedPrivilege,
+    /// Rows will be delivered in batches via the specified stream.
+    SendingRows(RowBatchStream),
     /// The specified variable was set to a new value.
     SetVariable {
         name: String,
@@ -335,11 +370,24 @@ impl SimpleResult {
 }
 
 /// The state of a cancellation request.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Canceled {
     /// A cancellation request has occurred.
     Canceled,
+    /// The statement exceeded the session's `statement_timeout`.
+    StatementTimeout,
     /// No cancellation request has yet occurred, or a previous request has been
     /// cleared.
     NotCanceled,
 }
+
+impl Canceled {
+    /// Returns the message to report to the client for a statement canceled
+    /// for this reason.
+    pub fn message(&self) -> &'static str {
+        match self {
+            Canceled::StatementTimeout => "canceling statement due to statement timeout",
+            Canceled::Canceled | Canceled::NotCanceled => "canceling statement due to user request",
+        }
+    }
+}
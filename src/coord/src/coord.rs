@@ -85,6 +85,7 @@ use timely::progress::frontier::MutableAntichain;
 use timely::progress::{Antichain, Timestamp as TimelyTimestamp};
 use tokio::runtime::Handle as TokioHandle;
 use tokio::select;
+use tokio::sync::mpsc::error::TrySendError;
 use tokio::sync::{mpsc, oneshot, watch};
 use tracing::warn;
 use uuid::Uuid;
@@ -102,16 +103,18 @@ use mz_dataflow_types::sources::{
     AwsExternalId, ExternalSourceConnector, PostgresSourceConnector, SourceConnector, Timeline,
 };
 use mz_dataflow_types::{
-    BuildDesc, DataflowDesc, DataflowDescription, IndexDesc, PeekResponse, Update,
+    AnalyzedPlanExplanation, BuildDesc, DataflowDesc, DataflowDescription, IndexDesc, PeekResponse,
+    PlanNodeStatistics, Update,
 };
 use mz_expr::{
-    permutation_for_arrangement, CollectionPlan, ExprHumanizer, MirRelationExpr, MirScalarExpr,
-    OptimizedMirRelationExpr, RowSetFinishing,
+    permutation_for_arrangement, CollectionPlan, ExprHumanizer, IncrementalFinishing,
+    MirRelationExpr, MirScalarExpr, OptimizedMirRelationExpr, RowSetFinishing,
 };
 use mz_ore::collections::CollectionExt;
 use mz_ore::metrics::MetricsRegistry;
 use mz_ore::now::{to_datetime, EpochMillis, NowFn};
 use mz_ore::retry::Retry;
+use mz_ore::str::StrExt;
 use mz_ore::task;
 use mz_ore::thread::JoinHandleExt;
 use mz_repr::adt::interval::Interval;
@@ -122,8 +125,8 @@ use mz_repr::{
 use mz_secrets::{SecretOp, SecretsController};
 use mz_sql::ast::display::AstDisplay;
 use mz_sql::ast::{
-    CreateIndexStatement, CreateSourceStatement, ExplainStage, FetchStatement, Ident, InsertSource,
-    ObjectType, Query, Raw, RawIdent, SetExpr, Statement,
+    CreateIndexStatement, CreateSourceStatement, ExplainFormat, ExplainStage, FetchStatement,
+    Ident, InsertSource, ObjectType, Query, Raw, RawIdent, SetExpr, Statement,
 };
 use mz_sql::catalog::{
     CatalogComputeInstance, CatalogError, CatalogItemType, CatalogTypeDetails, SessionCatalog as _,
@@ -138,9 +141,10 @@ use mz_sql::plan::{
     CreateSinkPlan, CreateSourcePlan, CreateTablePlan, CreateTypePlan, CreateViewPlan,
     CreateViewsPlan, DropComputeInstanceReplicaPlan, DropComputeInstancesPlan, DropDatabasePlan,
     DropItemsPlan, DropRolesPlan, DropSchemaPlan, ExecutePlan, ExplainPlan, FetchPlan,
-    HirRelationExpr, IndexOption, IndexOptionName, InsertPlan, MutationKind, OptimizerConfig,
-    Params, PeekPlan, Plan, QueryWhen, RaisePlan, ReadThenWritePlan, ReplicaConfig, SendDiffsPlan,
-    SetVariablePlan, ShowVariablePlan, StatementDesc, TailFrom, TailPlan, View,
+    GrantPrivilegesPlan, HirRelationExpr, IndexOption, IndexOptionName, InsertPlan, MutationKind,
+    OptimizerConfig, Params, PeekPlan, Plan, QueryWhen, RaisePlan, ReadThenWritePlan,
+    ReplicaConfig, RevokePrivilegesPlan, SendDiffsPlan, SetTransactionPlan, SetVariablePlan,
+    ShowVariablePlan, StatementDesc, TailFrom, TailPlan, View,
 };
 use mz_sql_parser::ast::RawObjectName;
 use mz_transform::Optimizer;
@@ -156,10 +160,15 @@ use crate::command::{
 };
 use crate::coord::dataflow_builder::{prep_relation_expr, prep_scalar_expr, ExprPrepStyle};
 use crate::coord::id_bundle::CollectionIdBundle;
+use crate::coord::index_advisor::IndexAdvisor;
+use crate::coord::plan_cache::{OptimizedPeekPlan, PlanCache, PlanCacheMetrics};
+use crate::coord::replication_slots::ReplicationSlots;
+use crate::coord::statement_log::{PlanKind, StatementLog};
 use crate::error::CoordError;
+use crate::scram::ScramVerifier;
 use crate::session::{
-    EndTransactionAction, PreparedStatement, Session, Transaction, TransactionOps,
-    TransactionStatus, WriteOp,
+    collect_row_batches, row_batch_channel, EndTransactionAction, IsolationLevel,
+    PreparedStatement, Session, Transaction, TransactionOps, TransactionStatus, WriteOp,
 };
 use crate::sink_connector;
 use crate::tail::PendingTail;
@@ -168,7 +177,15 @@ use crate::util::ClientTransmitter;
 pub mod id_bundle;
 
 mod dataflow_builder;
+mod index_advisor;
 mod indexes;
+mod plan_cache;
+mod privileges;
+mod replication_slots;
+mod statement_log;
+
+pub use plan_cache::PlanCacheConfig;
+pub use statement_log::StatementLogConfig;
 
 #[derive(Debug)]
 pub enum Message {
@@ -179,6 +196,22 @@ pub enum Message {
     SendDiffs(SendDiffs),
     WriteLockGrant(tokio::sync::OwnedMutexGuard<()>),
     AdvanceLocalInputs,
+    StatementTimeout {
+        conn_id: u32,
+        statement_id: u64,
+    },
+    LockTimeout {
+        conn_id: u32,
+        statement_id: u64,
+    },
+    StatementLogEnd {
+        id: u64,
+        rows_returned: Option<usize>,
+        error: Option<String>,
+    },
+    StatementLogPrune,
+    /// A batch of rows of the identified peek has been consumed.
+    PeekBatchConsumed(Uuid),
 }
 
 #[derive(Derivative)]
@@ -239,17 +272,25 @@ pub struct Config<S> {
     pub secrets_controller: Box<dyn SecretsController>,
     pub availability_zones: Vec<String>,
     pub replica_sizes: ClusterReplicaSizeMap,
+    pub statement_log: StatementLogConfig,
+    pub plan_cache: PlanCacheConfig,
 }
 
 struct PendingPeek {
-    sender: mpsc::UnboundedSender<PeekResponse>,
+    sender: mpsc::Sender<PeekResponse>,
     conn_id: u32,
+    /// Responses that have yet to fit in the channel of `sender`.
+    ///
+    /// Each worker sends a batch of rows only once the previous batch it sent
+    /// has been consumed, so there are at most as many of these as there are
+    /// workers, plus the final response.
+    queued: VecDeque<PeekResponse>,
 }
 
-/// The response from a `Peek`, with row multiplicities represented in unary.
+/// A response from a `Peek`, with row multiplicities represented in unary.
 ///
-/// Note that each `Peek` expects to generate exactly one `PeekResponse`, i.e.
-/// we expect a 1:1 contract between `Peek` and `PeekResponseUnary`.
+/// The rows of a `Peek` may arrive in several `Rows` responses. An `Error` or
+/// `Canceled` response is the last response to its `Peek`.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub enum PeekResponseUnary {
     Rows(Vec<Row>),
@@ -363,6 +404,21 @@ pub struct Coordinator<S> {
     replica_sizes: ClusterReplicaSizeMap,
     /// Valid availability zones for replicas.
     availability_zones: Vec<String>,
+
+    /// The sampled log of executed statements.
+    statement_log: StatementLog,
+
+    /// Configures the plan caches.
+    plan_cache_config: PlanCacheConfig,
+    /// The cache of optimized plans shared by all sessions.
+    global_plan_cache: PlanCache,
+    plan_cache_metrics: PlanCacheMetrics,
+
+    /// Suggests indexes that would speed up recent peeks.
+    index_advisor: IndexAdvisor,
+
+    /// The replication slots of logical replication clients.
+    replication_slots: ReplicationSlots,
 }
 
 /// Metadata about an active connection.
@@ -379,6 +435,15 @@ struct ConnMeta {
     /// requests are required to authenticate with the secret of the connection
     /// that they are targeting.
     secret_key: u32,
+    /// Identifies the statement that the connection most recently began
+    /// executing, so that timeouts armed for earlier statements can be
+    /// recognized as stale.
+    statement_id: u64,
+    /// The statement log ID of the statement that the connection is
+    /// executing, if that statement was sampled for the statement log.
+    statement_log_id: Option<u64>,
+    /// The cache of optimized plans for the connection's session.
+    plan_cache: PlanCache,
 }
 
 struct TxnReads {
@@ -734,6 +799,7 @@ impl<S: Append + 'static> Coordinator<S> {
                 }
             });
         }
+        self.spawn_statement_log_pruner();
 
         loop {
             let msg = select! {
@@ -780,6 +846,32 @@ impl<S: Append + 'static> Coordinator<S> {
                     // and advance inputs.
                     self.global_timeline.fast_forward(self.now());
                 }
+                Message::StatementTimeout {
+                    conn_id,
+                    statement_id,
+                } => {
+                    if self.is_current_statement(conn_id, statement_id) {
+                        self.cancel_conn(conn_id, Canceled::StatementTimeout).await;
+                    }
+                }
+                Message::LockTimeout {
+                    conn_id,
+                    statement_id,
+                } => {
+                    if self.is_current_statement(conn_id, statement_id) {
+                        self.cancel_deferred_write(conn_id, Err(CoordError::LockTimeout));
+                    }
+                }
+                Message::StatementLogEnd {
+                    id,
+                    rows_returned,
+                    error,
+                } => {
+                    self.message_statement_log_end(id, rows_returned, error)
+                        .await
+                }
+                Message::StatementLogPrune => self.message_statement_log_prune().await,
+                Message::PeekBatchConsumed(uuid) => self.message_peek_batch_consumed(uuid).await,
             }
 
             if let Some(timestamp) = self.global_timeline.should_advance_to() {
@@ -809,25 +901,12 @@ impl<S: Append + 'static> Coordinator<S> {
     async fn message_controller(&mut self, message: ControllerResponse) {
         match message {
             ControllerResponse::PeekResponse(uuid, response) => {
-                // We expect exactly one peek response, which we forward. Then we clean up the
-                // peek's state in the coordinator.
-                if let Some(PendingPeek {
-                    sender: rows_tx,
-                    conn_id,
-                }) = self.pending_peeks.remove(&uuid)
-                {
-                    rows_tx
-                        .send(response)
-                        .expect("Peek endpoint terminated prematurely");
-                    let uuids = self
-                        .client_pending_peeks
-                        .get_mut(&conn_id)
-                        .unwrap_or_else(|| panic!("no client state for connection {conn_id}"));
-                    uuids.remove(&uuid);
-                    if uuids.is_empty() {
-                        self.client_pending_peeks.remove(&conn_id);
-                    }
-                } else if response != PeekResponse::Canceled {
+                // We forward the responses as they arrive, through to exactly one final
+                // response, and then clean up the peek's state in the coordinator.
+                if let Some(pending_peek) = self.pending_peeks.get_mut(&uuid) {
+                    pending_peek.queued.push_back(response);
+                    self.forward_peek_responses(uuid).await;
+                } else if response.is_final() && response != PeekResponse::Canceled {
                     // Cancel is handled by handle_cancel, so do not need to log them here.
                     warn!("Received a PeekResponse without a pending peek: {uuid}");
                 }
@@ -870,10 +949,16 @@ impl<S: Append + 'static> Coordinator<S> {
             .handle_statement(&mut session, Statement::CreateSource(stmt), &params)
             .await
         {
-            Ok(Plan::CreateSource(plan)) => plan,
-            Ok(_) => unreachable!("planning CREATE SOURCE must result in a Plan::CreateSource"),
+            Ok(plan) => plan,
             Err(e) => return tx.send(Err(e), session),
         };
+        if let Err(e) = self.check_privileges(&session, &plan) {
+            return tx.send(Err(e), session);
+        }
+        let plan = match plan {
+            Plan::CreateSource(plan) => plan,
+            _ => unreachable!("planning CREATE SOURCE must result in a Plan::CreateSource"),
+        };
 
         let result = self.sequence_create_source(&mut session, plan).await;
         tx.send(result, session);
@@ -916,9 +1001,11 @@ impl<S: Append + 'static> Coordinator<S> {
             Err(e) => {
                 // Drop the placeholder sink if still present.
                 if self.catalog.try_get_entry(&id).is_some() {
-                    self.catalog_transact(vec![catalog::Op::DropItem(id)], |_| Ok(()))
-                        .await
-                        .expect("deleting placeholder sink cannot fail");
+                    self.catalog_transact(Some(&session), vec![catalog::Op::DropItem(id)], |_| {
+                        Ok(())
+                    })
+                    .await
+                    .expect("deleting placeholder sink cannot fail");
                 } else {
                     // Another session may have dropped the placeholder sink while we were
                     // attempting to create the connector, in which case we don't need to do
@@ -994,8 +1081,10 @@ impl<S: Append + 'static> Coordinator<S> {
                     }
                     let plan = CreateRolePlan {
                         name: session.user().to_string(),
+                        superuser: true,
+                        password: None,
                     };
-                    if let Err(err) = self.sequence_create_role(plan).await {
+                    if let Err(err) = self.sequence_create_role(&session, plan).await {
                         let _ = tx.send(Response {
                             result: Err(err),
                             session,
@@ -1019,6 +1108,9 @@ impl<S: Append + 'static> Coordinator<S> {
                     ConnMeta {
                         cancel_tx,
                         secret_key,
+                        statement_id: 0,
+                        statement_log_id: None,
+                        plan_cache: PlanCache::default(),
                     },
                 );
 
@@ -1069,6 +1161,10 @@ impl<S: Append + 'static> Coordinator<S> {
                 self.handle_cancel(conn_id, secret_key).await;
             }
 
+            Command::PasswordVerifier { role_name, tx } => {
+                let _ = tx.send(self.catalog.password_verifier(&role_name).cloned());
+            }
+
             Command::DumpCatalog { session, tx } => {
                 // TODO(benesch): when we have RBAC, dumping the catalog should
                 // require superuser permissions.
@@ -1079,6 +1175,46 @@ impl<S: Append + 'static> Coordinator<S> {
                 });
             }
 
+            Command::CreateReplicationSlot {
+                name,
+                temporary,
+                session,
+                tx,
+            } => {
+                let result = self
+                    .create_replication_slot(&session, name, temporary)
+                    .await;
+                let _ = tx.send(Response { result, session });
+            }
+
+            Command::DropReplicationSlot { name, session, tx } => {
+                let result = self.drop_replication_slot(&name).await;
+                let _ = tx.send(Response { result, session });
+            }
+
+            Command::AcquireReplicationSlot { name, session, tx } => {
+                let result = self.acquire_replication_slot(&session, &name);
+                let _ = tx.send(Response { result, session });
+            }
+
+            Command::ConfirmReplicationSlot {
+                name,
+                lsn,
+                session,
+                tx,
+            } => {
+                let result = self.confirm_replication_slot(&name, lsn).await;
+                let _ = tx.send(Response { result, session });
+            }
+
+            Command::ReleaseReplicationSlot { name, session, tx } => {
+                self.release_replication_slot(&session, &name);
+                let _ = tx.send(Response {
+                    result: Ok(()),
+                    session,
+                });
+            }
+
             Command::CopyRows {
                 id,
                 columns,
@@ -1115,6 +1251,7 @@ impl<S: Append + 'static> Coordinator<S> {
                 session,
                 tx,
             } => {
+                self.begin_statement(&session);
                 let tx = ClientTransmitter::new(tx, self.internal_cmd_tx.clone());
                 self.sequence_end_transaction(tx, session, action).await;
             }
@@ -1264,6 +1401,8 @@ impl<S: Append + 'static> Coordinator<S> {
         mut session: Session,
         tx: ClientTransmitter<ExecuteResponse>,
     ) {
+        self.begin_statement(&session);
+
         if let Err(err) = self.verify_portal(&mut session, &portal_name) {
             return tx.send(Err(err), session);
         }
@@ -1276,6 +1415,7 @@ impl<S: Append + 'static> Coordinator<S> {
             Some(stmt) => stmt,
             None => return tx.send(Ok(ExecuteResponse::EmptyQuery), session),
         };
+        let tx = self.begin_statement_log(&session, stmt, tx);
 
         // Verify that this statement type can be executed in the current
         // transaction state.
@@ -1384,6 +1524,8 @@ impl<S: Append + 'static> Coordinator<S> {
                     | Statement::DropRoles(_)
                     | Statement::DropClusters(_)
                     | Statement::DropClusterReplicas(_)
+                    | Statement::GrantPrivileges(_)
+                    | Statement::RevokePrivileges(_)
                     | Statement::Insert(_)
                     | Statement::Update(_) => {
                         return tx.send(
@@ -1429,6 +1571,11 @@ impl<S: Append + 'static> Coordinator<S> {
                 });
             }
 
+            // `SELECT` statements may reuse a cached plan.
+            Statement::Select(_) if self.plan_cache_config.enabled() => {
+                self.sequence_select(tx, session, stmt, params).await
+            }
+
             // All other statements are handled immediately.
             _ => match self.handle_statement(&mut session, stmt, &params).await {
                 Ok(plan) => self.sequence_plan(tx, session, plan).await,
@@ -1437,6 +1584,48 @@ impl<S: Append + 'static> Coordinator<S> {
         }
     }
 
+    /// Records that the session has begun executing a new statement, and arms
+    /// the session's `statement_timeout`, if any.
+    fn begin_statement(&mut self, session: &Session) {
+        let conn_id = session.conn_id();
+        let statement_id = match self.active_conns.get_mut(&conn_id) {
+            Some(conn_meta) => {
+                conn_meta.statement_id += 1;
+                conn_meta.statement_log_id = None;
+                conn_meta.statement_id
+            }
+            None => return,
+        };
+        let timeout = session.vars().statement_timeout();
+        if !timeout.is_zero() {
+            self.schedule_message(
+                timeout,
+                Message::StatementTimeout {
+                    conn_id,
+                    statement_id,
+                },
+            );
+        }
+    }
+
+    /// Reports whether `statement_id` identifies the statement that the
+    /// connection is currently executing.
+    fn is_current_statement(&self, conn_id: u32, statement_id: u64) -> bool {
+        self.active_conns
+            .get(&conn_id)
+            .map_or(false, |conn_meta| conn_meta.statement_id == statement_id)
+    }
+
+    /// Sends `msg` to the coordinator after `delay` has elapsed.
+    fn schedule_message(&self, delay: Duration, msg: Message) {
+        let internal_cmd_tx = self.internal_cmd_tx.clone();
+        task::spawn(|| "coordinator_schedule_message", async move {
+            tokio::time::sleep(delay).await;
+            // If sending fails, the main thread has shutdown.
+            let _ = internal_cmd_tx.send(msg);
+        });
+    }
+
     /// Instruct the dataflow layer to cancel any ongoing, interactive work for
     /// the named `conn_id`.
     async fn handle_cancel(&mut self, conn_id: u32, secret_key: u32) {
@@ -1447,19 +1636,126 @@ impl<S: Append + 'static> Coordinator<S> {
             if conn_meta.secret_key != secret_key {
                 return;
             }
+            self.cancel_conn(conn_id, Canceled::Canceled).await;
+        }
+    }
 
-            // Cancel deferred writes. There is at most one pending write per session.
-            if let Some(idx) = self
-                .write_lock_wait_group
-                .iter()
-                .position(|ready| ready.session.conn_id() == conn_id)
-            {
-                let ready = self.write_lock_wait_group.remove(idx).unwrap();
-                ready.tx.send(Ok(ExecuteResponse::Canceled), ready.session);
+    /// Responds to the deferred write for the named `conn_id`, if any, with
+    /// `response` rather than executing it.
+    fn cancel_deferred_write(
+        &mut self,
+        conn_id: u32,
+        response: Result<ExecuteResponse, CoordError>,
+    ) {
+        // There is at most one pending write per session.
+        if let Some(idx) = self
+            .write_lock_wait_group
+            .iter()
+            .position(|ready| ready.session.conn_id() == conn_id)
+        {
+            let ready = self.write_lock_wait_group.remove(idx).unwrap();
+            ready.tx.send(response, ready.session);
+        }
+    }
+
+    /// Forwards the queued responses of a peek for as long as they fit in its
+    /// channel.
+    ///
+    /// Should the receiver have hung up before the final response, nothing wants
+    /// the remaining rows, and we cancel the peek.
+    async fn forward_peek_responses(&mut self, uuid: Uuid) {
+        let pending_peek = match self.pending_peeks.get_mut(&uuid) {
+            Some(pending_peek) => pending_peek,
+            None => return,
+        };
+        while let Some(response) = pending_peek.queued.pop_front() {
+            let is_final = response.is_final();
+            match pending_peek.sender.try_send(response) {
+                Ok(()) => {
+                    if is_final {
+                        self.remove_pending_peek(uuid);
+                        return;
+                    }
+                }
+                Err(TrySendError::Full(response)) => {
+                    pending_peek.queued.push_front(response);
+                    return;
+                }
+                Err(TrySendError::Closed(_)) => {
+                    // The receiver may have hung up once it had all the rows it wanted.
+                    if is_final {
+                        self.remove_pending_peek(uuid);
+                    } else {
+                        self.cancel_peek(uuid).await;
+                    }
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Allows a peek to send another batch of rows now that one of its batches
+    /// has been consumed, and forwards its further responses.
+    ///
+    /// Should nothing receive the responses any longer, we cancel the peek.
+    async fn message_peek_batch_consumed(&mut self, uuid: Uuid) {
+        match self.pending_peeks.get(&uuid) {
+            Some(pending_peek) if pending_peek.sender.is_closed() => self.cancel_peek(uuid).await,
+            Some(pending_peek) => {
+                let compute_instance = self.client_pending_peeks[&pending_peek.conn_id][&uuid];
+                self.dataflow_client
+                    .compute_mut(compute_instance)
+                    .unwrap()
+                    .continue_peeks(BTreeSet::from([uuid]))
+                    .await
+                    .unwrap();
+                self.forward_peek_responses(uuid).await;
             }
+            None => (),
+        }
+    }
+
+    /// Removes the state of a pending peek, and reports the compute instance on
+    /// which the peek is present.
+    fn remove_pending_peek(&mut self, uuid: Uuid) -> Option<(PendingPeek, ComputeInstanceId)> {
+        let pending_peek = self.pending_peeks.remove(&uuid)?;
+        let conn_id = pending_peek.conn_id;
+        let uuids = self
+            .client_pending_peeks
+            .get_mut(&conn_id)
+            .unwrap_or_else(|| panic!("no client state for connection {conn_id}"));
+        let compute_instance = uuids
+            .remove(&uuid)
+            .unwrap_or_else(|| panic!("no compute instance for peek {uuid}"));
+        if uuids.is_empty() {
+            self.client_pending_peeks.remove(&conn_id);
+        }
+        Some((pending_peek, compute_instance))
+    }
 
+    /// Cancels a peek whose rows are no longer wanted.
+    ///
+    /// Unlike the peeks canceled along with their connection, nothing awaits
+    /// the rows of the peek, so there is nothing to inform of the cancellation.
+    async fn cancel_peek(&mut self, uuid: Uuid) {
+        if let Some((_, compute_instance)) = self.remove_pending_peek(uuid) {
+            self.dataflow_client
+                .compute_mut(compute_instance)
+                .unwrap()
+                .cancel_peeks(&BTreeSet::from([uuid]))
+                .await
+                .unwrap();
+        }
+    }
+
+    /// Cancels the ongoing, interactive work for the named `conn_id` for the
+    /// specified reason.
+    async fn cancel_conn(&mut self, conn_id: u32, reason: Canceled) {
+        if let Some(conn_meta) = self.active_conns.get(&conn_id) {
             // Inform the target session (if it asks) about the cancellation.
-            let _ = conn_meta.cancel_tx.send(Canceled::Canceled);
+            let _ = conn_meta.cancel_tx.send(reason);
+
+            self.cancel_deferred_write(conn_id, Ok(ExecuteResponse::Canceled));
 
             // The peek is present on some specific compute instance.
             // Allow dataflow to cancel any pending peeks.
@@ -1478,13 +1774,14 @@ impl<S: Append + 'static> Coordinator<S> {
                 }
                 for (uuid, _) in uuids {
                     if let Some(PendingPeek {
-                        sender: rows_tx,
-                        conn_id: _,
+                        sender: rows_tx, ..
                     }) = self.pending_peeks.remove(&uuid)
                     {
-                        rows_tx
-                            .send(PeekResponse::Canceled)
-                            .expect("Peek endpoint terminated prematurely");
+                        // The cancellation follows whatever responses the channel holds.
+                        // The receiver may have hung up once it had all the rows it wanted.
+                        task::spawn(|| format!("cancel_peek:{uuid}"), async move {
+                            let _ = rows_tx.send(PeekResponse::Canceled).await;
+                        });
                     } else {
                         warn!("Received a cancel request without a pending peek: {uuid}");
                     }
@@ -1503,6 +1800,7 @@ impl<S: Append + 'static> Coordinator<S> {
         self.catalog
             .drop_temporary_schema(session.conn_id())
             .expect("unable to drop temporary schema");
+        self.terminate_replication_slots(session.conn_id());
         self.active_conns.remove(&session.conn_id());
     }
 
@@ -1526,7 +1824,7 @@ impl<S: Append + 'static> Coordinator<S> {
     /// not the temporary schema itself.
     async fn drop_temp_items(&mut self, conn_id: u32) {
         let ops = self.catalog.drop_temp_item_ops(conn_id);
-        self.catalog_transact(ops, |_| Ok(()))
+        self.catalog_transact(None, ops, |_| Ok(()))
             .await
             .expect("unable to drop temporary items for conn_id");
     }
@@ -1541,6 +1839,7 @@ impl<S: Append + 'static> Coordinator<S> {
         // Update catalog entry with sink connector.
         let entry = self.catalog.get_entry(&id);
         let name = entry.name().clone();
+        let owner_id = entry.owner_id();
         let mut sink = match entry.item() {
             CatalogItem::Sink(sink) => sink.clone(),
             _ => unreachable!(),
@@ -1566,10 +1865,11 @@ impl<S: Append + 'static> Coordinator<S> {
                 oid,
                 name: name.clone(),
                 item: CatalogItem::Sink(sink.clone()),
+                owner_id,
             },
         ];
         let df = self
-            .catalog_transact(ops, |txn| {
+            .catalog_transact(None, ops, |txn| {
                 let mut builder = txn.dataflow_builder(compute_instance);
                 let from_entry = builder.catalog.get_entry(&sink.from);
                 let sink_description = mz_dataflow_types::sinks::SinkDesc {
@@ -1599,25 +1899,36 @@ impl<S: Append + 'static> Coordinator<S> {
         mut session: Session,
         plan: Plan,
     ) {
+        if let Err(e) = self.check_privileges(&session, &plan) {
+            return tx.send(Err(e), session);
+        }
+
         match plan {
             Plan::CreateConnector(plan) => {
-                tx.send(self.sequence_create_connector(plan).await, session);
+                tx.send(
+                    self.sequence_create_connector(&session, plan).await,
+                    session,
+                );
             }
             Plan::CreateDatabase(plan) => {
-                tx.send(self.sequence_create_database(plan).await, session);
+                tx.send(self.sequence_create_database(&session, plan).await, session);
             }
             Plan::CreateSchema(plan) => {
-                tx.send(self.sequence_create_schema(plan).await, session);
+                tx.send(self.sequence_create_schema(&session, plan).await, session);
             }
             Plan::CreateRole(plan) => {
-                tx.send(self.sequence_create_role(plan).await, session);
+                tx.send(self.sequence_create_role(&session, plan).await, session);
             }
             Plan::CreateComputeInstance(plan) => {
-                tx.send(self.sequence_create_compute_instance(plan).await, session);
+                tx.send(
+                    self.sequence_create_compute_instance(&session, plan).await,
+                    session,
+                );
             }
             Plan::CreateComputeInstanceReplica(plan) => {
                 tx.send(
-                    self.sequence_create_compute_instance_replica(plan).await,
+                    self.sequence_create_compute_instance_replica(&session, plan)
+                        .await,
                     session,
                 );
             }
@@ -1641,31 +1952,47 @@ impl<S: Append + 'static> Coordinator<S> {
                 );
             }
             Plan::CreateIndex(plan) => {
-                tx.send(self.sequence_create_index(plan).await, session);
+                tx.send(self.sequence_create_index(&session, plan).await, session);
             }
             Plan::CreateType(plan) => {
-                tx.send(self.sequence_create_type(plan).await, session);
+                tx.send(self.sequence_create_type(&session, plan).await, session);
             }
             Plan::DropDatabase(plan) => {
-                tx.send(self.sequence_drop_database(plan).await, session);
+                tx.send(self.sequence_drop_database(&session, plan).await, session);
             }
             Plan::DropSchema(plan) => {
-                tx.send(self.sequence_drop_schema(plan).await, session);
+                tx.send(self.sequence_drop_schema(&session, plan).await, session);
             }
             Plan::DropRoles(plan) => {
-                tx.send(self.sequence_drop_roles(plan).await, session);
+                tx.send(self.sequence_drop_roles(&session, plan).await, session);
             }
             Plan::DropComputeInstances(plan) => {
-                tx.send(self.sequence_drop_compute_instances(plan).await, session);
+                tx.send(
+                    self.sequence_drop_compute_instances(&session, plan).await,
+                    session,
+                );
             }
             Plan::DropComputeInstanceReplica(plan) => {
                 tx.send(
-                    self.sequence_drop_compute_instance_replica(plan).await,
+                    self.sequence_drop_compute_instance_replica(&session, plan)
+                        .await,
                     session,
                 );
             }
             Plan::DropItems(plan) => {
-                tx.send(self.sequence_drop_items(plan).await, session);
+                tx.send(self.sequence_drop_items(&session, plan).await, session);
+            }
+            Plan::GrantPrivileges(plan) => {
+                tx.send(
+                    self.sequence_grant_privileges(&session, plan).await,
+                    session,
+                );
+            }
+            Plan::RevokePrivileges(plan) => {
+                tx.send(
+                    self.sequence_revoke_privileges(&session, plan).await,
+                    session,
+                );
             }
             Plan::EmptyQuery => {
                 tx.send(Ok(ExecuteResponse::EmptyQuery), session);
@@ -1682,12 +2009,24 @@ impl<S: Append + 'static> Coordinator<S> {
             Plan::StartTransaction(plan) => {
                 let duplicated =
                     matches!(session.transaction(), TransactionStatus::InTransaction(_));
-                let session = session.start_transaction(self.now_datetime(), plan.access);
+                let mut session = session.start_transaction(self.now_datetime(), plan.access);
+                if let Some(level) = plan.isolation_level {
+                    if let Err(e) = session.vars_mut().set(
+                        "transaction_isolation",
+                        IsolationLevel::from(level).as_str(),
+                        true,
+                    ) {
+                        return tx.send(Err(e), session);
+                    }
+                }
                 tx.send(
                     Ok(ExecuteResponse::StartedTransaction { duplicated }),
                     session,
                 )
             }
+            Plan::SetTransaction(plan) => {
+                tx.send(self.sequence_set_transaction(&mut session, plan), session);
+            }
 
             Plan::CommitTransaction | Plan::AbortTransaction => {
                 let action = match plan {
@@ -1717,6 +2056,9 @@ impl<S: Append + 'static> Coordinator<S> {
                     session,
                 );
             }
+            Plan::Explain(plan) if plan.stage == ExplainStage::Analyze => {
+                tx.send(self.sequence_explain_analyze(&session, plan).await, session);
+            }
             Plan::Explain(plan) => {
                 tx.send(self.sequence_explain(&session, plan), session);
             }
@@ -1736,7 +2078,10 @@ impl<S: Append + 'static> Coordinator<S> {
                 );
             }
             Plan::AlterItemRename(plan) => {
-                tx.send(self.sequence_alter_item_rename(plan).await, session);
+                tx.send(
+                    self.sequence_alter_item_rename(&session, plan).await,
+                    session,
+                );
             }
             Plan::AlterIndexSetOptions(plan) => {
                 tx.send(self.sequence_alter_index_set_options(plan).await, session);
@@ -1866,8 +2211,10 @@ impl<S: Append + 'static> Coordinator<S> {
 
     async fn sequence_create_connector(
         &mut self,
+        session: &Session,
         plan: CreateConnectorPlan,
     ) -> Result<ExecuteResponse, CoordError> {
+        let owner_id = self.session_role_id(session)?;
         let connector_oid = self.catalog.allocate_oid().await?;
         let connector_gid = self.catalog.allocate_user_id().await?;
         let ops = vec![catalog::Op::CreateItem {
@@ -1878,8 +2225,9 @@ impl<S: Append + 'static> Coordinator<S> {
                 create_sql: plan.connector.create_sql,
                 connector: plan.connector.connector,
             }),
+            owner_id,
         }];
-        match self.catalog_transact(ops, |_| Ok(())).await {
+        match self.catalog_transact(Some(session), ops, |_| Ok(())).await {
             Ok(_) => Ok(ExecuteResponse::CreatedConnector { existed: false }),
             Err(CoordError::Catalog(catalog::Error {
                 kind: catalog::ErrorKind::ItemAlreadyExists(_),
@@ -1891,16 +2239,19 @@ impl<S: Append + 'static> Coordinator<S> {
 
     async fn sequence_create_database(
         &mut self,
+        session: &Session,
         plan: CreateDatabasePlan,
     ) -> Result<ExecuteResponse, CoordError> {
+        let owner_id = self.session_role_id(session)?;
         let db_oid = self.catalog.allocate_oid().await?;
         let schema_oid = self.catalog.allocate_oid().await?;
         let ops = vec![catalog::Op::CreateDatabase {
             name: plan.name.clone(),
             oid: db_oid,
             public_schema_oid: schema_oid,
+            owner_id,
         }];
-        match self.catalog_transact(ops, |_| Ok(())).await {
+        match self.catalog_transact(Some(session), ops, |_| Ok(())).await {
             Ok(_) => Ok(ExecuteResponse::CreatedDatabase { existed: false }),
             Err(CoordError::Catalog(catalog::Error {
                 kind: catalog::ErrorKind::DatabaseAlreadyExists(_),
@@ -1912,15 +2263,21 @@ impl<S: Append + 'static> Coordinator<S> {
 
     async fn sequence_create_schema(
         &mut self,
+        session: &Session,
         plan: CreateSchemaPlan,
     ) -> Result<ExecuteResponse, CoordError> {
+        let owner_id = self.session_role_id(session)?;
         let oid = self.catalog.allocate_oid().await?;
         let op = catalog::Op::CreateSchema {
             database_id: plan.database_spec,
             schema_name: plan.schema_name,
             oid,
+            owner_id,
         };
-        match self.catalog_transact(vec![op], |_| Ok(())).await {
+        match self
+            .catalog_transact(Some(session), vec![op], |_| Ok(()))
+            .await
+        {
             Ok(_) => Ok(ExecuteResponse::CreatedSchema { existed: false }),
             Err(CoordError::Catalog(catalog::Error {
                 kind: catalog::ErrorKind::SchemaAlreadyExists(_),
@@ -1932,26 +2289,37 @@ impl<S: Append + 'static> Coordinator<S> {
 
     async fn sequence_create_role(
         &mut self,
+        session: &Session,
         plan: CreateRolePlan,
     ) -> Result<ExecuteResponse, CoordError> {
+        let password_verifier = match plan.password {
+            Some(password) => Some(
+                ScramVerifier::new(&password).map_err(|e| CoordError::Unstructured(anyhow!(e)))?,
+            ),
+            None => None,
+        };
         let oid = self.catalog.allocate_oid().await?;
         let op = catalog::Op::CreateRole {
             name: plan.name,
             oid,
+            password_verifier,
+            superuser: plan.superuser,
         };
-        self.catalog_transact(vec![op], |_| Ok(()))
+        self.catalog_transact(Some(session), vec![op], |_| Ok(()))
             .await
             .map(|_| ExecuteResponse::CreatedRole)
     }
 
     async fn sequence_create_compute_instance(
         &mut self,
+        session: &Session,
         CreateComputeInstancePlan {
             name,
             config,
             replicas,
         }: CreateComputeInstancePlan,
     ) -> Result<ExecuteResponse, CoordError> {
+        let owner_id = self.session_role_id(session)?;
         let introspection_sources = if config.is_some() {
             self.catalog.allocate_introspection_source_indexes().await
         } else {
@@ -1961,6 +2329,7 @@ impl<S: Append + 'static> Coordinator<S> {
             name: name.clone(),
             config: config.clone(),
             introspection_sources,
+            owner_id,
         }];
 
         for (replica_name, config) in replicas {
@@ -1972,7 +2341,8 @@ impl<S: Append + 'static> Coordinator<S> {
                 on_cluster_name: name.clone(),
             });
         }
-        self.catalog_transact(ops, |_| Ok(())).await?;
+        self.catalog_transact(Some(session), ops, |_| Ok(()))
+            .await?;
         let instance = self
             .catalog
             .resolve_compute_instance(&name)
@@ -1992,6 +2362,7 @@ impl<S: Append + 'static> Coordinator<S> {
 
     async fn sequence_create_compute_instance_replica(
         &mut self,
+        session: &Session,
         CreateComputeInstanceReplicaPlan {
             name,
             of_cluster,
@@ -2006,7 +2377,8 @@ impl<S: Append + 'static> Coordinator<S> {
             on_cluster_name: of_cluster.clone(),
         };
 
-        self.catalog_transact(vec![op], |_| Ok(())).await?;
+        self.catalog_transact(Some(session), vec![op], |_| Ok(()))
+            .await?;
 
         let instance = self.catalog.resolve_compute_instance(&of_cluster)?;
         let replica_id = instance.replica_id_by_name[&name];
@@ -2022,6 +2394,7 @@ impl<S: Append + 'static> Coordinator<S> {
         session: &Session,
         plan: CreateSecretPlan,
     ) -> Result<ExecuteResponse, CoordError> {
+        let owner_id = self.session_role_id(session)?;
         let CreateSecretPlan {
             name,
             mut secret,
@@ -2049,9 +2422,10 @@ impl<S: Append + 'static> Coordinator<S> {
             oid,
             name,
             item: CatalogItem::Secret(secret.clone()),
+            owner_id,
         }];
 
-        match self.catalog_transact(ops, |_| Ok(())).await {
+        match self.catalog_transact(Some(session), ops, |_| Ok(())).await {
             Ok(()) => Ok(ExecuteResponse::CreatedSecret { existed: false }),
             Err(CoordError::Catalog(catalog::Error {
                 kind: catalog::ErrorKind::ItemAlreadyExists(_),
@@ -2081,6 +2455,7 @@ impl<S: Append + 'static> Coordinator<S> {
         session: &Session,
         plan: CreateTablePlan,
     ) -> Result<ExecuteResponse, CoordError> {
+        let owner_id = self.session_role_id(session)?;
         let CreateTablePlan {
             name,
             table,
@@ -2108,8 +2483,9 @@ impl<S: Append + 'static> Coordinator<S> {
             oid: table_oid,
             name,
             item: CatalogItem::Table(table.clone()),
+            owner_id,
         }];
-        match self.catalog_transact(ops, |_| Ok(())).await {
+        match self.catalog_transact(Some(session), ops, |_| Ok(())).await {
             Ok(()) => {
                 // Determine the initial validity for the table.
                 let since_ts = self.get_local_write_ts();
@@ -2148,6 +2524,7 @@ impl<S: Append + 'static> Coordinator<S> {
         session: &mut Session,
         plan: CreateSourcePlan,
     ) -> Result<ExecuteResponse, CoordError> {
+        let owner_id = self.session_role_id(session)?;
         let mut ops = vec![];
         let source_id = self.catalog.allocate_user_id().await?;
         let source_oid = self.catalog.allocate_oid().await?;
@@ -2162,6 +2539,7 @@ impl<S: Append + 'static> Coordinator<S> {
             oid: source_oid,
             name: plan.name.clone(),
             item: CatalogItem::Source(source.clone()),
+            owner_id,
         });
         let index = if plan.materialized {
             let compute_instance = self
@@ -2193,13 +2571,14 @@ impl<S: Append + 'static> Coordinator<S> {
                 oid: index_oid,
                 name: index_name,
                 item: CatalogItem::Index(index),
+                owner_id,
             });
             Some((index_id, compute_instance))
         } else {
             None
         };
         match self
-            .catalog_transact(ops, move |txn| {
+            .catalog_transact(Some(&*session), ops, move |txn| {
                 if let Some((index_id, compute_instance)) = index {
                     let mut builder = txn.dataflow_builder(compute_instance);
                     Ok(Some((
@@ -2269,6 +2648,14 @@ impl<S: Append + 'static> Coordinator<S> {
         // Use this in `catalog_transact` and stash for eventual sink construction.
         let compute_instance = sink.compute_instance;
 
+        let owner_id = match self.session_role_id(&session) {
+            Ok(owner_id) => owner_id,
+            Err(e) => {
+                tx.send(Err(e), session);
+                return;
+            }
+        };
+
         // First try to allocate an ID and an OID. If either fails, we're done.
         let id = match self.catalog.allocate_user_id().await {
             Ok(id) => id,
@@ -2304,10 +2691,11 @@ impl<S: Append + 'static> Coordinator<S> {
                 depends_on: sink.depends_on,
                 compute_instance,
             }),
+            owner_id,
         };
 
         let transact_result = self
-            .catalog_transact(vec![op], |txn| -> Result<(), CoordError> {
+            .catalog_transact(Some(&session), vec![op], |txn| -> Result<(), CoordError> {
                 let from_entry = txn.catalog.get_entry(&sink.from);
                 // Insert a dummy dataflow to trigger validation before we try to actually create
                 // the external sink resources (e.g. Kafka Topics)
@@ -2380,6 +2768,7 @@ impl<S: Append + 'static> Coordinator<S> {
         materialize: bool,
     ) -> Result<(Vec<catalog::Op>, Option<(GlobalId, ComputeInstanceId)>), CoordError> {
         self.validate_timeline(view.expr.depends_on())?;
+        let owner_id = self.session_role_id(session)?;
 
         let mut ops = vec![];
 
@@ -2400,12 +2789,14 @@ impl<S: Append + 'static> Coordinator<S> {
                 None
             },
             depends_on: view.depends_on,
+            native_outer_joins: session.vars().native_outer_joins(),
         };
         ops.push(catalog::Op::CreateItem {
             id: view_id,
             oid: view_oid,
             name: name.clone(),
             item: CatalogItem::View(view.clone()),
+            owner_id,
         });
         let index_id = if materialize {
             let compute_instance = self
@@ -2437,6 +2828,7 @@ impl<S: Append + 'static> Coordinator<S> {
                 oid: index_oid,
                 name: index_name,
                 item: CatalogItem::Index(index),
+                owner_id,
             });
             Some((index_id, compute_instance))
         } else {
@@ -2462,7 +2854,7 @@ impl<S: Append + 'static> Coordinator<S> {
             )
             .await?;
         match self
-            .catalog_transact(ops, |txn| {
+            .catalog_transact(Some(session), ops, |txn| {
                 if let Some((index_id, compute_instance)) = index {
                     let mut builder = txn.dataflow_builder(compute_instance);
                     Ok(Some((
@@ -2505,7 +2897,7 @@ impl<S: Append + 'static> Coordinator<S> {
             indexes.extend(index);
         }
         match self
-            .catalog_transact(ops, |txn| {
+            .catalog_transact(Some(&*session), ops, |txn| {
                 let mut dfs = HashMap::new();
                 for (index_id, compute_instance) in indexes {
                     let mut builder = txn.dataflow_builder(compute_instance);
@@ -2533,8 +2925,10 @@ impl<S: Append + 'static> Coordinator<S> {
 
     async fn sequence_create_index(
         &mut self,
+        session: &Session,
         plan: CreateIndexPlan,
     ) -> Result<ExecuteResponse, CoordError> {
+        let owner_id = self.session_role_id(session)?;
         let CreateIndexPlan {
             name,
             index,
@@ -2560,9 +2954,10 @@ impl<S: Append + 'static> Coordinator<S> {
             oid,
             name,
             item: CatalogItem::Index(index),
+            owner_id,
         };
         match self
-            .catalog_transact(vec![op], |txn| {
+            .catalog_transact(Some(session), vec![op], |txn| {
                 let mut builder = txn.dataflow_builder(compute_instance);
                 let df = builder.build_index_dataflow(id)?;
                 Ok(df)
@@ -2586,8 +2981,10 @@ impl<S: Append + 'static> Coordinator<S> {
 
     async fn sequence_create_type(
         &mut self,
+        session: &Session,
         plan: CreateTypePlan,
     ) -> Result<ExecuteResponse, CoordError> {
+        let owner_id = self.session_role_id(session)?;
         let typ = catalog::Type {
             create_sql: plan.typ.create_sql,
             details: CatalogTypeDetails {
@@ -2603,8 +3000,12 @@ impl<S: Append + 'static> Coordinator<S> {
             oid,
             name: plan.name,
             item: CatalogItem::Type(typ),
+            owner_id,
         };
-        match self.catalog_transact(vec![op], |_| Ok(())).await {
+        match self
+            .catalog_transact(Some(session), vec![op], |_| Ok(()))
+            .await
+        {
             Ok(()) => Ok(ExecuteResponse::CreatedType),
             Err(err) => Err(err),
         }
@@ -2612,24 +3013,29 @@ impl<S: Append + 'static> Coordinator<S> {
 
     async fn sequence_drop_database(
         &mut self,
+        session: &Session,
         plan: DropDatabasePlan,
     ) -> Result<ExecuteResponse, CoordError> {
         let ops = self.catalog.drop_database_ops(plan.id);
-        self.catalog_transact(ops, |_| Ok(())).await?;
+        self.catalog_transact(Some(session), ops, |_| Ok(()))
+            .await?;
         Ok(ExecuteResponse::DroppedDatabase)
     }
 
     async fn sequence_drop_schema(
         &mut self,
+        session: &Session,
         plan: DropSchemaPlan,
     ) -> Result<ExecuteResponse, CoordError> {
         let ops = self.catalog.drop_schema_ops(plan.id);
-        self.catalog_transact(ops, |_| Ok(())).await?;
+        self.catalog_transact(Some(session), ops, |_| Ok(()))
+            .await?;
         Ok(ExecuteResponse::DroppedSchema)
     }
 
     async fn sequence_drop_roles(
         &mut self,
+        session: &Session,
         plan: DropRolesPlan,
     ) -> Result<ExecuteResponse, CoordError> {
         let ops = plan
@@ -2637,12 +3043,67 @@ impl<S: Append + 'static> Coordinator<S> {
             .into_iter()
             .map(|name| catalog::Op::DropRole { name })
             .collect();
-        self.catalog_transact(ops, |_| Ok(())).await?;
+        self.catalog_transact(Some(session), ops, |_| Ok(()))
+            .await?;
         Ok(ExecuteResponse::DroppedRole)
     }
 
+    async fn sequence_grant_privileges(
+        &mut self,
+        session: &Session,
+        plan: GrantPrivilegesPlan,
+    ) -> Result<ExecuteResponse, CoordError> {
+        let mut ops = vec![];
+        for object in &plan.objects {
+            for grantee_id in &plan.grantees {
+                for privilege in &plan.privileges {
+                    ops.push(catalog::Op::GrantPrivilege {
+                        object: *object,
+                        grantee_id: *grantee_id,
+                        privilege: *privilege,
+                    });
+                }
+            }
+        }
+        self.catalog_transact(Some(session), ops, |_| Ok(()))
+            .await?;
+        Ok(ExecuteResponse::GrantedPrivilege)
+    }
+
+    async fn sequence_revoke_privileges(
+        &mut self,
+        session: &Session,
+        plan: RevokePrivilegesPlan,
+    ) -> Result<ExecuteResponse, CoordError> {
+        let mut ops = vec![];
+        for object in &plan.objects {
+            for grantee_id in &plan.grantees {
+                for privilege in &plan.privileges {
+                    ops.push(catalog::Op::RevokePrivilege {
+                        object: *object,
+                        grantee_id: *grantee_id,
+                        privilege: *privilege,
+                    });
+                }
+            }
+        }
+        self.catalog_transact(Some(session), ops, |_| Ok(()))
+            .await?;
+        Ok(ExecuteResponse::RevokedPrivilege)
+    }
+
+    /// Returns the ID of the role that `session` is logged in as, which owns
+    /// any objects that the session creates.
+    fn session_role_id(&self, session: &Session) -> Result<i64, CoordError> {
+        self.catalog
+            .get_role(session.user())
+            .map(|role| role.id)
+            .ok_or_else(|| CoordError::UnknownLoginRole(session.user().into()))
+    }
+
     async fn sequence_drop_compute_instances(
         &mut self,
+        session: &Session,
         plan: DropComputeInstancesPlan,
     ) -> Result<ExecuteResponse, CoordError> {
         let mut ops = Vec::new();
@@ -2661,7 +3122,8 @@ impl<S: Append + 'static> Coordinator<S> {
             ops.push(catalog::Op::DropComputeInstance { name });
         }
 
-        self.catalog_transact(ops, |_| Ok(())).await?;
+        self.catalog_transact(Some(session), ops, |_| Ok(()))
+            .await?;
         for (instance_id, replicas) in instance_replica_drop_sets {
             for (replica_id, config) in replicas {
                 self.dataflow_client
@@ -2680,6 +3142,7 @@ impl<S: Append + 'static> Coordinator<S> {
 
     async fn sequence_drop_compute_instance_replica(
         &mut self,
+        session: &Session,
         DropComputeInstanceReplicaPlan { names }: DropComputeInstanceReplicaPlan,
     ) -> Result<ExecuteResponse, CoordError> {
         if names.is_empty() {
@@ -2702,7 +3165,8 @@ impl<S: Append + 'static> Coordinator<S> {
             ));
         }
 
-        self.catalog_transact(ops, |_| Ok(())).await?;
+        self.catalog_transact(Some(session), ops, |_| Ok(()))
+            .await?;
 
         for (compute_id, replica_id, config) in replicas_to_drop {
             self.dataflow_client
@@ -2716,10 +3180,12 @@ impl<S: Append + 'static> Coordinator<S> {
 
     async fn sequence_drop_items(
         &mut self,
+        session: &Session,
         plan: DropItemsPlan,
     ) -> Result<ExecuteResponse, CoordError> {
         let ops = self.catalog.drop_items_ops(&plan.items);
-        self.catalog_transact(ops, |_| Ok(())).await?;
+        self.catalog_transact(Some(session), ops, |_| Ok(()))
+            .await?;
         Ok(match plan.ty {
             ObjectType::Source => ExecuteResponse::DroppedSource,
             ObjectType::View => ExecuteResponse::DroppedView,
@@ -2777,6 +3243,32 @@ impl<S: Append + 'static> Coordinator<S> {
         Ok(ExecuteResponse::SetVariable { name: plan.name })
     }
 
+    fn sequence_set_transaction(
+        &self,
+        session: &mut Session,
+        plan: SetTransactionPlan,
+    ) -> Result<ExecuteResponse, CoordError> {
+        if plan.local {
+            // Like PostgreSQL, only permit changing the characteristics of the
+            // current transaction before it has executed any queries.
+            if let Some(txn) = session.transaction().inner() {
+                if !matches!(txn.ops, TransactionOps::None) {
+                    return Err(CoordError::TransactionModeAfterQuery);
+                }
+            }
+            if let Some(access) = plan.access {
+                session.set_transaction_access(access);
+            }
+        }
+        let name = "transaction_isolation";
+        if let Some(level) = plan.isolation_level {
+            session
+                .vars_mut()
+                .set(name, IsolationLevel::from(level).as_str(), plan.local)?;
+        }
+        Ok(ExecuteResponse::SetVariable { name: name.into() })
+    }
+
     async fn sequence_end_transaction(
         &mut self,
         tx: ClientTransmitter<ExecuteResponse>,
@@ -2986,6 +3478,47 @@ impl<S: Append + 'static> Coordinator<S> {
         &mut self,
         session: &mut Session,
         plan: PeekPlan,
+    ) -> Result<ExecuteResponse, CoordError> {
+        let mut plan = self.optimize_peek(plan)?;
+        self.sequence_optimized_peek(session, &mut plan).await
+    }
+
+    /// Optimizes the query of a peek, independently of the timestamp at which
+    /// the peek occurs.
+    fn optimize_peek(&mut self, plan: PeekPlan) -> Result<OptimizedPeekPlan, CoordError> {
+        let PeekPlan {
+            mut source,
+            when,
+            finishing,
+            copy_to,
+        } = plan;
+        // Dependencies are recorded before optimization, which could remove
+        // some of them, so that the peek's timestamp is chosen as it would be
+        // for the query as written.
+        let source_ids = source.depends_on();
+        let contains_temporal = source.contains_temporal();
+        let source = self.view_optimizer.optimize(source)?;
+        Ok(OptimizedPeekPlan {
+            source,
+            source_ids,
+            contains_temporal,
+            when,
+            finishing,
+            copy_to,
+            fast_path: None,
+        })
+    }
+
+    /// Sequences a peek whose query has been optimized by
+    /// [`Coordinator::optimize_peek`].
+    ///
+    /// If `plan` does not yet have a fast path, and the peek can be answered
+    /// by reading an existing index in a way that does not depend on the
+    /// peek's timestamp, the fast path is recorded in `plan` for reuse.
+    async fn sequence_optimized_peek(
+        &mut self,
+        session: &mut Session,
+        plan: &mut OptimizedPeekPlan,
     ) -> Result<ExecuteResponse, CoordError> {
         // TODO: remove this function when sources are linearizable.
         // See: #11048.
@@ -3016,12 +3549,7 @@ impl<S: Append + 'static> Coordinator<S> {
             }
         }
 
-        let PeekPlan {
-            mut source,
-            when,
-            finishing,
-            copy_to,
-        } = plan;
+        let when = plan.when.clone();
 
         let compute_instance = self
             .catalog
@@ -3035,7 +3563,7 @@ impl<S: Append + 'static> Coordinator<S> {
 
         let compute_instance = compute_instance.id;
 
-        let source_ids = source.depends_on();
+        let source_ids = plan.source_ids.clone();
 
         let timeline = self.validate_timeline(source_ids.clone())?;
         let conn_id = session.conn_id();
@@ -3052,7 +3580,7 @@ impl<S: Append + 'static> Coordinator<S> {
         let timestamp = if in_transaction && when == QueryWhen::Immediately {
             // Queries are independent of the logical timestamp iff there are no referenced
             // sources or indexes and there is no reference to `mz_logical_timestamp()`.
-            let timestamp_independent = source_ids.is_empty() && !source.contains_temporal();
+            let timestamp_independent = source_ids.is_empty() && !plan.contains_temporal;
 
             // If all previous statements were timestamp-independent and the current one is
             // not, clear the transaction ops so it can get a new timestamp and timedomain.
@@ -3155,8 +3683,68 @@ impl<S: Append + 'static> Coordinator<S> {
         // ------------------------------
         // after we have the timestamp \/
 
-        let source = self.view_optimizer.optimize(source)?;
+        let fast_path = match plan.fast_path.clone() {
+            Some(fast_path) => fast_path,
+            None => {
+                let fast_path =
+                    self.create_peek_plan(session, &plan.source, timestamp, compute_instance)?;
+                // A plan that reads an existing index remains valid for as long as the
+                // catalog is unchanged, unless the query calls unmaterializable functions,
+                // whose values at `timestamp` were substituted into the plan.
+                if matches!(fast_path, fast_path_peek::Plan::PeekExisting(..))
+                    && !plan_cache::calls_unmaterializable(&mut plan.source)
+                {
+                    plan.fast_path = Some(fast_path.clone());
+                }
+                fast_path
+            }
+        };
+        let plan_kind = match &fast_path {
+            fast_path_peek::Plan::PeekDataflow(_) => PlanKind::Dataflow,
+            fast_path_peek::Plan::Constant(_) | fast_path_peek::Plan::PeekExisting(..) => {
+                PlanKind::FastPath
+            }
+        };
+        self.statement_log_record_peek(conn_id, timestamp, plan_kind);
+        if matches!(
+            fast_path,
+            fast_path_peek::Plan::PeekDataflow(_) | fast_path_peek::Plan::PeekExisting(_, None, _)
+        ) {
+            self.index_advisor_record_peek(session.user(), compute_instance, &plan.source.0)
+                .await;
+        }
 
+        // Implement the peek, and capture the response.
+        let resp = self
+            .implement_fast_path_peek(
+                fast_path,
+                timestamp,
+                plan.finishing.clone(),
+                conn_id,
+                plan.source.arity(),
+                compute_instance,
+                false,
+            )
+            .await?;
+
+        match plan.copy_to {
+            None => Ok(resp),
+            Some(format) => Ok(ExecuteResponse::CopyTo {
+                format,
+                resp: Box::new(resp),
+            }),
+        }
+    }
+
+    /// Determines the most efficient way to evaluate `source` at `timestamp`
+    /// on `compute_instance`.
+    fn create_peek_plan(
+        &mut self,
+        session: &Session,
+        source: &OptimizedMirRelationExpr,
+        timestamp: Timestamp,
+        compute_instance: ComputeInstanceId,
+    ) -> Result<fast_path_peek::Plan, CoordError> {
         // We create a dataflow and optimize it, to determine if we can avoid building it.
         // This can happen if the result optimizes to a constant, or to a `Get` expression
         // around a maintained arrangement.
@@ -3175,7 +3763,7 @@ impl<S: Append + 'static> Coordinator<S> {
         let mut dataflow = DataflowDesc::new(format!("temp-view-{}", view_id));
         dataflow.set_as_of(Antichain::from_elem(timestamp));
         let mut builder = self.dataflow_builder(compute_instance);
-        builder.import_view_into_dataflow(&view_id, &source, &mut dataflow)?;
+        builder.import_view_into_dataflow(&view_id, source, &mut dataflow)?;
         for BuildDesc { plan, .. } in &mut dataflow.objects_to_build {
             prep_relation_expr(
                 self.catalog.state(),
@@ -3203,34 +3791,14 @@ impl<S: Append + 'static> Coordinator<S> {
 
         // At this point, `dataflow_plan` contains our best optimized dataflow.
         // We will check the plan to see if there is a fast path to escape full dataflow construction.
-        let fast_path = fast_path_peek::create_plan(
+        fast_path_peek::create_plan(
             dataflow_plan,
             view_id,
             index_id,
             key,
             permutation,
             thinning.len(),
-        )?;
-
-        // Implement the peek, and capture the response.
-        let resp = self
-            .implement_fast_path_peek(
-                fast_path,
-                timestamp,
-                finishing,
-                conn_id,
-                source.arity(),
-                compute_instance,
-            )
-            .await?;
-
-        match copy_to {
-            None => Ok(resp),
-            Some(format) => Ok(ExecuteResponse::CopyTo {
-                format,
-                resp: Box::new(resp),
-            }),
-        }
+        )
     }
 
     async fn sequence_tail(
@@ -3318,9 +3886,20 @@ impl<S: Append + 'static> Coordinator<S> {
         let (sink_id, sink_desc) = dataflow.sink_exports.iter().next().unwrap();
         session.add_drop_sink(compute_instance, *sink_id);
         let arity = sink_desc.from_desc.arity();
-        let (tx, rx) = mpsc::unbounded_channel();
+        // The updates of a tail cannot wait on the client, so they accumulate
+        // ahead of the client's stream.
+        let (tx, mut updates_rx) = mpsc::unbounded_channel();
+        let (rows_tx, rx) = row_batch_channel();
+        let sink_id = *sink_id;
+        task::spawn(|| format!("tail_rows:{sink_id}"), async move {
+            while let Some(rows) = updates_rx.recv().await {
+                if rows_tx.send(rows).await.is_err() {
+                    return;
+                }
+            }
+        });
         self.pending_tails
-            .insert(*sink_id, PendingTail::new(tx, emit_progress, arity));
+            .insert(sink_id, PendingTail::new(tx, emit_progress, arity));
         self.ship_dataflow(dataflow, compute_instance).await;
 
         let resp = ExecuteResponse::Tailing { rx };
@@ -3459,11 +4038,27 @@ impl<S: Append + 'static> Coordinator<S> {
         if when.advance_to_since() {
             candidate.advance_by(since.borrow());
         }
+        // Reads are linearized by advancing them to the global timeline's read
+        // timestamp, which ensures that they observe all writes that completed
+        // before they began and never observe an earlier time than a prior
+        // linearized read. Reads of tables are always linearized, so that a
+        // session observes its own writes. Other reads are linearized only
+        // under strict serializable isolation; under serializable isolation
+        // they are instead served at the latest time at which their inputs are
+        // complete, which never needs to wait for the inputs to catch up.
+        //
+        // The global timeline only orders reads in the epoch milliseconds
+        // timeline. Timestamps in other timelines, like those of CDCv2 sources
+        // or sources with a user-specified timeline, bear no relation to it, so
+        // reads in them are never linearized.
         let uses_tables = id_bundle.iter().any(|id| self.catalog.uses_tables(id));
-        if when.advance_to_table_ts(uses_tables) {
+        let linearized = uses_tables
+            || (*session.vars().transaction_isolation() == IsolationLevel::StrictSerializable
+                && self.validate_timeline(id_bundle.iter())? == Some(Timeline::EpochMilliseconds));
+        if when.advance_to_timeline_ts(linearized) {
             candidate.join_assign(&self.get_local_read_ts());
         }
-        if when.advance_to_upper(uses_tables) {
+        if when.advance_to_upper(linearized) {
             let upper = self.least_valid_write(&id_bundle, compute_instance);
 
             // We peek at the largest element not in advance of `upper`, which
@@ -3549,8 +4144,15 @@ impl<S: Append + 'static> Coordinator<S> {
             row_set_finishing,
             stage,
             options,
+            format,
+            view_id: _,
         } = plan;
 
+        enum Explained {
+            Text(String),
+            Json(serde_json::Value),
+        }
+
         struct Timings {
             decorrelation: Option<Duration>,
             optimization: Option<Duration>,
@@ -3567,6 +4169,7 @@ impl<S: Append + 'static> Coordinator<S> {
             let start = Instant::now();
             let decorrelated_plan = raw_plan.optimize_and_lower(&OptimizerConfig {
                 qgm_optimizations: session.vars().qgm_optimizations(),
+                native_outer_joins: session.vars().native_outer_joins(),
             })?;
             timings.decorrelation = Some(start.elapsed());
             Ok(decorrelated_plan)
@@ -3596,28 +4199,46 @@ impl<S: Append + 'static> Coordinator<S> {
                 Ok(dataflow)
             };
 
-        let mut explanation_string = match stage {
+        let explained = match stage {
             ExplainStage::RawPlan => {
                 let catalog = self.catalog.for_session(session);
-                let mut explanation = mz_sql::plan::Explanation::new(&raw_plan, &catalog);
-                if let Some(row_set_finishing) = row_set_finishing {
-                    explanation.explain_row_set_finishing(row_set_finishing);
-                }
-                if options.typed {
-                    explanation.explain_types(&BTreeMap::new());
+                match format {
+                    ExplainFormat::Text => {
+                        let mut explanation = mz_sql::plan::Explanation::new(&raw_plan, &catalog);
+                        if let Some(row_set_finishing) = row_set_finishing {
+                            explanation.explain_row_set_finishing(row_set_finishing);
+                        }
+                        if options.typed {
+                            explanation.explain_types(&BTreeMap::new());
+                        }
+                        Explained::Text(explanation.to_string())
+                    }
+                    ExplainFormat::Json => {
+                        let formatter = mz_dataflow_types::JsonViewFormatter {};
+                        let mut explanation =
+                            mz_dataflow_types::Explanation::new(&raw_plan, &catalog, &formatter);
+                        if let Some(row_set_finishing) = row_set_finishing {
+                            explanation.explain_row_set_finishing(row_set_finishing);
+                        }
+                        let mut json = explanation.to_json();
+                        if options.typed {
+                            json.explain_types(|_, expr| Some(expr.typ(&[], &BTreeMap::new())));
+                        }
+                        Explained::Json(serde_json::to_value(&json)?)
+                    }
                 }
-                explanation.to_string()
             }
+            // JSON query graphs are rejected during planning.
             ExplainStage::QueryGraph => {
                 let catalog = self.catalog.for_session(session);
                 let mut model = mz_sql::query_model::Model::try_from(raw_plan)?;
-                model.as_dot("", &catalog, options.typed)?
+                Explained::Text(model.as_dot("", &catalog, options.typed)?)
             }
             ExplainStage::OptimizedQueryGraph => {
                 let catalog = self.catalog.for_session(session);
                 let mut model = mz_sql::query_model::Model::try_from(raw_plan)?;
                 model.optimize();
-                model.as_dot("", &catalog, options.typed)?
+                Explained::Text(model.as_dot("", &catalog, options.typed)?)
             }
             ExplainStage::DecorrelatedPlan => {
                 let decorrelated_plan = OptimizedMirRelationExpr::declare_optimized(decorrelate(
@@ -3632,7 +4253,16 @@ impl<S: Append + 'static> Coordinator<S> {
                 if let Some(row_set_finishing) = row_set_finishing {
                     explanation.explain_row_set_finishing(row_set_finishing);
                 }
-                explanation.to_string()
+                match format {
+                    ExplainFormat::Text => Explained::Text(explanation.to_string()),
+                    ExplainFormat::Json => {
+                        let mut json = explanation.to_json();
+                        if options.typed {
+                            json.explain_types(|_, expr| Some(expr.typ()));
+                        }
+                        Explained::Json(serde_json::to_value(&json)?)
+                    }
+                }
             }
             ExplainStage::OptimizedPlan => {
                 let decorrelated_plan = decorrelate(&mut timings, raw_plan)?;
@@ -3647,12 +4277,28 @@ impl<S: Append + 'static> Coordinator<S> {
                 if let Some(row_set_finishing) = row_set_finishing {
                     explanation.explain_row_set_finishing(row_set_finishing);
                 }
-                explanation.to_string()
+                match format {
+                    ExplainFormat::Text => Explained::Text(explanation.to_string()),
+                    ExplainFormat::Json => {
+                        let mut json = explanation.to_json();
+                        if options.typed {
+                            json.explain_types(|_, expr| Some(expr.typ()));
+                        }
+                        Explained::Json(serde_json::to_value(&json)?)
+                    }
+                }
             }
             ExplainStage::PhysicalPlan => {
                 let decorrelated_plan = decorrelate(&mut timings, raw_plan)?;
                 self.validate_timeline(decorrelated_plan.depends_on())?;
                 let dataflow = optimize(&mut timings, self, decorrelated_plan)?;
+                // Physical plans do not know their types, so record the types
+                // of the optimized plans they are derived from.
+                let types: HashMap<_, _> = dataflow
+                    .objects_to_build
+                    .iter()
+                    .map(|build_desc| (build_desc.id, build_desc.plan.typ()))
+                    .collect();
                 let dataflow_plan =
                     mz_dataflow_types::Plan::<mz_repr::Timestamp>::finalize_dataflow(dataflow)
                         .expect("Dataflow planning failed; unrecoverable error");
@@ -3665,7 +4311,16 @@ impl<S: Append + 'static> Coordinator<S> {
                 if let Some(row_set_finishing) = row_set_finishing {
                     explanation.explain_row_set_finishing(row_set_finishing);
                 }
-                explanation.to_string()
+                match format {
+                    ExplainFormat::Text => Explained::Text(explanation.to_string()),
+                    ExplainFormat::Json => {
+                        let mut json = explanation.to_json();
+                        if options.typed {
+                            json.explain_types(|id, _| types.get(&id).cloned());
+                        }
+                        Explained::Json(serde_json::to_value(&json)?)
+                    }
+                }
             }
             ExplainStage::Timestamp => {
                 let decorrelated_plan = decorrelate(&mut timings, raw_plan)?;
@@ -3759,38 +4414,283 @@ impl<S: Append + 'static> Coordinator<S> {
                     table_read_ts,
                     sources,
                 };
-                explanation.to_string()
+                match format {
+                    ExplainFormat::Text => Explained::Text(explanation.to_string()),
+                    ExplainFormat::Json => Explained::Json(serde_json::to_value(&explanation)?),
+                }
             }
+            ExplainStage::Analyze => unreachable!("EXPLAIN ANALYZE is sequenced separately"),
         };
-        if options.timing {
-            if let Some(decorrelation) = &timings.decorrelation {
-                explanation_string.push_str(&format!(
-                    "\nDecorrelation time: {}",
-                    Interval {
-                        months: 0,
-                        days: 0,
-                        micros: decorrelation.as_micros().try_into().unwrap(),
+        let explanation_string = match explained {
+            Explained::Text(mut explanation_string) => {
+                if options.timing {
+                    if let Some(decorrelation) = &timings.decorrelation {
+                        explanation_string.push_str(&format!(
+                            "\nDecorrelation time: {}",
+                            Interval {
+                                months: 0,
+                                days: 0,
+                                micros: decorrelation.as_micros().try_into().unwrap(),
+                            }
+                        ));
                     }
-                ));
-            }
-            if let Some(optimization) = &timings.optimization {
-                explanation_string.push_str(&format!(
-                    "\nOptimization time: {}",
-                    Interval {
-                        months: 0,
-                        days: 0,
-                        micros: optimization.as_micros().try_into().unwrap(),
+                    if let Some(optimization) = &timings.optimization {
+                        explanation_string.push_str(&format!(
+                            "\nOptimization time: {}",
+                            Interval {
+                                months: 0,
+                                days: 0,
+                                micros: optimization.as_micros().try_into().unwrap(),
+                            }
+                        ));
                     }
-                ));
-            }
-            if timings.decorrelation.is_some() || timings.optimization.is_some() {
-                explanation_string.push_str("\n");
+                    if timings.decorrelation.is_some() || timings.optimization.is_some() {
+                        explanation_string.push_str("\n");
+                    }
+                }
+                explanation_string
+            }
+            Explained::Json(mut explanation) => {
+                if options.timing {
+                    // Timings are reported in microseconds, under a "timing"
+                    // key that maps each phase that ran to its duration.
+                    let mut timing = serde_json::Map::new();
+                    if let Some(decorrelation) = &timings.decorrelation {
+                        timing.insert(
+                            "decorrelation_us".into(),
+                            u64::try_from(decorrelation.as_micros())?.into(),
+                        );
+                    }
+                    if let Some(optimization) = &timings.optimization {
+                        timing.insert(
+                            "optimization_us".into(),
+                            u64::try_from(optimization.as_micros())?.into(),
+                        );
+                    }
+                    if let Some(explanation) = explanation.as_object_mut() {
+                        explanation.insert("timing".into(), timing.into());
+                    }
+                }
+                serde_json::to_string_pretty(&explanation)?
             }
-        }
+        };
         let rows = vec![Row::pack_slice(&[Datum::from(&*explanation_string)])];
         Ok(send_immediate_rows(rows))
     }
 
+    /// Runs the dataflow for the query or view of `plan`, and explains its plan
+    /// annotated with the statistics gathered while running it.
+    ///
+    /// Queries are rendered as a transient dataflow, like a `SELECT` that has no
+    /// fast path. Views are analyzed by inspecting the dataflow that maintains
+    /// one of their indexes.
+    async fn sequence_explain_analyze(
+        &mut self,
+        session: &Session,
+        plan: ExplainPlan,
+    ) -> Result<ExecuteResponse, CoordError> {
+        let compute_instance = self
+            .catalog
+            .resolve_compute_instance(session.vars().cluster())?;
+        if compute_instance.replicas_by_id.is_empty() {
+            return Err(CoordError::NoClusterReplicasAvailable(
+                compute_instance.name.clone(),
+            ));
+        }
+        let compute_instance = compute_instance.id;
+        let conn_id = session.conn_id();
+        let format = plan.format;
+
+        let (fast_path, timestamp, arity, objects) = match plan.view_id {
+            Some(view_id) => {
+                let index_id = match self
+                    .index_oracle(compute_instance)
+                    .indexes_on(view_id)
+                    .next()
+                {
+                    Some((index_id, _)) => index_id,
+                    None => {
+                        let name = self.catalog.get_entry(&view_id).name();
+                        return Err(CoordError::Unstructured(anyhow!(
+                            "cannot analyze {} because it has no index in cluster {}",
+                            self.catalog
+                                .resolve_full_name(name, Some(conn_id))
+                                .to_string()
+                                .quoted(),
+                            session.vars().cluster().quoted(),
+                        )));
+                    }
+                };
+                let id_bundle = self
+                    .index_oracle(compute_instance)
+                    .sufficient_collections(&[view_id]);
+                let timestamp = self.determine_timestamp(
+                    session,
+                    &id_bundle,
+                    QueryWhen::Immediately,
+                    compute_instance,
+                )?;
+                let dataflow = self
+                    .dataflow_builder(compute_instance)
+                    .build_index_dataflow(index_id)?;
+                let objects = Self::explained_objects(&dataflow);
+                // The map-filter-project is ignored by analyzing peeks.
+                let map_filter_project = mz_expr::MapFilterProject::new(0)
+                    .into_plan()
+                    .unwrap()
+                    .into_nontemporal()
+                    .unwrap();
+                let fast_path =
+                    fast_path_peek::Plan::PeekExisting(index_id, None, map_filter_project);
+                (fast_path, timestamp, 0, objects)
+            }
+            None => {
+                let decorrelated_plan = plan.raw_plan.optimize_and_lower(&OptimizerConfig {
+                    qgm_optimizations: session.vars().qgm_optimizations(),
+                    native_outer_joins: session.vars().native_outer_joins(),
+                })?;
+                let source = self.view_optimizer.optimize(decorrelated_plan)?;
+                let source_ids = source.depends_on();
+                self.validate_timeline(source_ids.clone())?;
+                let id_bundle = self
+                    .index_oracle(compute_instance)
+                    .sufficient_collections(&source_ids);
+                let timestamp = self.determine_timestamp(
+                    session,
+                    &id_bundle,
+                    QueryWhen::Immediately,
+                    compute_instance,
+                )?;
+
+                // Assemble a dataflow containing the query and an index of it,
+                // as `sequence_peek` does.
+                let typ = source.typ();
+                let key: Vec<MirScalarExpr> = typ
+                    .default_key()
+                    .iter()
+                    .map(|k| MirScalarExpr::Column(*k))
+                    .collect();
+                let (permutation, thinning) = permutation_for_arrangement(&key, typ.arity());
+                let view_id = self.allocate_transient_id()?;
+                let index_id = self.allocate_transient_id()?;
+                let mut dataflow = DataflowDesc::new(format!("temp-view-{}", view_id));
+                dataflow.set_as_of(Antichain::from_elem(timestamp));
+                let mut builder = self.dataflow_builder(compute_instance);
+                builder.import_view_into_dataflow(&view_id, &source, &mut dataflow)?;
+                for BuildDesc { plan, .. } in &mut dataflow.objects_to_build {
+                    prep_relation_expr(
+                        self.catalog.state(),
+                        plan,
+                        ExprPrepStyle::OneShot {
+                            logical_time: Some(timestamp),
+                            session,
+                        },
+                    )?;
+                }
+                dataflow.export_index(
+                    index_id,
+                    IndexDesc {
+                        on_id: view_id,
+                        key: key.clone(),
+                    },
+                    typ,
+                );
+                mz_transform::optimize_dataflow(&mut dataflow, &builder.index_oracle())?;
+                let objects = Self::explained_objects(&dataflow);
+                let dataflow_plan = self.finalize_dataflow(dataflow, compute_instance);
+
+                // Always build the dataflow, even if the query has a fast path,
+                // as there is otherwise nothing to analyze.
+                let fast_path = fast_path_peek::create_dataflow_plan(
+                    dataflow_plan,
+                    index_id,
+                    key,
+                    permutation,
+                    thinning.len(),
+                );
+                (fast_path, timestamp, source.arity(), objects)
+            }
+        };
+
+        let (objects, indexes) = objects;
+        let catalog = self.catalog.for_session(session);
+        let names: HashMap<_, _> = objects
+            .into_iter()
+            .filter_map(|id| catalog.humanize_id(id).map(|name| (id, name)))
+            .collect();
+
+        let finishing = RowSetFinishing {
+            order_by: vec![],
+            limit: None,
+            offset: 0,
+            project: (0..PlanNodeStatistics::ARITY).collect(),
+        };
+        let resp = self
+            .implement_fast_path_peek(
+                fast_path,
+                timestamp,
+                finishing,
+                conn_id,
+                arity,
+                compute_instance,
+                true,
+            )
+            .await?;
+        let rows = match resp {
+            ExecuteResponse::SendingRows(rows) => rows,
+            _ => unreachable!("peeks send rows"),
+        };
+        // The explanation describes the statistics of all plan nodes at once.
+        let (tx, rx) = row_batch_channel();
+        task::spawn(|| "explain_analyze", async move {
+            let resp = match collect_row_batches(rows).await {
+                PeekResponseUnary::Rows(rows) => {
+                    let statistics = rows
+                        .iter()
+                        .map(PlanNodeStatistics::from_row)
+                        .collect::<Result<Vec<_>, _>>();
+                    let explanation = statistics.and_then(|statistics| {
+                        let explanation = AnalyzedPlanExplanation::new(statistics, names, indexes);
+                        match format {
+                            ExplainFormat::Text => Ok(explanation.to_string()),
+                            ExplainFormat::Json => serde_json::to_string_pretty(&explanation)
+                                .map_err(|e| e.to_string()),
+                        }
+                    });
+                    match explanation {
+                        Ok(explanation) => {
+                            PeekResponseUnary::Rows(vec![Row::pack_slice(&[Datum::from(
+                                &*explanation,
+                            )])])
+                        }
+                        Err(e) => PeekResponseUnary::Error(e),
+                    }
+                }
+                resp => resp,
+            };
+            // The receiver may have hung up, if the client went away.
+            let _ = tx.send(resp).await;
+        });
+        Ok(ExecuteResponse::SendingRows(rx))
+    }
+
+    /// Returns the objects that an explanation of `dataflow` names, which are
+    /// the objects it builds and the collections it reads, along with the
+    /// indexes it exports.
+    fn explained_objects<P>(
+        dataflow: &DataflowDescription<P>,
+    ) -> (Vec<GlobalId>, BTreeSet<GlobalId>) {
+        let objects = dataflow
+            .source_imports
+            .keys()
+            .copied()
+            .chain(dataflow.index_imports.values().map(|(desc, _)| desc.on_id))
+            .chain(dataflow.objects_to_build.iter().map(|object| object.id))
+            .collect();
+        let indexes = dataflow.index_exports.keys().copied().collect();
+        (objects, indexes)
+    }
+
     fn sequence_send_diffs(
         &mut self,
         session: &mut Session,
@@ -4090,7 +4990,7 @@ impl<S: Append + 'static> Coordinator<S> {
         task::spawn(|| format!("sequence_read_then_write:{id}"), async move {
             let arena = RowArena::new();
             let diffs = match peek_response {
-                ExecuteResponse::SendingRows(batch) => match batch.await {
+                ExecuteResponse::SendingRows(batches) => match collect_row_batches(batches).await {
                     PeekResponseUnary::Rows(rows) => {
                         |rows: Vec<Row>| -> Result<Vec<(Row, Diff)>, CoordError> {
                             // Use 2x row len incase there's some assignments.
@@ -4154,6 +5054,7 @@ impl<S: Append + 'static> Coordinator<S> {
 
     async fn sequence_alter_item_rename(
         &mut self,
+        session: &Session,
         plan: AlterItemRenamePlan,
     ) -> Result<ExecuteResponse, CoordError> {
         let op = catalog::Op::RenameItem {
@@ -4161,7 +5062,10 @@ impl<S: Append + 'static> Coordinator<S> {
             current_full_name: plan.current_full_name,
             to_name: plan.to_name,
         };
-        match self.catalog_transact(vec![op], |_| Ok(())).await {
+        match self
+            .catalog_transact(Some(session), vec![op], |_| Ok(()))
+            .await
+        {
             Ok(()) => Ok(ExecuteResponse::AlteredObject(plan.object_type)),
             Err(err) => Err(err),
         }
@@ -4253,7 +5157,12 @@ impl<S: Append + 'static> Coordinator<S> {
     /// function successfully returns on any built `DataflowDesc`.
     ///
     /// [`CatalogState`]: crate::catalog::CatalogState
-    async fn catalog_transact<F, R>(&mut self, ops: Vec<catalog::Op>, f: F) -> Result<R, CoordError>
+    async fn catalog_transact<F, R>(
+        &mut self,
+        session: Option<&Session>,
+        ops: Vec<catalog::Op>,
+        f: F,
+    ) -> Result<R, CoordError>
     where
         F: FnOnce(CatalogTxn<Timestamp>) -> Result<R, CoordError>,
     {
@@ -4308,15 +5217,18 @@ impl<S: Append + 'static> Coordinator<S> {
             }
         }
 
-        let (builtin_table_updates, result) = self
+        let (mut builtin_table_updates, result) = self
             .catalog
-            .transact(ops, |catalog| {
+            .transact(session, ops, |catalog| {
                 f(CatalogTxn {
                     dataflow_client: &self.dataflow_client,
                     catalog,
                 })
             })
             .await?;
+        // The new catalog can retire advice about the indexes it created or
+        // the objects it dropped.
+        builtin_table_updates.extend(self.index_advisor_updates());
 
         // No error returns are allowed after this point. Enforce this at compile time
         // by using this odd structure so we don't accidentally add a stray `?`.
@@ -4680,9 +5592,22 @@ impl<S: Append + 'static> Coordinator<S> {
         plan: Plan,
     ) {
         let conn_id = session.conn_id();
+        let lock_timeout = session.vars().lock_timeout();
         let plan = DeferredPlan { tx, session, plan };
         self.write_lock_wait_group.push_back(plan);
 
+        if !lock_timeout.is_zero() {
+            if let Some(conn_meta) = self.active_conns.get(&conn_id) {
+                self.schedule_message(
+                    lock_timeout,
+                    Message::LockTimeout {
+                        conn_id,
+                        statement_id: conn_meta.statement_id,
+                    },
+                );
+            }
+        }
+
         let internal_cmd_tx = self.internal_cmd_tx.clone();
         let write_lock = Arc::clone(&self.write_lock);
         // TODO(guswynn): see if there is more relevant info to add to this name
@@ -4716,6 +5641,8 @@ pub async fn serve<S: Append + 'static>(
         secrets_controller,
         replica_sizes,
         availability_zones,
+        statement_log,
+        plan_cache,
     }: Config<S>,
 ) -> Result<(Handle, Client), CoordError> {
     let (cmd_tx, cmd_rx) = mpsc::unbounded_channel();
@@ -4734,7 +5661,9 @@ pub async fn serve<S: Append + 'static>(
     .await?;
     let cluster_id = catalog.config().cluster_id;
     let session_id = catalog.config().session_id;
+    let plan_cache_metrics = PlanCacheMetrics::register_with(&metrics_registry);
     let start_instant = catalog.config().start_instant;
+    let replication_slots = ReplicationSlots::new(catalog.load_replication_slots().await?);
 
     // In order for the coordinator to support Rc and Refcell types, it cannot be
     // sent across threads. Spawn it in a thread and have this parent thread wait
@@ -4765,6 +5694,12 @@ pub async fn serve<S: Append + 'static>(
                 secrets_controller,
                 replica_sizes,
                 availability_zones,
+                statement_log: StatementLog::new(statement_log),
+                plan_cache_config: plan_cache,
+                global_plan_cache: PlanCache::default(),
+                plan_cache_metrics,
+                index_advisor: IndexAdvisor::default(),
+                replication_slots,
             };
             let bootstrap = handle.block_on(coord.bootstrap(builtin_table_updates));
             let ok = bootstrap.is_ok();
@@ -4789,11 +5724,69 @@ pub async fn serve<S: Append + 'static>(
     }
 }
 
+/// Finishes the rows of a peek as its responses arrive, and sends them on in
+/// batches.
+///
+/// Unless the finishing orders the rows, they are sent on as they arrive, and
+/// otherwise all at once when the final response arrives. Each batch of rows
+/// is reported as consumed through `internal_cmd_tx` only once it has been
+/// sent on, so that the peek produces rows no faster than the receiver of the
+/// batches consumes them. Should the finishing need no further rows, or the
+/// receiver of the batches hang up, the responses are no longer received,
+/// which cancels the peek.
+async fn finish_peek_responses(
+    uuid: Uuid,
+    mut responses: mpsc::Receiver<PeekResponse>,
+    finishing: RowSetFinishing,
+    batches: mpsc::Sender<PeekResponseUnary>,
+    internal_cmd_tx: mpsc::UnboundedSender<Message>,
+) {
+    let mut finishing = IncrementalFinishing::new(finishing);
+    while let Some(response) = responses.recv().await {
+        let batch = match response {
+            PeekResponse::Batch(rows) => finishing.push(rows),
+            PeekResponse::Rows(rows) => {
+                let mut rows = finishing.push(rows);
+                rows.extend(finishing.finish());
+                // The receiver may have hung up, if the client went away.
+                let _ = batches.send(PeekResponseUnary::Rows(rows)).await;
+                return;
+            }
+            PeekResponse::Error(e) => {
+                let _ = batches.send(PeekResponseUnary::Error(e)).await;
+                return;
+            }
+            PeekResponse::Canceled => {
+                let _ = batches.send(PeekResponseUnary::Canceled).await;
+                return;
+            }
+        };
+        let done = if batch.is_empty() {
+            finishing.is_done()
+        } else {
+            let batch = PeekResponseUnary::Rows(batch);
+            batches.send(batch).await.is_err() || finishing.is_done()
+        };
+        if done {
+            // The coordinator cancels the peek once it learns that nothing
+            // receives its responses any longer.
+            responses.close();
+        }
+        let consumed = Message::PeekBatchConsumed(uuid);
+        if internal_cmd_tx.send(consumed).is_err() || done {
+            return;
+        }
+    }
+}
+
 /// Constructs an [`ExecuteResponse`] that that will send some rows to the
 /// client immediately, as opposed to asking the dataflow layer to send along
 /// the rows after some computation.
 fn send_immediate_rows(rows: Vec<Row>) -> ExecuteResponse {
-    ExecuteResponse::SendingRows(Box::pin(async { PeekResponseUnary::Rows(rows) }))
+    let (tx, rx) = row_batch_channel();
+    tx.try_send(PeekResponseUnary::Rows(rows))
+        .expect("send must succeed");
+    ExecuteResponse::SendingRows(rx)
 }
 
 fn auto_generate_primary_idx(
@@ -4911,16 +5904,22 @@ pub fn describe<S: Append>(
 pub mod fast_path_peek {
 
     use mz_dataflow_types::client::ComputeInstanceId;
+    use mz_ore::task;
     use mz_stash::Append;
-    use std::{collections::HashMap, num::NonZeroUsize};
+    use std::{
+        collections::{HashMap, VecDeque},
+        num::NonZeroUsize,
+    };
+    use tokio::sync::mpsc;
     use uuid::Uuid;
 
-    use crate::coord::{PeekResponseUnary, PendingPeek};
+    use crate::coord::{finish_peek_responses, PeekResponseUnary, PendingPeek};
+    use crate::session::row_batch_channel;
     use crate::CoordError;
     use mz_expr::{EvalError, Id, MirScalarExpr};
     use mz_repr::{Diff, GlobalId, Row};
 
-    #[derive(Debug)]
+    #[derive(Clone, Debug)]
     pub struct PeekDataflowPlan<T> {
         desc: mz_dataflow_types::DataflowDescription<mz_dataflow_types::Plan<T>, (), T>,
         id: GlobalId,
@@ -4930,21 +5929,29 @@ pub mod fast_path_peek {
     }
 
     /// Possible ways in which the coordinator could produce the result for a goal view.
-    #[derive(Debug)]
+    #[derive(Clone, Debug)]
     pub enum Plan<T = mz_repr::Timestamp> {
         /// The view evaluates to a constant result that can be returned.
         Constant(Result<Vec<(Row, T, Diff)>, EvalError>),
-        /// The view can be read out of an existing arrangement.
-        PeekExisting(GlobalId, Option<Row>, mz_expr::SafeMfpPlan),
+        /// The view can be read out of an existing arrangement, optionally
+        /// restricted to the keys in a set of literal constraints.
+        PeekExisting(GlobalId, Option<Vec<Row>>, mz_expr::SafeMfpPlan),
         /// The view must be installed as a dataflow and then read.
         PeekDataflow(PeekDataflowPlan<T>),
     }
 
+    /// The maximum number of keys that a peek of an existing arrangement will
+    /// look up individually, rather than scanning the whole arrangement.
+    const MAX_LITERAL_CONSTRAINTS: usize = 1024;
+
     /// Determine if the dataflow plan can be implemented without an actual dataflow.
     ///
     /// If the optimized plan is a `Constant` or a `Get` of a maintained arrangement,
     /// we can avoid building a dataflow (and either just return the results, or peek
-    /// out of the arrangement, respectively).
+    /// out of the arrangement, respectively). In the latter case, if the filters of
+    /// the plan constrain the key of the arrangement to a small set of literals,
+    /// as equalities, `IN` lists and bounded integer ranges do, only those keys are
+    /// read out of the arrangement.
     pub fn create_plan(
         dataflow_plan: mz_dataflow_types::DataflowDescription<mz_dataflow_types::Plan>,
         view_id: GlobalId,
@@ -5009,12 +6016,36 @@ pub mod fast_path_peek {
                                 })?;
                             // We should only get excited if we can track down an index for `id`.
                             // If `keys` is non-empty, that means we think one exists.
-                            for (index_id, (desc, _typ)) in dataflow_plan.index_imports.iter() {
+                            for (index_id, (desc, typ)) in dataflow_plan.index_imports.iter() {
                                 if Id::Global(desc.on_id) == *id && &desc.key == key {
+                                    let literal_constraints = match val {
+                                        Some(val) => Some(vec![val.clone()]),
+                                        None => {
+                                            // `mfp` has been permuted to read from the
+                                            // arrangement, so the key must be too.
+                                            let (permutation, _thinning): (HashMap<_, _>, _) =
+                                                mz_expr::permutation_for_arrangement(
+                                                    key,
+                                                    typ.arity(),
+                                                );
+                                            let key: Vec<_> = key
+                                                .iter()
+                                                .map(|expr| {
+                                                    let mut expr = expr.clone();
+                                                    expr.permute_map(&permutation);
+                                                    expr
+                                                })
+                                                .collect();
+                                            mfp.literal_constraint_rows(
+                                                &key,
+                                                MAX_LITERAL_CONSTRAINTS,
+                                            )
+                                        }
+                                    };
                                     // Indicate an early exit with a specific index and key_val.
                                     return Ok(Plan::PeekExisting(
                                         *index_id,
-                                        val.clone(),
+                                        literal_constraints,
                                         map_filter_project,
                                     ));
                                 }
@@ -5029,17 +6060,40 @@ pub mod fast_path_peek {
                 _ => {}
             }
         }
-        return Ok(Plan::PeekDataflow(PeekDataflowPlan {
+        Ok(create_dataflow_plan(
+            dataflow_plan,
+            index_id,
+            index_key,
+            index_permutation,
+            index_thinned_arity,
+        ))
+    }
+
+    /// Plans to build the dataflow and read its results from the index `index_id`,
+    /// without looking for a fast path.
+    pub fn create_dataflow_plan(
+        dataflow_plan: mz_dataflow_types::DataflowDescription<mz_dataflow_types::Plan>,
+        index_id: GlobalId,
+        index_key: Vec<MirScalarExpr>,
+        index_permutation: HashMap<usize, usize>,
+        index_thinned_arity: usize,
+    ) -> Plan {
+        Plan::PeekDataflow(PeekDataflowPlan {
             desc: dataflow_plan,
             id: index_id,
             key: index_key,
             permutation: index_permutation,
             thinned_arity: index_thinned_arity,
-        }));
+        })
     }
 
     impl<S: Append + 'static> crate::coord::Coordinator<S> {
         /// Implements a peek plan produced by `create_plan` above.
+        ///
+        /// If `analyze` is set, the peek responds with statistics about the
+        /// dataflow that maintains the peeked arrangement, as described by
+        /// [`mz_dataflow_types::PlanNodeStatistics`], rather than with its
+        /// contents.
         pub async fn implement_fast_path_peek(
             &mut self,
             fast_path: Plan,
@@ -5048,6 +6102,7 @@ pub mod fast_path_peek {
             conn_id: u32,
             source_arity: usize,
             compute_instance: ComputeInstanceId,
+            analyze: bool,
         ) -> Result<crate::ExecuteResponse, CoordError> {
             // If the dataflow optimizes to a constant expression, we can immediately return the result.
             if let Plan::Constant(rows) = fast_path {
@@ -5092,8 +6147,14 @@ pub mod fast_path_peek {
 
             // If we must build the view, ship the dataflow.
             let (peek_command, drop_dataflow) = match fast_path {
-                Plan::PeekExisting(id, key, map_filter_project) => (
-                    (id, key, timestamp, finishing.clone(), map_filter_project),
+                Plan::PeekExisting(id, literal_constraints, map_filter_project) => (
+                    (
+                        id,
+                        literal_constraints,
+                        timestamp,
+                        finishing.clone(),
+                        map_filter_project,
+                    ),
                     None,
                 ),
                 Plan::PeekDataflow(PeekDataflowPlan {
@@ -5152,7 +6213,8 @@ pub mod fast_path_peek {
             };
 
             // Endpoints for sending and receiving peek responses.
-            let (rows_tx, rows_rx) = tokio::sync::mpsc::unbounded_channel();
+            // The coordinator queues the responses that do not fit.
+            let (rows_tx, rows_rx) = mpsc::channel(1);
 
             // Generate unique UUID. Guaranteed to be unique to all pending peeks, there's an very
             // small but unlikely chance that it's not unique to completed peeks.
@@ -5168,59 +6230,48 @@ pub mod fast_path_peek {
                 PendingPeek {
                     sender: rows_tx,
                     conn_id,
+                    queued: VecDeque::new(),
                 },
             );
             self.client_pending_peeks
                 .entry(conn_id)
                 .or_default()
                 .insert(uuid, compute_instance);
-            let (id, key, timestamp, _finishing, map_filter_project) = peek_command;
+            let (id, literal_constraints, timestamp, _finishing, map_filter_project) = peek_command;
             self.dataflow_client
                 .compute_mut(compute_instance)
                 .unwrap()
                 .peek(
                     id,
-                    key,
+                    literal_constraints,
                     uuid,
                     timestamp,
                     finishing.clone(),
                     map_filter_project,
+                    analyze,
                 )
                 .await
                 .unwrap();
 
-            use futures::FutureExt;
-            use futures::StreamExt;
-            use mz_dataflow_types::PeekResponse;
-
-            // Prepare the receiver to return as a response.
-            let rows_rx = tokio_stream::wrappers::UnboundedReceiverStream::new(rows_rx)
-                .fold(PeekResponse::Rows(vec![]), |memo, resp| async {
-                    match (memo, resp) {
-                        (PeekResponse::Rows(mut memo), PeekResponse::Rows(rows)) => {
-                            memo.extend(rows);
-                            PeekResponse::Rows(memo)
-                        }
-                        (PeekResponse::Error(e), _) | (_, PeekResponse::Error(e)) => {
-                            PeekResponse::Error(e)
-                        }
-                        (PeekResponse::Canceled, _) | (_, PeekResponse::Canceled) => {
-                            PeekResponse::Canceled
-                        }
-                    }
-                })
-                .map(move |resp| match resp {
-                    PeekResponse::Rows(rows) => PeekResponseUnary::Rows(finishing.finish(rows)),
-                    PeekResponse::Canceled => PeekResponseUnary::Canceled,
-                    PeekResponse::Error(e) => PeekResponseUnary::Error(e),
-                });
+            // Finish the rows of the peek as they arrive, and send them on as the response.
+            let (batches_tx, batches_rx) = row_batch_channel();
+            task::spawn(
+                || format!("finish_peek:{uuid}"),
+                finish_peek_responses(
+                    uuid,
+                    rows_rx,
+                    finishing,
+                    batches_tx,
+                    self.internal_cmd_tx.clone(),
+                ),
+            );
 
             // If it was created, drop the dataflow once the peek command is sent.
             if let Some(index_id) = drop_dataflow {
                 self.drop_indexes(vec![(compute_instance, index_id)]).await;
             }
 
-            Ok(crate::ExecuteResponse::SendingRows(Box::pin(rows_rx)))
+            Ok(crate::ExecuteResponse::SendingRows(batches_rx))
         }
     }
 }
@@ -0,0 +1,559 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Suggestions of indexes that would speed up recent peeks.
+//!
+//! The coordinator remembers the optimized plans of the most recent peeks that
+//! required a dataflow or a scan of an entire index. From each plan it notes
+//! the indexes that would have helped: an index on the columns that a filter
+//! binds to literals, which lets the peek look up those keys directly, and an
+//! index on the columns by which a join reads each of its inputs, which lets
+//! the join reuse the index's arrangement. The suggestions for indexes that do
+//! not yet exist are published, per role that issued the peeks, in the
+//! `mz_catalog.mz_all_index_advice` table, which the `mz_index_advice` view
+//! restricts to the current role. Like the history from which they are drawn,
+//! they are kept in memory only.
+//!
+//! The advice estimates the size of each index from the size of a record of
+//! the collection and, if an existing index on the collection has reported
+//! statistics about its arrangement, the number of records in it.
+
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::mem;
+
+use mz_dataflow_types::client::ComputeInstanceId;
+use mz_expr::visit::Visit;
+use mz_expr::{BinaryFunc, Id, JoinInputMapper, MapFilterProject, MirRelationExpr, MirScalarExpr};
+use mz_ore::now::{to_datetime, EpochMillis};
+use mz_repr::{Datum, Diff, GlobalId, RelationType, Row, ScalarType, Timestamp};
+use mz_sql::ast::display::AstDisplay;
+use mz_sql::ast::{Ident, UnresolvedObjectName};
+use mz_stash::Append;
+use mz_transform::IndexOracle;
+
+use crate::catalog::builtin::MZ_ALL_INDEX_ADVICE;
+use crate::catalog::BuiltinTableUpdate;
+use crate::coord::Coordinator;
+
+/// The number of recent peeks from which indexes are suggested.
+const HISTORY_SIZE: usize = 1000;
+
+/// The assumed size of a value of variable size, like a string, in a row.
+const VARIABLE_DATUM_BYTES: usize = 16;
+
+/// An index that would have helped a peek.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Candidate {
+    /// The collection to index.
+    on: GlobalId,
+    /// The columns of the collection to index by.
+    key: Vec<usize>,
+    /// Whether the index would have let the peek look up its results directly,
+    /// without building a dataflow.
+    fast_path: bool,
+    /// The estimated size of a record in the index, in bytes.
+    record_bytes: usize,
+}
+
+/// A peek that was slower than it needed to be.
+#[derive(Debug)]
+struct RecordedPeek {
+    /// The role that issued the peek.
+    role: String,
+    compute_instance: ComputeInstanceId,
+    at: EpochMillis,
+    candidates: Vec<Candidate>,
+}
+
+/// The suggestion of one index, aggregated over the recorded peeks.
+#[derive(Debug)]
+struct Advice {
+    peeks: i64,
+    fast_path: bool,
+    record_bytes: usize,
+    last_seen: EpochMillis,
+}
+
+/// The in-memory state of the index advisor.
+#[derive(Debug, Default)]
+pub(super) struct IndexAdvisor {
+    /// The most recent peeks that suggested indexes, oldest first.
+    history: VecDeque<RecordedPeek>,
+    /// The rows currently in `mz_index_advice`.
+    published: HashSet<Row>,
+}
+
+impl IndexAdvisor {
+    /// Records the indexes that would have sped up a peek of `expr` by `role`
+    /// on `compute_instance` at `at`.
+    ///
+    /// Returns whether there were any.
+    fn record(
+        &mut self,
+        role: &str,
+        compute_instance: ComputeInstanceId,
+        at: EpochMillis,
+        expr: &MirRelationExpr,
+    ) -> bool {
+        let candidates = candidates(expr);
+        if candidates.is_empty() {
+            return false;
+        }
+        if self.history.len() == HISTORY_SIZE {
+            self.history.pop_front();
+        }
+        self.history.push_back(RecordedPeek {
+            role: role.into(),
+            compute_instance,
+            at,
+            candidates,
+        });
+        true
+    }
+
+    /// Aggregates the suggested indexes by role, compute instance, collection
+    /// and key.
+    fn advice(&self) -> BTreeMap<(String, ComputeInstanceId, GlobalId, Vec<usize>), Advice> {
+        let mut advice = BTreeMap::new();
+        for peek in &self.history {
+            for candidate in &peek.candidates {
+                let advice = advice
+                    .entry((
+                        peek.role.clone(),
+                        peek.compute_instance,
+                        candidate.on,
+                        candidate.key.clone(),
+                    ))
+                    .or_insert(Advice {
+                        peeks: 0,
+                        fast_path: false,
+                        record_bytes: candidate.record_bytes,
+                        last_seen: peek.at,
+                    });
+                advice.peeks += 1;
+                advice.fast_path |= candidate.fast_path;
+                advice.last_seen = peek.at;
+            }
+        }
+        advice
+    }
+
+    /// Replaces the published rows with `rows`, returning the differences.
+    fn publish(&mut self, rows: HashSet<Row>) -> Vec<(Row, Diff)> {
+        let mut updates = vec![];
+        for row in self.published.difference(&rows) {
+            updates.push((row.clone(), -1));
+        }
+        for row in rows.difference(&self.published) {
+            updates.push((row.clone(), 1));
+        }
+        self.published = rows;
+        updates
+    }
+}
+
+impl<S: Append + 'static> Coordinator<S> {
+    /// Records the indexes that would have sped up a peek of `expr` by `role`
+    /// on `compute_instance`, and publishes the resulting advice.
+    pub(super) async fn index_advisor_record_peek(
+        &mut self,
+        role: &str,
+        compute_instance: ComputeInstanceId,
+        expr: &MirRelationExpr,
+    ) {
+        let now = self.now();
+        if self.index_advisor.record(role, compute_instance, now, expr) {
+            let updates = self.index_advisor_updates();
+            self.send_builtin_table_updates(updates).await;
+        }
+    }
+
+    /// Returns the updates to `mz_index_advice` that bring it in line with the
+    /// recorded peeks and the current catalog.
+    ///
+    /// Indexes are not suggested on collections that no longer exist or are
+    /// temporary, in compute instances that no longer exist, or to roles that
+    /// no longer exist, and neither are indexes that already exist.
+    pub(super) fn index_advisor_updates(&mut self) -> Vec<BuiltinTableUpdate> {
+        let mut rows = HashSet::new();
+        for ((role, compute_instance, on, key), advice) in self.index_advisor.advice() {
+            if self.catalog.get_role(&role).is_none() {
+                continue;
+            }
+            let compute_instance = match self
+                .catalog
+                .compute_instances()
+                .find(|instance| instance.id == compute_instance)
+            {
+                Some(instance) => instance,
+                None => continue,
+            };
+            let entry = match self.catalog.try_get_entry(&on) {
+                Some(entry) if entry.conn_id().is_none() => entry,
+                _ => continue,
+            };
+            let key_exprs: Vec<_> = key.iter().map(|c| MirScalarExpr::Column(*c)).collect();
+            let exists = self.catalog.state().get_indexes_on(on).any(|(_, index)| {
+                index.compute_instance == compute_instance.id && index.keys == key_exprs
+            });
+            if exists {
+                continue;
+            }
+            let full_name = self.catalog.resolve_full_name(entry.name(), None);
+            let desc = match entry.desc(&full_name) {
+                Ok(desc) => desc,
+                Err(_) => continue,
+            };
+            let columns: Vec<_> = key
+                .iter()
+                .map(|c| Ident::new(desc.get_name(*c).as_str()).to_ast_string())
+                .collect();
+            let create_sql = format!(
+                "CREATE INDEX IN CLUSTER {} ON {} ({})",
+                Ident::new(compute_instance.name.clone()).to_ast_string(),
+                UnresolvedObjectName::from(full_name).to_ast_string(),
+                columns.join(", "),
+            );
+
+            // The index would hold every record of the collection, of which
+            // any existing index knows the number.
+            let record_bytes =
+                i64::try_from(advice.record_bytes).expect("record sizes fit in an i64");
+            let estimated_bytes =
+                self.index_oracle(compute_instance.id)
+                    .statistics(on)
+                    .map(|statistics| {
+                        let rows = i64::try_from(statistics.rows).unwrap_or(i64::MAX);
+                        record_bytes.saturating_mul(rows)
+                    });
+
+            let mut row = Row::default();
+            let mut packer = row.packer();
+            packer.push(Datum::String(&on.to_string()));
+            packer.push(Datum::String(&compute_instance.name));
+            packer.push(Datum::String(&role));
+            packer.push(Datum::String(&create_sql));
+            packer.push(Datum::Int64(advice.peeks));
+            packer.push(Datum::from(advice.fast_path));
+            packer.push(Datum::Int64(record_bytes));
+            packer.push(Datum::from(estimated_bytes));
+            packer.push(Datum::TimestampTz(to_datetime(advice.last_seen)));
+            rows.insert(row);
+        }
+
+        let table_id = self.catalog.resolve_builtin_table(&MZ_ALL_INDEX_ADVICE);
+        self.index_advisor
+            .publish(rows)
+            .into_iter()
+            .map(|(row, diff)| BuiltinTableUpdate {
+                id: table_id,
+                row,
+                diff,
+            })
+            .collect()
+    }
+}
+
+/// Finds the indexes that would speed up a peek of `expr`.
+fn candidates(expr: &MirRelationExpr) -> Vec<Candidate> {
+    // A query that is only a filter of a collection, up to maps and
+    // projections, can look up the literals of the filter in an index.
+    let mut root = expr;
+    while let MirRelationExpr::Project { input, .. } | MirRelationExpr::Map { input, .. } = root {
+        root = &**input;
+    }
+
+    let mut candidates = vec![];
+    // Expressions too deep to visit simply produce fewer candidates.
+    let _ = expr.visit_pre(&mut |e| match e {
+        MirRelationExpr::Filter { input, predicates } => {
+            if let Some((on, typ)) = get_global(input) {
+                let mut key: Vec<_> = predicates.iter().filter_map(literal_column).collect();
+                key.sort_unstable();
+                key.dedup();
+                if !key.is_empty() {
+                    candidates.push(Candidate {
+                        on,
+                        key,
+                        fast_path: std::ptr::eq(e, root),
+                        record_bytes: estimated_record_bytes(typ),
+                    });
+                }
+            }
+        }
+        MirRelationExpr::Join {
+            inputs,
+            equivalences,
+            ..
+        } => {
+            let input_mapper = JoinInputMapper::new(inputs);
+            let mut keys = vec![vec![]; inputs.len()];
+            for class in equivalences {
+                for expr in class {
+                    if let MirScalarExpr::Column(c) = expr {
+                        let (column, input) = input_mapper.map_column_to_local(*c);
+                        if !keys[input].contains(&column) {
+                            keys[input].push(column);
+                        }
+                    }
+                }
+            }
+            for (input, key) in inputs.iter().zip(keys) {
+                // Like `JoinImplementation`, look through maps, filters and
+                // projections that the join could apply after reading an
+                // index of the input.
+                let (mfp, input) = MapFilterProject::extract_non_errors_from_expr(input);
+                let key: Option<Vec<_>> = key
+                    .iter()
+                    .map(|c| Some(mfp.projection[*c]).filter(|c| *c < mfp.input_arity))
+                    .collect();
+                if let (Some((on, typ)), Some(key)) = (get_global(input), key) {
+                    if !key.is_empty() {
+                        candidates.push(Candidate {
+                            on,
+                            key,
+                            fast_path: false,
+                            record_bytes: estimated_record_bytes(typ),
+                        });
+                    }
+                }
+            }
+        }
+        MirRelationExpr::OuterJoin {
+            left,
+            right,
+            left_key,
+            right_key,
+            ..
+        } => {
+            // Outer joins read their inputs through an arrangement by their
+            // whole key, and so only benefit from an index on exactly it.
+            for (input, key) in [(left, left_key), (right, right_key)] {
+                let key: Option<Vec<_>> = key
+                    .iter()
+                    .map(|expr| match expr {
+                        MirScalarExpr::Column(c) => Some(*c),
+                        _ => None,
+                    })
+                    .collect();
+                if let (Some((on, typ)), Some(key)) = (get_global(input), key) {
+                    if !key.is_empty() {
+                        candidates.push(Candidate {
+                            on,
+                            key,
+                            fast_path: false,
+                            record_bytes: estimated_record_bytes(typ),
+                        });
+                    }
+                }
+            }
+        }
+        _ => (),
+    });
+
+    // A peek that would benefit from the same index twice counts only once.
+    let mut deduped: Vec<Candidate> = vec![];
+    for candidate in candidates {
+        match deduped
+            .iter_mut()
+            .find(|c| c.on == candidate.on && c.key == candidate.key)
+        {
+            Some(existing) => existing.fast_path |= candidate.fast_path,
+            None => deduped.push(candidate),
+        }
+    }
+    deduped
+}
+
+/// If `expr` reads a user collection, possibly through an arrangement, returns
+/// the collection's ID and type.
+fn get_global(expr: &MirRelationExpr) -> Option<(GlobalId, &RelationType)> {
+    match expr {
+        MirRelationExpr::ArrangeBy { input, .. } => get_global(input),
+        MirRelationExpr::Get {
+            id: Id::Global(id),
+            typ,
+        } if id.is_user() => Some((*id, typ)),
+        _ => None,
+    }
+}
+
+/// If `predicate` requires a column to equal a literal, or one of a list of
+/// literals, returns that column.
+fn literal_column(predicate: &MirScalarExpr) -> Option<usize> {
+    match predicate {
+        MirScalarExpr::CallBinary {
+            func: BinaryFunc::Eq,
+            expr1,
+            expr2,
+        } => match (&**expr1, &**expr2) {
+            (MirScalarExpr::Column(c), e) | (e, MirScalarExpr::Column(c)) if e.is_literal_ok() => {
+                Some(*c)
+            }
+            _ => None,
+        },
+        MirScalarExpr::CallBinary {
+            func: BinaryFunc::Or,
+            expr1,
+            expr2,
+        } => {
+            let c = literal_column(expr1)?;
+            if literal_column(expr2)? == c {
+                Some(c)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Estimates the number of bytes that an arrangement uses for each record of
+/// a collection of type `typ`: the record's datums, split between a key row
+/// and a value row, and the time and diff of the update.
+fn estimated_record_bytes(typ: &RelationType) -> usize {
+    let datum_bytes: usize = typ
+        .column_types
+        .iter()
+        .map(|typ| estimated_datum_bytes(&typ.scalar_type))
+        .sum();
+    datum_bytes + 2 * mem::size_of::<Row>() + mem::size_of::<Timestamp>() + mem::size_of::<Diff>()
+}
+
+/// Estimates the size of a datum of type `typ` in a row, following
+/// [`mz_repr::row::datum_size`].
+fn estimated_datum_bytes(typ: &ScalarType) -> usize {
+    match typ {
+        ScalarType::Bool | ScalarType::PgLegacyChar => 1,
+        ScalarType::Int16 => 1 + 2,
+        ScalarType::Int32
+        | ScalarType::Float32
+        | ScalarType::Oid
+        | ScalarType::RegProc
+        | ScalarType::RegType
+        | ScalarType::RegClass => 1 + 4,
+        ScalarType::Int64 | ScalarType::Float64 | ScalarType::Date | ScalarType::Time => 1 + 8,
+        ScalarType::Timestamp
+        | ScalarType::TimestampTz
+        | ScalarType::Interval
+        | ScalarType::Uuid => 1 + 16,
+        _ => 1 + VARIABLE_DATUM_BYTES,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mz_expr::{BinaryFunc, MirRelationExpr, MirScalarExpr, OuterJoinKind};
+    use mz_repr::{Datum, GlobalId, RelationType, ScalarType};
+
+    use super::{candidates, Candidate, IndexAdvisor, HISTORY_SIZE};
+
+    fn get(id: u64) -> MirRelationExpr {
+        MirRelationExpr::global_get(
+            GlobalId::User(id),
+            RelationType::new(vec![
+                ScalarType::Int32.nullable(false),
+                ScalarType::String.nullable(true),
+            ]),
+        )
+    }
+
+    fn eq_literal(column: usize, value: i32) -> MirScalarExpr {
+        MirScalarExpr::Column(column).call_binary(
+            MirScalarExpr::literal_ok(Datum::Int32(value), ScalarType::Int32),
+            BinaryFunc::Eq,
+        )
+    }
+
+    #[test]
+    fn test_candidates() {
+        // `SELECT #1 FROM u1 WHERE #0 IN (1, 2)` can read an index on #0.
+        let expr = get(1)
+            .filter(vec![
+                eq_literal(0, 1).call_binary(eq_literal(0, 2), BinaryFunc::Or)
+            ])
+            .project(vec![1]);
+        assert_eq!(
+            candidates(&expr),
+            vec![Candidate {
+                on: GlobalId::User(1),
+                key: vec![0],
+                fast_path: true,
+                record_bytes: 5 + 17 + 2 * std::mem::size_of::<mz_repr::Row>() + 16,
+            }]
+        );
+
+        // Joining u1 and u2 on their second columns can use an index of each
+        // on that column, as the join can filter u2 after reading the index.
+        // The filter of u2 could use an index of its own. None of them avoids
+        // a dataflow.
+        let expr = MirRelationExpr::join(
+            vec![get(1), get(2).filter(vec![eq_literal(0, 1)])],
+            vec![vec![(0, 1), (1, 1)]],
+        );
+        let found: Vec<_> = candidates(&expr)
+            .into_iter()
+            .map(|c| (c.on, c.key, c.fast_path))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (GlobalId::User(1), vec![1], false),
+                (GlobalId::User(2), vec![1], false),
+                (GlobalId::User(2), vec![0], false),
+            ]
+        );
+
+        // A left outer join of u1 and u2 on their first columns can use an
+        // index of each on that column.
+        let expr = get(1).outer_join(
+            get(2),
+            vec![MirScalarExpr::Column(0)],
+            vec![MirScalarExpr::Column(0)],
+            OuterJoinKind::Left,
+        );
+        let found: Vec<_> = candidates(&expr)
+            .into_iter()
+            .map(|c| (c.on, c.key, c.fast_path))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (GlobalId::User(1), vec![0], false),
+                (GlobalId::User(2), vec![0], false),
+            ]
+        );
+
+        // System collections and unfiltered reads suggest nothing.
+        assert!(candidates(&get(1)).is_empty());
+        let system = MirRelationExpr::global_get(
+            GlobalId::System(1),
+            RelationType::new(vec![ScalarType::Int32.nullable(false)]),
+        );
+        assert!(candidates(&system.filter(vec![eq_literal(0, 1)])).is_empty());
+    }
+
+    #[test]
+    fn test_history() {
+        let mut advisor = IndexAdvisor::default();
+        let expr = get(1).filter(vec![eq_literal(0, 1)]);
+        for at in 0..HISTORY_SIZE + 10 {
+            assert!(advisor.record("materialize", 1, at as u64, &expr));
+        }
+        assert!(!advisor.record("materialize", 1, 0, &get(1)));
+        advisor.record("other", 1, 0, &expr);
+        let advice = advisor.advice();
+        assert_eq!(advice.len(), 2);
+        let advice = &advice[&("materialize".into(), 1, GlobalId::User(1), vec![0])];
+        // The peek by the other role pushed out the oldest one.
+        assert_eq!(advice.peeks, HISTORY_SIZE as i64 - 1);
+        assert!(advice.fast_path);
+        assert_eq!(advice.last_seen, (HISTORY_SIZE + 9) as u64);
+    }
+}
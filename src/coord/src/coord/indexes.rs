@@ -14,7 +14,7 @@ use mz_dataflow_types::client::ComputeInstanceId;
 use mz_expr::MirScalarExpr;
 use mz_repr::GlobalId;
 use mz_stash::Append;
-use mz_transform::IndexOracle;
+use mz_transform::{CollectionStatistics, IndexOracle};
 
 use crate::catalog::{CatalogItem, CatalogState, Index};
 use crate::coord::dataflow_builder::DataflowBuilder;
@@ -106,4 +106,29 @@ impl<T: CoordTimestamp> IndexOracle for ComputeInstanceIndexOracle<'_, T> {
                 .map(|(_idx_id, idx)| idx.keys.as_slice()),
         )
     }
+
+    /// Derives statistics from the arrangements that back the indexes on the
+    /// collection, as last reported by the compute instance. Every index
+    /// contains all records of the collection, and contributes the number of
+    /// distinct values of its key. The number of rows is the number of
+    /// updates in an arrangement, and so overestimates the number of records
+    /// when updates have not yet been consolidated.
+    fn statistics(&self, id: GlobalId) -> Option<CollectionStatistics> {
+        let mut statistics: Option<CollectionStatistics> = None;
+        for (idx_id, idx) in ComputeInstanceIndexOracle::indexes_on(self, id) {
+            let arrangement = match self.compute.collection(idx_id) {
+                Ok(collection) => match collection.statistics {
+                    Some(arrangement) => arrangement,
+                    None => continue,
+                },
+                Err(_) => continue,
+            };
+            let statistics = statistics.get_or_insert_with(CollectionStatistics::default);
+            statistics.rows = std::cmp::max(statistics.rows, arrangement.records);
+            statistics
+                .distinct_keys
+                .push((idx.keys.clone(), arrangement.keys));
+        }
+        statistics
+    }
 }
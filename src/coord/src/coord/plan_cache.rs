@@ -0,0 +1,328 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Caches of optimized plans for `SELECT` statements.
+//!
+//! Planning and optimizing a `SELECT` statement can take longer than executing
+//! it, particularly for point lookups in an index. The coordinator therefore
+//! keeps the optimized plans of recently executed `SELECT` statements in a
+//! cache for each session, and optionally in a cache shared by all sessions.
+//!
+//! A plan is cached under the statement, the values of its parameters, and the
+//! session variables that affect name resolution. Every cached plan is valid
+//! for one revision of the catalog only, so that any DDL statement invalidates
+//! all of them. When the optimized query reads from an existing index, the
+//! fast path chosen to read it is cached too, unless the query calls
+//! unmaterializable functions like `now()`, whose values are substituted into
+//! the plan at each execution.
+
+use std::collections::{BTreeSet, HashMap};
+
+use mz_expr::visit::Visit;
+use mz_expr::{
+    BinaryFunc, MirRelationExpr, MirScalarExpr, OptimizedMirRelationExpr, RowSetFinishing,
+};
+use mz_ore::metric;
+use mz_ore::metrics::raw::IntCounterVec;
+use mz_ore::metrics::MetricsRegistry;
+use mz_repr::{GlobalId, Row, ScalarType};
+use mz_sql::ast::{Raw, Statement};
+use mz_sql::plan::{CopyFormat, Params, Plan, QueryWhen};
+use mz_stash::Append;
+
+use crate::command::ExecuteResponse;
+use crate::coord::{fast_path_peek, Coordinator};
+use crate::session::Session;
+use crate::util::ClientTransmitter;
+
+/// Configures the plan caches.
+#[derive(Debug, Clone)]
+pub struct PlanCacheConfig {
+    /// The number of plans to cache for each session. Zero disables the
+    /// per-session caches.
+    pub session_capacity: usize,
+    /// The number of plans to cache for all sessions together. Zero disables
+    /// the global cache.
+    pub global_capacity: usize,
+}
+
+impl PlanCacheConfig {
+    /// Reports whether any plans are cached.
+    pub fn enabled(&self) -> bool {
+        self.session_capacity > 0 || self.global_capacity > 0
+    }
+}
+
+impl Default for PlanCacheConfig {
+    fn default() -> PlanCacheConfig {
+        PlanCacheConfig {
+            session_capacity: 128,
+            global_capacity: 0,
+        }
+    }
+}
+
+/// Metrics for the plan caches.
+#[derive(Debug, Clone)]
+pub struct PlanCacheMetrics {
+    lookups: IntCounterVec,
+}
+
+impl PlanCacheMetrics {
+    pub fn register_with(registry: &MetricsRegistry) -> PlanCacheMetrics {
+        PlanCacheMetrics {
+            lookups: registry.register(metric!(
+                name: "mz_plan_cache_lookups_total",
+                help: "The number of lookups of SELECT statements in the plan caches, by the cache that held the plan, or \"miss\".",
+                var_labels: ["result"],
+            )),
+        }
+    }
+}
+
+/// Identifies a cached plan.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PlanCacheKey {
+    stmt: Statement<Raw>,
+    params: Row,
+    param_types: Vec<ScalarType>,
+    database: String,
+    search_path: Vec<String>,
+    cluster: String,
+    qgm_optimizations: bool,
+    native_outer_joins: bool,
+}
+
+impl PlanCacheKey {
+    fn new(session: &Session, stmt: Statement<Raw>, params: &Params) -> PlanCacheKey {
+        let vars = session.vars();
+        PlanCacheKey {
+            stmt,
+            params: params.datums.clone(),
+            param_types: params.types.clone(),
+            database: vars.database().into(),
+            search_path: vars.search_path().into_iter().map(String::from).collect(),
+            cluster: vars.cluster().into(),
+            qgm_optimizations: session.pcx().qgm_optimizations,
+            native_outer_joins: session.pcx().native_outer_joins,
+        }
+    }
+}
+
+/// A peek whose query has been optimized independently of the timestamp at
+/// which it occurs.
+#[derive(Debug, Clone)]
+pub struct OptimizedPeekPlan {
+    /// The optimized query.
+    pub source: OptimizedMirRelationExpr,
+    /// The collections on which the query depended before optimization.
+    pub source_ids: BTreeSet<GlobalId>,
+    /// Whether the query referred to `mz_logical_timestamp()` before
+    /// optimization.
+    pub contains_temporal: bool,
+    pub when: QueryWhen,
+    pub finishing: RowSetFinishing,
+    pub copy_to: Option<CopyFormat>,
+    /// The way to read the results of the query out of an existing index, if
+    /// that does not depend on the timestamp of the peek.
+    pub fast_path: Option<fast_path_peek::Plan>,
+}
+
+/// A bounded cache of optimized plans, all valid for the same catalog
+/// revision.
+///
+/// When the cache is full, inserting a plan evicts the least recently used
+/// one.
+#[derive(Debug, Default)]
+pub struct PlanCache {
+    /// The catalog revision for which the cached plans are valid.
+    revision: u64,
+    entries: HashMap<PlanCacheKey, CacheEntry>,
+    /// Advanced on every access, to order the entries by recency.
+    clock: u64,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    plan: OptimizedPeekPlan,
+    last_used: u64,
+}
+
+impl PlanCache {
+    /// Returns the plan cached under `key` for the catalog `revision`, if any.
+    fn get(&mut self, revision: u64, key: &PlanCacheKey) -> Option<&OptimizedPeekPlan> {
+        self.advance_revision(revision);
+        self.clock += 1;
+        let entry = self.entries.get_mut(key)?;
+        entry.last_used = self.clock;
+        Some(&entry.plan)
+    }
+
+    /// Caches `plan` under `key` for the catalog `revision`, keeping at most
+    /// `capacity` plans.
+    fn insert(
+        &mut self,
+        revision: u64,
+        capacity: usize,
+        key: PlanCacheKey,
+        plan: OptimizedPeekPlan,
+    ) {
+        if capacity == 0 {
+            return;
+        }
+        self.advance_revision(revision);
+        if !self.entries.contains_key(&key) && self.entries.len() >= capacity {
+            let lru = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(lru) = lru {
+                self.entries.remove(&lru);
+            }
+        }
+        self.clock += 1;
+        self.entries.insert(
+            key,
+            CacheEntry {
+                plan,
+                last_used: self.clock,
+            },
+        );
+    }
+
+    /// Drops all cached plans if they were made for an earlier revision.
+    fn advance_revision(&mut self, revision: u64) {
+        if self.revision != revision {
+            self.entries.clear();
+            self.revision = revision;
+        }
+    }
+}
+
+impl<S: Append + 'static> Coordinator<S> {
+    /// Plans and sequences a `SELECT` statement, reusing the optimized plan of
+    /// an earlier execution from the session's plan cache or the global plan
+    /// cache if either has one.
+    pub(crate) async fn sequence_select(
+        &mut self,
+        tx: ClientTransmitter<ExecuteResponse>,
+        mut session: Session,
+        stmt: Statement<Raw>,
+        params: Params,
+    ) {
+        let conn_id = session.conn_id();
+        let revision = self.catalog.transient_revision();
+        // Plans cached by other sessions could resolve names differently than
+        // this session does once it has created temporary items.
+        let use_global = self.plan_cache_config.global_capacity > 0
+            && !self.catalog.has_temporary_items(conn_id);
+        let key = PlanCacheKey::new(&session, stmt, &params);
+
+        let mut cached = None;
+        if let Some(conn_meta) = self.active_conns.get_mut(&conn_id) {
+            if let Some(plan) = conn_meta.plan_cache.get(revision, &key) {
+                cached = Some((plan.clone(), "session"));
+            }
+        }
+        if cached.is_none() && use_global {
+            if let Some(plan) = self.global_plan_cache.get(revision, &key) {
+                cached = Some((plan.clone(), "global"));
+            }
+        }
+        let result = match &cached {
+            Some((_, cache)) => *cache,
+            None => "miss",
+        };
+        self.plan_cache_metrics
+            .lookups
+            .with_label_values(&[result])
+            .inc();
+
+        let (mut plan, cache) = match cached {
+            Some((plan, _)) => (plan, false),
+            None => {
+                let mut plan = match self
+                    .handle_statement(&mut session, key.stmt.clone(), &params)
+                    .await
+                {
+                    Ok(Plan::Peek(plan)) => plan,
+                    Ok(plan) => return self.sequence_plan(tx, session, plan).await,
+                    Err(e) => return tx.send(Err(e), session),
+                };
+                let cache = !depends_on_wall_time(&mut plan.source);
+                match self.optimize_peek(plan) {
+                    Ok(plan) => (plan, cache),
+                    Err(e) => return tx.send(Err(e), session),
+                }
+            }
+        };
+
+        if let Err(e) = self.check_peek_privileges(&session, &plan.source_ids) {
+            return tx.send(Err(e), session);
+        }
+        let result = self.sequence_optimized_peek(&mut session, &mut plan).await;
+
+        if cache {
+            if use_global {
+                self.global_plan_cache.insert(
+                    revision,
+                    self.plan_cache_config.global_capacity,
+                    key.clone(),
+                    plan.clone(),
+                );
+            }
+            if let Some(conn_meta) = self.active_conns.get_mut(&conn_id) {
+                conn_meta.plan_cache.insert(
+                    revision,
+                    self.plan_cache_config.session_capacity,
+                    key,
+                    plan,
+                );
+            }
+        }
+        tx.send(result, session);
+    }
+}
+
+/// Reports whether `expr` calls any unmaterializable function.
+pub fn calls_unmaterializable(expr: &mut OptimizedMirRelationExpr) -> bool {
+    any_scalar(&mut expr.0, |e| {
+        matches!(e, MirScalarExpr::CallUnmaterializable(_))
+    })
+}
+
+/// Reports whether `expr` captured the wall-clock time at which it was
+/// planned, as `timezone` does for values of type `time`.
+fn depends_on_wall_time(expr: &mut MirRelationExpr) -> bool {
+    any_scalar(expr, |e| {
+        matches!(
+            e,
+            MirScalarExpr::CallBinary {
+                func: BinaryFunc::TimezoneTime { .. },
+                ..
+            }
+        )
+    })
+}
+
+/// Reports whether any scalar expression in `expr` satisfies `f`. Expressions
+/// too deep to visit are assumed to.
+fn any_scalar<F>(expr: &mut MirRelationExpr, f: F) -> bool
+where
+    F: Fn(&MirScalarExpr) -> bool,
+{
+    let mut found = false;
+    expr.visit_scalars_mut(&mut |e| {
+        if e.visit_post(&mut |e| found = found || f(e)).is_err() {
+            found = true;
+        }
+    });
+    found
+}
@@ -0,0 +1,416 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Privilege checks for planned statements.
+
+use std::collections::BTreeSet;
+
+use mz_expr::CollectionPlan;
+use mz_repr::GlobalId;
+use mz_sql::ast::{ExplainStage, Privilege};
+use mz_sql::names::{ObjectId, QualifiedObjectName, ResolvedDatabaseSpecifier, SchemaSpecifier};
+use mz_sql::plan::{MutationKind, Plan, TailFrom};
+use mz_stash::Append;
+
+use crate::catalog::builtin::{MZ_ALL_INDEX_ADVICE, MZ_ALL_STATEMENT_LOG};
+use crate::catalog::{Catalog, CatalogItem};
+use crate::coord::Coordinator;
+use crate::error::CoordError;
+use crate::session::Session;
+
+impl<S: Append> Coordinator<S> {
+    /// Verifies that the role of `session` may execute `plan`.
+    ///
+    /// Superusers may execute any plan. Other roles need the privileges that
+    /// the plan requires on each object it touches, which they hold either by
+    /// owning the object or through a grant to them or to `PUBLIC`. Objects
+    /// that do not exist are not checked, so that sequencing can report the
+    /// more specific error.
+    pub(crate) fn check_privileges(
+        &self,
+        session: &Session,
+        plan: &Plan,
+    ) -> Result<(), CoordError> {
+        let checker = match self.privilege_checker(session)? {
+            Some(checker) => checker,
+            None => return Ok(()),
+        };
+        let superuser_required = |action: &str| Err(CoordError::SuperuserRequired(action.into()));
+
+        match plan {
+            Plan::CreateDatabase(_) => superuser_required("create databases"),
+            Plan::CreateRole(_) => superuser_required("create roles"),
+            Plan::DropRoles(_) => superuser_required("drop roles"),
+            Plan::CreateComputeInstance(_) => superuser_required("create clusters"),
+            Plan::CreateComputeInstanceReplica(plan) => {
+                checker.require_cluster_owner(&plan.of_cluster)
+            }
+            Plan::CreateSchema(plan) => match plan.database_spec {
+                ResolvedDatabaseSpecifier::Id(id) => {
+                    checker.require(ObjectId::Database(id), Privilege::Create)
+                }
+                ResolvedDatabaseSpecifier::Ambient => Ok(()),
+            },
+            Plan::CreateConnector(plan) => checker.require_create_in(&plan.name),
+            Plan::CreateSecret(plan) => checker.require_create_in(&plan.name),
+            Plan::CreateSource(plan) => {
+                checker.require_create_in(&plan.name)?;
+                checker.require_uses(&plan.source.depends_on)?;
+                if plan.materialized {
+                    checker.require_active_cluster(session, Privilege::Create)?;
+                }
+                Ok(())
+            }
+            Plan::CreateSink(plan) => {
+                checker.require_create_in(&plan.name)?;
+                checker.require_uses(&plan.sink.depends_on)?;
+                checker.require(
+                    ObjectId::ComputeInstance(plan.sink.compute_instance),
+                    Privilege::Create,
+                )
+            }
+            Plan::CreateTable(plan) => {
+                checker.require_create_in(&plan.name)?;
+                checker.require_uses(&plan.table.depends_on)
+            }
+            Plan::CreateView(plan) => {
+                checker.require_create_in(&plan.name)?;
+                checker.require_uses(&plan.view.depends_on)?;
+                if let Some(id) = plan.replace {
+                    checker.require_owner(ObjectId::Item(id))?;
+                }
+                if plan.materialize {
+                    checker.require_active_cluster(session, Privilege::Create)?;
+                }
+                Ok(())
+            }
+            Plan::CreateViews(plan) => {
+                for (name, view) in &plan.views {
+                    checker.require_create_in(name)?;
+                    checker.require_uses(&view.depends_on)?;
+                }
+                if plan.materialize {
+                    checker.require_active_cluster(session, Privilege::Create)?;
+                }
+                Ok(())
+            }
+            Plan::CreateIndex(plan) => {
+                // As in PostgreSQL, only the owner of a relation may index it.
+                checker.require_owner(ObjectId::Item(plan.index.on))?;
+                checker.require(
+                    ObjectId::ComputeInstance(plan.index.compute_instance),
+                    Privilege::Create,
+                )
+            }
+            Plan::CreateType(plan) => {
+                checker.require_create_in(&plan.name)?;
+                checker.require_uses(&plan.typ.depends_on)
+            }
+            Plan::DropDatabase(plan) => match plan.id {
+                Some(id) => checker.require_owner(ObjectId::Database(id)),
+                None => Ok(()),
+            },
+            Plan::DropSchema(plan) => match plan.id {
+                Some((_, id)) => checker.require_owner(ObjectId::Schema(id)),
+                None => Ok(()),
+            },
+            Plan::DropComputeInstances(plan) => {
+                for name in &plan.names {
+                    checker.require_cluster_owner(name)?;
+                }
+                Ok(())
+            }
+            Plan::DropComputeInstanceReplica(plan) => {
+                for (cluster_name, _replica_name) in &plan.names {
+                    checker.require_cluster_owner(cluster_name)?;
+                }
+                Ok(())
+            }
+            Plan::DropItems(plan) => {
+                for id in &plan.items {
+                    checker.require_owner(ObjectId::Item(*id))?;
+                }
+                Ok(())
+            }
+            Plan::GrantPrivileges(plan) => {
+                for object in &plan.objects {
+                    checker.require_owner(*object)?;
+                }
+                Ok(())
+            }
+            Plan::RevokePrivileges(plan) => {
+                for object in &plan.objects {
+                    checker.require_owner(*object)?;
+                }
+                Ok(())
+            }
+            Plan::Peek(plan) => {
+                checker.require_uses(&plan.source.depends_on())?;
+                checker.require_active_cluster(session, Privilege::Usage)
+            }
+            Plan::Tail(plan) => {
+                match &plan.from {
+                    TailFrom::Id(id) => checker.require_uses(&[*id])?,
+                    TailFrom::Query { depends_on, .. } => checker.require_uses(depends_on)?,
+                }
+                checker.require_active_cluster(session, Privilege::Usage)
+            }
+            Plan::CopyFrom(plan) => checker.require(ObjectId::Item(plan.id), Privilege::Insert),
+            Plan::Insert(plan) => {
+                checker.require(ObjectId::Item(plan.id), Privilege::Insert)?;
+                checker.require_uses(&plan.values.depends_on())
+            }
+            Plan::ReadThenWrite(plan) => {
+                let privilege = match plan.kind {
+                    MutationKind::Insert => Privilege::Insert,
+                    MutationKind::Update => Privilege::Update,
+                    MutationKind::Delete => Privilege::Delete,
+                };
+                checker.require(ObjectId::Item(plan.id), privilege)?;
+                let mut uses = plan.selection.depends_on();
+                uses.remove(&plan.id);
+                checker.require_uses(&uses)
+            }
+            Plan::AlterItemRename(plan) => checker.require_owner(ObjectId::Item(plan.id)),
+            Plan::AlterIndexSetOptions(plan) => checker.require_owner(ObjectId::Item(plan.id)),
+            Plan::AlterIndexResetOptions(plan) => checker.require_owner(ObjectId::Item(plan.id)),
+            Plan::AlterSecret(plan) => checker.require_owner(ObjectId::Item(plan.id)),
+            Plan::Explain(plan) => {
+                checker.require_uses(&plan.raw_plan.depends_on())?;
+                if let Some(id) = plan.view_id {
+                    checker.require_uses(&[id])?;
+                }
+                match plan.stage {
+                    // These stages determine a timestamp or run the query on
+                    // the active cluster.
+                    ExplainStage::Timestamp | ExplainStage::Analyze => {
+                        checker.require_active_cluster(session, Privilege::Usage)
+                    }
+                    ExplainStage::RawPlan
+                    | ExplainStage::QueryGraph
+                    | ExplainStage::OptimizedQueryGraph
+                    | ExplainStage::DecorrelatedPlan
+                    | ExplainStage::OptimizedPlan
+                    | ExplainStage::PhysicalPlan => Ok(()),
+                }
+            }
+            // These plans only touch the session, or produce rows that were
+            // computed by a plan that was checked itself. Statements that are
+            // prepared, declared, or executed are checked when they are
+            // planned for execution.
+            Plan::DiscardTemp
+            | Plan::DiscardAll
+            | Plan::EmptyQuery
+            | Plan::ShowAllVariables
+            | Plan::ShowVariable(_)
+            | Plan::SetVariable(_)
+            | Plan::StartTransaction(_)
+            | Plan::SetTransaction(_)
+            | Plan::CommitTransaction
+            | Plan::AbortTransaction
+            | Plan::SendRows(_)
+            | Plan::SendDiffs(_)
+            | Plan::AlterNoop(_)
+            | Plan::Declare(_)
+            | Plan::Fetch(_)
+            | Plan::Close(_)
+            | Plan::Prepare(_)
+            | Plan::Execute(_)
+            | Plan::Deallocate(_)
+            | Plan::Raise(_) => Ok(()),
+        }
+    }
+
+    /// Verifies that the role of `session` may execute a peek of a query that
+    /// depends on `source_ids`, as [`Coordinator::check_privileges`] does for
+    /// a [`Plan::Peek`].
+    pub(crate) fn check_peek_privileges(
+        &self,
+        session: &Session,
+        source_ids: &BTreeSet<GlobalId>,
+    ) -> Result<(), CoordError> {
+        match self.privilege_checker(session)? {
+            Some(checker) => {
+                checker.require_uses(source_ids)?;
+                checker.require_active_cluster(session, Privilege::Usage)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Returns a checker for the privileges of the role of `session`, or
+    /// `None` if the role is a superuser.
+    fn privilege_checker(
+        &self,
+        session: &Session,
+    ) -> Result<Option<PrivilegeChecker<'_, S>>, CoordError> {
+        let role = self
+            .catalog
+            .get_role(session.user())
+            .ok_or_else(|| CoordError::UnknownLoginRole(session.user().into()))?;
+        if role.superuser {
+            return Ok(None);
+        }
+        Ok(Some(PrivilegeChecker {
+            catalog: &self.catalog,
+            role_id: role.id,
+            conn_id: session.conn_id(),
+        }))
+    }
+}
+
+/// Checks the privileges of a non-superuser role.
+struct PrivilegeChecker<'a, S> {
+    catalog: &'a Catalog<S>,
+    role_id: i64,
+    conn_id: u32,
+}
+
+impl<S: Append> PrivilegeChecker<'_, S> {
+    /// Requires that the role holds `privilege` on `object`.
+    fn require(&self, object: ObjectId, privilege: Privilege) -> Result<(), CoordError> {
+        if self.is_system(object)
+            || self
+                .catalog
+                .state()
+                .has_privilege(self.role_id, object, privilege)
+        {
+            Ok(())
+        } else {
+            let (object_type, object_name) = self.describe(object);
+            Err(CoordError::PermissionDenied {
+                object_type,
+                object_name,
+            })
+        }
+    }
+
+    /// Requires that the role owns `object`.
+    fn require_owner(&self, object: ObjectId) -> Result<(), CoordError> {
+        if self.catalog.state().owner_id(object) == self.role_id {
+            Ok(())
+        } else {
+            let (object_type, object_name) = self.describe(object);
+            Err(CoordError::MustBeOwner {
+                object_type,
+                object_name,
+            })
+        }
+    }
+
+    /// Requires that the role owns the named cluster.
+    fn require_cluster_owner(&self, name: &str) -> Result<(), CoordError> {
+        match self.catalog.resolve_compute_instance(name) {
+            Ok(instance) => self.require_owner(ObjectId::ComputeInstance(instance.id)),
+            Err(_) => Ok(()),
+        }
+    }
+
+    /// Requires that the role holds `privilege` on the session's active
+    /// cluster.
+    fn require_active_cluster(
+        &self,
+        session: &Session,
+        privilege: Privilege,
+    ) -> Result<(), CoordError> {
+        match self
+            .catalog
+            .resolve_compute_instance(session.vars().cluster())
+        {
+            Ok(instance) => self.require(ObjectId::ComputeInstance(instance.id), privilege),
+            Err(_) => Ok(()),
+        }
+    }
+
+    /// Requires that the role may create an object named `name`.
+    fn require_create_in(&self, name: &QualifiedObjectName) -> Result<(), CoordError> {
+        match &name.qualifiers.schema_spec {
+            SchemaSpecifier::Temporary => Ok(()),
+            SchemaSpecifier::Id(id) => self.require(ObjectId::Schema(*id), Privilege::Create),
+        }
+    }
+
+    /// Requires that the role may refer to each of the items in `ids`: it
+    /// must be able to look the item up in its schema, and to read relations
+    /// and use secrets and connectors.
+    fn require_uses<'i>(
+        &self,
+        ids: impl IntoIterator<Item = &'i GlobalId>,
+    ) -> Result<(), CoordError> {
+        for id in ids {
+            let entry = self.catalog.get_entry(id);
+            if let SchemaSpecifier::Id(schema_id) = entry.name().qualifiers.schema_spec {
+                self.require(ObjectId::Schema(schema_id), Privilege::Usage)?;
+            }
+            let privilege = match entry.item() {
+                CatalogItem::Table(_) | CatalogItem::Source(_) | CatalogItem::View(_) => {
+                    Privilege::Select
+                }
+                CatalogItem::Secret(_) | CatalogItem::Connector(_) => Privilege::Usage,
+                CatalogItem::Sink(_)
+                | CatalogItem::Index(_)
+                | CatalogItem::Type(_)
+                | CatalogItem::Func(_) => continue,
+            };
+            self.require(ObjectId::Item(*id), privilege)?;
+        }
+        Ok(())
+    }
+
+    /// Reports whether `object` is part of the database system. System
+    /// objects are usable by every role, except for the index advice and the
+    /// statement log drawn from the queries of all roles, which only
+    /// superusers may read.
+    fn is_system(&self, object: ObjectId) -> bool {
+        match object {
+            ObjectId::Database(_) | ObjectId::ComputeInstance(_) => false,
+            ObjectId::Schema(id) => matches!(
+                self.catalog.state().get_schema_by_id(&id).name.database,
+                ResolvedDatabaseSpecifier::Ambient
+            ),
+            ObjectId::Item(id) => {
+                id.is_system()
+                    && id != self.catalog.resolve_builtin_table(&MZ_ALL_INDEX_ADVICE)
+                    && id != self.catalog.resolve_builtin_table(&MZ_ALL_STATEMENT_LOG)
+            }
+        }
+    }
+
+    /// Returns the type and name of `object` for use in error messages.
+    fn describe(&self, object: ObjectId) -> (String, String) {
+        match object {
+            ObjectId::Database(id) => (
+                "database".into(),
+                self.catalog.get_database(&id).name.clone(),
+            ),
+            ObjectId::Schema(id) => (
+                "schema".into(),
+                self.catalog
+                    .state()
+                    .get_schema_by_id(&id)
+                    .name
+                    .schema
+                    .clone(),
+            ),
+            ObjectId::Item(id) => {
+                let entry = self.catalog.get_entry(&id);
+                (
+                    entry.item().typ().to_string(),
+                    self.catalog
+                        .resolve_full_name(entry.name(), Some(self.conn_id))
+                        .to_string(),
+                )
+            }
+            ObjectId::ComputeInstance(id) => (
+                "cluster".into(),
+                self.catalog.get_compute_instance(id).name.clone(),
+            ),
+        }
+    }
+}
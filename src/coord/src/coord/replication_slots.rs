@@ -0,0 +1,172 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Replication slots for logical replication clients.
+//!
+//! A slot records how far its consumer has progressed, as the latest log
+//! sequence number (LSN) that the consumer has confirmed flushing, so that the
+//! consumer can resume streaming where it left off. Slots other than temporary
+//! slots are recorded in the catalog and survive restarts. Temporary slots and
+//! the connection that is streaming from each slot are held in memory only.
+
+use std::collections::BTreeMap;
+
+use mz_stash::Append;
+
+use crate::coord::Coordinator;
+use crate::error::CoordError;
+use crate::session::Session;
+
+/// A replication slot.
+#[derive(Debug)]
+struct ReplicationSlot {
+    /// The connection that created the slot, if the slot is temporary.
+    temporary_owner: Option<u32>,
+    /// The latest LSN that a consumer of the slot has confirmed flushing.
+    confirmed_flush: u64,
+    /// The connection that is streaming from the slot, if any.
+    active_conn: Option<u32>,
+}
+
+/// The replication slots known to the coordinator.
+#[derive(Debug, Default)]
+pub(crate) struct ReplicationSlots {
+    slots: BTreeMap<String, ReplicationSlot>,
+}
+
+impl ReplicationSlots {
+    /// Constructs the replication slots from the durable slots recorded in the
+    /// catalog.
+    pub(crate) fn new(durable: Vec<(String, u64)>) -> ReplicationSlots {
+        let slots = durable
+            .into_iter()
+            .map(|(name, confirmed_flush)| {
+                let slot = ReplicationSlot {
+                    temporary_owner: None,
+                    confirmed_flush,
+                    active_conn: None,
+                };
+                (name, slot)
+            })
+            .collect();
+        ReplicationSlots { slots }
+    }
+
+    /// Returns the slot named `name` if it is not in use by another connection.
+    fn get_inactive(&mut self, name: &str) -> Result<&mut ReplicationSlot, CoordError> {
+        match self.slots.get_mut(name) {
+            None => Err(CoordError::UnknownReplicationSlot(name.into())),
+            Some(ReplicationSlot {
+                active_conn: Some(conn_id),
+                ..
+            }) => Err(CoordError::ReplicationSlotActive {
+                name: name.into(),
+                conn_id: *conn_id,
+            }),
+            Some(slot) => Ok(slot),
+        }
+    }
+}
+
+impl<S: Append> Coordinator<S> {
+    /// Creates a replication slot. Temporary slots are dropped when the
+    /// connection that created them terminates; other slots are recorded in
+    /// the catalog.
+    pub(crate) async fn create_replication_slot(
+        &mut self,
+        session: &Session,
+        name: String,
+        temporary: bool,
+    ) -> Result<(), CoordError> {
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        {
+            return Err(CoordError::InvalidReplicationSlotName(name));
+        }
+        if self.replication_slots.slots.contains_key(&name) {
+            return Err(CoordError::DuplicateReplicationSlot(name));
+        }
+        if !temporary {
+            self.catalog.set_replication_slot(&name, 0).await?;
+        }
+        self.replication_slots.slots.insert(
+            name,
+            ReplicationSlot {
+                temporary_owner: temporary.then(|| session.conn_id()),
+                confirmed_flush: 0,
+                active_conn: None,
+            },
+        );
+        Ok(())
+    }
+
+    /// Drops a replication slot that is not in use.
+    pub(crate) async fn drop_replication_slot(&mut self, name: &str) -> Result<(), CoordError> {
+        let slot = self.replication_slots.get_inactive(name)?;
+        if slot.temporary_owner.is_none() {
+            self.catalog.remove_replication_slot(name).await?;
+        }
+        self.replication_slots.slots.remove(name);
+        Ok(())
+    }
+
+    /// Marks a replication slot as in use by the connection of `session`, and
+    /// returns the latest LSN that a consumer of the slot has confirmed.
+    pub(crate) fn acquire_replication_slot(
+        &mut self,
+        session: &Session,
+        name: &str,
+    ) -> Result<u64, CoordError> {
+        let slot = self.replication_slots.get_inactive(name)?;
+        slot.active_conn = Some(session.conn_id());
+        Ok(slot.confirmed_flush)
+    }
+
+    /// Records that the consumer of a replication slot has flushed all changes
+    /// up to and including `lsn`.
+    pub(crate) async fn confirm_replication_slot(
+        &mut self,
+        name: &str,
+        lsn: u64,
+    ) -> Result<(), CoordError> {
+        let slot = match self.replication_slots.slots.get_mut(name) {
+            Some(slot) if lsn > slot.confirmed_flush => slot,
+            _ => return Ok(()),
+        };
+        if slot.temporary_owner.is_none() {
+            self.catalog.set_replication_slot(name, lsn).await?;
+        }
+        slot.confirmed_flush = lsn;
+        Ok(())
+    }
+
+    /// Marks a replication slot as no longer in use by the connection of
+    /// `session`.
+    pub(crate) fn release_replication_slot(&mut self, session: &Session, name: &str) {
+        if let Some(slot) = self.replication_slots.slots.get_mut(name) {
+            if slot.active_conn == Some(session.conn_id()) {
+                slot.active_conn = None;
+            }
+        }
+    }
+
+    /// Releases the replication slots in use by `conn_id` and drops the
+    /// temporary slots that it created.
+    pub(crate) fn terminate_replication_slots(&mut self, conn_id: u32) {
+        let slots = &mut self.replication_slots.slots;
+        slots.retain(|_, slot| slot.temporary_owner != Some(conn_id));
+        for slot in slots.values_mut() {
+            if slot.active_conn == Some(conn_id) {
+                slot.active_conn = None;
+            }
+        }
+    }
+}
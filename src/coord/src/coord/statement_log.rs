@@ -0,0 +1,524 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Sampled logging of executed statements.
+//!
+//! When enabled, the coordinator records a random sample of the statements
+//! that clients execute in the `mz_catalog.mz_statement_log` table, along with
+//! the timestamp and kind of plan chosen for peeks, the number of rows
+//! returned, any error, and how long the statement took. The log is kept in
+//! memory only, and so starts out empty whenever the server restarts.
+//!
+//! The log records the statements of every role, and so only superusers may
+//! read `mz_catalog.mz_all_statement_log`. Other roles read the
+//! `mz_catalog.mz_statement_log` view, which shows only their own statements.
+//!
+//! Records are retracted once their retention period elapses by a timer that
+//! runs for as long as the log is enabled, so that expired records disappear
+//! even when no further statements are sampled.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+use tokio::sync::mpsc::UnboundedSender;
+
+use mz_ore::now::{to_datetime, EpochMillis};
+use mz_ore::task;
+use mz_repr::adt::interval::Interval;
+use mz_repr::adt::numeric::Numeric;
+use mz_repr::{Datum, Diff, Row, Timestamp};
+use mz_sql::ast::display::AstDisplay;
+use mz_sql::ast::visit_mut::VisitMut;
+use mz_sql::ast::{CreateRoleOption, Raw, Statement, Value};
+use mz_stash::Append;
+
+use crate::catalog::builtin::MZ_ALL_STATEMENT_LOG;
+use crate::catalog::BuiltinTableUpdate;
+use crate::command::ExecuteResponse;
+use crate::coord::{Coordinator, Message, PeekResponseUnary};
+use crate::session::{row_batch_channel, Session};
+use crate::util::ClientTransmitter;
+
+/// The text that replaces redacted literals.
+const REDACTED: &str = "<REDACTED>";
+
+/// How often to retract the records whose retention period has elapsed.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Configures the statement log.
+#[derive(Debug, Clone)]
+pub struct StatementLogConfig {
+    /// The fraction of statements to record, between 0.0 and 1.0. A rate of
+    /// zero disables the statement log.
+    pub sample_rate: f64,
+    /// How long to retain the record of a statement after it finishes.
+    pub retention: Duration,
+    /// Whether to replace every literal in the recorded SQL text with a
+    /// placeholder. Secret values and passwords are always redacted.
+    pub redact_literals: bool,
+}
+
+impl Default for StatementLogConfig {
+    fn default() -> StatementLogConfig {
+        StatementLogConfig {
+            sample_rate: 0.0,
+            retention: Duration::from_secs(60 * 60),
+            redact_literals: false,
+        }
+    }
+}
+
+/// The kind of plan chosen to execute a peek.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanKind {
+    /// The peek was answered from a constant or from an existing arrangement,
+    /// without building a dataflow.
+    FastPath,
+    /// The peek required building a temporary dataflow.
+    Dataflow,
+}
+
+impl PlanKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PlanKind::FastPath => "fast-path",
+            PlanKind::Dataflow => "dataflow",
+        }
+    }
+}
+
+/// A sampled statement that has not yet finished executing.
+struct ActiveStatement {
+    connection_id: u32,
+    user: String,
+    sql: String,
+    began_at: EpochMillis,
+    began_instant: Instant,
+    timestamp: Option<Timestamp>,
+    plan_kind: Option<PlanKind>,
+}
+
+/// The in-memory state of the statement log.
+pub(super) struct StatementLog {
+    config: StatementLogConfig,
+    next_id: u64,
+    /// Sampled statements that are still executing, by log ID.
+    active: HashMap<u64, ActiveStatement>,
+    /// The rows of finished statements in `mz_all_statement_log`, in the order
+    /// in which the statements finished, along with the time they finished.
+    finished: VecDeque<(EpochMillis, Row)>,
+}
+
+impl StatementLog {
+    pub(super) fn new(config: StatementLogConfig) -> StatementLog {
+        StatementLog {
+            config,
+            next_id: 1,
+            active: HashMap::new(),
+            finished: VecDeque::new(),
+        }
+    }
+
+    /// Reports whether any statements are recorded.
+    fn enabled(&self) -> bool {
+        self.config.sample_rate > 0.0
+    }
+
+    /// Reports whether the next statement should be recorded.
+    fn sample(&self) -> bool {
+        self.enabled() && rand::thread_rng().gen::<f64>() < self.config.sample_rate
+    }
+
+    /// Records the timestamp and plan kind chosen for the peek performed by
+    /// the statement with log ID `id`.
+    pub(super) fn record_peek(&mut self, id: u64, timestamp: Timestamp, plan_kind: PlanKind) {
+        if let Some(statement) = self.active.get_mut(&id) {
+            statement.timestamp = Some(timestamp);
+            statement.plan_kind = Some(plan_kind);
+        }
+    }
+
+    /// Forgets the rows of statements whose retention period has elapsed as
+    /// of `now`, and returns them.
+    fn prune(&mut self, now: EpochMillis) -> Vec<Row> {
+        let mut expired = vec![];
+        let retention = u64::try_from(self.config.retention.as_millis()).unwrap_or(u64::MAX);
+        while let Some((finished_at, _)) = self.finished.front() {
+            if finished_at.saturating_add(retention) > now {
+                break;
+            }
+            let (_, row) = self.finished.pop_front().expect("known to exist");
+            expired.push(row);
+        }
+        expired
+    }
+
+    /// Records that the statement with log ID `id` finished at `now`.
+    ///
+    /// Returns the statement's row in `mz_all_statement_log`, or `None` if the
+    /// statement is unknown.
+    fn end(
+        &mut self,
+        id: u64,
+        rows_returned: Option<usize>,
+        error: Option<String>,
+        now: EpochMillis,
+    ) -> Option<Row> {
+        let statement = self.active.remove(&id)?;
+        let duration =
+            i64::try_from(statement.began_instant.elapsed().as_micros()).unwrap_or(i64::MAX);
+        let mut row = Row::default();
+        let mut packer = row.packer();
+        packer.push(Datum::Int64(
+            i64::try_from(id).expect("statement log IDs fit in an i64"),
+        ));
+        packer.push(Datum::Int32(statement.connection_id as i32));
+        packer.push(Datum::String(&statement.user));
+        packer.push(Datum::String(&statement.sql));
+        packer.push(match statement.timestamp {
+            Some(ts) => Datum::from(Numeric::from(ts)),
+            None => Datum::Null,
+        });
+        packer.push(match statement.plan_kind {
+            Some(plan_kind) => Datum::String(plan_kind.as_str()),
+            None => Datum::Null,
+        });
+        packer.push(match rows_returned {
+            Some(n) => Datum::Int64(i64::try_from(n).expect("row counts fit in an i64")),
+            None => Datum::Null,
+        });
+        packer.push(match &error {
+            Some(error) => Datum::String(error),
+            None => Datum::Null,
+        });
+        packer.push(Datum::TimestampTz(to_datetime(statement.began_at)));
+        packer.push(Datum::Interval(
+            Interval::new(0, 0, duration).expect("durations fit in an interval"),
+        ));
+        self.finished.push_back((now, row.clone()));
+        Some(row)
+    }
+}
+
+/// Renders `stmt` for the statement log.
+///
+/// Secret values and passwords are always redacted. All other literals are
+/// redacted only if `redact_literals` is set.
+fn redact(stmt: &Statement<Raw>, redact_literals: bool) -> String {
+    struct LiteralRedactor;
+
+    impl<'ast> VisitMut<'ast, Raw> for LiteralRedactor {
+        fn visit_value_mut(&mut self, value: &'ast mut Value) {
+            *value = Value::String(REDACTED.into());
+        }
+    }
+
+    let mut stmt = stmt.clone();
+    match &mut stmt {
+        Statement::CreateSecret(stmt) => LiteralRedactor.visit_expr_mut(&mut stmt.value),
+        Statement::AlterSecret(stmt) => LiteralRedactor.visit_expr_mut(&mut stmt.value),
+        Statement::CreateRole(stmt) => {
+            for option in &mut stmt.options {
+                if let CreateRoleOption::Password(Some(password)) = option {
+                    *password = REDACTED.into();
+                }
+            }
+        }
+        _ => (),
+    }
+    if redact_literals {
+        LiteralRedactor.visit_statement_mut(&mut stmt);
+    }
+    stmt.to_ast_string()
+}
+
+impl<S: Append + 'static> Coordinator<S> {
+    /// Begins recording `stmt` in the statement log, if it is sampled.
+    ///
+    /// Returns a transmitter that records the outcome of the statement when
+    /// its result is sent. If the result streams rows to the client, the
+    /// statement finishes when the last row has been produced.
+    pub(super) fn begin_statement_log(
+        &mut self,
+        session: &Session,
+        stmt: &Statement<Raw>,
+        tx: ClientTransmitter<ExecuteResponse>,
+    ) -> ClientTransmitter<ExecuteResponse> {
+        // `EXECUTE` hands its transmitter to the prepared statement it names,
+        // which is recorded in its own right.
+        if matches!(stmt, Statement::Execute(_)) || !self.statement_log.sample() {
+            return tx;
+        }
+        let now = self.now();
+        let conn_meta = match self.active_conns.get_mut(&session.conn_id()) {
+            Some(conn_meta) => conn_meta,
+            None => return tx,
+        };
+
+        let log = &mut self.statement_log;
+        let id = log.next_id;
+        log.next_id += 1;
+        log.active.insert(
+            id,
+            ActiveStatement {
+                connection_id: session.conn_id(),
+                user: session.user().into(),
+                sql: redact(stmt, log.config.redact_literals),
+                began_at: now,
+                began_instant: Instant::now(),
+                timestamp: None,
+                plan_kind: None,
+            },
+        );
+        conn_meta.statement_log_id = Some(id);
+
+        let end = StatementLogEnd {
+            id,
+            internal_cmd_tx: Some(self.internal_cmd_tx.clone()),
+        };
+        tx.with_result_hook(move |result| match result {
+            Ok(resp) => Ok(end.on_response(resp)),
+            Err(e) => {
+                end.send(None, Some(e.to_string()));
+                Err(e)
+            }
+        })
+    }
+
+    /// Records the timestamp and plan kind chosen for a peek by the statement
+    /// that the connection identified by `conn_id` is executing.
+    pub(super) fn statement_log_record_peek(
+        &mut self,
+        conn_id: u32,
+        timestamp: Timestamp,
+        plan_kind: PlanKind,
+    ) {
+        let id = self
+            .active_conns
+            .get(&conn_id)
+            .and_then(|conn_meta| conn_meta.statement_log_id);
+        if let Some(id) = id {
+            self.statement_log.record_peek(id, timestamp, plan_kind);
+        }
+    }
+
+    /// Spawns a task that periodically asks the coordinator to retract the
+    /// records whose retention period has elapsed, if the log is enabled.
+    pub(super) fn spawn_statement_log_pruner(&self) {
+        if !self.statement_log.enabled() {
+            return;
+        }
+        let internal_cmd_tx = self.internal_cmd_tx.clone();
+        task::spawn(|| "coordinator_prune_statement_log", async move {
+            let mut interval = tokio::time::interval(PRUNE_INTERVAL);
+            loop {
+                interval.tick().await;
+                // If sending fails, the main thread has shutdown.
+                if internal_cmd_tx.send(Message::StatementLogPrune).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    /// Finishes the record of the statement with log ID `id`.
+    pub(super) async fn message_statement_log_end(
+        &mut self,
+        id: u64,
+        rows_returned: Option<usize>,
+        error: Option<String>,
+    ) {
+        let now = self.now();
+        if let Some(row) = self.statement_log.end(id, rows_returned, error, now) {
+            self.send_statement_log_updates(vec![(row, 1)]).await;
+        }
+    }
+
+    /// Retracts the records whose retention period has elapsed.
+    pub(super) async fn message_statement_log_prune(&mut self) {
+        let now = self.now();
+        let updates: Vec<_> = self
+            .statement_log
+            .prune(now)
+            .into_iter()
+            .map(|row| (row, -1))
+            .collect();
+        if !updates.is_empty() {
+            self.send_statement_log_updates(updates).await;
+        }
+    }
+
+    async fn send_statement_log_updates(&mut self, updates: Vec<(Row, Diff)>) {
+        let table_id = self.catalog.resolve_builtin_table(&MZ_ALL_STATEMENT_LOG);
+        let updates = updates
+            .into_iter()
+            .map(|(row, diff)| BuiltinTableUpdate {
+                id: table_id,
+                row,
+                diff,
+            })
+            .collect();
+        self.send_builtin_table_updates(updates).await;
+    }
+}
+
+/// Reports the end of a sampled statement to the coordinator.
+///
+/// If dropped without being sent, as happens when a client abandons a result
+/// before all of its rows are produced, reports that the statement was
+/// canceled.
+struct StatementLogEnd {
+    id: u64,
+    internal_cmd_tx: Option<UnboundedSender<Message>>,
+}
+
+impl StatementLogEnd {
+    /// Arranges for the end of the statement to be reported once `resp` is
+    /// complete.
+    fn on_response(self, resp: ExecuteResponse) -> ExecuteResponse {
+        match resp {
+            ExecuteResponse::SendingRows(mut batches) => {
+                let (tx, rx) = row_batch_channel();
+                let id = self.id;
+                task::spawn(move || format!("statement_log_rows:{id}"), async move {
+                    let mut rows_returned = 0;
+                    while let Some(batch) = batches.recv().await {
+                        let error = match &batch {
+                            PeekResponseUnary::Rows(rows) => {
+                                rows_returned += rows.len();
+                                None
+                            }
+                            PeekResponseUnary::Error(e) => Some(e.clone()),
+                            PeekResponseUnary::Canceled => Some("canceled".into()),
+                        };
+                        // If the client abandons the result, dropping `self`
+                        // reports that the statement was canceled.
+                        if tx.send(batch).await.is_err() {
+                            return;
+                        }
+                        if error.is_some() {
+                            return self.send(None, error);
+                        }
+                    }
+                    self.send(Some(rows_returned), None);
+                });
+                ExecuteResponse::SendingRows(rx)
+            }
+            ExecuteResponse::CopyTo { format, resp } => ExecuteResponse::CopyTo {
+                format,
+                resp: Box::new(self.on_response(*resp)),
+            },
+            resp => {
+                self.send(None, None);
+                resp
+            }
+        }
+    }
+
+    fn send(mut self, rows_returned: Option<usize>, error: Option<String>) {
+        self.send_inner(rows_returned, error);
+    }
+
+    fn send_inner(&mut self, rows_returned: Option<usize>, error: Option<String>) {
+        if let Some(internal_cmd_tx) = self.internal_cmd_tx.take() {
+            // The coordinator may have shut down while rows were streaming, in
+            // which case there is no log left to update.
+            let _ = internal_cmd_tx.send(Message::StatementLogEnd {
+                id: self.id,
+                rows_returned,
+                error,
+            });
+        }
+    }
+}
+
+impl Drop for StatementLogEnd {
+    fn drop(&mut self) {
+        self.send_inner(None, Some("canceled".into()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use mz_sql_parser::ast::{Raw, Statement};
+    use mz_sql_parser::parser::parse_statements;
+
+    use super::{redact, ActiveStatement, StatementLog, StatementLogConfig};
+
+    fn parse(sql: &str) -> Statement<Raw> {
+        parse_statements(sql).unwrap().into_iter().next().unwrap()
+    }
+
+    #[test]
+    fn test_redact() {
+        for (sql, redact_literals, expected) in [
+            ("SELECT 1, 'a' FROM t", false, "SELECT 1, 'a' FROM t"),
+            (
+                "SELECT 1, 'a' FROM t",
+                true,
+                "SELECT '<REDACTED>', '<REDACTED>' FROM t",
+            ),
+            (
+                "CREATE SECRET s AS 'hunter2'",
+                false,
+                "CREATE SECRET s AS '<REDACTED>'",
+            ),
+            (
+                "ALTER SECRET s AS decode('aHVudGVyMg==', 'base64')",
+                false,
+                "ALTER SECRET s AS decode('<REDACTED>', '<REDACTED>')",
+            ),
+            (
+                "CREATE ROLE r LOGIN PASSWORD 'hunter2'",
+                false,
+                "CREATE ROLE r LOGIN PASSWORD '<REDACTED>'",
+            ),
+        ] {
+            assert_eq!(redact(&parse(sql), redact_literals), expected, "{}", sql);
+        }
+    }
+
+    #[test]
+    fn test_retention() {
+        let mut log = StatementLog::new(StatementLogConfig {
+            sample_rate: 1.0,
+            retention: Duration::from_millis(10),
+            redact_literals: false,
+        });
+        for id in 1..=3 {
+            log.active.insert(
+                id,
+                ActiveStatement {
+                    connection_id: 1,
+                    user: "materialize".into(),
+                    sql: "SELECT 1".into(),
+                    began_at: 0,
+                    began_instant: Instant::now(),
+                    timestamp: None,
+                    plan_kind: None,
+                },
+            );
+        }
+
+        assert!(log.end(1, Some(1), None, 5).is_some());
+        assert!(log.end(2, Some(1), None, 10).is_some());
+        assert_eq!(log.prune(14).len(), 0);
+        // The first statement's retention period has elapsed.
+        assert_eq!(log.prune(15).len(), 1);
+        assert!(log.end(3, Some(1), None, 15).is_some());
+        // Unknown statements add no rows.
+        assert!(log.end(4, None, None, 100).is_none());
+        // Expired rows are retracted without any further statements ending.
+        assert_eq!(log.prune(100).len(), 2);
+        assert!(log.finished.is_empty());
+    }
+}
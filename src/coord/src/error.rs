@@ -44,6 +44,8 @@ pub enum CoordError {
     },
     /// The cursor already exists.
     DuplicateCursor(String),
+    /// The named replication slot already exists.
+    DuplicateReplicationSlot(String),
     /// An error while evaluating an expression.
     Eval(EvalError),
     /// The specified parameter is fixed to a single specific value.
@@ -58,6 +60,8 @@ pub enum CoordError {
         existing_indexes: Vec<String>,
         source_type: RematerializedSourceType,
     },
+    /// The name of a replication slot contains an invalid character.
+    InvalidReplicationSlotName(String),
     /// The value for the specified parameter does not have the right type.
     InvalidParameterType(&'static (dyn Var + Send + Sync)),
     /// The value of the specified parameter is incorrect
@@ -78,6 +82,9 @@ pub enum CoordError {
     },
     /// The selection value for a table mutation operation refers to an invalid object.
     InvalidTableMutationSelection,
+    /// The statement waited longer than the session's `lock_timeout` to acquire
+    /// a lock.
+    LockTimeout,
     /// Expression violated a column's constraint
     ConstraintViolation(NotNullViolation),
     /// Target cluster has no replicas to service query.
@@ -86,12 +93,28 @@ pub enum CoordError {
     OperationProhibitsTransaction(String),
     /// The named operation requires an active transaction.
     OperationRequiresTransaction(String),
+    /// The session's role must own the named object to perform the operation.
+    MustBeOwner {
+        object_type: String,
+        object_name: String,
+    },
+    /// The session's role lacks a privilege on the named object that the
+    /// operation requires.
+    PermissionDenied {
+        object_type: String,
+        object_name: String,
+    },
     /// The named prepared statement already exists.
     PreparedStatementExists(String),
     /// An error occurred in the QGM stage of the optimizer.
     QGM(QGMError),
     /// The transaction is in read-only mode.
     ReadOnlyTransaction,
+    /// The named replication slot is in use by the specified connection.
+    ReplicationSlotActive {
+        name: String,
+        conn_id: u32,
+    },
     /// The specified session parameter is read-only.
     ReadOnlyParameter(&'static (dyn Var + Send + Sync)),
     /// The recursion limit of some operation was exceeded.
@@ -104,10 +127,14 @@ pub enum CoordError {
     },
     /// The specified feature is not permitted in safe mode.
     SafeModeViolation(String),
+    /// The named action may only be performed by a superuser.
+    SuperuserRequired(String),
     /// An error occurred in a SQL catalog operation.
     SqlCatalog(mz_sql::catalog::CatalogError),
     /// The transaction is in single-tail mode.
     TailOnlyTransaction,
+    /// `SET TRANSACTION` was called after the transaction executed a query.
+    TransactionModeAfterQuery,
     /// An error occurred in the MIR stage of the optimizer.
     Transform(TransformError),
     /// The specified function cannot be called
@@ -119,6 +146,8 @@ pub enum CoordError {
     UnknownCursor(String),
     /// The named role does not exist.
     UnknownLoginRole(String),
+    /// The named replication slot does not exist.
+    UnknownReplicationSlot(String),
     /// The named parameter is unknown to the system.
     UnknownParameter(String),
     UnknownPreparedStatement(String),
@@ -263,6 +292,9 @@ impl fmt::Display for CoordError {
             CoordError::DuplicateCursor(name) => {
                 write!(f, "cursor {} already exists", name.quoted())
             }
+            CoordError::DuplicateReplicationSlot(name) => {
+                write!(f, "replication slot {} already exists", name.quoted())
+            }
             CoordError::Eval(e) => e.fmt(f),
             CoordError::FixedValueParameter(p) => write!(
                 f,
@@ -279,6 +311,11 @@ impl fmt::Display for CoordError {
             } => {
                 write!(f, "Cannot re-materialize source {}", base_source)
             }
+            CoordError::InvalidReplicationSlotName(name) => write!(
+                f,
+                "replication slot name {} contains invalid character",
+                name.quoted()
+            ),
             CoordError::InvalidParameterType(p) => write!(
                 f,
                 "parameter {} requires a {} value",
@@ -305,6 +342,7 @@ impl fmt::Display for CoordError {
             CoordError::InvalidTableMutationSelection => {
                 f.write_str("invalid selection: operation may only refer to user-defined tables")
             }
+            CoordError::LockTimeout => f.write_str("canceling statement due to lock timeout"),
             CoordError::ConstraintViolation(not_null_violation) => {
                 write!(f, "{}", not_null_violation)
             }
@@ -321,11 +359,35 @@ impl fmt::Display for CoordError {
             CoordError::OperationRequiresTransaction(op) => {
                 write!(f, "{} can only be used in transaction blocks", op)
             }
+            CoordError::MustBeOwner {
+                object_type,
+                object_name,
+            } => write!(
+                f,
+                "must be owner of {} {}",
+                object_type,
+                object_name.quoted()
+            ),
+            CoordError::PermissionDenied {
+                object_type,
+                object_name,
+            } => write!(
+                f,
+                "permission denied for {} {}",
+                object_type,
+                object_name.quoted()
+            ),
             CoordError::PreparedStatementExists(name) => {
                 write!(f, "prepared statement {} already exists", name.quoted())
             }
             CoordError::QGM(e) => e.fmt(f),
             CoordError::ReadOnlyTransaction => f.write_str("transaction in read-only mode"),
+            CoordError::ReplicationSlotActive { name, conn_id } => write!(
+                f,
+                "replication slot {} is active for PID {}",
+                name.quoted(),
+                conn_id
+            ),
             CoordError::ReadOnlyParameter(p) => {
                 write!(f, "parameter {} cannot be changed", p.name().quoted())
             }
@@ -340,10 +402,16 @@ impl fmt::Display for CoordError {
             CoordError::SafeModeViolation(feature) => {
                 write!(f, "cannot create {} in safe mode", feature)
             }
+            CoordError::SuperuserRequired(action) => {
+                write!(f, "must be superuser to {}", action)
+            }
             CoordError::SqlCatalog(e) => e.fmt(f),
             CoordError::TailOnlyTransaction => {
                 f.write_str("TAIL in transactions must be the only read statement")
             }
+            CoordError::TransactionModeAfterQuery => {
+                f.write_str("SET TRANSACTION must be called before any query")
+            }
             CoordError::Transform(e) => e.fmt(f),
             CoordError::UncallableFunction { func, context } => {
                 write!(f, "cannot call {} in {}", func, context)
@@ -354,6 +422,9 @@ impl fmt::Display for CoordError {
             CoordError::UnknownLoginRole(name) => {
                 write!(f, "role {} does not exist", name.quoted())
             }
+            CoordError::UnknownReplicationSlot(name) => {
+                write!(f, "replication slot {} does not exist", name.quoted())
+            }
             CoordError::UnknownParameter(name) => {
                 write!(f, "unrecognized configuration parameter {}", name.quoted())
             }
@@ -385,6 +456,12 @@ impl From<TryFromIntError> for CoordError {
     }
 }
 
+impl From<serde_json::Error> for CoordError {
+    fn from(e: serde_json::Error) -> CoordError {
+        CoordError::Unstructured(e.into())
+    }
+}
+
 impl From<TryFromDecimalError> for CoordError {
     fn from(e: TryFromDecimalError) -> CoordError {
         CoordError::Unstructured(e.into())
@@ -41,9 +41,10 @@ mod tail;
 mod util;
 
 pub mod catalog;
+pub mod scram;
 pub mod session;
 
-pub use crate::client::{Client, ConnClient, Handle, SessionClient};
+pub use crate::client::{datum_to_json, Client, ConnClient, Handle, SessionClient};
 pub use crate::command::{Canceled, ExecuteResponse, StartupMessage, StartupResponse};
-pub use crate::coord::{serve, Config, PeekResponseUnary};
+pub use crate::coord::{serve, Config, PeekResponseUnary, PlanCacheConfig, StatementLogConfig};
 pub use crate::error::CoordError;
@@ -0,0 +1,376 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! SCRAM-SHA-256 password verifiers and authentication exchanges.
+//!
+//! Passwords are never stored. Instead, `CREATE ROLE ... PASSWORD` stores a
+//! salted verifier in the same format as PostgreSQL:
+//!
+//! ```text
+//! SCRAM-SHA-256$<iterations>:<salt>$<StoredKey>:<ServerKey>
+//! ```
+//!
+//! The server side of the exchange described in [RFC 5802] and [RFC 7677] is
+//! implemented by [`ScramServer`]. Channel binding is not supported.
+//!
+//! [RFC 5802]: https://datatracker.ietf.org/doc/html/rfc5802
+//! [RFC 7677]: https://datatracker.ietf.org/doc/html/rfc7677
+
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail};
+use openssl::base64;
+use openssl::error::ErrorStack;
+use openssl::hash::MessageDigest;
+use openssl::memcmp;
+use openssl::pkcs5;
+use openssl::pkey::PKey;
+use openssl::rand;
+use openssl::sha;
+use openssl::sign::Signer;
+
+/// The name of the only SASL mechanism we support.
+pub const SCRAM_SHA_256: &str = "SCRAM-SHA-256";
+
+/// The number of PBKDF2 iterations used for new verifiers. This matches
+/// PostgreSQL's default.
+const ITERATIONS: u32 = 4096;
+
+/// The length of the random salt used for new verifiers, in bytes.
+const SALT_LEN: usize = 16;
+
+/// The length of the random server nonce, in bytes, before base64 encoding.
+const NONCE_LEN: usize = 18;
+
+/// A salted SCRAM-SHA-256 password verifier.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ScramVerifier {
+    iterations: u32,
+    salt: Vec<u8>,
+    stored_key: [u8; 32],
+    server_key: [u8; 32],
+}
+
+impl ScramVerifier {
+    /// Creates a verifier for `password` with a fresh random salt.
+    pub fn new(password: &str) -> Result<ScramVerifier, ErrorStack> {
+        let mut salt = vec![0; SALT_LEN];
+        rand::rand_bytes(&mut salt)?;
+        ScramVerifier::with_salt(password, salt, ITERATIONS)
+    }
+
+    fn with_salt(
+        password: &str,
+        salt: Vec<u8>,
+        iterations: u32,
+    ) -> Result<ScramVerifier, ErrorStack> {
+        let salted_password = salt_password(password, &salt, iterations)?;
+        let client_key = hmac(&salted_password, b"Client Key")?;
+        let server_key = hmac(&salted_password, b"Server Key")?;
+        Ok(ScramVerifier {
+            iterations,
+            salt,
+            stored_key: sha::sha256(&client_key),
+            server_key,
+        })
+    }
+
+    /// Reports whether `password` matches this verifier.
+    ///
+    /// This is used by authentication methods that receive the password in
+    /// cleartext, like HTTP basic authentication.
+    pub fn verify_password(&self, password: &str) -> Result<bool, ErrorStack> {
+        let salted_password = salt_password(password, &self.salt, self.iterations)?;
+        let client_key = hmac(&salted_password, b"Client Key")?;
+        Ok(memcmp::eq(&sha::sha256(&client_key), &self.stored_key))
+    }
+}
+
+// Verifiers are secrets, so avoid printing them in debug output.
+impl fmt::Debug for ScramVerifier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ScramVerifier")
+            .field("iterations", &self.iterations)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for ScramVerifier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}${}:{}${}:{}",
+            SCRAM_SHA_256,
+            self.iterations,
+            base64::encode_block(&self.salt),
+            base64::encode_block(&self.stored_key),
+            base64::encode_block(&self.server_key),
+        )
+    }
+}
+
+impl FromStr for ScramVerifier {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<ScramVerifier, anyhow::Error> {
+        let malformed = || anyhow!("malformed SCRAM-SHA-256 verifier");
+        let parts: Vec<_> = s.split('$').collect();
+        let (iterations, salt, stored_key, server_key) = match parts.as_slice() {
+            [mechanism, params, keys] if *mechanism == SCRAM_SHA_256 => {
+                let (iterations, salt) = params.split_once(':').ok_or_else(malformed)?;
+                let (stored_key, server_key) = keys.split_once(':').ok_or_else(malformed)?;
+                (iterations, salt, stored_key, server_key)
+            }
+            _ => return Err(malformed()),
+        };
+        let decode_key = |key: &str| -> Result<[u8; 32], anyhow::Error> {
+            base64::decode_block(key)
+                .map_err(|_| malformed())?
+                .try_into()
+                .map_err(|_| malformed())
+        };
+        Ok(ScramVerifier {
+            iterations: iterations.parse().map_err(|_| malformed())?,
+            salt: base64::decode_block(salt).map_err(|_| malformed())?,
+            stored_key: decode_key(stored_key)?,
+            server_key: decode_key(server_key)?,
+        })
+    }
+}
+
+/// The server side of a SCRAM-SHA-256 exchange, awaiting the
+/// client-first-message.
+#[derive(Debug)]
+pub struct ScramServer {
+    verifier: ScramVerifier,
+    server_nonce: String,
+}
+
+impl ScramServer {
+    /// Starts an exchange that authenticates the client against `verifier`.
+    pub fn new(verifier: ScramVerifier) -> Result<ScramServer, ErrorStack> {
+        let mut nonce = [0; NONCE_LEN];
+        rand::rand_bytes(&mut nonce)?;
+        Ok(ScramServer::with_nonce(
+            verifier,
+            base64::encode_block(&nonce),
+        ))
+    }
+
+    fn with_nonce(verifier: ScramVerifier, server_nonce: String) -> ScramServer {
+        ScramServer {
+            verifier,
+            server_nonce,
+        }
+    }
+
+    /// Processes the client-first-message.
+    ///
+    /// Returns the state of the exchange and the server-first-message to send
+    /// to the client.
+    pub fn handle_client_first(
+        self,
+        client_first: &[u8],
+    ) -> Result<(ScramServerFirst, String), anyhow::Error> {
+        let client_first = std::str::from_utf8(client_first)
+            .map_err(|_| anyhow!("SCRAM client-first-message is not valid UTF-8"))?;
+
+        // The GS2 header is everything up to and including the second comma.
+        let mut parts = client_first.splitn(3, ',');
+        let (cbind_flag, authzid, client_first_bare) =
+            match (parts.next(), parts.next(), parts.next()) {
+                (Some(cbind_flag), Some(authzid), Some(bare)) => (cbind_flag, authzid, bare),
+                _ => bail!("malformed SCRAM client-first-message"),
+            };
+        match cbind_flag {
+            "n" | "y" => (),
+            _ if cbind_flag.starts_with("p=") => bail!("SCRAM channel binding is not supported"),
+            _ => bail!("malformed SCRAM client-first-message"),
+        }
+        if !authzid.is_empty() {
+            bail!("SCRAM authorization identities are not supported");
+        }
+        let gs2_header = &client_first[..client_first.len() - client_first_bare.len()];
+
+        // The username is ignored, as the user was already specified in the
+        // startup message.
+        let mut client_nonce = None;
+        for attr in client_first_bare.split(',') {
+            match attr.split_once('=') {
+                Some(("m", _)) => bail!("SCRAM mandatory extensions are not supported"),
+                Some(("r", nonce)) if !nonce.is_empty() => client_nonce = Some(nonce),
+                _ => (),
+            }
+        }
+        let client_nonce = match client_nonce {
+            Some(nonce) => nonce,
+            None => bail!("SCRAM client-first-message is missing nonce"),
+        };
+
+        let nonce = format!("{}{}", client_nonce, self.server_nonce);
+        let server_first = format!(
+            "r={},s={},i={}",
+            nonce,
+            base64::encode_block(&self.verifier.salt),
+            self.verifier.iterations
+        );
+        let state = ScramServerFirst {
+            verifier: self.verifier,
+            gs2_header: gs2_header.into(),
+            client_first_bare: client_first_bare.into(),
+            server_first: server_first.clone(),
+            nonce,
+        };
+        Ok((state, server_first))
+    }
+}
+
+/// The server side of a SCRAM-SHA-256 exchange, awaiting the
+/// client-final-message.
+#[derive(Debug)]
+pub struct ScramServerFirst {
+    verifier: ScramVerifier,
+    gs2_header: String,
+    client_first_bare: String,
+    server_first: String,
+    nonce: String,
+}
+
+impl ScramServerFirst {
+    /// Processes the client-final-message.
+    ///
+    /// If the client's proof is valid, returns the server-final-message to
+    /// send to the client. Otherwise returns an error, and the client must not
+    /// be authenticated.
+    pub fn handle_client_final(self, client_final: &[u8]) -> Result<String, anyhow::Error> {
+        let client_final = std::str::from_utf8(client_final)
+            .map_err(|_| anyhow!("SCRAM client-final-message is not valid UTF-8"))?;
+        let (without_proof, proof) = match client_final.rsplit_once(",p=") {
+            Some((without_proof, proof)) => (without_proof, proof),
+            None => bail!("SCRAM client-final-message is missing proof"),
+        };
+
+        let mut attrs = without_proof.split(',');
+        match attrs.next().and_then(|a| a.strip_prefix("c=")) {
+            Some(cbind) if cbind == base64::encode_block(self.gs2_header.as_bytes()) => (),
+            _ => bail!("SCRAM channel binding does not match"),
+        }
+        match attrs.next().and_then(|a| a.strip_prefix("r=")) {
+            Some(nonce) if nonce == self.nonce => (),
+            _ => bail!("SCRAM nonce does not match"),
+        }
+
+        let proof = base64::decode_block(proof).map_err(|_| anyhow!("malformed SCRAM proof"))?;
+        if proof.len() != self.verifier.stored_key.len() {
+            bail!("malformed SCRAM proof");
+        }
+        let auth_message = format!(
+            "{},{},{}",
+            self.client_first_bare, self.server_first, without_proof
+        );
+        let client_signature = hmac(&self.verifier.stored_key, auth_message.as_bytes())?;
+        let client_key: Vec<u8> = proof
+            .iter()
+            .zip(client_signature)
+            .map(|(p, s)| p ^ s)
+            .collect();
+        if !memcmp::eq(&sha::sha256(&client_key), &self.verifier.stored_key) {
+            bail!("SCRAM proof is invalid");
+        }
+
+        let server_signature = hmac(&self.verifier.server_key, auth_message.as_bytes())?;
+        Ok(format!("v={}", base64::encode_block(&server_signature)))
+    }
+}
+
+/// Computes `Hi(Normalize(password), salt, i)`.
+fn salt_password(password: &str, salt: &[u8], iterations: u32) -> Result<[u8; 32], ErrorStack> {
+    // Like PostgreSQL, fall back to the raw password if it cannot be
+    // normalized with SASLprep.
+    let password = match stringprep::saslprep(password) {
+        Ok(password) => password,
+        Err(_) => password.into(),
+    };
+    let mut salted_password = [0; 32];
+    pkcs5::pbkdf2_hmac(
+        password.as_bytes(),
+        salt,
+        usize::try_from(iterations).expect("u32 fits in usize"),
+        MessageDigest::sha256(),
+        &mut salted_password,
+    )?;
+    Ok(salted_password)
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Result<[u8; 32], ErrorStack> {
+    let key = PKey::hmac(key)?;
+    let mut signer = Signer::new(MessageDigest::sha256(), &key)?;
+    signer.update(data)?;
+    let mut out = [0; 32];
+    signer.sign(&mut out)?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The example exchange from RFC 7677, section 3.
+    const SALT: &str = "W22ZaJ0SNY7soEsUEjb6gQ==";
+    const CLIENT_FIRST: &str = "n,,n=user,r=rOprNGfwEbeRWgbNEkqO";
+    const SERVER_NONCE: &str = "%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0";
+    const SERVER_FIRST: &str =
+        "r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,s=W22ZaJ0SNY7soEsUEjb6gQ==,i=4096";
+    const CLIENT_FINAL: &str = "c=biws,r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,\
+                                p=dHzbZapWIk4jUhN+Ute9ytag9zjfMHgsqmmiz7AndVQ=";
+    const SERVER_FINAL: &str = "v=6rriTRBi23WpRR/wtup+mMhUZUn/dB5nLTJRsjl95G4=";
+
+    fn rfc_verifier() -> ScramVerifier {
+        let salt = base64::decode_block(SALT).unwrap();
+        ScramVerifier::with_salt("pencil", salt, 4096).unwrap()
+    }
+
+    #[test]
+    fn rfc7677_exchange() {
+        let server = ScramServer::with_nonce(rfc_verifier(), SERVER_NONCE.into());
+        let (server, server_first) = server.handle_client_first(CLIENT_FIRST.as_bytes()).unwrap();
+        assert_eq!(server_first, SERVER_FIRST);
+        let server_final = server.handle_client_final(CLIENT_FINAL.as_bytes()).unwrap();
+        assert_eq!(server_final, SERVER_FINAL);
+    }
+
+    #[test]
+    fn invalid_proof() {
+        let verifier = ScramVerifier::new("not pencil").unwrap();
+        let server = ScramServer::with_nonce(verifier, SERVER_NONCE.into());
+        let (server, _) = server.handle_client_first(CLIENT_FIRST.as_bytes()).unwrap();
+        assert!(server.handle_client_final(CLIENT_FINAL.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn channel_binding_unsupported() {
+        let server = ScramServer::with_nonce(rfc_verifier(), SERVER_NONCE.into());
+        assert!(server
+            .handle_client_first(b"p=tls-server-end-point,,n=,r=abc")
+            .is_err());
+    }
+
+    #[test]
+    fn verifier_roundtrip() {
+        let verifier = ScramVerifier::new("pencil").unwrap();
+        let s = verifier.to_string();
+        assert!(s.starts_with("SCRAM-SHA-256$4096:"));
+        let parsed: ScramVerifier = s.parse().unwrap();
+        assert_eq!(parsed, verifier);
+        assert!(parsed.verify_password("pencil").unwrap());
+        assert!(!parsed.verify_password("pencil2").unwrap());
+        assert!("md5abcdef".parse::<ScramVerifier>().is_err());
+    }
+}
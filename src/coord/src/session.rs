@@ -17,7 +17,7 @@ use std::mem;
 
 use chrono::{DateTime, Utc};
 use derivative::Derivative;
-use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+use tokio::sync::mpsc;
 use tokio::sync::OwnedMutexGuard;
 
 use mz_dataflow_types::client::ComputeInstanceId;
@@ -26,15 +26,14 @@ use mz_repr::{Datum, Diff, GlobalId, Row, ScalarType};
 use mz_sql::ast::{Raw, Statement, TransactionAccessMode};
 use mz_sql::plan::{Params, PlanContext, StatementDesc};
 
-use crate::command::RowsFuture;
 use crate::coord::{CoordTimestamp, PeekResponseUnary};
 use crate::error::CoordError;
 
 mod vars;
 
 pub use self::vars::{
-    ClientSeverity, Var, Vars, DEFAULT_DATABASE_NAME, SERVER_MAJOR_VERSION, SERVER_MINOR_VERSION,
-    SERVER_PATCH_VERSION,
+    ClientSeverity, IsolationLevel, Var, Vars, DEFAULT_DATABASE_NAME, SERVER_MAJOR_VERSION,
+    SERVER_MINOR_VERSION, SERVER_PATCH_VERSION,
 };
 
 const DUMMY_CONNECTION_ID: u32 = 0;
@@ -101,7 +100,11 @@ impl<T: CoordTimestamp> Session<T> {
         match self.transaction {
             TransactionStatus::Default | TransactionStatus::Started(_) => {
                 self.transaction = TransactionStatus::InTransaction(Transaction {
-                    pcx: PlanContext::new(wall_time, self.vars.qgm_optimizations()),
+                    pcx: PlanContext::new(
+                        wall_time,
+                        self.vars.qgm_optimizations(),
+                        self.vars.native_outer_joins(),
+                    ),
                     ops: TransactionOps::None,
                     write_lock_guard: None,
                     access,
@@ -121,7 +124,11 @@ impl<T: CoordTimestamp> Session<T> {
     pub fn start_transaction_implicit(mut self, wall_time: DateTime<Utc>, stmts: usize) -> Self {
         if let TransactionStatus::Default = self.transaction {
             let txn = Transaction {
-                pcx: PlanContext::new(wall_time, self.vars.qgm_optimizations()),
+                pcx: PlanContext::new(
+                    wall_time,
+                    self.vars.qgm_optimizations(),
+                    self.vars.native_outer_joins(),
+                ),
                 ops: TransactionOps::None,
                 write_lock_guard: None,
                 access: None,
@@ -169,6 +176,13 @@ impl<T: CoordTimestamp> Session<T> {
         self
     }
 
+    /// Sets the access mode of the current transaction, if any.
+    pub fn set_transaction_access(&mut self, access: TransactionAccessMode) {
+        if let Some(txn) = self.transaction.inner_mut() {
+            txn.access = Some(access);
+        }
+    }
+
     /// Returns the current transaction status.
     pub fn transaction(&self) -> &TransactionStatus<T> {
         &self.transaction
@@ -523,13 +537,32 @@ impl InProgressRows {
 }
 
 /// A channel of batched rows.
-pub type RowBatchStream = UnboundedReceiver<PeekResponseUnary>;
+///
+/// The channel holds at most [`ROW_BATCH_STREAM_CAPACITY`] batches, so that the
+/// rows are produced no faster than the client consumes them.
+pub type RowBatchStream = mpsc::Receiver<PeekResponseUnary>;
+
+/// The number of batches of rows a [`RowBatchStream`] holds.
+pub const ROW_BATCH_STREAM_CAPACITY: usize = 2;
 
-/// Converts a RowsFuture to a RowBatchStream.
-pub async fn row_future_to_stream(rows: RowsFuture) -> RowBatchStream {
-    let (tx, rx) = unbounded_channel();
-    tx.send(rows.await).expect("send must succeed");
-    rx
+/// Creates a [`RowBatchStream`] along with the sender of its batches.
+pub fn row_batch_channel() -> (mpsc::Sender<PeekResponseUnary>, RowBatchStream) {
+    mpsc::channel(ROW_BATCH_STREAM_CAPACITY)
+}
+
+/// Collects all of the batches of a RowBatchStream into a single response.
+///
+/// The response is the first error or cancellation in the stream, if any, and
+/// otherwise all of the rows of its batches.
+pub async fn collect_row_batches(mut rows: RowBatchStream) -> PeekResponseUnary {
+    let mut collected = Vec::new();
+    while let Some(batch) = rows.recv().await {
+        match batch {
+            PeekResponseUnary::Rows(rows) => collected.extend(rows),
+            PeekResponseUnary::Error(_) | PeekResponseUnary::Canceled => return batch,
+        }
+    }
+    PeekResponseUnary::Rows(collected)
 }
 
 /// The transaction status of a session.
@@ -9,12 +9,14 @@
 
 use std::borrow::Borrow;
 use std::fmt;
+use std::time::Duration;
 
 use const_format::concatcp;
 use lazy_static::lazy_static;
 use uncased::UncasedStr;
 
 use mz_ore::cast;
+use mz_sql::ast::TransactionIsolationLevel;
 use mz_sql::DEFAULT_SCHEMA;
 
 use crate::error::CoordError;
@@ -100,6 +102,14 @@ const INTEGER_DATETIMES: ServerVar<bool> = ServerVar {
     description: "Reports whether the server uses 64-bit-integer dates and times (PostgreSQL).",
 };
 
+const IDLE_IN_TRANSACTION_SESSION_TIMEOUT: ServerVar<Duration> = ServerVar {
+    name: static_uncased_str!("idle_in_transaction_session_timeout"),
+    value: &Duration::ZERO,
+    description:
+        "Sets the maximum allowed duration that a session can sit idle in a transaction before \
+         being terminated. A value of zero disables the timeout (PostgreSQL).",
+};
+
 const INTERVAL_STYLE: ServerVar<str> = ServerVar {
     // IntervalStyle has nonstandard capitalization for historical reasons.
     name: static_uncased_str!("IntervalStyle"),
@@ -107,6 +117,20 @@ const INTERVAL_STYLE: ServerVar<str> = ServerVar {
     description: "Sets the display format for interval values (PostgreSQL).",
 };
 
+const LOCK_TIMEOUT: ServerVar<Duration> = ServerVar {
+    name: static_uncased_str!("lock_timeout"),
+    value: &Duration::ZERO,
+    description: "Sets the maximum allowed duration of any wait for a lock. A value of zero \
+                  disables the timeout (PostgreSQL).",
+};
+
+const NATIVE_OUTER_JOINS: ServerVar<bool> = ServerVar {
+    name: static_uncased_str!("native_outer_joins_experimental"),
+    value: &false,
+    description: "Plans equality outer joins as dedicated outer join operators rather than as \
+                  unions of inner joins and anti-joins.",
+};
+
 const QGM_OPTIMIZATIONS: ServerVar<bool> = ServerVar {
     name: static_uncased_str!("qgm_optimizations_experimental"),
     value: &false,
@@ -149,6 +173,13 @@ const SQL_SAFE_UPDATES: ServerVar<bool> = ServerVar {
     description: "Prohibits SQL statements that may be overly destructive (CockroachDB).",
 };
 
+const STATEMENT_TIMEOUT: ServerVar<Duration> = ServerVar {
+    name: static_uncased_str!("statement_timeout"),
+    value: &Duration::ZERO,
+    description: "Sets the maximum allowed duration of any statement. A value of zero disables \
+                  the timeout (PostgreSQL).",
+};
+
 const STANDARD_CONFORMING_STRINGS: ServerVar<bool> = ServerVar {
     name: static_uncased_str!("standard_conforming_strings"),
     value: &true,
@@ -162,9 +193,9 @@ const TIMEZONE: ServerVar<TimeZone> = ServerVar {
     description: "Sets the time zone for displaying and interpreting time stamps (PostgreSQL).",
 };
 
-const TRANSACTION_ISOLATION: ServerVar<str> = ServerVar {
+const TRANSACTION_ISOLATION: ServerVar<IsolationLevel> = ServerVar {
     name: static_uncased_str!("transaction_isolation"),
-    value: "serializable",
+    value: &IsolationLevel::Serializable,
     description: "Sets the current transaction's isolation level (PostgreSQL).",
 };
 
@@ -202,16 +233,20 @@ pub struct Vars {
     date_style: ServerVar<str>,
     extra_float_digits: SessionVar<i32>,
     failpoints: ServerVar<str>,
+    idle_in_transaction_session_timeout: SessionVar<Duration>,
     integer_datetimes: ServerVar<bool>,
     interval_style: ServerVar<str>,
+    lock_timeout: SessionVar<Duration>,
+    native_outer_joins: SessionVar<bool>,
     qgm_optimizations: SessionVar<bool>,
     search_path: SessionVar<[String]>,
     server_version: ServerVar<str>,
     server_version_num: ServerVar<i32>,
     sql_safe_updates: SessionVar<bool>,
     standard_conforming_strings: ServerVar<bool>,
+    statement_timeout: SessionVar<Duration>,
     timezone: SessionVar<TimeZone>,
-    transaction_isolation: ServerVar<str>,
+    transaction_isolation: SessionVar<IsolationLevel>,
 }
 
 impl Default for Vars {
@@ -225,16 +260,22 @@ impl Default for Vars {
             date_style: DATE_STYLE,
             extra_float_digits: SessionVar::new(&EXTRA_FLOAT_DIGITS),
             failpoints: FAILPOINTS,
+            idle_in_transaction_session_timeout: SessionVar::new(
+                &IDLE_IN_TRANSACTION_SESSION_TIMEOUT,
+            ),
             integer_datetimes: INTEGER_DATETIMES,
             interval_style: INTERVAL_STYLE,
+            lock_timeout: SessionVar::new(&LOCK_TIMEOUT),
+            native_outer_joins: SessionVar::new(&NATIVE_OUTER_JOINS),
             qgm_optimizations: SessionVar::new(&QGM_OPTIMIZATIONS),
             search_path: SessionVar::new(&SEARCH_PATH),
             server_version: SERVER_VERSION,
             server_version_num: SERVER_VERSION_NUM,
             sql_safe_updates: SessionVar::new(&SQL_SAFE_UPDATES),
             standard_conforming_strings: STANDARD_CONFORMING_STRINGS,
+            statement_timeout: SessionVar::new(&STATEMENT_TIMEOUT),
             timezone: SessionVar::new(&TIMEZONE),
-            transaction_isolation: TRANSACTION_ISOLATION,
+            transaction_isolation: SessionVar::new(&TRANSACTION_ISOLATION),
         }
     }
 }
@@ -252,14 +293,18 @@ impl Vars {
             &self.date_style,
             &self.extra_float_digits,
             &self.failpoints,
+            &self.idle_in_transaction_session_timeout,
             &self.integer_datetimes,
             &self.interval_style,
+            &self.lock_timeout,
+            &self.native_outer_joins,
             &self.qgm_optimizations,
             &self.search_path,
             &self.server_version,
             &self.server_version_num,
             &self.sql_safe_updates,
             &self.standard_conforming_strings,
+            &self.statement_timeout,
             &self.timezone,
             &self.transaction_isolation,
         ]
@@ -308,10 +353,16 @@ impl Vars {
             Ok(&self.extra_float_digits)
         } else if name == FAILPOINTS.name {
             Ok(&self.failpoints)
+        } else if name == IDLE_IN_TRANSACTION_SESSION_TIMEOUT.name {
+            Ok(&self.idle_in_transaction_session_timeout)
         } else if name == INTEGER_DATETIMES.name {
             Ok(&self.integer_datetimes)
         } else if name == INTERVAL_STYLE.name {
             Ok(&self.interval_style)
+        } else if name == LOCK_TIMEOUT.name {
+            Ok(&self.lock_timeout)
+        } else if name == NATIVE_OUTER_JOINS.name {
+            Ok(&self.native_outer_joins)
         } else if name == QGM_OPTIMIZATIONS.name {
             Ok(&self.qgm_optimizations)
         } else if name == SEARCH_PATH.name {
@@ -324,6 +375,8 @@ impl Vars {
             Ok(&self.sql_safe_updates)
         } else if name == STANDARD_CONFORMING_STRINGS.name {
             Ok(&self.standard_conforming_strings)
+        } else if name == STATEMENT_TIMEOUT.name {
+            Ok(&self.statement_timeout)
         } else if name == TIMEZONE.name {
             Ok(&self.timezone)
         } else if name == TRANSACTION_ISOLATION.name {
@@ -408,6 +461,8 @@ impl Vars {
                 })?;
             }
             Ok(())
+        } else if name == IDLE_IN_TRANSACTION_SESSION_TIMEOUT.name {
+            self.idle_in_transaction_session_timeout.set(value, local)
         } else if name == INTEGER_DATETIMES.name {
             Err(CoordError::ReadOnlyParameter(&INTEGER_DATETIMES))
         } else if name == INTERVAL_STYLE.name {
@@ -417,6 +472,10 @@ impl Vars {
             } else {
                 Ok(())
             }
+        } else if name == LOCK_TIMEOUT.name {
+            self.lock_timeout.set(value, local)
+        } else if name == NATIVE_OUTER_JOINS.name {
+            self.native_outer_joins.set(value, local)
         } else if name == QGM_OPTIMIZATIONS.name {
             self.qgm_optimizations.set(value, local)
         } else if name == SEARCH_PATH.name {
@@ -437,6 +496,8 @@ impl Vars {
                     &STANDARD_CONFORMING_STRINGS,
                 )),
             }
+        } else if name == STATEMENT_TIMEOUT.name {
+            self.statement_timeout.set(value, local)
         } else if name == TIMEZONE.name {
             if let Ok(_) = TimeZone::parse(value) {
                 self.timezone.set(value, local)
@@ -448,7 +509,15 @@ impl Vars {
                 });
             }
         } else if name == TRANSACTION_ISOLATION.name {
-            Err(CoordError::ReadOnlyParameter(&TRANSACTION_ISOLATION))
+            if IsolationLevel::parse(value).is_ok() {
+                self.transaction_isolation.set(value, local)
+            } else {
+                return Err(CoordError::ConstrainedParameter {
+                    parameter: &TRANSACTION_ISOLATION,
+                    value: value.into(),
+                    valid_values: Some(IsolationLevel::valid_values()),
+                });
+            }
         } else {
             Err(CoordError::UnknownParameter(name.into()))
         }
@@ -468,16 +537,20 @@ impl Vars {
             date_style: _,
             extra_float_digits,
             failpoints: _,
+            idle_in_transaction_session_timeout,
             integer_datetimes: _,
             interval_style: _,
+            lock_timeout,
+            native_outer_joins,
             qgm_optimizations,
             search_path,
             server_version: _,
             server_version_num: _,
             sql_safe_updates,
             standard_conforming_strings: _,
+            statement_timeout,
             timezone: _,
-            transaction_isolation: _,
+            transaction_isolation,
         } = self;
         application_name.end_transaction(action);
         client_min_messages.end_transaction(action);
@@ -485,7 +558,12 @@ impl Vars {
         qgm_optimizations.end_transaction(action);
         search_path.end_transaction(action);
         extra_float_digits.end_transaction(action);
+        idle_in_transaction_session_timeout.end_transaction(action);
+        lock_timeout.end_transaction(action);
+        native_outer_joins.end_transaction(action);
         sql_safe_updates.end_transaction(action);
+        statement_timeout.end_transaction(action);
+        transaction_isolation.end_transaction(action);
     }
 
     /// Returns the value of the `application_name` configuration parameter.
@@ -523,6 +601,12 @@ impl Vars {
         *self.extra_float_digits.value()
     }
 
+    /// Returns the value of the `idle_in_transaction_session_timeout`
+    /// configuration parameter.
+    pub fn idle_in_transaction_session_timeout(&self) -> Duration {
+        *self.idle_in_transaction_session_timeout.value()
+    }
+
     /// Returns the value of the `integer_datetimes` configuration parameter.
     pub fn integer_datetimes(&self) -> bool {
         *self.integer_datetimes.value
@@ -533,6 +617,16 @@ impl Vars {
         self.interval_style.value
     }
 
+    /// Returns the value of the `lock_timeout` configuration parameter.
+    pub fn lock_timeout(&self) -> Duration {
+        *self.lock_timeout.value()
+    }
+
+    /// Returns the value of the `native_outer_joins` configuration parameter.
+    pub fn native_outer_joins(&self) -> bool {
+        *self.native_outer_joins.value()
+    }
+
     /// Returns the value of the `qgm_optimizations` configuration parameter.
     pub fn qgm_optimizations(&self) -> bool {
         *self.qgm_optimizations.value()
@@ -568,6 +662,11 @@ impl Vars {
         *self.standard_conforming_strings.value
     }
 
+    /// Returns the value of the `statement_timeout` configuration parameter.
+    pub fn statement_timeout(&self) -> Duration {
+        *self.statement_timeout.value()
+    }
+
     /// Returns the value of the `timezone` configuration parameter.
     pub fn timezone(&self) -> &TimeZone {
         self.timezone.value()
@@ -575,8 +674,8 @@ impl Vars {
 
     /// Returns the value of the `transaction_isolation` configuration
     /// parameter.
-    pub fn transaction_isolation(&self) -> &'static str {
-        self.transaction_isolation.value
+    pub fn transaction_isolation(&self) -> &IsolationLevel {
+        self.transaction_isolation.value()
     }
 }
 
@@ -787,6 +886,52 @@ impl Value for [String] {
     }
 }
 
+impl Value for Duration {
+    const TYPE_NAME: &'static str = "duration";
+
+    /// Parses a duration in the format of PostgreSQL time-valued parameters.
+    ///
+    /// A duration is an integer, optionally followed by one of the units `us`,
+    /// `ms`, `s`, `min`, `h`, or `d`. If no unit is specified, the integer is
+    /// interpreted as milliseconds.
+    fn parse(s: &str) -> Result<Duration, ()> {
+        let s = s.trim();
+        let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let n: u64 = s[..split].parse().map_err(|_| ())?;
+        let (unit, scale): (fn(u64) -> Duration, u64) = match s[split..].trim_start() {
+            "us" => (Duration::from_micros, 1),
+            "ms" | "" => (Duration::from_millis, 1),
+            "s" => (Duration::from_secs, 1),
+            "min" => (Duration::from_secs, 60),
+            "h" => (Duration::from_secs, 60 * 60),
+            "d" => (Duration::from_secs, 60 * 60 * 24),
+            _ => return Err(()),
+        };
+        Ok(unit(n.checked_mul(scale).ok_or(())?))
+    }
+
+    /// Formats a duration using the largest unit that represents it exactly.
+    fn format(&self) -> String {
+        const UNITS: [(&str, u128); 6] = [
+            ("d", 24 * 60 * 60 * 1_000_000),
+            ("h", 60 * 60 * 1_000_000),
+            ("min", 60 * 1_000_000),
+            ("s", 1_000_000),
+            ("ms", 1_000),
+            ("us", 1),
+        ];
+        let micros = self.as_micros();
+        if micros == 0 {
+            return "0".into();
+        }
+        let (unit, scale) = UNITS
+            .iter()
+            .find(|(_, scale)| micros % scale == 0)
+            .expect("every duration is a multiple of one microsecond");
+        format!("{}{}", micros / scale, unit)
+    }
+}
+
 /// Severity levels can used to be used to filter which messages get sent
 /// to a client.
 ///
@@ -888,6 +1033,76 @@ impl Value for ClientSeverity {
     }
 }
 
+/// The isolation level of a transaction.
+///
+/// Lower PostgreSQL isolation levels, like `READ COMMITTED`, are accepted but
+/// treated as `SERIALIZABLE`, as serializability meets the guarantees that they
+/// require.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum IsolationLevel {
+    /// Transactions appear to execute in some total order. Reads are served at
+    /// the latest timestamp at which all of their inputs are complete, so a
+    /// read may not reflect writes to sources that completed before it began.
+    Serializable,
+    /// Transactions appear to execute in some total order that is consistent
+    /// with real time. Reads are served at the current timestamp of the
+    /// timeline and wait for their inputs to catch up to it.
+    StrictSerializable,
+}
+
+impl IsolationLevel {
+    /// Returns the name of the isolation level, as reported by the
+    /// `transaction_isolation` configuration parameter.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IsolationLevel::Serializable => "serializable",
+            IsolationLevel::StrictSerializable => "strict serializable",
+        }
+    }
+
+    fn valid_values() -> Vec<&'static str> {
+        vec![
+            IsolationLevel::Serializable.as_str(),
+            IsolationLevel::StrictSerializable.as_str(),
+        ]
+    }
+}
+
+impl From<TransactionIsolationLevel> for IsolationLevel {
+    fn from(level: TransactionIsolationLevel) -> IsolationLevel {
+        match level {
+            TransactionIsolationLevel::ReadUncommitted
+            | TransactionIsolationLevel::ReadCommitted
+            | TransactionIsolationLevel::RepeatableRead
+            | TransactionIsolationLevel::Serializable => IsolationLevel::Serializable,
+            TransactionIsolationLevel::StrictSerializable => IsolationLevel::StrictSerializable,
+        }
+    }
+}
+
+impl Value for IsolationLevel {
+    const TYPE_NAME: &'static str = "string";
+
+    fn parse(s: &str) -> Result<Self::Owned, ()> {
+        let s = UncasedStr::new(s.trim());
+        if s == "read uncommitted"
+            || s == "read committed"
+            || s == "repeatable read"
+            || s == IsolationLevel::Serializable.as_str()
+        {
+            Ok(IsolationLevel::Serializable)
+        } else if s == IsolationLevel::StrictSerializable.as_str() {
+            Ok(IsolationLevel::StrictSerializable)
+        } else {
+            Err(())
+        }
+    }
+
+    fn format(&self) -> String {
+        self.as_str().into()
+    }
+}
+
 /// List of valid time zones.
 ///
 /// Names are following the tz database, but only time zones equivalent
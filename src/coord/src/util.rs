@@ -15,10 +15,14 @@ use crate::coord::Message;
 use crate::error::CoordError;
 use crate::session::Session;
 
+/// A function applied to a result before it is sent to the client.
+type ResultHook<T> = Box<dyn FnOnce(Result<T, CoordError>) -> Result<T, CoordError> + Send>;
+
 /// Handles responding to clients.
 pub struct ClientTransmitter<T> {
     tx: Option<oneshot::Sender<Response<T>>>,
     internal_cmd_tx: UnboundedSender<Message>,
+    result_hook: Option<ResultHook<T>>,
 }
 
 impl<T> ClientTransmitter<T> {
@@ -30,12 +34,30 @@ impl<T> ClientTransmitter<T> {
         ClientTransmitter {
             tx: Some(tx),
             internal_cmd_tx,
+            result_hook: None,
         }
     }
 
+    /// Arranges for `hook` to be applied to the result before it is sent to
+    /// the client. Any previously installed hook is replaced.
+    ///
+    /// The hook is not applied if the underlying sender is extracted with
+    /// [`ClientTransmitter::take`].
+    pub fn with_result_hook<F>(mut self, hook: F) -> ClientTransmitter<T>
+    where
+        F: FnOnce(Result<T, CoordError>) -> Result<T, CoordError> + Send + 'static,
+    {
+        self.result_hook = Some(Box::new(hook));
+        self
+    }
+
     /// Transmits `result` to the client, returning ownership of the session
     /// `session` as well.
     pub fn send(mut self, result: Result<T, CoordError>, session: Session) {
+        let result = match self.result_hook.take() {
+            Some(hook) => hook(result),
+            None => result,
+        };
         // If we were not able to send a message, we must clean up the session
         // ourselves. Return it to the caller for disposal.
         if let Err(res) = self.tx.take().unwrap().send(Response { result, session }) {
@@ -22,6 +22,7 @@ use std::sync::Arc;
 
 use tempfile::TempDir;
 
+use mz_coord::catalog::builtin::MZ_SYSTEM;
 use mz_coord::catalog::{Catalog, CatalogItem, Op, Table, SYSTEM_CONN_ID};
 use mz_coord::session::{Session, DEFAULT_DATABASE_NAME};
 use mz_ore::now::NOW_ZERO;
@@ -78,6 +79,7 @@ async fn datadriven() {
                             .clone();
                         catalog
                             .transact(
+                                None,
                                 vec![Op::CreateItem {
                                     id,
                                     oid,
@@ -95,6 +97,7 @@ async fn datadriven() {
                                         conn_id: None,
                                         depends_on: vec![],
                                     }),
+                                    owner_id: MZ_SYSTEM.id,
                                 }],
                                 |_| Ok(()),
                             )
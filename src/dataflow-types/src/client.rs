@@ -35,7 +35,7 @@ use mz_repr::{GlobalId, Row};
 
 use crate::logging::LoggingConfig;
 use crate::{
-    sources::{MzOffset, SourceDesc},
+    sources::{MzOffset, SourceCheckpoint, SourceDesc},
     DataflowDescription, PeekResponse, Plan, SourceInstanceDesc, TailResponse, Update,
 };
 
@@ -93,8 +93,9 @@ pub enum ConcreteComputeInstanceReplicaConfig {
 pub struct Peek<T = mz_repr::Timestamp> {
     /// The identifier of the arrangement.
     pub id: GlobalId,
-    /// An optional key that should be used for the arrangement.
-    pub key: Option<Row>,
+    /// If present, the keys of the arrangement to read, rather than reading
+    /// every key.
+    pub literal_constraints: Option<Vec<Row>>,
     /// The identifier of this peek request.
     ///
     /// Used in responses and cancellation requests.
@@ -106,17 +107,29 @@ pub struct Peek<T = mz_repr::Timestamp> {
     pub finishing: RowSetFinishing,
     /// Linear operation to apply in-line on each result.
     pub map_filter_project: mz_expr::SafeMfpPlan,
+    /// Whether to respond with statistics about the dataflow that maintains
+    /// the arrangement, rather than with its contents.
+    ///
+    /// When set, `literal_constraints`, `finishing`, and `map_filter_project` are ignored and
+    /// the response contains one row per plan node of the dataflow, as
+    /// described by [`crate::PlanNodeStatistics`].
+    pub analyze: bool,
 }
 
 impl From<&Peek> for ProtoPeek {
     fn from(x: &Peek) -> Self {
         ProtoPeek {
             id: Some((&x.id).into()),
-            key: x.key.as_ref().map(Into::into),
+            literal_constraints: x.literal_constraints.as_ref().map(|rows| {
+                proto_peek::ProtoLiteralConstraints {
+                    rows: rows.iter().map(Into::into).collect(),
+                }
+            }),
             uuid: Some(x.uuid.into_proto()),
             timestamp: x.timestamp,
             finishing: Some((&x.finishing).into()),
             map_filter_project: Some((&x.map_filter_project).into()),
+            analyze: x.analyze,
         }
     }
 }
@@ -127,7 +140,15 @@ impl TryFrom<ProtoPeek> for Peek {
     fn try_from(x: ProtoPeek) -> Result<Self, Self::Error> {
         Ok(Self {
             id: x.id.try_into_if_some("ProtoPeek::id")?,
-            key: x.key.map(|x| x.try_into()).transpose()?,
+            literal_constraints: x
+                .literal_constraints
+                .map(|x| {
+                    x.rows
+                        .into_iter()
+                        .map(TryFrom::try_from)
+                        .collect::<Result<_, _>>()
+                })
+                .transpose()?,
             uuid: Uuid::from_proto(
                 x.uuid
                     .ok_or_else(|| TryFromProtoError::missing_field("ProtoPeek::uuid"))?,
@@ -137,6 +158,7 @@ impl TryFrom<ProtoPeek> for Peek {
             map_filter_project: x
                 .map_filter_project
                 .try_into_if_some("ProtoPeek::map_filter_project")?,
+            analyze: x.analyze,
         })
     }
 }
@@ -174,6 +196,16 @@ pub enum ComputeCommand<T = mz_repr::Timestamp> {
         /// The identifiers of the peek requests to cancel.
         uuids: BTreeSet<Uuid>,
     },
+    /// Allow the peeks associated with the given `uuids` to send another
+    /// `PeekResponse::Batch` each.
+    ///
+    /// A peek sends a batch only when it has not already sent one that is yet
+    /// to be continued, so that peeks advance no faster than their batches are
+    /// consumed.
+    ContinuePeeks {
+        /// The identifiers of the peek requests to continue.
+        uuids: BTreeSet<Uuid>,
+    },
 }
 
 impl From<&ComputeCommand<mz_repr::Timestamp>> for ProtoComputeCommand {
@@ -206,6 +238,9 @@ impl From<&ComputeCommand<mz_repr::Timestamp>> for ProtoComputeCommand {
                 ComputeCommand::CancelPeeks { uuids } => CancelPeeks(ProtoCancelPeeks {
                     uuids: uuids.into_iter().map(|id| id.into_proto()).collect(),
                 }),
+                ComputeCommand::ContinuePeeks { uuids } => ContinuePeeks(ProtoContinuePeeks {
+                    uuids: uuids.into_iter().map(|id| id.into_proto()).collect(),
+                }),
             }),
         }
     }
@@ -252,6 +287,14 @@ impl TryFrom<ProtoComputeCommand> for ComputeCommand<mz_repr::Timestamp> {
                     .map(Uuid::from_proto)
                     .collect::<Result<BTreeSet<_>, _>>()?,
             }),
+            Some(ContinuePeeks(ProtoContinuePeeks { uuids })) => {
+                Ok(ComputeCommand::ContinuePeeks {
+                    uuids: uuids
+                        .into_iter()
+                        .map(Uuid::from_proto)
+                        .collect::<Result<BTreeSet<_>, _>>()?,
+                })
+            }
             None => Err(TryFromProtoError::missing_field(
                 "ProtoComputeCommand::kind",
             )),
@@ -290,6 +333,11 @@ impl Arbitrary for ComputeCommand<mz_repr::Timestamp> {
                 ComputeCommand::CancelPeeks {
                     uuids: BTreeSet::from_iter(uuids.into_iter()),
                 }
+            }),
+            proptest::collection::vec(any_uuid(), 1..6).prop_map(|uuids| {
+                ComputeCommand::ContinuePeeks {
+                    uuids: BTreeSet::from_iter(uuids.into_iter()),
+                }
             })
         ]
         .boxed()
@@ -307,6 +355,8 @@ pub struct CreateSourceCommand<T> {
     pub since: Antichain<T>,
     /// Any previously stored timestamp bindings
     pub ts_bindings: Vec<(PartitionId, T, crate::sources::MzOffset)>,
+    /// Any previously stored checkpoints from which partitions can resume reading
+    pub checkpoints: Vec<(PartitionId, SourceCheckpoint)>,
     /// Additional storage controller metadata needed to ingest this source
     pub storage_metadata: CollectionMetadata,
 }
@@ -446,6 +496,10 @@ impl<T: timely::progress::Timestamp> ComputeCommandHistory<T> {
                     uuids.retain(|uuid| peeks.contains(uuid));
                     live_cancels.extend(uuids);
                 }
+                ComputeCommand::ContinuePeeks { .. } => {
+                    // Continuations only matter to the replica whose batches
+                    // were consumed, and a replayed peek starts afresh.
+                }
             }
         }
 
@@ -554,6 +608,9 @@ pub struct TimestampBindingFeedback<T = mz_repr::Timestamp> {
     pub changes: Vec<(GlobalId, ChangeBatch<T>)>,
     /// Timestamp bindings for all of those frontier changes
     pub bindings: Vec<(GlobalId, Vec<(PartitionId, T, MzOffset)>)>,
+    /// The latest checkpoints covered by those timestamp bindings, for sources that produce
+    /// checkpoints
+    pub checkpoints: Vec<(GlobalId, Vec<(PartitionId, SourceCheckpoint)>)>,
 }
 
 /// Responses that the controller can provide back to the coordinator.
@@ -578,6 +635,29 @@ pub enum ComputeResponse<T = mz_repr::Timestamp> {
     PeekResponse(Uuid, PeekResponse),
     /// The worker's next response to a specified tail.
     TailResponse(GlobalId, TailResponse<T>),
+    /// Statistics about the contents of the identified arrangements.
+    ArrangementStatistics(Vec<(GlobalId, ArrangementStatistics)>),
+}
+
+/// Statistics about the contents of an arrangement.
+///
+/// These are estimates intended to inform query planning, and are not exact.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArrangementStatistics {
+    /// The number of updates in the arrangement's batches.
+    ///
+    /// Updates are not consolidated across batches, so this is an upper
+    /// bound on the number of records in the arrangement.
+    pub records: u64,
+    /// The number of distinct keys in the arrangement.
+    pub keys: u64,
+}
+
+impl std::ops::AddAssign for ArrangementStatistics {
+    fn add_assign(&mut self, other: Self) {
+        self.records += other.records;
+        self.keys += other.keys;
+    }
 }
 
 /// Responses that the storage nature of a worker/dataflow can provide back to the coordinator.
@@ -68,6 +68,10 @@ pub struct ClusterReplicaSizeConfig {
     pub cpu_limit: Option<CpuLimit>,
     pub scale: NonZeroUsize,
     pub workers: NonZeroUsize,
+    /// The memory each process may devote to arrangements before spilling
+    /// them to disk. Arrangements never spill if unset.
+    #[serde(default)]
+    pub arrangement_memory_budget: Option<MemoryLimit>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -97,6 +101,7 @@ impl Default for ClusterReplicaSizeMap {
                         cpu_limit: None,
                         scale: NonZeroUsize::new(1).unwrap(),
                         workers: NonZeroUsize::new(workers).unwrap(),
+                        arrangement_memory_budget: None,
                     },
                 )
             })
@@ -108,6 +113,7 @@ impl Default for ClusterReplicaSizeMap {
                 cpu_limit: None,
                 scale: NonZeroUsize::new(2).unwrap(),
                 workers: NonZeroUsize::new(1).unwrap(),
+                arrangement_memory_budget: None,
             },
         );
         inner.insert(
@@ -117,6 +123,7 @@ impl Default for ClusterReplicaSizeMap {
                 cpu_limit: None,
                 scale: NonZeroUsize::new(2).unwrap(),
                 workers: NonZeroUsize::new(2).unwrap(),
+                arrangement_memory_budget: None,
             },
         );
         inner.insert(
@@ -126,6 +133,7 @@ impl Default for ClusterReplicaSizeMap {
                 cpu_limit: None,
                 scale: NonZeroUsize::new(2).unwrap(),
                 workers: NonZeroUsize::new(4).unwrap(),
+                arrangement_memory_budget: None,
             },
         );
         Self(inner)
@@ -239,6 +247,12 @@ where
                                     if *linger {
                                         compute_opts.push(format!("--linger"));
                                     }
+                                    if let Some(budget) = size_config.arrangement_memory_budget {
+                                        compute_opts.push(format!(
+                                            "--arrangement-memory-budget={}",
+                                            budget.0.as_u64()
+                                        ));
+                                    }
                                     compute_opts
                                 },
                                 ports: vec![
@@ -388,10 +402,13 @@ where
                                 .await?;
                         }
                         ComputeResponse::PeekResponse(uuid, response) => {
-                            self.compute_mut(instance)
-                                .expect("Reference to absent instance")
-                                .remove_peeks(std::iter::once(uuid))
-                                .await?;
+                            // The peek holds back compaction until its final response.
+                            if response.is_final() {
+                                self.compute_mut(instance)
+                                    .expect("Reference to absent instance")
+                                    .remove_peeks(std::iter::once(uuid))
+                                    .await?;
+                            }
                             return Ok(Some(ControllerResponse::PeekResponse(uuid, response)));
                         }
                         ComputeResponse::TailResponse(global_id, response) => {
@@ -413,6 +430,11 @@ where
                                 .await?;
                             return Ok(Some(ControllerResponse::TailResponse(global_id, response)));
                         }
+                        ComputeResponse::ArrangementStatistics(statistics) => {
+                            self.compute_mut(instance)
+                                .expect("Reference to absent instance")
+                                .update_statistics(statistics);
+                        }
                     }
                 }
                 response = self.storage_controller.recv(), if storage_alive => {
@@ -34,7 +34,7 @@ use uuid::Uuid;
 
 use crate::client::controller::storage::{StorageController, StorageError};
 use crate::client::replicated::ActiveReplication;
-use crate::client::{ComputeClient, ComputeCommand, ComputeInstanceId};
+use crate::client::{ArrangementStatistics, ComputeClient, ComputeCommand, ComputeInstanceId};
 use crate::client::{GenericClient, Peek};
 use crate::logging::LoggingConfig;
 use crate::{DataflowDescription, SourceInstanceDesc};
@@ -396,11 +396,12 @@ where
     pub async fn peek(
         &mut self,
         id: GlobalId,
-        key: Option<Row>,
+        literal_constraints: Option<Vec<Row>>,
         uuid: Uuid,
         timestamp: T,
         finishing: RowSetFinishing,
         map_filter_project: mz_expr::SafeMfpPlan,
+        analyze: bool,
     ) -> Result<(), ComputeError> {
         let since = self.as_ref().collection(id)?.read_capabilities.frontier();
 
@@ -418,11 +419,12 @@ where
             .client
             .send(ComputeCommand::Peek(Peek {
                 id,
-                key,
+                literal_constraints,
                 uuid,
                 timestamp,
                 finishing,
                 map_filter_project,
+                analyze,
             }))
             .await
             .map_err(ComputeError::from)
@@ -438,6 +440,15 @@ where
             .await
             .map_err(ComputeError::from)
     }
+    /// Allows existing peek requests to send further batches of rows, once
+    /// for each batch that has been consumed.
+    pub async fn continue_peeks(&mut self, uuids: BTreeSet<Uuid>) -> Result<(), ComputeError> {
+        self.compute
+            .client
+            .send(ComputeCommand::ContinuePeeks { uuids })
+            .await
+            .map_err(ComputeError::from)
+    }
 
     /// Downgrade the read capabilities of specific identifiers to specific frontiers.
     ///
@@ -567,6 +578,18 @@ where
         Ok(())
     }
 
+    /// Records the most recently reported statistics for arrangements.
+    ///
+    /// Statistics for collections that no longer exist are ignored, as they
+    /// may be reported after the collection has been dropped.
+    pub(super) fn update_statistics(&mut self, updates: Vec<(GlobalId, ArrangementStatistics)>) {
+        for (id, statistics) in updates {
+            if let Ok(collection) = self.collection_mut(id) {
+                collection.statistics = Some(statistics);
+            }
+        }
+    }
+
     /// Applies `updates`, propagates consequences through other read capabilities, and sends an appropriate compaction command.
     pub(super) async fn update_read_capabilities(
         &mut self,
@@ -676,6 +699,10 @@ pub struct CollectionState<T> {
     /// write capabilities of others. All future writes will have times greater than or
     /// equal to `upper_frontier.frontier()`.
     pub write_frontier: MutableAntichain<T>,
+
+    /// The most recently reported statistics about the collection's
+    /// arrangement, if any have been reported.
+    pub statistics: Option<ArrangementStatistics>,
 }
 
 impl<T: Timestamp> CollectionState<T> {
@@ -694,6 +721,7 @@ impl<T: Timestamp> CollectionState<T> {
             storage_dependencies,
             compute_dependencies,
             write_frontier: MutableAntichain::new_bottom(Timestamp::minimum()),
+            statistics: None,
         }
     }
 
@@ -43,14 +43,14 @@ use mz_persist_types::Codec64;
 use mz_repr::Diff;
 use mz_repr::GlobalId;
 use mz_repr::Row;
-use mz_stash::{self, Stash, StashError};
+use mz_stash::{self, Append, Stash, StashError};
 
 use crate::client::controller::ReadPolicy;
 use crate::client::{
     CreateSourceCommand, MzOffset, StorageClient, StorageCommand, StorageResponse,
     TimestampBindingFeedback,
 };
-use crate::sources::SourceDesc;
+use crate::sources::{SourceCheckpoint, SourceDesc};
 use crate::Update;
 
 include!(concat!(
@@ -372,13 +372,45 @@ where
                 prev_offset.offset += diff;
             }
 
+            let checkpoint_collection = self
+                .state
+                .stash
+                .collection::<PartitionId, SourceCheckpoint>(&format!("source-checkpoints-{id}"))
+                .await?;
+            let mut checkpoint_updates: Vec<_> = self
+                .state
+                .stash
+                .iter(checkpoint_collection)
+                .await?
+                .into_iter()
+                .map(|(data, _time, diff)| (data, diff))
+                .collect();
+            differential_dataflow::consolidation::consolidate(&mut checkpoint_updates);
+            let last_checkpoints: HashMap<_, _> = checkpoint_updates
+                .into_iter()
+                .map(|(data, diff)| {
+                    assert_eq!(diff, 1, "unexpected checkpoint multiplicity");
+                    data
+                })
+                .collect();
+
             let read_handle = read_handle.map(|read| {
                 Box::new(ReadHandleWrapper { read_handle: read })
                     as Box<dyn CollectionReadHandle<T>>
             });
 
-            let collection_state =
-                CollectionState::new(desc.clone(), since.clone(), read_handle, last_bindings);
+            let checkpoints = last_checkpoints
+                .iter()
+                .map(|(pid, checkpoint)| (pid.clone(), checkpoint.clone()))
+                .collect();
+
+            let collection_state = CollectionState::new(
+                desc.clone(),
+                since.clone(),
+                read_handle,
+                last_bindings,
+                last_checkpoints,
+            );
             self.state.collections.insert(id, collection_state);
 
             let storage_metadata = self.collection_metadata(id)?;
@@ -388,6 +420,7 @@ where
                 desc,
                 since,
                 ts_bindings,
+                checkpoints,
                 storage_metadata,
             };
 
@@ -535,6 +568,53 @@ where
                 .await?;
         }
 
+        // Checkpoints are only meaningful together with the bindings that cover them, so we
+        // record them after the bindings above. Only the latest checkpoint of each partition is
+        // retained.
+        let mut checkpoint_batches = vec![];
+        let mut checkpoint_collections = vec![];
+        for (id, checkpoints) in &feedback.checkpoints {
+            if checkpoints.is_empty() {
+                continue;
+            }
+            let checkpoint_collection = self
+                .state
+                .stash
+                .collection::<PartitionId, SourceCheckpoint>(&format!("source-checkpoints-{id}"))
+                .await?;
+            let mut batch = checkpoint_collection
+                .make_batch(&mut self.state.stash)
+                .await?;
+            let collection_state = self.collection_mut(*id).expect("missing source id");
+            for (pid, checkpoint) in checkpoints {
+                let prev = collection_state
+                    .last_reported_checkpoints
+                    .insert(pid.clone(), checkpoint.clone());
+                if prev.as_ref() == Some(checkpoint) {
+                    continue;
+                }
+                if let Some(prev) = prev {
+                    checkpoint_collection.append_to_batch(&mut batch, pid, &prev, -1);
+                }
+                checkpoint_collection.append_to_batch(&mut batch, pid, checkpoint, 1);
+            }
+            checkpoint_batches.push(batch);
+            checkpoint_collections.push(checkpoint_collection);
+        }
+        if !checkpoint_batches.is_empty() {
+            self.state.stash.append(checkpoint_batches).await?;
+            let mut compactions = vec![];
+            for collection in checkpoint_collections {
+                let upper = self.state.stash.upper(collection).await?;
+                compactions.push((collection, upper));
+            }
+            self.state.stash.compact_batch(&compactions).await?;
+            self.state
+                .stash
+                .consolidate_batch(&compactions.iter().map(|(c, _)| *c).collect::<Vec<_>>())
+                .await?;
+        }
+
         let mut durability_updates = vec![];
         let mut seals = vec![];
         for (id, _changes) in &feedback.changes {
@@ -754,6 +834,10 @@ pub struct CollectionState<T> {
     /// This is used to differentialize timestamp bindings received before storing them in stash
     pub(super) last_reported_ts_bindings: HashMap<PartitionId, MzOffset>,
 
+    /// The last reported checkpoint of each partition, if any.
+    /// This is used to retract checkpoints from the stash once they are superseded
+    pub(super) last_reported_checkpoints: HashMap<PartitionId, SourceCheckpoint>,
+
     /// A `ReadHandle` for the backing persist shard/collection. This internally holds back the
     /// since frontier and we need to downgrade that when the read capabilities change.
     // TODO(aljoscha): Once all sources are wired up to go through persist/STORAGE, this will stop
@@ -768,6 +852,7 @@ impl<T: Timestamp> CollectionState<T> {
         since: Antichain<T>,
         read_handle: Option<Box<dyn CollectionReadHandle<T>>>,
         last_reported_ts_bindings: HashMap<PartitionId, MzOffset>,
+        last_reported_checkpoints: HashMap<PartitionId, SourceCheckpoint>,
     ) -> Self {
         let mut read_capabilities = MutableAntichain::new();
         read_capabilities.update_iter(since.iter().map(|time| (time.clone(), 1)));
@@ -778,6 +863,7 @@ impl<T: Timestamp> CollectionState<T> {
             read_policy: ReadPolicy::ValidFrom(since),
             write_frontier: MutableAntichain::new_bottom(Timestamp::minimum()),
             last_reported_ts_bindings,
+            last_reported_checkpoints,
             read_handle,
         }
     }
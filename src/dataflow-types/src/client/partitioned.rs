@@ -10,7 +10,7 @@
 //! Clients whose implementation is partitioned across a set of subclients
 //! (e.g. timely workers).
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, VecDeque};
 use std::fmt;
 use std::iter;
 
@@ -26,7 +26,8 @@ use mz_ore::cast::CastFrom;
 use mz_repr::{Diff, GlobalId, Row};
 
 use crate::client::{
-    ComputeCommand, ComputeResponse, GenericClient, PeekResponse, StorageCommand, StorageResponse,
+    ArrangementStatistics, ComputeCommand, ComputeResponse, GenericClient, PeekResponse,
+    StorageCommand, StorageResponse,
 };
 use crate::{DataflowDescription, TailResponse};
 
@@ -261,9 +262,14 @@ pub struct PartitionedComputeState<T> {
     uppers: HashMap<GlobalId, MutableAntichain<T>>,
     /// Pending responses for a peek; returnable once all are available.
     peek_responses: HashMap<Uuid, HashMap<usize, PeekResponse>>,
+    /// The parts that sent each forwarded batch of a peek that is yet to be
+    /// continued, in the order the batches were forwarded.
+    peek_batches: HashMap<Uuid, VecDeque<usize>>,
     /// Tracks in-progress `TAIL`s, and the stashed rows we are holding
     /// back until their timestamps are complete.
     pending_tails: HashMap<GlobalId, Option<(MutableAntichain<T>, Vec<(T, Row, Diff)>)>>,
+    /// The most recently reported arrangement statistics from each part.
+    statistics: HashMap<GlobalId, Vec<ArrangementStatistics>>,
 }
 
 impl<T> Partitionable<ComputeCommand<T>, ComputeResponse<T>>
@@ -278,7 +284,9 @@ where
             parts,
             uppers: HashMap::new(),
             peek_responses: HashMap::new(),
+            peek_batches: HashMap::new(),
             pending_tails: HashMap::new(),
+            statistics: HashMap::new(),
         }
     }
 }
@@ -292,11 +300,15 @@ where
             parts: _,
             uppers,
             peek_responses,
+            peek_batches,
             pending_tails,
+            statistics,
         } = self;
         uppers.clear();
         peek_responses.clear();
+        peek_batches.clear();
         pending_tails.clear();
+        statistics.clear();
     }
 
     /// Observes commands that move past, and prepares state for responses.
@@ -326,6 +338,7 @@ where
             if previous.is_none() {
                 debug!("Protocol error: ceasing frontier tracking for absent identifier {:?} due to command {:?}", id, command);
             }
+            self.statistics.remove(&id);
         }
     }
 }
@@ -377,6 +390,24 @@ where
                     .map(ComputeCommand::CreateDataflows)
                     .collect()
             }
+            ComputeCommand::ContinuePeeks { uuids } => {
+                // Batches are consumed in the order they were forwarded, and
+                // each continues the part that sent it.
+                let mut uuids_parts = vec![BTreeSet::new(); self.parts];
+                for uuid in uuids {
+                    if let Some(part) = self
+                        .peek_batches
+                        .get_mut(&uuid)
+                        .and_then(|parts| parts.pop_front())
+                    {
+                        uuids_parts[part].insert(uuid);
+                    }
+                }
+                uuids_parts
+                    .into_iter()
+                    .map(|uuids| ComputeCommand::ContinuePeeks { uuids })
+                    .collect()
+            }
             command => vec![command; self.parts],
         }
     }
@@ -415,6 +446,17 @@ where
                     Some(Ok(ComputeResponse::FrontierUppers(list)))
                 }
             }
+            ComputeResponse::PeekResponse(uuid, PeekResponse::Batch(rows)) => {
+                // Batches of rows from any shard can be forwarded immediately.
+                self.peek_batches
+                    .entry(uuid)
+                    .or_default()
+                    .push_back(shard_id);
+                Some(Ok(ComputeResponse::PeekResponse(
+                    uuid,
+                    PeekResponse::Batch(rows),
+                )))
+            }
             ComputeResponse::PeekResponse(uuid, response) => {
                 // Incorporate new peek responses; awaiting all responses.
                 let entry = self
@@ -436,9 +478,13 @@ where
                                 rows.extend(r.into_iter());
                                 PeekResponse::Rows(rows)
                             }
+                            (_, PeekResponse::Batch(_)) | (PeekResponse::Batch(_), _) => {
+                                unreachable!("batches are not final peek responses")
+                            }
                         };
                     }
                     self.peek_responses.remove(&uuid);
+                    self.peek_batches.remove(&uuid);
                     Some(Ok(ComputeResponse::PeekResponse(uuid, response)))
                 } else {
                     None
@@ -507,6 +553,31 @@ where
                     }
                 }
             }
+            ComputeResponse::ArrangementStatistics(list) => {
+                // Each part maintains a disjoint subset of each arrangement's
+                // keys, so statistics for the whole arrangement are the sum of
+                // the most recent statistics from each part.
+                let list =
+                    list.into_iter()
+                        .filter(|(id, _)| self.uppers.contains_key(id))
+                        .map(|(id, stats)| {
+                            let parts = self.statistics.entry(id).or_insert_with(|| {
+                                vec![ArrangementStatistics::default(); self.parts]
+                            });
+                            parts[shard_id] = stats;
+                            let mut total = ArrangementStatistics::default();
+                            for stats in parts.iter() {
+                                total += *stats;
+                            }
+                            (id, total)
+                        })
+                        .collect::<Vec<_>>();
+                if list.is_empty() {
+                    None
+                } else {
+                    Some(Ok(ComputeResponse::ArrangementStatistics(list)))
+                }
+            }
         }
     }
 }
@@ -22,7 +22,7 @@
 //! compacted frontiers, as the underlying resources to rebuild them any earlier may not
 //! exist any longer.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 
 use timely::progress::{frontier::MutableAntichain, Antichain};
 use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
@@ -32,7 +32,7 @@ use crate::client::Peek;
 use mz_repr::GlobalId;
 
 use super::{ComputeClient, GenericClient};
-use super::{ComputeCommand, ComputeResponse};
+use super::{ComputeCommand, ComputeResponse, PeekResponse};
 
 /// Spawns a task that repeatedly sends messages back and forth
 /// between a client and its owner, and return channels to communicate with it.
@@ -98,6 +98,12 @@ pub struct ActiveReplication<T> {
     >,
     /// Outstanding peek identifiers, to guide responses (and which to suppress).
     peeks: HashSet<uuid::Uuid>,
+    /// The replica whose responses are forwarded for each outstanding peek that
+    /// has already forwarded a batch of rows.
+    peek_replicas: HashMap<uuid::Uuid, String>,
+    /// Peeks abandoned because their replica went away while forwarding their
+    /// rows, which have yet to be reported as failed.
+    abandoned_peeks: Vec<uuid::Uuid>,
     /// Reported frontier of each in-progress tail.
     tails: HashMap<GlobalId, Antichain<T>>,
     /// Frontier information, both unioned across all replicas and from each individual replica.
@@ -113,6 +119,8 @@ impl<T> Default for ActiveReplication<T> {
         Self {
             replicas: Default::default(),
             peeks: Default::default(),
+            peek_replicas: Default::default(),
+            abandoned_peeks: Default::default(),
             tails: Default::default(),
             uppers: Default::default(),
             history: Default::default(),
@@ -157,6 +165,47 @@ where
         for (_frontier, frontiers) in self.uppers.iter_mut() {
             frontiers.1.remove(id);
         }
+        self.abandon_peeks(id);
+    }
+
+    /// Abandons the peeks whose rows are being forwarded from the indicated replica.
+    ///
+    /// Some of the rows of these peeks have already been forwarded, and the rows of
+    /// other replicas cannot be reconciled with them, so the peeks must fail.
+    fn abandon_peeks(&mut self, replica_id: &str) {
+        let abandoned = self
+            .peek_replicas
+            .iter()
+            .filter(|(_uuid, id)| id.as_str() == replica_id)
+            .map(|(uuid, _id)| *uuid)
+            .collect::<Vec<_>>();
+        for uuid in abandoned {
+            self.peek_replicas.remove(&uuid);
+            self.peeks.remove(&uuid);
+            self.abandoned_peeks.push(uuid);
+        }
+    }
+
+    /// Reports the failure of an abandoned peek, if there is one.
+    fn abandoned_peek_response(&mut self) -> Option<ComputeResponse<T>> {
+        self.abandoned_peeks.pop().map(|uuid| {
+            ComputeResponse::PeekResponse(
+                uuid,
+                PeekResponse::Error("replica failed while returning rows".into()),
+            )
+        })
+    }
+
+    /// The outstanding peeks that a replica could still contribute responses to.
+    ///
+    /// Peeks that forward the responses of some replica are not among them, and
+    /// are not replayed at other replicas.
+    fn replayable_peeks(&self) -> HashSet<uuid::Uuid> {
+        self.peeks
+            .iter()
+            .filter(|uuid| !self.peek_replicas.contains_key(uuid))
+            .copied()
+            .collect()
     }
 
     /// Pipes a command stream at the indicated replica, introducing new dataflow identifiers.
@@ -171,7 +220,7 @@ where
                 .update_iter(Some((T::minimum(), 1)));
         }
         // Take this opportunity to clean up the history we should present.
-        self.last_command_count = self.history.reduce(&self.peeks);
+        self.last_command_count = self.history.reduce(&self.replayable_peeks());
 
         // Replay the commands at the client, creating new dataflow identifiers.
         let (cmd_tx, _) = self.replicas.get_mut(replica_id).unwrap();
@@ -201,6 +250,22 @@ where
             self.peeks.insert(*uuid);
         }
 
+        // Only the replica whose batches were consumed has peeks to continue.
+        if let ComputeCommand::ContinuePeeks { uuids } = cmd {
+            let mut replica_uuids: HashMap<_, BTreeSet<_>> = HashMap::new();
+            for uuid in uuids {
+                if let Some(replica_id) = self.peek_replicas.get(&uuid) {
+                    replica_uuids.entry(replica_id).or_default().insert(uuid);
+                }
+            }
+            for (replica_id, uuids) in replica_uuids {
+                if let Some((tx, _)) = self.replicas.get(replica_id) {
+                    let _ = tx.send(ComputeCommand::ContinuePeeks { uuids });
+                }
+            }
+            return Ok(());
+        }
+
         // Initialize any necessary frontier tracking.
         let mut start = Vec::new();
         let mut cease = Vec::new();
@@ -229,7 +294,7 @@ where
 
         // If we have reached a point that justifies history reduction, do that.
         if self.history.len() > 2 * self.last_command_count {
-            self.last_command_count = self.history.reduce(&self.peeks);
+            self.last_command_count = self.history.reduce(&self.replayable_peeks());
         }
 
         // Clone the command for each active replica.
@@ -251,6 +316,9 @@ where
     }
 
     async fn recv(&mut self) -> Result<Option<ComputeResponse<T>>, anyhow::Error> {
+        if let Some(response) = self.abandoned_peek_response() {
+            return Ok(Some(response));
+        }
         if self.replicas.is_empty() {
             // We want to communicate that the result is not ready
             futures::future::pending().await
@@ -272,10 +340,30 @@ where
                     match message {
                         Ok(ComputeResponse::PeekResponse(uuid, response)) => {
                             // If this is the first response, forward it; otherwise do not.
+                            // Once a replica has forwarded a batch of rows, only its
+                            // responses are forwarded, through to its final response.
                             // TODO: we could collect the other responses to assert equivalence?
                             // Trades resources (memory) for reassurances; idk which is best.
-                            if self.peeks.remove(&uuid) {
-                                return Ok(Some(ComputeResponse::PeekResponse(uuid, response)));
+                            if self.peeks.contains(&uuid) {
+                                let forwarding = self.peek_replicas.get(&uuid);
+                                if forwarding.map_or(true, |id| *id == replica_id) {
+                                    if response.is_final() {
+                                        self.peeks.remove(&uuid);
+                                        self.peek_replicas.remove(&uuid);
+                                    } else if forwarding.is_none() {
+                                        // The other replicas' rows will not be forwarded,
+                                        // and they would otherwise wait to be continued.
+                                        self.peek_replicas.insert(uuid, replica_id.clone());
+                                        for (id, (tx, _)) in self.replicas.iter() {
+                                            if *id != replica_id {
+                                                let _ = tx.send(ComputeCommand::CancelPeeks {
+                                                    uuids: BTreeSet::from([uuid]),
+                                                });
+                                            }
+                                        }
+                                    }
+                                    return Ok(Some(ComputeResponse::PeekResponse(uuid, response)));
+                                }
                             }
                         }
                         Ok(ComputeResponse::FrontierUppers(mut list)) => {
@@ -355,6 +443,11 @@ where
                                 }
                             }
                         }
+                        Ok(ComputeResponse::ArrangementStatistics(list)) => {
+                            // Each replica maintains a complete copy of each arrangement, so
+                            // statistics from any replica describe the arrangement as a whole.
+                            return Ok(Some(ComputeResponse::ArrangementStatistics(list)));
+                        }
                         Err(_error) => {
                             errored_replica = Some(replica_id);
                             break;
@@ -365,7 +458,11 @@ where
 
                 if let Some(replica_id) = &errored_replica {
                     tracing::warn!("Rehydrating replica {:?}", replica_id);
+                    self.abandon_peeks(replica_id);
                     self.hydrate_replica(replica_id);
+                    if let Some(response) = self.abandoned_peek_response() {
+                        return Ok(Some(response));
+                    }
                 }
 
                 clean_recv = errored_replica.is_none();
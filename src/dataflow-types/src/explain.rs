@@ -28,7 +28,11 @@
 //! printed in contexts where trailing whitespace is unacceptable, like
 //! sqllogictest files.
 
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fmt;
+use std::time::Duration;
+
+use serde::Serialize;
 
 use crate::{DataflowDescription, LinearOperator};
 
@@ -36,7 +40,7 @@ use mz_expr::explain::{Indices, ViewExplanation};
 use mz_expr::{ExprHumanizer, OptimizedMirRelationExpr, RowSetFinishing};
 use mz_ore::result::ResultExt;
 use mz_ore::str::{bracketed, separated};
-use mz_repr::GlobalId;
+use mz_repr::{Datum, GlobalId, RelationType, Row};
 
 pub trait ViewFormatter<ViewExpr> {
     fn fmt_source_body(&self, f: &mut fmt::Formatter, operator: &LinearOperator) -> fmt::Result;
@@ -117,6 +121,103 @@ where
     pub fn explain_row_set_finishing(&mut self, finishing: RowSetFinishing) {
         self.finishing = Some(finishing);
     }
+
+    /// Renders the explanation as a [`JsonExplanation`] rather than as text.
+    ///
+    /// The formatter is not consulted; sources and views are instead
+    /// serialized as they are.
+    pub fn to_json(&self) -> JsonExplanation<'_, ViewExpr> {
+        let humanize = |id: GlobalId| match id {
+            GlobalId::Explain => None,
+            _ => self.expr_humanizer.humanize_id(id),
+        };
+        JsonExplanation {
+            sources: self
+                .sources
+                .iter()
+                .map(|(id, operator)| JsonSource {
+                    id: *id,
+                    name: humanize(*id),
+                    operator: *operator,
+                })
+                .collect(),
+            views: self
+                .views
+                .iter()
+                .map(|(id, view)| JsonView {
+                    id: *id,
+                    name: humanize(*id),
+                    plan: *view,
+                    typ: None,
+                })
+                .collect(),
+            finishing: self.finishing.as_ref(),
+        }
+    }
+}
+
+/// A machine-readable rendering of an [`Explanation`], as produced by
+/// [`Explanation::to_json`].
+///
+/// When serialized, this is an object with the following keys:
+///
+///   * `sources`: the sources that have [`LinearOperator`]s. Each is an object
+///     with the `id` and `name` of the source and its `operator`.
+///   * `views`: the views in the dataflow, in the order they are built. Each is
+///     an object with the `id` and `name` of the view and its `plan`, along
+///     with its `type` if types were attached via
+///     [`JsonExplanation::explain_types`]. The view or query being explained
+///     comes last; a query has the id "Explained Query" and a null name.
+///   * `finishing`: the `RowSetFinishing` of the query, or null.
+///
+/// Operators, plans, types and finishings use their `serde` representations,
+/// so they name every operator, expression, key and join implementation
+/// exactly as they appear in the corresponding Rust types.
+#[derive(Debug, Serialize)]
+pub struct JsonExplanation<'a, ViewExpr> {
+    sources: Vec<JsonSource<'a>>,
+    views: Vec<JsonView<'a, ViewExpr>>,
+    finishing: Option<&'a RowSetFinishing>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonSource<'a> {
+    #[serde(serialize_with = "serialize_display")]
+    id: GlobalId,
+    name: Option<String>,
+    operator: &'a LinearOperator,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonView<'a, ViewExpr> {
+    #[serde(serialize_with = "serialize_display")]
+    id: GlobalId,
+    name: Option<String>,
+    plan: &'a ViewExpr,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    typ: Option<RelationType>,
+}
+
+impl<'a, ViewExpr> JsonExplanation<'a, ViewExpr> {
+    /// Attach the type of each view, as computed by `typ`, to the explanation.
+    ///
+    /// Views for which `typ` returns `None` are left untyped.
+    pub fn explain_types<F>(&mut self, mut typ: F)
+    where
+        F: FnMut(GlobalId, &ViewExpr) -> Option<RelationType>,
+    {
+        for view in &mut self.views {
+            view.typ = typ(view.id, view.plan);
+        }
+    }
+}
+
+fn serialize_display<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: fmt::Display,
+    S: serde::Serializer,
+{
+    serializer.collect_str(value)
 }
 
 impl<'a, Formatter, ViewExpr> fmt::Display for Explanation<'a, Formatter, ViewExpr>
@@ -234,6 +335,7 @@ impl<'a> ViewFormatter<OptimizedMirRelationExpr> for DataflowGraphFormatter<'a>
 }
 
 /// Information used when determining the timestamp for a query.
+#[derive(Serialize)]
 pub struct TimestampExplanation<T> {
     /// The chosen timestamp from `determine_timestamp`.
     pub timestamp: T,
@@ -249,6 +351,7 @@ pub struct TimestampExplanation<T> {
     pub sources: Vec<TimestampSource<T>>,
 }
 
+#[derive(Serialize)]
 pub struct TimestampSource<T> {
     pub name: String,
     pub read_frontier: Vec<T>,
@@ -273,3 +376,251 @@ impl<T: fmt::Display + fmt::Debug> fmt::Display for TimestampExplanation<T> {
         Ok(())
     }
 }
+
+/// Statistics about a single node of a rendered [`Plan`](crate::Plan),
+/// gathered by one worker while running the node's dataflow.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlanNodeStatistics {
+    /// The object in the dataflow whose plan contains the node.
+    pub object: GlobalId,
+    /// The position of the node in a pre-order traversal of the plans of the
+    /// dataflow.
+    pub node: u64,
+    /// The depth of the node within the plan of `object`.
+    pub depth: u64,
+    /// A short description of the node, like `Join`.
+    pub operator: String,
+    /// The collection that the node reads, if the node is a `Get` of a
+    /// collection outside of the dataflow.
+    pub id: Option<GlobalId>,
+    /// The time spent running the node's operators.
+    pub elapsed: Duration,
+    /// The number of updates received by the node's operators.
+    pub updates: u64,
+    /// The number of records in the arrangements built by the node.
+    pub arrangement_records: u64,
+    /// The number of batches in the arrangements built by the node.
+    pub arrangement_batches: u64,
+}
+
+impl PlanNodeStatistics {
+    /// The number of columns in the rows produced by
+    /// [`PlanNodeStatistics::to_row`].
+    pub const ARITY: usize = 9;
+
+    /// Encodes the statistics as a row, for use in a peek response.
+    pub fn to_row(&self) -> Row {
+        let object = self.object.to_string();
+        let id = self.id.map(|id| id.to_string());
+        Row::pack_slice(&[
+            Datum::String(&object),
+            Datum::Int64(self.node as i64),
+            Datum::Int64(self.depth as i64),
+            Datum::String(&self.operator),
+            match &id {
+                Some(id) => Datum::String(id),
+                None => Datum::Null,
+            },
+            Datum::Int64(self.elapsed.as_nanos() as i64),
+            Datum::Int64(self.updates as i64),
+            Datum::Int64(self.arrangement_records as i64),
+            Datum::Int64(self.arrangement_batches as i64),
+        ])
+    }
+
+    /// Decodes statistics from a row produced by [`PlanNodeStatistics::to_row`].
+    pub fn from_row(row: &Row) -> Result<PlanNodeStatistics, String> {
+        let datums = row.unpack();
+        let id = |datum: Datum| {
+            datum
+                .unwrap_str()
+                .parse::<GlobalId>()
+                .map_err(|e| e.to_string())
+        };
+        match &datums[..] {
+            [object, node, depth, operator, get_id, elapsed, updates, records, batches] => {
+                Ok(PlanNodeStatistics {
+                    object: id(*object)?,
+                    node: node.unwrap_int64() as u64,
+                    depth: depth.unwrap_int64() as u64,
+                    operator: operator.unwrap_str().to_owned(),
+                    id: match get_id {
+                        Datum::Null => None,
+                        datum => Some(id(*datum)?),
+                    },
+                    elapsed: Duration::from_nanos(elapsed.unwrap_int64() as u64),
+                    updates: updates.unwrap_int64() as u64,
+                    arrangement_records: records.unwrap_int64() as u64,
+                    arrangement_batches: batches.unwrap_int64() as u64,
+                })
+            }
+            _ => Err(format!("malformed plan statistics row: {:?}", row)),
+        }
+    }
+}
+
+/// Renders the plans of a dataflow as trees of nodes annotated with the
+/// statistics gathered while running them.
+///
+/// Statistics reported by each worker are summed, so `elapsed` is the total
+/// time spent across all workers rather than wall-clock time. Plans are
+/// printed in the order that the dataflow builds them, each under a
+/// "View <name> (<id>):" or "Index <name> (<id>):" header. Transient objects
+/// belong to the query being explained, and are printed under a "Query:" or
+/// "Query index:" header instead.
+#[derive(Debug)]
+pub struct AnalyzedPlanExplanation {
+    objects: Vec<(GlobalId, Vec<PlanNodeStatistics>)>,
+    names: HashMap<GlobalId, String>,
+    indexes: BTreeSet<GlobalId>,
+}
+
+impl AnalyzedPlanExplanation {
+    /// Constructs an explanation from the statistics reported by each worker.
+    ///
+    /// `names` supplies the human-readable names of the objects built by the
+    /// dataflow and of the collections it reads, and `indexes` identifies the
+    /// objects that are indexes exported by the dataflow.
+    pub fn new<I>(
+        statistics: I,
+        names: HashMap<GlobalId, String>,
+        indexes: BTreeSet<GlobalId>,
+    ) -> AnalyzedPlanExplanation
+    where
+        I: IntoIterator<Item = PlanNodeStatistics>,
+    {
+        let mut nodes = BTreeMap::<u64, PlanNodeStatistics>::new();
+        for stats in statistics {
+            match nodes.get_mut(&stats.node) {
+                Some(node) => {
+                    node.elapsed += stats.elapsed;
+                    node.updates += stats.updates;
+                    node.arrangement_records += stats.arrangement_records;
+                    node.arrangement_batches += stats.arrangement_batches;
+                }
+                None => {
+                    nodes.insert(stats.node, stats);
+                }
+            }
+        }
+        let mut objects: Vec<(GlobalId, Vec<PlanNodeStatistics>)> = vec![];
+        for node in nodes.into_values() {
+            match objects.last_mut() {
+                Some((object, nodes)) if *object == node.object => nodes.push(node),
+                _ => objects.push((node.object, vec![node])),
+            }
+        }
+        AnalyzedPlanExplanation {
+            objects,
+            names,
+            indexes,
+        }
+    }
+
+    fn name(&self, id: GlobalId) -> &str {
+        self.names.get(&id).map(|name| name.as_str()).unwrap_or("?")
+    }
+}
+
+impl fmt::Display for AnalyzedPlanExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (object_num, (object, nodes)) in self.objects.iter().enumerate() {
+            if object_num > 0 {
+                writeln!(f)?;
+            }
+            let is_index = self.indexes.contains(object);
+            match object {
+                GlobalId::Transient(_) | GlobalId::Explain if is_index => {
+                    writeln!(f, "Query index:")?
+                }
+                GlobalId::Transient(_) | GlobalId::Explain => writeln!(f, "Query:")?,
+                _ if is_index => writeln!(f, "Index {} ({}):", self.name(*object), object)?,
+                _ => writeln!(f, "View {} ({}):", self.name(*object), object)?,
+            }
+            for node in nodes {
+                write!(f, "{:1$}{2}", "", 2 * node.depth as usize, node.operator)?;
+                if let Some(id) = node.id {
+                    match self.names.get(&id) {
+                        Some(name) => write!(f, " {} ({})", name, id)?,
+                        None => write!(f, " {}", id)?,
+                    }
+                }
+                writeln!(
+                    f,
+                    " elapsed={:?} updates={} arrangement_records={} arrangement_batches={}",
+                    node.elapsed, node.updates, node.arrangement_records, node.arrangement_batches
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Serializes the explanation as a list of objects, one per plan, in the order
+/// the plans are printed.
+///
+/// Each object has the `id` and `name` of the object whose plan it describes,
+/// its `kind` (one of "query", "query index", "view" or "index"), and its
+/// `nodes`. Each node has an `operator`, a `depth`, the `id` and `name` of the
+/// collection that it reads (both null unless the node is a `Get` of a
+/// collection outside the dataflow), and the statistics `elapsed_ns`,
+/// `updates`, `arrangement_records` and `arrangement_batches`.
+impl Serialize for AnalyzedPlanExplanation {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        #[derive(Serialize)]
+        struct Object<'a> {
+            #[serde(serialize_with = "serialize_display")]
+            id: GlobalId,
+            name: Option<&'a str>,
+            kind: &'static str,
+            nodes: Vec<Node<'a>>,
+        }
+
+        #[derive(Serialize)]
+        struct Node<'a> {
+            operator: &'a str,
+            depth: u64,
+            id: Option<String>,
+            name: Option<&'a str>,
+            elapsed_ns: u64,
+            updates: u64,
+            arrangement_records: u64,
+            arrangement_batches: u64,
+        }
+
+        let objects = self.objects.iter().map(|(object, nodes)| {
+            let is_index = self.indexes.contains(object);
+            let kind = match object {
+                GlobalId::Transient(_) | GlobalId::Explain if is_index => "query index",
+                GlobalId::Transient(_) | GlobalId::Explain => "query",
+                _ if is_index => "index",
+                _ => "view",
+            };
+            Object {
+                id: *object,
+                name: self.names.get(object).map(|name| name.as_str()),
+                kind,
+                nodes: nodes
+                    .iter()
+                    .map(|node| Node {
+                        operator: &node.operator,
+                        depth: node.depth,
+                        id: node.id.map(|id| id.to_string()),
+                        name: node
+                            .id
+                            .and_then(|id| self.names.get(&id))
+                            .map(|name| name.as_str()),
+                        elapsed_ns: u64::try_from(node.elapsed.as_nanos()).unwrap_or(u64::MAX),
+                        updates: node.updates,
+                        arrangement_records: node.arrangement_records,
+                        arrangement_batches: node.arrangement_batches,
+                    })
+                    .collect(),
+            }
+        });
+        serializer.collect_seq(objects)
+    }
+}
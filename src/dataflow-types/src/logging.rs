@@ -218,6 +218,7 @@ pub enum MaterializedLog {
     FrontierCurrent,
     PeekCurrent,
     PeekDuration,
+    ArrangementSpills,
 }
 
 impl From<&MaterializedLog> for ProtoMaterializedLog {
@@ -230,6 +231,7 @@ impl From<&MaterializedLog> for ProtoMaterializedLog {
                 MaterializedLog::FrontierCurrent => FrontierCurrent(()),
                 MaterializedLog::PeekCurrent => PeekCurrent(()),
                 MaterializedLog::PeekDuration => PeekDuration(()),
+                MaterializedLog::ArrangementSpills => ArrangementSpills(()),
             }),
         }
     }
@@ -246,6 +248,7 @@ impl TryFrom<ProtoMaterializedLog> for MaterializedLog {
             Some(FrontierCurrent(())) => Ok(MaterializedLog::FrontierCurrent),
             Some(PeekCurrent(())) => Ok(MaterializedLog::PeekCurrent),
             Some(PeekDuration(())) => Ok(MaterializedLog::PeekDuration),
+            Some(ArrangementSpills(())) => Ok(MaterializedLog::ArrangementSpills),
             None => Err(TryFromProtoError::missing_field(
                 "ProtoMaterializedLog::kind",
             )),
@@ -370,6 +373,13 @@ impl LogVariant {
                 .with_column("duration_ns", ScalarType::Int64.nullable(false))
                 .with_column("count", ScalarType::Int64.nullable(false))
                 .with_key(vec![0, 1]),
+
+            LogVariant::Materialized(MaterializedLog::ArrangementSpills) => RelationDesc::empty()
+                .with_column("worker", ScalarType::Int64.nullable(false))
+                .with_column("batches", ScalarType::Int64.nullable(false))
+                .with_column("records", ScalarType::Int64.nullable(false))
+                .with_column("bytes", ScalarType::Int64.nullable(false))
+                .with_key(vec![0]),
         }
     }
 
@@ -417,6 +427,7 @@ impl LogVariant {
             LogVariant::Materialized(MaterializedLog::FrontierCurrent) => vec![],
             LogVariant::Materialized(MaterializedLog::PeekCurrent) => vec![],
             LogVariant::Materialized(MaterializedLog::PeekDuration) => vec![],
+            LogVariant::Materialized(MaterializedLog::ArrangementSpills) => vec![],
         }
     }
 }
@@ -32,6 +32,7 @@
 
 pub mod delta_join;
 pub mod linear_join;
+pub mod outer_join;
 
 use std::collections::HashMap;
 
@@ -45,6 +46,7 @@ use mz_repr::{Datum, Row, RowArena};
 
 pub use delta_join::DeltaJoinPlan;
 pub use linear_join::LinearJoinPlan;
+pub use outer_join::OuterJoinPlan;
 
 include!(concat!(env!("OUT_DIR"), "/mz_dataflow_types.plan.join.rs"));
 
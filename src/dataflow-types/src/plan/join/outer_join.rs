@@ -0,0 +1,192 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Outer join execution planning.
+//!
+//! An outer join is implemented directly from one arrangement of each input,
+//! both keyed by the join key. Matching rows are produced by joining the two
+//! arrangements. For each input whose unmatched rows must be preserved, the
+//! other input's arrangement is reduced to its set of distinct non-null keys,
+//! and the rows of the preserved input whose keys are absent from that set are
+//! padded with nulls and added to the output.
+//!
+//! Compared to lowering an outer join into an inner join and a union with the
+//! anti-joined complement of each preserved input, this maintains neither the
+//! inner join results nor the join inputs in additional arrangements.
+
+use std::collections::HashMap;
+
+use mz_expr::permutation_for_arrangement;
+use mz_expr::MirScalarExpr;
+use mz_expr::OuterJoinKind;
+use mz_repr::proto::ProtoRepr;
+use mz_repr::proto::TryFromProtoError;
+use mz_repr::proto::TryIntoIfSome;
+use proptest::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::ProtoOuterJoinPlan;
+use crate::plan::AvailableCollections;
+
+/// A plan for the execution of an outer join between two inputs.
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct OuterJoinPlan {
+    /// The key by which the left input is arranged.
+    pub left_key: Vec<MirScalarExpr>,
+    /// The key by which the right input is arranged.
+    pub right_key: Vec<MirScalarExpr>,
+    /// For each column of the left input, the position of its value in the
+    /// concatenation of the key and value of the left arrangement.
+    pub left_permutation: Vec<usize>,
+    /// For each column of the right input, the position of its value in the
+    /// concatenation of the key and value of the right arrangement.
+    pub right_permutation: Vec<usize>,
+    /// Which of the inputs have their unmatched rows preserved.
+    pub kind: OuterJoinKind,
+}
+
+impl Arbitrary for OuterJoinPlan {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (
+            prop::collection::vec(any::<MirScalarExpr>(), 0..3),
+            prop::collection::vec(any::<MirScalarExpr>(), 0..3),
+            prop::collection::vec(any::<usize>(), 0..3),
+            prop::collection::vec(any::<usize>(), 0..3),
+            any::<OuterJoinKind>(),
+        )
+            .prop_map(
+                |(left_key, right_key, left_permutation, right_permutation, kind)| OuterJoinPlan {
+                    left_key,
+                    right_key,
+                    left_permutation,
+                    right_permutation,
+                    kind,
+                },
+            )
+            .boxed()
+    }
+}
+
+impl From<&OuterJoinPlan> for ProtoOuterJoinPlan {
+    fn from(x: &OuterJoinPlan) -> Self {
+        Self {
+            left_key: x.left_key.iter().map(Into::into).collect(),
+            right_key: x.right_key.iter().map(Into::into).collect(),
+            left_permutation: x.left_permutation.iter().map(|x| x.into_proto()).collect(),
+            right_permutation: x.right_permutation.iter().map(|x| x.into_proto()).collect(),
+            kind: Some((&x.kind).into()),
+        }
+    }
+}
+
+impl TryFrom<ProtoOuterJoinPlan> for OuterJoinPlan {
+    type Error = TryFromProtoError;
+
+    fn try_from(x: ProtoOuterJoinPlan) -> Result<Self, Self::Error> {
+        Ok(Self {
+            left_key: x
+                .left_key
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+            right_key: x
+                .right_key
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+            left_permutation: x
+                .left_permutation
+                .into_iter()
+                .map(ProtoRepr::from_proto)
+                .collect::<Result<_, _>>()?,
+            right_permutation: x
+                .right_permutation
+                .into_iter()
+                .map(ProtoRepr::from_proto)
+                .collect::<Result<_, _>>()?,
+            kind: x.kind.try_into_if_some("ProtoOuterJoinPlan::kind")?,
+        })
+    }
+}
+
+impl OuterJoinPlan {
+    /// Create a new outer join plan from the required arguments.
+    ///
+    /// Returns the plan along with the arrangements of the left and right
+    /// inputs that are not present in `left_available` and `right_available`,
+    /// and must be constructed before the plan can be rendered.
+    pub fn create_from(
+        left_key: &[MirScalarExpr],
+        right_key: &[MirScalarExpr],
+        kind: OuterJoinKind,
+        (left_available, left_arity): (&AvailableCollections, usize),
+        (right_available, right_arity): (&AvailableCollections, usize),
+    ) -> (Self, AvailableCollections, AvailableCollections) {
+        let (left_permutation, left_missing) =
+            Self::arrangement_permutation(left_key, left_available, left_arity);
+        let (right_permutation, right_missing) =
+            Self::arrangement_permutation(right_key, right_available, right_arity);
+        let plan = OuterJoinPlan {
+            left_key: left_key.to_vec(),
+            right_key: right_key.to_vec(),
+            left_permutation,
+            right_permutation,
+            kind,
+        };
+        (plan, left_missing, right_missing)
+    }
+
+    /// Determines the column permutation of the arrangement of an input by
+    /// `key`, and requests that arrangement if it is not already available.
+    fn arrangement_permutation(
+        key: &[MirScalarExpr],
+        available: &AvailableCollections,
+        arity: usize,
+    ) -> (Vec<usize>, AvailableCollections) {
+        let mut requested = AvailableCollections::default();
+        let permutation = available
+            .arranged
+            .iter()
+            .find_map(|(k, permutation, _thinning)| {
+                if k == key {
+                    Some(permutation.clone())
+                } else {
+                    None
+                }
+            })
+            .unwrap_or_else(|| {
+                let (permutation, thinning) =
+                    permutation_for_arrangement::<HashMap<_, _>>(key, arity);
+                requested
+                    .arranged
+                    .push((key.to_vec(), permutation.clone(), thinning));
+                permutation
+            });
+        let permutation = (0..arity).map(|c| permutation[&c]).collect();
+        (permutation, requested)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mz_repr::proto::protobuf_roundtrip;
+
+    proptest! {
+        #[test]
+        fn outer_join_plan_protobuf_roundtrip(expect in any::<OuterJoinPlan>()) {
+            let actual = protobuf_roundtrip::<_, ProtoOuterJoinPlan>(&expect);
+            assert!(actual.is_ok());
+            assert_eq!(actual.unwrap(), expect);
+        }
+    }
+}
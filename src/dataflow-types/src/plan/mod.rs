@@ -32,7 +32,7 @@ use mz_expr::{
 };
 use mz_repr::{Datum, Diff, GlobalId, Row};
 
-use self::join::{DeltaJoinPlan, JoinPlan, LinearJoinPlan};
+use self::join::{DeltaJoinPlan, JoinPlan, LinearJoinPlan, OuterJoinPlan};
 use self::reduce::{KeyValPlan, ReducePlan};
 use self::threshold::ThresholdPlan;
 use self::top_k::TopKPlan;
@@ -278,6 +278,20 @@ pub enum Plan<T = mz_repr::Timestamp> {
         /// potentially pushed down into the implementation of the join.
         plan: JoinPlan,
     },
+    /// An outer equijoin of two inputs.
+    ///
+    /// This stage produces the rows of `left` and `right` whose keys match,
+    /// as well as the unmatched rows of the inputs preserved by the join,
+    /// padded with nulls. Both inputs are expected to be arranged by their
+    /// respective keys in `plan`.
+    OuterJoin {
+        /// The left input.
+        left: Box<Plan<T>>,
+        /// The right input.
+        right: Box<Plan<T>>,
+        /// The keys and arrangement layouts of the inputs, and the join kind.
+        plan: OuterJoinPlan,
+    },
     /// Aggregation by key.
     Reduce {
         /// The input collection.
@@ -421,6 +435,14 @@ impl Arbitrary for Plan {
                 )
                     .prop_map(|(inputs, plan)| Plan::Join { inputs, plan })
                     .boxed(),
+                //Plan::OuterJoin
+                (inner.clone(), inner.clone(), any::<OuterJoinPlan>())
+                    .prop_map(|(left, right, plan)| Plan::OuterJoin {
+                        left: left.into(),
+                        right: right.into(),
+                        plan,
+                    })
+                    .boxed(),
                 //Plan::Reduce
                 (
                     inner.clone(),
@@ -581,6 +603,14 @@ impl From<&Plan> for ProtoPlan {
                     inputs: inputs.iter().map(Into::into).collect(),
                     plan: Some(plan.into()),
                 }),
+                Plan::OuterJoin { left, right, plan } => OuterJoin(
+                    ProtoPlanOuterJoin {
+                        left: left.into(),
+                        right: right.into(),
+                        plan: Some(plan.into()),
+                    }
+                    .into(),
+                ),
                 Plan::Reduce {
                     input,
                     key_val_plan,
@@ -752,6 +782,11 @@ impl TryFrom<ProtoPlan> for Plan {
                     .collect::<Result<_, _>>()?,
                 plan: proto.plan.try_into_if_some("")?,
             },
+            OuterJoin(proto) => Plan::OuterJoin {
+                left: proto.left.try_into_if_some("ProtoPlanOuterJoin::left")?,
+                right: proto.right.try_into_if_some("ProtoPlanOuterJoin::right")?,
+                plan: proto.plan.try_into_if_some("ProtoPlanOuterJoin::plan")?,
+            },
             Reduce(proto) => Plan::Reduce {
                 input: proto.input.try_into_if_some("ProtoPlanReduce::input")?,
                 key_val_plan: proto
@@ -1191,6 +1226,46 @@ This is not expected to cause incorrect results, but could indicate a performanc
                     AvailableCollections::new_raw(),
                 )
             }
+            MirRelationExpr::OuterJoin {
+                left,
+                right,
+                left_key,
+                right_key,
+                kind,
+            } => {
+                let left_arity = left.arity();
+                let right_arity = right.arity();
+                let (left, left_keys) = Self::from_mir(left, arrangements, debug_info)?;
+                let (right, right_keys) = Self::from_mir(right, arrangements, debug_info)?;
+
+                let (plan, left_missing, right_missing) = OuterJoinPlan::create_from(
+                    left_key,
+                    right_key,
+                    *kind,
+                    (&left_keys, left_arity),
+                    (&right_keys, right_arity),
+                );
+                // Plan any arrangements the renderer expects but which are not yet available.
+                let left = if left_missing != Default::default() {
+                    left.arrange_by(left_missing, &left_keys, left_arity)
+                } else {
+                    left
+                };
+                let right = if right_missing != Default::default() {
+                    right.arrange_by(right_missing, &right_keys, right_arity)
+                } else {
+                    right
+                };
+                // Return the plan, and no arrangements.
+                (
+                    Plan::OuterJoin {
+                        left: Box::new(left),
+                        right: Box::new(right),
+                        plan,
+                    },
+                    AvailableCollections::new_raw(),
+                )
+            }
             MirRelationExpr::Reduce {
                 input,
                 group_key,
@@ -1624,6 +1699,19 @@ This is not expected to cause incorrect results, but could indicate a performanc
                         })
                         .collect()
                 }
+                Plan::OuterJoin { left, right, plan } => {
+                    let left_parts = left.partition_among(parts);
+                    let right_parts = right.partition_among(parts);
+                    left_parts
+                        .into_iter()
+                        .zip(right_parts)
+                        .map(|(left, right)| Plan::OuterJoin {
+                            left: Box::new(left),
+                            right: Box::new(right),
+                            plan: plan.clone(),
+                        })
+                        .collect()
+                }
                 Plan::Reduce {
                     input,
                     key_val_plan,
@@ -1722,6 +1810,14 @@ impl<T> CollectionPlan for Plan<T> {
                     input.depends_on_into(out);
                 }
             }
+            Plan::OuterJoin {
+                left,
+                right,
+                plan: _,
+            } => {
+                left.depends_on_into(out);
+                right.depends_on_into(out);
+            }
             Plan::Mfp {
                 input,
                 mfp: _,
@@ -149,7 +149,13 @@ where
                     .push_back(ComputeResponse::FrontierUppers(list));
             }
             ComputeResponse::PeekResponse(uuid, response) => {
-                if self.peeks.remove(&uuid) {
+                // Only the final response to a peek concludes it.
+                let pending = if response.is_final() {
+                    self.peeks.remove(&uuid)
+                } else {
+                    self.peeks.contains(&uuid)
+                };
+                if pending {
                     self.responses
                         .push_back(ComputeResponse::PeekResponse(uuid, response));
                 }
@@ -158,6 +164,10 @@ where
                 self.responses
                     .push_back(ComputeResponse::TailResponse(id, response));
             }
+            ComputeResponse::ArrangementStatistics(list) => {
+                self.responses
+                    .push_back(ComputeResponse::ArrangementStatistics(list));
+            }
         }
     }
 
@@ -232,6 +242,14 @@ where
                 }
                 self.client.send(CancelPeeks { uuids }).await
             }
+            ContinuePeeks { mut uuids } => {
+                uuids.retain(|uuid| self.peeks.contains(uuid));
+                if !uuids.is_empty() {
+                    self.client.send(ContinuePeeks { uuids }).await
+                } else {
+                    Ok(())
+                }
+            }
         }
     }
 }
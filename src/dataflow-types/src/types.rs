@@ -35,10 +35,14 @@ include!(concat!(env!("OUT_DIR"), "/mz_dataflow_types.types.rs"));
 
 /// The response from a `Peek`.
 ///
-/// Note that each `Peek` expects to generate exactly one `PeekResponse`, i.e.
-/// we expect a 1:1 contract between `Peek` and `PeekResponse`.
+/// Note that each `Peek` expects to generate any number of `Batch` responses
+/// followed by exactly one final `PeekResponse` of another kind, i.e. we expect
+/// a 1:1 contract between `Peek` and final `PeekResponse`. The rows of the peek
+/// are those of all of its batches together with those of its final response.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub enum PeekResponse {
+    /// Some of the rows of the peek, which are followed by further responses.
+    Batch(Vec<(Row, NonZeroUsize)>),
     Rows(Vec<(Row, NonZeroUsize)>),
     Error(String),
     Canceled,
@@ -48,11 +52,16 @@ impl PeekResponse {
     pub fn unwrap_rows(self) -> Vec<(Row, NonZeroUsize)> {
         match self {
             PeekResponse::Rows(rows) => rows,
-            PeekResponse::Error(_) | PeekResponse::Canceled => {
+            PeekResponse::Batch(_) | PeekResponse::Error(_) | PeekResponse::Canceled => {
                 panic!("PeekResponse::unwrap_rows called on {:?}", self)
             }
         }
     }
+
+    /// Reports whether this is the last response to its peek.
+    pub fn is_final(&self) -> bool {
+        !matches!(self, PeekResponse::Batch(_))
+    }
 }
 
 /// Various responses that can be communicated about the progress of a TAIL command.
@@ -664,6 +673,7 @@ impl TryFrom<ProtoDataflowDescription>
 /// Types and traits related to the introduction of changing collections into `dataflow`.
 pub mod sources {
     use std::collections::{BTreeMap, HashMap};
+    use std::fmt;
     use std::ops::{Add, Deref, DerefMut};
     use std::time::Duration;
 
@@ -676,13 +686,16 @@ pub mod sources {
     use mz_persist_client::read::ReadHandle;
     use mz_persist_client::{PersistLocation, ShardId};
     use mz_persist_types::Codec64;
+    use ordered_float::OrderedFloat;
     use prost::Message;
+    use regex::Regex;
     use serde::{Deserialize, Serialize};
     use timely::progress::Timestamp;
     use uuid::Uuid;
 
     use mz_kafka_util::KafkaAddrs;
     use mz_persist_types::Codec;
+    use mz_repr::adt::numeric::NumericMaxScale;
     use mz_repr::proto::TryFromProtoError;
     use mz_repr::{ColumnType, GlobalId, RelationDesc, RelationType, Row, ScalarType};
 
@@ -720,6 +733,8 @@ pub mod sources {
         #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
         pub enum DataEncoding {
             Avro(AvroEncoding),
+            AvroOcf(AvroOcfEncoding),
+            Parquet(ParquetEncoding),
             Protobuf(ProtobufEncoding),
             Csv(CsvEncoding),
             Regex(RegexEncoding),
@@ -781,7 +796,11 @@ pub mod sources {
                     DataEncoding::Bytes => {
                         RelationDesc::empty().with_column("data", ScalarType::Bytes.nullable(false))
                     }
-                    DataEncoding::Avro(AvroEncoding { schema, .. }) => {
+                    DataEncoding::Avro(AvroEncoding { schema, .. })
+                    | DataEncoding::AvroOcf(AvroOcfEncoding {
+                        reader_schema: schema,
+                    })
+                    | DataEncoding::Parquet(ParquetEncoding { schema }) => {
                         let parsed_schema =
                             avro::parse_schema(schema).context("validating avro schema")?;
                         avro::schema_to_relationdesc(parsed_schema)
@@ -852,6 +871,8 @@ pub mod sources {
                 match self {
                     DataEncoding::Bytes => "Bytes",
                     DataEncoding::Avro(_) => "Avro",
+                    DataEncoding::AvroOcf(_) => "AvroOcf",
+                    DataEncoding::Parquet(_) => "Parquet",
                     DataEncoding::Protobuf(_) => "Protobuf",
                     DataEncoding::Regex { .. } => "Regex",
                     DataEncoding::Csv(_) => "Csv",
@@ -870,6 +891,25 @@ pub mod sources {
             pub confluent_wire_format: bool,
         }
 
+        /// Encoding as Avro object container files.
+        ///
+        /// Each object is a complete file, whose records are resolved against
+        /// `reader_schema`.
+        #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+        pub struct AvroOcfEncoding {
+            pub reader_schema: String,
+        }
+
+        /// Encoding as Parquet files.
+        ///
+        /// Each object is a complete file. The columns of the relation are
+        /// described by `schema`, an Avro record schema, and are matched to
+        /// the columns of each file by name.
+        #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+        pub struct ParquetEncoding {
+            pub schema: String,
+        }
+
         /// Encoding in Protobuf format.
         #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
         pub struct ProtobufEncoding {
@@ -945,6 +985,16 @@ pub mod sources {
         }
     }
 
+    /// A point in a partition from which reading can resume, for sources whose upstream
+    /// positions cannot be represented as an `MzOffset` (e.g. Kinesis sequence numbers).
+    #[derive(Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Hash, Serialize, Deserialize)]
+    pub struct SourceCheckpoint {
+        /// The offset of the message at `upstream_position`.
+        pub offset: MzOffset,
+        /// The upstream position of that message.
+        pub upstream_position: String,
+    }
+
     #[derive(Clone, Copy, Eq, PartialEq)]
     pub struct KafkaOffset {
         pub offset: i64,
@@ -969,6 +1019,37 @@ pub mod sources {
         }
     }
 
+    /// What a source does with records that fail to decode.
+    #[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+    pub enum DecodeErrorPolicy {
+        /// Decode failures become errors of the source, which poison every
+        /// downstream view until they are retracted.
+        Fail,
+        /// Records that fail to decode are dropped.
+        Skip,
+        /// Records that fail to decode are dropped, and can be read back through
+        /// a source created with `FROM DECODE ERRORS OF`.
+        Divert,
+        /// Only the records that the decode operator of `source`, a source
+        /// with the [`DecodeErrorPolicy::Divert`] policy, failed to decode are
+        /// produced, as rows of [`DecodeErrorPolicy::error_desc`]. Used by
+        /// sources created with `FROM DECODE ERRORS OF`.
+        Emit { source: GlobalId },
+    }
+
+    impl DecodeErrorPolicy {
+        /// The relation produced by sources that emit decode errors: the raw key
+        /// and value bytes, the decode error, and where the record came from.
+        pub fn error_desc() -> RelationDesc {
+            RelationDesc::empty()
+                .with_column("key", ScalarType::Bytes.nullable(true))
+                .with_column("value", ScalarType::Bytes.nullable(true))
+                .with_column("error", ScalarType::String.nullable(false))
+                .with_column("partition", ScalarType::String.nullable(true))
+                .with_column("offset", ScalarType::Int64.nullable(false))
+        }
+    }
+
     /// Which piece of metadata a column corresponds to
     #[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
     pub enum IncludedColumnSource {
@@ -1384,7 +1465,7 @@ pub mod sources {
     #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
     pub struct KafkaSourceConnector {
         pub addrs: KafkaAddrs,
-        pub topic: String,
+        pub topics: KafkaTopics,
         // Represents options specified by user when creating the source, e.g.
         // security settings.
         pub config_options: BTreeMap<String, String>,
@@ -1403,6 +1484,55 @@ pub mod sources {
         pub include_headers: Option<IncludedColumnPos>,
     }
 
+    impl KafkaSourceConnector {
+        /// Whether the partitions of this source are identified by topic as
+        /// well as by partition number, i.e. as
+        /// [`PartitionId::KafkaTopic`](mz_expr::PartitionId::KafkaTopic) rather
+        /// than [`PartitionId::Kafka`](mz_expr::PartitionId::Kafka).
+        ///
+        /// Sources that read a single topic and do not include the topic name
+        /// keep the plain partition numbers, so that they don't pay for
+        /// carrying the topic name around.
+        pub fn topic_qualified_partitions(&self) -> bool {
+            self.include_topic.is_some() || self.topics.single().is_none()
+        }
+    }
+
+    /// The topics that a Kafka source reads from.
+    #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+    pub enum KafkaTopics {
+        /// A fixed, non-empty list of topics.
+        Names(Vec<String>),
+        /// A regular expression that must match a topic's entire name. Topics
+        /// that are created after the source are picked up as well.
+        Pattern(String),
+    }
+
+    impl KafkaTopics {
+        /// Compiles a [`KafkaTopics::Pattern`] into a regular expression that
+        /// only matches a topic's entire name, as Kafka's own clients do.
+        pub fn compile_pattern(pattern: &str) -> Result<Regex, regex::Error> {
+            Regex::new(&format!("^(?:{})$", pattern))
+        }
+
+        /// Returns the topic name if exactly one topic is named.
+        pub fn single(&self) -> Option<&str> {
+            match self {
+                KafkaTopics::Names(names) if names.len() == 1 => Some(names[0].as_str()),
+                _ => None,
+            }
+        }
+    }
+
+    impl fmt::Display for KafkaTopics {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                KafkaTopics::Names(names) => f.write_str(&names.join(", ")),
+                KafkaTopics::Pattern(pattern) => write!(f, "pattern {}", pattern),
+            }
+        }
+    }
+
     /// Legacy logic included something like an offset into almost data streams
     ///
     /// Eventually we will require `INCLUDE <metadata>` for everything.
@@ -1449,7 +1579,9 @@ pub mod sources {
 
     #[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
     pub enum Compression {
+        Bzip2,
         Gzip,
+        Zstd,
         None,
     }
 
@@ -1488,6 +1620,7 @@ pub mod sources {
             metadata_columns: Vec<IncludedColumnSource>,
             ts_frequency: Duration,
             timeline: Timeline,
+            decode_errors: DecodeErrorPolicy,
         },
 
         /// A local "source" is fed by a local input handle.
@@ -1578,6 +1711,7 @@ pub mod sources {
         Postgres(PostgresSourceConnector),
         PubNub(PubNubSourceConnector),
         Persist(PersistSourceConnector),
+        LoadGenerator(LoadGeneratorSourceConnector),
     }
 
     impl ExternalSourceConnector {
@@ -1662,6 +1796,7 @@ pub mod sources {
                 Self::Postgres(_) => vec![],
                 Self::PubNub(_) => vec![],
                 Self::Persist(_) => vec![],
+                Self::LoadGenerator(_) => vec![],
             }
         }
 
@@ -1674,6 +1809,7 @@ pub mod sources {
                 ExternalSourceConnector::Postgres(_) => None,
                 ExternalSourceConnector::PubNub(_) => None,
                 ExternalSourceConnector::Persist(_) => None,
+                ExternalSourceConnector::LoadGenerator(_) => None,
             }
         }
 
@@ -1718,7 +1854,8 @@ pub mod sources {
                 }
                 ExternalSourceConnector::Postgres(_)
                 | ExternalSourceConnector::PubNub(_)
-                | ExternalSourceConnector::Persist(_) => Vec::new(),
+                | ExternalSourceConnector::Persist(_)
+                | ExternalSourceConnector::LoadGenerator(_) => Vec::new(),
             }
         }
 
@@ -1731,6 +1868,7 @@ pub mod sources {
                 ExternalSourceConnector::Postgres(_) => "postgres",
                 ExternalSourceConnector::PubNub(_) => "pubnub",
                 ExternalSourceConnector::Persist(_) => "persist",
+                ExternalSourceConnector::LoadGenerator(_) => "load-generator",
             }
         }
 
@@ -1739,8 +1877,8 @@ pub mod sources {
         ///  TODO: decide whether we want file paths and other upstream names to show up in metrics too.
         pub fn upstream_name(&self) -> Option<&str> {
             match self {
-                ExternalSourceConnector::Kafka(KafkaSourceConnector { topic, .. }) => {
-                    Some(topic.as_str())
+                ExternalSourceConnector::Kafka(KafkaSourceConnector { topics, .. }) => {
+                    topics.single()
                 }
                 ExternalSourceConnector::Kinesis(KinesisSourceConnector {
                     stream_name, ..
@@ -1749,6 +1887,7 @@ pub mod sources {
                 ExternalSourceConnector::Postgres(_) => None,
                 ExternalSourceConnector::PubNub(_) => None,
                 ExternalSourceConnector::Persist(_) => None,
+                ExternalSourceConnector::LoadGenerator(_) => None,
             }
         }
 
@@ -1760,7 +1899,8 @@ pub mod sources {
                 ExternalSourceConnector::Kafka(_)
                 | ExternalSourceConnector::Kinesis(_)
                 | ExternalSourceConnector::PubNub(_)
-                | ExternalSourceConnector::Persist(_) => false,
+                | ExternalSourceConnector::Persist(_)
+                | ExternalSourceConnector::LoadGenerator(_) => false,
             }
         }
     }
@@ -1792,6 +1932,212 @@ pub mod sources {
         pub shard_id: ShardId,
     }
 
+    #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+    pub struct LoadGeneratorSourceConnector {
+        pub generator: LoadGenerator,
+        /// The amount of time between the batches of data the generator
+        /// produces.
+        pub tick_interval: Duration,
+    }
+
+    /// The data produced by a `LOAD GENERATOR` source.
+    ///
+    /// Every generator is deterministic: the `n`th record it produces is the
+    /// same across runs and across restarts, which makes the generated data
+    /// suitable for reproducible demos and benchmarks.
+    #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+    pub enum LoadGenerator {
+        /// A single column that counts up from one, once per tick.
+        Counter,
+        /// One of the tables of an auction house, where each tick opens a new
+        /// auction and places a fixed number of bids on it.
+        Auction { table: AuctionTable },
+        /// One of the tables of the TPC-H benchmark at the given scale factor.
+        /// The entire table is produced in the first tick.
+        Tpch {
+            table: TpchTable,
+            scale_factor: OrderedFloat<f64>,
+        },
+    }
+
+    impl LoadGenerator {
+        /// Returns the schema of the relation produced by this generator.
+        pub fn desc(&self) -> RelationDesc {
+            let int32 = || ScalarType::Int32.nullable(false);
+            let int64 = || ScalarType::Int64.nullable(false);
+            let string = || ScalarType::String.nullable(false);
+            let date = || ScalarType::Date.nullable(false);
+            let timestamptz = || ScalarType::TimestampTz.nullable(false);
+            let decimal = || {
+                ScalarType::Numeric {
+                    max_scale: Some(NumericMaxScale::try_from(2_i64).unwrap()),
+                }
+                .nullable(false)
+            };
+
+            match self {
+                LoadGenerator::Counter => RelationDesc::empty()
+                    .with_column("counter", int64())
+                    .with_key(vec![0]),
+                LoadGenerator::Auction {
+                    table: AuctionTable::Auctions,
+                } => RelationDesc::empty()
+                    .with_column("id", int64())
+                    .with_column("seller", int64())
+                    .with_column("item", string())
+                    .with_column("end_time", timestamptz())
+                    .with_key(vec![0]),
+                LoadGenerator::Auction {
+                    table: AuctionTable::Bids,
+                } => RelationDesc::empty()
+                    .with_column("id", int64())
+                    .with_column("buyer", int64())
+                    .with_column("auction_id", int64())
+                    .with_column("amount", int32())
+                    .with_column("bid_time", timestamptz())
+                    .with_key(vec![0]),
+                LoadGenerator::Tpch { table, .. } => match table {
+                    TpchTable::Region => RelationDesc::empty()
+                        .with_column("r_regionkey", int32())
+                        .with_column("r_name", string())
+                        .with_column("r_comment", string())
+                        .with_key(vec![0]),
+                    TpchTable::Nation => RelationDesc::empty()
+                        .with_column("n_nationkey", int32())
+                        .with_column("n_name", string())
+                        .with_column("n_regionkey", int32())
+                        .with_column("n_comment", string())
+                        .with_key(vec![0]),
+                    TpchTable::Supplier => RelationDesc::empty()
+                        .with_column("s_suppkey", int64())
+                        .with_column("s_name", string())
+                        .with_column("s_address", string())
+                        .with_column("s_nationkey", int32())
+                        .with_column("s_phone", string())
+                        .with_column("s_acctbal", decimal())
+                        .with_column("s_comment", string())
+                        .with_key(vec![0]),
+                    TpchTable::Part => RelationDesc::empty()
+                        .with_column("p_partkey", int64())
+                        .with_column("p_name", string())
+                        .with_column("p_mfgr", string())
+                        .with_column("p_brand", string())
+                        .with_column("p_type", string())
+                        .with_column("p_size", int32())
+                        .with_column("p_container", string())
+                        .with_column("p_retailprice", decimal())
+                        .with_column("p_comment", string())
+                        .with_key(vec![0]),
+                    TpchTable::PartSupp => RelationDesc::empty()
+                        .with_column("ps_partkey", int64())
+                        .with_column("ps_suppkey", int64())
+                        .with_column("ps_availqty", int32())
+                        .with_column("ps_supplycost", decimal())
+                        .with_column("ps_comment", string())
+                        .with_key(vec![0, 1]),
+                    TpchTable::Customer => RelationDesc::empty()
+                        .with_column("c_custkey", int64())
+                        .with_column("c_name", string())
+                        .with_column("c_address", string())
+                        .with_column("c_nationkey", int32())
+                        .with_column("c_phone", string())
+                        .with_column("c_acctbal", decimal())
+                        .with_column("c_mktsegment", string())
+                        .with_column("c_comment", string())
+                        .with_key(vec![0]),
+                    TpchTable::Orders => RelationDesc::empty()
+                        .with_column("o_orderkey", int64())
+                        .with_column("o_custkey", int64())
+                        .with_column("o_orderstatus", string())
+                        .with_column("o_totalprice", decimal())
+                        .with_column("o_orderdate", date())
+                        .with_column("o_orderpriority", string())
+                        .with_column("o_clerk", string())
+                        .with_column("o_shippriority", int32())
+                        .with_column("o_comment", string())
+                        .with_key(vec![0]),
+                    TpchTable::LineItem => RelationDesc::empty()
+                        .with_column("l_orderkey", int64())
+                        .with_column("l_partkey", int64())
+                        .with_column("l_suppkey", int64())
+                        .with_column("l_linenumber", int32())
+                        .with_column("l_quantity", decimal())
+                        .with_column("l_extendedprice", decimal())
+                        .with_column("l_discount", decimal())
+                        .with_column("l_tax", decimal())
+                        .with_column("l_returnflag", string())
+                        .with_column("l_linestatus", string())
+                        .with_column("l_shipdate", date())
+                        .with_column("l_commitdate", date())
+                        .with_column("l_receiptdate", date())
+                        .with_column("l_shipinstruct", string())
+                        .with_column("l_shipmode", string())
+                        .with_column("l_comment", string())
+                        .with_key(vec![0, 3]),
+                },
+            }
+        }
+    }
+
+    /// The tables produced by [`LoadGenerator::Auction`].
+    #[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+    pub enum AuctionTable {
+        Auctions,
+        Bids,
+    }
+
+    impl AuctionTable {
+        pub const ALL: &'static [AuctionTable] = &[AuctionTable::Auctions, AuctionTable::Bids];
+
+        /// Returns the name of the table, as used in `LOAD GENERATOR AUCTION TABLE <name>`.
+        pub fn name(&self) -> &'static str {
+            match self {
+                AuctionTable::Auctions => "auctions",
+                AuctionTable::Bids => "bids",
+            }
+        }
+    }
+
+    /// The tables produced by [`LoadGenerator::Tpch`].
+    #[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+    pub enum TpchTable {
+        Region,
+        Nation,
+        Supplier,
+        Part,
+        PartSupp,
+        Customer,
+        Orders,
+        LineItem,
+    }
+
+    impl TpchTable {
+        pub const ALL: &'static [TpchTable] = &[
+            TpchTable::Region,
+            TpchTable::Nation,
+            TpchTable::Supplier,
+            TpchTable::Part,
+            TpchTable::PartSupp,
+            TpchTable::Customer,
+            TpchTable::Orders,
+            TpchTable::LineItem,
+        ];
+
+        /// Returns the name of the table, as used in `LOAD GENERATOR TPCH TABLE <name>`.
+        pub fn name(&self) -> &'static str {
+            match self {
+                TpchTable::Region => "region",
+                TpchTable::Nation => "nation",
+                TpchTable::Supplier => "supplier",
+                TpchTable::Part => "part",
+                TpchTable::PartSupp => "partsupp",
+                TpchTable::Customer => "customer",
+                TpchTable::Orders => "orders",
+                TpchTable::LineItem => "lineitem",
+            }
+        }
+    }
+
     #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
     pub struct S3SourceConnector {
         pub key_sources: Vec<S3KeySource>,
@@ -2026,6 +2372,41 @@ pub mod sources {
             Self::try_from(proto).map_err(|err| err.to_string())
         }
     }
+
+    impl From<&SourceCheckpoint> for ProtoSourceCheckpoint {
+        fn from(x: &SourceCheckpoint) -> Self {
+            ProtoSourceCheckpoint {
+                offset: x.offset.offset,
+                upstream_position: x.upstream_position.clone(),
+            }
+        }
+    }
+
+    impl From<ProtoSourceCheckpoint> for SourceCheckpoint {
+        fn from(x: ProtoSourceCheckpoint) -> Self {
+            SourceCheckpoint {
+                offset: MzOffset { offset: x.offset },
+                upstream_position: x.upstream_position,
+            }
+        }
+    }
+
+    impl Codec for SourceCheckpoint {
+        fn codec_name() -> String {
+            "protobuf[SourceCheckpoint]".into()
+        }
+
+        fn encode<B: BufMut>(&self, buf: &mut B) {
+            ProtoSourceCheckpoint::from(self)
+                .encode(buf)
+                .expect("no required fields means no initialization errors");
+        }
+
+        fn decode(buf: &[u8]) -> Result<Self, String> {
+            let proto = ProtoSourceCheckpoint::decode(buf).map_err(|err| err.to_string())?;
+            Ok(proto.into())
+        }
+    }
 }
 
 /// Types and traits related to reporting changing collections out of `dataflow`.
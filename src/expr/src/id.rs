@@ -142,10 +142,14 @@ impl fmt::Display for SourceInstanceId {
 
 /// Unique identifier for each part of a whole source.
 ///     Kafka -> partition
+///     KafkaTopic -> topic and partition, for sources that read more than one topic
+///     Kinesis -> shard
 ///     None -> sources that have no notion of partitioning (e.g file sources)
 #[derive(Arbitrary, Clone, Debug, Eq, Hash, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
 pub enum PartitionId {
     Kafka(i32),
+    KafkaTopic { topic: String, partition: i32 },
+    Kinesis(String),
     None,
 }
 
@@ -153,6 +157,8 @@ impl fmt::Display for PartitionId {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             PartitionId::Kafka(id) => write!(f, "{}", id),
+            PartitionId::KafkaTopic { topic, partition } => write!(f, "{}:{}", topic, partition),
+            PartitionId::Kinesis(shard) => write!(f, "{}", shard),
             PartitionId::None => write!(f, "none"),
         }
     }
@@ -173,10 +179,19 @@ impl FromStr for PartitionId {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "none" => Ok(PartitionId::None),
-            s => {
-                let val: i32 = s.parse()?;
-                Ok(PartitionId::Kafka(val))
-            }
+            // Kinesis shard IDs are always of the form `shardId-<number>`.
+            s if s.starts_with("shardId-") => Ok(PartitionId::Kinesis(s.to_string())),
+            s => match s.rsplit_once(':') {
+                // Kafka topic names cannot contain colons.
+                Some((topic, partition)) => Ok(PartitionId::KafkaTopic {
+                    topic: topic.to_string(),
+                    partition: partition.parse()?,
+                }),
+                None => {
+                    let val: i32 = s.parse()?;
+                    Ok(PartitionId::Kafka(val))
+                }
+            },
         }
     }
 }
@@ -186,6 +201,13 @@ impl From<&PartitionId> for ProtoPartitionId {
         ProtoPartitionId {
             kind: Some(match x {
                 PartitionId::Kafka(x) => proto_partition_id::Kind::Kafka(*x),
+                PartitionId::KafkaTopic { topic, partition } => {
+                    proto_partition_id::Kind::KafkaTopic(ProtoKafkaTopicPartition {
+                        topic: topic.clone(),
+                        partition: *partition,
+                    })
+                }
+                PartitionId::Kinesis(shard) => proto_partition_id::Kind::Kinesis(shard.clone()),
                 PartitionId::None => proto_partition_id::Kind::None(()),
             }),
         }
@@ -198,6 +220,11 @@ impl TryFrom<ProtoPartitionId> for PartitionId {
     fn try_from(x: ProtoPartitionId) -> Result<Self, Self::Error> {
         match x.kind {
             Some(proto_partition_id::Kind::Kafka(x)) => Ok(PartitionId::Kafka(x)),
+            Some(proto_partition_id::Kind::KafkaTopic(x)) => Ok(PartitionId::KafkaTopic {
+                topic: x.topic,
+                partition: x.partition,
+            }),
+            Some(proto_partition_id::Kind::Kinesis(x)) => Ok(PartitionId::Kinesis(x)),
             Some(proto_partition_id::Kind::None(_)) => Ok(PartitionId::None),
             None => Err(TryFromProtoError::missing_field("ProtoPartitionId::kind")),
         }
@@ -41,11 +41,13 @@ pub use relation::func::{AggregateFunc, LagLeadType, TableFunc};
 pub use relation::func::{AnalyzedRegex, CaptureGroupDesc};
 pub use relation::join_input_mapper::JoinInputMapper;
 pub use relation::{
-    compare_columns, AggregateExpr, CollectionPlan, ColumnOrder, JoinImplementation,
-    MirRelationExpr, ProtoAggregateExpr, RowSetFinishing, WindowFrame, WindowFrameBound,
-    WindowFrameUnits, RECURSION_LIMIT,
+    compare_columns, AggregateExpr, CollectionPlan, ColumnOrder, IncrementalFinishing,
+    JoinImplementation, MirRelationExpr, OuterJoinKind, ProtoAggregateExpr, RowSetFinishing,
+    WindowFrame, WindowFrameBound, WindowFrameUnits, RECURSION_LIMIT,
+};
+pub use relation::{
+    ProtoAggregateFunc, ProtoColumnOrder, ProtoOuterJoinKind, ProtoRowSetFinishing, ProtoTableFunc,
 };
-pub use relation::{ProtoAggregateFunc, ProtoColumnOrder, ProtoRowSetFinishing, ProtoTableFunc};
 pub use scalar::func::{self, BinaryFunc, UnaryFunc, UnmaterializableFunc, VariadicFunc};
 pub use scalar::{like_pattern, EvalError, MirScalarExpr};
 pub use scalar::{ProtoDomainLimit, ProtoEvalError, ProtoMirScalarExpr};
@@ -15,7 +15,7 @@ use mz_repr::proto::{ProtoRepr, TryFromProtoError, TryIntoIfSome};
 use mz_repr::{Datum, Row};
 
 use crate::visit::Visit;
-use crate::{MirRelationExpr, MirScalarExpr};
+use crate::{BinaryFunc, MirRelationExpr, MirScalarExpr};
 
 include!(concat!(env!("OUT_DIR"), "/mz_expr.linear.rs"));
 
@@ -295,6 +295,104 @@ impl MapFilterProject {
         Some(row)
     }
 
+    /// Determines the rows of literals that a sequence of scalar expressions could
+    /// take on, as constrained by the predicates.
+    ///
+    /// Each expression must be constrained to a finite set of literals by some
+    /// predicate, which can be an equality with a literal, a disjunction of such
+    /// equalities (as produced by `IN` lists), or lower and upper bounds on an
+    /// integer (as produced by `BETWEEN`). The result is the cross product of
+    /// those sets. Every row that satisfies the predicates is in the result,
+    /// but rows in the result need not satisfy the predicates.
+    ///
+    /// Like [`MapFilterProject::literal_constraints`], this method returns `None` on
+    /// an empty `exprs`. It also returns `None` if the result would contain more
+    /// than `limit` rows.
+    pub fn literal_constraint_rows(
+        &self,
+        exprs: &[MirScalarExpr],
+        limit: usize,
+    ) -> Option<Vec<Row>> {
+        if exprs.is_empty() {
+            return None;
+        }
+        let mut rows = vec![Row::default()];
+        for expr in exprs {
+            let datums = self.literal_constraint_set(expr, limit)?;
+            if rows.len().saturating_mul(datums.len()) > limit {
+                return None;
+            }
+            let mut extended = Vec::with_capacity(rows.len() * datums.len());
+            for row in &rows {
+                for datum in &datums {
+                    let mut row = row.clone();
+                    row.packer().push(*datum);
+                    extended.push(row);
+                }
+            }
+            rows = extended;
+        }
+        Some(rows)
+    }
+
+    /// Determines a set of at most `limit` literals that a scalar expression must
+    /// be equal to one of, as described in
+    /// [`MapFilterProject::literal_constraint_rows`].
+    fn literal_constraint_set(&self, expr: &MirScalarExpr, limit: usize) -> Option<Vec<Datum>> {
+        // The tightest inclusive integer bounds on `expr`, and a function that
+        // converts integers in those bounds to datums of the type of `expr`.
+        let mut lower = None;
+        let mut upper = None;
+        let mut to_datum = None;
+        for (_pos, predicate) in self.predicates.iter() {
+            let mut datums = Vec::new();
+            if equality_disjunction(predicate, expr, &mut datums) {
+                datums.sort();
+                datums.dedup();
+                if datums.len() <= limit {
+                    return Some(datums);
+                }
+            }
+            if let Some((func, literal)) = comparison_with_literal(predicate, expr) {
+                let (bound, f): (i64, fn(i64) -> Option<Datum<'static>>) = match literal {
+                    Datum::Int16(i) => (i.into(), |i| i16::try_from(i).ok().map(Datum::Int16)),
+                    Datum::Int32(i) => (i.into(), |i| i32::try_from(i).ok().map(Datum::Int32)),
+                    Datum::Int64(i) => (i, |i| Some(Datum::Int64(i))),
+                    _ => continue,
+                };
+                to_datum = Some(f);
+                // Bounds that overflow are ignored, which only loosens them.
+                match func {
+                    BinaryFunc::Gt => lower = lower.max(bound.checked_add(1)),
+                    BinaryFunc::Gte => lower = lower.max(Some(bound)),
+                    BinaryFunc::Lt | BinaryFunc::Lte => {
+                        let bound = match func {
+                            BinaryFunc::Lt => bound.checked_sub(1),
+                            _ => Some(bound),
+                        };
+                        upper = match (upper, bound) {
+                            (Some(upper), Some(bound)) => Some(std::cmp::min(upper, bound)),
+                            (upper, bound) => upper.or(bound),
+                        };
+                    }
+                    _ => unreachable!(),
+                }
+            }
+        }
+        let (lower, upper, to_datum) = (lower?, upper?, to_datum?);
+        if upper < lower {
+            return Some(vec![]);
+        }
+        if u64::try_from(upper.checked_sub(lower)?).ok()? >= u64::try_from(limit).ok()? {
+            return None;
+        }
+        let mut datums = Vec::new();
+        for i in lower..=upper {
+            datums.push(to_datum(i)?);
+        }
+        Some(datums)
+    }
+
     /// Extracts any MapFilterProject at the root of the expression.
     ///
     /// The expression will be modified to extract any maps, filters, and
@@ -1797,12 +1895,105 @@ pub mod plan {
     }
 }
 
+/// Determines whether `predicate` is a disjunction of equalities between `expr`
+/// and literals, and if so, adds those literals to `datums`.
+fn equality_disjunction<'a>(
+    predicate: &'a MirScalarExpr,
+    expr: &MirScalarExpr,
+    datums: &mut Vec<Datum<'a>>,
+) -> bool {
+    match predicate {
+        MirScalarExpr::CallBinary {
+            func: BinaryFunc::Or,
+            expr1,
+            expr2,
+        } => equality_disjunction(expr1, expr, datums) && equality_disjunction(expr2, expr, datums),
+        MirScalarExpr::CallBinary {
+            func: BinaryFunc::Eq,
+            expr1,
+            expr2,
+        } => match (expr1.as_literal(), expr2.as_literal()) {
+            (Some(Ok(datum)), _) if &**expr2 == expr => {
+                datums.push(datum);
+                true
+            }
+            (_, Some(Ok(datum))) if &**expr1 == expr => {
+                datums.push(datum);
+                true
+            }
+            _ => false,
+        },
+        // `IN` lists are planned as disjunctions that start with `false`.
+        _ => predicate.is_literal_false(),
+    }
+}
+
+/// Determines whether `predicate` compares `expr` to a literal, and if so, returns
+/// the comparison as `expr <func> literal`, where `func` is one of `<`, `<=`, `>`
+/// and `>=`.
+fn comparison_with_literal<'a>(
+    predicate: &'a MirScalarExpr,
+    expr: &MirScalarExpr,
+) -> Option<(BinaryFunc, Datum<'a>)> {
+    if let MirScalarExpr::CallBinary { func, expr1, expr2 } = predicate {
+        let flipped = match func {
+            BinaryFunc::Lt => BinaryFunc::Gt,
+            BinaryFunc::Lte => BinaryFunc::Gte,
+            BinaryFunc::Gt => BinaryFunc::Lt,
+            BinaryFunc::Gte => BinaryFunc::Lte,
+            _ => return None,
+        };
+        if &**expr1 == expr {
+            if let Some(Ok(datum)) = expr2.as_literal() {
+                return Some((func.clone(), datum));
+            }
+        }
+        if &**expr2 == expr {
+            if let Some(Ok(datum)) = expr1.as_literal() {
+                return Some((flipped, datum));
+            }
+        }
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::plan::*;
     use super::*;
     use mz_repr::proto::protobuf_roundtrip;
 
+    #[test]
+    fn test_literal_constraint_rows() {
+        use mz_repr::ScalarType;
+
+        let col = MirScalarExpr::column;
+        let lit = |i| MirScalarExpr::literal_ok(Datum::Int32(i), ScalarType::Int32);
+        let row = |datums: &[i32]| Row::pack(datums.iter().map(|i| Datum::Int32(*i)));
+
+        // #0 IN (3, 1) AND #1 BETWEEN 5 AND 6
+        let mfp = MapFilterProject::new(3).filter(vec![
+            col(0)
+                .call_binary(lit(3), BinaryFunc::Eq)
+                .call_binary(lit(1).call_binary(col(0), BinaryFunc::Eq), BinaryFunc::Or),
+            col(1).call_binary(lit(5), BinaryFunc::Gte),
+            lit(7).call_binary(col(1), BinaryFunc::Gt),
+        ]);
+        assert_eq!(
+            mfp.literal_constraint_rows(&[col(0), col(1)], 4),
+            Some(vec![row(&[1, 5]), row(&[1, 6]), row(&[3, 5]), row(&[3, 6])]),
+        );
+        assert_eq!(
+            mfp.literal_constraint_rows(&[col(1)], 4),
+            Some(vec![row(&[5]), row(&[6])]),
+        );
+        // Too many rows.
+        assert_eq!(mfp.literal_constraint_rows(&[col(0), col(1)], 3), None);
+        // Unconstrained expressions.
+        assert_eq!(mfp.literal_constraint_rows(&[col(0), col(2)], 4), None);
+        assert_eq!(mfp.literal_constraint_rows(&[], 4), None);
+    }
+
     proptest! {
         #![proptest_config(ProptestConfig::with_cases(32))]
 
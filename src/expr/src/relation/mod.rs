@@ -168,6 +168,29 @@ pub enum MirRelationExpr {
         #[serde(default)]
         implementation: JoinImplementation,
     },
+    /// Join two collections on equal keys, retaining the rows of one or both
+    /// inputs that have no match, padded with nulls.
+    ///
+    /// A row of `left` matches a row of `right` if `left_key` and `right_key`
+    /// evaluate to the same values and none of these values are null. The
+    /// output contains the columns of `left` followed by the columns of
+    /// `right`.
+    ///
+    /// The runtime memory footprint of this operator is proportional to the
+    /// sizes of both inputs, and the number of distinct keys of the inputs
+    /// whose matches are tracked.
+    OuterJoin {
+        /// The left input.
+        left: Box<MirRelationExpr>,
+        /// The right input.
+        right: Box<MirRelationExpr>,
+        /// Expressions over the columns of `left` that form the join key.
+        left_key: Vec<MirScalarExpr>,
+        /// Expressions over the columns of `right` that form the join key.
+        right_key: Vec<MirScalarExpr>,
+        /// The inputs whose unmatched rows are retained.
+        kind: OuterJoinKind,
+    },
     /// Group a dataflow by some columns and aggregate over each group
     ///
     /// The runtime memory footprint of this operator is at most proportional to the
@@ -579,6 +602,44 @@ impl MirRelationExpr {
                 }
                 typ
             }
+            MirRelationExpr::OuterJoin {
+                right_key, kind, ..
+            } => {
+                let (left_typ, right_typ) = (&input_types[0], &input_types[1]);
+                let column_types = left_typ
+                    .column_types
+                    .iter()
+                    .map(|typ| typ.clone().nullable(typ.nullable || kind.preserves_right()))
+                    .chain(
+                        right_typ
+                            .column_types
+                            .iter()
+                            .map(|typ| typ.clone().nullable(typ.nullable || kind.preserves_left())),
+                    )
+                    .collect();
+                let mut typ = RelationType::new(column_types);
+                // A left outer join produces each row of `left` exactly once
+                // if each row of `left` matches at most one row of `right`,
+                // and so preserves the keys of `left`.
+                if *kind == OuterJoinKind::Left {
+                    let right_key_columns = right_key
+                        .iter()
+                        .map(|expr| expr.as_column())
+                        .collect::<Option<Vec<_>>>();
+                    if let Some(right_key_columns) = right_key_columns {
+                        if right_typ
+                            .keys
+                            .iter()
+                            .any(|key| key.iter().all(|c| right_key_columns.contains(c)))
+                        {
+                            for key in left_typ.keys.iter() {
+                                typ = typ.with_key(key.clone());
+                            }
+                        }
+                    }
+                }
+                typ
+            }
             MirRelationExpr::Reduce {
                 group_key,
                 aggregates,
@@ -748,6 +809,7 @@ impl MirRelationExpr {
             }
             MirRelationExpr::Filter { input, .. } => input.arity(),
             MirRelationExpr::Join { inputs, .. } => inputs.iter().map(|i| i.arity()).sum(),
+            MirRelationExpr::OuterJoin { left, right, .. } => left.arity() + right.arity(),
             MirRelationExpr::Reduce {
                 input: _,
                 group_key,
@@ -977,6 +1039,24 @@ impl MirRelationExpr {
         }
     }
 
+    /// Joins `self` with `right` on `left_key = right_key`, retaining the
+    /// unmatched rows of the inputs selected by `kind`.
+    pub fn outer_join(
+        self,
+        right: Self,
+        left_key: Vec<MirScalarExpr>,
+        right_key: Vec<MirScalarExpr>,
+        kind: OuterJoinKind,
+    ) -> Self {
+        MirRelationExpr::OuterJoin {
+            left: Box::new(self),
+            right: Box::new(right),
+            left_key,
+            right_key,
+            kind,
+        }
+    }
+
     /// Removes all but the first occurrence of each row.
     pub fn distinct(self) -> Self {
         let arity = self.arity();
@@ -1227,6 +1307,15 @@ impl MirRelationExpr {
                     }
                 }
             }
+            OuterJoin {
+                left_key,
+                right_key,
+                ..
+            } => {
+                for s in left_key.iter_mut().chain(right_key.iter_mut()) {
+                    f(s)?;
+                }
+            }
             ArrangeBy { keys, .. } => {
                 for key in keys {
                     for s in key {
@@ -1324,6 +1413,10 @@ impl VisitChildren for MirRelationExpr {
                     f(input);
                 }
             }
+            OuterJoin { left, right, .. } => {
+                f(left);
+                f(right);
+            }
             Union { base, inputs } => {
                 f(base);
                 for input in inputs {
@@ -1360,6 +1453,10 @@ impl VisitChildren for MirRelationExpr {
                     f(input);
                 }
             }
+            OuterJoin { left, right, .. } => {
+                f(left);
+                f(right);
+            }
             Union { base, inputs } => {
                 f(base);
                 for input in inputs {
@@ -1396,6 +1493,10 @@ impl VisitChildren for MirRelationExpr {
                     f(input)?;
                 }
             }
+            OuterJoin { left, right, .. } => {
+                f(left)?;
+                f(right)?;
+            }
             Union { base, inputs } => {
                 f(base)?;
                 for input in inputs {
@@ -1433,6 +1534,10 @@ impl VisitChildren for MirRelationExpr {
                     f(input)?;
                 }
             }
+            OuterJoin { left, right, .. } => {
+                f(left)?;
+                f(right)?;
+            }
             Union { base, inputs } => {
                 f(base)?;
                 for input in inputs {
@@ -1941,6 +2046,80 @@ impl Default for JoinImplementation {
     }
 }
 
+/// The inputs of a [`MirRelationExpr::OuterJoin`] whose unmatched rows are
+/// retained in its output.
+#[derive(Arbitrary, Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize, Hash, MzReflect)]
+pub enum OuterJoinKind {
+    /// Retain the unmatched rows of the left input.
+    Left,
+    /// Retain the unmatched rows of the right input.
+    Right,
+    /// Retain the unmatched rows of both inputs.
+    Full,
+}
+
+impl OuterJoinKind {
+    /// Whether unmatched rows of the left input are retained.
+    pub fn preserves_left(&self) -> bool {
+        matches!(self, OuterJoinKind::Left | OuterJoinKind::Full)
+    }
+
+    /// Whether unmatched rows of the right input are retained.
+    pub fn preserves_right(&self) -> bool {
+        matches!(self, OuterJoinKind::Right | OuterJoinKind::Full)
+    }
+
+    /// The kind of the same join with its inputs swapped.
+    pub fn swapped(&self) -> OuterJoinKind {
+        match self {
+            OuterJoinKind::Left => OuterJoinKind::Right,
+            OuterJoinKind::Right => OuterJoinKind::Left,
+            OuterJoinKind::Full => OuterJoinKind::Full,
+        }
+    }
+}
+
+impl fmt::Display for OuterJoinKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            OuterJoinKind::Left => f.write_str("left"),
+            OuterJoinKind::Right => f.write_str("right"),
+            OuterJoinKind::Full => f.write_str("full"),
+        }
+    }
+}
+
+impl From<&OuterJoinKind> for ProtoOuterJoinKind {
+    fn from(x: &OuterJoinKind) -> Self {
+        use proto_outer_join_kind::Kind;
+        ProtoOuterJoinKind {
+            kind: Some(match x {
+                OuterJoinKind::Left => Kind::Left(()),
+                OuterJoinKind::Right => Kind::Right(()),
+                OuterJoinKind::Full => Kind::Full(()),
+            }),
+        }
+    }
+}
+
+impl TryFrom<ProtoOuterJoinKind> for OuterJoinKind {
+    type Error = TryFromProtoError;
+
+    fn try_from(x: ProtoOuterJoinKind) -> Result<Self, Self::Error> {
+        use proto_outer_join_kind::Kind;
+        Ok(match x.kind {
+            Some(Kind::Left(())) => OuterJoinKind::Left,
+            Some(Kind::Right(())) => OuterJoinKind::Right,
+            Some(Kind::Full(())) => OuterJoinKind::Full,
+            None => {
+                return Err(TryFromProtoError::MissingField(
+                    "ProtoOuterJoinKind::kind".into(),
+                ))
+            }
+        })
+    }
+}
+
 /// Instructions for finishing the result of a query.
 ///
 /// The primary reason for the existence of this structure and attendant code
@@ -2021,9 +2200,8 @@ impl RowSetFinishing {
         // return nothing.
         (rows.len(), 0)
     }
-    /// Applies finishing actions to a row set,
-    /// and unrolls it to a unary representation.
-    pub fn finish(&self, mut rows: Vec<(Row, NonZeroUsize)>) -> Vec<Row> {
+    /// Sorts a row set by `self.order_by`, breaking ties by the rows themselves.
+    fn sort(&self, rows: &mut [(Row, NonZeroUsize)]) {
         let mut left_datum_vec = mz_repr::DatumVec::new();
         let mut right_datum_vec = mz_repr::DatumVec::new();
         let sort_by = |(left, _): &(Row, _), (right, _): &(Row, _)| {
@@ -2034,6 +2212,11 @@ impl RowSetFinishing {
             })
         };
         rows.sort_by(sort_by);
+    }
+    /// Applies finishing actions to a row set,
+    /// and unrolls it to a unary representation.
+    pub fn finish(&self, mut rows: Vec<(Row, NonZeroUsize)>) -> Vec<Row> {
+        self.sort(&mut rows);
 
         let (offset_nth_row, offset_kth_copy) = self.find_offset(&rows);
 
@@ -2096,6 +2279,93 @@ impl RowSetFinishing {
     }
 }
 
+/// Applies a [`RowSetFinishing`] to a row set that arrives in batches.
+///
+/// Unless the finishing orders the rows, each batch is finished as it arrives,
+/// and it is known once the finishing will produce no further rows. Otherwise
+/// the rows are held back until all of them have arrived, though no more of
+/// them than the finishing could return.
+#[derive(Debug)]
+pub struct IncrementalFinishing {
+    finishing: RowSetFinishing,
+    /// The number of rows still to omit, if the rows are not ordered.
+    offset: usize,
+    /// The number of rows still to include, if the rows are not ordered.
+    limit: usize,
+    /// The rows held back until all have arrived, if the rows are ordered.
+    held: Vec<(Row, NonZeroUsize)>,
+}
+
+impl IncrementalFinishing {
+    /// Prepares to apply `finishing` to a row set that arrives in batches.
+    pub fn new(finishing: RowSetFinishing) -> Self {
+        IncrementalFinishing {
+            offset: finishing.offset,
+            limit: finishing.limit.unwrap_or(usize::MAX),
+            finishing,
+            held: Vec::new(),
+        }
+    }
+
+    /// Applies finishing actions to a batch of rows, and returns the rows that
+    /// can be produced so far in a unary representation.
+    pub fn push(&mut self, rows: Vec<(Row, NonZeroUsize)>) -> Vec<Row> {
+        if !self.finishing.order_by.is_empty() {
+            self.held.extend(rows);
+            // If we hold many more rows than the finishing could return, we can
+            // thin them down to those that come first in its ordering. We use a
+            // threshold twice what we intend, to amortize the work of sorting.
+            if let Some(limit) = self.finishing.limit {
+                let max_rows = limit + self.finishing.offset;
+                if self.held.len() >= 2 * max_rows {
+                    self.finishing.sort(&mut self.held);
+                    self.held.truncate(max_rows);
+                }
+            }
+            return Vec::new();
+        }
+
+        let mut ret = Vec::new();
+        let mut row_buf = Row::default();
+        let mut datum_vec = mz_repr::DatumVec::new();
+        for (row, count) in rows {
+            if self.limit == 0 {
+                break;
+            }
+            let skipped = std::cmp::min(count.get(), self.offset);
+            self.offset -= skipped;
+            let count = std::cmp::min(count.get() - skipped, self.limit);
+            self.limit -= count;
+            if count > 0 {
+                let new_row = {
+                    let datums = datum_vec.borrow_with(&row);
+                    row_buf
+                        .packer()
+                        .extend(self.finishing.project.iter().map(|i| &datums[*i]));
+                    row_buf.clone()
+                };
+                ret.extend(std::iter::repeat(new_row).take(count));
+            }
+        }
+        ret
+    }
+
+    /// Reports whether the finishing will produce no further rows.
+    pub fn is_done(&self) -> bool {
+        self.finishing.order_by.is_empty() && self.limit == 0
+    }
+
+    /// Applies finishing actions to the rows held back until all had arrived,
+    /// and returns them in a unary representation.
+    pub fn finish(self) -> Vec<Row> {
+        if self.finishing.order_by.is_empty() {
+            Vec::new()
+        } else {
+            self.finishing.finish(self.held)
+        }
+    }
+}
+
 /// Compare `left` and `right` using `order`. If that doesn't produce a strict ordering, call `tiebreaker`.
 pub fn compare_columns<F>(
     order: &[ColumnOrder],
@@ -2353,6 +2623,55 @@ mod tests {
     use mz_repr::proto::protobuf_roundtrip;
     use proptest::prelude::*;
 
+    #[test]
+    fn test_incremental_finishing() {
+        let one = NonZeroUsize::new(1).unwrap();
+        let two = NonZeroUsize::new(2).unwrap();
+        let mut rows = (0..20)
+            .map(|i| {
+                let row = Row::pack_slice(&[Datum::Int64(i % 7), Datum::Int64(i)]);
+                (row, if i % 3 == 0 { two } else { one })
+            })
+            .collect::<Vec<_>>();
+        // Without an ordering, rows are finished in the order they arrive.
+        rows.sort();
+
+        for order_by in [
+            vec![],
+            vec![ColumnOrder {
+                column: 0,
+                desc: true,
+            }],
+        ] {
+            for (offset, limit) in [
+                (0, None),
+                (3, None),
+                (0, Some(4)),
+                (5, Some(7)),
+                (2, Some(0)),
+            ] {
+                let finishing = RowSetFinishing {
+                    order_by: order_by.clone(),
+                    limit,
+                    offset,
+                    project: vec![1],
+                };
+                let expected = finishing.finish(rows.clone());
+
+                let mut incremental = IncrementalFinishing::new(finishing);
+                let mut actual = vec![];
+                for batch in rows.chunks(3) {
+                    actual.extend(incremental.push(batch.to_vec()));
+                }
+                if order_by.is_empty() {
+                    assert_eq!(incremental.is_done(), limit.is_some());
+                }
+                actual.extend(incremental.finish());
+                assert_eq!(actual, expected, "offset {offset}, limit {limit:?}");
+            }
+        }
+    }
+
     proptest! {
         #[test]
         fn column_order_protobuf_roundtrip(expect in any::<ColumnOrder>()) {
@@ -2371,6 +2690,15 @@ mod tests {
         }
     }
 
+    proptest! {
+        #[test]
+        fn outer_join_kind_protobuf_roundtrip(expect in any::<OuterJoinKind>()) {
+            let actual = protobuf_roundtrip::<_, ProtoOuterJoinKind>(&expect);
+            assert!(actual.is_ok());
+            assert_eq!(actual.unwrap(), expect);
+        }
+    }
+
     proptest! {
         #[test]
         fn window_frame_units_protobuf_roundtrip(expect in any::<WindowFrameUnits>()) {
@@ -20,15 +20,15 @@ use uuid::Uuid;
 use mz_avro::error::{DecodeError, Error as AvroError};
 use mz_avro::{
     define_unexpected, give_value, AvroArrayAccess, AvroDecode, AvroDeserializer, AvroMapAccess,
-    AvroRead, AvroRecordAccess, GeneralDeserializer, StatefulAvroDecodable, ValueDecoder,
-    ValueOrReader,
+    AvroRead, AvroRecordAccess, BlockIter, GeneralDeserializer, Schema, StatefulAvroDecodable,
+    ValueDecoder, ValueOrReader,
 };
 use mz_ore::result::ResultExt;
 use mz_repr::adt::jsonb::JsonbPacker;
 use mz_repr::adt::numeric;
 use mz_repr::{Datum, Row, RowPacker};
 
-use crate::avro::ConfluentAvroResolver;
+use crate::avro::{parse_schema, ConfluentAvroResolver};
 
 /// Manages decoding of Avro-encoded bytes.
 #[derive(Debug)]
@@ -43,7 +43,10 @@ pub struct Decoder {
 mod tests {
     use futures::executor::block_on;
 
-    use crate::avro::Decoder;
+    use mz_avro::types::Value;
+    use mz_avro::Writer;
+
+    use crate::avro::{parse_schema, Decoder, OcfDecoder};
     use mz_repr::{Datum, Row};
 
     #[test]
@@ -65,6 +68,41 @@ mod tests {
             Row::pack([Datum::Int32(0), Datum::Int32(0)])
         );
     }
+
+    #[test]
+    fn test_ocf_schema_resolution() {
+        let writer_schema = r#"{
+"type": "record",
+"name": "test",
+"fields": [{"name": "f1", "type": "int"}, {"name": "f2", "type": "string"}]
+}"#;
+        let reader_schema = r#"{
+"type": "record",
+"name": "test",
+"fields": [{"name": "f2", "type": "string"}, {"name": "f1", "type": "long"}]
+}"#;
+        let mut writer = Writer::new(parse_schema(writer_schema).unwrap(), vec![]);
+        for i in 0..3 {
+            writer
+                .append(Value::Record(vec![
+                    ("f1".into(), Value::Int(i)),
+                    ("f2".into(), Value::String(i.to_string())),
+                ]))
+                .unwrap();
+        }
+        writer.flush().unwrap();
+        let file = writer.into_inner();
+
+        let mut decoder = OcfDecoder::new(reader_schema).unwrap();
+        assert_eq!(
+            decoder.decode(&file).unwrap(),
+            (0..3)
+                .map(|i| Row::pack([Datum::String(&i.to_string()), Datum::Int64(i)]))
+                .collect::<Vec<_>>()
+        );
+        // Garbage is an error, not a panic.
+        assert!(decoder.decode(&[1, 2, 3]).is_err());
+    }
 }
 
 impl Decoder {
@@ -126,6 +164,54 @@ impl Decoder {
     }
 }
 
+/// Manages decoding of Avro object container files.
+#[derive(Debug)]
+pub struct OcfDecoder {
+    reader_schema: Schema,
+    buf1: Vec<u8>,
+    row_buf: Row,
+}
+
+impl OcfDecoder {
+    /// Creates a new `OcfDecoder`.
+    ///
+    /// The records of each file are resolved from the writer schema in the
+    /// file's header to the provided reader schema.
+    pub fn new(reader_schema: &str) -> anyhow::Result<OcfDecoder> {
+        Ok(OcfDecoder {
+            reader_schema: parse_schema(reader_schema)?,
+            buf1: vec![],
+            row_buf: Row::default(),
+        })
+    }
+
+    /// Decodes every record in the object container file `bytes` into a `Row`.
+    pub fn decode(&mut self, bytes: &[u8]) -> anyhow::Result<Vec<Row>> {
+        let blocks = BlockIter::with_schema(&self.reader_schema, bytes)?;
+        let schema = blocks.schema().clone();
+        let mut rows = vec![];
+        for block in blocks {
+            let block = block?;
+            let mut data = &block.bytes[..];
+            for _ in 0..block.len {
+                let mut packer = self.row_buf.packer();
+                let dec = AvroFlatDecoder {
+                    packer: &mut packer,
+                    buf: &mut self.buf1,
+                    is_top: true,
+                };
+                let dsr = GeneralDeserializer {
+                    schema: schema.top_node(),
+                };
+                dsr.deserialize(&mut data, dec)
+                    .with_context(|| format!("unable to decode record {}", rows.len() + 1))?;
+                rows.push(self.row_buf.clone());
+            }
+        }
+        Ok(rows)
+    }
+}
+
 pub struct AvroStringDecoder<'a> {
     pub buf: &'a mut Vec<u8>,
 }
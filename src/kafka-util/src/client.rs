@@ -9,6 +9,7 @@
 
 //! Helpers for working with Kafka's client API.
 
+use std::collections::BTreeMap;
 use std::time::Duration;
 
 use anyhow::bail;
@@ -90,3 +91,22 @@ pub fn get_partitions<C: ClientContext>(
 
     Ok(meta_topic.partitions().iter().map(|x| x.id()).collect())
 }
+
+/// Retrieve the partitions of every topic whose name satisfies `filter` using
+/// the given `client`
+pub fn get_matching_partitions<C: ClientContext>(
+    client: &Client<C>,
+    filter: impl Fn(&str) -> bool,
+    timeout: Duration,
+) -> Result<BTreeMap<String, Vec<i32>>, anyhow::Error> {
+    let meta = client.fetch_metadata(None, timeout)?;
+    Ok(meta
+        .topics()
+        .iter()
+        .filter(|topic| filter(topic.name()) && !topic.partitions().is_empty())
+        .map(|topic| {
+            let partitions = topic.partitions().iter().map(|x| x.id()).collect();
+            (topic.name().to_string(), partitions)
+        })
+        .collect())
+}
@@ -49,6 +49,7 @@ use uuid::Uuid;
 use materialized::{
     OrchestratorBackend, OrchestratorConfig, SecretsControllerConfig, TlsConfig, TlsMode,
 };
+use mz_coord::{PlanCacheConfig, StatementLogConfig};
 use mz_dataflow_types::sources::AwsExternalId;
 use mz_frontegg_auth::{FronteggAuthentication, FronteggConfig};
 use mz_orchestrator_kubernetes::{KubernetesImagePullPolicy, KubernetesOrchestratorConfig};
@@ -143,6 +144,14 @@ pub struct Args {
     /// The mount location of the Kubernetes secret object to use for storing user secrets
     #[structopt(long, hide = true, required_if_eq("orchestrator", "kubernetes"))]
     user_defined_secret_mount_path: Option<String>,
+    /// The file that holds the master key that encrypts secrets stored in the
+    /// data directory. Defaults to `secrets.key` in the data directory.
+    #[clap(long, env = "MZ_SECRETS_MASTER_KEY_PATH", value_name = "PATH")]
+    secrets_master_key_path: Option<PathBuf>,
+    /// Rotate the master key that encrypts secrets stored in the data
+    /// directory, re-encrypting every secret, before starting.
+    #[clap(long, env = "MZ_SECRETS_ROTATE_MASTER_KEY")]
+    secrets_rotate_master_key: bool,
     /// The storaged image reference to use.
     #[structopt(
         long,
@@ -227,6 +236,52 @@ pub struct Args {
     )]
     log_filter: String,
 
+    /// The fraction of executed statements to record in the
+    /// `mz_statement_log` table, between 0 and 1.
+    ///
+    /// The default value of 0 disables the statement log.
+    #[clap(
+        long,
+        env = "MZ_STATEMENT_LOGGING_SAMPLE_RATE",
+        value_name = "RATE",
+        default_value = "0"
+    )]
+    statement_logging_sample_rate: f64,
+    /// How long to retain statements in the `mz_statement_log` table after
+    /// they finish.
+    #[clap(long, env = "MZ_STATEMENT_LOGGING_RETENTION", parse(try_from_str = mz_repr::util::parse_duration), value_name = "DURATION", default_value = "1h")]
+    statement_logging_retention: Duration,
+    /// Replace all literals in the SQL text recorded in the `mz_statement_log`
+    /// table with a placeholder.
+    ///
+    /// Secret values and passwords are redacted regardless of this option.
+    #[clap(long, env = "MZ_STATEMENT_LOGGING_REDACT_LITERALS")]
+    statement_logging_redact_literals: bool,
+
+    // === Planning options. ===
+    /// The number of optimized plans of `SELECT` statements to cache for each
+    /// session.
+    ///
+    /// A value of 0 disables the per-session plan caches.
+    #[clap(
+        long,
+        env = "MZ_PLAN_CACHE_SIZE",
+        value_name = "N",
+        default_value = "128"
+    )]
+    plan_cache_size: usize,
+    /// The number of optimized plans of `SELECT` statements to cache for all
+    /// sessions together.
+    ///
+    /// The default value of 0 disables the global plan cache.
+    #[clap(
+        long,
+        env = "MZ_GLOBAL_PLAN_CACHE_SIZE",
+        value_name = "N",
+        default_value = "0"
+    )]
+    global_plan_cache_size: usize,
+
     /// Prevent dumping of backtraces on SIGSEGV/SIGBUS
     ///
     /// In the case of OOMs and memory corruptions, it may be advantageous to NOT dump backtraces,
@@ -741,6 +796,10 @@ max log level: {max_log_level}",
         bail!("--availability-zone values must be unique");
     }
 
+    if !(0.0..=1.0).contains(&args.statement_logging_sample_rate) {
+        bail!("--statement-logging-sample-rate must be between 0 and 1");
+    }
+
     let secrets_controller = match args.orchestrator {
         Orchestrator::Kubernetes => SecretsControllerConfig::Kubernetes {
             context: args.kubernetes_context,
@@ -748,12 +807,24 @@ max log level: {max_log_level}",
             user_defined_secret_mount_path: args.user_defined_secret_mount_path.unwrap_or_default(),
             refresh_pod_name: args.pod_name.unwrap_or_default(),
         },
-        Orchestrator::Process => SecretsControllerConfig::LocalFileSystem,
+        Orchestrator::Process => SecretsControllerConfig::LocalFileSystem {
+            master_key_path: args.secrets_master_key_path,
+            rotate_master_key: args.secrets_rotate_master_key,
+        },
     };
 
     let server = runtime.block_on(materialized::serve(materialized::Config {
         logical_compaction_window: args.logical_compaction_window,
         timestamp_frequency: args.timestamp_frequency,
+        statement_log: StatementLogConfig {
+            sample_rate: args.statement_logging_sample_rate,
+            retention: args.statement_logging_retention,
+            redact_literals: args.statement_logging_redact_literals,
+        },
+        plan_cache: PlanCacheConfig {
+            session_capacity: args.plan_cache_size,
+            global_capacity: args.global_plan_cache_size,
+        },
         listen_addr: args.listen_addr,
         metrics_listen_addr: args.metrics_listen_addr,
         tls,
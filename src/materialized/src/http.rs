@@ -11,7 +11,7 @@
 //!
 //! materialized embeds an HTTP server for introspection into the running
 //! process. At the moment, its primary exports are Prometheus metrics, heap
-//! profiles, and catalog dumps.
+//! profiles, catalog dumps, and the SQL-over-HTTP and SQL-over-WebSocket APIs.
 
 // Axum handlers must use async, but often don't actually use `await`.
 #![allow(clippy::unused_async)]
@@ -115,6 +115,7 @@ impl Server {
                 routing::get(catalog::handle_internal_catalog),
             )
             .route("/api/sql", routing::post(sql::handle_sql))
+            .route("/api/ws", routing::get(sql::handle_sql_ws))
             .route("/memory", routing::get(memory::handle_memory))
             .route(
                 "/hierarchical-memory",
@@ -122,10 +123,11 @@ impl Server {
             )
             .nest("/prof/", mz_prof::http::router(&BUILD_INFO))
             .route("/static/*path", routing::get(root::handle_static))
-            .layer(Extension(coord_client))
+            .layer(Extension(coord_client.clone()))
             .layer(middleware::from_fn(move |req, next| {
                 let frontegg = Arc::clone(&frontegg);
-                async move { auth(req, next, tls_mode, &frontegg).await }
+                let coord_client = coord_client.clone();
+                async move { auth(req, next, tls_mode, &frontegg, &coord_client).await }
             }))
             .layer(
                 CorsLayer::new()
@@ -184,7 +186,10 @@ impl Server {
         let router = self.router.lock().expect("lock poisoned").clone();
         let svc = router.layer(Extension(conn_protocol));
         let http = hyper::server::conn::Http::new();
-        http.serve_connection(conn, svc).err_into().await
+        http.serve_connection(conn, svc)
+            .with_upgrades()
+            .err_into()
+            .await
     }
 
     // Handler functions are attached by various submodules. They all have a
@@ -250,6 +255,8 @@ enum AuthError {
     MissingHttpAuthentication,
     #[error("{0}")]
     MismatchedUser(&'static str),
+    #[error("password authentication failed for user {0}")]
+    InvalidPassword(String),
 }
 
 impl IntoResponse for AuthError {
@@ -270,6 +277,7 @@ async fn auth<B>(
     next: Next<B>,
     tls_mode: Option<TlsMode>,
     frontegg: &Option<FronteggAuthentication>,
+    coord_client: &mz_coord::Client,
 ) -> impl IntoResponse {
     // There are three places a username may be specified:
     //
@@ -299,9 +307,28 @@ async fn auth<B>(
 
     // Then, handle Frontegg authentication if required.
     let user = match frontegg {
-        // If no Frontegg authentication, we can use the cert's username if
-        // present, otherwise the system user.
-        None => user.unwrap_or_else(|| SYSTEM_USER.to_string()),
+        // If no Frontegg authentication, basic auth credentials are checked
+        // against the password of the specified role, if it has one. Otherwise
+        // we can use the cert's username if present, otherwise the system
+        // user.
+        None => match req.headers().typed_get::<Authorization<Basic>>() {
+            Some(basic) => {
+                if let Some(user) = user {
+                    if basic.username() != user {
+                        return Err(AuthError::MismatchedUser(
+                            "user in client certificate did not match user specified in authorization header",
+                        ));
+                    }
+                }
+                if let Some(verifier) = coord_client.password_verifier(basic.username()).await {
+                    if !verifier.verify_password(basic.password()).unwrap_or(false) {
+                        return Err(AuthError::InvalidPassword(basic.username().into()));
+                    }
+                }
+                basic.username().to_string()
+            }
+            None => user.unwrap_or_else(|| SYSTEM_USER.to_string()),
+        },
         // If we require Frontegg auth, fetch credentials from the HTTP auth
         // header. Basic auth comes with a username/password, where the password
         // is the client+secret pair. Bearer auth is an existing JWT that must
@@ -7,10 +7,18 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
 use axum::response::IntoResponse;
 use axum::Json;
 use http::StatusCode;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::debug;
+
+use mz_coord::session::{EndTransactionAction, RowBatchStream, TransactionStatus};
+use mz_coord::{ExecuteResponse, PeekResponseUnary, SessionClient};
+use mz_repr::{DatumVec, RelationDesc};
+use mz_sql::ast::{Raw, Statement};
 
 use crate::http::AuthedClient;
 
@@ -28,3 +36,315 @@ pub async fn handle_sql(
         Err(e) => Err((StatusCode::BAD_REQUEST, e.to_string())),
     }
 }
+
+/// A request sent by a client of the WebSocket SQL API.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum WebSocketRequest {
+    /// Executes the statements in `query`.
+    ///
+    /// `params` supplies values for the parameters of `query`, in the
+    /// PostgreSQL text format. Parameters are only permitted if `query`
+    /// contains exactly one statement.
+    Query {
+        query: String,
+        #[serde(default)]
+        params: Vec<Option<String>>,
+    },
+    /// Cancels the currently executing statement, if any.
+    Cancel,
+}
+
+/// A response sent to a client of the WebSocket SQL API.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", content = "payload")]
+enum WebSocketResponse {
+    /// The names of the columns of the rows that follow.
+    Rows(Vec<String>),
+    /// A row returned by a statement.
+    Row(Vec<serde_json::Value>),
+    /// A statement completed successfully. The payload is the PostgreSQL
+    /// command tag, e.g., `SELECT 1`.
+    CommandComplete(String),
+    /// A statement failed.
+    Error(String),
+    /// The server is ready for the next request.
+    ReadyForQuery,
+}
+
+#[derive(Debug, Error)]
+enum WebSocketError {
+    /// The statement failed. The failure is reported to the client.
+    #[error("{0}")]
+    Statement(String),
+    /// The client closed the connection.
+    #[error("connection closed")]
+    Closed,
+    #[error(transparent)]
+    Socket(#[from] axum::Error),
+}
+
+impl WebSocketError {
+    fn canceled(client: &SessionClient) -> WebSocketError {
+        WebSocketError::Statement(client.cancel_reason().message().into())
+    }
+}
+
+/// Serves the WebSocket SQL API.
+///
+/// Unlike [`handle_sql`], the WebSocket API supports parameterized statements
+/// and streams results as they become available, which permits `TAIL`.
+pub async fn handle_sql_ws(
+    AuthedClient(client): AuthedClient,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    ws.on_upgrade(|ws| async move {
+        if let Err(e) = run_ws(client, ws).await {
+            debug!("WebSocket SQL connection terminated: {}", e);
+        }
+    })
+}
+
+async fn run_ws(mut client: SessionClient, mut ws: WebSocket) -> Result<(), WebSocketError> {
+    send(&mut ws, WebSocketResponse::ReadyForQuery).await?;
+    loop {
+        // Like pgwire, terminate sessions that sit idle in an explicit
+        // transaction for longer than `idle_in_transaction_session_timeout`.
+        let session = client.session();
+        let idle_timeout = match session.transaction() {
+            TransactionStatus::InTransaction(_) | TransactionStatus::Failed(_) => {
+                Some(session.vars().idle_in_transaction_session_timeout())
+            }
+            _ => None,
+        }
+        .filter(|timeout| !timeout.is_zero());
+        let request = match idle_timeout {
+            None => recv(&mut ws).await?,
+            Some(timeout) => match tokio::time::timeout(timeout, recv(&mut ws)).await {
+                Ok(request) => request?,
+                Err(_) => {
+                    let message = "terminating connection due to idle-in-transaction timeout";
+                    send(&mut ws, WebSocketResponse::Error(message.into())).await?;
+                    return Err(WebSocketError::Closed);
+                }
+            },
+        };
+        let request = match request {
+            Some(request) => request,
+            None => continue,
+        };
+        match request {
+            Ok(WebSocketRequest::Query { query, params }) => {
+                client.reset_canceled();
+                execute_query(&mut client, &mut ws, &query, params).await?;
+            }
+            // There is no running statement to cancel.
+            Ok(WebSocketRequest::Cancel) => (),
+            Err(e) => send(&mut ws, WebSocketResponse::Error(e)).await?,
+        }
+        send(&mut ws, WebSocketResponse::ReadyForQuery).await?;
+    }
+}
+
+/// Receives the next request from the client.
+///
+/// Returns `None` for messages that carry no request, like pings, and an
+/// error in the inner result if the request is malformed.
+async fn recv(
+    ws: &mut WebSocket,
+) -> Result<Option<Result<WebSocketRequest, String>>, WebSocketError> {
+    match ws.recv().await {
+        None | Some(Ok(Message::Close(_))) => Err(WebSocketError::Closed),
+        Some(Err(e)) => Err(e.into()),
+        Some(Ok(Message::Text(text))) => Ok(Some(
+            serde_json::from_str(&text).map_err(|e| format!("invalid request: {}", e)),
+        )),
+        Some(Ok(Message::Binary(_))) => Ok(Some(Err("binary messages are not supported".into()))),
+        Some(Ok(Message::Ping(_))) | Some(Ok(Message::Pong(_))) => Ok(None),
+    }
+}
+
+async fn send(ws: &mut WebSocket, response: WebSocketResponse) -> Result<(), WebSocketError> {
+    let text = serde_json::to_string(&response).expect("responses serialize to JSON");
+    Ok(ws.send(Message::Text(text)).await?)
+}
+
+/// Executes the statements in `query`, mirroring the PostgreSQL simple query
+/// protocol.
+///
+/// See the `pgwire::protocol::StateMachine::query` method for details.
+async fn execute_query(
+    client: &mut SessionClient,
+    ws: &mut WebSocket,
+    query: &str,
+    params: Vec<Option<String>>,
+) -> Result<(), WebSocketError> {
+    let stmts = match mz_sql::parse::parse(query) {
+        Ok(stmts) => stmts,
+        Err(e) => return send(ws, WebSocketResponse::Error(e.to_string())).await,
+    };
+    if !params.is_empty() && stmts.len() != 1 {
+        let message = "parameters are only supported for queries with exactly one statement";
+        return send(ws, WebSocketResponse::Error(message.into())).await;
+    }
+
+    let num_stmts = stmts.len();
+    for stmt in stmts {
+        // In an aborted transaction, reject all commands except COMMIT/ROLLBACK.
+        let is_txn_exit_stmt = matches!(stmt, Statement::Commit(_) | Statement::Rollback(_));
+        if let TransactionStatus::Failed(_) = client.session().transaction() {
+            if !is_txn_exit_stmt {
+                let message = "current transaction is aborted, \
+                    commands ignored until end of transaction block";
+                send(ws, WebSocketResponse::Error(message.into())).await?;
+                break;
+            }
+        }
+
+        let res = match client.start_transaction(Some(num_stmts)).await {
+            Ok(()) => execute_stmt(client, ws, stmt, params.clone()).await,
+            Err(e) => Err(WebSocketError::Statement(e.to_string())),
+        };
+        client.session().remove_portal(EMPTY_PORTAL);
+        match res {
+            Ok(()) => (),
+            Err(WebSocketError::Statement(message)) => {
+                send(ws, WebSocketResponse::Error(message)).await?;
+                match client.session().transaction() {
+                    TransactionStatus::Default | TransactionStatus::Failed(_) => (),
+                    // Single statements and implicit transactions are rolled
+                    // back. Among other things, this drops any `TAIL`.
+                    TransactionStatus::Started(_) | TransactionStatus::InTransactionImplicit(_) => {
+                        end_transaction(client, ws, EndTransactionAction::Rollback).await?
+                    }
+                    // Explicit transactions move to failed.
+                    TransactionStatus::InTransaction(_) => client.fail_transaction(),
+                }
+                break;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    // Implicit transactions are closed at the end of a query.
+    if client.session().transaction().is_implicit() {
+        end_transaction(client, ws, EndTransactionAction::Commit).await?;
+    }
+    Ok(())
+}
+
+async fn end_transaction(
+    client: &mut SessionClient,
+    ws: &mut WebSocket,
+    action: EndTransactionAction,
+) -> Result<(), WebSocketError> {
+    match client.end_transaction(action).await {
+        Ok(_) => Ok(()),
+        Err(e) => send(ws, WebSocketResponse::Error(e.to_string())).await,
+    }
+}
+
+const EMPTY_PORTAL: &str = "";
+
+async fn execute_stmt(
+    client: &mut SessionClient,
+    ws: &mut WebSocket,
+    stmt: Statement<Raw>,
+    params: Vec<Option<String>>,
+) -> Result<(), WebSocketError> {
+    let statement_error = |e: mz_coord::CoordError| WebSocketError::Statement(e.to_string());
+    client
+        .declare_with_params(EMPTY_PORTAL.into(), stmt, params)
+        .await
+        .map_err(statement_error)?;
+    let desc = client
+        .session()
+        .get_portal_unverified(EMPTY_PORTAL)
+        .map(|portal| portal.desc.relation_desc.clone())
+        .expect("unnamed portal should be present");
+    let tag = match client
+        .execute(EMPTY_PORTAL.into())
+        .await
+        .map_err(statement_error)?
+    {
+        ExecuteResponse::SendingRows(rows) => {
+            let desc = desc.expect("missing row description for ExecuteResponse::SendingRows");
+            let n = stream_rows(client, ws, desc, rows).await?;
+            format!("SELECT {}", n)
+        }
+        ExecuteResponse::Tailing { rx } => {
+            let desc = desc.expect("missing row description for ExecuteResponse::Tailing");
+            let n = stream_rows(client, ws, desc, rx).await?;
+            format!("TAIL {}", n)
+        }
+        ExecuteResponse::Canceled => return Err(WebSocketError::canceled(client)),
+        ExecuteResponse::Inserted(n) => format!("INSERT 0 {}", n),
+        ExecuteResponse::Updated(n) => format!("UPDATE {}", n),
+        ExecuteResponse::Deleted(n) => format!("DELETE {}", n),
+        ExecuteResponse::StartedTransaction { .. } => "BEGIN".into(),
+        ExecuteResponse::TransactionExited { tag, .. } => tag.into(),
+        ExecuteResponse::SetVariable { .. } => "SET".into(),
+        ExecuteResponse::Fetch { .. }
+        | ExecuteResponse::CopyTo { .. }
+        | ExecuteResponse::CopyFrom { .. }
+        | ExecuteResponse::Raise { .. }
+        | ExecuteResponse::DeclaredCursor
+        | ExecuteResponse::ClosedCursor => {
+            return Err(WebSocketError::Statement(
+                "executing statements of this type is unsupported via this API".into(),
+            ))
+        }
+        // Other statements, like DDL, have no results to report.
+        _ => "OK".into(),
+    };
+    send(ws, WebSocketResponse::CommandComplete(tag)).await
+}
+
+/// Streams rows to the client until `rows` is exhausted or the client cancels
+/// the statement. Returns the number of rows sent.
+async fn stream_rows(
+    client: &mut SessionClient,
+    ws: &mut WebSocket,
+    desc: RelationDesc,
+    mut rows: RowBatchStream,
+) -> Result<usize, WebSocketError> {
+    let columns = desc.iter_names().map(|name| name.to_string()).collect();
+    send(ws, WebSocketResponse::Rows(columns)).await?;
+
+    let mut count = 0;
+    let mut datum_vec = DatumVec::new();
+    loop {
+        tokio::select! {
+            _ = client.canceled() => return Err(WebSocketError::canceled(client)),
+            request = recv(ws) => match request? {
+                Some(Ok(WebSocketRequest::Cancel)) => {
+                    // Cancel the statement in the coordinator too, so that
+                    // pending peeks are cleaned up.
+                    client.cancel().await;
+                    return Err(WebSocketError::canceled(client));
+                }
+                Some(Ok(WebSocketRequest::Query { .. })) => {
+                    let message = "cannot execute a query while another query is in progress";
+                    send(ws, WebSocketResponse::Error(message.into())).await?;
+                }
+                Some(Err(e)) => send(ws, WebSocketResponse::Error(e)).await?,
+                None => (),
+            },
+            batch = rows.recv() => match batch {
+                None => return Ok(count),
+                Some(PeekResponseUnary::Rows(batch)) => {
+                    count += batch.len();
+                    for row in batch {
+                        let datums = datum_vec.borrow_with(&row);
+                        let row = datums.iter().map(mz_coord::datum_to_json).collect();
+                        send(ws, WebSocketResponse::Row(row)).await?;
+                    }
+                }
+                Some(PeekResponseUnary::Error(e)) => return Err(WebSocketError::Statement(e)),
+                Some(PeekResponseUnary::Canceled) => {
+                    return Err(WebSocketError::canceled(client))
+                }
+            },
+        }
+    }
+}
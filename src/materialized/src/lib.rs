@@ -18,7 +18,7 @@ use std::fs::Permissions;
 use std::net::SocketAddr;
 use std::num::NonZeroUsize;
 use std::os::unix::fs::PermissionsExt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::time::Duration;
 use std::{env, fs};
@@ -26,6 +26,7 @@ use std::{env, fs};
 use anyhow::{anyhow, Context};
 use futures::StreamExt;
 use mz_build_info::{build_info, BuildInfo};
+use mz_coord::{PlanCacheConfig, StatementLogConfig};
 use mz_dataflow_types::client::controller::ClusterReplicaSizeMap;
 use mz_dataflow_types::client::RemoteClient;
 use mz_dataflow_types::sources::AwsExternalId;
@@ -76,6 +77,14 @@ pub struct Config {
     /// The interval at which sources should be timestamped.
     pub timestamp_frequency: Duration,
 
+    // === Logging options. ===
+    /// Configuration of the sampled log of executed statements.
+    pub statement_log: StatementLogConfig,
+
+    // === Planning options. ===
+    /// Configuration of the caches of optimized plans.
+    pub plan_cache: PlanCacheConfig,
+
     // === Connection options. ===
     /// The IP address and port to listen on.
     pub listen_addr: SocketAddr,
@@ -183,7 +192,16 @@ pub enum OrchestratorBackend {
 /// Configuration for the service orchestrator.
 #[derive(Debug, Clone)]
 pub enum SecretsControllerConfig {
-    LocalFileSystem,
+    /// Store encrypted secrets in the `secrets` subdirectory of the data
+    /// directory.
+    LocalFileSystem {
+        /// The file that holds the master key that encrypts the secrets. If
+        /// `None`, the key is stored in `secrets.key` in the data directory.
+        master_key_path: Option<PathBuf>,
+        /// Whether to rotate the master key, re-encrypting every secret, at
+        /// startup.
+        rotate_master_key: bool,
+    },
     // Create a Kubernetes Controller.
     Kubernetes {
         /// The name of a Kubernetes context to use, if the Kubernetes configuration
@@ -195,6 +213,28 @@ pub enum SecretsControllerConfig {
     },
 }
 
+/// Opens the secrets stored in the `secrets` subdirectory of `data_directory`.
+fn local_secrets_controller(
+    data_directory: &Path,
+    master_key_path: Option<PathBuf>,
+    rotate_master_key: bool,
+) -> Result<Box<dyn SecretsController>, anyhow::Error> {
+    let secrets_storage = data_directory.join("secrets");
+    fs::create_dir_all(&secrets_storage)
+        .with_context(|| format!("creating secrets directory: {}", secrets_storage.display()))?;
+    let permissions = Permissions::from_mode(0o700);
+    fs::set_permissions(secrets_storage.clone(), permissions)?;
+    let master_key_path = master_key_path.unwrap_or_else(|| data_directory.join("secrets.key"));
+    let mut controller = FilesystemSecretsController::new(secrets_storage, master_key_path)
+        .context("opening secrets")?;
+    if rotate_master_key {
+        controller
+            .rotate_master_key()
+            .context("rotating secrets master key")?;
+    }
+    Ok(Box::new(controller))
+}
+
 /// Start a `materialized` server.
 pub async fn serve(config: Config) -> Result<Server, anyhow::Error> {
     match &config.catalog_postgres_stash {
@@ -337,15 +377,11 @@ async fn serve_stash<S: mz_stash::Append + 'static>(
 
     // Initialize secrets controller.
     let secrets_controller: Box<dyn SecretsController> = match config.secrets_controller {
-        None | Some(SecretsControllerConfig::LocalFileSystem) => {
-            let secrets_storage = config.data_directory.join("secrets");
-            fs::create_dir_all(&secrets_storage).with_context(|| {
-                format!("creating secrets directory: {}", secrets_storage.display())
-            })?;
-            let permissions = Permissions::from_mode(0o700);
-            fs::set_permissions(secrets_storage.clone(), permissions)?;
-            Box::new(FilesystemSecretsController::new(secrets_storage))
-        }
+        None => local_secrets_controller(&config.data_directory, None, false)?,
+        Some(SecretsControllerConfig::LocalFileSystem {
+            master_key_path,
+            rotate_master_key,
+        }) => local_secrets_controller(&config.data_directory, master_key_path, rotate_master_key)?,
         Some(SecretsControllerConfig::Kubernetes {
             context,
             user_defined_secret,
@@ -394,6 +430,8 @@ async fn serve_stash<S: mz_stash::Append + 'static>(
         secrets_controller,
         replica_sizes: config.replica_sizes.clone(),
         availability_zones: config.availability_zones.clone(),
+        statement_log: config.statement_log.clone(),
+        plan_cache: config.plan_cache.clone(),
     })
     .await?;
 
@@ -1487,3 +1487,88 @@ fn test_auth_intermediate_ca() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[test]
+fn test_auth_password() -> Result<(), Box<dyn Error>> {
+    mz_ore::test::init_logging();
+
+    let server = util::start_server(util::Config::default())?;
+    let mut client = server.connect(postgres::NoTls)?;
+    client.batch_execute("CREATE ROLE alice LOGIN SUPERUSER PASSWORD 'hunter2'")?;
+    client.batch_execute("CREATE ROLE bob LOGIN SUPERUSER")?;
+
+    let no_headers = HeaderMap::new();
+    let alice_header = make_header(Authorization::basic("alice", "hunter2"));
+    let alice_header_bad = make_header(Authorization::basic("alice", "hunter3"));
+    let bob_header = make_header(Authorization::basic("bob", "anything"));
+
+    run_tests(
+        "TlsMode::Disable, passwords",
+        &server,
+        &[
+            // Roles with a password must authenticate with SCRAM-SHA-256.
+            TestCase::Pgwire {
+                user: "alice",
+                password: Some("hunter2"),
+                ssl_mode: SslMode::Disable,
+                configure: Box::new(|_| Ok(())),
+                assert: Assert::Success,
+            },
+            TestCase::Pgwire {
+                user: "alice",
+                password: Some("hunter3"),
+                ssl_mode: SslMode::Disable,
+                configure: Box::new(|_| Ok(())),
+                assert: Assert::Err(Box::new(|err| {
+                    let err = err.unwrap_db_error();
+                    assert_eq!(
+                        err.message(),
+                        "password authentication failed for user \"alice\""
+                    );
+                    assert_eq!(*err.code(), SqlState::INVALID_PASSWORD);
+                })),
+            },
+            TestCase::Http {
+                user: "alice",
+                scheme: Scheme::HTTP,
+                headers: &alice_header,
+                configure: Box::new(|_| Ok(())),
+                assert: Assert::Success,
+            },
+            TestCase::Http {
+                user: "alice",
+                scheme: Scheme::HTTP,
+                headers: &alice_header_bad,
+                configure: Box::new(|_| Ok(())),
+                assert: Assert::Err(Box::new(|code, message| {
+                    assert_eq!(code, Some(StatusCode::UNAUTHORIZED));
+                    assert_eq!(message, "unauthorized");
+                })),
+            },
+            // Roles without a password are trusted.
+            TestCase::Pgwire {
+                user: "bob",
+                password: None,
+                ssl_mode: SslMode::Disable,
+                configure: Box::new(|_| Ok(())),
+                assert: Assert::Success,
+            },
+            TestCase::Http {
+                user: "bob",
+                scheme: Scheme::HTTP,
+                headers: &bob_header,
+                configure: Box::new(|_| Ok(())),
+                assert: Assert::Success,
+            },
+            TestCase::Http {
+                user: "mz_system",
+                scheme: Scheme::HTTP,
+                headers: &no_headers,
+                configure: Box::new(|_| Ok(())),
+                assert: Assert::Success,
+            },
+        ],
+    );
+
+    Ok(())
+}
@@ -13,19 +13,26 @@ use std::error::Error;
 use std::io::{Read, Write};
 use std::net::TcpStream;
 use std::path::PathBuf;
+use std::pin::Pin;
 use std::time::Duration;
 
 use bytes::BytesMut;
 use fallible_iterator::FallibleIterator;
 use futures::future;
+use futures::TryStreamExt;
 use mz_coord::session::DEFAULT_DATABASE_NAME;
 use postgres::binary_copy::BinaryCopyOutIter;
 use postgres::error::SqlState;
 use postgres::types::Type;
 use postgres::SimpleQueryMessage;
 use postgres_array::{Array, Dimension};
+use postgres_protocol::message::backend::{
+    LogicalReplicationMessage, ReplicationMessage, TupleData,
+};
 use tokio::runtime::Runtime;
 use tokio::sync::mpsc;
+use tokio_postgres::config::ReplicationMode;
+use tokio_postgres::replication::LogicalReplicationStream;
 
 use mz_ore::collections::CollectionExt;
 use mz_ore::task;
@@ -461,6 +468,223 @@ fn test_record_types() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn test_logical_replication() -> Result<(), Box<dyn Error>> {
+    mz_ore::test::init_logging();
+
+    let server = util::start_server(util::Config::default())?;
+
+    // Returns the pgoutput messages of the next transaction in the stream.
+    async fn next_txn(
+        stream: &mut Pin<&mut LogicalReplicationStream>,
+    ) -> Result<Vec<LogicalReplicationMessage>, Box<dyn Error>> {
+        let mut messages = vec![];
+        while let Some(message) = stream.try_next().await? {
+            if let ReplicationMessage::XLogData(body) = message {
+                let message = body.into_data();
+                let done = matches!(message, LogicalReplicationMessage::Commit(_));
+                messages.push(message);
+                if done {
+                    return Ok(messages);
+                }
+            }
+        }
+        Err("replication stream ended unexpectedly".into())
+    }
+
+    fn tuple(data: &[TupleData]) -> Vec<Option<String>> {
+        data.iter()
+            .map(|datum| match datum {
+                TupleData::Text(bytes) => Some(String::from_utf8(bytes.to_vec()).unwrap()),
+                TupleData::Null => None,
+                TupleData::UnchangedToast => panic!("unexpected unchanged toast value"),
+            })
+            .collect()
+    }
+
+    Runtime::new()?.block_on(async {
+        let (client, _handle) = server.connect_async(postgres::NoTls).await?;
+        client
+            .batch_execute("CREATE TABLE t (a int, b text); INSERT INTO t VALUES (1, 'one')")
+            .await?;
+
+        let (repl_client, conn) = server
+            .pg_config_async()
+            .replication_mode(ReplicationMode::Logical)
+            .connect(postgres::NoTls)
+            .await?;
+        task::spawn(|| "test_logical_replication", async move {
+            if let Err(err) = conn.await {
+                panic!("connection error: {}", err);
+            }
+        });
+
+        // Replication commands are only accepted on replication connections.
+        assert!(client
+            .simple_query("IDENTIFY_SYSTEM")
+            .await
+            .unwrap_err()
+            .to_string()
+            .contains("syntax error"));
+
+        let rows = repl_client.simple_query("IDENTIFY_SYSTEM").await?;
+        match &rows[0] {
+            SimpleQueryMessage::Row(row) => assert_eq!(row.get("timeline"), Some("1")),
+            _ => panic!("IDENTIFY_SYSTEM returned no rows"),
+        }
+        repl_client
+            .simple_query("CREATE_REPLICATION_SLOT s TEMPORARY LOGICAL pgoutput")
+            .await?;
+        let err = repl_client
+            .simple_query("CREATE_REPLICATION_SLOT s LOGICAL pgoutput")
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Some(&SqlState::DUPLICATE_OBJECT));
+
+        let copy_stream = repl_client
+            .copy_both_simple::<bytes::Bytes>(
+                r#"START_REPLICATION SLOT s LOGICAL 0/0
+                   ("proto_version" '1', "publication_names" 't')"#,
+            )
+            .await?;
+        let stream = LogicalReplicationStream::new(copy_stream);
+        tokio::pin!(stream);
+
+        // The first transaction is the snapshot of the relation, and describes
+        // the relation before any changes to it.
+        let txn = next_txn(&mut stream).await?;
+        let oid = match txn.as_slice() {
+            [LogicalReplicationMessage::Begin(_), LogicalReplicationMessage::Relation(relation), LogicalReplicationMessage::Insert(insert), LogicalReplicationMessage::Commit(_)] =>
+            {
+                assert_eq!(relation.namespace()?, "public");
+                assert_eq!(relation.name()?, "t");
+                let columns: Vec<_> = relation
+                    .columns()
+                    .iter()
+                    .map(|column| column.name().unwrap().to_string())
+                    .collect();
+                assert_eq!(columns, vec!["a", "b"]);
+                assert_eq!(insert.rel_id(), relation.rel_id());
+                assert_eq!(
+                    tuple(insert.tuple().tuple_data()),
+                    vec![Some("1".into()), Some("one".into())]
+                );
+                relation.rel_id()
+            }
+            _ => panic!("unexpected snapshot transaction: {:?}", txn),
+        };
+
+        client.batch_execute("INSERT INTO t VALUES (2, NULL)").await?;
+        let txn = next_txn(&mut stream).await?;
+        match txn.as_slice() {
+            [LogicalReplicationMessage::Begin(_), LogicalReplicationMessage::Insert(insert), LogicalReplicationMessage::Commit(_)] =>
+            {
+                assert_eq!(insert.rel_id(), oid);
+                assert_eq!(
+                    tuple(insert.tuple().tuple_data()),
+                    vec![Some("2".into()), None]
+                );
+            }
+            _ => panic!("unexpected insert transaction: {:?}", txn),
+        }
+
+        client.batch_execute("DELETE FROM t WHERE a = 1").await?;
+        let txn = next_txn(&mut stream).await?;
+        match txn.as_slice() {
+            [LogicalReplicationMessage::Begin(_), LogicalReplicationMessage::Delete(delete), LogicalReplicationMessage::Commit(_)] =>
+            {
+                assert_eq!(delete.rel_id(), oid);
+                assert_eq!(
+                    tuple(delete.old_tuple().unwrap().tuple_data()),
+                    vec![Some("1".into()), Some("one".into())]
+                );
+            }
+            _ => panic!("unexpected delete transaction: {:?}", txn),
+        }
+
+        Ok::<_, Box<dyn Error>>(())
+    })?;
+
+    Ok(())
+}
+
+#[test]
+fn test_replication_slot_persistence() -> Result<(), Box<dyn Error>> {
+    mz_ore::test::init_logging();
+
+    let data_dir = tempfile::tempdir()?;
+    let config = util::Config::default().data_directory(data_dir.path());
+
+    // Runs the replication commands in `cmds` on a new replication connection
+    // to a new server, and returns the SQLSTATE of each command's error.
+    fn run_commands(
+        config: &util::Config,
+        cmds: &[&str],
+    ) -> Result<Vec<Option<SqlState>>, Box<dyn Error>> {
+        let server = util::start_server(config.clone())?;
+        Runtime::new()?.block_on(async {
+            let (repl_client, conn) = server
+                .pg_config_async()
+                .replication_mode(ReplicationMode::Logical)
+                .connect(postgres::NoTls)
+                .await?;
+            task::spawn(|| "test_replication_slot_persistence", async move {
+                if let Err(err) = conn.await {
+                    panic!("connection error: {}", err);
+                }
+            });
+            let mut codes = vec![];
+            for cmd in cmds {
+                let code = match repl_client.simple_query(cmd).await {
+                    Ok(_) => None,
+                    Err(err) => Some(err.code().cloned().ok_or(err)?),
+                };
+                codes.push(code);
+            }
+            Ok(codes)
+        })
+    }
+
+    assert_eq!(
+        run_commands(
+            &config,
+            &[
+                "CREATE_REPLICATION_SLOT durable LOGICAL pgoutput",
+                "CREATE_REPLICATION_SLOT dropped LOGICAL pgoutput",
+                "CREATE_REPLICATION_SLOT temp TEMPORARY LOGICAL pgoutput",
+                "DROP_REPLICATION_SLOT dropped",
+            ],
+        )?,
+        vec![None, None, None, None],
+    );
+
+    // Only the slot that is neither temporary nor dropped survives a restart.
+    assert_eq!(
+        run_commands(
+            &config,
+            &[
+                "CREATE_REPLICATION_SLOT durable LOGICAL pgoutput",
+                "DROP_REPLICATION_SLOT dropped",
+                "DROP_REPLICATION_SLOT temp",
+                "DROP_REPLICATION_SLOT durable",
+            ],
+        )?,
+        vec![
+            Some(SqlState::DUPLICATE_OBJECT),
+            Some(SqlState::UNDEFINED_OBJECT),
+            Some(SqlState::UNDEFINED_OBJECT),
+            None,
+        ],
+    );
+
+    assert_eq!(
+        run_commands(&config, &["DROP_REPLICATION_SLOT durable"])?,
+        vec![Some(SqlState::UNDEFINED_OBJECT)],
+    );
+
+    Ok(())
+}
+
 fn pg_test_inner(dir: PathBuf) -> Result<(), Box<dyn Error>> {
     // We want a new server per file, so we can't use pgtest::walk.
     datadriven::walk(dir.to_str().unwrap(), |tf| {
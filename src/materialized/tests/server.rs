@@ -16,11 +16,14 @@ use std::time::Duration;
 use mz_ore::retry::Retry;
 use reqwest::{blocking::Client, StatusCode, Url};
 use serde_json::json;
+use tungstenite::stream::MaybeTlsStream;
 
 use crate::util::KAFKA_ADDRS;
 
 pub mod util;
 
+type WebSocket = tungstenite::WebSocket<MaybeTlsStream<std::net::TcpStream>>;
+
 #[test]
 fn test_persistence() -> Result<(), Box<dyn Error>> {
     mz_ore::test::init_logging();
@@ -97,6 +100,59 @@ fn test_persistence() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+// Tests that the audit log is retained across restarts.
+#[test]
+fn test_audit_log_persistence() -> Result<(), Box<dyn Error>> {
+    mz_ore::test::init_logging();
+
+    let data_dir = tempfile::tempdir()?;
+    let config = util::Config::default().data_directory(data_dir.path());
+
+    let query = "SELECT event_type, object_type, event_details->>'id'
+        FROM mz_audit_events ORDER BY id";
+    let events =
+        |client: &mut postgres::Client| -> Result<Vec<(String, String, String)>, Box<dyn Error>> {
+            Ok(client
+                .query(query, &[])?
+                .into_iter()
+                .map(|row| (row.get(0), row.get(1), row.get(2)))
+                .collect())
+        };
+
+    let before = {
+        let server = util::start_server(config.clone())?;
+        let mut client = server.connect(postgres::NoTls)?;
+        client.batch_execute("CREATE VIEW v AS SELECT 1")?;
+        client.batch_execute("ALTER VIEW v RENAME TO w")?;
+        client.batch_execute("DROP VIEW w")?;
+        events(&mut client)?
+    };
+    assert_eq!(
+        before,
+        &[
+            ("create".to_string(), "view".to_string(), "u1".to_string()),
+            ("alter".to_string(), "view".to_string(), "u1".to_string()),
+            ("drop".to_string(), "view".to_string(), "u1".to_string()),
+        ]
+    );
+
+    let server = util::start_server(config)?;
+    let mut client = server.connect(postgres::NoTls)?;
+    assert_eq!(events(&mut client)?, before);
+
+    // New events are assigned IDs after those of the existing events.
+    client.batch_execute("CREATE VIEW v AS SELECT 1")?;
+    let after = events(&mut client)?;
+    assert_eq!(after.len(), 4);
+    assert_eq!(after[..3], before[..]);
+    assert_eq!(
+        after[3],
+        ("create".to_string(), "view".to_string(), "u2".to_string())
+    );
+
+    Ok(())
+}
+
 // Ensures that once a node is started with `--experimental`, it requires
 // `--experimental` on reboot.
 #[test]
@@ -252,6 +308,126 @@ fn test_http_sql() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn test_http_ws() -> Result<(), Box<dyn Error>> {
+    mz_ore::test::init_logging();
+    let server = util::start_server(util::Config::default())?;
+    let url = format!("ws://{}/api/ws", server.inner.local_addr());
+    let (mut ws, _) = tungstenite::connect(url)?;
+
+    fn send(ws: &mut WebSocket, request: serde_json::Value) -> Result<(), Box<dyn Error>> {
+        Ok(ws.write_message(tungstenite::Message::Text(request.to_string()))?)
+    }
+
+    fn recv(ws: &mut WebSocket) -> Result<serde_json::Value, Box<dyn Error>> {
+        loop {
+            if let tungstenite::Message::Text(text) = ws.read_message()? {
+                return Ok(serde_json::from_str(&text)?);
+            }
+        }
+    }
+
+    // Sends `request` and returns the responses up to the next ReadyForQuery.
+    fn query(
+        ws: &mut WebSocket,
+        request: serde_json::Value,
+    ) -> Result<Vec<serde_json::Value>, Box<dyn Error>> {
+        send(ws, request)?;
+        let mut responses = vec![];
+        loop {
+            match recv(ws)? {
+                response if response["type"] == "ReadyForQuery" => return Ok(responses),
+                response => responses.push(response),
+            }
+        }
+    }
+
+    assert_eq!(recv(&mut ws)?, json!({"type": "ReadyForQuery"}));
+
+    // Parameterized queries stream their rows.
+    assert_eq!(
+        query(
+            &mut ws,
+            json!({"type": "query", "query": "SELECT $1::int + 1 AS a", "params": ["41"]})
+        )?,
+        vec![
+            json!({"type": "Rows", "payload": ["a"]}),
+            json!({"type": "Row", "payload": [42]}),
+            json!({"type": "CommandComplete", "payload": "SELECT 1"}),
+        ],
+    );
+
+    // Errors are reported without closing the connection.
+    assert_eq!(
+        query(&mut ws, json!({"type": "query", "query": "SELECT $1::int"}))?,
+        vec![json!({
+            "type": "Error",
+            "payload": "request supplies 0 parameters, but the statement requires 1",
+        })],
+    );
+    // Numbers that JSON cannot represent are rendered as strings.
+    assert_eq!(
+        query(
+            &mut ws,
+            json!({
+                "type": "query",
+                "query": "SELECT 'NaN'::numeric AS a, 'NaN'::float8 AS b, 1.5::numeric AS c",
+            })
+        )?,
+        vec![
+            json!({"type": "Rows", "payload": ["a", "b", "c"]}),
+            json!({"type": "Row", "payload": ["NaN", "NaN", 1.5]}),
+            json!({"type": "CommandComplete", "payload": "SELECT 1"}),
+        ],
+    );
+
+    assert_eq!(
+        query(
+            &mut ws,
+            json!({"type": "query", "query": "CREATE TABLE t (a int); INSERT INTO t VALUES (1)"})
+        )?,
+        vec![
+            json!({"type": "CommandComplete", "payload": "OK"}),
+            json!({"type": "CommandComplete", "payload": "INSERT 0 1"}),
+        ],
+    );
+
+    // TAIL streams rows until it is canceled.
+    send(&mut ws, json!({"type": "query", "query": "TAIL t"}))?;
+    assert_eq!(
+        recv(&mut ws)?,
+        json!({"type": "Rows", "payload": ["mz_timestamp", "mz_diff", "a"]}),
+    );
+    let row = recv(&mut ws)?;
+    assert_eq!(row["type"], "Row");
+    assert_eq!(row["payload"][1], json!(1));
+    assert_eq!(row["payload"][2], json!(1));
+    // Further rows may arrive before the cancellation is processed.
+    let responses: Vec<_> = query(&mut ws, json!({"type": "cancel"}))?
+        .into_iter()
+        .filter(|response| response["type"] != "Row")
+        .collect();
+    assert_eq!(
+        responses,
+        vec![json!({"type": "Error", "payload": "canceling statement due to user request"})],
+    );
+
+    // The connection is usable after cancellation.
+    assert_eq!(
+        query(
+            &mut ws,
+            json!({"type": "query", "query": "SELECT count(*) FROM t"})
+        )?,
+        vec![
+            json!({"type": "Rows", "payload": ["count"]}),
+            json!({"type": "Row", "payload": [1]}),
+            json!({"type": "CommandComplete", "payload": "SELECT 1"}),
+        ],
+    );
+
+    Ok(())
+}
+
 // Test that the server properly handles cancellation requests.
 #[test]
 fn test_cancel_long_running_query() -> Result<(), Box<dyn Error>> {
@@ -282,6 +458,41 @@ fn test_cancel_long_running_query() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+// Test that sessions are terminated if they sit idle in a transaction for too
+// long.
+#[test]
+fn test_idle_in_transaction_session_timeout() -> Result<(), Box<dyn Error>> {
+    let config = util::Config::default();
+    let server = util::start_server(config)?;
+
+    let mut client = server.connect(postgres::NoTls)?;
+    client.batch_execute("SET idle_in_transaction_session_timeout = '500ms'")?;
+    client.batch_execute("CREATE TABLE t (i INT)")?;
+
+    // Idle sessions outside of a transaction are unaffected.
+    thread::sleep(Duration::from_secs(1));
+    client.batch_execute("BEGIN")?;
+    client.query("SELECT * FROM t", &[])?;
+
+    // The session is terminated once the transaction has been idle for longer
+    // than the timeout.
+    thread::sleep(Duration::from_secs(1));
+    assert!(client.simple_query("SELECT 1").is_err());
+    assert!(client.is_closed());
+
+    // The terminated session does not hold back other sessions.
+    let mut client = server.connect(postgres::NoTls)?;
+    client.batch_execute("INSERT INTO t VALUES (1)")?;
+    assert_eq!(
+        client
+            .query_one("SELECT count(*) FROM t", &[])?
+            .get::<_, i64>(0),
+        1
+    );
+
+    Ok(())
+}
+
 // Test that dataflow uninstalls cancelled peeks.
 #[test]
 fn test_cancel_dataflow_removal() -> Result<(), Box<dyn Error>> {
@@ -27,9 +27,11 @@ use std::thread::sleep;
 use std::time::{Duration, Instant};
 
 use chrono::{DateTime, Utc};
+use mz_coord::{PlanCacheConfig, StatementLogConfig};
 use mz_ore::now::NowFn;
 use mz_ore::now::NOW_ZERO;
 use mz_ore::now::SYSTEM_TIME;
+use mz_ore::retry::Retry;
 use postgres::Row;
 use regex::Regex;
 use tracing::info;
@@ -1008,6 +1010,76 @@ fn test_linearizable() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+// Tests that reads under strict serializable isolation are linearizable across
+// sessions: neither the timestamp at which a read is served nor the data it
+// observes may go backwards, even when consecutive reads are issued by
+// different sessions and read from a source that is still being written.
+#[test]
+fn test_strict_serializable() -> Result<(), Box<dyn Error>> {
+    mz_ore::test::init_logging();
+    let server = util::start_server(util::Config::default())?;
+    let mut client_a = server.connect(postgres::NoTls)?;
+    let mut client_b = server.connect(postgres::NoTls)?;
+
+    const NUM_WRITES: usize = 1000;
+    const NUM_READS: usize = 100;
+
+    let temp_dir = tempfile::tempdir()?;
+    let path = Path::join(temp_dir.path(), "in.csv");
+    let mut file = File::create(&path)?;
+    file.write_all(b"a\n")?;
+    file.sync_all()?;
+    let writer = thread::spawn(move || {
+        for _ in 0..NUM_WRITES {
+            file.write_all(b"a\n").unwrap();
+            file.sync_all().unwrap();
+            sleep(Duration::from_millis(1));
+        }
+    });
+
+    client_a.batch_execute(&*format!(
+        "CREATE MATERIALIZED SOURCE s FROM FILE '{}' WITH (tail = true)
+         FORMAT CSV WITH 1 COLUMNS",
+        path.display()
+    ))?;
+    client_a.batch_execute("CREATE MATERIALIZED VIEW v AS SELECT count(*) AS c FROM s")?;
+    for client in [&mut client_a, &mut client_b] {
+        client.batch_execute(
+            "SET SESSION CHARACTERISTICS AS TRANSACTION ISOLATION LEVEL STRICT SERIALIZABLE",
+        )?;
+    }
+
+    let mut largest_ts = MzTimestamp(0);
+    let mut largest_count = 0;
+    for i in 0..NUM_READS {
+        let client = if i % 2 == 0 {
+            &mut client_a
+        } else {
+            &mut client_b
+        };
+        let row = client.query_one("SELECT c, mz_logical_timestamp() FROM v", &[])?;
+        let count: i64 = row.get("c");
+        let ts: MzTimestamp = row.get("mz_logical_timestamp");
+        assert!(
+            ts >= largest_ts,
+            "timestamp went backwards: {} < {}",
+            ts.0,
+            largest_ts.0
+        );
+        assert!(
+            count >= largest_count,
+            "count went backwards: {} < {}",
+            count,
+            largest_count
+        );
+        largest_ts = ts;
+        largest_count = count;
+    }
+
+    writer.join().unwrap();
+    Ok(())
+}
+
 // Test EXPLAIN TIMESTAMP with tables. Mock time to verify initial table since
 // is now(), not 0.
 #[test]
@@ -1042,3 +1114,241 @@ write frontier:[            0]\n",
 
     Ok(())
 }
+
+// Test that sampled statements are recorded in mz_statement_log, along with
+// the plan kind, rows returned, and errors of peeks.
+#[test]
+fn test_statement_log() -> Result<(), Box<dyn Error>> {
+    mz_ore::test::init_logging();
+    let config = util::Config::default().with_statement_log(StatementLogConfig {
+        sample_rate: 1.0,
+        ..Default::default()
+    });
+    let server = util::start_server(config)?;
+    let mut client = server.connect(postgres::NoTls)?;
+
+    client.batch_execute("CREATE TABLE t (a int)")?;
+    client.batch_execute("INSERT INTO t VALUES (1), (2), (3)")?;
+    client.batch_execute("CREATE MATERIALIZED VIEW v AS SELECT a FROM t")?;
+    assert_eq!(client.query("SELECT * FROM v", &[])?.len(), 3);
+    assert_eq!(client.query("SELECT count(*) FROM v", &[])?.len(), 1);
+    assert!(client.query("SELECT a / 0 FROM v", &[]).is_err());
+    // Passwords are redacted even when literals are not.
+    let _ = client.batch_execute("CREATE ROLE r LOGIN PASSWORD 'hunter2'");
+
+    // Statements are recorded asynchronously, so wait for the last one.
+    Retry::default()
+        .retry(|_state| {
+            let count: i64 = client
+                .query_one(
+                    "SELECT count(*) FROM mz_statement_log WHERE sql LIKE 'CREATE ROLE%'",
+                    &[],
+                )
+                .map_err(|_| ())?
+                .get(0);
+            if count == 0 {
+                Err(())
+            } else {
+                Ok(())
+            }
+        })
+        .unwrap();
+
+    let rows = client.query(
+        "SELECT sql, plan_kind, rows_returned, error, timestamp IS NOT NULL AS has_timestamp
+         FROM mz_statement_log
+         WHERE sql LIKE '% FROM v'
+         ORDER BY id",
+        &[],
+    )?;
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0].get::<_, String>("sql"), "SELECT * FROM v");
+    assert_eq!(rows[0].get::<_, String>("plan_kind"), "fast-path");
+    assert_eq!(rows[0].get::<_, Option<i64>>("rows_returned"), Some(3));
+    assert_eq!(rows[0].get::<_, Option<String>>("error"), None);
+    assert!(rows[0].get::<_, bool>("has_timestamp"));
+    assert_eq!(rows[1].get::<_, String>("sql"), "SELECT count(*) FROM v");
+    assert_eq!(rows[1].get::<_, String>("plan_kind"), "dataflow");
+    assert_eq!(rows[1].get::<_, Option<i64>>("rows_returned"), Some(1));
+    assert!(rows[1].get::<_, bool>("has_timestamp"));
+    assert_eq!(rows[2].get::<_, String>("sql"), "SELECT a / 0 FROM v");
+    assert_eq!(rows[2].get::<_, Option<i64>>("rows_returned"), None);
+    assert_contains!(
+        rows[2].get::<_, Option<String>>("error").unwrap(),
+        "division by zero"
+    );
+
+    let row = client.query_one(
+        "SELECT sql FROM mz_statement_log WHERE sql LIKE 'CREATE ROLE%'",
+        &[],
+    )?;
+    assert_eq!(
+        row.get::<_, String>("sql"),
+        "CREATE ROLE r LOGIN PASSWORD '<REDACTED>'"
+    );
+
+    let row = client.query_one(
+        "SELECT DISTINCT connection_id, \"user\" FROM mz_statement_log",
+        &[],
+    )?;
+    assert_eq!(
+        row.get::<_, i32>("connection_id"),
+        client
+            .query_one("SELECT pg_backend_pid()", &[])?
+            .get::<_, i32>(0)
+    );
+    assert_eq!(row.get::<_, String>("user"), "materialize");
+
+    // Roles other than superusers see only their own statements, and may not
+    // read the statements of all roles.
+    client.batch_execute("CREATE ROLE alice LOGIN")?;
+    let mut alice = server.pg_config().user("alice").connect(postgres::NoTls)?;
+    alice.batch_execute("SELECT 1")?;
+    Retry::default()
+        .retry(|_state| {
+            let count: i64 = alice
+                .query_one(
+                    "SELECT count(*) FROM mz_statement_log WHERE sql = 'SELECT 1'",
+                    &[],
+                )
+                .map_err(|_| ())?
+                .get(0);
+            if count == 0 {
+                Err(())
+            } else {
+                Ok(())
+            }
+        })
+        .unwrap();
+    let row = alice.query_one(
+        "SELECT count(*) FROM mz_statement_log WHERE \"user\" <> 'alice'",
+        &[],
+    )?;
+    assert_eq!(row.get::<_, i64>(0), 0);
+    let err = alice
+        .query("SELECT * FROM mz_all_statement_log", &[])
+        .unwrap_err();
+    assert_contains!(err.to_string(), "permission denied");
+
+    // Superusers see the statements of every role.
+    let row = client.query_one(
+        "SELECT count(*) FROM mz_statement_log WHERE \"user\" = 'alice'",
+        &[],
+    )?;
+    assert!(row.get::<_, i64>(0) > 0);
+
+    Ok(())
+}
+
+// Test that statements are removed from the statement log once their retention
+// period elapses, even if no further statements finish.
+#[test]
+fn test_statement_log_retention() -> Result<(), Box<dyn Error>> {
+    mz_ore::test::init_logging();
+    let config = util::Config::default().with_statement_log(StatementLogConfig {
+        sample_rate: 1.0,
+        retention: Duration::from_secs(2),
+        ..Default::default()
+    });
+    let server = util::start_server(config)?;
+    let mut client = server.connect(postgres::NoTls)?;
+
+    client.batch_execute("SELECT 1")?;
+    let count_query = "SELECT count(*) FROM mz_statement_log WHERE sql = 'SELECT 1'";
+    Retry::default()
+        .retry(|_state| {
+            let count: i64 = client.query_one(count_query, &[]).map_err(|_| ())?.get(0);
+            if count == 0 {
+                Err(())
+            } else {
+                Ok(())
+            }
+        })
+        .unwrap();
+
+    // Idle past the retention period, so that only the periodic pruning can
+    // remove the statement.
+    sleep(Duration::from_secs(4));
+    let count: i64 = client.query_one(count_query, &[])?.get(0);
+    assert_eq!(count, 0);
+
+    Ok(())
+}
+
+/// Returns the number of plan cache lookups with the given result.
+fn plan_cache_lookups(server: &util::Server, result: &str) -> f64 {
+    server
+        .metrics_registry
+        .gather()
+        .into_iter()
+        .filter(|family| family.get_name() == "mz_plan_cache_lookups_total")
+        .flat_map(|family| family.get_metric().to_vec())
+        .filter(|metric| {
+            metric
+                .get_label()
+                .iter()
+                .any(|label| label.get_name() == "result" && label.get_value() == result)
+        })
+        .map(|metric| metric.get_counter().get_value())
+        .sum()
+}
+
+// Test that executions of a SELECT statement reuse its cached plan until the
+// catalog changes, and that sessions share plans through the global cache.
+#[test]
+fn test_plan_cache() -> Result<(), Box<dyn Error>> {
+    mz_ore::test::init_logging();
+    let config = util::Config::default().with_plan_cache(PlanCacheConfig {
+        session_capacity: 1,
+        global_capacity: 16,
+    });
+    let server = util::start_server(config)?;
+    let mut client1 = server.connect(postgres::NoTls)?;
+    let mut client2 = server.connect(postgres::NoTls)?;
+
+    client1.batch_execute("CREATE TABLE t (a int)")?;
+    client1.batch_execute("INSERT INTO t VALUES (1), (2), (3)")?;
+    client1.batch_execute("CREATE DEFAULT INDEX ON t")?;
+
+    let lookups = || {
+        (
+            plan_cache_lookups(&server, "session"),
+            plan_cache_lookups(&server, "global"),
+            plan_cache_lookups(&server, "miss"),
+        )
+    };
+    let select = |client: &mut postgres::Client, a: i32| {
+        let stmt = client.prepare("SELECT a FROM t WHERE a = $1").unwrap();
+        let rows = client.query(&stmt, &[&a]).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].get::<_, i32>(0), a);
+    };
+    let (session, global, miss) = lookups();
+
+    select(&mut client1, 1);
+    assert_eq!(lookups(), (session, global, miss + 1.0));
+    select(&mut client1, 1);
+    assert_eq!(lookups(), (session + 1.0, global, miss + 1.0));
+    // Plans are cached under the values of their parameters.
+    select(&mut client1, 2);
+    assert_eq!(lookups(), (session + 1.0, global, miss + 2.0));
+    // The session cache holds only one plan, but the global cache holds both.
+    select(&mut client1, 1);
+    assert_eq!(lookups(), (session + 1.0, global + 1.0, miss + 2.0));
+    select(&mut client2, 2);
+    assert_eq!(lookups(), (session + 1.0, global + 2.0, miss + 2.0));
+
+    // Any change to the catalog invalidates the cached plans.
+    client2.batch_execute("CREATE TABLE u (b int)")?;
+    select(&mut client1, 1);
+    assert_eq!(lookups(), (session + 1.0, global + 2.0, miss + 3.0));
+
+    // A session with temporary items does not use the plans of other
+    // sessions, which could refer to items that its temporary items shadow.
+    client2.batch_execute("CREATE TEMPORARY TABLE t (a int)")?;
+    select(&mut client1, 1);
+    let stmt = client2.prepare("SELECT a FROM t WHERE a = $1")?;
+    assert!(client2.query(&stmt, &[&1])?.is_empty());
+
+    Ok(())
+}
@@ -25,6 +25,7 @@ use tokio::runtime::Runtime;
 use tower_http::cors::Origin;
 
 use materialized::{OrchestratorBackend, OrchestratorConfig, TlsMode};
+use mz_coord::{PlanCacheConfig, StatementLogConfig};
 use mz_dataflow_types::sources::AwsExternalId;
 use mz_frontegg_auth::FronteggAuthentication;
 use mz_orchestrator_process::ProcessOrchestratorConfig;
@@ -52,6 +53,8 @@ pub struct Config {
     workers: usize,
     logical_compaction_window: Option<Duration>,
     now: NowFn,
+    statement_log: StatementLogConfig,
+    plan_cache: PlanCacheConfig,
 }
 
 impl Default for Config {
@@ -66,6 +69,8 @@ impl Default for Config {
             workers: 1,
             logical_compaction_window: None,
             now: SYSTEM_TIME.clone(),
+            statement_log: StatementLogConfig::default(),
+            plan_cache: PlanCacheConfig::default(),
         }
     }
 }
@@ -119,6 +124,16 @@ impl Config {
         self.now = now;
         self
     }
+
+    pub fn with_statement_log(mut self, statement_log: StatementLogConfig) -> Self {
+        self.statement_log = statement_log;
+        self
+    }
+
+    pub fn with_plan_cache(mut self, plan_cache: PlanCacheConfig) -> Self {
+        self.plan_cache = plan_cache;
+        self
+    }
 }
 
 pub fn start_server(config: Config) -> Result<Server, anyhow::Error> {
@@ -138,6 +153,8 @@ pub fn start_server(config: Config) -> Result<Server, anyhow::Error> {
     let inner = runtime.block_on(materialized::serve(materialized::Config {
         timestamp_frequency: Duration::from_secs(1),
         logical_compaction_window: config.logical_compaction_window,
+        statement_log: config.statement_log,
+        plan_cache: config.plan_cache,
         persist_location: PersistLocation {
             blob_uri: format!("file://{}/persist/blob", data_directory.display()),
             consensus_uri: format!("sqlite://{}/persist/consensus", data_directory.display()),
@@ -27,6 +27,7 @@ use tokio::io::{self, AsyncRead, AsyncReadExt, AsyncWrite, Interest, Ready};
 use tokio_util::codec::{Decoder, Encoder, Framed};
 use tracing::trace;
 
+use mz_coord::scram::SCRAM_SHA_256;
 use mz_ore::cast::CastFrom;
 use mz_ore::future::OreSinkExt;
 use mz_ore::netio::{self, AsyncReady};
@@ -200,6 +201,9 @@ impl Encoder<BackendMessage> for Codec {
         let byte = match &msg {
             BackendMessage::AuthenticationOk => b'R',
             BackendMessage::AuthenticationCleartextPassword => b'R',
+            BackendMessage::AuthenticationSASL => b'R',
+            BackendMessage::AuthenticationSASLContinue(_) => b'R',
+            BackendMessage::AuthenticationSASLFinal(_) => b'R',
             BackendMessage::RowDescription(_) => b'T',
             BackendMessage::DataRow(_) => b'D',
             BackendMessage::CommandComplete { .. } => b'C',
@@ -222,6 +226,7 @@ impl Encoder<BackendMessage> for Codec {
             }
             BackendMessage::CopyInResponse { .. } => b'G',
             BackendMessage::CopyOutResponse { .. } => b'H',
+            BackendMessage::CopyBothResponse => b'W',
             BackendMessage::CopyData(_) => b'd',
             BackendMessage::CopyDone => b'c',
         };
@@ -247,6 +252,13 @@ impl Encoder<BackendMessage> for Codec {
                     dst.put_format_i16(format);
                 }
             }
+            BackendMessage::CopyBothResponse => {
+                // Like PostgreSQL, describe replication streams as textual
+                // with no columns. The format of their contents is defined
+                // by the replication protocol.
+                dst.put_format_i8(mz_pgrepr::Format::Text);
+                dst.put_length_i16(0)?;
+            }
             BackendMessage::CopyData(data) => {
                 dst.put_slice(&data);
             }
@@ -257,6 +269,19 @@ impl Encoder<BackendMessage> for Codec {
             BackendMessage::AuthenticationCleartextPassword => {
                 dst.put_u32(3);
             }
+            BackendMessage::AuthenticationSASL => {
+                dst.put_u32(10);
+                dst.put_string(SCRAM_SHA_256);
+                dst.put_u8(b'\0');
+            }
+            BackendMessage::AuthenticationSASLContinue(data) => {
+                dst.put_u32(11);
+                dst.put_slice(&data);
+            }
+            BackendMessage::AuthenticationSASLFinal(data) => {
+                dst.put_u32(12);
+                dst.put_slice(&data);
+            }
             BackendMessage::RowDescription(fields) => {
                 dst.put_length_i16(fields.len())?;
                 for f in &fields {
@@ -508,7 +533,7 @@ impl Decoder for Codec {
                         b'X' => decode_terminate(buf)?,
 
                         // Authentication.
-                        b'p' => decode_password(buf)?,
+                        b'p' => FrontendMessage::RawAuthentication(buf.buf.to_vec()),
 
                         // Copy from flow.
                         b'f' => decode_copy_fail(buf)?,
@@ -537,12 +562,42 @@ fn decode_terminate(mut _buf: Cursor) -> Result<FrontendMessage, io::Error> {
     Ok(FrontendMessage::Terminate)
 }
 
-fn decode_password(mut buf: Cursor) -> Result<FrontendMessage, io::Error> {
+/// Decodes the payload of a [`FrontendMessage::RawAuthentication`] message as
+/// a Password message.
+pub fn decode_password(buf: &[u8]) -> Result<FrontendMessage, io::Error> {
+    let mut buf = Cursor::new(buf);
     Ok(FrontendMessage::Password {
         password: buf.read_cstr()?.to_owned(),
     })
 }
 
+/// Decodes the payload of a [`FrontendMessage::RawAuthentication`] message as
+/// a SASLInitialResponse message.
+pub fn decode_sasl_initial_response(buf: &[u8]) -> Result<FrontendMessage, io::Error> {
+    let mut buf = Cursor::new(buf);
+    let mechanism = buf.read_cstr()?.to_owned();
+    let initial_response = match buf.read_i32()? {
+        -1 => vec![],
+        len => {
+            let len = usize::try_from(len).map_err(input_err)?;
+            if buf.buf.len() < len {
+                return Err(input_err("not enough buffer for SASL initial response"));
+            }
+            buf.buf[..len].to_vec()
+        }
+    };
+    Ok(FrontendMessage::SASLInitialResponse {
+        mechanism,
+        initial_response,
+    })
+}
+
+/// Decodes the payload of a [`FrontendMessage::RawAuthentication`] message as
+/// a SASLResponse message.
+pub fn decode_sasl_response(buf: &[u8]) -> Result<FrontendMessage, io::Error> {
+    Ok(FrontendMessage::SASLResponse(buf.to_vec()))
+}
+
 fn decode_query(mut buf: Cursor) -> Result<FrontendMessage, io::Error> {
     Ok(FrontendMessage::Query {
         sql: buf.read_cstr()?.to_string(),
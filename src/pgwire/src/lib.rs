@@ -26,6 +26,7 @@ mod codec;
 mod message;
 mod metrics;
 mod protocol;
+mod replication;
 mod server;
 
 pub use metrics::Metrics;
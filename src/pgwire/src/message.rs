@@ -188,9 +188,29 @@ pub enum FrontendMessage {
 
     CopyFail(String),
 
+    /// An authentication message whose payload has not yet been decoded.
+    ///
+    /// Password, SASLInitialResponse, and SASLResponse messages share a type
+    /// byte, so they can only be distinguished by the authentication method
+    /// the server requested. Use [`codec::decode_password`],
+    /// [`codec::decode_sasl_initial_response`], or
+    /// [`codec::decode_sasl_response`] to decode the payload.
+    ///
+    /// [`codec::decode_password`]: crate::codec::decode_password
+    /// [`codec::decode_sasl_initial_response`]: crate::codec::decode_sasl_initial_response
+    /// [`codec::decode_sasl_response`]: crate::codec::decode_sasl_response
+    RawAuthentication(Vec<u8>),
+
     Password {
         password: String,
     },
+
+    SASLInitialResponse {
+        mechanism: String,
+        initial_response: Vec<u8>,
+    },
+
+    SASLResponse(Vec<u8>),
 }
 
 impl FrontendMessage {
@@ -210,7 +230,10 @@ impl FrontendMessage {
             FrontendMessage::CopyData(_) => "copy_data",
             FrontendMessage::CopyDone => "copy_done",
             FrontendMessage::CopyFail(_) => "copy_fail",
+            FrontendMessage::RawAuthentication(_) => "raw_authentication",
             FrontendMessage::Password { .. } => "password",
+            FrontendMessage::SASLInitialResponse { .. } => "sasl_initial_response",
+            FrontendMessage::SASLResponse(_) => "sasl_response",
         }
     }
 }
@@ -222,6 +245,9 @@ impl FrontendMessage {
 pub enum BackendMessage {
     AuthenticationOk,
     AuthenticationCleartextPassword,
+    AuthenticationSASL,
+    AuthenticationSASLContinue(Vec<u8>),
+    AuthenticationSASLFinal(Vec<u8>),
     CommandComplete {
         tag: String,
     },
@@ -249,6 +275,7 @@ pub enum BackendMessage {
         overall_format: mz_pgrepr::Format,
         column_formats: Vec<mz_pgrepr::Format>,
     },
+    CopyBothResponse,
     CopyData(Vec<u8>),
     CopyDone,
 }
@@ -368,6 +395,7 @@ impl ErrorResponse {
             CoordError::ConstrainedParameter { .. } => SqlState::INVALID_PARAMETER_VALUE,
             CoordError::AutomaticTimestampFailure { .. } => SqlState::INTERNAL_ERROR,
             CoordError::DuplicateCursor(_) => SqlState::DUPLICATE_CURSOR,
+            CoordError::DuplicateReplicationSlot(_) => SqlState::DUPLICATE_OBJECT,
             CoordError::Eval(EvalError::CharacterNotValidForEncoding(_)) => {
                 SqlState::PROGRAM_LIMIT_EXCEEDED
             }
@@ -382,30 +410,38 @@ impl ErrorResponse {
             CoordError::IdExhaustionError => SqlState::INTERNAL_ERROR,
             CoordError::Internal(_) => SqlState::INTERNAL_ERROR,
             CoordError::InvalidRematerialization { .. } => SqlState::FEATURE_NOT_SUPPORTED,
+            CoordError::InvalidReplicationSlotName(_) => SqlState::INVALID_NAME,
             CoordError::InvalidParameterType(_) => SqlState::INVALID_PARAMETER_VALUE,
             CoordError::InvalidParameterValue { .. } => SqlState::INVALID_PARAMETER_VALUE,
             CoordError::InvalidClusterReplicaAz { .. } => SqlState::FEATURE_NOT_SUPPORTED,
             CoordError::InvalidClusterReplicaSize { .. } => SqlState::FEATURE_NOT_SUPPORTED,
             CoordError::InvalidTableMutationSelection => SqlState::INVALID_TRANSACTION_STATE,
+            CoordError::LockTimeout => SqlState::LOCK_NOT_AVAILABLE,
             CoordError::ConstraintViolation(NotNullViolation(_)) => SqlState::NOT_NULL_VIOLATION,
             CoordError::NoClusterReplicasAvailable(_) => SqlState::FEATURE_NOT_SUPPORTED,
             CoordError::OperationProhibitsTransaction(_) => SqlState::ACTIVE_SQL_TRANSACTION,
             CoordError::OperationRequiresTransaction(_) => SqlState::NO_ACTIVE_SQL_TRANSACTION,
+            CoordError::MustBeOwner { .. } => SqlState::INSUFFICIENT_PRIVILEGE,
+            CoordError::PermissionDenied { .. } => SqlState::INSUFFICIENT_PRIVILEGE,
             CoordError::PreparedStatementExists(_) => SqlState::DUPLICATE_PSTATEMENT,
             CoordError::QGM(_) => SqlState::INTERNAL_ERROR,
             CoordError::ReadOnlyTransaction => SqlState::READ_ONLY_SQL_TRANSACTION,
+            CoordError::ReplicationSlotActive { .. } => SqlState::OBJECT_IN_USE,
             CoordError::ReadOnlyParameter(_) => SqlState::CANT_CHANGE_RUNTIME_PARAM,
             CoordError::RecursionLimit(_) => SqlState::INTERNAL_ERROR,
             CoordError::RelationOutsideTimeDomain { .. } => SqlState::INVALID_TRANSACTION_STATE,
             CoordError::SafeModeViolation(_) => SqlState::INTERNAL_ERROR,
+            CoordError::SuperuserRequired(_) => SqlState::INSUFFICIENT_PRIVILEGE,
             CoordError::SqlCatalog(_) => SqlState::INTERNAL_ERROR,
             CoordError::TailOnlyTransaction => SqlState::INVALID_TRANSACTION_STATE,
+            CoordError::TransactionModeAfterQuery => SqlState::ACTIVE_SQL_TRANSACTION,
             CoordError::Transform(_) => SqlState::INTERNAL_ERROR,
             CoordError::UncallableFunction { .. } => SqlState::FEATURE_NOT_SUPPORTED,
             CoordError::UnknownCursor(_) => SqlState::INVALID_CURSOR_NAME,
             CoordError::UnknownParameter(_) => SqlState::UNDEFINED_OBJECT,
             CoordError::UnknownPreparedStatement(_) => SqlState::UNDEFINED_PSTATEMENT,
             CoordError::UnknownLoginRole(_) => SqlState::INVALID_AUTHORIZATION_SPECIFICATION,
+            CoordError::UnknownReplicationSlot(_) => SqlState::UNDEFINED_OBJECT,
             CoordError::UnmaterializableFunction(_) => SqlState::FEATURE_NOT_SUPPORTED,
             CoordError::Unsupported(..) => SqlState::FEATURE_NOT_SUPPORTED,
             CoordError::Unstructured(_) => SqlState::INTERNAL_ERROR,
@@ -8,12 +8,13 @@
 // by the Apache License, Version 2.0.
 
 use std::cmp;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::convert::{TryFrom, TryInto};
 use std::future::Future;
 use std::iter;
 use std::mem;
 
+use anyhow::anyhow;
 use byteorder::{ByteOrder, NetworkEndian};
 use futures::future::{pending, BoxFuture, FutureExt};
 use itertools::izip;
@@ -25,26 +26,36 @@ use tokio::select;
 use tokio::time::{self, Duration, Instant};
 use tracing::{debug, warn};
 
+use mz_coord::scram::{ScramServer, ScramVerifier, SCRAM_SHA_256};
 use mz_coord::session::{
-    row_future_to_stream, EndTransactionAction, InProgressRows, Portal, PortalState,
-    RowBatchStream, Session, TransactionStatus,
+    EndTransactionAction, InProgressRows, Portal, PortalState, RowBatchStream, Session,
+    TransactionStatus,
 };
 use mz_coord::{ExecuteResponse, PeekResponseUnary};
 use mz_frontegg_auth::FronteggAuthentication;
 use mz_ore::cast::CastFrom;
+use mz_ore::collections::CollectionExt;
 use mz_ore::netio::AsyncReady;
 use mz_ore::str::StrExt;
 use mz_pgcopy::CopyFormatParams;
+use mz_repr::adt::numeric;
 use mz_repr::{Datum, RelationDesc, RelationType, Row, RowArena, ScalarType};
-use mz_sql::ast::display::AstDisplay;
-use mz_sql::ast::{FetchDirection, Ident, NoticeSeverity, Raw, Statement};
+use mz_sql::ast::display::{escape_single_quote_string, AstDisplay};
+use mz_sql::ast::{
+    FetchDirection, Ident, NoticeSeverity, Raw, RawObjectName, Statement, TailRelation,
+    TailStatement,
+};
 use mz_sql::plan::{CopyFormat, CopyParams, ExecuteTimeout, StatementDesc};
 
-use crate::codec::FramedConn;
+use crate::codec::{self, FramedConn};
 use crate::message::{
     self, BackendMessage, ErrorResponse, FrontendMessage, Severity, VERSIONS, VERSION_3,
 };
 use crate::metrics::Metrics;
+use crate::replication::{
+    self, Lsn, PgOutputEncoder, PgOutputOptions, ReplicatedRelation, ReplicationCommand,
+    ReplicationMode, StandbyMessage,
+};
 use crate::server::{Conn, TlsMode};
 
 /// Reports whether the given stream begins with a pgwire handshake.
@@ -117,6 +128,13 @@ where
     }
 
     let user = params.remove("user").unwrap_or_else(String::new);
+    let replication_mode = match params.remove("replication") {
+        None => ReplicationMode::Off,
+        Some(value) => match ReplicationMode::parse(&value) {
+            Ok(mode) => mode,
+            Err(err) => return conn.send(err).await,
+        },
+    };
 
     // Validate that the connection is compatible with the TLS mode.
     //
@@ -162,7 +180,7 @@ where
         conn.send(BackendMessage::AuthenticationCleartextPassword)
             .await?;
         conn.flush().await?;
-        let password = match conn.recv().await? {
+        let password = match recv_authentication(conn, codec::decode_password).await? {
             Some(FrontendMessage::Password { password }) => password,
             _ => {
                 return conn
@@ -190,6 +208,19 @@ where
             }
         }
     } else {
+        // Without Frontegg, roles with a password must prove that they know
+        // it. Roles without a password are trusted.
+        if let Some(verifier) = coord_client.password_verifier(&user).await {
+            if let Err(e) = authenticate_scram(conn, verifier).await? {
+                warn!("PGwire connection failed authentication: {}", e);
+                return conn
+                    .send(ErrorResponse::fatal(
+                        SqlState::INVALID_PASSWORD,
+                        format!("password authentication failed for user {}", user.quoted()),
+                    ))
+                    .await;
+            }
+        }
         // No frontegg check, so is_expired never resolves.
         pending().right_future()
     };
@@ -232,6 +263,7 @@ where
         metrics,
         conn,
         coord_client: &mut coord_client,
+        replication_mode,
     };
 
     select! {
@@ -245,6 +277,80 @@ where
     }
 }
 
+/// Receives an authentication message from the client and decodes its payload
+/// with `decode`.
+///
+/// Returns `None` if the client sends any other kind of message.
+async fn recv_authentication<A>(
+    conn: &mut FramedConn<A>,
+    decode: fn(&[u8]) -> Result<FrontendMessage, io::Error>,
+) -> Result<Option<FrontendMessage>, io::Error>
+where
+    A: AsyncRead + AsyncWrite + Unpin,
+{
+    match conn.recv().await? {
+        Some(FrontendMessage::RawAuthentication(data)) => Ok(Some(decode(&data)?)),
+        _ => Ok(None),
+    }
+}
+
+/// Runs the server side of a SCRAM-SHA-256 exchange with the client.
+///
+/// Returns an error in the inner result if the client fails to authenticate.
+/// Returns an error in the outer result if an I/O error occurs.
+async fn authenticate_scram<A>(
+    conn: &mut FramedConn<A>,
+    verifier: ScramVerifier,
+) -> Result<Result<(), anyhow::Error>, io::Error>
+where
+    A: AsyncRead + AsyncWrite + Unpin,
+{
+    conn.send(BackendMessage::AuthenticationSASL).await?;
+    conn.flush().await?;
+    let msg = recv_authentication(conn, codec::decode_sasl_initial_response).await?;
+    let client_first = match msg {
+        Some(FrontendMessage::SASLInitialResponse {
+            mechanism,
+            initial_response,
+        }) if mechanism == SCRAM_SHA_256 => initial_response,
+        Some(FrontendMessage::SASLInitialResponse { mechanism, .. }) => {
+            return Ok(Err(anyhow!(
+                "unsupported SASL mechanism {}",
+                mechanism.quoted()
+            )))
+        }
+        _ => return Ok(Err(anyhow!("expected SASLInitialResponse message"))),
+    };
+
+    let server = ScramServer::new(verifier)
+        .map_err(anyhow::Error::from)
+        .and_then(|server| server.handle_client_first(&client_first));
+    let (server, server_first) = match server {
+        Ok(server) => server,
+        Err(e) => return Ok(Err(e)),
+    };
+    conn.send(BackendMessage::AuthenticationSASLContinue(
+        server_first.into_bytes(),
+    ))
+    .await?;
+    conn.flush().await?;
+
+    let client_final = match recv_authentication(conn, codec::decode_sasl_response).await? {
+        Some(FrontendMessage::SASLResponse(data)) => data,
+        _ => return Ok(Err(anyhow!("expected SASLResponse message"))),
+    };
+    match server.handle_client_final(&client_final) {
+        Ok(server_final) => {
+            conn.send(BackendMessage::AuthenticationSASLFinal(
+                server_final.into_bytes(),
+            ))
+            .await?;
+            Ok(Ok(()))
+        }
+        Err(e) => Ok(Err(e)),
+    }
+}
+
 #[derive(Debug)]
 enum State {
     Ready,
@@ -256,6 +362,7 @@ struct StateMachine<'a, A> {
     conn: &'a mut FramedConn<A>,
     coord_client: &'a mut mz_coord::SessionClient,
     metrics: &'a Metrics,
+    replication_mode: ReplicationMode,
 }
 
 impl<'a, A> StateMachine<'a, A>
@@ -280,7 +387,33 @@ where
     }
 
     async fn advance_ready(&mut self) -> Result<State, io::Error> {
-        let message = self.conn.recv().await?;
+        // Sessions that sit idle in an explicit transaction hold back the
+        // compaction of every collection the transaction has read, so they
+        // are terminated after `idle_in_transaction_session_timeout`.
+        let session = self.coord_client.session();
+        let idle_timeout = match session.transaction() {
+            TransactionStatus::InTransaction(_) | TransactionStatus::Failed(_) => {
+                Some(session.vars().idle_in_transaction_session_timeout())
+            }
+            _ => None,
+        }
+        .filter(|timeout| !timeout.is_zero());
+        let message = match idle_timeout {
+            None => self.conn.recv().await?,
+            Some(timeout) => match time::timeout(timeout, self.conn.recv()).await {
+                Ok(message) => message?,
+                Err(_) => {
+                    let state = self
+                        .error(ErrorResponse::fatal(
+                            SqlState::IDLE_IN_TRANSACTION_SESSION_TIMEOUT,
+                            "terminating connection due to idle-in-transaction timeout",
+                        ))
+                        .await?;
+                    self.conn.flush().await?;
+                    return Ok(state);
+                }
+            },
+        };
         let timer = Instant::now();
         let name = match &message {
             Some(message) => message.name(),
@@ -343,7 +476,10 @@ where
             Some(FrontendMessage::CopyData(_))
             | Some(FrontendMessage::CopyDone)
             | Some(FrontendMessage::CopyFail(_))
-            | Some(FrontendMessage::Password { .. }) => State::Drain,
+            | Some(FrontendMessage::RawAuthentication(_))
+            | Some(FrontendMessage::Password { .. })
+            | Some(FrontendMessage::SASLInitialResponse { .. })
+            | Some(FrontendMessage::SASLResponse(_)) => State::Drain,
             None => State::Done,
         };
 
@@ -445,6 +581,21 @@ where
     // transactions are handled.
     // From https://www.postgresql.org/docs/current/protocol-flow.html
     async fn query(&mut self, sql: String) -> Result<State, io::Error> {
+        // Replication connections accept replication commands in addition to
+        // SQL.
+        if self.replication_mode == ReplicationMode::Database {
+            if let Some(command) = replication::parse_command(&sql) {
+                let state = match command {
+                    Ok(command) => self.replication_command(command).await?,
+                    Err(err) => self.error(err).await?,
+                };
+                return match state {
+                    State::Done => Ok(State::Done),
+                    State::Ready | State::Drain => self.ready().await,
+                };
+            }
+        }
+
         // Parse first before doing any transaction checking.
         let stmts = match parse_sql(&sql) {
             Ok(stmts) => stmts,
@@ -1040,12 +1191,7 @@ where
 
         match response {
             ExecuteResponse::Canceled => {
-                return self
-                    .error(ErrorResponse::error(
-                        SqlState::QUERY_CANCELED,
-                        "canceling statement due to user request",
-                    ))
-                    .await;
+                return self.error(self.canceled_error()).await;
             }
             ExecuteResponse::ClosedCursor => {
                 self.complete_portal(&portal_name);
@@ -1130,6 +1276,7 @@ where
                 )
                 .await
             }
+            ExecuteResponse::GrantedPrivilege => command_complete!("GRANT"),
             ExecuteResponse::Inserted(n) => {
                 // "On successful completion, an INSERT command returns a
                 // command tag of the form `INSERT <oid> <count>`."
@@ -1146,7 +1293,7 @@ where
                 self.send_rows(
                     row_desc,
                     portal_name,
-                    InProgressRows::new(row_future_to_stream(rx).await),
+                    InProgressRows::new(rx),
                     max_rows,
                     get_response,
                     fetch_portal_name,
@@ -1154,6 +1301,7 @@ where
                 )
                 .await
             }
+            ExecuteResponse::RevokedPrivilege => command_complete!("REVOKE"),
             ExecuteResponse::SetVariable { name } => {
                 // This code is somewhat awkwardly structured because we
                 // can't hold `var` across an await point.
@@ -1228,7 +1376,7 @@ where
                     row_desc.expect("missing row description for ExecuteResponse::CopyTo");
                 let rows: RowBatchStream = match *resp {
                     ExecuteResponse::Tailing { rx } => rx,
-                    ExecuteResponse::SendingRows(rows_rx) => row_future_to_stream(rows_rx).await,
+                    ExecuteResponse::SendingRows(rows_rx) => rows_rx,
                     _ => {
                         return self
                             .error(ErrorResponse::error(
@@ -1423,12 +1571,7 @@ where
                         .await;
                 }
                 FetchResult::Canceled => {
-                    return self
-                        .error(ErrorResponse::error(
-                            SqlState::QUERY_CANCELED,
-                            "canceling statement due to user request",
-                        ))
-                        .await;
+                    return self.error(self.canceled_error()).await;
                 }
             }
         }
@@ -1532,10 +1675,7 @@ where
                 },
                 _ = self.coord_client.canceled() => {
                     return self
-                        .error(ErrorResponse::error(
-                            SqlState::QUERY_CANCELED,
-                            "canceling statement due to user request",
-                        ))
+                        .error(self.canceled_error())
                     .await;
                 },
                 batch = stream.recv() => match batch {
@@ -1546,10 +1686,7 @@ where
                             .await;
                     }
                     Some(PeekResponseUnary::Canceled) => {
-                        return self.error(ErrorResponse::error(
-                                SqlState::QUERY_CANCELED,
-                                "canceling statement due to user request",
-                            ))
+                        return self.error(self.canceled_error())
                             .await;
                     }
                     Some(PeekResponseUnary::Rows(rows)) => {
@@ -1681,6 +1818,557 @@ where
         Ok(next_state)
     }
 
+    async fn replication_command(
+        &mut self,
+        command: ReplicationCommand,
+    ) -> Result<State, io::Error> {
+        if !matches!(
+            self.coord_client.session().transaction(),
+            TransactionStatus::Default
+        ) {
+            return self
+                .error(ErrorResponse::error(
+                    SqlState::ACTIVE_SQL_TRANSACTION,
+                    format!(
+                        "{} cannot be executed inside a transaction block",
+                        command.tag()
+                    ),
+                ))
+                .await;
+        }
+
+        let tag = command.tag().to_string();
+        match command {
+            ReplicationCommand::IdentifySystem => {
+                let systemid = match self
+                    .internal_query("SELECT mz_catalog.mz_cluster_id()::text")
+                    .await
+                {
+                    Ok(rows) => {
+                        let row = rows.into_element();
+                        row.unpack_first().unwrap_str().to_string()
+                    }
+                    Err(err) => return self.error(err).await,
+                };
+                let xlogpos = Lsn(replication::now_millis()).to_string();
+                let dbname = self.coord_client.session().vars().database().to_string();
+                let desc = RelationDesc::empty()
+                    .with_column("systemid", ScalarType::String.nullable(false))
+                    .with_column("timeline", ScalarType::Int32.nullable(false))
+                    .with_column("xlogpos", ScalarType::String.nullable(false))
+                    .with_column("dbname", ScalarType::String.nullable(false));
+                self.send_single_row(
+                    desc,
+                    &[
+                        Datum::String(&systemid),
+                        Datum::Int32(1),
+                        Datum::String(&xlogpos),
+                        Datum::String(&dbname),
+                    ],
+                )
+                .await?;
+            }
+            ReplicationCommand::CreateSlot {
+                name,
+                temporary,
+                plugin,
+            } => {
+                if plugin != replication::PGOUTPUT {
+                    return self
+                        .error(ErrorResponse::error(
+                            SqlState::FEATURE_NOT_SUPPORTED,
+                            format!(
+                                "output plugin {} is not supported; use {}",
+                                plugin.quoted(),
+                                replication::PGOUTPUT.quoted()
+                            ),
+                        ))
+                        .await;
+                }
+                if let Err(e) = self
+                    .coord_client
+                    .create_replication_slot(name.clone(), temporary)
+                    .await
+                {
+                    return self
+                        .error(ErrorResponse::from_coord(Severity::Error, e))
+                        .await;
+                }
+                let desc = RelationDesc::empty()
+                    .with_column("slot_name", ScalarType::String.nullable(false))
+                    .with_column("consistent_point", ScalarType::String.nullable(false))
+                    .with_column("snapshot_name", ScalarType::String.nullable(true))
+                    .with_column("output_plugin", ScalarType::String.nullable(false));
+                let consistent_point = Lsn(0).to_string();
+                self.send_single_row(
+                    desc,
+                    &[
+                        Datum::String(&name),
+                        Datum::String(&consistent_point),
+                        Datum::Null,
+                        Datum::String(&plugin),
+                    ],
+                )
+                .await?;
+            }
+            ReplicationCommand::DropSlot { name } => {
+                if let Err(e) = self.coord_client.drop_replication_slot(name).await {
+                    return self
+                        .error(ErrorResponse::from_coord(Severity::Error, e))
+                        .await;
+                }
+            }
+            ReplicationCommand::StartReplication { slot, lsn, options } => {
+                return self.start_replication(slot, lsn, options).await;
+            }
+        }
+        self.send(BackendMessage::CommandComplete { tag }).await?;
+        Ok(State::Ready)
+    }
+
+    /// Sends the description of and the single row of a replication command's
+    /// result.
+    async fn send_single_row(
+        &mut self,
+        desc: RelationDesc,
+        datums: &[Datum<'_>],
+    ) -> Result<(), io::Error> {
+        let formats = vec![mz_pgrepr::Format::Text; desc.arity()];
+        self.send(BackendMessage::RowDescription(
+            message::encode_row_description(&desc, &formats),
+        ))
+        .await?;
+        self.conn.set_encode_state(
+            desc.typ()
+                .column_types
+                .iter()
+                .map(|ty| mz_pgrepr::Type::from(&ty.scalar_type))
+                .zip(formats)
+                .collect(),
+        );
+        let row = Row::pack_slice(datums);
+        self.send(BackendMessage::DataRow(mz_pgrepr::values_from_row(
+            row,
+            desc.typ(),
+        )))
+        .await
+    }
+
+    /// Runs a single-statement query on behalf of the client, in its own
+    /// transaction, and returns the resulting rows.
+    async fn internal_query(&mut self, sql: &str) -> Result<Vec<Row>, ErrorResponse> {
+        let stmt = parse_sql(sql)?.into_element();
+        self.start_transaction(Some(1)).await;
+        let res = self.internal_query_inner(stmt).await;
+        self.coord_client.session().remove_portal(INTERNAL_PORTAL);
+        let action = match res {
+            Ok(_) => EndTransactionAction::Commit,
+            Err(_) => EndTransactionAction::Rollback,
+        };
+        self.coord_client
+            .end_transaction(action)
+            .await
+            .map_err(|e| ErrorResponse::from_coord(Severity::Error, e))?;
+        res
+    }
+
+    async fn internal_query_inner(
+        &mut self,
+        stmt: Statement<Raw>,
+    ) -> Result<Vec<Row>, ErrorResponse> {
+        let from_coord = |e| ErrorResponse::from_coord(Severity::Error, e);
+        self.coord_client
+            .declare(INTERNAL_PORTAL.into(), stmt, vec![])
+            .await
+            .map_err(from_coord)?;
+        let mut stream = match self
+            .coord_client
+            .execute(INTERNAL_PORTAL.into())
+            .await
+            .map_err(from_coord)?
+        {
+            ExecuteResponse::SendingRows(rx) => rx,
+            _ => {
+                return Err(ErrorResponse::error(
+                    SqlState::INTERNAL_ERROR,
+                    "internal query did not return rows",
+                ))
+            }
+        };
+        let mut rows = vec![];
+        while let Some(batch) = stream.recv().await {
+            match batch {
+                PeekResponseUnary::Rows(batch) => rows.extend(batch),
+                PeekResponseUnary::Error(text) => {
+                    return Err(ErrorResponse::error(SqlState::INTERNAL_ERROR, text))
+                }
+                PeekResponseUnary::Canceled => return Err(self.canceled_error()),
+            }
+        }
+        Ok(rows)
+    }
+
+    /// Resolves the name of a publication to the relation that it replicates.
+    ///
+    /// Materialize has no `CREATE PUBLICATION`. Instead, every table, source,
+    /// and view is implicitly published under its own name.
+    async fn resolve_publication(
+        &mut self,
+        publication: &str,
+    ) -> Result<(ReplicatedRelation, String), ErrorResponse> {
+        let invalid_name = || {
+            ErrorResponse::error(
+                SqlState::INVALID_NAME,
+                format!("invalid publication name {}", publication.quoted()),
+            )
+        };
+        let stmts = parse_sql(&format!("TAIL {}", publication)).map_err(|_| invalid_name())?;
+        let name = match stmts.as_slice() {
+            [Statement::Tail(TailStatement {
+                relation: TailRelation::Name(RawObjectName::Name(name)),
+                options,
+                as_of: None,
+            })] if options.is_empty() => name.0.clone(),
+            _ => return Err(invalid_name()),
+        };
+        let literal = |s: &str| format!("'{}'", escape_single_quote_string(s));
+        let (database, schema, item) = match name.as_slice() {
+            [item] => (
+                "current_database()".into(),
+                "current_schema()".into(),
+                literal(item.as_str()),
+            ),
+            [schema, item] => (
+                "current_database()".into(),
+                literal(schema.as_str()),
+                literal(item.as_str()),
+            ),
+            [database, schema, item] => (
+                literal(database.as_str()),
+                literal(schema.as_str()),
+                literal(item.as_str()),
+            ),
+            _ => return Err(invalid_name()),
+        };
+        let rows = self
+            .internal_query(&format!(
+                "SELECT r.oid, d.name, s.name, r.name
+                FROM mz_catalog.mz_relations r
+                JOIN mz_catalog.mz_schemas s ON r.schema_id = s.id
+                LEFT JOIN mz_catalog.mz_databases d ON s.database_id = d.id
+                WHERE r.name = {item} AND s.name = {schema}
+                AND coalesce(d.name, {database}) = {database}",
+                item = item,
+                schema = schema,
+                database = database,
+            ))
+            .await?;
+        let row = match rows.as_slice() {
+            [row] => row,
+            _ => {
+                return Err(ErrorResponse::error(
+                    SqlState::UNDEFINED_OBJECT,
+                    format!("publication {} does not exist", publication.quoted()),
+                ))
+            }
+        };
+        let datums = row.unpack();
+        let namespace = datums[2].unwrap_str().to_string();
+        let name = datums[3].unwrap_str().to_string();
+        // Refer to the relation by its fully qualified name, so that the TAIL
+        // cannot resolve to a different relation than the one described to
+        // the client.
+        let mut qualified = vec![];
+        if !datums[1].is_null() {
+            qualified.push(Ident::new(datums[1].unwrap_str()).to_ast_string());
+        }
+        qualified.push(Ident::new(&namespace).to_ast_string());
+        qualified.push(Ident::new(&name).to_ast_string());
+        let relation = ReplicatedRelation {
+            oid: datums[0].unwrap_uint32(),
+            namespace,
+            name,
+            columns: vec![],
+        };
+        Ok((relation, qualified.join(".")))
+    }
+
+    async fn start_replication(
+        &mut self,
+        slot: String,
+        lsn: Lsn,
+        options: Vec<(String, String)>,
+    ) -> Result<State, io::Error> {
+        let options = match PgOutputOptions::from_options(options) {
+            Ok(options) => options,
+            Err(err) => return self.error(err).await,
+        };
+        let publication = match options.publication_names.as_slice() {
+            [publication] => publication.clone(),
+            _ => {
+                return self
+                    .error(ErrorResponse::error(
+                        SqlState::FEATURE_NOT_SUPPORTED,
+                        "replicating more than one publication is not supported",
+                    ))
+                    .await
+            }
+        };
+        let confirmed_flush = match self
+            .coord_client
+            .acquire_replication_slot(slot.clone())
+            .await
+        {
+            Ok(lsn) => Lsn(lsn),
+            Err(e) => {
+                return self
+                    .error(ErrorResponse::from_coord(Severity::Error, e))
+                    .await
+            }
+        };
+        let start = cmp::max(lsn, confirmed_flush);
+        let res = self.stream_replication(&slot, &publication, start).await;
+        // Releasing the slot cannot fail. If the connection is gone, the
+        // coordinator releases the slot when the session terminates.
+        let _ = self.coord_client.release_replication_slot(slot).await;
+        res
+    }
+
+    /// Streams the changes to the relation named by `publication` that
+    /// occur after `start` to the client.
+    ///
+    /// If `start` is zero, the stream begins with a snapshot of the relation.
+    async fn stream_replication(
+        &mut self,
+        slot: &str,
+        publication: &str,
+        start: Lsn,
+    ) -> Result<State, io::Error> {
+        let (mut relation, name) = match self.resolve_publication(publication).await {
+            Ok(relation) => relation,
+            Err(err) => return self.error(err).await,
+        };
+
+        let mut sql = format!(
+            "TAIL {} WITH (SNAPSHOT = {}, PROGRESS)",
+            name,
+            start == Lsn(0)
+        );
+        if start > Lsn(0) {
+            sql += &format!(" AS OF {}", start.0);
+        }
+        let stmt = match parse_sql(&sql) {
+            Ok(stmts) => stmts.into_element(),
+            Err(err) => return self.error(err).await,
+        };
+
+        self.start_transaction(Some(1)).await;
+        self.coord_client.session().remove_portal(INTERNAL_PORTAL);
+        if let Err(e) = self
+            .coord_client
+            .declare(INTERNAL_PORTAL.into(), stmt, vec![])
+            .await
+        {
+            return self
+                .error(ErrorResponse::from_coord(Severity::Error, e))
+                .await;
+        }
+        let desc = self
+            .coord_client
+            .session()
+            .get_portal_unverified(INTERNAL_PORTAL)
+            .and_then(|portal| portal.desc.relation_desc.clone())
+            .expect("TAIL portal should have a relation description");
+        // Skip the `mz_timestamp`, `mz_progressed`, and `mz_diff` columns.
+        relation.columns = desc
+            .iter()
+            .skip(3)
+            .map(|(name, typ)| (name.clone(), typ.scalar_type.clone()))
+            .collect();
+
+        let state = match self.coord_client.execute(INTERNAL_PORTAL.into()).await {
+            Ok(ExecuteResponse::Tailing { rx }) => {
+                self.send_replication_stream(slot, relation, start, rx)
+                    .await?
+            }
+            Ok(_) => {
+                self.error(ErrorResponse::error(
+                    SqlState::INTERNAL_ERROR,
+                    "unexpected response to TAIL",
+                ))
+                .await?
+            }
+            Err(e) => {
+                self.error(ErrorResponse::from_coord(Severity::Error, e))
+                    .await?
+            }
+        };
+        self.coord_client.session().remove_portal(INTERNAL_PORTAL);
+        // Ending the transaction drops the TAIL.
+        if let State::Ready = state {
+            self.commit_transaction().await?;
+        }
+        Ok(state)
+    }
+
+    async fn send_replication_stream(
+        &mut self,
+        slot: &str,
+        relation: ReplicatedRelation,
+        start: Lsn,
+        mut rx: RowBatchStream,
+    ) -> Result<State, io::Error> {
+        self.send(BackendMessage::CopyBothResponse).await?;
+        self.conn.flush().await?;
+
+        let mut encoder = PgOutputEncoder::default();
+        let mut relation_sent = false;
+        let mut xid: u32 = 0;
+        // The latest LSN through which all changes have been sent.
+        let mut wal_end = start;
+        // Updates at timestamps that are not yet complete.
+        let mut pending: BTreeMap<u64, Vec<(Vec<Option<mz_pgrepr::Value>>, i64)>> = BTreeMap::new();
+        let mut keepalive = time::interval(Duration::from_secs(10));
+        loop {
+            let mut messages = vec![];
+            tokio::select! {
+                _ = keepalive.tick() => {
+                    messages.push(replication::keepalive(wal_end, replication::pg_now(), false));
+                }
+                _ = self.coord_client.canceled() => {
+                    return self
+                        .error(self.canceled_error())
+                        .await;
+                }
+                message = self.conn.recv() => match message? {
+                    Some(FrontendMessage::CopyData(data)) => match StandbyMessage::decode(&data) {
+                        Ok(StandbyMessage::StatusUpdate { flushed, reply_requested }) => {
+                            if let Err(e) = self
+                                .coord_client
+                                .confirm_replication_slot(slot.into(), flushed.0)
+                                .await
+                            {
+                                return self
+                                    .error(ErrorResponse::from_coord(Severity::Error, e))
+                                    .await;
+                            }
+                            if reply_requested {
+                                messages.push(replication::keepalive(
+                                    wal_end,
+                                    replication::pg_now(),
+                                    false,
+                                ));
+                            }
+                        }
+                        Ok(StandbyMessage::Other) => (),
+                        Err(err) => return self.error(err).await,
+                    },
+                    Some(FrontendMessage::CopyDone) => break,
+                    Some(FrontendMessage::CopyFail(err)) => {
+                        return self
+                            .error(ErrorResponse::error(
+                                SqlState::QUERY_CANCELED,
+                                format!("replication stream failed: {}", err),
+                            ))
+                            .await
+                    }
+                    Some(FrontendMessage::Flush) | Some(FrontendMessage::Sync) => (),
+                    Some(FrontendMessage::Terminate) | None => return Ok(State::Done),
+                    Some(_) => {
+                        return self
+                            .error(ErrorResponse::error(
+                                SqlState::PROTOCOL_VIOLATION,
+                                "unexpected message type during replication",
+                            ))
+                            .await
+                    }
+                },
+                batch = rx.recv() => match batch {
+                    None => break,
+                    Some(PeekResponseUnary::Error(text)) => {
+                        return self
+                            .error(ErrorResponse::error(SqlState::INTERNAL_ERROR, text))
+                            .await;
+                    }
+                    Some(PeekResponseUnary::Canceled) => {
+                        return self
+                            .error(self.canceled_error())
+                            .await;
+                    }
+                    Some(PeekResponseUnary::Rows(rows)) => {
+                        for row in rows {
+                            let datums = row.unpack();
+                            let ts = match tail_timestamp(datums[0]) {
+                                Ok(ts) => ts,
+                                Err(err) => return self.error(err).await,
+                            };
+                            if datums[1].unwrap_bool() {
+                                // All updates at times before the new frontier
+                                // are complete, and each such time is sent as
+                                // a transaction.
+                                let incomplete = pending.split_off(&ts);
+                                let complete = mem::replace(&mut pending, incomplete);
+                                for (ts, updates) in complete {
+                                    xid = xid.wrapping_add(1);
+                                    let lsn = Lsn(ts);
+                                    let commit_time = replication::pg_timestamp(ts);
+                                    let mut txn = vec![encoder.begin(lsn, commit_time, xid)];
+                                    if !relation_sent {
+                                        match encoder.relation(&relation) {
+                                            Ok(msg) => txn.push(msg),
+                                            Err(err) => return self.error(err).await,
+                                        }
+                                        relation_sent = true;
+                                    }
+                                    for (values, diff) in updates {
+                                        for _ in 0..diff.unsigned_abs() {
+                                            let msg = if diff > 0 {
+                                                encoder.insert(relation.oid, &values)
+                                            } else {
+                                                encoder.delete(relation.oid, &values)
+                                            };
+                                            match msg {
+                                                Ok(msg) => txn.push(msg),
+                                                Err(err) => return self.error(err).await,
+                                            }
+                                        }
+                                    }
+                                    txn.push(encoder.commit(lsn, lsn, commit_time));
+                                    let send_time = replication::pg_now();
+                                    messages.extend(
+                                        txn.iter()
+                                            .map(|msg| replication::xlog_data(lsn, lsn, send_time, msg)),
+                                    );
+                                }
+                                wal_end = cmp::max(wal_end, Lsn(ts.saturating_sub(1)));
+                            } else {
+                                let diff = datums[2].unwrap_int64();
+                                let values = datums[3..]
+                                    .iter()
+                                    .zip(&relation.columns)
+                                    .map(|(datum, (_name, typ))| {
+                                        mz_pgrepr::Value::from_datum(*datum, typ)
+                                    })
+                                    .collect();
+                                pending.entry(ts).or_default().push((values, diff));
+                            }
+                        }
+                    }
+                },
+            }
+            self.send_all(messages.into_iter().map(BackendMessage::CopyData))
+                .await?;
+            self.conn.flush().await?;
+        }
+
+        self.send(BackendMessage::CopyDone).await?;
+        self.send(BackendMessage::CommandComplete {
+            tag: "START_REPLICATION".into(),
+        })
+        .await?;
+        Ok(State::Ready)
+    }
+
     async fn error(&mut self, err: ErrorResponse) -> Result<State, io::Error> {
         assert!(err.severity.is_error());
         debug!(
@@ -1716,6 +2404,14 @@ where
         }
     }
 
+    /// Returns the error to report for a statement that was canceled.
+    fn canceled_error(&self) -> ErrorResponse {
+        ErrorResponse::error(
+            SqlState::QUERY_CANCELED,
+            self.coord_client.cancel_reason().message(),
+        )
+    }
+
     async fn aborted_txn_error(&mut self) -> Result<State, io::Error> {
         self.send(BackendMessage::ErrorResponse(ErrorResponse::error(
             SqlState::IN_FAILED_SQL_TRANSACTION,
@@ -1823,6 +2519,29 @@ fn is_txn_exit_stmt(stmt: Option<&Statement<Raw>>) -> bool {
     }
 }
 
+/// The portal used by queries that pgwire runs on behalf of the client.
+///
+/// Portal and cursor names arrive in NUL-terminated strings, so no client can
+/// name a portal that contains a NUL character.
+const INTERNAL_PORTAL: &str = "\0mz_internal";
+
+/// Converts an `mz_timestamp` produced by `TAIL` to a timestamp.
+fn tail_timestamp(datum: Datum) -> Result<u64, ErrorResponse> {
+    let invalid = || {
+        ErrorResponse::error(
+            SqlState::INTERNAL_ERROR,
+            format!("invalid TAIL timestamp: {}", datum),
+        )
+    };
+    let ts = match datum {
+        Datum::Numeric(ts) => numeric::cx_datum()
+            .try_into_i64(ts.0)
+            .map_err(|_| invalid())?,
+        _ => return Err(invalid()),
+    };
+    u64::try_from(ts).map_err(|_| invalid())
+}
+
 #[derive(Debug)]
 enum FetchResult {
     Rows(Option<Vec<Row>>),
@@ -0,0 +1,783 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Logical replication in pgwire.
+//!
+//! Connections that set the `replication` startup parameter to `database`
+//! may issue the replication commands of the [streaming replication
+//! protocol][1] in addition to SQL. `START_REPLICATION` streams the changes to
+//! a relation, as computed by `TAIL`, encoded with PostgreSQL's `pgoutput`
+//! [logical replication message format][2]. This allows tools that consume
+//! PostgreSQL publications to consume Materialize views instead.
+//!
+//! Materialize has no write-ahead log, so log sequence numbers (LSNs) are
+//! Materialize timestamps. Each timestamp at which the relation changes is
+//! streamed as one transaction whose commit LSN is that timestamp.
+//!
+//! [1]: https://www.postgresql.org/docs/current/protocol-replication.html
+//! [2]: https://www.postgresql.org/docs/current/protocol-logicalrep-message-formats.html
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use byteorder::{ByteOrder, NetworkEndian};
+use bytes::{BufMut, BytesMut};
+use postgres::error::SqlState;
+
+use mz_ore::str::StrExt;
+use mz_repr::{ColumnName, ScalarType};
+
+use crate::message::ErrorResponse;
+
+/// The only output plugin that Materialize supports.
+pub const PGOUTPUT: &str = "pgoutput";
+
+/// The number of milliseconds between the Unix epoch and the PostgreSQL epoch
+/// (2000-01-01).
+const PG_EPOCH_OFFSET_MILLIS: i64 = 946_684_800_000;
+
+/// The value of the `replication` startup parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplicationMode {
+    /// The connection is a normal SQL connection.
+    Off,
+    /// The connection may issue logical replication commands.
+    Database,
+}
+
+impl ReplicationMode {
+    /// Parses the value of the `replication` startup parameter.
+    pub fn parse(value: &str) -> Result<ReplicationMode, ErrorResponse> {
+        match value.to_lowercase().as_str() {
+            "database" => Ok(ReplicationMode::Database),
+            "false" | "off" | "no" | "0" => Ok(ReplicationMode::Off),
+            "true" | "on" | "yes" | "1" => Err(ErrorResponse::fatal(
+                SqlState::FEATURE_NOT_SUPPORTED,
+                "physical replication is not supported",
+            )),
+            _ => Err(ErrorResponse::fatal(
+                SqlState::INVALID_PARAMETER_VALUE,
+                format!(
+                    "invalid value for parameter \"replication\": {}",
+                    value.quoted()
+                ),
+            )),
+        }
+    }
+}
+
+/// A log sequence number.
+///
+/// Formatted like PostgreSQL LSNs, as two hexadecimal 32-bit halves separated
+/// by a slash, e.g., `16/B374D848`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Lsn(pub u64);
+
+impl fmt::Display for Lsn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:X}/{:X}", self.0 >> 32, self.0 & 0xFFFF_FFFF)
+    }
+}
+
+impl FromStr for Lsn {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Lsn, ()> {
+        let (hi, lo) = s.split_once('/').ok_or(())?;
+        let hi = u32::from_str_radix(hi, 16).map_err(|_| ())?;
+        let lo = u32::from_str_radix(lo, 16).map_err(|_| ())?;
+        Ok(Lsn((u64::from(hi) << 32) | u64::from(lo)))
+    }
+}
+
+/// A replication command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplicationCommand {
+    /// `IDENTIFY_SYSTEM`
+    IdentifySystem,
+    /// `CREATE_REPLICATION_SLOT name [TEMPORARY] LOGICAL plugin [...]`
+    CreateSlot {
+        name: String,
+        temporary: bool,
+        plugin: String,
+    },
+    /// `DROP_REPLICATION_SLOT name [WAIT]`
+    DropSlot { name: String },
+    /// `START_REPLICATION SLOT name LOGICAL lsn [(option 'value', ...)]`
+    StartReplication {
+        slot: String,
+        lsn: Lsn,
+        options: Vec<(String, String)>,
+    },
+}
+
+impl ReplicationCommand {
+    /// Returns the command tag to report upon successful completion of the
+    /// command.
+    pub fn tag(&self) -> &'static str {
+        match self {
+            ReplicationCommand::IdentifySystem => "IDENTIFY_SYSTEM",
+            ReplicationCommand::CreateSlot { .. } => "CREATE_REPLICATION_SLOT",
+            ReplicationCommand::DropSlot { .. } => "DROP_REPLICATION_SLOT",
+            ReplicationCommand::StartReplication { .. } => "START_REPLICATION",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    QuotedIdent(String),
+    String(String),
+    LParen,
+    RParen,
+    Comma,
+    Semicolon,
+}
+
+fn tokenize(sql: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut chars = sql.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' | ')' | ',' | ';' => {
+                chars.next();
+                tokens.push(match c {
+                    '(' => Token::LParen,
+                    ')' => Token::RParen,
+                    ',' => Token::Comma,
+                    _ => Token::Semicolon,
+                });
+            }
+            '"' | '\'' => {
+                chars.next();
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some(q) if q == c => {
+                            // A doubled quote is an escaped quote.
+                            if chars.peek() == Some(&c) {
+                                chars.next();
+                                s.push(c);
+                            } else {
+                                break;
+                            }
+                        }
+                        Some(ch) => s.push(ch),
+                        None => return Err("unterminated quoted string".into()),
+                    }
+                }
+                tokens.push(if c == '"' {
+                    Token::QuotedIdent(s)
+                } else {
+                    Token::String(s)
+                });
+            }
+            _ => {
+                let mut s = String::new();
+                while let Some(&ch) = chars.peek() {
+                    if ch.is_whitespace() || "(),;\"'".contains(ch) {
+                        break;
+                    }
+                    s.push(ch);
+                    chars.next();
+                }
+                tokens.push(Token::Word(s));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct CommandParser {
+    tokens: std::vec::IntoIter<Token>,
+    peeked: Option<Token>,
+}
+
+impl CommandParser {
+    fn peek(&mut self) -> Option<&Token> {
+        if self.peeked.is_none() {
+            self.peeked = self.tokens.next();
+        }
+        self.peeked.as_ref()
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        self.peeked.take().or_else(|| self.tokens.next())
+    }
+
+    fn parse_keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Word(w)) if w.eq_ignore_ascii_case(keyword) => {
+                self.next();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), String> {
+        if self.parse_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(format!("expected {}", keyword))
+        }
+    }
+
+    fn parse_ident(&mut self) -> Result<String, String> {
+        match self.next() {
+            Some(Token::Word(w)) => Ok(w.to_lowercase()),
+            Some(Token::QuotedIdent(s)) => Ok(s),
+            _ => Err("expected identifier".into()),
+        }
+    }
+
+    fn parse_lsn(&mut self) -> Result<Lsn, String> {
+        match self.next() {
+            Some(Token::Word(w)) => w
+                .parse()
+                .map_err(|_| format!("invalid log sequence number {}", w.quoted())),
+            _ => Err("expected log sequence number".into()),
+        }
+    }
+
+    /// Parses a parenthesized list of options of the form
+    /// `(name ['value'], ...)`.
+    fn parse_options(&mut self) -> Result<Vec<(String, String)>, String> {
+        let mut options = vec![];
+        if self.peek() != Some(&Token::LParen) {
+            return Ok(options);
+        }
+        self.next();
+        loop {
+            let name = self.parse_ident()?;
+            let value = match self.peek() {
+                Some(Token::String(_)) | Some(Token::Word(_)) => match self.next() {
+                    Some(Token::String(s)) | Some(Token::Word(s)) => s,
+                    _ => unreachable!(),
+                },
+                _ => String::new(),
+            };
+            options.push((name, value));
+            match self.next() {
+                Some(Token::Comma) => (),
+                Some(Token::RParen) => return Ok(options),
+                _ => return Err("expected , or )".into()),
+            }
+        }
+    }
+
+    fn expect_end(&mut self) -> Result<(), String> {
+        if self.peek() == Some(&Token::Semicolon) {
+            self.next();
+        }
+        match self.next() {
+            None => Ok(()),
+            Some(_) => Err("unexpected trailing input".into()),
+        }
+    }
+
+    fn parse_logical(&mut self) -> Result<(), String> {
+        if self.parse_keyword("PHYSICAL") {
+            return Err("physical replication is not supported".into());
+        }
+        self.expect_keyword("LOGICAL")
+    }
+
+    fn parse_create_slot(&mut self) -> Result<ReplicationCommand, String> {
+        let name = self.parse_ident()?;
+        let temporary = self.parse_keyword("TEMPORARY");
+        self.parse_logical()?;
+        let plugin = self.parse_ident()?;
+        // Materialize never exports snapshots. Instead, the first
+        // `START_REPLICATION` on a slot streams the relation's snapshot.
+        // Snapshot options are accepted for compatibility and ignored.
+        if !(self.parse_keyword("EXPORT_SNAPSHOT")
+            || self.parse_keyword("NOEXPORT_SNAPSHOT")
+            || self.parse_keyword("USE_SNAPSHOT"))
+        {
+            self.parse_options()?;
+        }
+        self.expect_end()?;
+        Ok(ReplicationCommand::CreateSlot {
+            name,
+            temporary,
+            plugin,
+        })
+    }
+
+    fn parse_drop_slot(&mut self) -> Result<ReplicationCommand, String> {
+        let name = self.parse_ident()?;
+        self.parse_keyword("WAIT");
+        self.expect_end()?;
+        Ok(ReplicationCommand::DropSlot { name })
+    }
+
+    fn parse_start_replication(&mut self) -> Result<ReplicationCommand, String> {
+        self.expect_keyword("SLOT")?;
+        let slot = self.parse_ident()?;
+        self.parse_logical()?;
+        let lsn = self.parse_lsn()?;
+        let options = self.parse_options()?;
+        self.expect_end()?;
+        Ok(ReplicationCommand::StartReplication { slot, lsn, options })
+    }
+}
+
+/// Parses a replication command.
+///
+/// Returns `None` if `sql` does not begin with the name of a replication
+/// command, in which case it should be treated as SQL.
+pub fn parse_command(sql: &str) -> Option<Result<ReplicationCommand, ErrorResponse>> {
+    let command = sql.split_whitespace().next()?.to_uppercase();
+    if !matches!(
+        command.as_str(),
+        "IDENTIFY_SYSTEM"
+            | "CREATE_REPLICATION_SLOT"
+            | "DROP_REPLICATION_SLOT"
+            | "START_REPLICATION"
+            | "READ_REPLICATION_SLOT"
+            | "TIMELINE_HISTORY"
+            | "BASE_BACKUP"
+    ) {
+        return None;
+    }
+    let result = tokenize(sql).and_then(|tokens| {
+        let mut parser = CommandParser {
+            tokens: tokens.into_iter(),
+            peeked: None,
+        };
+        parser.next();
+        match command.as_str() {
+            "IDENTIFY_SYSTEM" => parser
+                .expect_end()
+                .map(|()| ReplicationCommand::IdentifySystem),
+            "CREATE_REPLICATION_SLOT" => parser.parse_create_slot(),
+            "DROP_REPLICATION_SLOT" => parser.parse_drop_slot(),
+            "START_REPLICATION" => parser.parse_start_replication(),
+            _ => Err(format!("{} is not supported", command)),
+        }
+    });
+    Some(result.map_err(|e| ErrorResponse::error(SqlState::SYNTAX_ERROR, e)))
+}
+
+/// The options of the `pgoutput` plugin.
+#[derive(Debug)]
+pub struct PgOutputOptions {
+    /// The publications to stream.
+    pub publication_names: Vec<String>,
+}
+
+impl PgOutputOptions {
+    /// Validates the options passed to `START_REPLICATION`.
+    pub fn from_options(options: Vec<(String, String)>) -> Result<PgOutputOptions, ErrorResponse> {
+        let invalid =
+            |message: String| ErrorResponse::error(SqlState::INVALID_PARAMETER_VALUE, message);
+        let mut proto_version = None;
+        let mut publication_names = None;
+        for (name, value) in options {
+            match name.as_str() {
+                "proto_version" => match value.parse::<u32>() {
+                    // Version 2 adds streaming of in-progress transactions,
+                    // which Materialize never does, so it is otherwise
+                    // identical to version 1.
+                    Ok(v @ (1 | 2)) => proto_version = Some(v),
+                    Ok(v) => {
+                        return Err(invalid(format!(
+                            "client sent proto_version={} but we only support protocol 2 or lower",
+                            v
+                        )))
+                    }
+                    Err(_) => return Err(invalid(format!("invalid proto_version {}", value))),
+                },
+                "publication_names" => publication_names = Some(split_identifiers(&value)?),
+                "binary" if value != "false" && value != "off" => {
+                    return Err(ErrorResponse::error(
+                        SqlState::FEATURE_NOT_SUPPORTED,
+                        "binary pgoutput format is not supported",
+                    ))
+                }
+                "binary" | "messages" | "streaming" => (),
+                _ => return Err(invalid(format!("unrecognized pgoutput option: {}", name))),
+            }
+        }
+        if proto_version.is_none() {
+            return Err(invalid("proto_version option missing".into()));
+        }
+        match publication_names {
+            Some(publication_names) if !publication_names.is_empty() => {
+                Ok(PgOutputOptions { publication_names })
+            }
+            _ => Err(invalid("publication_names parameter missing".into())),
+        }
+    }
+}
+
+/// Splits a comma-separated list of possibly-quoted identifiers, like
+/// PostgreSQL's `SplitIdentifierString`. Quoting is preserved, so that the
+/// identifiers can be parsed as SQL object names.
+fn split_identifiers(s: &str) -> Result<Vec<String>, ErrorResponse> {
+    let mut out = vec![];
+    let mut current = String::new();
+    let mut in_quotes = false;
+    for c in s.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                current.push(c);
+            }
+            ',' if !in_quotes => out.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    out.push(current);
+    if in_quotes || out.iter().any(|name| name.trim().is_empty()) {
+        return Err(ErrorResponse::error(
+            SqlState::INVALID_NAME,
+            "invalid publication_names syntax",
+        ));
+    }
+    Ok(out
+        .into_iter()
+        .map(|name| name.trim().to_string())
+        .collect())
+}
+
+/// A relation whose changes are streamed to a replication client.
+#[derive(Debug)]
+pub struct ReplicatedRelation {
+    pub oid: u32,
+    pub namespace: String,
+    pub name: String,
+    pub columns: Vec<(ColumnName, ScalarType)>,
+}
+
+/// Converts a Materialize timestamp, which counts milliseconds since the Unix
+/// epoch, to a PostgreSQL timestamp, which counts microseconds since the
+/// PostgreSQL epoch.
+pub fn pg_timestamp(millis: u64) -> i64 {
+    let millis = i64::try_from(millis).unwrap_or(i64::MAX);
+    millis
+        .saturating_sub(PG_EPOCH_OFFSET_MILLIS)
+        .saturating_mul(1000)
+}
+
+/// Returns the number of milliseconds since the Unix epoch.
+pub fn now_millis() -> u64 {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system clock set before 1970")
+        .as_millis();
+    u64::try_from(millis).unwrap_or(u64::MAX)
+}
+
+/// Returns the current time as a PostgreSQL timestamp.
+pub fn pg_now() -> i64 {
+    pg_timestamp(now_millis())
+}
+
+/// Encodes `pgoutput` messages.
+#[derive(Debug, Default)]
+pub struct PgOutputEncoder {
+    buf: BytesMut,
+}
+
+impl PgOutputEncoder {
+    fn take(&mut self) -> Vec<u8> {
+        self.buf.split().to_vec()
+    }
+
+    /// Encodes a Begin message.
+    pub fn begin(&mut self, final_lsn: Lsn, commit_time: i64, xid: u32) -> Vec<u8> {
+        self.buf.put_u8(b'B');
+        self.buf.put_u64(final_lsn.0);
+        self.buf.put_i64(commit_time);
+        self.buf.put_u32(xid);
+        self.take()
+    }
+
+    /// Encodes a Commit message.
+    pub fn commit(&mut self, commit_lsn: Lsn, end_lsn: Lsn, commit_time: i64) -> Vec<u8> {
+        self.buf.put_u8(b'C');
+        self.buf.put_u8(0);
+        self.buf.put_u64(commit_lsn.0);
+        self.buf.put_u64(end_lsn.0);
+        self.buf.put_i64(commit_time);
+        self.take()
+    }
+
+    /// Encodes a Relation message.
+    ///
+    /// Relations are described with a replica identity of `FULL`, as deletes
+    /// carry the entire old row.
+    pub fn relation(&mut self, relation: &ReplicatedRelation) -> Result<Vec<u8>, ErrorResponse> {
+        self.buf.put_u8(b'R');
+        self.buf.put_u32(relation.oid);
+        put_cstr(&mut self.buf, &relation.namespace);
+        put_cstr(&mut self.buf, &relation.name);
+        self.buf.put_u8(b'f');
+        self.buf.put_i16(column_count(relation.columns.len())?);
+        for (name, typ) in &relation.columns {
+            let pg_type = mz_pgrepr::Type::from(typ);
+            // With a replica identity of `FULL`, every column is part of the
+            // key.
+            self.buf.put_u8(1);
+            put_cstr(&mut self.buf, name.as_str());
+            self.buf.put_u32(pg_type.oid());
+            self.buf.put_i32(pg_type.typmod());
+        }
+        Ok(self.take())
+    }
+
+    /// Encodes an Insert message.
+    pub fn insert(
+        &mut self,
+        oid: u32,
+        values: &[Option<mz_pgrepr::Value>],
+    ) -> Result<Vec<u8>, ErrorResponse> {
+        self.buf.put_u8(b'I');
+        self.buf.put_u32(oid);
+        self.buf.put_u8(b'N');
+        self.tuple(values)?;
+        Ok(self.take())
+    }
+
+    /// Encodes a Delete message that carries the entire old row.
+    pub fn delete(
+        &mut self,
+        oid: u32,
+        values: &[Option<mz_pgrepr::Value>],
+    ) -> Result<Vec<u8>, ErrorResponse> {
+        self.buf.put_u8(b'D');
+        self.buf.put_u32(oid);
+        self.buf.put_u8(b'O');
+        self.tuple(values)?;
+        Ok(self.take())
+    }
+
+    fn tuple(&mut self, values: &[Option<mz_pgrepr::Value>]) -> Result<(), ErrorResponse> {
+        self.buf.put_i16(column_count(values.len())?);
+        for value in values {
+            match value {
+                None => self.buf.put_u8(b'n'),
+                Some(value) => {
+                    let mut text = BytesMut::new();
+                    value.encode_text(&mut text);
+                    let len = i32::try_from(text.len()).map_err(|_| {
+                        ErrorResponse::error(
+                            SqlState::PROGRAM_LIMIT_EXCEEDED,
+                            "value too large to replicate",
+                        )
+                    })?;
+                    self.buf.put_u8(b't');
+                    self.buf.put_i32(len);
+                    self.buf.put_slice(&text);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn column_count(n: usize) -> Result<i16, ErrorResponse> {
+    i16::try_from(n).map_err(|_| {
+        ErrorResponse::error(
+            SqlState::PROGRAM_LIMIT_EXCEEDED,
+            "too many columns to replicate",
+        )
+    })
+}
+
+fn put_cstr(buf: &mut BytesMut, s: &str) {
+    buf.put_slice(s.as_bytes());
+    buf.put_u8(b'\0');
+}
+
+/// Wraps a `pgoutput` message in an XLogData message.
+pub fn xlog_data(start: Lsn, end: Lsn, send_time: i64, data: &[u8]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(25 + data.len());
+    buf.put_u8(b'w');
+    buf.put_u64(start.0);
+    buf.put_u64(end.0);
+    buf.put_i64(send_time);
+    buf.put_slice(data);
+    buf
+}
+
+/// Encodes a primary keepalive message.
+pub fn keepalive(wal_end: Lsn, send_time: i64, reply_requested: bool) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(18);
+    buf.put_u8(b'k');
+    buf.put_u64(wal_end.0);
+    buf.put_i64(send_time);
+    buf.put_u8(u8::from(reply_requested));
+    buf
+}
+
+/// A message sent by a replication client during streaming.
+#[derive(Debug, PartialEq, Eq)]
+pub enum StandbyMessage {
+    /// The client has durably flushed all changes up to and including the
+    /// given LSN. Also requests a keepalive if `reply_requested` is set.
+    StatusUpdate { flushed: Lsn, reply_requested: bool },
+    /// A message that Materialize has no use for, like hot standby feedback.
+    Other,
+}
+
+impl StandbyMessage {
+    /// Decodes the contents of a CopyData message sent by a replication
+    /// client.
+    pub fn decode(data: &[u8]) -> Result<StandbyMessage, ErrorResponse> {
+        match data.first() {
+            Some(b'r') if data.len() >= 34 => Ok(StandbyMessage::StatusUpdate {
+                flushed: Lsn(NetworkEndian::read_u64(&data[9..17])),
+                reply_requested: data[33] != 0,
+            }),
+            Some(b'r') => Err(ErrorResponse::error(
+                SqlState::PROTOCOL_VIOLATION,
+                format!(
+                    "invalid standby status update message length {}",
+                    data.len()
+                ),
+            )),
+            Some(b'h') => Ok(StandbyMessage::Other),
+            Some(b) => Err(ErrorResponse::error(
+                SqlState::PROTOCOL_VIOLATION,
+                format!("unexpected message type \"{}\"", char::from(*b)),
+            )),
+            None => Err(ErrorResponse::error(
+                SqlState::PROTOCOL_VIOLATION,
+                "empty replication message",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lsn() {
+        for (s, lsn) in [("0/0", 0), ("16/B374D848", 0x16_B374_D848), ("0/1", 1)] {
+            assert_eq!(s.parse::<Lsn>(), Ok(Lsn(lsn)));
+            assert_eq!(Lsn(lsn).to_string(), s);
+        }
+        assert!("16".parse::<Lsn>().is_err());
+        assert!("G/0".parse::<Lsn>().is_err());
+    }
+
+    #[test]
+    fn test_parse_command() {
+        let parse = |sql| parse_command(sql).map(|r| r.map_err(|e| e.message));
+
+        assert_eq!(parse("SELECT 1"), None);
+        assert_eq!(
+            parse("IDENTIFY_SYSTEM;"),
+            Some(Ok(ReplicationCommand::IdentifySystem))
+        );
+        assert_eq!(
+            parse(r#"CREATE_REPLICATION_SLOT "Slot" TEMPORARY LOGICAL pgoutput NOEXPORT_SNAPSHOT"#),
+            Some(Ok(ReplicationCommand::CreateSlot {
+                name: "Slot".into(),
+                temporary: true,
+                plugin: "pgoutput".into(),
+            }))
+        );
+        assert_eq!(
+            parse("create_replication_slot s LOGICAL pgoutput (SNAPSHOT 'nothing')"),
+            Some(Ok(ReplicationCommand::CreateSlot {
+                name: "s".into(),
+                temporary: false,
+                plugin: "pgoutput".into(),
+            }))
+        );
+        assert_eq!(
+            parse("CREATE_REPLICATION_SLOT s PHYSICAL"),
+            Some(Err("physical replication is not supported".into()))
+        );
+        assert_eq!(
+            parse("DROP_REPLICATION_SLOT s WAIT"),
+            Some(Ok(ReplicationCommand::DropSlot { name: "s".into() }))
+        );
+        assert_eq!(
+            parse(
+                r#"START_REPLICATION SLOT "s" LOGICAL 0/1A
+                   ("proto_version" '1', "publication_names" 'v1,"V2"')"#
+            ),
+            Some(Ok(ReplicationCommand::StartReplication {
+                slot: "s".into(),
+                lsn: Lsn(0x1A),
+                options: vec![
+                    ("proto_version".into(), "1".into()),
+                    ("publication_names".into(), r#"v1,"V2""#.into()),
+                ],
+            }))
+        );
+        assert_eq!(
+            parse("START_REPLICATION SLOT s LOGICAL 0/0 extra"),
+            Some(Err("unexpected trailing input".into()))
+        );
+        assert_eq!(
+            parse("BASE_BACKUP"),
+            Some(Err("BASE_BACKUP is not supported".into()))
+        );
+    }
+
+    #[test]
+    fn test_pgoutput_options() {
+        let options = |opts: &[(&str, &str)]| {
+            PgOutputOptions::from_options(
+                opts.iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            )
+            .map(|o| o.publication_names)
+            .map_err(|e| e.message)
+        };
+        assert_eq!(
+            options(&[("proto_version", "1"), ("publication_names", r#"a, "B,c""#)]),
+            Ok(vec!["a".into(), r#""B,c""#.into()])
+        );
+        assert_eq!(
+            options(&[("proto_version", "1")]),
+            Err("publication_names parameter missing".into())
+        );
+        assert_eq!(
+            options(&[("proto_version", "3"), ("publication_names", "a")]),
+            Err("client sent proto_version=3 but we only support protocol 2 or lower".into())
+        );
+        assert_eq!(
+            options(&[("publication_names", "a,")]),
+            Err("invalid publication_names syntax".into())
+        );
+    }
+
+    #[test]
+    fn test_standby_message() {
+        let mut data = vec![b'r'];
+        data.extend(1u64.to_be_bytes());
+        data.extend(2u64.to_be_bytes());
+        data.extend(3u64.to_be_bytes());
+        data.extend(0i64.to_be_bytes());
+        data.push(1);
+        assert_eq!(
+            StandbyMessage::decode(&data).map_err(|e| e.message),
+            Ok(StandbyMessage::StatusUpdate {
+                flushed: Lsn(2),
+                reply_requested: true,
+            })
+        );
+        assert!(StandbyMessage::decode(&data[..10]).is_err());
+    }
+}
@@ -1176,6 +1176,16 @@ impl RowRef {
     pub fn is_empty(&self) -> bool {
         self.data.is_empty()
     }
+
+    /// Returns the number of bytes the row has allocated on the heap, which
+    /// is zero for rows small enough to be stored inline.
+    pub fn heap_size(&self) -> usize {
+        if self.data.spilled() {
+            self.data.capacity()
+        } else {
+            0
+        }
+    }
 }
 
 impl std::ops::Deref for Row {
@@ -6,60 +6,736 @@
 // As of the Change Date specified in that file, in accordance with
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
-use anyhow::{bail, Error};
+
+//! A secrets controller that stores secrets as encrypted files in a local
+//! directory.
+//!
+//! Secrets are protected with envelope encryption. The contents of each secret
+//! are encrypted with a freshly generated data key, and the data key is in
+//! turn encrypted with a master key that is stored in a separate file. Both
+//! layers use AES-256-GCM, with the secret's name and the file header as
+//! associated data, so that encrypted files cannot be swapped for one another
+//! undetected. The master key can be replaced with
+//! [`FilesystemSecretsController::rotate_master_key`], which re-encrypts every
+//! secret under a new master key.
+//!
+//! Batches of operations are applied atomically. New contents are first
+//! written to temporary files, then a journal describing the batch is durably
+//! written, and only then are the temporary files renamed into place and
+//! deleted secrets removed. If the process crashes before the journal is
+//! written, none of the batch is applied; if it crashes afterwards, the
+//! journal is replayed when the controller is next opened.
+
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context, Error};
 use async_trait::async_trait;
+use openssl::rand::rand_bytes;
+use openssl::sha::sha256;
+use openssl::symm::{decrypt_aead, encrypt_aead, Cipher};
+
+use mz_repr::GlobalId;
 use mz_secrets::{SecretOp, SecretsController};
-use std::fs;
-use std::fs::OpenOptions;
-use std::io::Write;
-use std::os::unix::fs::OpenOptionsExt;
-use std::path::PathBuf;
+
+/// The prefix of every encrypted secret file.
+const MAGIC: &[u8; 8] = b"MZSECRET";
+/// The version of the encrypted secret file format.
+const FORMAT_VERSION: u8 = 1;
+const KEY_LEN: usize = 32;
+const KEY_ID_LEN: usize = 8;
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+const HEADER_LEN: usize = MAGIC.len() + 1 + KEY_ID_LEN;
+
+/// The name of the journal that records the batch of operations being
+/// applied.
+const JOURNAL_NAME: &str = "apply.journal";
+/// The suffix of temporary files, which are renamed into place once they
+/// have been completely written.
+const TEMP_SUFFIX: &str = ".tmp";
+/// The suffix of the file that holds the new master key while a rotation is
+/// in progress.
+const NEXT_KEY_SUFFIX: &str = ".next";
 
 pub struct FilesystemSecretsController {
     secrets_storage_path: PathBuf,
+    master_key_path: PathBuf,
+    master_key: MasterKey,
 }
 
 impl FilesystemSecretsController {
-    pub fn new(secrets_storage_path: PathBuf) -> Self {
-        Self {
+    /// Opens the secrets stored in `secrets_storage_path`, which are
+    /// encrypted with the master key stored in `master_key_path`.
+    ///
+    /// If the master key file does not exist, a new master key is generated,
+    /// unless there are already encrypted secrets that would be unreadable
+    /// without the original key. Any batch of operations or master key
+    /// rotation that was interrupted by a crash is completed, and any
+    /// secrets that were stored in plaintext by earlier versions are
+    /// encrypted.
+    pub fn new(secrets_storage_path: PathBuf, master_key_path: PathBuf) -> Result<Self, Error> {
+        let master_key = match MasterKey::load(&master_key_path)? {
+            Some(master_key) => master_key,
+            None => {
+                let controller = FilesystemSecretsController {
+                    secrets_storage_path: secrets_storage_path.clone(),
+                    master_key_path: master_key_path.clone(),
+                    master_key: MasterKey::generate()?,
+                };
+                controller.recover()?;
+                for name in controller.secret_names()? {
+                    if is_encrypted(&controller.read_file(&name)?) {
+                        bail!(
+                            "master key file {} does not exist, but secret {} is encrypted",
+                            master_key_path.display(),
+                            name
+                        );
+                    }
+                }
+                controller.master_key.store(&master_key_path)?;
+                controller.master_key
+            }
+        };
+        let mut controller = FilesystemSecretsController {
             secrets_storage_path,
+            master_key_path,
+            master_key,
+        };
+        controller.recover()?;
+        if let Some(next_master_key) = MasterKey::load(&controller.next_master_key_path())? {
+            controller.finish_rotation(next_master_key)?;
+        }
+        controller.encrypt_plaintext_secrets()?;
+        Ok(controller)
+    }
+
+    /// Reads the decrypted contents of the secret with the specified ID.
+    pub fn read(&self, id: GlobalId) -> Result<Vec<u8>, Error> {
+        let name = id.to_string();
+        self.decrypt(&[&self.master_key], &name, &self.read_file(&name)?)
+    }
+
+    /// Replaces the master key with a newly generated key, and re-encrypts
+    /// every secret with the new master key.
+    ///
+    /// The new key is durably stored alongside the current key before any
+    /// secret is re-encrypted, and replaces the current key only once every
+    /// secret has been re-encrypted. A rotation that is interrupted by a crash
+    /// is completed when the controller is next opened.
+    pub fn rotate_master_key(&mut self) -> Result<(), Error> {
+        let next_master_key = MasterKey::generate()?;
+        next_master_key.store(&self.next_master_key_path())?;
+        self.finish_rotation(next_master_key)
+    }
+
+    /// Re-encrypts every secret that is not already encrypted with
+    /// `next_master_key`, then makes `next_master_key` the master key.
+    fn finish_rotation(&mut self, next_master_key: MasterKey) -> Result<(), Error> {
+        for name in self.secret_names()? {
+            let bytes = self.read_file(&name)?;
+            if key_id(&bytes) == Some(next_master_key.id) {
+                continue;
+            }
+            let contents = self.decrypt(&[&self.master_key], &name, &bytes)?;
+            let bytes = encrypt(&next_master_key, &name, &contents)?;
+            write_file_durably(&self.secret_path(&name), &bytes)?;
+        }
+        fs::rename(self.next_master_key_path(), &self.master_key_path)?;
+        sync_parent_dir(&self.master_key_path)?;
+        self.master_key = next_master_key;
+        Ok(())
+    }
+
+    /// Encrypts any secrets that were stored in plaintext by earlier
+    /// versions.
+    fn encrypt_plaintext_secrets(&self) -> Result<(), Error> {
+        for name in self.secret_names()? {
+            let bytes = self.read_file(&name)?;
+            if !is_encrypted(&bytes) {
+                let bytes = encrypt(&self.master_key, &name, &bytes)?;
+                write_file_durably(&self.secret_path(&name), &bytes)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Completes the batch of operations recorded in the journal, if any, and
+    /// removes the temporary files of any batch that was never committed.
+    fn recover(&self) -> Result<(), Error> {
+        match fs::read_to_string(self.journal_path()) {
+            Ok(journal) => {
+                let ops = journal
+                    .lines()
+                    .map(JournalOp::parse)
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.apply_journal(&ops)?;
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => (),
+            Err(e) => return Err(e.into()),
+        }
+        for entry in fs::read_dir(&self.secrets_storage_path)? {
+            let path = entry?.path();
+            if path.to_string_lossy().ends_with(TEMP_SUFFIX) {
+                fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Renames the temporary files of the secrets ensured by `ops` into place
+    /// and removes the secrets deleted by `ops`, then removes the journal.
+    ///
+    /// Applying the same operations again is harmless, so this can be used to
+    /// replay a journal that was only partially applied.
+    fn apply_journal(&self, ops: &[JournalOp]) -> Result<(), Error> {
+        for op in ops {
+            match op {
+                JournalOp::Ensure(name) => {
+                    let path = self.secret_path(name);
+                    ignore_not_found(fs::rename(temp_path(&path), path))?;
+                }
+                JournalOp::Delete(name) => {
+                    ignore_not_found(fs::remove_file(self.secret_path(name)))?;
+                }
+            }
+        }
+        sync_dir(&self.secrets_storage_path)?;
+        fs::remove_file(self.journal_path())?;
+        sync_dir(&self.secrets_storage_path)?;
+        Ok(())
+    }
+
+    /// Stages the new contents of every secret ensured by `ops` in a
+    /// temporary file, recording the path of each file in `staged`, then
+    /// durably writes the journal of the batch.
+    fn commit(&self, ops: &[SecretOp], staged: &mut Vec<PathBuf>) -> Result<Vec<JournalOp>, Error> {
+        let mut journal_ops: Vec<JournalOp> = vec![];
+        for op in ops {
+            let journal_op = match op {
+                SecretOp::Ensure { id, contents } => {
+                    let name = id.to_string();
+                    let path = temp_path(&self.secret_path(&name));
+                    staged.push(path.clone());
+                    write_file(&path, &encrypt(&self.master_key, &name, contents)?)?;
+                    JournalOp::Ensure(name)
+                }
+                SecretOp::Delete { id } => JournalOp::Delete(id.to_string()),
+            };
+            if journal_ops.iter().any(|op| op.name() == journal_op.name()) {
+                bail!(
+                    "secrets controller does not support multiple operations on secret {} in a single batch",
+                    journal_op.name()
+                );
+            }
+            journal_ops.push(journal_op);
+        }
+        let journal: String = journal_ops.iter().map(|op| format!("{}\n", op)).collect();
+        write_file_durably(&self.journal_path(), journal.as_bytes())?;
+        Ok(journal_ops)
+    }
+
+    /// Returns the names of all stored secrets.
+    fn secret_names(&self) -> Result<Vec<String>, Error> {
+        let mut names = vec![];
+        for entry in fs::read_dir(&self.secrets_storage_path)? {
+            let entry = entry?;
+            let path = entry.path();
+            if path == self.journal_path()
+                || path == self.master_key_path
+                || path == self.next_master_key_path()
+                || path.to_string_lossy().ends_with(TEMP_SUFFIX)
+            {
+                continue;
+            }
+            match entry.file_name().into_string() {
+                Ok(name) => names.push(name),
+                Err(name) => bail!("invalid secret file name: {:?}", name),
+            }
         }
+        Ok(names)
+    }
+
+    /// Decrypts the secret `name`, whose encrypted file contents are `bytes`,
+    /// with whichever of `master_keys` it was encrypted with.
+    fn decrypt(
+        &self,
+        master_keys: &[&MasterKey],
+        name: &str,
+        bytes: &[u8],
+    ) -> Result<Vec<u8>, Error> {
+        let secret = EncryptedSecret::parse(bytes)
+            .ok_or_else(|| anyhow!("secret {} is not a valid encrypted secret", name))?;
+        let master_key = master_keys
+            .iter()
+            .find(|key| key.id == secret.key_id)
+            .ok_or_else(|| anyhow!("secret {} is encrypted with an unknown master key", name))?;
+        let aad = associated_data(secret.header, name);
+        let data_key = open(
+            &master_key.key,
+            secret.data_key_nonce,
+            &aad,
+            secret.data_key,
+            secret.data_key_tag,
+        )
+        .with_context(|| format!("decrypting data key of secret {}", name))?;
+        open(&data_key, secret.nonce, &aad, secret.ciphertext, secret.tag)
+            .with_context(|| format!("decrypting secret {}", name))
+    }
+
+    fn read_file(&self, name: &str) -> Result<Vec<u8>, Error> {
+        let path = self.secret_path(name);
+        fs::read(&path).with_context(|| format!("reading secret file {}", path.display()))
+    }
+
+    fn secret_path(&self, name: &str) -> PathBuf {
+        self.secrets_storage_path.join(name)
+    }
+
+    fn journal_path(&self) -> PathBuf {
+        self.secrets_storage_path.join(JOURNAL_NAME)
+    }
+
+    fn next_master_key_path(&self) -> PathBuf {
+        with_suffix(&self.master_key_path, NEXT_KEY_SUFFIX)
     }
 }
 
 #[async_trait]
 impl SecretsController for FilesystemSecretsController {
     async fn apply(&mut self, ops: Vec<SecretOp>) -> Result<(), Error> {
-        // The filesystem controller can not execute multiple FS operations in a single atomic txn.
-        // Deletes are a special case. They are being executed as a post-commit event and once
-        // the coordinator reaches that code, the secrets can no longer be reached by any reader.
-        // This behavior is atomic in nature and the FS operations do not have to be.
-        // On the other hand, Ensure has to be atomic, since a concurrent reader should not
-        // see torn FS transactions.
-        // Hence we enforce the limit of exactly 1 ensure (update/create) operation per txn
-
-        for op in ops.iter() {
-            match op {
-                SecretOp::Ensure { id, contents } => {
-                    if ops.len() > 1 {
-                        bail!("secrets controller does not support creating multiple secrets atomically")
-                    }
-                    let file_path = self.secrets_storage_path.join(format!("{}", id));
-                    let mut file = OpenOptions::new()
-                        .mode(0o600)
-                        .create(true)
-                        .write(true)
-                        .truncate(true)
-                        .open(file_path)?;
-                    file.write_all(contents)?;
-                    file.sync_all()?;
-                }
-                SecretOp::Delete { id } => {
-                    fs::remove_file(self.secrets_storage_path.join(format!("{}", id)))?;
+        // Until the journal is written, nothing that a reader can observe has
+        // changed, and a failure only requires cleaning up the staged files.
+        let mut staged = vec![];
+        let journal_ops = match self.commit(&ops, &mut staged) {
+            Ok(journal_ops) => journal_ops,
+            Err(e) => {
+                for path in staged {
+                    let _ = fs::remove_file(path);
                 }
+                return Err(e);
             }
+        };
+
+        // The batch is now committed. If applying the journal fails, it is
+        // replayed when the controller is next opened.
+        self.apply_journal(&journal_ops)
+    }
+}
+
+/// An operation recorded in the journal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum JournalOp {
+    /// Rename the temporary file of the named secret into place.
+    Ensure(String),
+    /// Remove the named secret.
+    Delete(String),
+}
+
+impl JournalOp {
+    fn name(&self) -> &str {
+        match self {
+            JournalOp::Ensure(name) | JournalOp::Delete(name) => name,
         }
+    }
+
+    fn parse(line: &str) -> Result<JournalOp, Error> {
+        match line.split_once(' ') {
+            Some(("ensure", name)) => Ok(JournalOp::Ensure(name.into())),
+            Some(("delete", name)) => Ok(JournalOp::Delete(name.into())),
+            _ => bail!("invalid secrets journal entry: {}", line),
+        }
+    }
+}
+
+impl std::fmt::Display for JournalOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            JournalOp::Ensure(name) => write!(f, "ensure {}", name),
+            JournalOp::Delete(name) => write!(f, "delete {}", name),
+        }
+    }
+}
+
+/// A key that encrypts the data keys of secrets.
+struct MasterKey {
+    /// Identifies the key in the files it encrypts, so that the right key can
+    /// be chosen during a rotation. Derived from a hash of the key.
+    id: [u8; KEY_ID_LEN],
+    key: [u8; KEY_LEN],
+}
+
+impl MasterKey {
+    fn generate() -> Result<MasterKey, Error> {
+        let mut key = [0; KEY_LEN];
+        rand_bytes(&mut key)?;
+        Ok(MasterKey::from_bytes(key))
+    }
+
+    fn from_bytes(key: [u8; KEY_LEN]) -> MasterKey {
+        let mut id = [0; KEY_ID_LEN];
+        id.copy_from_slice(&sha256(&key)[..KEY_ID_LEN]);
+        MasterKey { id, key }
+    }
+
+    /// Loads the master key stored in `path`, if it exists.
+    fn load(path: &Path) -> Result<Option<MasterKey>, Error> {
+        let bytes = match fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(
+                    Error::new(e).context(format!("reading master key file {}", path.display()))
+                )
+            }
+        };
+        let key = bytes.try_into().map_err(|_| {
+            anyhow!(
+                "master key file {} must contain exactly {} bytes",
+                path.display(),
+                KEY_LEN
+            )
+        })?;
+        Ok(Some(MasterKey::from_bytes(key)))
+    }
+
+    fn store(&self, path: &Path) -> Result<(), Error> {
+        write_file_durably(path, &self.key)
+            .with_context(|| format!("writing master key file {}", path.display()))
+    }
+}
+
+/// The parts of an encrypted secret file.
+///
+/// The file consists of a header, holding the format version and the ID of
+/// the master key; the data key encrypted with the master key; and the
+/// contents of the secret encrypted with the data key.
+struct EncryptedSecret<'a> {
+    header: &'a [u8],
+    key_id: [u8; KEY_ID_LEN],
+    data_key_nonce: &'a [u8],
+    data_key_tag: &'a [u8],
+    data_key: &'a [u8],
+    nonce: &'a [u8],
+    tag: &'a [u8],
+    ciphertext: &'a [u8],
+}
+
+impl<'a> EncryptedSecret<'a> {
+    fn parse(bytes: &'a [u8]) -> Option<EncryptedSecret<'a>> {
+        if !is_encrypted(bytes) || bytes.get(MAGIC.len()) != Some(&FORMAT_VERSION) {
+            return None;
+        }
+        let (header, rest) = split(bytes, HEADER_LEN)?;
+        let (data_key_nonce, rest) = split(rest, NONCE_LEN)?;
+        let (data_key_tag, rest) = split(rest, TAG_LEN)?;
+        let (data_key, rest) = split(rest, KEY_LEN)?;
+        let (nonce, rest) = split(rest, NONCE_LEN)?;
+        let (tag, ciphertext) = split(rest, TAG_LEN)?;
+        Some(EncryptedSecret {
+            header,
+            key_id: key_id(bytes)?,
+            data_key_nonce,
+            data_key_tag,
+            data_key,
+            nonce,
+            tag,
+            ciphertext,
+        })
+    }
+}
+
+/// Encrypts the contents of the secret `name` with a new data key, which is
+/// itself encrypted with `master_key`.
+fn encrypt(master_key: &MasterKey, name: &str, contents: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut data_key = [0; KEY_LEN];
+    rand_bytes(&mut data_key)?;
+
+    let mut bytes =
+        Vec::with_capacity(HEADER_LEN + 2 * (NONCE_LEN + TAG_LEN) + KEY_LEN + contents.len());
+    bytes.extend(MAGIC);
+    bytes.push(FORMAT_VERSION);
+    bytes.extend(master_key.id);
+    let aad = associated_data(&bytes, name);
+    for (key, plaintext) in [(&master_key.key, &data_key[..]), (&data_key, contents)] {
+        let mut nonce = [0; NONCE_LEN];
+        rand_bytes(&mut nonce)?;
+        let mut tag = [0; TAG_LEN];
+        let ciphertext = encrypt_aead(
+            Cipher::aes_256_gcm(),
+            key,
+            Some(&nonce),
+            &aad,
+            plaintext,
+            &mut tag,
+        )?;
+        bytes.extend(nonce);
+        bytes.extend(tag);
+        bytes.extend(ciphertext);
+    }
+    Ok(bytes)
+}
+
+fn open(
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    ciphertext: &[u8],
+    tag: &[u8],
+) -> Result<Vec<u8>, Error> {
+    decrypt_aead(
+        Cipher::aes_256_gcm(),
+        key,
+        Some(nonce),
+        aad,
+        ciphertext,
+        tag,
+    )
+    .map_err(|_| anyhow!("authentication failed"))
+}
+
+/// Returns the data authenticated along with both layers of encryption of the
+/// secret `name`.
+fn associated_data(header: &[u8], name: &str) -> Vec<u8> {
+    let mut aad = header.to_vec();
+    aad.extend(name.as_bytes());
+    aad
+}
+
+/// Reports whether `bytes` are the contents of an encrypted secret file.
+fn is_encrypted(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+/// Returns the ID of the master key that encrypted the secret file `bytes`.
+fn key_id(bytes: &[u8]) -> Option<[u8; KEY_ID_LEN]> {
+    if !is_encrypted(bytes) {
+        return None;
+    }
+    bytes.get(MAGIC.len() + 1..HEADER_LEN)?.try_into().ok()
+}
+
+fn split(bytes: &[u8], len: usize) -> Option<(&[u8], &[u8])> {
+    (bytes.len() >= len).then(|| bytes.split_at(len))
+}
+
+/// Writes `contents` to a new file at `path` that only its owner can access,
+/// and flushes it to disk.
+fn write_file(path: &Path, contents: &[u8]) -> Result<(), Error> {
+    let mut file = OpenOptions::new()
+        .mode(0o600)
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open(path)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    Ok(())
+}
+
+/// Atomically replaces the file at `path` with one containing `contents`.
+fn write_file_durably(path: &Path, contents: &[u8]) -> Result<(), Error> {
+    let temp_path = temp_path(path);
+    write_file(&temp_path, contents)?;
+    fs::rename(&temp_path, path)?;
+    sync_parent_dir(path)
+}
+
+fn sync_dir(path: &Path) -> Result<(), Error> {
+    File::open(path)?.sync_all()?;
+    Ok(())
+}
+
+fn sync_parent_dir(path: &Path) -> Result<(), Error> {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => sync_dir(parent),
+        _ => sync_dir(Path::new(".")),
+    }
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    with_suffix(path, TEMP_SUFFIX)
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = OsString::from(path);
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+fn ignore_not_found(result: Result<(), io::Error>) -> Result<(), io::Error> {
+    match result {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use tempfile::TempDir;
+
+    use mz_repr::GlobalId;
+    use mz_secrets::{SecretOp, SecretsController};
+
+    use super::{FilesystemSecretsController, JournalOp, JOURNAL_NAME};
+
+    fn paths(dir: &TempDir) -> (PathBuf, PathBuf) {
+        let secrets_path = dir.path().join("secrets");
+        fs::create_dir_all(&secrets_path).unwrap();
+        (secrets_path, dir.path().join("secrets.key"))
+    }
+
+    fn ensure(id: u64, contents: &str) -> SecretOp {
+        SecretOp::Ensure {
+            id: GlobalId::User(id),
+            contents: contents.as_bytes().to_vec(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_at_rest() {
+        let dir = TempDir::new().unwrap();
+        let (secrets_path, key_path) = paths(&dir);
+        let mut controller =
+            FilesystemSecretsController::new(secrets_path.clone(), key_path.clone()).unwrap();
+        controller
+            .apply(vec![ensure(1, "s3cret"), ensure(2, "tops3cret")])
+            .await
+            .unwrap();
+
+        assert_eq!(controller.read(GlobalId::User(1)).unwrap(), b"s3cret");
+        assert_eq!(controller.read(GlobalId::User(2)).unwrap(), b"tops3cret");
+        let on_disk = fs::read(secrets_path.join("u1")).unwrap();
+        assert!(!on_disk.windows(6).any(|w| w == b"s3cret"));
+        let mut names: Vec<_> = fs::read_dir(&secrets_path)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["u1", "u2"]);
+
+        // The secrets can be read after reopening with the same key...
+        let controller =
+            FilesystemSecretsController::new(secrets_path.clone(), key_path.clone()).unwrap();
+        assert_eq!(controller.read(GlobalId::User(1)).unwrap(), b"s3cret");
+
+        // ...but the key cannot be silently replaced.
+        fs::remove_file(&key_path).unwrap();
+        assert!(FilesystemSecretsController::new(secrets_path, key_path).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_swapped_files_rejected() {
+        let dir = TempDir::new().unwrap();
+        let (secrets_path, key_path) = paths(&dir);
+        let mut controller =
+            FilesystemSecretsController::new(secrets_path.clone(), key_path).unwrap();
+        controller
+            .apply(vec![ensure(1, "a"), ensure(2, "b")])
+            .await
+            .unwrap();
+        fs::copy(secrets_path.join("u1"), secrets_path.join("u2")).unwrap();
+        assert!(controller.read(GlobalId::User(2)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rotation() {
+        let dir = TempDir::new().unwrap();
+        let (secrets_path, key_path) = paths(&dir);
+        let mut controller =
+            FilesystemSecretsController::new(secrets_path.clone(), key_path.clone()).unwrap();
+        controller
+            .apply(vec![ensure(1, "a"), ensure(2, "b")])
+            .await
+            .unwrap();
+        let old_key = fs::read(&key_path).unwrap();
+        let old_secret = fs::read(secrets_path.join("u1")).unwrap();
+
+        controller.rotate_master_key().unwrap();
+        assert_ne!(fs::read(&key_path).unwrap(), old_key);
+        assert_ne!(fs::read(secrets_path.join("u1")).unwrap(), old_secret);
+        assert_eq!(controller.read(GlobalId::User(1)).unwrap(), b"a");
+        assert_eq!(controller.read(GlobalId::User(2)).unwrap(), b"b");
+
+        // Simulate a crash partway through a rotation, after the new key was
+        // stored but before any secret was re-encrypted.
+        let next_key_path = controller.next_master_key_path();
+        let rotated_key = fs::read(&key_path).unwrap();
+        fs::write(&next_key_path, [7; super::KEY_LEN]).unwrap();
+        let controller =
+            FilesystemSecretsController::new(secrets_path.clone(), key_path.clone()).unwrap();
+        assert!(!next_key_path.exists());
+        assert_eq!(fs::read(&key_path).unwrap(), [7; super::KEY_LEN]);
+        assert_ne!(fs::read(&key_path).unwrap(), rotated_key);
+        assert_eq!(controller.read(GlobalId::User(1)).unwrap(), b"a");
+        assert_eq!(controller.read(GlobalId::User(2)).unwrap(), b"b");
+    }
+
+    #[tokio::test]
+    async fn test_plaintext_migration() {
+        let dir = TempDir::new().unwrap();
+        let (secrets_path, key_path) = paths(&dir);
+        fs::write(secrets_path.join("u1"), "s3cret").unwrap();
+        let controller = FilesystemSecretsController::new(secrets_path.clone(), key_path).unwrap();
+        assert_ne!(fs::read(secrets_path.join("u1")).unwrap(), b"s3cret");
+        assert_eq!(controller.read(GlobalId::User(1)).unwrap(), b"s3cret");
+    }
+
+    #[tokio::test]
+    async fn test_journal_recovery() {
+        let dir = TempDir::new().unwrap();
+        let (secrets_path, key_path) = paths(&dir);
+        let mut controller =
+            FilesystemSecretsController::new(secrets_path.clone(), key_path.clone()).unwrap();
+        controller
+            .apply(vec![ensure(1, "a"), ensure(2, "b")])
+            .await
+            .unwrap();
+
+        // Stage a batch that updates u1 and deletes u2, and simulate a crash
+        // after the journal was written but before it was applied.
+        let staged = super::encrypt(&controller.master_key, "u1", b"c").unwrap();
+        fs::write(secrets_path.join("u1.tmp"), staged).unwrap();
+        let journal = format!(
+            "{}\n{}\n",
+            JournalOp::Ensure("u1".into()),
+            JournalOp::Delete("u2".into())
+        );
+        fs::write(secrets_path.join(JOURNAL_NAME), journal).unwrap();
+        // A batch staged without a journal was never committed.
+        fs::write(secrets_path.join("u3.tmp"), "uncommitted").unwrap();
+
+        let controller = FilesystemSecretsController::new(secrets_path.clone(), key_path).unwrap();
+        assert_eq!(controller.read(GlobalId::User(1)).unwrap(), b"c");
+        assert!(controller.read(GlobalId::User(2)).is_err());
+        let names: Vec<_> = fs::read_dir(&secrets_path)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        assert_eq!(names, vec!["u1"]);
+    }
 
-        return Ok(());
+    #[tokio::test]
+    async fn test_multiple_ops_on_one_secret_rejected() {
+        let dir = TempDir::new().unwrap();
+        let (secrets_path, key_path) = paths(&dir);
+        let mut controller =
+            FilesystemSecretsController::new(secrets_path.clone(), key_path).unwrap();
+        let res = controller
+            .apply(vec![
+                ensure(1, "a"),
+                SecretOp::Delete {
+                    id: GlobalId::User(1),
+                },
+            ])
+            .await;
+        assert!(res.is_err());
+        assert_eq!(fs::read_dir(&secrets_path).unwrap().count(), 0);
     }
 }
@@ -315,6 +315,10 @@ impl_display_t!(CreateSourceFormat);
 pub enum Format<T: AstInfo> {
     Bytes,
     Avro(AvroSchema<T>),
+    /// Avro object container files, read with the given reader schema.
+    AvroOcf(Schema),
+    /// Parquet files, whose columns are described by the given Avro schema.
+    Parquet(Schema),
     Protobuf(ProtobufSchema<T>),
     Regex(String),
     Csv {
@@ -429,6 +433,26 @@ impl AstDisplay for Envelope {
 }
 impl_display!(Envelope);
 
+/// The action to take for records that fail to decode, as specified by the
+/// `ON DECODE ERROR` clause of `CREATE SOURCE`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OnDecodeError {
+    Fail,
+    Skip,
+    Divert,
+}
+
+impl AstDisplay for OnDecodeError {
+    fn fmt<W: fmt::Write>(&self, f: &mut AstFormatter<W>) {
+        match self {
+            Self::Fail => f.write_str("FAIL"),
+            Self::Skip => f.write_str("SKIP"),
+            Self::Divert => f.write_str("DIVERT"),
+        }
+    }
+}
+impl_display!(OnDecodeError);
+
 impl<T: AstInfo> AstDisplay for Format<T> {
     fn fmt<W: fmt::Write>(&self, f: &mut AstFormatter<W>) {
         match self {
@@ -437,6 +461,14 @@ impl<T: AstInfo> AstDisplay for Format<T> {
                 f.write_str("AVRO ");
                 f.write_node(inner);
             }
+            Self::AvroOcf(schema) => {
+                f.write_str("AVRO OCF USING ");
+                f.write_node(schema);
+            }
+            Self::Parquet(schema) => {
+                f.write_str("PARQUET USING ");
+                f.write_node(schema);
+            }
             Self::Protobuf(inner) => {
                 f.write_str("PROTOBUF ");
                 f.write_node(inner);
@@ -465,20 +497,43 @@ impl_display_t!(Format);
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Compression {
+    Bzip2,
     Gzip,
+    Zstd,
     None,
 }
 
 impl AstDisplay for Compression {
     fn fmt<W: fmt::Write>(&self, f: &mut AstFormatter<W>) {
         match self {
+            Self::Bzip2 => f.write_str("BZIP2"),
             Self::Gzip => f.write_str("GZIP"),
+            Self::Zstd => f.write_str("ZSTD"),
             Self::None => f.write_str("NONE"),
         }
     }
 }
 impl_display!(Compression);
 
+/// The kinds of data a `LOAD GENERATOR` source can produce.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LoadGenerator {
+    Counter,
+    Auction,
+    Tpch,
+}
+
+impl AstDisplay for LoadGenerator {
+    fn fmt<W: fmt::Write>(&self, f: &mut AstFormatter<W>) {
+        match self {
+            Self::Counter => f.write_str("COUNTER"),
+            Self::Auction => f.write_str("AUCTION"),
+            Self::Tpch => f.write_str("TPCH"),
+        }
+    }
+}
+impl_display!(LoadGenerator);
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum DbzMode {
     /// `ENVELOPE DEBEZIUM` with no suffix
@@ -561,10 +616,42 @@ pub enum KafkaConnector {
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct KafkaSourceConnector {
     pub connector: KafkaConnector,
-    pub topic: String,
+    pub topic: KafkaTopic,
     pub key: Option<Vec<Ident>>,
 }
 
+/// The topics that a Kafka source reads from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum KafkaTopic {
+    /// `TOPIC 'a', 'b'`
+    Names(Vec<String>),
+    /// `TOPIC PATTERN 'tenant-.*'`
+    Pattern(String),
+}
+
+impl AstDisplay for KafkaTopic {
+    fn fmt<W: fmt::Write>(&self, f: &mut AstFormatter<W>) {
+        match self {
+            KafkaTopic::Names(names) => {
+                for (i, name) in names.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ");
+                    }
+                    f.write_str("'");
+                    f.write_node(&display::escape_single_quote_string(name));
+                    f.write_str("'");
+                }
+            }
+            KafkaTopic::Pattern(pattern) => {
+                f.write_str("PATTERN '");
+                f.write_node(&display::escape_single_quote_string(pattern));
+                f.write_str("'");
+            }
+        }
+    }
+}
+impl_display!(KafkaTopic);
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, EnumKind)]
 #[enum_kind(SourceConnectorType)]
 pub enum CreateSourceConnector<T: AstInfo> {
@@ -601,6 +688,21 @@ pub enum CreateSourceConnector<T: AstInfo> {
         collection_id: String,
         columns: Vec<ColumnDef<T>>,
     },
+    LoadGenerator {
+        generator: LoadGenerator,
+        /// The argument to the `TABLE` clause, for generators that produce
+        /// more than one table
+        table: Option<Ident>,
+        /// The argument to the `TICK INTERVAL` clause: `TICK INTERVAL '1s'`
+        tick_interval: Option<String>,
+        /// The argument to the `SCALE FACTOR` clause: `SCALE FACTOR 0.01`
+        scale_factor: Option<String>,
+    },
+    /// The records that failed to decode in a source created with
+    /// `ON DECODE ERROR DIVERT`
+    DecodeErrors {
+        source: T::ObjectName,
+    },
 }
 
 impl<T: AstInfo> AstDisplay for CreateSourceConnector<T> {
@@ -623,9 +725,8 @@ impl<T: AstInfo> AstDisplay for CreateSourceConnector<T> {
                         f.write_node(connector);
                     }
                 }
-                f.write_str(" TOPIC '");
-                f.write_node(&display::escape_single_quote_string(topic));
-                f.write_str("'");
+                f.write_str(" TOPIC ");
+                f.write_node(topic);
                 if let Some(key) = key.as_ref() {
                     f.write_str(" KEY (");
                     f.write_node(&display::comma_separated(&key));
@@ -701,6 +802,32 @@ impl<T: AstInfo> AstDisplay for CreateSourceConnector<T> {
                 f.write_node(&display::comma_separated(columns));
                 f.write_str(")");
             }
+            CreateSourceConnector::LoadGenerator {
+                generator,
+                table,
+                tick_interval,
+                scale_factor,
+            } => {
+                f.write_str("LOAD GENERATOR ");
+                f.write_node(generator);
+                if let Some(table) = table {
+                    f.write_str(" TABLE ");
+                    f.write_node(table);
+                }
+                if let Some(tick_interval) = tick_interval {
+                    f.write_str(" TICK INTERVAL '");
+                    f.write_node(&display::escape_single_quote_string(tick_interval));
+                    f.write_str("'");
+                }
+                if let Some(scale_factor) = scale_factor {
+                    f.write_str(" SCALE FACTOR ");
+                    f.write_str(scale_factor);
+                }
+            }
+            CreateSourceConnector::DecodeErrors { source } => {
+                f.write_str("DECODE ERRORS OF ");
+                f.write_node(source);
+            }
         }
     }
 }
@@ -734,9 +861,8 @@ impl<T: AstInfo> AstDisplay for CreateSinkConnector<T> {
                 f.write_str("KAFKA BROKER '");
                 f.write_node(&display::escape_single_quote_string(broker));
                 f.write_str("'");
-                f.write_str(" TOPIC '");
-                f.write_node(&display::escape_single_quote_string(topic));
-                f.write_str("'");
+                f.write_str(" TOPIC ");
+                f.write_node(topic);
                 if let Some(key) = key.as_ref() {
                     f.write_node(key);
                 }
@@ -61,6 +61,8 @@ pub enum Statement<T: AstInfo> {
     DropRoles(DropRolesStatement),
     DropClusters(DropClustersStatement),
     DropClusterReplicas(DropClusterReplicasStatement),
+    GrantPrivileges(GrantPrivilegesStatement<T>),
+    RevokePrivileges(RevokePrivilegesStatement<T>),
     SetVariable(SetVariableStatement),
     ShowDatabases(ShowDatabasesStatement<T>),
     ShowSchemas(ShowSchemasStatement<T>),
@@ -121,6 +123,8 @@ impl<T: AstInfo> AstDisplay for Statement<T> {
             Statement::DropRoles(stmt) => f.write_node(stmt),
             Statement::DropClusters(stmt) => f.write_node(stmt),
             Statement::DropClusterReplicas(stmt) => f.write_node(stmt),
+            Statement::GrantPrivileges(stmt) => f.write_node(stmt),
+            Statement::RevokePrivileges(stmt) => f.write_node(stmt),
             Statement::SetVariable(stmt) => f.write_node(stmt),
             Statement::ShowDatabases(stmt) => f.write_node(stmt),
             Statement::ShowSchemas(stmt) => f.write_node(stmt),
@@ -414,6 +418,7 @@ pub struct CreateSourceStatement<T: AstInfo> {
     pub include_metadata: Vec<SourceIncludeMetadata>,
     pub format: CreateSourceFormat<T>,
     pub envelope: Option<Envelope>,
+    pub on_decode_error: Option<OnDecodeError>,
     pub if_not_exists: bool,
     pub materialized: bool,
     pub key_constraint: Option<KeyConstraint>,
@@ -465,6 +470,11 @@ impl<T: AstInfo> AstDisplay for CreateSourceStatement<T> {
                 f.write_node(envelope);
             }
         }
+
+        if let Some(on_decode_error) = &self.on_decode_error {
+            f.write_str(" ON DECODE ERROR ");
+            f.write_node(on_decode_error);
+        }
     }
 }
 impl_display_t!(CreateSourceStatement);
@@ -807,6 +817,8 @@ pub enum CreateRoleOption {
     Login,
     /// The `NOLOGIN` option.
     NoLogin,
+    /// The `PASSWORD` option. `None` indicates `PASSWORD NULL`.
+    Password(Option<String>),
 }
 
 impl AstDisplay for CreateRoleOption {
@@ -816,6 +828,12 @@ impl AstDisplay for CreateRoleOption {
             CreateRoleOption::NoSuperUser => f.write_str("NOSUPERUSER"),
             CreateRoleOption::Login => f.write_str("LOGIN"),
             CreateRoleOption::NoLogin => f.write_str("NOLOGIN"),
+            CreateRoleOption::Password(None) => f.write_str("PASSWORD NULL"),
+            CreateRoleOption::Password(Some(password)) => {
+                f.write_str("PASSWORD '");
+                f.write_node(&display::escape_single_quote_string(password));
+                f.write_str("'");
+            }
         }
     }
 }
@@ -1292,6 +1310,141 @@ impl AstDisplay for DropClusterReplicasStatement {
 }
 impl_display!(DropClusterReplicasStatement);
 
+/// `GRANT ... ON ... TO ...`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GrantPrivilegesStatement<T: AstInfo> {
+    /// The privileges being granted.
+    pub privileges: PrivilegeSpecification,
+    /// The objects on which the privileges are granted.
+    pub objects: PrivilegeObjects<T>,
+    /// The roles that will receive the privileges.
+    pub roles: Vec<Ident>,
+}
+
+impl<T: AstInfo> AstDisplay for GrantPrivilegesStatement<T> {
+    fn fmt<W: fmt::Write>(&self, f: &mut AstFormatter<W>) {
+        f.write_str("GRANT ");
+        f.write_node(&self.privileges);
+        f.write_str(" ON ");
+        f.write_node(&self.objects);
+        f.write_str(" TO ");
+        f.write_node(&display::comma_separated(&self.roles));
+    }
+}
+impl_display_t!(GrantPrivilegesStatement);
+
+/// `REVOKE ... ON ... FROM ...`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RevokePrivilegesStatement<T: AstInfo> {
+    /// The privileges being revoked.
+    pub privileges: PrivilegeSpecification,
+    /// The objects on which the privileges are revoked.
+    pub objects: PrivilegeObjects<T>,
+    /// The roles that will lose the privileges.
+    pub roles: Vec<Ident>,
+}
+
+impl<T: AstInfo> AstDisplay for RevokePrivilegesStatement<T> {
+    fn fmt<W: fmt::Write>(&self, f: &mut AstFormatter<W>) {
+        f.write_str("REVOKE ");
+        f.write_node(&self.privileges);
+        f.write_str(" ON ");
+        f.write_node(&self.objects);
+        f.write_str(" FROM ");
+        f.write_node(&display::comma_separated(&self.roles));
+    }
+}
+impl_display_t!(RevokePrivilegesStatement);
+
+/// A privilege that can be granted to or revoked from a role.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Privilege {
+    Select,
+    Insert,
+    Update,
+    Delete,
+    Usage,
+    Create,
+}
+
+impl AstDisplay for Privilege {
+    fn fmt<W: fmt::Write>(&self, f: &mut AstFormatter<W>) {
+        f.write_str(match self {
+            Privilege::Select => "SELECT",
+            Privilege::Insert => "INSERT",
+            Privilege::Update => "UPDATE",
+            Privilege::Delete => "DELETE",
+            Privilege::Usage => "USAGE",
+            Privilege::Create => "CREATE",
+        })
+    }
+}
+impl_display!(Privilege);
+
+/// The privileges named in a [`GrantPrivilegesStatement`] or
+/// [`RevokePrivilegesStatement`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PrivilegeSpecification {
+    /// `ALL [PRIVILEGES]`
+    All,
+    /// An explicit list of privileges.
+    Privileges(Vec<Privilege>),
+}
+
+impl AstDisplay for PrivilegeSpecification {
+    fn fmt<W: fmt::Write>(&self, f: &mut AstFormatter<W>) {
+        match self {
+            PrivilegeSpecification::All => f.write_str("ALL"),
+            PrivilegeSpecification::Privileges(privileges) => {
+                f.write_node(&display::comma_separated(privileges))
+            }
+        }
+    }
+}
+impl_display!(PrivilegeSpecification);
+
+/// The objects named in a [`GrantPrivilegesStatement`] or
+/// [`RevokePrivilegesStatement`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PrivilegeObjects<T: AstInfo> {
+    /// `DATABASE <name>, ...`
+    Databases(Vec<UnresolvedDatabaseName>),
+    /// `SCHEMA <name>, ...`
+    Schemas(Vec<UnresolvedSchemaName>),
+    /// `{TABLE | VIEW | SOURCE | SINK | SECRET} <name>, ...`
+    Items {
+        object_type: ObjectType,
+        names: Vec<T::ObjectName>,
+    },
+    /// `CLUSTER <name>, ...`
+    Clusters(Vec<Ident>),
+}
+
+impl<T: AstInfo> AstDisplay for PrivilegeObjects<T> {
+    fn fmt<W: fmt::Write>(&self, f: &mut AstFormatter<W>) {
+        match self {
+            PrivilegeObjects::Databases(names) => {
+                f.write_str("DATABASE ");
+                f.write_node(&display::comma_separated(names));
+            }
+            PrivilegeObjects::Schemas(names) => {
+                f.write_str("SCHEMA ");
+                f.write_node(&display::comma_separated(names));
+            }
+            PrivilegeObjects::Items { object_type, names } => {
+                f.write_node(object_type);
+                f.write_str(" ");
+                f.write_node(&display::comma_separated(names));
+            }
+            PrivilegeObjects::Clusters(names) => {
+                f.write_str("CLUSTER ");
+                f.write_node(&display::comma_separated(names));
+            }
+        }
+    }
+}
+impl_display_t!(PrivilegeObjects);
+
 /// `SET <variable>`
 ///
 /// Note: this is not a standard SQL statement, but it is supported by at
@@ -1598,15 +1751,23 @@ impl AstDisplay for StartTransactionStatement {
 }
 impl_display!(StartTransactionStatement);
 
-/// `SET TRANSACTION ...`
+/// `SET TRANSACTION ...` or `SET SESSION CHARACTERISTICS AS TRANSACTION ...`
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct SetTransactionStatement {
+    /// Whether the modes apply only to the current transaction (`SET
+    /// TRANSACTION`), rather than to all subsequent transactions in the session
+    /// (`SET SESSION CHARACTERISTICS AS TRANSACTION`).
+    pub local: bool,
     pub modes: Vec<TransactionMode>,
 }
 
 impl AstDisplay for SetTransactionStatement {
     fn fmt<W: fmt::Write>(&self, f: &mut AstFormatter<W>) {
-        f.write_str("SET TRANSACTION");
+        if self.local {
+            f.write_str("SET TRANSACTION");
+        } else {
+            f.write_str("SET SESSION CHARACTERISTICS AS TRANSACTION");
+        }
         if !self.modes.is_empty() {
             f.write_str(" ");
             f.write_node(&display::comma_separated(&self.modes));
@@ -1698,6 +1859,7 @@ pub struct ExplainStatement<T: AstInfo> {
     pub stage: ExplainStage,
     pub explainee: Explainee<T>,
     pub options: ExplainOptions,
+    pub format: ExplainFormat,
 }
 
 impl<T: AstInfo> AstDisplay for ExplainStatement<T> {
@@ -1712,6 +1874,10 @@ impl<T: AstInfo> AstDisplay for ExplainStatement<T> {
             f.write_str("TYPED ");
         }
         f.write_node(&self.stage);
+        if self.format != ExplainFormat::Text {
+            f.write_str(" AS ");
+            f.write_node(&self.format);
+        }
         f.write_str(" FOR ");
         f.write_node(&self.explainee);
     }
@@ -1872,6 +2038,7 @@ pub enum TransactionIsolationLevel {
     ReadCommitted,
     RepeatableRead,
     Serializable,
+    StrictSerializable,
 }
 
 impl AstDisplay for TransactionIsolationLevel {
@@ -1882,6 +2049,7 @@ impl AstDisplay for TransactionIsolationLevel {
             ReadCommitted => "READ COMMITTED",
             RepeatableRead => "REPEATABLE READ",
             Serializable => "SERIALIZABLE",
+            StrictSerializable => "STRICT SERIALIZABLE",
         })
     }
 }
@@ -1937,6 +2105,9 @@ pub enum ExplainStage {
     PhysicalPlan,
     /// The dependent and selected timestamps
     Timestamp,
+    /// The render::plan::Plan, annotated with statistics gathered while
+    /// running it
+    Analyze,
 }
 
 impl AstDisplay for ExplainStage {
@@ -1949,11 +2120,31 @@ impl AstDisplay for ExplainStage {
             ExplainStage::OptimizedPlan => f.write_str("OPTIMIZED PLAN"),
             ExplainStage::PhysicalPlan => f.write_str("PHYSICAL PLAN"),
             ExplainStage::Timestamp => f.write_str("TIMESTAMP"),
+            ExplainStage::Analyze => f.write_str("ANALYZE"),
         }
     }
 }
 impl_display!(ExplainStage);
 
+/// Specifies the format of the output of [Statement::Explain]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExplainFormat {
+    /// Text meant for humans
+    Text,
+    /// A JSON document meant for programs
+    Json,
+}
+
+impl AstDisplay for ExplainFormat {
+    fn fmt<W: fmt::Write>(&self, f: &mut AstFormatter<W>) {
+        match self {
+            ExplainFormat::Text => f.write_str("TEXT"),
+            ExplainFormat::Json => f.write_str("JSON"),
+        }
+    }
+}
+impl_display!(ExplainFormat);
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Explainee<T: AstInfo> {
     View(T::ObjectName),
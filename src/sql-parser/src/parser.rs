@@ -238,6 +238,8 @@ impl<'a> Parser<'a> {
                 Token::Keyword(CREATE) => Ok(self.parse_create()?),
                 Token::Keyword(DISCARD) => Ok(self.parse_discard()?),
                 Token::Keyword(DROP) => Ok(self.parse_drop()?),
+                Token::Keyword(GRANT) => Ok(self.parse_grant()?),
+                Token::Keyword(REVOKE) => Ok(self.parse_revoke()?),
                 Token::Keyword(DELETE) => Ok(self.parse_delete()?),
                 Token::Keyword(INSERT) => Ok(self.parse_insert()?),
                 Token::Keyword(UPDATE) => Ok(self.parse_update()?),
@@ -1631,9 +1633,15 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_format(&mut self) -> Result<Format<Raw>, ParserError> {
-        let format = if self.parse_keyword(AVRO) {
+        let format = if self.parse_keywords(&[AVRO, OCF]) {
+            self.expect_keyword(USING)?;
+            Format::AvroOcf(self.parse_schema()?)
+        } else if self.parse_keyword(AVRO) {
             self.expect_keyword(USING)?;
             Format::Avro(self.parse_avro_schema()?)
+        } else if self.parse_keyword(PARQUET) {
+            self.expect_keyword(USING)?;
+            Format::Parquet(self.parse_schema()?)
         } else if self.parse_keyword(PROTOBUF) {
             Format::Protobuf(self.parse_protobuf_schema()?)
         } else if self.parse_keyword(REGEX) {
@@ -1669,7 +1677,7 @@ impl<'a> Parser<'a> {
         } else {
             return self.expected(
                 self.peek_pos(),
-                "AVRO, PROTOBUF, REGEX, CSV, JSON, TEXT, or BYTES",
+                "AVRO, PARQUET, PROTOBUF, REGEX, CSV, JSON, TEXT, or BYTES",
                 self.peek_token(),
             );
         };
@@ -1881,8 +1889,16 @@ impl<'a> Parser<'a> {
             Compression::None
         } else if self.parse_keyword(GZIP) {
             Compression::Gzip
+        } else if self.parse_keyword(BZIP2) {
+            Compression::Bzip2
+        } else if self.parse_keyword(ZSTD) {
+            Compression::Zstd
         } else {
-            return self.expected(self.peek_pos(), "NONE or GZIP", self.peek_token());
+            return self.expected(
+                self.peek_pos(),
+                "NONE, GZIP, BZIP2, or ZSTD",
+                self.peek_token(),
+            );
         };
         Ok(compression)
     }
@@ -1967,6 +1983,17 @@ impl<'a> Parser<'a> {
             None
         };
 
+        let on_decode_error = if self.parse_keywords(&[ON, DECODE, ERROR]) {
+            match self.expect_one_of_keywords(&[FAIL, SKIP, DIVERT])? {
+                FAIL => Some(OnDecodeError::Fail),
+                SKIP => Some(OnDecodeError::Skip),
+                DIVERT => Some(OnDecodeError::Divert),
+                _ => unreachable!(),
+            }
+        } else {
+            None
+        };
+
         Ok(Statement::CreateSource(CreateSourceStatement {
             name,
             col_names,
@@ -1975,6 +2002,7 @@ impl<'a> Parser<'a> {
             format,
             include_metadata,
             envelope,
+            on_decode_error,
             if_not_exists,
             materialized,
             key_constraint,
@@ -2077,7 +2105,52 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_create_source_connector(&mut self) -> Result<CreateSourceConnector<Raw>, ParserError> {
-        match self.expect_one_of_keywords(&[KAFKA, KINESIS, AVRO, S3, PERSIST, POSTGRES, PUBNUB])? {
+        match self.expect_one_of_keywords(&[
+            KAFKA, KINESIS, AVRO, S3, PERSIST, POSTGRES, PUBNUB, LOAD, DECODE,
+        ])? {
+            DECODE => {
+                // FROM DECODE ERRORS OF <source>
+                self.expect_keywords(&[ERRORS, OF])?;
+                let source = self.parse_raw_name()?;
+                Ok(CreateSourceConnector::DecodeErrors { source })
+            }
+            LOAD => {
+                // FROM LOAD GENERATOR (COUNTER | AUCTION | TPCH)
+                // (TABLE <name>)?
+                // (TICK INTERVAL '<interval>')?
+                // (SCALE FACTOR <number>)?
+                self.expect_keyword(GENERATOR)?;
+                let generator = match self.expect_one_of_keywords(&[COUNTER, AUCTION, TPCH])? {
+                    COUNTER => LoadGenerator::Counter,
+                    AUCTION => LoadGenerator::Auction,
+                    TPCH => LoadGenerator::Tpch,
+                    _ => unreachable!(),
+                };
+                let table = if self.parse_keyword(TABLE) {
+                    Some(self.parse_identifier()?)
+                } else {
+                    None
+                };
+                let tick_interval = if self.parse_keywords(&[TICK, INTERVAL]) {
+                    Some(self.parse_literal_string()?)
+                } else {
+                    None
+                };
+                let scale_factor = if self.parse_keywords(&[SCALE, FACTOR]) {
+                    match self.parse_number_value()? {
+                        Value::Number(n) => Some(n),
+                        _ => unreachable!(),
+                    }
+                } else {
+                    None
+                };
+                Ok(CreateSourceConnector::LoadGenerator {
+                    generator,
+                    table,
+                    tick_interval,
+                    scale_factor,
+                })
+            }
             PUBNUB => {
                 self.expect_keywords(&[SUBSCRIBE, KEY])?;
                 let subscribe_key = self.parse_literal_string()?;
@@ -2144,7 +2217,11 @@ impl<'a> Parser<'a> {
                     _ => unreachable!(),
                 };
                 self.expect_keyword(TOPIC)?;
-                let topic = self.parse_literal_string()?;
+                let topic = if self.parse_keyword(PATTERN) {
+                    KafkaTopic::Pattern(self.parse_literal_string()?)
+                } else {
+                    KafkaTopic::Names(self.parse_comma_separated(Parser::parse_literal_string)?)
+                };
                 // one token of lookahead:
                 // * `KEY (` means we're parsing a list of columns for the key
                 // * `KEY FORMAT` means there is no key, we'll parse a KeyValueFormat later
@@ -2222,7 +2299,11 @@ impl<'a> Parser<'a> {
                 self.expect_keyword(BROKER)?;
                 let broker = self.parse_literal_string()?;
                 self.expect_keyword(TOPIC)?;
-                let topic = self.parse_literal_string()?;
+                let topic = if self.parse_keyword(PATTERN) {
+                    KafkaTopic::Pattern(self.parse_literal_string()?)
+                } else {
+                    KafkaTopic::Names(self.parse_comma_separated(Parser::parse_literal_string)?)
+                };
                 // one token of lookahead:
                 // * `KEY (` means we're parsing a list of columns for the key
                 // * `KEY FORMAT` means there is no key, we'll parse a KeyValueFormat later
@@ -2493,12 +2574,20 @@ impl<'a> Parser<'a> {
         let _ = self.parse_keyword(WITH);
         let mut options = vec![];
         loop {
-            match self.parse_one_of_keywords(&[SUPERUSER, NOSUPERUSER, LOGIN, NOLOGIN]) {
+            match self.parse_one_of_keywords(&[SUPERUSER, NOSUPERUSER, LOGIN, NOLOGIN, PASSWORD]) {
                 None => break,
                 Some(SUPERUSER) => options.push(CreateRoleOption::SuperUser),
                 Some(NOSUPERUSER) => options.push(CreateRoleOption::NoSuperUser),
                 Some(LOGIN) => options.push(CreateRoleOption::Login),
                 Some(NOLOGIN) => options.push(CreateRoleOption::NoLogin),
+                Some(PASSWORD) => {
+                    let password = if self.parse_keyword(NULL) {
+                        None
+                    } else {
+                        Some(self.parse_literal_string()?)
+                    };
+                    options.push(CreateRoleOption::Password(password));
+                }
                 Some(_) => unreachable!(),
             }
         }
@@ -2812,6 +2901,84 @@ impl<'a> Parser<'a> {
         ))
     }
 
+    fn parse_grant(&mut self) -> Result<Statement<Raw>, ParserError> {
+        let privileges = self.parse_privilege_specification()?;
+        self.expect_keyword(ON)?;
+        let objects = self.parse_privilege_objects()?;
+        self.expect_keyword(TO)?;
+        let roles = self.parse_comma_separated(Parser::parse_identifier)?;
+        Ok(Statement::GrantPrivileges(GrantPrivilegesStatement {
+            privileges,
+            objects,
+            roles,
+        }))
+    }
+
+    fn parse_revoke(&mut self) -> Result<Statement<Raw>, ParserError> {
+        let privileges = self.parse_privilege_specification()?;
+        self.expect_keyword(ON)?;
+        let objects = self.parse_privilege_objects()?;
+        self.expect_keyword(FROM)?;
+        let roles = self.parse_comma_separated(Parser::parse_identifier)?;
+        Ok(Statement::RevokePrivileges(RevokePrivilegesStatement {
+            privileges,
+            objects,
+            roles,
+        }))
+    }
+
+    fn parse_privilege_specification(&mut self) -> Result<PrivilegeSpecification, ParserError> {
+        if self.parse_keyword(ALL) {
+            let _ = self.parse_keyword(PRIVILEGES);
+            return Ok(PrivilegeSpecification::All);
+        }
+        let privileges = self.parse_comma_separated(|parser| {
+            match parser.parse_one_of_keywords(&[SELECT, INSERT, UPDATE, DELETE, USAGE, CREATE]) {
+                Some(SELECT) => Ok(Privilege::Select),
+                Some(INSERT) => Ok(Privilege::Insert),
+                Some(UPDATE) => Ok(Privilege::Update),
+                Some(DELETE) => Ok(Privilege::Delete),
+                Some(USAGE) => Ok(Privilege::Usage),
+                Some(CREATE) => Ok(Privilege::Create),
+                _ => parser.expected(
+                    parser.peek_pos(),
+                    "ALL, SELECT, INSERT, UPDATE, DELETE, USAGE, or CREATE",
+                    parser.peek_token(),
+                ),
+            }
+        })?;
+        Ok(PrivilegeSpecification::Privileges(privileges))
+    }
+
+    fn parse_privilege_objects(&mut self) -> Result<PrivilegeObjects<Raw>, ParserError> {
+        let object_type = match self.parse_one_of_keywords(&[
+            DATABASE, SCHEMA, CLUSTER, TABLE, VIEW, SOURCE, SINK, SECRET, CONNECTOR,
+        ]) {
+            Some(DATABASE) => {
+                let names = self.parse_comma_separated(Parser::parse_database_name)?;
+                return Ok(PrivilegeObjects::Databases(names));
+            }
+            Some(SCHEMA) => {
+                let names = self.parse_comma_separated(Parser::parse_schema_name)?;
+                return Ok(PrivilegeObjects::Schemas(names));
+            }
+            Some(CLUSTER) => {
+                let names = self.parse_comma_separated(Parser::parse_identifier)?;
+                return Ok(PrivilegeObjects::Clusters(names));
+            }
+            Some(VIEW) => ObjectType::View,
+            Some(SOURCE) => ObjectType::Source,
+            Some(SINK) => ObjectType::Sink,
+            Some(SECRET) => ObjectType::Secret,
+            Some(CONNECTOR) => ObjectType::Connector,
+            // As in PostgreSQL, the object type defaults to `TABLE`.
+            Some(TABLE) | None => ObjectType::Table,
+            Some(_) => unreachable!(),
+        };
+        let names = self.parse_comma_separated(Parser::parse_raw_name)?;
+        Ok(PrivilegeObjects::Items { object_type, names })
+    }
+
     fn parse_create_table(&mut self) -> Result<Statement<Raw>, ParserError> {
         let temporary = self.parse_keyword(TEMPORARY) | self.parse_keyword(TEMP);
         self.expect_keyword(TABLE)?;
@@ -4070,6 +4237,7 @@ impl<'a> Parser<'a> {
                 && self.parse_keywords(&[AS, TRANSACTION]))
         {
             Ok(Statement::SetTransaction(SetTransactionStatement {
+                local: modifier.is_none(),
                 modes: self.parse_transaction_modes()?,
             }))
         } else {
@@ -4672,6 +4840,8 @@ impl<'a> Parser<'a> {
                     TransactionIsolationLevel::RepeatableRead
                 } else if self.parse_keyword(SERIALIZABLE) {
                     TransactionIsolationLevel::Serializable
+                } else if self.parse_keywords(&[STRICT, SERIALIZABLE]) {
+                    TransactionIsolationLevel::StrictSerializable
                 } else {
                     self.expected(self.peek_pos(), "isolation level", self.peek_token())?
                 };
@@ -4762,8 +4932,9 @@ impl<'a> Parser<'a> {
             }
         }
 
-        // (RAW | DECORRELATED | OPTIMIZED | PHYSICAL)? PLAN
-        let stage = match self.parse_one_of_keywords(&[
+        // (RAW | DECORRELATED | OPTIMIZED | PHYSICAL)? PLAN | ANALYZE
+        let (stage, for_required) = match self.parse_one_of_keywords(&[
+            ANALYZE,
             RAW,
             DECORRELATED,
             OPTIMIZED,
@@ -4773,42 +4944,59 @@ impl<'a> Parser<'a> {
             TIMESTAMP,
         ]) {
             Some(RAW) => {
-                self.expect_keywords(&[PLAN, FOR])?;
-                ExplainStage::RawPlan
+                self.expect_keyword(PLAN)?;
+                (Some(ExplainStage::RawPlan), true)
             }
             Some(QUERY) => {
-                self.expect_keywords(&[GRAPH, FOR])?;
-                ExplainStage::QueryGraph
+                self.expect_keyword(GRAPH)?;
+                (Some(ExplainStage::QueryGraph), true)
             }
             Some(DECORRELATED) => {
-                self.expect_keywords(&[PLAN, FOR])?;
-                ExplainStage::DecorrelatedPlan
+                self.expect_keyword(PLAN)?;
+                (Some(ExplainStage::DecorrelatedPlan), true)
             }
             Some(OPTIMIZED) => {
                 if self.parse_keyword(QUERY) {
-                    self.expect_keywords(&[GRAPH, FOR])?;
-                    ExplainStage::OptimizedQueryGraph
+                    self.expect_keyword(GRAPH)?;
+                    (Some(ExplainStage::OptimizedQueryGraph), true)
                 } else {
-                    self.expect_keywords(&[PLAN, FOR])?;
-                    ExplainStage::OptimizedPlan
+                    self.expect_keyword(PLAN)?;
+                    (Some(ExplainStage::OptimizedPlan), true)
                 }
             }
-            Some(PLAN) => {
-                self.expect_keyword(FOR)?;
-                ExplainStage::OptimizedPlan
-            }
+            Some(PLAN) => (Some(ExplainStage::OptimizedPlan), true),
             Some(PHYSICAL) => {
-                self.expect_keywords(&[PLAN, FOR])?;
-                ExplainStage::PhysicalPlan
+                self.expect_keyword(PLAN)?;
+                (Some(ExplainStage::PhysicalPlan), true)
             }
-            Some(TIMESTAMP) => {
-                self.expect_keywords(&[FOR])?;
-                ExplainStage::Timestamp
-            }
-            None => ExplainStage::OptimizedPlan,
+            Some(TIMESTAMP) => (Some(ExplainStage::Timestamp), true),
+            // Like PostgreSQL, allow `EXPLAIN ANALYZE <query>`.
+            Some(ANALYZE) => (Some(ExplainStage::Analyze), false),
+            None => (None, false),
             _ => unreachable!(),
         };
 
+        // (AS (TEXT | JSON))?
+        let format = if self.parse_keyword(AS) {
+            match self.expect_one_of_keywords(&[TEXT, JSON])? {
+                TEXT => Some(ExplainFormat::Text),
+                JSON => Some(ExplainFormat::Json),
+                _ => unreachable!(),
+            }
+        } else {
+            None
+        };
+
+        // FOR is required after an explicit stage, except for ANALYZE. When
+        // the stage is omitted, FOR may only follow an explicit format.
+        if for_required {
+            self.expect_keyword(FOR)?;
+        } else if stage.is_some() || format.is_some() {
+            let _ = self.parse_keyword(FOR);
+        }
+        let stage = stage.unwrap_or(ExplainStage::OptimizedPlan);
+        let format = format.unwrap_or(ExplainFormat::Text);
+
         // VIEW view_name | query
         let explainee = if self.parse_keyword(VIEW) {
             Explainee::View(self.parse_raw_name()?)
@@ -4821,6 +5009,7 @@ impl<'a> Parser<'a> {
             stage,
             explainee,
             options,
+            format,
         }))
     }
 
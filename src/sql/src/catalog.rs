@@ -243,6 +243,12 @@ pub trait CatalogSchema {
     fn has_items(&self) -> bool;
 }
 
+/// The ID of the `PUBLIC` pseudo-role, which stands for every role.
+///
+/// Privileges granted to `PUBLIC` are available to all roles. No role may be
+/// named `public`.
+pub const PUBLIC_ROLE_ID: i64 = 0;
+
 /// A role in a [`SessionCatalog`].
 pub trait CatalogRole {
     /// Returns a fully-specified name of the role.
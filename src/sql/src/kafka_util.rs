@@ -15,7 +15,7 @@ use std::fs::File;
 use std::io::Read;
 use std::sync::{Arc, Mutex};
 
-use anyhow::bail;
+use anyhow::{anyhow, bail};
 
 use mz_kafka_util::client::MzClientContext;
 use mz_ore::task;
@@ -26,6 +26,7 @@ use reqwest::Url;
 use tokio::time::Duration;
 
 use mz_ccsr::tls::{Certificate, Identity};
+use mz_dataflow_types::sources::KafkaTopics;
 use mz_sql_parser::ast::Value;
 
 enum ValType {
@@ -252,7 +253,7 @@ pub fn extract_config(
 /// - `librdkafka` cannot create a BaseConsumer using the provided `options`.
 pub async fn create_consumer(
     broker: &str,
-    topic: &str,
+    topic: Option<&str>,
     options: &BTreeMap<String, String>,
 ) -> Result<Arc<BaseConsumer<KafkaErrCheckContext>>, anyhow::Error> {
     let mut config = rdkafka::ClientConfig::new();
@@ -264,16 +265,16 @@ pub async fn create_consumer(
     let consumer: Arc<BaseConsumer<KafkaErrCheckContext>> =
         Arc::new(config.create_with_context(KafkaErrCheckContext::default())?);
     let context = Arc::clone(&consumer.context());
-    let owned_topic = String::from(topic);
+    let owned_topic = topic.map(String::from);
     // Wait for a metadata request for up to one second. This greatly
     // increases the probability that we'll see a connection error if
     // e.g. the hostname was mistyped. librdkafka doesn't expose a
     // better API for asking whether a connection succeeded or failed,
     // unfortunately.
-    task::spawn_blocking(move || format!("kafka_set_metadata:{broker}:{topic}"), {
+    task::spawn_blocking(move || format!("kafka_set_metadata:{broker}:{topic:?}"), {
         let consumer = Arc::clone(&consumer);
         move || {
-            let _ = consumer.fetch_metadata(Some(&owned_topic), Duration::from_secs(1));
+            let _ = consumer.fetch_metadata(owned_topic.as_deref(), Duration::from_secs(1));
         }
     })
     .await?;
@@ -379,6 +380,30 @@ pub async fn lookup_start_offsets(
     .await?
 }
 
+/// Returns the names of the topics that match `pattern`, in sorted order.
+///
+/// The pattern must match a topic's entire name, as described by
+/// [`KafkaTopics::Pattern`].
+pub async fn lookup_matching_topics(
+    consumer: Arc<BaseConsumer<KafkaErrCheckContext>>,
+    pattern: &str,
+) -> Result<Vec<String>, anyhow::Error> {
+    let regex = KafkaTopics::compile_pattern(pattern)
+        .map_err(|e| anyhow!("invalid TOPIC PATTERN: {}", e))?;
+    task::spawn_blocking(
+        || format!("kafka_lookup_matching_topics:{pattern}"),
+        move || {
+            let partitions = mz_kafka_util::client::get_matching_partitions(
+                consumer.as_ref().client(),
+                |topic| regex.is_match(topic),
+                Duration::from_secs(10),
+            )?;
+            Ok(partitions.into_keys().collect())
+        },
+    )
+    .await?
+}
+
 // Kafka supports bulk lookup of watermarks, but it is not exposed in rdkafka.
 // If that ever changes, we will want to first collect all pids that have no
 // offset for a given timestamp and then do a single request (instead of doing
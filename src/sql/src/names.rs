@@ -9,7 +9,7 @@
 
 //! Structured name types for SQL objects.
 
-use anyhow::Error;
+use anyhow::{anyhow, Error};
 use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::str::FromStr;
@@ -650,6 +650,40 @@ impl FromStr for DatabaseId {
     }
 }
 
+/// The identifier for an object on which privileges can be granted.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum ObjectId {
+    Database(DatabaseId),
+    Schema(SchemaId),
+    Item(GlobalId),
+    ComputeInstance(ComputeInstanceId),
+}
+
+impl fmt::Display for ObjectId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ObjectId::Database(id) => write!(f, "database:{}", id),
+            ObjectId::Schema(id) => write!(f, "schema:{}", id),
+            ObjectId::Item(id) => write!(f, "item:{}", id),
+            ObjectId::ComputeInstance(id) => write!(f, "cluster:{}", id),
+        }
+    }
+}
+
+impl FromStr for ObjectId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some(("database", id)) => Ok(ObjectId::Database(id.parse()?)),
+            Some(("schema", id)) => Ok(ObjectId::Schema(id.parse()?)),
+            Some(("item", id)) => Ok(ObjectId::Item(id.parse()?)),
+            Some(("cluster", id)) => Ok(ObjectId::ComputeInstance(id.parse()?)),
+            _ => Err(anyhow!("couldn't parse object id {}", s)),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct NameResolver<'a> {
     catalog: &'a dyn SessionCatalog,
@@ -327,6 +327,7 @@ pub fn create_statement(
             format,
             include_metadata: _,
             envelope: _,
+            on_decode_error: _,
             if_not_exists,
             materialized,
             key_constraint: _,
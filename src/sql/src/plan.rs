@@ -41,12 +41,13 @@ use mz_ore::now::{self, NOW_ZERO};
 use mz_repr::{ColumnName, Diff, GlobalId, RelationDesc, Row, ScalarType};
 
 use crate::ast::{
-    ExplainOptions, ExplainStage, Expr, FetchDirection, NoticeSeverity, ObjectType, Raw, Statement,
-    TransactionAccessMode,
+    ExplainFormat, ExplainOptions, ExplainStage, Expr, FetchDirection, NoticeSeverity, ObjectType,
+    Privilege, Raw, Statement, TransactionAccessMode, TransactionIsolationLevel,
 };
 use crate::catalog::{CatalogType, IdReference};
 use crate::names::{
-    Aug, DatabaseId, FullObjectName, QualifiedObjectName, ResolvedDatabaseSpecifier, SchemaId,
+    Aug, DatabaseId, FullObjectName, ObjectId, QualifiedObjectName, ResolvedDatabaseSpecifier,
+    SchemaId,
 };
 
 pub(crate) mod error;
@@ -94,11 +95,14 @@ pub enum Plan {
     DropComputeInstances(DropComputeInstancesPlan),
     DropComputeInstanceReplica(DropComputeInstanceReplicaPlan),
     DropItems(DropItemsPlan),
+    GrantPrivileges(GrantPrivilegesPlan),
+    RevokePrivileges(RevokePrivilegesPlan),
     EmptyQuery,
     ShowAllVariables,
     ShowVariable(ShowVariablePlan),
     SetVariable(SetVariablePlan),
     StartTransaction(StartTransactionPlan),
+    SetTransaction(SetTransactionPlan),
     CommitTransaction,
     AbortTransaction,
     Peek(PeekPlan),
@@ -126,6 +130,16 @@ pub enum Plan {
 #[derive(Debug)]
 pub struct StartTransactionPlan {
     pub access: Option<TransactionAccessMode>,
+    pub isolation_level: Option<TransactionIsolationLevel>,
+}
+
+#[derive(Debug)]
+pub struct SetTransactionPlan {
+    /// Whether the modes apply only to the current transaction, rather than to
+    /// the session.
+    pub local: bool,
+    pub access: Option<TransactionAccessMode>,
+    pub isolation_level: Option<TransactionIsolationLevel>,
 }
 
 #[derive(Debug)]
@@ -144,6 +158,10 @@ pub struct CreateSchemaPlan {
 #[derive(Debug)]
 pub struct CreateRolePlan {
     pub name: String,
+    pub superuser: bool,
+    /// The role's password, if any. The coordinator stores only a salted
+    /// verifier derived from it.
+    pub password: Option<String>,
 }
 
 #[derive(Debug)]
@@ -281,6 +299,24 @@ pub struct DropItemsPlan {
     pub ty: ObjectType,
 }
 
+#[derive(Debug)]
+pub struct GrantPrivilegesPlan {
+    pub objects: Vec<ObjectId>,
+    pub privileges: BTreeSet<Privilege>,
+    /// The IDs of the roles receiving the privileges. May include
+    /// [`PUBLIC_ROLE_ID`](crate::catalog::PUBLIC_ROLE_ID).
+    pub grantees: Vec<i64>,
+}
+
+#[derive(Debug)]
+pub struct RevokePrivilegesPlan {
+    pub objects: Vec<ObjectId>,
+    pub privileges: BTreeSet<Privilege>,
+    /// The IDs of the roles losing the privileges. May include
+    /// [`PUBLIC_ROLE_ID`](crate::catalog::PUBLIC_ROLE_ID).
+    pub grantees: Vec<i64>,
+}
+
 #[derive(Debug)]
 pub struct ShowVariablePlan {
     pub name: String,
@@ -338,6 +374,10 @@ pub struct ExplainPlan {
     pub row_set_finishing: Option<RowSetFinishing>,
     pub stage: ExplainStage,
     pub options: ExplainOptions,
+    pub format: ExplainFormat,
+    /// The view being explained, if the explainee is a view rather than a
+    /// query.
+    pub view_id: Option<GlobalId>,
 }
 
 #[derive(Debug)]
@@ -500,7 +540,7 @@ pub struct Type {
 }
 
 /// Specifies when a `Peek` or `Tail` should occur.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum QueryWhen {
     /// The peek should occur at the latest possible timestamp that allows the
     /// peek to complete immediately.
@@ -531,16 +571,23 @@ impl QueryWhen {
         }
     }
     /// Returns whether the candidate must be advanced to the upper.
-    pub fn advance_to_upper(&self, uses_tables: bool) -> bool {
+    ///
+    /// `linearized` indicates whether the read must be linearized with respect
+    /// to the global timeline.
+    pub fn advance_to_upper(&self, linearized: bool) -> bool {
         match self {
-            QueryWhen::Immediately | QueryWhen::AtLeastTimestamp(_) => !uses_tables,
+            QueryWhen::Immediately | QueryWhen::AtLeastTimestamp(_) => !linearized,
             QueryWhen::AtTimestamp(_) => false,
         }
     }
-    /// Returns whether the candidate must be advanced to the global table timestamp.
-    pub fn advance_to_table_ts(&self, uses_tables: bool) -> bool {
+    /// Returns whether the candidate must be advanced to the global timeline's
+    /// read timestamp.
+    ///
+    /// `linearized` indicates whether the read must be linearized with respect
+    /// to the global timeline.
+    pub fn advance_to_timeline_ts(&self, linearized: bool) -> bool {
         match self {
-            QueryWhen::Immediately | QueryWhen::AtLeastTimestamp(_) => uses_tables,
+            QueryWhen::Immediately | QueryWhen::AtLeastTimestamp(_) => linearized,
             QueryWhen::AtTimestamp(_) => false,
         }
     }
@@ -607,13 +654,19 @@ impl Params {
 pub struct PlanContext {
     pub wall_time: DateTime<Utc>,
     pub qgm_optimizations: bool,
+    pub native_outer_joins: bool,
 }
 
 impl PlanContext {
-    pub fn new(wall_time: DateTime<Utc>, qgm_optimizations: bool) -> Self {
+    pub fn new(
+        wall_time: DateTime<Utc>,
+        qgm_optimizations: bool,
+        native_outer_joins: bool,
+    ) -> Self {
         Self {
             wall_time,
             qgm_optimizations,
+            native_outer_joins,
         }
     }
 
@@ -624,6 +677,7 @@ impl PlanContext {
         PlanContext {
             wall_time: now::to_datetime(NOW_ZERO()),
             qgm_optimizations: false,
+            native_outer_joins: false,
         }
     }
 }
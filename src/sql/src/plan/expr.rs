@@ -12,7 +12,7 @@
 //! similar to that file, with some differences which are noted below. It gets turned into that
 //! representation via a call to decorrelate().
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt;
 use std::mem;
 
@@ -20,7 +20,7 @@ use anyhow::bail;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
-use mz_expr::DummyHumanizer;
+use mz_expr::{CollectionPlan, DummyHumanizer};
 
 use mz_ore::collections::CollectionExt;
 use mz_ore::stack;
@@ -1387,6 +1387,33 @@ impl HirRelationExpr {
     }
 }
 
+impl CollectionPlan for HirRelationExpr {
+    fn depends_on_into(&self, out: &mut BTreeSet<GlobalId>) {
+        // Gets in the relation itself, and in the relations of the subqueries
+        // within its scalar expressions, at any depth.
+        let mut gets = |expr: &HirRelationExpr| {
+            expr.visit(0, &mut |e, _| {
+                if let HirRelationExpr::Get {
+                    id: mz_expr::Id::Global(id),
+                    ..
+                } = e
+                {
+                    out.insert(*id);
+                }
+            })
+        };
+        gets(self);
+        let _ = self.visit_scalar_expressions(0, &mut |e, depth| {
+            e.visit_recursively(depth, &mut |_, e| {
+                if let HirScalarExpr::Exists(expr) | HirScalarExpr::Select(expr) = e {
+                    gets(expr);
+                }
+                Ok::<_, ()>(())
+            })
+        });
+    }
+}
+
 impl HirScalarExpr {
     /// Replaces any parameter references in the expression with the
     /// corresponding datum in `params`.
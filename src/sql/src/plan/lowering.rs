@@ -127,10 +127,23 @@ struct CteDesc {
     outer_relation: mz_expr::MirRelationExpr,
 }
 
+/// Feature flags for the lowering of a [`HirRelationExpr`].
+#[derive(Debug, Default)]
+pub struct Config {
+    /// Whether to lower uncorrelated equality outer joins into
+    /// `mz_expr::MirRelationExpr::OuterJoin` operators.
+    pub native_outer_joins: bool,
+}
+
 impl HirRelationExpr {
     /// Rewrite `self` into a `mz_expr::MirRelationExpr`.
     /// This requires rewriting all correlated subqueries (nested `HirRelationExpr`s) into flat queries
     pub fn lower(self) -> mz_expr::MirRelationExpr {
+        self.lower_with_config(&Config::default())
+    }
+
+    /// Like [`HirRelationExpr::lower`], but with the feature flags in `config`.
+    pub fn lower_with_config(self, config: &Config) -> mz_expr::MirRelationExpr {
         match self {
             // We directly rewrite a Constant into the corresponding `MirRelationExpr::Constant`
             // to ensure that the downstream optimizer can easily bypass most
@@ -151,7 +164,13 @@ impl HirRelationExpr {
                 mz_expr::MirRelationExpr::constant(vec![vec![]], RelationType::new(vec![])).let_in(
                     &mut id_gen,
                     |id_gen, get_outer| {
-                        other.applied_to(id_gen, get_outer, &ColumnMap::empty(), &mut CteMap::new())
+                        other.applied_to(
+                            id_gen,
+                            get_outer,
+                            &ColumnMap::empty(),
+                            &mut CteMap::new(),
+                            config,
+                        )
                     },
                 )
             }
@@ -176,6 +195,7 @@ impl HirRelationExpr {
         get_outer: mz_expr::MirRelationExpr,
         col_map: &ColumnMap,
         cte_map: &mut CteMap,
+        config: &Config,
     ) -> mz_expr::MirRelationExpr {
         maybe_grow(|| {
             use self::HirRelationExpr::*;
@@ -260,7 +280,8 @@ impl HirRelationExpr {
                     value,
                     body,
                 } => {
-                    let value = value.applied_to(id_gen, get_outer.clone(), col_map, cte_map);
+                    let value =
+                        value.applied_to(id_gen, get_outer.clone(), col_map, cte_map, config);
                     value.let_in(id_gen, |id_gen, get_value| {
                         let (new_id, typ) = if let mz_expr::MirRelationExpr::Get {
                             id: mz_expr::Id::Local(id),
@@ -285,7 +306,7 @@ impl HirRelationExpr {
                                 outer_relation: get_outer.clone(),
                             },
                         );
-                        let body = body.applied_to(id_gen, get_outer, col_map, cte_map);
+                        let body = body.applied_to(id_gen, get_outer, col_map, cte_map, config);
                         if let Some(old_value) = old_value {
                             cte_map.insert(id, old_value);
                         } else {
@@ -297,7 +318,8 @@ impl HirRelationExpr {
                 Project { input, outputs } => {
                     // Projections should be applied to the decorrelated `inner`, and to its columns,
                     // which means rebasing `outputs` to start `get_outer.arity()` columns later.
-                    let input = input.applied_to(id_gen, get_outer.clone(), col_map, cte_map);
+                    let input =
+                        input.applied_to(id_gen, get_outer.clone(), col_map, cte_map, config);
                     let outputs = (0..get_outer.arity())
                         .chain(outputs.into_iter().map(|i| get_outer.arity() + i))
                         .collect::<Vec<_>>();
@@ -305,7 +327,7 @@ impl HirRelationExpr {
                 }
                 Map { input, mut scalars } => {
                     // Scalar expressions may contain correlated subqueries. We must be cautious!
-                    let mut input = input.applied_to(id_gen, get_outer, col_map, cte_map);
+                    let mut input = input.applied_to(id_gen, get_outer, col_map, cte_map, config);
 
                     // Lower subqueries in maximally sized batches, such as no subquery in the current
                     // batch depends on columns from the same batch.
@@ -330,7 +352,7 @@ impl HirRelationExpr {
 
                         let scalars = scalars.drain(0..end_idx).collect_vec();
                         let (with_subqueries, subquery_map) = HirScalarExpr::lower_subqueries(
-                            &scalars, id_gen, col_map, cte_map, input,
+                            &scalars, id_gen, col_map, cte_map, config, input,
                         );
                         input = with_subqueries;
 
@@ -348,6 +370,7 @@ impl HirRelationExpr {
                                 id_gen,
                                 col_map,
                                 cte_map,
+                                config,
                                 &mut input,
                                 &Some(&subquery_map),
                             );
@@ -372,7 +395,7 @@ impl HirRelationExpr {
 
                     let exprs = exprs
                         .into_iter()
-                        .map(|e| e.applied_to(id_gen, col_map, cte_map, &mut input, &None))
+                        .map(|e| e.applied_to(id_gen, col_map, cte_map, config, &mut input, &None))
                         .collect::<Vec<_>>();
 
                     let new_arity = input.arity();
@@ -392,11 +415,11 @@ impl HirRelationExpr {
                     // Filter expressions may contain correlated subqueries.
                     // We extend `get_outer` with sufficient values to determine the value of the predicate,
                     // then filter the results, then strip off any columns that were added for this purpose.
-                    let mut input = input.applied_to(id_gen, get_outer, col_map, cte_map);
+                    let mut input = input.applied_to(id_gen, get_outer, col_map, cte_map, config);
                     for predicate in predicates {
                         let old_arity = input.arity();
-                        let predicate =
-                            predicate.applied_to(id_gen, col_map, cte_map, &mut input, &None);
+                        let predicate = predicate
+                            .applied_to(id_gen, col_map, cte_map, config, &mut input, &None);
                         let new_arity = input.arity();
                         input = input.filter(vec![predicate]);
                         if old_arity != new_arity {
@@ -423,7 +446,7 @@ impl HirRelationExpr {
 
                     assert!(kind.can_be_correlated());
 
-                    let left = left.applied_to(id_gen, get_outer, col_map, cte_map);
+                    let left = left.applied_to(id_gen, get_outer, col_map, cte_map, config);
                     left.let_in(id_gen, |id_gen, get_left| {
                         let apply_requires_distinct_outer = false;
                         let mut join = branch(
@@ -434,13 +457,13 @@ impl HirRelationExpr {
                             *right,
                             apply_requires_distinct_outer,
                             |id_gen, right, get_left, col_map, cte_map| {
-                                right.applied_to(id_gen, get_left, col_map, cte_map)
+                                right.applied_to(id_gen, get_left, col_map, cte_map, config)
                             },
                         );
 
                         // Plan the `on` predicate.
                         let old_arity = join.arity();
-                        let on = on.applied_to(id_gen, col_map, cte_map, &mut join, &None);
+                        let on = on.applied_to(id_gen, col_map, cte_map, config, &mut join, &None);
                         join = join.filter(vec![on]);
                         let new_arity = join.arity();
                         if old_arity != new_arity {
@@ -481,13 +504,18 @@ impl HirRelationExpr {
                     // against the records present in the left and right (decorrelated) inputs,
                     // depending on the type of join.
                     let oa = get_outer.arity();
-                    let left = left.applied_to(id_gen, get_outer.clone(), col_map, cte_map);
+                    let left = left.applied_to(id_gen, get_outer.clone(), col_map, cte_map, config);
                     let lt = left.typ().column_types.into_iter().skip(oa).collect_vec();
                     let la = lt.len();
                     left.let_in(id_gen, |id_gen, get_left| {
                         let right_col_map = col_map.enter_scope(0);
-                        let right =
-                            right.applied_to(id_gen, get_outer.clone(), &right_col_map, cte_map);
+                        let right = right.applied_to(
+                            id_gen,
+                            get_outer.clone(),
+                            &right_col_map,
+                            cte_map,
+                            config,
+                        );
                         let rt = right.typ().column_types.into_iter().skip(oa).collect_vec();
                         let ra = rt.len();
                         right.let_in(id_gen, |id_gen, get_right| {
@@ -502,7 +530,14 @@ impl HirRelationExpr {
                                     .collect(),
                             );
                             let old_arity = product.arity();
-                            let on = on.applied_to(id_gen, col_map, cte_map, &mut product, &None);
+                            let on = on.applied_to(
+                                id_gen,
+                                col_map,
+                                cte_map,
+                                config,
+                                &mut product,
+                                &None,
+                            );
 
                             // Attempt an efficient equijoin implementation, in which outer joins are
                             // more efficiently rendered than in general. This can return `None` if
@@ -516,6 +551,7 @@ impl HirRelationExpr {
                                     kind.clone(),
                                     oa,
                                     id_gen,
+                                    config,
                                 ) {
                                     return joined;
                                 }
@@ -581,11 +617,18 @@ impl HirRelationExpr {
                             get_outer.clone(),
                             col_map,
                             cte_map,
+                            config,
                         )),
                         inputs: inputs
                             .into_iter()
                             .map(|input| {
-                                input.applied_to(id_gen, get_outer.clone(), col_map, cte_map)
+                                input.applied_to(
+                                    id_gen,
+                                    get_outer.clone(),
+                                    col_map,
+                                    cte_map,
+                                    config,
+                                )
                             })
                             .collect(),
                     }
@@ -599,13 +642,16 @@ impl HirRelationExpr {
                     // Reduce may contain expressions with correlated subqueries.
                     // In addition, here an empty reduction key signifies that we need to supply default values
                     // in the case that there are no results (as in a SQL aggregation without an explicit GROUP BY).
-                    let mut input = input.applied_to(id_gen, get_outer.clone(), col_map, cte_map);
+                    let mut input =
+                        input.applied_to(id_gen, get_outer.clone(), col_map, cte_map, config);
                     let applied_group_key = (0..get_outer.arity())
                         .chain(group_key.iter().map(|i| get_outer.arity() + i))
                         .collect();
                     let applied_aggregates = aggregates
                         .into_iter()
-                        .map(|aggregate| aggregate.applied_to(id_gen, col_map, cte_map, &mut input))
+                        .map(|aggregate| {
+                            aggregate.applied_to(id_gen, col_map, cte_map, config, &mut input)
+                        })
                         .collect::<Vec<_>>();
                     let input_type = input.typ();
                     let default = applied_aggregates
@@ -625,7 +671,7 @@ impl HirRelationExpr {
                 Distinct { input } => {
                     // Distinct is uncomplicated.
                     input
-                        .applied_to(id_gen, get_outer, col_map, cte_map)
+                        .applied_to(id_gen, get_outer, col_map, cte_map, config)
                         .distinct()
                 }
                 TopK {
@@ -636,7 +682,8 @@ impl HirRelationExpr {
                     offset,
                 } => {
                     // TopK is uncomplicated, except that we must group by the columns of `get_outer` as well.
-                    let input = input.applied_to(id_gen, get_outer.clone(), col_map, cte_map);
+                    let input =
+                        input.applied_to(id_gen, get_outer.clone(), col_map, cte_map, config);
                     let applied_group_key = (0..get_outer.arity())
                         .chain(group_key.iter().map(|i| get_outer.arity() + i))
                         .collect();
@@ -652,13 +699,13 @@ impl HirRelationExpr {
                 Negate { input } => {
                     // Negate is uncomplicated.
                     input
-                        .applied_to(id_gen, get_outer, col_map, cte_map)
+                        .applied_to(id_gen, get_outer, col_map, cte_map, config)
                         .negate()
                 }
                 Threshold { input } => {
                     // Threshold is uncomplicated.
                     input
-                        .applied_to(id_gen, get_outer, col_map, cte_map)
+                        .applied_to(id_gen, get_outer, col_map, cte_map, config)
                         .threshold()
                 }
             }
@@ -682,6 +729,7 @@ impl HirScalarExpr {
         id_gen: &mut mz_ore::id_gen::IdGen,
         col_map: &ColumnMap,
         cte_map: &mut CteMap,
+        config: &Config,
         inner: &mut mz_expr::MirRelationExpr,
         subquery_map: &Option<&HashMap<HirScalarExpr, usize>>,
     ) -> mz_expr::MirScalarExpr {
@@ -702,7 +750,14 @@ impl HirScalarExpr {
                 CallUnmaterializable(func) => SS::CallUnmaterializable(func),
                 CallUnary { func, expr } => SS::CallUnary {
                     func,
-                    expr: Box::new(expr.applied_to(id_gen, col_map, cte_map, inner, subquery_map)),
+                    expr: Box::new(expr.applied_to(
+                        id_gen,
+                        col_map,
+                        cte_map,
+                        config,
+                        inner,
+                        subquery_map,
+                    )),
                 },
                 CallBinary { func, expr1, expr2 } => SS::CallBinary {
                     func,
@@ -710,6 +765,7 @@ impl HirScalarExpr {
                         id_gen,
                         col_map,
                         cte_map,
+                        config,
                         inner,
                         subquery_map,
                     )),
@@ -717,6 +773,7 @@ impl HirScalarExpr {
                         id_gen,
                         col_map,
                         cte_map,
+                        config,
                         inner,
                         subquery_map,
                     )),
@@ -725,7 +782,9 @@ impl HirScalarExpr {
                     func,
                     exprs: exprs
                         .into_iter()
-                        .map(|expr| expr.applied_to(id_gen, col_map, cte_map, inner, subquery_map))
+                        .map(|expr| {
+                            expr.applied_to(id_gen, col_map, cte_map, config, inner, subquery_map)
+                        })
                         .collect::<Vec<_>>(),
                 },
                 If { cond, then, els } => {
@@ -750,7 +809,8 @@ impl HirScalarExpr {
                     // and we would benefit from not introducing the complexity.
 
                     let inner_arity = inner.arity();
-                    let cond_expr = cond.applied_to(id_gen, col_map, cte_map, inner, subquery_map);
+                    let cond_expr =
+                        cond.applied_to(id_gen, col_map, cte_map, config, inner, subquery_map);
 
                     // Defensive copies, in case we mangle these in decorrelation.
                     let inner_clone = inner.clone();
@@ -758,8 +818,10 @@ impl HirScalarExpr {
                     let else_clone = els.clone();
 
                     let cond_arity = inner.arity();
-                    let then_expr = then.applied_to(id_gen, col_map, cte_map, inner, subquery_map);
-                    let else_expr = els.applied_to(id_gen, col_map, cte_map, inner, subquery_map);
+                    let then_expr =
+                        then.applied_to(id_gen, col_map, cte_map, config, inner, subquery_map);
+                    let else_expr =
+                        els.applied_to(id_gen, col_map, cte_map, config, inner, subquery_map);
 
                     if cond_arity == inner.arity() {
                         // If no additional columns were added, we simply return the
@@ -782,6 +844,7 @@ impl HirScalarExpr {
                                 id_gen,
                                 col_map,
                                 cte_map,
+                                config,
                                 &mut then_inner,
                                 subquery_map,
                             );
@@ -807,6 +870,7 @@ impl HirScalarExpr {
                                 id_gen,
                                 col_map,
                                 cte_map,
+                                config,
                                 &mut else_inner,
                                 subquery_map,
                             );
@@ -841,6 +905,7 @@ impl HirScalarExpr {
                         inner.take_dangerous(),
                         col_map,
                         cte_map,
+                        config,
                         *expr,
                         apply_requires_distinct_outer,
                     );
@@ -854,6 +919,7 @@ impl HirScalarExpr {
                         inner.take_dangerous(),
                         col_map,
                         cte_map,
+                        config,
                         *expr,
                         apply_requires_distinct_outer,
                     );
@@ -878,6 +944,7 @@ impl HirScalarExpr {
                                                     id_gen,
                                                     col_map,
                                                     cte_map,
+                                                    config,
                                                     &mut get_inner,
                                                     subquery_map,
                                                 )
@@ -902,6 +969,7 @@ impl HirScalarExpr {
                                                 id_gen,
                                                 col_map,
                                                 cte_map,
+                                                config,
                                                 &mut get_inner,
                                                 subquery_map,
                                             );
@@ -1055,6 +1123,7 @@ impl HirScalarExpr {
                                                     id_gen,
                                                     col_map,
                                                     cte_map,
+                                                    config,
                                                     &mut get_inner,
                                                     subquery_map,
                                                 )
@@ -1066,6 +1135,7 @@ impl HirScalarExpr {
                                             id_gen,
                                             col_map,
                                             cte_map,
+                                            config,
                                             &mut get_inner,
                                             subquery_map,
                                         );
@@ -1090,6 +1160,7 @@ impl HirScalarExpr {
                                                 id_gen,
                                                 col_map,
                                                 cte_map,
+                                                config,
                                                 &mut get_inner,
                                                 subquery_map,
                                             );
@@ -1276,6 +1347,7 @@ impl HirScalarExpr {
         id_gen: &mut mz_ore::id_gen::IdGen,
         col_map: &ColumnMap,
         cte_map: &mut CteMap,
+        config: &Config,
         inner: mz_expr::MirRelationExpr,
     ) -> (mz_expr::MirRelationExpr, HashMap<HirScalarExpr, usize>) {
         let mut subquery_map = HashMap::new();
@@ -1298,6 +1370,7 @@ impl HirScalarExpr {
                                 distinct_inner.clone(),
                                 col_map,
                                 cte_map,
+                                config,
                                 (**expr).clone(),
                                 apply_requires_distinct_outer,
                             );
@@ -1311,6 +1384,7 @@ impl HirScalarExpr {
                                 distinct_inner.clone(),
                                 col_map,
                                 cte_map,
+                                config,
                                 (**expr).clone(),
                                 apply_requires_distinct_outer,
                             );
@@ -1604,6 +1678,7 @@ fn apply_scalar_subquery(
     outer: mz_expr::MirRelationExpr,
     col_map: &ColumnMap,
     cte_map: &mut CteMap,
+    config: &Config,
     scalar_subquery: HirRelationExpr,
     apply_requires_distinct_outer: bool,
 ) -> mz_expr::MirRelationExpr {
@@ -1617,7 +1692,7 @@ fn apply_scalar_subquery(
         |id_gen, expr, get_inner, col_map, cte_map| {
             let select = expr
                 // compute for every row in get_inner
-                .applied_to(id_gen, get_inner.clone(), col_map, cte_map);
+                .applied_to(id_gen, get_inner.clone(), col_map, cte_map, config);
             let col_type = select.typ().column_types.into_last();
 
             let inner_arity = get_inner.arity();
@@ -1661,6 +1736,7 @@ fn apply_existential_subquery(
     outer: mz_expr::MirRelationExpr,
     col_map: &ColumnMap,
     cte_map: &mut CteMap,
+    config: &Config,
     subquery_expr: HirRelationExpr,
     apply_requires_distinct_outer: bool,
 ) -> mz_expr::MirRelationExpr {
@@ -1674,7 +1750,7 @@ fn apply_existential_subquery(
         |id_gen, expr, get_inner, col_map, cte_map| {
             let exists = expr
                 // compute for every row in get_inner
-                .applied_to(id_gen, get_inner.clone(), col_map, cte_map)
+                .applied_to(id_gen, get_inner.clone(), col_map, cte_map, config)
                 // throw away actual values and just remember whether or not there were __any__ rows
                 .distinct_by((0..get_inner.arity()).collect())
                 // Append true to anything that returned any rows. This
@@ -1698,6 +1774,7 @@ impl AggregateExpr {
         id_gen: &mut mz_ore::id_gen::IdGen,
         col_map: &ColumnMap,
         cte_map: &mut CteMap,
+        config: &Config,
         inner: &mut mz_expr::MirRelationExpr,
     ) -> mz_expr::AggregateExpr {
         let AggregateExpr {
@@ -1708,7 +1785,7 @@ impl AggregateExpr {
 
         mz_expr::AggregateExpr {
             func: func.into_expr(),
-            expr: expr.applied_to(id_gen, col_map, cte_map, inner, &None),
+            expr: expr.applied_to(id_gen, col_map, cte_map, config, inner, &None),
             distinct,
         }
     }
@@ -1786,6 +1863,7 @@ fn attempt_outer_join(
     kind: JoinKind,
     oa: usize,
     id_gen: &mut mz_ore::id_gen::IdGen,
+    config: &Config,
 ) -> Option<mz_expr::MirRelationExpr> {
     // Both `left` and `right` are decorrelated inputs, whose first `oa` columns
     // correspond to an outer context: we should do the outer join independently
@@ -1804,6 +1882,37 @@ fn attempt_outer_join(
 
     let (l_keys, r_keys) = equijoin_keys.unwrap();
 
+    // Without an outer context, the outer join can be planned as is. The
+    // equalities in `on` do not match nulls, which is also how the keys of
+    // `OuterJoin` are compared.
+    //
+    // With an outer context, both inputs would also need to be matched on
+    // their first `oa` columns. Those columns must match even when they are
+    // null, which `OuterJoin` keys never do, so correlated outer joins keep
+    // using the plan below.
+    if config.native_outer_joins && oa == 0 {
+        let kind = match kind {
+            JoinKind::LeftOuter { .. } => mz_expr::OuterJoinKind::Left,
+            JoinKind::RightOuter => mz_expr::OuterJoinKind::Right,
+            JoinKind::FullOuter => mz_expr::OuterJoinKind::Full,
+            JoinKind::Inner => unreachable!("inner joins are not outer joins"),
+        };
+        return Some(
+            left.outer_join(
+                right,
+                l_keys
+                    .into_iter()
+                    .map(mz_expr::MirScalarExpr::Column)
+                    .collect(),
+                r_keys
+                    .into_iter()
+                    .map(mz_expr::MirScalarExpr::Column)
+                    .collect(),
+                kind,
+            ),
+        );
+    }
+
     // If we've gotten this far, we can do the clever thing.
     // We'll want to use left and right multiple times
     let result = left.let_in(id_gen, |id_gen, get_left| {
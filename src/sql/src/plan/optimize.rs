@@ -11,12 +11,14 @@
 use crate::plan::expr::HirRelationExpr;
 use crate::query_model::{Model, QGMError};
 
+use super::lowering;
 use super::StatementContext;
 
 /// Feature flags for the [`HirRelationExpr::optimize_and_lower()`] logic.
 #[derive(Debug)]
 pub struct OptimizerConfig {
     pub qgm_optimizations: bool,
+    pub native_outer_joins: bool,
 }
 
 /// Convert a reference to a [`StatementContext`] to an [`OptimizerConfig`].
@@ -28,14 +30,24 @@ impl<'a> From<&StatementContext<'a>> for OptimizerConfig {
         match scx.pcx() {
             Ok(pcx) => OptimizerConfig {
                 qgm_optimizations: pcx.qgm_optimizations,
+                native_outer_joins: pcx.native_outer_joins,
             },
             Err(..) => OptimizerConfig {
                 qgm_optimizations: false,
+                native_outer_joins: false,
             },
         }
     }
 }
 
+impl From<&OptimizerConfig> for lowering::Config {
+    fn from(config: &OptimizerConfig) -> Self {
+        lowering::Config {
+            native_outer_joins: config.native_outer_joins,
+        }
+    }
+}
+
 impl HirRelationExpr {
     /// Perform optimizing algebraic rewrites on this [`HirRelationExpr`] and lower it to a [`mz_expr::MirRelationExpr`].
     ///
@@ -49,7 +61,7 @@ impl HirRelationExpr {
             self.try_qgm_path()
         } else {
             // directly decorrelate and lower into a MirRelationExpr
-            Ok(self.lower())
+            Ok(self.lower_with_config(&config.into()))
         }
     }
 
@@ -131,6 +131,8 @@ pub fn describe(
         Statement::DropClusterReplicas(stmt) => {
             Some(ddl::describe_drop_cluster_replica(&scx, stmt)?)
         }
+        Statement::GrantPrivileges(stmt) => Some(ddl::describe_grant_privileges(&scx, stmt)?),
+        Statement::RevokePrivileges(stmt) => Some(ddl::describe_revoke_privileges(&scx, stmt)?),
         Statement::AlterObjectRename(stmt) => Some(ddl::describe_alter_object_rename(&scx, stmt)?),
         Statement::AlterIndex(stmt) => Some(ddl::describe_alter_index_options(&scx, stmt)?),
         Statement::AlterSecret(stmt) => Some(ddl::describe_alter_secret_options(&scx, stmt)?),
@@ -348,6 +350,8 @@ pub fn plan(
             let (stmt, _) = resolve_stmt!(Statement::DropClusterReplicas, scx, stmt);
             ddl::plan_drop_cluster_replica(scx, stmt)
         }
+        Statement::GrantPrivileges(stmt) => ddl::plan_grant_privileges(scx, stmt),
+        Statement::RevokePrivileges(stmt) => ddl::plan_revoke_privileges(scx, stmt),
         stmt @ Statement::AlterIndex(_) => {
             let (stmt, _) = resolve_stmt!(Statement::AlterIndex, scx, stmt);
             ddl::plan_alter_index_options(scx, stmt)
@@ -22,6 +22,7 @@ use bytes::Bytes;
 use chrono::{NaiveDate, NaiveDateTime};
 use globset::GlobBuilder;
 use itertools::Itertools;
+use ordered_float::OrderedFloat;
 use prost::Message;
 use regex::Regex;
 use reqwest::Url;
@@ -33,15 +34,16 @@ use mz_dataflow_types::sinks::{
     PersistSinkConnectorBuilder, SinkConnectorBuilder, SinkEnvelope,
 };
 use mz_dataflow_types::sources::encoding::{
-    included_column_desc, AvroEncoding, ColumnSpec, CsvEncoding, DataEncoding, ProtobufEncoding,
-    RegexEncoding, SourceDataEncoding,
+    included_column_desc, AvroEncoding, AvroOcfEncoding, ColumnSpec, CsvEncoding, DataEncoding,
+    ParquetEncoding, ProtobufEncoding, RegexEncoding, SourceDataEncoding,
 };
 use mz_dataflow_types::sources::{
-    provide_default_metadata, ConnectorInner, DebeziumDedupProjection, DebeziumEnvelope,
-    DebeziumMode, DebeziumSourceProjection, DebeziumTransactionMetadata, ExternalSourceConnector,
-    IncludedColumnPos, KafkaSourceConnector, KeyEnvelope, KinesisSourceConnector,
+    provide_default_metadata, AuctionTable, ConnectorInner, DebeziumDedupProjection,
+    DebeziumEnvelope, DebeziumMode, DebeziumSourceProjection, DebeziumTransactionMetadata,
+    DecodeErrorPolicy, ExternalSourceConnector, IncludedColumnPos, KafkaSourceConnector,
+    KafkaTopics, KeyEnvelope, KinesisSourceConnector, LoadGenerator, LoadGeneratorSourceConnector,
     PersistSourceConnector, PostgresSourceConnector, PubNubSourceConnector, S3SourceConnector,
-    SourceConnector, SourceEnvelope, Timeline, UnplannedSourceEnvelope, UpsertStyle,
+    SourceConnector, SourceEnvelope, Timeline, TpchTable, UnplannedSourceEnvelope, UpsertStyle,
 };
 use mz_expr::CollectionPlan;
 use mz_interchange::avro::{self, AvroSchemaGenerator};
@@ -68,19 +70,23 @@ use crate::ast::{
     CreateViewsSourceTarget, CreateViewsStatement, CsrConnectorAvro, CsrConnectorProto,
     CsrSeedCompiled, CsrSeedCompiledOrLegacy, CsvColumns, DbzMode, DropClusterReplicasStatement,
     DropClustersStatement, DropDatabaseStatement, DropObjectsStatement, DropRolesStatement,
-    DropSchemaStatement, Envelope, Expr, Format, Ident, IfExistsBehavior, KafkaConsistency,
-    KeyConstraint, ObjectType, Op, ProtobufSchema, Query, Raw, Select, SelectItem, SetExpr,
-    SourceIncludeMetadata, SourceIncludeMetadataType, Statement, SubscriptPosition,
-    TableConstraint, TableFactor, TableWithJoins, UnresolvedDatabaseName, UnresolvedObjectName,
-    Value, ViewDefinition, WithOption,
+    DropSchemaStatement, Envelope, Expr, Format, GrantPrivilegesStatement, Ident, IfExistsBehavior,
+    KafkaConsistency, KafkaTopic, KeyConstraint, ObjectType, OnDecodeError, Op, Privilege,
+    PrivilegeObjects, PrivilegeSpecification, ProtobufSchema, Query, Raw,
+    RevokePrivilegesStatement, Select, SelectItem, SetExpr, SourceIncludeMetadata,
+    SourceIncludeMetadataType, Statement, SubscriptPosition, TableConstraint, TableFactor,
+    TableWithJoins, UnresolvedDatabaseName, UnresolvedObjectName, Value, ViewDefinition,
+    WithOption,
+};
+use crate::catalog::{
+    CatalogItem, CatalogItemType, CatalogType, CatalogTypeDetails, PUBLIC_ROLE_ID,
 };
-use crate::catalog::{CatalogItem, CatalogItemType, CatalogType, CatalogTypeDetails};
 use crate::connectors::populate_connectors;
 use crate::kafka_util;
 use crate::names::{
-    resolve_names_data_type, resolve_object_name, Aug, FullSchemaName, QualifiedObjectName,
-    RawDatabaseSpecifier, ResolvedClusterName, ResolvedDataType, ResolvedDatabaseSpecifier,
-    ResolvedObjectName, SchemaSpecifier,
+    resolve_names_data_type, resolve_object_name, Aug, FullSchemaName, ObjectId,
+    QualifiedObjectName, RawDatabaseSpecifier, ResolvedClusterName, ResolvedDataType,
+    ResolvedDatabaseSpecifier, ResolvedObjectName, SchemaSpecifier,
 };
 use crate::normalize;
 use crate::normalize::ident;
@@ -94,8 +100,9 @@ use crate::plan::{
     CreateDatabasePlan, CreateIndexPlan, CreateRolePlan, CreateSchemaPlan, CreateSecretPlan,
     CreateSinkPlan, CreateSourcePlan, CreateTablePlan, CreateTypePlan, CreateViewPlan,
     CreateViewsPlan, DropComputeInstanceReplicaPlan, DropComputeInstancesPlan, DropDatabasePlan,
-    DropItemsPlan, DropRolesPlan, DropSchemaPlan, Index, IndexOption, IndexOptionName, Params,
-    Plan, ReplicaConfig, Secret, Sink, Source, Table, Type, View,
+    DropItemsPlan, DropRolesPlan, DropSchemaPlan, GrantPrivilegesPlan, Index, IndexOption,
+    IndexOptionName, Params, Plan, ReplicaConfig, RevokePrivilegesPlan, Secret, Sink, Source,
+    Table, Type, View,
 };
 use crate::pure::Schema;
 
@@ -307,12 +314,16 @@ pub fn plan_create_source(
 ) -> Result<Plan, anyhow::Error> {
     let mut depends_on = vec![];
     let stmt = populate_connectors(stmt, scx.catalog, &mut depends_on)?;
+    if let CreateSourceConnector::DecodeErrors { .. } = &stmt.connector {
+        return plan_create_decode_errors_source(scx, stmt, depends_on);
+    }
     let CreateSourceStatement {
         name,
         col_names,
         connector,
         with_options,
         envelope,
+        on_decode_error,
         if_not_exists,
         materialized,
         format,
@@ -359,6 +370,25 @@ pub fn plan_create_source(
         bail_unsupported!("INCLUDE metadata with non-Kafka sources");
     }
 
+    let decode_errors = match on_decode_error {
+        None | Some(OnDecodeError::Fail) => DecodeErrorPolicy::Fail,
+        Some(OnDecodeError::Skip) => DecodeErrorPolicy::Skip,
+        Some(OnDecodeError::Divert) => DecodeErrorPolicy::Divert,
+    };
+    if decode_errors != DecodeErrorPolicy::Fail {
+        if !matches!(
+            connector,
+            CreateSourceConnector::Kafka(_)
+                | CreateSourceConnector::Kinesis { .. }
+                | CreateSourceConnector::S3 { .. }
+        ) {
+            bail!("ON DECODE ERROR is only supported for Kafka, Kinesis, and S3 sources");
+        }
+        if let Envelope::CdcV2 = envelope {
+            bail_unsupported!("ON DECODE ERROR with ENVELOPE MATERIALIZE");
+        }
+    }
+
     let (external_connector, encoding) = match connector {
         CreateSourceConnector::Kafka(kafka) => {
             let (broker, options) = match &kafka.connector {
@@ -399,6 +429,19 @@ pub fn plan_create_source(
                 _ => bail!("start_offset must be a nonnegative integer"),
             };
 
+            let topics = match &kafka.topic {
+                KafkaTopic::Names(names) => KafkaTopics::Names(names.clone()),
+                KafkaTopic::Pattern(pattern) => {
+                    if let Err(e) = KafkaTopics::compile_pattern(pattern) {
+                        bail!("invalid TOPIC PATTERN: {}", e);
+                    }
+                    KafkaTopics::Pattern(pattern.clone())
+                }
+            };
+            if topics.single().is_none() && with_options.contains_key("start_offset") {
+                bail!("start_offset is only supported for Kafka sources that read a single topic");
+            }
+
             let mut start_offsets = HashMap::new();
             match with_options.remove("start_offset") {
                 None => {
@@ -424,7 +467,7 @@ pub fn plan_create_source(
 
             let mut connector = KafkaSourceConnector {
                 addrs: broker.parse()?,
-                topic: kafka.topic.clone(),
+                topics,
                 config_options,
                 start_offsets,
                 group_id_prefix,
@@ -476,12 +519,10 @@ pub fn plan_create_source(
                         connector.include_partition = unwrap_name(item.alias, "partition", pos);
                     }
                     SourceIncludeMetadataType::Topic => {
-                        // TODO(bwm): This requires deeper thought, the current structure of the
-                        // code requires us to clone the topic name around all over the place
-                        // whether or not anyone ever uses it. Considering we expect the
-                        // overwhelming majority of people will *not* want topics in dataflows that
-                        // is an unnacceptable cost.
-                        bail_unsupported!("INCLUDE TOPIC");
+                        // Including the topic makes the source carry topic names
+                        // around with every message, see
+                        // `KafkaSourceConnector::topic_qualified_partitions`.
+                        connector.include_topic = unwrap_name(item.alias, "topic", pos);
                     }
                     SourceIncludeMetadataType::Offset => {
                         connector.include_offset = unwrap_name(item.alias, "offset", pos);
@@ -554,7 +595,9 @@ pub fn plan_create_source(
                     .transpose()?,
                 aws,
                 compression: match compression {
+                    Compression::Bzip2 => mz_dataflow_types::sources::Compression::Bzip2,
                     Compression::Gzip => mz_dataflow_types::sources::Compression::Gzip,
+                    Compression::Zstd => mz_dataflow_types::sources::Compression::Zstd,
                     Compression::None => mz_dataflow_types::sources::Compression::None,
                 },
             });
@@ -674,6 +717,89 @@ pub fn plan_create_source(
                 SourceDataEncoding::Single(DataEncoding::RowCodec(desc)),
             )
         }
+        CreateSourceConnector::LoadGenerator {
+            generator,
+            table,
+            tick_interval,
+            scale_factor,
+        } => {
+            if !matches!(format, CreateSourceFormat::None) {
+                bail!("CREATE SOURCE ... LOAD GENERATOR does not support FORMAT");
+            }
+            if envelope != Envelope::None {
+                bail!("CREATE SOURCE ... LOAD GENERATOR does not support ENVELOPE");
+            }
+
+            let table = table.as_ref().map(|t| normalize::ident(t.clone()));
+            let generator = match generator {
+                mz_sql_parser::ast::LoadGenerator::Counter => {
+                    if let Some(table) = table {
+                        bail!(
+                            "LOAD GENERATOR COUNTER does not have table {}",
+                            table.quoted()
+                        );
+                    }
+                    if scale_factor.is_some() {
+                        bail!("LOAD GENERATOR COUNTER does not support SCALE FACTOR");
+                    }
+                    LoadGenerator::Counter
+                }
+                mz_sql_parser::ast::LoadGenerator::Auction => {
+                    if scale_factor.is_some() {
+                        bail!("LOAD GENERATOR AUCTION does not support SCALE FACTOR");
+                    }
+                    let table = plan_load_generator_table(
+                        "AUCTION",
+                        AuctionTable::ALL,
+                        AuctionTable::name,
+                        table,
+                    )?;
+                    LoadGenerator::Auction { table }
+                }
+                mz_sql_parser::ast::LoadGenerator::Tpch => {
+                    if tick_interval.is_some() {
+                        bail!("LOAD GENERATOR TPCH does not support TICK INTERVAL");
+                    }
+                    let table =
+                        plan_load_generator_table("TPCH", TpchTable::ALL, TpchTable::name, table)?;
+                    let scale_factor = match scale_factor {
+                        None => 1.0,
+                        Some(sf) => match sf.parse::<f64>() {
+                            Ok(sf) if sf > 0.0 && sf.is_finite() => sf,
+                            _ => bail!("SCALE FACTOR must be a positive number"),
+                        },
+                    };
+                    LoadGenerator::Tpch {
+                        table,
+                        scale_factor: OrderedFloat(scale_factor),
+                    }
+                }
+            };
+
+            let tick_interval = match tick_interval {
+                None => Duration::from_secs(1),
+                Some(interval) => {
+                    let interval = mz_repr::util::parse_duration(interval)?;
+                    if interval == Duration::ZERO {
+                        bail!("TICK INTERVAL must be positive");
+                    }
+                    interval
+                }
+            };
+
+            let desc = generator.desc();
+            let connector = ExternalSourceConnector::LoadGenerator(LoadGeneratorSourceConnector {
+                generator,
+                tick_interval,
+            });
+            (
+                connector,
+                SourceDataEncoding::Single(DataEncoding::RowCodec(desc)),
+            )
+        }
+        CreateSourceConnector::DecodeErrors { .. } => {
+            unreachable!("DECODE ERRORS sources are planned separately")
+        }
     };
     let (key_desc, value_desc) = encoding.desc()?;
 
@@ -982,6 +1108,7 @@ pub fn plan_create_source(
             metadata_columns: metadata_column_types,
             ts_frequency,
             timeline,
+            decode_errors,
         },
         desc,
         depends_on,
@@ -997,6 +1124,126 @@ pub fn plan_create_source(
     }))
 }
 
+/// Plans a source that reads back the records that failed to decode in a source
+/// created with `ON DECODE ERROR DIVERT`.
+///
+/// The new source does not ingest the upstream data itself. It is rendered
+/// from the ingestion of the diverting source, and produces the records that the
+/// diverting source's decode operator failed to decode.
+fn plan_create_decode_errors_source(
+    scx: &StatementContext,
+    stmt: CreateSourceStatement<Aug>,
+    mut depends_on: Vec<GlobalId>,
+) -> Result<Plan, anyhow::Error> {
+    let CreateSourceStatement {
+        name,
+        col_names,
+        connector,
+        with_options,
+        include_metadata,
+        format,
+        envelope,
+        on_decode_error,
+        if_not_exists,
+        materialized,
+        key_constraint,
+    } = &stmt;
+
+    let source_name = match connector {
+        CreateSourceConnector::DecodeErrors { source } => source,
+        _ => unreachable!("only called for DECODE ERRORS sources"),
+    };
+    if !matches!(format, CreateSourceFormat::None)
+        || !include_metadata.is_empty()
+        || envelope.is_some()
+        || on_decode_error.is_some()
+    {
+        bail!(
+            "FROM DECODE ERRORS sources do not support FORMAT, INCLUDE, ENVELOPE, \
+             or ON DECODE ERROR"
+        );
+    }
+    if key_constraint.is_some() {
+        bail!("FROM DECODE ERRORS sources do not support key constraints");
+    }
+    normalize::ensure_empty_options(&normalize::options(with_options), "CREATE SOURCE")?;
+
+    let item = scx.get_item_by_resolved_name(source_name)?;
+    let connector = match item.source_connector() {
+        Ok(SourceConnector::External {
+            connector,
+            encoding,
+            ts_frequency,
+            timeline,
+            decode_errors: DecodeErrorPolicy::Divert,
+            ..
+        }) => SourceConnector::External {
+            connector: connector.clone(),
+            encoding: encoding.clone(),
+            envelope: SourceEnvelope::None(KeyEnvelope::None),
+            metadata_columns: vec![],
+            ts_frequency: *ts_frequency,
+            timeline: timeline.clone(),
+            decode_errors: DecodeErrorPolicy::Emit { source: item.id() },
+        },
+        _ => bail!(
+            "{} is not a source created with ON DECODE ERROR DIVERT",
+            source_name.full_name_str()
+        ),
+    };
+    depends_on.push(item.id());
+
+    let desc = plan_utils::maybe_rename_columns(
+        format!("source {}", name),
+        DecodeErrorPolicy::error_desc(),
+        col_names,
+    )?;
+    let if_not_exists = *if_not_exists;
+    let materialized = *materialized;
+    let name = scx.allocate_qualified_name(normalize::unresolved_object_name(name.clone())?)?;
+    let create_sql = normalize::create_statement(&scx, Statement::CreateSource(stmt))?;
+
+    Ok(Plan::CreateSource(CreateSourcePlan {
+        name,
+        source: Source {
+            create_sql,
+            connector,
+            desc,
+            depends_on,
+        },
+        if_not_exists,
+        materialized,
+    }))
+}
+
+/// Resolves the `TABLE` clause of a `LOAD GENERATOR` that produces more than
+/// one table.
+fn plan_load_generator_table<T: Copy>(
+    generator: &str,
+    tables: &[T],
+    name: fn(&T) -> &'static str,
+    table: Option<String>,
+) -> Result<T, anyhow::Error> {
+    match table {
+        Some(table) => tables
+            .iter()
+            .find(|t| name(*t) == table)
+            .copied()
+            .ok_or_else(|| {
+                anyhow!(
+                    "LOAD GENERATOR {} does not have table {}",
+                    generator,
+                    table.quoted()
+                )
+            }),
+        None => bail!(
+            "LOAD GENERATOR {} requires a TABLE, one of: {}",
+            generator,
+            tables.iter().map(name).join(", ")
+        ),
+    }
+}
+
 fn typecheck_debezium(value_desc: &RelationDesc) -> Result<(usize, usize), anyhow::Error> {
     let (before_idx, before_ty) = value_desc
         .get_by_name(&"before".into())
@@ -1385,6 +1632,22 @@ fn get_encoding_inner(
                 })
             }
         }
+        Format::AvroOcf(schema) => DataEncoding::AvroOcf(AvroOcfEncoding {
+            reader_schema: match schema {
+                mz_sql_parser::ast::Schema::Inline(schema) => schema.clone(),
+                mz_sql_parser::ast::Schema::File(_) => {
+                    unreachable!("File schema should already have been inlined")
+                }
+            },
+        }),
+        Format::Parquet(schema) => DataEncoding::Parquet(ParquetEncoding {
+            schema: match schema {
+                mz_sql_parser::ast::Schema::Inline(schema) => schema.clone(),
+                mz_sql_parser::ast::Schema::File(_) => {
+                    unreachable!("File schema should already have been inlined")
+                }
+            },
+        }),
         Format::Protobuf(schema) => match schema {
             ProtobufSchema::Csr {
                 csr_connector:
@@ -1523,6 +1786,8 @@ fn get_key_envelope(
                     }
                     DataEncoding::Bytes | DataEncoding::Text => false,
                     DataEncoding::Avro(_)
+                    | DataEncoding::AvroOcf(_)
+                    | DataEncoding::Parquet(_)
                     | DataEncoding::Csv(_)
                     | DataEncoding::Protobuf(_)
                     | DataEncoding::Regex { .. } => true,
@@ -2757,6 +3022,7 @@ pub fn plan_create_role(
 ) -> Result<Plan, anyhow::Error> {
     let mut login = None;
     let mut super_user = None;
+    let mut password = None;
     for option in options {
         match option {
             CreateRoleOption::Login | CreateRoleOption::NoLogin if login.is_some() => {
@@ -2765,10 +3031,14 @@ pub fn plan_create_role(
             CreateRoleOption::SuperUser | CreateRoleOption::NoSuperUser if super_user.is_some() => {
                 bail!("conflicting or redundant options");
             }
+            CreateRoleOption::Password(_) if password.is_some() => {
+                bail!("conflicting or redundant options");
+            }
             CreateRoleOption::Login => login = Some(true),
             CreateRoleOption::NoLogin => login = Some(false),
             CreateRoleOption::SuperUser => super_user = Some(true),
             CreateRoleOption::NoSuperUser => super_user = Some(false),
+            CreateRoleOption::Password(p) => password = Some(p),
         }
     }
     if is_user && login.is_none() {
@@ -2777,11 +3047,17 @@ pub fn plan_create_role(
     if login != Some(true) {
         bail_unsupported!("non-login users");
     }
-    if super_user != Some(true) {
-        bail_unsupported!("non-superusers");
+    if matches!(&password, Some(Some(p)) if p.is_empty()) {
+        bail!("password must not be empty");
+    }
+    let name = normalize::ident(name);
+    if name == "public" {
+        bail!("role name \"public\" is reserved");
     }
     Ok(Plan::CreateRole(CreateRolePlan {
-        name: normalize::ident(name),
+        name,
+        superuser: super_user.unwrap_or(false),
+        password: password.flatten(),
     }))
 }
 
@@ -3370,6 +3646,175 @@ pub fn plan_drop_item(
     Ok(Some(catalog_entry.id()))
 }
 
+pub fn describe_grant_privileges(
+    _: &StatementContext,
+    _: &GrantPrivilegesStatement<Raw>,
+) -> Result<StatementDesc, anyhow::Error> {
+    Ok(StatementDesc::new(None))
+}
+
+pub fn plan_grant_privileges(
+    scx: &StatementContext,
+    GrantPrivilegesStatement {
+        privileges,
+        objects,
+        roles,
+    }: GrantPrivilegesStatement<Raw>,
+) -> Result<Plan, anyhow::Error> {
+    let privileges = plan_privileges(&objects, privileges)?;
+    let objects = plan_privilege_objects(scx, objects)?;
+    let grantees = plan_grantees(scx, roles)?;
+    Ok(Plan::GrantPrivileges(GrantPrivilegesPlan {
+        objects,
+        privileges,
+        grantees,
+    }))
+}
+
+pub fn describe_revoke_privileges(
+    _: &StatementContext,
+    _: &RevokePrivilegesStatement<Raw>,
+) -> Result<StatementDesc, anyhow::Error> {
+    Ok(StatementDesc::new(None))
+}
+
+pub fn plan_revoke_privileges(
+    scx: &StatementContext,
+    RevokePrivilegesStatement {
+        privileges,
+        objects,
+        roles,
+    }: RevokePrivilegesStatement<Raw>,
+) -> Result<Plan, anyhow::Error> {
+    let privileges = plan_privileges(&objects, privileges)?;
+    let objects = plan_privilege_objects(scx, objects)?;
+    let grantees = plan_grantees(scx, roles)?;
+    Ok(Plan::RevokePrivileges(RevokePrivilegesPlan {
+        objects,
+        privileges,
+        grantees,
+    }))
+}
+
+/// Returns the privileges that can be granted on objects of the specified
+/// type, along with the name of the type for use in error messages.
+fn applicable_privileges(objects: &PrivilegeObjects<Raw>) -> (&'static str, &'static [Privilege]) {
+    match objects {
+        PrivilegeObjects::Databases(_) => ("database", &[Privilege::Create]),
+        PrivilegeObjects::Schemas(_) => ("schema", &[Privilege::Usage, Privilege::Create]),
+        PrivilegeObjects::Clusters(_) => ("cluster", &[Privilege::Usage, Privilege::Create]),
+        PrivilegeObjects::Items { object_type, .. } => match object_type {
+            ObjectType::Table => (
+                "table",
+                &[
+                    Privilege::Select,
+                    Privilege::Insert,
+                    Privilege::Update,
+                    Privilege::Delete,
+                ],
+            ),
+            ObjectType::View => ("view", &[Privilege::Select]),
+            ObjectType::Source => ("source", &[Privilege::Select]),
+            ObjectType::Sink => ("sink", &[]),
+            ObjectType::Secret => ("secret", &[Privilege::Usage]),
+            ObjectType::Connector => ("connector", &[Privilege::Usage]),
+            ObjectType::Index
+            | ObjectType::Type
+            | ObjectType::Role
+            | ObjectType::Cluster
+            | ObjectType::ClusterReplica
+            | ObjectType::Object => unreachable!("cannot grant privileges on {}", object_type),
+        },
+    }
+}
+
+fn plan_privileges(
+    objects: &PrivilegeObjects<Raw>,
+    privileges: PrivilegeSpecification,
+) -> Result<BTreeSet<Privilege>, anyhow::Error> {
+    let (object_type, applicable) = applicable_privileges(objects);
+    match privileges {
+        PrivilegeSpecification::All => Ok(applicable.iter().copied().collect()),
+        PrivilegeSpecification::Privileges(privileges) => {
+            for privilege in &privileges {
+                if !applicable.contains(privilege) {
+                    bail!("invalid privilege type {} for {}", privilege, object_type);
+                }
+            }
+            Ok(privileges.into_iter().collect())
+        }
+    }
+}
+
+fn plan_privilege_objects(
+    scx: &StatementContext,
+    objects: PrivilegeObjects<Raw>,
+) -> Result<Vec<ObjectId>, anyhow::Error> {
+    let mut out = vec![];
+    match objects {
+        PrivilegeObjects::Databases(names) => {
+            for name in names {
+                out.push(ObjectId::Database(scx.resolve_database(&name)?.id()));
+            }
+        }
+        PrivilegeObjects::Schemas(names) => {
+            for name in names {
+                let schema = scx.resolve_schema(name)?;
+                match (schema.database(), schema.id()) {
+                    (ResolvedDatabaseSpecifier::Id(_), SchemaSpecifier::Id(id)) => {
+                        out.push(ObjectId::Schema(*id))
+                    }
+                    _ => bail!(
+                        "cannot grant privileges on schema {} because it is required by the \
+                         database system",
+                        schema.name().schema
+                    ),
+                }
+            }
+        }
+        PrivilegeObjects::Items { object_type, names } => {
+            for name in names {
+                let entry = scx.resolve_item(name)?;
+                let full_name = scx.catalog.resolve_full_name(entry.name());
+                if entry.id().is_system() {
+                    bail!(
+                        "cannot grant privileges on {} because it is required by the database \
+                         system",
+                        full_name
+                    );
+                }
+                if entry.item_type() != object_type {
+                    bail!("{} is not of type {}", full_name, object_type);
+                }
+                out.push(ObjectId::Item(entry.id()));
+            }
+        }
+        PrivilegeObjects::Clusters(names) => {
+            for name in names {
+                let instance = scx.catalog.resolve_compute_instance(Some(name.as_str()))?;
+                out.push(ObjectId::ComputeInstance(instance.id()));
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn plan_grantees(scx: &StatementContext, roles: Vec<Ident>) -> Result<Vec<i64>, anyhow::Error> {
+    let mut out = vec![];
+    for role in roles {
+        let name = normalize::ident(role);
+        let id = if name == "public" {
+            PUBLIC_ROLE_ID
+        } else {
+            scx.catalog.resolve_role(&name)?.id()
+        };
+        if !out.contains(&id) {
+            out.push(id);
+        }
+    }
+    Ok(out)
+}
+
 with_options! {
     struct IndexWithOptions {
         logical_compaction_window: String,
@@ -24,8 +24,8 @@ use mz_sql_parser::ast::AstInfo;
 
 use crate::ast::{
     CopyDirection, CopyRelation, CopyStatement, CopyTarget, CreateViewStatement, DeleteStatement,
-    ExplainStage, ExplainStatement, Explainee, Ident, InsertStatement, Query, SelectStatement,
-    Statement, TailRelation, TailStatement, UpdateStatement, ViewDefinition,
+    ExplainFormat, ExplainStage, ExplainStatement, Explainee, Ident, InsertStatement, Query,
+    SelectStatement, Statement, TailRelation, TailStatement, UpdateStatement, ViewDefinition,
 };
 use crate::catalog::CatalogItemType;
 use crate::names::{resolve_names, Aug, ResolvedObjectName};
@@ -177,6 +177,7 @@ pub fn describe_explain(
             ExplainStage::OptimizedPlan { .. } => "Optimized Plan",
             ExplainStage::PhysicalPlan => "Physical Plan",
             ExplainStage::Timestamp => "Timestamp",
+            ExplainStage::Analyze => "Analyzed Plan",
         },
         ScalarType::String.nullable(false),
     )))
@@ -201,16 +202,27 @@ pub fn plan_explain(
         stage,
         explainee,
         options,
+        format,
     }: ExplainStatement<Aug>,
     params: &Params,
 ) -> Result<Plan, anyhow::Error> {
+    if format == ExplainFormat::Json
+        && matches!(
+            stage,
+            ExplainStage::QueryGraph | ExplainStage::OptimizedQueryGraph
+        )
+    {
+        bail_unsupported!("EXPLAIN QUERY GRAPH AS JSON");
+    }
     let is_view = matches!(explainee, Explainee::View(_));
+    let mut view_id = None;
     let query = match explainee {
         Explainee::View(name) => {
             let view = scx.get_item_by_resolved_name(&name)?;
             if view.item_type() != CatalogItemType::View {
                 bail!("Expected {} to be a view, not a {}", name, view.item_type());
             }
+            view_id = Some(view.id());
             let parsed = crate::parse::parse(view.create_sql())
                 .expect("Sql for existing view should be valid sql");
             let query = match parsed.into_last() {
@@ -247,6 +259,8 @@ pub fn plan_explain(
         row_set_finishing: finishing,
         stage,
         options,
+        format,
+        view_id,
     }))
 }
 
@@ -14,10 +14,10 @@
 
 use crate::ast::{
     CommitStatement, RollbackStatement, SetTransactionStatement, StartTransactionStatement,
-    TransactionAccessMode, TransactionMode,
+    TransactionAccessMode, TransactionIsolationLevel, TransactionMode,
 };
 use crate::plan::statement::{StatementContext, StatementDesc};
-use crate::plan::{Plan, StartTransactionPlan};
+use crate::plan::{Plan, SetTransactionPlan, StartTransactionPlan};
 
 pub fn describe_start_transaction(
     _: &StatementContext,
@@ -30,39 +30,57 @@ pub fn plan_start_transaction(
     _: &StatementContext,
     StartTransactionStatement { modes }: StartTransactionStatement,
 ) -> Result<Plan, anyhow::Error> {
-    let access = verify_transaction_modes(modes)?;
-    Ok(Plan::StartTransaction(StartTransactionPlan { access }))
+    let (access, isolation_level) = verify_transaction_modes(modes)?;
+    Ok(Plan::StartTransaction(StartTransactionPlan {
+        access,
+        isolation_level,
+    }))
 }
 
 pub fn describe_set_transaction(
     _: &StatementContext,
     _: &SetTransactionStatement,
 ) -> Result<StatementDesc, anyhow::Error> {
-    bail_unsupported!("SET TRANSACTION")
+    Ok(StatementDesc::new(None))
 }
 
 pub fn plan_set_transaction(
     _: &StatementContext,
-    _: SetTransactionStatement,
+    SetTransactionStatement { local, modes }: SetTransactionStatement,
 ) -> Result<Plan, anyhow::Error> {
-    bail_unsupported!("SET TRANSACTION")
+    let (access, isolation_level) = verify_transaction_modes(modes)?;
+    if !local && access.is_some() {
+        bail_unsupported!("SET SESSION CHARACTERISTICS AS TRANSACTION with an access mode");
+    }
+    Ok(Plan::SetTransaction(SetTransactionPlan {
+        local,
+        access,
+        isolation_level,
+    }))
 }
 
 fn verify_transaction_modes(
     modes: Vec<TransactionMode>,
-) -> Result<Option<TransactionAccessMode>, anyhow::Error> {
+) -> Result<
+    (
+        Option<TransactionAccessMode>,
+        Option<TransactionIsolationLevel>,
+    ),
+    anyhow::Error,
+> {
     let mut access = None;
+    let mut isolation_level = None;
     for mode in modes {
         match mode {
-            // Although we are only serializable, it's not wrong to accept lower isolation
-            // levels because we still meet the required guarantees for those.
-            TransactionMode::IsolationLevel(_) => {}
+            TransactionMode::IsolationLevel(level) => {
+                isolation_level = Some(level);
+            }
             TransactionMode::AccessMode(mode) => {
                 access = Some(mode);
             }
         }
     }
-    Ok(access)
+    Ok((access, isolation_level))
 }
 
 pub fn describe_rollback(
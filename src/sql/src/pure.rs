@@ -18,7 +18,7 @@ use std::sync::Arc;
 
 use anyhow::{anyhow, bail, Context};
 use aws_arn::ARN;
-use mz_sql_parser::ast::{CsrConnector, KafkaConnector, KafkaSourceConnector};
+use mz_sql_parser::ast::{CsrConnector, KafkaConnector, KafkaSourceConnector, KafkaTopic};
 use prost::Message;
 use protobuf_native::compiler::{SourceTreeDescriptorDatabase, VirtualSourceTree};
 use protobuf_native::MessageLite;
@@ -66,6 +66,8 @@ pub async fn purify_create_source(
 
     let mut with_options_map = normalize::options(with_options);
     let mut config_options = BTreeMap::new();
+    // The topic whose schema registry subjects describe a Kafka source.
+    let mut kafka_schema_topic = None;
 
     match connector {
         CreateSourceConnector::Kafka(KafkaSourceConnector {
@@ -102,19 +104,43 @@ pub async fn purify_create_source(
                 // Verify that the provided security options are valid and then test them.
                 kafka_util::extract_config(&mut with_options_map)?
             };
-            let consumer = kafka_util::create_consumer(&broker, &topic, &config_options)
+            let single_topic = match topic {
+                KafkaTopic::Names(names) if names.len() == 1 => Some(names[0].as_str()),
+                _ => None,
+            };
+            let consumer = kafka_util::create_consumer(&broker, single_topic, &config_options)
                 .await
                 .map_err(|e| anyhow!("Failed to create and connect Kafka consumer: {}", e))?;
 
+            // All topics of a source are expected to share a schema, so the
+            // first of them stands in for the rest.
+            kafka_schema_topic = match topic {
+                KafkaTopic::Names(names) => names.first().cloned(),
+                KafkaTopic::Pattern(pattern) => {
+                    kafka_util::lookup_matching_topics(Arc::clone(&consumer), pattern)
+                        .await?
+                        .into_iter()
+                        .next()
+                }
+            };
+
             // Translate `kafka_time_offset` to `start_offset`.
-            match kafka_util::lookup_start_offsets(
-                Arc::clone(&consumer),
-                &topic,
-                &with_options_map,
-                now,
-            )
-            .await?
-            {
+            let start_offsets = match single_topic {
+                Some(topic) => {
+                    kafka_util::lookup_start_offsets(
+                        Arc::clone(&consumer),
+                        topic,
+                        &with_options_map,
+                        now,
+                    )
+                    .await?
+                }
+                None if with_options_map.contains_key("kafka_time_offset") => bail!(
+                    "kafka_time_offset is only supported for Kafka sources that read a single topic"
+                ),
+                None => None,
+            };
+            match start_offsets {
                 Some(start_offsets) => {
                     // Drop `kafka_time_offset`
                     with_options.retain(|val| match val {
@@ -173,9 +199,19 @@ pub async fn purify_create_source(
         }
         CreateSourceConnector::PubNub { .. } => (),
         CreateSourceConnector::Persist { .. } => (),
+        CreateSourceConnector::LoadGenerator { .. } => (),
+        CreateSourceConnector::DecodeErrors { .. } => (),
     }
 
-    purify_source_format(format, connector, &envelope, &config_options, with_options).await?;
+    purify_source_format(
+        format,
+        connector,
+        kafka_schema_topic.as_deref(),
+        &envelope,
+        &config_options,
+        with_options,
+    )
+    .await?;
 
     Ok(stmt)
 }
@@ -183,6 +219,7 @@ pub async fn purify_create_source(
 async fn purify_source_format(
     format: &mut CreateSourceFormat<Raw>,
     connector: &mut CreateSourceConnector<Raw>,
+    kafka_schema_topic: Option<&str>,
     envelope: &Option<Envelope>,
     connector_options: &BTreeMap<String, String>,
     with_options: &Vec<WithOption<Raw>>,
@@ -219,6 +256,7 @@ async fn purify_source_format(
             purify_source_format_single(
                 format,
                 connector,
+                kafka_schema_topic,
                 envelope,
                 connector_options,
                 with_options,
@@ -227,10 +265,24 @@ async fn purify_source_format(
         }
 
         CreateSourceFormat::KeyValue { key, value: val } => {
-            purify_source_format_single(key, connector, envelope, connector_options, with_options)
-                .await?;
-            purify_source_format_single(val, connector, envelope, connector_options, with_options)
-                .await?;
+            purify_source_format_single(
+                key,
+                connector,
+                kafka_schema_topic,
+                envelope,
+                connector_options,
+                with_options,
+            )
+            .await?;
+            purify_source_format_single(
+                val,
+                connector,
+                kafka_schema_topic,
+                envelope,
+                connector_options,
+                with_options,
+            )
+            .await?;
         }
     }
     Ok(())
@@ -239,6 +291,7 @@ async fn purify_source_format(
 async fn purify_source_format_single(
     format: &mut Format<Raw>,
     connector: &mut CreateSourceConnector<Raw>,
+    kafka_schema_topic: Option<&str>,
     envelope: &Option<Envelope>,
     connector_options: &BTreeMap<String, String>,
     with_options: &Vec<WithOption<Raw>>,
@@ -246,8 +299,14 @@ async fn purify_source_format_single(
     match format {
         Format::Avro(schema) => match schema {
             AvroSchema::Csr { csr_connector } => {
-                purify_csr_connector_avro(connector, csr_connector, envelope, connector_options)
-                    .await?
+                purify_csr_connector_avro(
+                    connector,
+                    kafka_schema_topic,
+                    csr_connector,
+                    envelope,
+                    connector_options,
+                )
+                .await?
             }
             AvroSchema::InlineSchema {
                 schema: mz_sql_parser::ast::Schema::File(path),
@@ -277,8 +336,14 @@ async fn purify_source_format_single(
         },
         Format::Protobuf(schema) => match schema {
             ProtobufSchema::Csr { csr_connector } => {
-                purify_csr_connector_proto(connector, csr_connector, envelope, with_options)
-                    .await?;
+                purify_csr_connector_proto(
+                    connector,
+                    kafka_schema_topic,
+                    csr_connector,
+                    envelope,
+                    with_options,
+                )
+                .await?;
             }
             ProtobufSchema::InlineSchema {
                 message_name: _,
@@ -307,23 +372,43 @@ async fn purify_source_format_single(
                 }
             }
         }
+        Format::AvroOcf(schema) | Format::Parquet(schema) => {
+            if !matches!(connector, CreateSourceConnector::S3 { .. }) {
+                bail!("FORMAT AVRO OCF and FORMAT PARQUET are only supported for S3 sources");
+            }
+            if let mz_sql_parser::ast::Schema::File(path) = schema {
+                let file_schema = tokio::fs::read_to_string(path).await?;
+                *schema = mz_sql_parser::ast::Schema::Inline(file_schema);
+            }
+        }
         Format::Bytes | Format::Regex(_) | Format::Json | Format::Text => (),
     }
     Ok(())
 }
 
+/// Returns the topic whose `<topic>-key` and `<topic>-value` subjects hold the
+/// schemas for `connector`.
+fn csr_subject_topic<'a>(
+    connector: &CreateSourceConnector<Raw>,
+    kafka_schema_topic: Option<&'a str>,
+) -> Result<&'a str, anyhow::Error> {
+    match (connector, kafka_schema_topic) {
+        (CreateSourceConnector::Kafka(_), Some(topic)) => Ok(topic),
+        (CreateSourceConnector::Kafka(_), None) => bail!(
+            "Confluent Schema Registry requires at least one existing topic that matches TOPIC PATTERN"
+        ),
+        _ => bail!("Confluent Schema Registry is only supported with Kafka sources"),
+    }
+}
+
 async fn purify_csr_connector_proto(
     connector: &mut CreateSourceConnector<Raw>,
+    kafka_schema_topic: Option<&str>,
     csr_connector: &mut CsrConnectorProto<Raw>,
     envelope: &Option<Envelope>,
     with_options: &Vec<WithOption<Raw>>,
 ) -> Result<(), anyhow::Error> {
-    let topic = if let CreateSourceConnector::Kafka(KafkaSourceConnector { topic, .. }) = connector
-    {
-        topic
-    } else {
-        bail!("Confluent Schema Registry is only supported with Kafka sources")
-    };
+    let topic = csr_subject_topic(connector, kafka_schema_topic)?;
 
     let CsrConnectorProto {
         connector,
@@ -372,16 +457,12 @@ async fn purify_csr_connector_proto(
 
 async fn purify_csr_connector_avro(
     connector: &mut CreateSourceConnector<Raw>,
+    kafka_schema_topic: Option<&str>,
     csr_connector: &mut CsrConnectorAvro<Raw>,
     envelope: &Option<Envelope>,
     connector_options: &BTreeMap<String, String>,
 ) -> Result<(), anyhow::Error> {
-    let topic = if let CreateSourceConnector::Kafka(KafkaSourceConnector { topic, .. }) = connector
-    {
-        topic
-    } else {
-        bail!("Confluent Schema Registry is only supported with Kafka sources")
-    };
+    let topic = csr_subject_topic(connector, kafka_schema_topic)?;
 
     let CsrConnectorAvro {
         connector,
@@ -409,7 +490,7 @@ async fn purify_csr_connector_avro(
             key_schema,
             value_schema,
             ..
-        } = get_remote_csr_schema(ccsr_config, topic.clone()).await?;
+        } = get_remote_csr_schema(ccsr_config, topic.to_string()).await?;
         if matches!(envelope, Some(Envelope::Debezium(DbzMode::Upsert))) && key_schema.is_none() {
             bail!("Key schema is required for ENVELOPE DEBEZIUM UPSERT");
         }
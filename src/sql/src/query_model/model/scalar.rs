@@ -230,6 +230,34 @@ impl BoxScalarExpr {
         f(self);
     }
 
+    /// Returns the operands of this expression if it is a (possibly nested)
+    /// conjunction, or the expression itself otherwise.
+    pub fn conjuncts(&self) -> Vec<&BoxScalarExpr> {
+        match self {
+            BoxScalarExpr::CallBinary {
+                func: BinaryFunc::And,
+                expr1,
+                expr2,
+            } => {
+                let mut conjuncts = expr1.conjuncts();
+                conjuncts.extend(expr2.conjuncts());
+                conjuncts
+            }
+            _ => vec![self],
+        }
+    }
+
+    /// Returns the set of quantifiers referenced by this expression.
+    pub fn quantifiers(&self) -> QuantifierSet {
+        let mut quantifiers = QuantifierSet::new();
+        self.visit_post(&mut |expr| {
+            if let BoxScalarExpr::ColumnReference(c) = expr {
+                quantifiers.insert(c.quantifier_id);
+            }
+        });
+        quantifiers
+    }
+
     pub fn collect_column_references_from_context(
         &self,
         context: &QuantifierSet,
@@ -22,6 +22,21 @@ impl Model {
     pub fn optimize(&mut self) {
         rewrite_model(self);
     }
+
+    /// Apply only the rewrite rules with the given names to the model.
+    #[cfg(test)]
+    pub(crate) fn optimize_with_rules(&mut self, names: &[String]) -> Result<(), String> {
+        let mut rules = all_rules();
+        if let Some(name) = names
+            .iter()
+            .find(|name| !rules.iter().any(|rule| rule.name() == name.as_str()))
+        {
+            return Err(format!("unknown rewrite rule: {}", name));
+        }
+        rules.retain(|rule| names.iter().any(|name| name == rule.name()));
+        rewrite_model_with_rules(self, rules);
+        Ok(())
+    }
 }
 
 /// Trait that all rewrite rules must implement.
@@ -122,12 +137,23 @@ impl Default for VisitOrder {
     }
 }
 
-/// Apply all available rewrite rules to the model.
-pub fn rewrite_model(model: &mut Model) {
-    let rules: Vec<Box<dyn ApplyRule>> = vec![
+/// All available rewrite rules, in the order in which they are applied.
+fn all_rules() -> Vec<Box<dyn ApplyRule>> {
+    vec![
         // simplify outer joins first
         Box::new(rule::simplify_outer_joins::SimplifyOuterJoins),
-    ];
+        Box::new(rule::decorrelate_exists::DecorrelateExists),
+        Box::new(rule::push_down_predicates::PushDownPredicates),
+    ]
+}
+
+/// Apply all available rewrite rules to the model.
+pub fn rewrite_model(model: &mut Model) {
+    rewrite_model_with_rules(model, all_rules());
+}
+
+/// Apply the given rewrite rules to the model.
+fn rewrite_model_with_rules(model: &mut Model, rules: Vec<Box<dyn ApplyRule>>) {
     apply_rules_to_model(model, rules);
     model.garbage_collect();
 
@@ -0,0 +1,514 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Implements decorrelation of `EXISTS` subqueries.
+//!
+//! An existential quantifier that is only used as a conjunct of the
+//! predicates of its Select box is converted into a Foreach quantifier
+//! ranging over a new box that projects the distinct values of the
+//! subquery's correlation keys. The correlated equality predicates of the
+//! subquery become join predicates of the Select box. For example,
+//!
+//! ```text
+//! SELECT * FROM x WHERE EXISTS (SELECT * FROM y WHERE y.a = x.a)
+//! ```
+//!
+//! is rewritten as
+//!
+//! ```text
+//! SELECT x.* FROM x, (SELECT DISTINCT y.a FROM y) d WHERE d.a = x.a
+//! ```
+//!
+//! Uncorrelated subqueries are rewritten in the same way, with a distinct
+//! box without columns producing at most one row.
+//!
+//! Only `EXISTS` subqueries used as conjuncts, whose correlated predicates
+//! are all equalities, are decorrelated. `IN`, `NOT EXISTS` and scalar
+//! subqueries, `EXISTS` under a disjunction and non-equality correlation are
+//! left as they are, and fail to lower to MIR.
+
+use std::collections::HashSet;
+
+use crate::plan::expr::BinaryFunc;
+use crate::query_model::attribute::core::Attribute;
+use crate::query_model::model::{
+    BoxScalarExpr, BoxType, ColumnReference, DistinctOperation, QuantifierSet, QuantifierType,
+};
+use crate::query_model::rewrite::ApplyStrategy;
+use crate::query_model::rewrite::Rule;
+use crate::query_model::rewrite::VisitOrder;
+use crate::query_model::{BoxId, Model, QuantifierId};
+
+pub(crate) struct DecorrelateExists;
+
+/// A decorrelatable existential quantifier.
+pub(crate) struct ExistsMatch {
+    /// The Select box that owns the existential quantifier.
+    box_id: BoxId,
+    /// The existential quantifier.
+    quantifier_id: QuantifierId,
+    /// The chain of Select boxes at the top of the subquery containing
+    /// correlated predicates, ordered from the input box of the quantifier
+    /// downwards. For each box, the correlated predicates are given as pairs
+    /// of an inner expression, over the only quantifier of the box, and an
+    /// outer expression, over the quantifiers of `box_id`, that are required
+    /// to be equal.
+    chain: Vec<(BoxId, Vec<(BoxScalarExpr, BoxScalarExpr)>)>,
+}
+
+impl Rule for DecorrelateExists {
+    type Match = ExistsMatch;
+
+    fn name(&self) -> &'static str {
+        "DecorrelateExists"
+    }
+
+    fn strategy(&self) -> ApplyStrategy {
+        ApplyStrategy::AllBoxes(VisitOrder::Pre)
+    }
+
+    fn required_attributes(&self) -> HashSet<Box<dyn Attribute>> {
+        HashSet::new()
+    }
+
+    fn check(&self, model: &Model, box_id: BoxId) -> Option<Self::Match> {
+        let the_box = model.get_box(box_id);
+        let predicates = match &the_box.box_type {
+            BoxType::Select(select) => &select.predicates,
+            _ => return None,
+        };
+
+        let correlation_info = the_box.correlation_info();
+        for q in the_box.input_quantifiers() {
+            if q.quantifier_type != QuantifierType::Existential {
+                continue;
+            }
+
+            // The quantifier must only be referenced as a conjunct of the
+            // predicates of the box.
+            let exists = BoxScalarExpr::ColumnReference(ColumnReference {
+                quantifier_id: q.id,
+                position: 0,
+            });
+            let conjunct_uses = predicates
+                .iter()
+                .flat_map(|p| p.conjuncts())
+                .filter(|c| **c == exists)
+                .count();
+            let mut uses = 0;
+            let _ = the_box.visit_expressions(&mut |expr| -> Result<(), ()> {
+                uses += count_references(expr, &QuantifierSet::from([q.id]));
+                Ok(())
+            });
+            if conjunct_uses == 0 || conjunct_uses != uses {
+                continue;
+            }
+            // ... and must not be referenced from the subgraphs of its
+            // siblings.
+            if correlation_info
+                .values()
+                .any(|refs| refs.iter().any(|c| c.quantifier_id == q.id))
+            {
+                continue;
+            }
+
+            let outer_quantifiers = the_box
+                .quantifiers
+                .iter()
+                .filter(|q_id| **q_id != q.id)
+                .cloned()
+                .collect::<QuantifierSet>();
+            if let Some(chain) =
+                correlated_chain(model, q.input_box, &the_box.quantifiers, &outer_quantifiers)
+            {
+                return Some(ExistsMatch {
+                    box_id,
+                    quantifier_id: q.id,
+                    chain,
+                });
+            }
+        }
+
+        None
+    }
+
+    fn rewrite(&self, model: &mut Model, mat: Self::Match) {
+        let input_box_id = model.get_quantifier(mat.quantifier_id).input_box;
+
+        // Remove the correlated predicates from the subquery, and project
+        // their inner expressions from its input box instead.
+        let mut keys = vec![];
+        for (i, (chain_box_id, correlated_predicates)) in mat.chain.iter().enumerate() {
+            for (inner, outer) in correlated_predicates {
+                let mut chain_box = model.get_mut_box(*chain_box_id);
+                if let BoxType::Select(select) = &mut chain_box.box_type {
+                    let predicate = BoxScalarExpr::CallBinary {
+                        func: BinaryFunc::Eq,
+                        expr1: Box::new(inner.clone()),
+                        expr2: Box::new(outer.clone()),
+                    };
+                    let reversed = BoxScalarExpr::CallBinary {
+                        func: BinaryFunc::Eq,
+                        expr1: Box::new(outer.clone()),
+                        expr2: Box::new(inner.clone()),
+                    };
+                    remove_conjuncts(&mut select.predicates, |c| {
+                        *c == predicate || *c == reversed
+                    });
+                }
+                let mut position = chain_box.add_column_if_not_exists(inner.clone());
+                drop(chain_box);
+
+                for (parent_box_id, _) in mat.chain[..i].iter().rev() {
+                    let mut parent_box = model.get_mut_box(*parent_box_id);
+                    let quantifier_id = *parent_box.quantifiers.iter().next().unwrap();
+                    position = parent_box.add_column_if_not_exists(BoxScalarExpr::ColumnReference(
+                        ColumnReference {
+                            quantifier_id,
+                            position,
+                        },
+                    ));
+                }
+                keys.push((position, outer.clone()));
+            }
+        }
+
+        // Create a box projecting the distinct keys of the subquery, and make
+        // the quantifier range over it.
+        let distinct_box_id = model.make_select_box();
+        let distinct_quantifier_id =
+            model.make_quantifier(QuantifierType::Foreach, input_box_id, distinct_box_id);
+        let mut distinct_box = model.get_mut_box(distinct_box_id);
+        for (position, _) in keys.iter() {
+            distinct_box.add_column(BoxScalarExpr::ColumnReference(ColumnReference {
+                quantifier_id: distinct_quantifier_id,
+                position: *position,
+            }));
+        }
+        distinct_box.distinct = DistinctOperation::Enforce;
+        distinct_box.ranging_quantifiers.insert(mat.quantifier_id);
+        drop(distinct_box);
+
+        model
+            .get_mut_box(input_box_id)
+            .ranging_quantifiers
+            .remove(&mat.quantifier_id);
+        let mut q = model.get_mut_quantifier(mat.quantifier_id);
+        q.input_box = distinct_box_id;
+        q.quantifier_type = QuantifierType::Foreach;
+        drop(q);
+
+        // Replace the references to the existential quantifier with join
+        // predicates on the keys.
+        let mut the_box = model.get_mut_box(mat.box_id);
+        if let BoxType::Select(select) = &mut the_box.box_type {
+            let exists = BoxScalarExpr::ColumnReference(ColumnReference {
+                quantifier_id: mat.quantifier_id,
+                position: 0,
+            });
+            remove_conjuncts(&mut select.predicates, |c| *c == exists);
+            for (position, (_, outer)) in keys.into_iter().enumerate() {
+                select.predicates.push(BoxScalarExpr::CallBinary {
+                    func: BinaryFunc::Eq,
+                    expr1: Box::new(outer),
+                    expr2: Box::new(BoxScalarExpr::ColumnReference(ColumnReference {
+                        quantifier_id: mat.quantifier_id,
+                        position,
+                    })),
+                });
+            }
+        }
+    }
+}
+
+/// Collects the correlated predicates of the subquery rooted at `box_id`,
+/// provided that its only correlation with the `context` quantifiers are
+/// equality predicates between an inner expression and an expression over
+/// `outer_quantifiers`, found in the chain of non-shared, single-quantifier
+/// Select boxes at the top of the subquery.
+///
+/// Returns `None` if the subquery is correlated in any other way.
+fn correlated_chain(
+    model: &Model,
+    box_id: BoxId,
+    context: &QuantifierSet,
+    outer_quantifiers: &QuantifierSet,
+) -> Option<Vec<(BoxId, Vec<(BoxScalarExpr, BoxScalarExpr)>)>> {
+    let mut chain = vec![];
+    let mut matched_references = 0;
+    let mut current = box_id;
+    loop {
+        let the_box = model.get_box(current);
+        let select = match &the_box.box_type {
+            BoxType::Select(select)
+                if select.limit.is_none()
+                    && select.offset.is_none()
+                    && the_box.quantifiers.len() == 1
+                    && the_box.ranging_quantifiers.len() == 1 =>
+            {
+                select
+            }
+            _ => break,
+        };
+        let q = the_box.input_quantifiers().next().unwrap();
+        if q.quantifier_type != QuantifierType::Foreach {
+            break;
+        }
+
+        let mut correlated_predicates = vec![];
+        for conjunct in select.predicates.iter().flat_map(|p| p.conjuncts()) {
+            let quantifiers = conjunct.quantifiers();
+            if quantifiers.is_disjoint(context) {
+                continue;
+            }
+            let (inner, outer) = match conjunct {
+                BoxScalarExpr::CallBinary {
+                    func: BinaryFunc::Eq,
+                    expr1,
+                    expr2,
+                } => {
+                    let is_inner = |e: &BoxScalarExpr| e.quantifiers().iter().all(|id| *id == q.id);
+                    let is_outer = |e: &BoxScalarExpr| {
+                        let quantifiers = e.quantifiers();
+                        !quantifiers.is_empty() && quantifiers.is_subset(outer_quantifiers)
+                    };
+                    if is_inner(expr1) && is_outer(expr2) {
+                        (&**expr1, &**expr2)
+                    } else if is_outer(expr1) && is_inner(expr2) {
+                        (&**expr2, &**expr1)
+                    } else {
+                        return None;
+                    }
+                }
+                _ => return None,
+            };
+            matched_references += count_references(conjunct, context);
+            correlated_predicates.push((inner.clone(), outer.clone()));
+        }
+        chain.push((current, correlated_predicates));
+        current = q.input_box;
+    }
+
+    // There must be no other references to the context quantifiers within
+    // the subquery.
+    let mut references = 0;
+    model
+        .try_visit_pre_post_descendants(
+            &mut |m: &Model, box_id: &BoxId| -> Result<(), ()> {
+                m.get_box(*box_id)
+                    .visit_expressions(&mut |expr| -> Result<(), ()> {
+                        references += count_references(expr, context);
+                        Ok(())
+                    })
+            },
+            &mut |_, _| Ok(()),
+            box_id,
+        )
+        .ok()?;
+    if references != matched_references {
+        return None;
+    }
+
+    // Drop the boxes at the bottom of the chain without correlated
+    // predicates, since they don't need to be modified.
+    while matches!(chain.last(), Some((_, predicates)) if predicates.is_empty()) {
+        chain.pop();
+    }
+    Some(chain)
+}
+
+/// Counts the column references in `expr` to the given quantifiers.
+fn count_references(expr: &BoxScalarExpr, quantifiers: &QuantifierSet) -> usize {
+    let mut count = 0;
+    expr.visit_post(&mut |e| {
+        if let BoxScalarExpr::ColumnReference(c) = e {
+            if quantifiers.contains(&c.quantifier_id) {
+                count += 1;
+            }
+        }
+    });
+    count
+}
+
+/// Removes the conjuncts matching `f` from the given predicates, splitting
+/// the predicates they were part of.
+fn remove_conjuncts<F>(predicates: &mut Vec<BoxScalarExpr>, f: F)
+where
+    F: Fn(&BoxScalarExpr) -> bool,
+{
+    *predicates = predicates
+        .drain(..)
+        .flat_map(|predicate| {
+            let conjuncts = predicate.conjuncts();
+            if conjuncts.iter().any(|c| f(c)) {
+                conjuncts
+                    .into_iter()
+                    .filter(|c| !f(c))
+                    .cloned()
+                    .collect::<Vec<_>>()
+            } else {
+                vec![predicate]
+            }
+        })
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query_model::model::*;
+    use crate::query_model::test::util::*;
+
+    /// Create a model for `SELECT * FROM x WHERE EXISTS (SELECT * FROM (SELECT
+    /// * FROM y WHERE <predicate>))`, where `predicate` is built from the
+    /// quantifiers over `x` and `y`.
+    ///
+    /// Returns the model and the ids of the quantifiers over `x`, `y`, the
+    /// projection of the subquery and the subquery itself.
+    fn exists_model<F>(
+        predicate: F,
+    ) -> (
+        Model,
+        QuantifierId,
+        QuantifierId,
+        QuantifierId,
+        QuantifierId,
+    )
+    where
+        F: Fn(QuantifierId, QuantifierId) -> BoxScalarExpr,
+    {
+        let mut model = Model::default();
+        let x = model.make_box(qgm::get(0).into());
+        let y = model.make_box(qgm::get(1).into());
+        for id in [x, y] {
+            let mut b = model.get_mut_box(id);
+            b.add_column(exp::base(0, typ::int32(true)));
+            b.add_column(exp::base(1, typ::int32(true)));
+        }
+
+        let top = model.make_select_box();
+        let qx = model.make_quantifier(QuantifierType::Foreach, x, top);
+        let filter = model.make_select_box();
+        let qy = model.make_quantifier(QuantifierType::Foreach, y, filter);
+        let project = model.make_select_box();
+        let qf = model.make_quantifier(QuantifierType::Foreach, filter, project);
+        let qe = model.make_quantifier(QuantifierType::Existential, project, top);
+
+        let mut b = model.get_mut_box(filter);
+        b.add_all_input_columns();
+        if let BoxType::Select(select) = &mut b.box_type {
+            select.predicates.push(predicate(qx, qy));
+        }
+        drop(b);
+        model.get_mut_box(project).add_all_input_columns();
+        let mut b = model.get_mut_box(top);
+        b.add_all_input_columns();
+        if let BoxType::Select(select) = &mut b.box_type {
+            select.predicates.push(exp::cref(qe, 0));
+        }
+        drop(b);
+        model.top_box = top;
+
+        (model, qx, qy, qf, qe)
+    }
+
+    fn predicates(model: &Model, box_id: BoxId) -> Vec<BoxScalarExpr> {
+        match &model.get_box(box_id).box_type {
+            BoxType::Select(select) => select.predicates.clone(),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn it_decorrelates_equality_predicates() {
+        let (mut model, qx, qy, qf, qe) = exists_model(|qx, qy| {
+            exp::and(
+                exp::eq(exp::cref(qy, 1), exp::cref(qx, 1)),
+                exp::gt(exp::cref(qy, 0), exp::lit::int32(5)),
+            )
+        });
+        let top = model.top_box;
+        let project = model.get_quantifier(qe).input_box;
+        let filter = model.get_quantifier(qf).input_box;
+
+        let mat = DecorrelateExists.check(&model, top).unwrap();
+        DecorrelateExists.rewrite(&mut model, mat);
+
+        // The correlated predicate is removed from the subquery.
+        assert_eq!(
+            predicates(&model, filter),
+            vec![exp::gt(exp::cref(qy, 0), exp::lit::int32(5))]
+        );
+        // The subquery is joined on its distinct keys.
+        let q = model.get_quantifier(qe);
+        assert_eq!(q.quantifier_type, QuantifierType::Foreach);
+        let distinct_box = model.get_box(q.input_box);
+        assert_eq!(distinct_box.distinct, DistinctOperation::Enforce);
+        let qd = *distinct_box.quantifiers.iter().next().unwrap();
+        assert_eq!(model.get_quantifier(qd).input_box, project);
+        assert_eq!(
+            distinct_box
+                .columns
+                .iter()
+                .map(|c| c.expr.clone())
+                .collect::<Vec<_>>(),
+            vec![exp::cref(qd, 1)]
+        );
+        assert_eq!(
+            model.get_box(project).ranging_quantifiers,
+            QuantifierSet::from([qd])
+        );
+        assert_eq!(
+            predicates(&model, top),
+            vec![exp::eq(exp::cref(qx, 1), exp::cref(qe, 0))]
+        );
+    }
+
+    #[test]
+    fn it_decorrelates_uncorrelated_subqueries() {
+        let (mut model, .., qe) =
+            exists_model(|_, qy| exp::gt(exp::cref(qy, 0), exp::lit::int32(5)));
+        let top = model.top_box;
+
+        let mat = DecorrelateExists.check(&model, top).unwrap();
+        DecorrelateExists.rewrite(&mut model, mat);
+
+        let q = model.get_quantifier(qe);
+        assert_eq!(q.quantifier_type, QuantifierType::Foreach);
+        let distinct_box = model.get_box(q.input_box);
+        assert_eq!(distinct_box.distinct, DistinctOperation::Enforce);
+        assert!(distinct_box.columns.is_empty());
+        assert!(predicates(&model, top).is_empty());
+    }
+
+    #[test]
+    fn it_ignores_other_correlated_predicates() {
+        let (model, ..) = exists_model(|qx, qy| exp::lt(exp::cref(qy, 0), exp::cref(qx, 0)));
+        assert!(DecorrelateExists.check(&model, model.top_box).is_none());
+
+        let (model, ..) = exists_model(|qx, qy| {
+            exp::eq(
+                exp::cref(qy, 0),
+                exp::add(exp::cref(qx, 0), exp::cref(qy, 1)),
+            )
+        });
+        assert!(DecorrelateExists.check(&model, model.top_box).is_none());
+    }
+
+    #[test]
+    fn it_ignores_negated_subqueries() {
+        let (mut model, _, _, _, qe) =
+            exists_model(|qx, qy| exp::eq(exp::cref(qy, 0), exp::cref(qx, 0)));
+        let top = model.top_box;
+        if let BoxType::Select(select) = &mut model.get_mut_box(top).box_type {
+            select.predicates = vec![exp::not(exp::cref(qe, 0))];
+        }
+        assert!(DecorrelateExists.check(&model, top).is_none());
+    }
+}
@@ -7,12 +7,16 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-//! Implements outerjoin simplification as a variant of Algorithm A in the seminal
-//! paper by Rosenthal and Galindo-Legaria[^1].
+//! Rewrite rules for the Query Graph Model.
+//!
+//! Outerjoin simplification is implemented as a variant of Algorithm A in the
+//! seminal paper by Rosenthal and Galindo-Legaria[^1].
 //!
 //! [^1]: [Galindo-Legaria, Cesar, and Arnon Rosenthal.
 //! "Outerjoin simplification and reordering for query optimization."
 //! ACM Transactions on Database Systems (TODS) 22.1 (1997): 43-74.
 //! ](https://www.academia.edu/26160408/Outerjoin_simplification_and_reordering_for_query_optimization)
 
+pub(crate) mod decorrelate_exists;
+pub(crate) mod push_down_predicates;
 pub(crate) mod simplify_outer_joins;
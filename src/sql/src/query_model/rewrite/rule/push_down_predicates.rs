@@ -0,0 +1,231 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Implements predicate pushdown.
+//!
+//! For each Select box, move the conjuncts of its predicates that only
+//! reference a single Foreach quantifier into the input box of that
+//! quantifier, if the input box is a Select box that is not shared with
+//! other parents.
+
+use std::collections::HashSet;
+
+use crate::query_model::attribute::core::Attribute;
+use crate::query_model::model::{BoxScalarExpr, BoxType, ColumnReference, QuantifierType};
+use crate::query_model::rewrite::ApplyStrategy;
+use crate::query_model::rewrite::Rule;
+use crate::query_model::rewrite::VisitOrder;
+use crate::query_model::{BoxId, Model, QuantifierId};
+
+pub(crate) struct PushDownPredicates;
+
+impl Rule for PushDownPredicates {
+    /// The box whose predicates should be pushed down, and a (non-empty)
+    /// sequence of the conjuncts to push down together with the quantifier
+    /// whose input box they should be pushed into.
+    type Match = (BoxId, Vec<(BoxScalarExpr, QuantifierId)>);
+
+    fn name(&self) -> &'static str {
+        "PushDownPredicates"
+    }
+
+    fn strategy(&self) -> ApplyStrategy {
+        ApplyStrategy::AllBoxes(VisitOrder::Pre)
+    }
+
+    fn required_attributes(&self) -> HashSet<Box<dyn Attribute>> {
+        HashSet::new()
+    }
+
+    fn check(&self, model: &Model, box_id: BoxId) -> Option<Self::Match> {
+        let the_box = model.get_box(box_id);
+        let predicates = match &the_box.box_type {
+            BoxType::Select(select) => &select.predicates,
+            _ => return None,
+        };
+
+        let mut conjuncts_to_push = vec![];
+        for predicate in predicates {
+            for conjunct in predicate.conjuncts() {
+                let quantifiers = conjunct.quantifiers();
+                if quantifiers.len() != 1 {
+                    continue;
+                }
+                let q_id = *quantifiers.iter().next().unwrap();
+                if the_box.quantifiers.contains(&q_id) && accepts_predicates(model, q_id) {
+                    conjuncts_to_push.push((conjunct.clone(), q_id));
+                }
+            }
+        }
+
+        if conjuncts_to_push.len() > 0 {
+            Some((box_id, conjuncts_to_push))
+        } else {
+            None
+        }
+    }
+
+    fn rewrite(&self, model: &mut Model, mat: Self::Match) {
+        let (box_id, conjuncts_to_push) = (mat.0, mat.1);
+
+        // Remove the conjuncts from the box, splitting the predicates they
+        // were part of.
+        let mut r#box = model.get_mut_box(box_id);
+        if let BoxType::Select(select) = &mut r#box.box_type {
+            select.predicates = select
+                .predicates
+                .drain(..)
+                .flat_map(|predicate| {
+                    let conjuncts = predicate.conjuncts();
+                    if conjuncts
+                        .iter()
+                        .any(|c| conjuncts_to_push.iter().any(|(p, _)| p == *c))
+                    {
+                        conjuncts
+                            .into_iter()
+                            .filter(|c| !conjuncts_to_push.iter().any(|(p, _)| p == *c))
+                            .cloned()
+                            .collect::<Vec<_>>()
+                    } else {
+                        vec![predicate]
+                    }
+                })
+                .collect();
+        }
+        drop(r#box);
+
+        // Add them to the input boxes of the quantifiers they reference,
+        // expressed in terms of the projection of the input boxes.
+        for (mut conjunct, q_id) in conjuncts_to_push {
+            let input_box_id = model.get_quantifier(q_id).input_box;
+            let mut input_box = model.get_mut_box(input_box_id);
+            conjunct.visit_mut_post(&mut |expr| {
+                if let BoxScalarExpr::ColumnReference(ColumnReference { position, .. }) = expr {
+                    *expr = input_box.columns[*position].expr.clone();
+                }
+            });
+            if let BoxType::Select(select) = &mut input_box.box_type {
+                select.predicates.push(conjunct);
+            }
+        }
+    }
+}
+
+/// Returns whether predicates over the quantifier identified by `q_id` can
+/// be evaluated by its input box instead.
+fn accepts_predicates(model: &Model, q_id: QuantifierId) -> bool {
+    let q = model.get_quantifier(q_id);
+    if q.quantifier_type != QuantifierType::Foreach {
+        return false;
+    }
+    let input_box = model.get_box(q.input_box);
+    match &input_box.box_type {
+        BoxType::Select(select) => {
+            select.limit.is_none()
+                && select.offset.is_none()
+                && input_box.ranging_quantifiers.len() == 1
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query_model::model::*;
+    use crate::query_model::test::util::*;
+
+    #[test]
+    fn it_pushes_down_predicates_over_a_single_quantifier() {
+        let mut model = Model::default();
+        let x = model.make_box(qgm::get(0).into());
+        let y = model.make_box(qgm::get(1).into());
+        for id in [x, y] {
+            let mut b = model.get_mut_box(id);
+            b.add_column(exp::base(0, typ::int32(true)));
+            b.add_column(exp::base(1, typ::int32(true)));
+        }
+
+        // SELECT * FROM (SELECT a, b + 1 FROM x) t, y WHERE t.b > 3 AND t.a = y.a
+        let derived = model.make_select_box();
+        let qx = model.make_quantifier(QuantifierType::Foreach, x, derived);
+        let mut b = model.get_mut_box(derived);
+        b.add_column(exp::cref(qx, 0));
+        b.add_column(exp::add(exp::cref(qx, 1), exp::lit::int32(1)));
+        drop(b);
+
+        let top = model.make_select_box();
+        let qt = model.make_quantifier(QuantifierType::Foreach, derived, top);
+        let qy = model.make_quantifier(QuantifierType::Foreach, y, top);
+        let mut b = model.get_mut_box(top);
+        b.add_all_input_columns();
+        if let BoxType::Select(select) = &mut b.box_type {
+            select.predicates.push(exp::and(
+                exp::gt(exp::cref(qt, 1), exp::lit::int32(3)),
+                exp::eq(exp::cref(qt, 0), exp::cref(qy, 0)),
+            ));
+        }
+        drop(b);
+        model.top_box = top;
+
+        let mat = PushDownPredicates.check(&model, top).unwrap();
+        PushDownPredicates.rewrite(&mut model, mat);
+
+        let predicates = |box_id| match &model.get_box(box_id).box_type {
+            BoxType::Select(select) => select.predicates.clone(),
+            _ => unreachable!(),
+        };
+        assert_eq!(
+            predicates(top),
+            vec![exp::eq(exp::cref(qt, 0), exp::cref(qy, 0))]
+        );
+        assert_eq!(
+            predicates(derived),
+            vec![exp::gt(
+                exp::add(exp::cref(qx, 1), exp::lit::int32(1)),
+                exp::lit::int32(3)
+            )]
+        );
+
+        // Nothing else can be pushed down.
+        assert!(PushDownPredicates.check(&model, top).is_none());
+        assert!(PushDownPredicates.check(&model, derived).is_none());
+    }
+
+    #[test]
+    fn it_does_not_push_down_into_shared_boxes() {
+        let mut model = Model::default();
+        let x = model.make_box(qgm::get(0).into());
+        model
+            .get_mut_box(x)
+            .add_column(exp::base(0, typ::int32(true)));
+
+        let shared = model.make_select_box();
+        let qx = model.make_quantifier(QuantifierType::Foreach, x, shared);
+        model.get_mut_box(shared).add_column(exp::cref(qx, 0));
+
+        let top = model.make_select_box();
+        let q1 = model.make_quantifier(QuantifierType::Foreach, shared, top);
+        let q2 = model.make_quantifier(QuantifierType::Foreach, shared, top);
+        let mut b = model.get_mut_box(top);
+        b.add_all_input_columns();
+        if let BoxType::Select(select) = &mut b.box_type {
+            select
+                .predicates
+                .push(exp::gt(exp::cref(q1, 0), exp::lit::int32(3)));
+            select
+                .predicates
+                .push(exp::gt(exp::cref(q2, 0), exp::lit::int32(5)));
+        }
+        drop(b);
+        model.top_box = top;
+
+        assert!(PushDownPredicates.check(&model, top).is_none());
+    }
+}
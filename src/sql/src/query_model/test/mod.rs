@@ -32,6 +32,8 @@ enum Directive {
     /// Decorrelate the model and convert it to a `MirRelationExpr`.
     Lower,
     /// Optimize the model.
+    ///
+    /// Use `opt apply=(<rule>,...)` to apply only the named rewrite rules.
     Opt,
     /// Optimize and decorrelate the model. Then convert it to a `MirRelationExpr`.
     EndToEnd,
@@ -108,7 +110,10 @@ fn run_command(
     )?;
 
     if matches!(directive, Directive::Opt | Directive::EndToEnd) {
-        model.optimize();
+        match args.get("apply") {
+            Some(rules) => model.optimize_with_rules(rules)?,
+            None => model.optimize(),
+        }
     }
 
     // TODO: allow printing multiple stages of the transformation of the query.
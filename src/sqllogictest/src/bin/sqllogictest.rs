@@ -9,12 +9,13 @@
 
 use std::cell::RefCell;
 use std::fmt;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::{self, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process;
 
 use chrono::Utc;
+use regex::Regex;
 use time::Instant;
 use walkdir::WalkDir;
 
@@ -52,6 +53,13 @@ struct Args {
     /// Stop on first failure.
     #[clap(long)]
     fail_fast: bool,
+    /// Plan queries through the Query Graph Model.
+    #[clap(long)]
+    qgm_optimizations: bool,
+    /// Count errors matching the regular expressions in the specified file,
+    /// one per line, as known failures rather than as failures.
+    #[clap(long, value_name = "FILE")]
+    known_failures: Option<PathBuf>,
 }
 
 #[tokio::main]
@@ -61,6 +69,17 @@ async fn main() {
 
     let args: Args = mz_ore::cli::parse_args();
 
+    let known_failures = match &args.known_failures {
+        Some(filename) => match read_known_failures(filename) {
+            Ok(known_failures) => known_failures,
+            Err(err) => {
+                eprintln!("reading {}: {:#}", filename.display(), err);
+                process::exit(1);
+            }
+        },
+        None => vec![],
+    };
+
     let config = RunConfig {
         stdout: &OutputStream::new(io::stdout(), args.timestamps),
         stderr: &OutputStream::new(io::stderr(), args.timestamps),
@@ -68,6 +87,8 @@ async fn main() {
         workers: args.workers,
         no_fail: args.no_fail,
         fail_fast: args.fail_fast,
+        qgm_optimizations: args.qgm_optimizations,
+        known_failures,
     };
 
     if args.rewrite_results {
@@ -199,6 +220,21 @@ async fn rewrite(config: &RunConfig<'_>, args: Args) {
     }
 }
 
+/// Reads the known failures from `filename`, skipping blank lines and lines
+/// starting with `#`.
+fn read_known_failures(filename: &Path) -> Result<Vec<Regex>, anyhow::Error> {
+    let contents = fs::read_to_string(filename)?;
+    let mut known_failures = vec![];
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        known_failures.push(Regex::new(line)?);
+    }
+    Ok(known_failures)
+}
+
 struct OutputStream<W> {
     inner: RefCell<W>,
     need_timestamp: RefCell<bool>,
@@ -114,10 +114,16 @@ pub enum Outcome<'a> {
         cause: Box<Outcome<'a>>,
         location: Location,
     },
+    /// An error that matches one of the known failures of the run.
+    KnownFailure {
+        error: anyhow::Error,
+        location: Location,
+    },
     Success,
 }
 
-const NUM_OUTCOMES: usize = 10;
+const NUM_OUTCOMES: usize = 11;
+const KNOWN_FAILURE_OUTCOME: usize = NUM_OUTCOMES - 2;
 const SUCCESS_OUTCOME: usize = NUM_OUTCOMES - 1;
 
 impl<'a> Outcome<'a> {
@@ -131,14 +137,25 @@ impl<'a> Outcome<'a> {
             Outcome::WrongColumnCount { .. } => 5,
             Outcome::WrongColumnNames { .. } => 6,
             Outcome::OutputFailure { .. } => 7,
+            // Bailing out of a file because of a known failure is itself a
+            // known failure.
+            Outcome::Bail { cause, .. } if matches!(**cause, Outcome::KnownFailure { .. }) => {
+                KNOWN_FAILURE_OUTCOME
+            }
             Outcome::Bail { .. } => 8,
-            Outcome::Success => 9,
+            Outcome::KnownFailure { .. } => KNOWN_FAILURE_OUTCOME,
+            Outcome::Success => SUCCESS_OUTCOME,
         }
     }
 
     fn success(&self) -> bool {
         matches!(self, Outcome::Success)
     }
+
+    /// Reports whether the outcome is a success or a known failure.
+    fn passed(&self) -> bool {
+        matches!(self.code(), SUCCESS_OUTCOME | KNOWN_FAILURE_OUTCOME)
+    }
 }
 
 impl fmt::Display for Outcome<'_> {
@@ -209,6 +226,9 @@ impl fmt::Display for Outcome<'_> {
                 location, INDENT, expected_output, INDENT, actual_output, INDENT, actual_raw_output
             ),
             Bail { cause, location } => write!(f, "Bail:{} {}", location, cause),
+            KnownFailure { error, location } => {
+                write!(f, "KnownFailure:{}:\n{:#}", location, error)
+            }
             Success => f.write_str("Success"),
         }
     }
@@ -226,7 +246,12 @@ impl ops::AddAssign<Outcomes> for Outcomes {
 }
 impl Outcomes {
     pub fn any_failed(&self) -> bool {
-        self.0[SUCCESS_OUTCOME] < self.0.iter().sum::<usize>()
+        self.passed() < self.0.iter().sum::<usize>()
+    }
+
+    /// Returns the number of records that succeeded or failed in a known way.
+    fn passed(&self) -> usize {
+        self.0[SUCCESS_OUTCOME] + self.0[KNOWN_FAILURE_OUTCOME]
     }
 
     pub fn as_json(&self) -> serde_json::Value {
@@ -240,7 +265,8 @@ impl Outcomes {
             "wrong_column_names": self.0[6],
             "output_failure": self.0[7],
             "bail": self.0[8],
-            "success": self.0[9],
+            "known_failure": self.0[9],
+            "success": self.0[10],
         })
     }
 
@@ -263,7 +289,7 @@ impl<'a> fmt::Display for OutcomesDisplay<'a> {
         write!(
             f,
             "{}:",
-            if self.inner.0[SUCCESS_OUTCOME] == total {
+            if self.inner.passed() == total {
                 "PASS"
             } else if self.no_fail {
                 "FAIL-IGNORE"
@@ -282,6 +308,7 @@ impl<'a> fmt::Display for OutcomesDisplay<'a> {
                 "wrong-column-names",
                 "output-failure",
                 "bail",
+                "known-failure",
                 "success",
                 "total",
             ];
@@ -301,6 +328,8 @@ pub(crate) struct Runner {
     clients: HashMap<String, tokio_postgres::Client>,
     server: materialized::Server,
     _temp_dir: TempDir,
+    qgm_optimizations: bool,
+    known_failures: Vec<Regex>,
 }
 
 #[derive(Debug)]
@@ -555,11 +584,13 @@ fn format_row(row: &Row, types: &[Type], mode: Mode, sort: &Sort) -> Vec<String>
 }
 
 impl Runner {
-    pub async fn start() -> Result<Self, anyhow::Error> {
+    pub async fn start(config: &RunConfig<'_>) -> Result<Self, anyhow::Error> {
         let temp_dir = tempfile::tempdir()?;
         let mz_config = materialized::Config {
             timestamp_frequency: Duration::from_secs(1),
             logical_compaction_window: None,
+            statement_log: Default::default(),
+            plan_cache: Default::default(),
             data_directory: temp_dir.path().to_path_buf(),
             persist_location: PersistLocation {
                 blob_uri: format!("file://{}/persist/blob", temp_dir.path().display()),
@@ -592,16 +623,24 @@ impl Runner {
             availability_zones: Default::default(),
         };
         let server = materialized::serve(mz_config).await?;
-        let client = connect(&server).await;
+        let client = connect(&server, config.qgm_optimizations).await;
 
         Ok(Runner {
             server,
             _temp_dir: temp_dir,
             client,
             clients: HashMap::new(),
+            qgm_optimizations: config.qgm_optimizations,
+            known_failures: config.known_failures.clone(),
         })
     }
 
+    /// Reports whether `error` matches one of the known failures of the run.
+    fn is_known_failure(&self, error: &tokio_postgres::Error) -> bool {
+        let error = error.to_string();
+        self.known_failures.iter().any(|re| re.is_match(&error))
+    }
+
     async fn run_record<'a>(
         &mut self,
         record: &'a Record<'a>,
@@ -708,6 +747,12 @@ impl Runner {
                         return Ok(Outcome::Success);
                     }
                 }
+                if self.is_known_failure(&error) {
+                    return Ok(Outcome::KnownFailure {
+                        error: anyhow!(error),
+                        location,
+                    });
+                }
                 Ok(Outcome::PlanFailure {
                     error: anyhow!(error),
                     location,
@@ -767,7 +812,14 @@ impl Runner {
                 return match output {
                     Ok(_) => {
                         let error_string = format!("{}", error);
-                        if error_string.contains("supported") || error_string.contains("overload") {
+                        if self.is_known_failure(&error) {
+                            Ok(Outcome::KnownFailure {
+                                error: anyhow!(error),
+                                location,
+                            })
+                        } else if error_string.contains("supported")
+                            || error_string.contains("overload")
+                        {
                             // this is a failure, but it's caused by lack of support rather than by bugs
                             Ok(Outcome::Unsupported {
                                 error: anyhow!(error),
@@ -898,7 +950,7 @@ impl Runner {
             None => &self.client,
             Some(name) => {
                 if !self.clients.contains_key(name) {
-                    let client = connect(&self.server).await;
+                    let client = connect(&self.server, self.qgm_optimizations).await;
                     self.clients.insert(name.into(), client);
                 }
                 self.clients.get(name).unwrap()
@@ -947,7 +999,7 @@ impl Runner {
     }
 }
 
-async fn connect(server: &materialized::Server) -> tokio_postgres::Client {
+async fn connect(server: &materialized::Server, qgm_optimizations: bool) -> tokio_postgres::Client {
     let addr = server.local_addr();
     let (client, connection) = tokio_postgres::connect(
         &format!("host={} port={} user=materialize", addr.ip(), addr.port()),
@@ -961,6 +1013,12 @@ async fn connect(server: &materialized::Server) -> tokio_postgres::Client {
             eprintln!("connection error: {}", e);
         }
     });
+    if qgm_optimizations {
+        client
+            .batch_execute("SET qgm_optimizations_experimental = true")
+            .await
+            .unwrap();
+    }
     client
 }
 
@@ -975,6 +1033,10 @@ pub struct RunConfig<'a> {
     pub workers: usize,
     pub no_fail: bool,
     pub fail_fast: bool,
+    /// Plan queries through the Query Graph Model.
+    pub qgm_optimizations: bool,
+    /// Errors that are counted as known failures rather than as failures.
+    pub known_failures: Vec<Regex>,
 }
 
 fn print_record(config: &RunConfig<'_>, record: &Record) {
@@ -992,7 +1054,7 @@ pub async fn run_string(
     input: &str,
 ) -> Result<Outcomes, anyhow::Error> {
     let mut outcomes = Outcomes::default();
-    let mut state = Runner::start().await.unwrap();
+    let mut state = Runner::start(config).await.unwrap();
     let mut parser = crate::parser::Parser::new(source, input);
     writeln!(config.stdout, "==> {}", source);
     for record in parser.parse_records()? {
@@ -1030,7 +1092,7 @@ pub async fn run_string(
             break;
         }
 
-        if config.fail_fast && !outcome.success() {
+        if config.fail_fast && !outcome.passed() {
             break;
         }
     }
@@ -1051,7 +1113,7 @@ pub async fn rewrite_file(config: &RunConfig<'_>, filename: &Path) -> Result<(),
 
     let mut buf = RewriteBuffer::new(&input);
 
-    let mut state = Runner::start().await?;
+    let mut state = Runner::start(config).await?;
     let mut parser = crate::parser::Parser::new(filename.to_str().unwrap_or(""), &input);
     writeln!(config.stdout, "==> {}", filename.display());
     for record in parser.parse_records()? {
@@ -0,0 +1,152 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Backs up and restores the catalog stash.
+//!
+//! A backup contains every stash collection, along with its since and upper
+//! frontiers, in a versioned JSON format that does not depend on the backend
+//! that produced it. Backups can therefore be used both to take point-in-time
+//! copies of the catalog and to move a catalog between the SQLite and Postgres
+//! backends.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process;
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+
+use mz_build_info::{build_info, BuildInfo};
+use mz_coord::catalog::{self, storage, Catalog};
+use mz_dataflow_types::sources::AwsExternalId;
+use mz_ore::metrics::MetricsRegistry;
+use mz_ore::now::SYSTEM_TIME;
+use mz_stash::backup::{self, Backup};
+use mz_stash::{Append, Postgres, Sqlite};
+
+const BUILD_INFO: BuildInfo = build_info!();
+
+/// Backs up and restores Materialize's catalog stash.
+///
+/// Materialize must not be running against the stash while this tool is in
+/// use.
+#[derive(clap::Parser)]
+struct Args {
+    /// Path to a SQLite stash, like `mzdata/stash`.
+    #[clap(
+        long,
+        value_name = "PATH",
+        conflicts_with = "postgres",
+        required_unless_present = "postgres"
+    )]
+    sqlite: Option<PathBuf>,
+    /// Connection string for a Postgres stash.
+    #[clap(long, value_name = "URL")]
+    postgres: Option<String>,
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(clap::Subcommand)]
+enum Command {
+    /// Writes a backup of every stash collection.
+    Dump {
+        /// The file to write the backup to. Defaults to standard output.
+        #[clap(value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// Restores a backup into an empty stash, then migrates the catalog to
+    /// this version of Materialize.
+    Restore {
+        /// The backup file to restore.
+        #[clap(value_name = "FILE")]
+        input: PathBuf,
+    },
+}
+
+#[tokio::main]
+async fn main() {
+    mz_ore::test::init_logging_default("warn");
+
+    let args: Args = mz_ore::cli::parse_args();
+    if let Err(err) = run(args).await {
+        eprintln!("error: {:#}", err);
+        process::exit(1);
+    }
+}
+
+async fn run(args: Args) -> Result<(), anyhow::Error> {
+    match (args.sqlite, args.postgres) {
+        (Some(path), None) => {
+            // Opening a SQLite stash creates it if it does not exist, which is
+            // only desirable when restoring.
+            if matches!(args.command, Command::Dump { .. }) && !path.exists() {
+                bail!("sqlite stash {} does not exist", path.display());
+            }
+            let stash = Sqlite::open(&path)
+                .with_context(|| format!("opening sqlite stash at {}", path.display()))?;
+            run_command(stash, args.command).await
+        }
+        (None, Some(url)) => {
+            let tls = mz_postgres_util::make_tls(&tokio_postgres::Config::from_str(&url)?)?;
+            let stash = Postgres::new(url, None, tls)
+                .await
+                .context("opening postgres stash")?;
+            run_command(stash, args.command).await
+        }
+        _ => bail!("exactly one of --sqlite or --postgres must be specified"),
+    }
+}
+
+async fn run_command<S: Append>(mut stash: S, command: Command) -> Result<(), anyhow::Error> {
+    match command {
+        Command::Dump { output } => {
+            let json = backup::dump(&mut stash).await?.to_json();
+            match output {
+                Some(path) => fs::write(&path, json)
+                    .with_context(|| format!("writing backup to {}", path.display()))?,
+                None => {
+                    let mut stdout = io::stdout();
+                    stdout.write_all(&json)?;
+                    stdout.write_all(b"\n")?;
+                }
+            }
+        }
+        Command::Restore { input } => {
+            let json = fs::read(&input)
+                .with_context(|| format!("reading backup from {}", input.display()))?;
+            let backup = Backup::from_json(&json)?;
+            backup::restore(&mut stash, &backup).await?;
+            migrate(stash).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Opens the catalog stored in `stash`, which runs any stash and catalog
+/// migrations required to bring it up to date with this version of
+/// Materialize.
+async fn migrate<S: Append>(stash: S) -> Result<(), anyhow::Error> {
+    let storage = storage::Connection::open(stash, None).await?;
+    Catalog::open(catalog::Config {
+        storage,
+        experimental_mode: None,
+        build_info: &BUILD_INFO,
+        aws_external_id: AwsExternalId::NotProvided,
+        timestamp_frequency: Duration::from_secs(1),
+        now: SYSTEM_TIME.clone(),
+        skip_migrations: false,
+        metrics_registry: &MetricsRegistry::new(),
+    })
+    .await
+    .context("migrating restored catalog")?;
+    Ok(())
+}
@@ -0,0 +1,216 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Logical backups of a stash.
+//!
+//! A [`Backup`] captures every collection in a stash, including its since and
+//! upper frontiers, independently of the backend that stored it. Backups
+//! serialize to JSON, so they can be inspected by hand, and can be restored
+//! into a fresh stash of any backend.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use timely::progress::Antichain;
+use timely::PartialOrder;
+
+use crate::{AntichainFormatter, Diff, Stash, StashCollection, StashError, Timestamp};
+
+/// The version of the backup format produced by this version of the code.
+///
+/// Bump this whenever the structure of [`Backup`] changes in a way that older
+/// versions of the code would misinterpret.
+pub const BACKUP_VERSION: u64 = 1;
+
+/// A point-in-time copy of all collections in a stash.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Backup {
+    /// The version of the backup format. See [`BACKUP_VERSION`].
+    pub version: u64,
+    /// The contents of each collection, by name.
+    pub collections: BTreeMap<String, CollectionBackup>,
+}
+
+/// The contents of a single stash collection.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CollectionBackup {
+    /// The elements of the collection's since frontier.
+    pub since: Vec<Timestamp>,
+    /// The elements of the collection's upper frontier.
+    pub upper: Vec<Timestamp>,
+    /// The consolidated entries of the collection.
+    pub entries: Vec<BackupEntry>,
+}
+
+/// A single `(key, value, time, diff)` entry in a collection.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupEntry {
+    pub key: BackupBytes,
+    pub value: BackupBytes,
+    pub time: Timestamp,
+    pub diff: Diff,
+}
+
+/// The encoded form of a key or value.
+///
+/// Stash keys and values are opaque bytes. Bytes that happen to be valid UTF-8
+/// are written as strings to keep backups readable; all other bytes are written
+/// in hex.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupBytes {
+    Utf8(String),
+    Hex(String),
+}
+
+impl BackupBytes {
+    fn encode(bytes: Vec<u8>) -> BackupBytes {
+        match String::from_utf8(bytes) {
+            Ok(s) => BackupBytes::Utf8(s),
+            Err(e) => BackupBytes::Hex(hex::encode(e.into_bytes())),
+        }
+    }
+
+    fn decode(&self) -> Result<Vec<u8>, StashError> {
+        match self {
+            BackupBytes::Utf8(s) => Ok(s.as_bytes().to_vec()),
+            BackupBytes::Hex(s) => {
+                hex::decode(s).map_err(|e| StashError::from(format!("invalid hex bytes: {e}")))
+            }
+        }
+    }
+}
+
+impl Backup {
+    /// Parses a backup from its JSON representation.
+    ///
+    /// Returns an error if the backup was produced by an unsupported version of
+    /// the backup format.
+    pub fn from_json(json: &[u8]) -> Result<Backup, StashError> {
+        #[derive(Deserialize)]
+        struct Header {
+            version: u64,
+        }
+
+        let header: Header = serde_json::from_slice(json)
+            .map_err(|e| StashError::from(format!("invalid backup: {e}")))?;
+        if header.version != BACKUP_VERSION {
+            return Err(format!(
+                "unsupported backup version {}, expected {}",
+                header.version, BACKUP_VERSION
+            )
+            .into());
+        }
+        serde_json::from_slice(json).map_err(|e| format!("invalid backup: {e}").into())
+    }
+
+    /// Renders the backup as pretty-printed JSON.
+    pub fn to_json(&self) -> Vec<u8> {
+        serde_json::to_vec_pretty(self).expect("backup serialization cannot fail")
+    }
+}
+
+/// Copies the contents of every collection in `stash` into a [`Backup`].
+pub async fn dump<S: Stash>(stash: &mut S) -> Result<Backup, StashError> {
+    let mut collections = BTreeMap::new();
+    for name in stash.collections().await? {
+        let collection: StashCollection<Vec<u8>, Vec<u8>> = stash.collection(&name).await?;
+        let since = stash.since(collection).await?;
+        let upper = stash.upper(collection).await?;
+        let entries = stash
+            .iter(collection)
+            .await?
+            .into_iter()
+            .map(|((key, value), time, diff)| BackupEntry {
+                key: BackupBytes::encode(key),
+                value: BackupBytes::encode(value),
+                time,
+                diff,
+            })
+            .collect();
+        collections.insert(
+            name,
+            CollectionBackup {
+                since: since.elements().to_vec(),
+                upper: upper.elements().to_vec(),
+                entries,
+            },
+        );
+    }
+    Ok(Backup {
+        version: BACKUP_VERSION,
+        collections,
+    })
+}
+
+/// Restores `backup` into `stash`, which must not contain any collections.
+///
+/// After writing each collection, its since frontier, upper frontier, and
+/// entries are read back and compared against the backup. An error is
+/// returned if any of them differ.
+pub async fn restore<S: Stash>(stash: &mut S, backup: &Backup) -> Result<(), StashError> {
+    if backup.version != BACKUP_VERSION {
+        return Err(format!(
+            "unsupported backup version {}, expected {}",
+            backup.version, BACKUP_VERSION
+        )
+        .into());
+    }
+    if !stash.collections().await?.is_empty() {
+        return Err("cannot restore into a stash that already contains collections".into());
+    }
+    for (name, contents) in &backup.collections {
+        let since = Antichain::from(contents.since.clone());
+        let upper = Antichain::from(contents.upper.clone());
+        if !PartialOrder::less_equal(&since, &upper) {
+            return Err(format!(
+                "collection {name} has since {} beyond its upper {}",
+                AntichainFormatter(&since),
+                AntichainFormatter(&upper)
+            )
+            .into());
+        }
+        let entries = contents
+            .entries
+            .iter()
+            .map(|entry| {
+                Ok((
+                    (entry.key.decode()?, entry.value.decode()?),
+                    entry.time,
+                    entry.diff,
+                ))
+            })
+            .collect::<Result<Vec<_>, StashError>>()?;
+
+        let collection: StashCollection<Vec<u8>, Vec<u8>> = stash.collection(name).await?;
+        stash.update_many(collection, entries).await?;
+        stash.seal(collection, upper.borrow()).await?;
+        stash.compact(collection, since.borrow()).await?;
+
+        let restored_since = stash.since(collection).await?;
+        let restored_upper = stash.upper(collection).await?;
+        if restored_since != since || restored_upper != upper {
+            return Err(format!(
+                "restored collection {name} has since {} and upper {}, expected since {} and upper {}",
+                AntichainFormatter(&restored_since),
+                AntichainFormatter(&restored_upper),
+                AntichainFormatter(&since),
+                AntichainFormatter(&upper),
+            )
+            .into());
+        }
+    }
+    let restored = dump(stash).await?;
+    for (name, contents) in &backup.collections {
+        if restored.collections.get(name) != Some(contents) {
+            return Err(format!("restored collection {name} does not match the backup").into());
+        }
+    }
+    Ok(())
+}
@@ -9,7 +9,7 @@
 
 //! Durable metadata storage.
 
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::error::Error;
 use std::fmt::{self, Debug};
 use std::hash::Hash;
@@ -26,6 +26,7 @@ use timely::PartialOrder;
 use mz_ore::collections::CollectionExt;
 use mz_persist_types::Codec;
 
+pub mod backup;
 mod postgres;
 mod sqlite;
 
@@ -73,6 +74,13 @@ pub trait Stash: std::fmt::Debug + Send {
         K: Data,
         V: Data;
 
+    /// Returns the names of all collections in the stash.
+    ///
+    /// This is intended for tools, like backups, that need to operate on every
+    /// collection without knowing their key and value types in advance. Such
+    /// tools can load the collections with `Vec<u8>` keys and values.
+    async fn collections(&mut self) -> Result<BTreeSet<String>, StashError>;
+
     /// Iterates over all entries in the stash.
     ///
     /// Entries are iterated in `(key, value, time)` order and are guaranteed
@@ -663,6 +671,31 @@ where
         Ok(())
     }
 
+    /// Removes the given key, returning its value if it was present.
+    pub async fn delete_key<S>(&self, stash: &mut S, key: &K) -> Result<Option<V>, StashError>
+    where
+        S: Append,
+    {
+        let collection = self.get(stash).await?;
+        let mut batch = collection.make_batch(stash).await?;
+        let prev = match stash.peek_key_one(collection, key).await {
+            Ok(prev) => prev,
+            Err(err) => match err.inner {
+                InternalStashError::PeekSinceUpper(_) => {
+                    stash.append(once(batch)).await?;
+                    batch = collection.make_batch(stash).await?;
+                    stash.peek_key_one(collection, key).await?
+                }
+                _ => return Err(err),
+            },
+        };
+        if let Some(prev) = &prev {
+            collection.append_to_batch(&mut batch, &key, &prev, -1);
+            stash.append(once(batch)).await?;
+        }
+        Ok(prev)
+    }
+
     /// Sets the given key value pairs, removing existing entries match any key.
     pub async fn upsert<S, I>(&self, stash: &mut S, entries: I) -> Result<(), StashError>
     where
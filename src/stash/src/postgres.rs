@@ -9,6 +9,7 @@
 
 //! Durable metadata storage.
 
+use std::collections::BTreeSet;
 use std::marker::PhantomData;
 use std::{cmp, time::Duration};
 
@@ -365,6 +366,21 @@ impl Stash for Postgres {
         .await
     }
 
+    async fn collections(&mut self) -> Result<BTreeSet<String>, StashError> {
+        self.transact(move |tx| {
+            Box::pin(async move {
+                let names = tx
+                    .query("SELECT name FROM collections", &[])
+                    .await?
+                    .into_iter()
+                    .map(|row| row.try_get("name"))
+                    .collect::<Result<BTreeSet<String>, _>>()?;
+                Ok(names)
+            })
+        })
+        .await
+    }
+
     async fn iter<K, V>(
         &mut self,
         collection: StashCollection<K, V>,
@@ -10,6 +10,7 @@
 //! Durable metadata storage.
 
 use std::cmp;
+use std::collections::BTreeSet;
 use std::marker::PhantomData;
 use std::path::Path;
 
@@ -201,6 +202,15 @@ impl Stash for Sqlite {
         })
     }
 
+    async fn collections(&mut self) -> Result<BTreeSet<String>, StashError> {
+        let names = self
+            .conn
+            .prepare("SELECT name FROM collections")?
+            .query_and_then(params![], |row| row.get("name"))?
+            .collect::<Result<BTreeSet<String>, rusqlite::Error>>()?;
+        Ok(names)
+    }
+
     async fn iter<K, V>(
         &mut self,
         collection: StashCollection<K, V>,
@@ -14,6 +14,7 @@ use tempfile::NamedTempFile;
 use timely::progress::Antichain;
 use tokio_postgres::Config;
 
+use mz_stash::backup::{self, Backup};
 use mz_stash::{
     Append, Postgres, Sqlite, Stash, StashCollection, StashError, TableTransaction, Timestamp,
     TypedCollection,
@@ -31,6 +32,15 @@ async fn test_stash_sqlite() -> Result<(), anyhow::Error> {
         let mut conn = Sqlite::open(file.path())?;
         test_append(&mut conn).await?;
     }
+    {
+        let src = NamedTempFile::new()?;
+        let dst = NamedTempFile::new()?;
+        test_backup(
+            &mut Sqlite::open(src.path())?,
+            &mut Sqlite::open(dst.path())?,
+        )
+        .await?;
+    }
     Ok(())
 }
 
@@ -87,6 +97,12 @@ async fn test_stash_postgres() -> Result<(), anyhow::Error> {
         let mut conn = connect(&connstr, tls.clone(), true).await;
         test_append(&mut conn).await?;
     }
+    // Test restoring a SQLite backup into Postgres.
+    {
+        let src = NamedTempFile::new()?;
+        let mut conn = connect(&connstr, tls.clone(), true).await;
+        test_backup(&mut Sqlite::open(src.path())?, &mut conn).await?;
+    }
     // Test the fence.
     {
         let mut conn1 = connect(&connstr, tls.clone(), true).await;
@@ -148,6 +164,15 @@ async fn test_append(stash: &mut impl Append) -> Result<(), anyhow::Error> {
             ("k2".to_string(), "v4".to_string())
         ])
     );
+    assert_eq!(
+        TYPED.delete_key(stash, &"k1".to_string()).await?,
+        Some("v3".to_string()),
+    );
+    assert_eq!(TYPED.delete_key(stash, &"k1".to_string()).await?, None);
+    assert_eq!(
+        TYPED.peek_one(stash).await.unwrap(),
+        BTreeMap::from([("k2".to_string(), "v4".to_string())])
+    );
 
     // Test append across collections.
     let orders = stash.collection::<String, String>("orders").await?;
@@ -574,6 +599,74 @@ async fn test_stash_table(stash: &mut impl Append) -> Result<(), anyhow::Error>
     Ok(())
 }
 
+async fn test_backup(src: &mut impl Append, dst: &mut impl Append) -> Result<(), anyhow::Error> {
+    const TYPED: TypedCollection<String, String> = TypedCollection::new("typed");
+    const BINARY: TypedCollection<Vec<u8>, String> = TypedCollection::new("binary");
+
+    TYPED
+        .upsert(
+            src,
+            vec![
+                ("k1".to_string(), "v1".to_string()),
+                ("k2".to_string(), "v2".to_string()),
+            ],
+        )
+        .await?;
+    TYPED
+        .upsert_key(src, &"k1".to_string(), &"v3".to_string())
+        .await?;
+    BINARY
+        .upsert_key(src, &vec![0xff, 0x00], &"v".to_string())
+        .await?;
+    let typed = TYPED.get(src).await?;
+    src.compact(typed, Antichain::from_elem(Timestamp::MIN + 1).borrow())
+        .await?;
+
+    // The backup survives a round trip through JSON.
+    let backup = backup::dump(src).await?;
+    assert_eq!(backup.version, backup::BACKUP_VERSION);
+    assert_eq!(
+        backup.collections.keys().collect::<Vec<_>>(),
+        vec!["binary", "typed"]
+    );
+    assert_eq!(
+        backup.collections["binary"].entries[0].key,
+        backup::BackupBytes::Hex("ff00".into())
+    );
+    let backup = Backup::from_json(&backup.to_json())?;
+
+    backup::restore(dst, &backup).await?;
+    assert_eq!(backup::dump(dst).await?, backup);
+    assert_eq!(TYPED.peek_one(dst).await?, TYPED.peek_one(src).await?);
+    assert_eq!(TYPED.upper(dst).await?, TYPED.upper(src).await?);
+    let dst_typed = TYPED.get(dst).await?;
+    assert_eq!(
+        dst.since(dst_typed).await?,
+        Antichain::from_elem(Timestamp::MIN + 1)
+    );
+
+    // Restoring into a stash that already has data is refused.
+    assert_eq!(
+        backup::restore(dst, &backup).await.unwrap_err().to_string(),
+        "stash error: cannot restore into a stash that already contains collections"
+    );
+
+    // Backups from other versions of the format are refused.
+    let mut json: serde_json::Value = serde_json::from_slice(&backup.to_json())?;
+    json["version"] = (backup::BACKUP_VERSION + 1).into();
+    assert_eq!(
+        Backup::from_json(&serde_json::to_vec(&json)?)
+            .unwrap_err()
+            .to_string(),
+        format!(
+            "stash error: unsupported backup version {}, expected {}",
+            backup::BACKUP_VERSION + 1,
+            backup::BACKUP_VERSION
+        )
+    );
+    Ok(())
+}
+
 #[test]
 fn test_table() {
     fn numeric_identity(k: &Vec<u8>) -> i64 {
@@ -10,7 +10,7 @@
 use futures::executor::block_on;
 
 use mz_dataflow_types::DecodeError;
-use mz_interchange::avro::Decoder;
+use mz_interchange::avro::{Decoder, OcfDecoder};
 use mz_repr::Row;
 
 #[derive(Debug)]
@@ -50,3 +50,23 @@ impl AvroDecoderState {
         }
     }
 }
+
+#[derive(Debug)]
+pub struct AvroOcfDecoderState {
+    decoder: OcfDecoder,
+}
+
+impl AvroOcfDecoderState {
+    pub fn new(reader_schema: &str) -> Result<Self, anyhow::Error> {
+        Ok(AvroOcfDecoderState {
+            decoder: OcfDecoder::new(reader_schema)?,
+        })
+    }
+
+    /// Decodes every record of the object container file `bytes`.
+    pub fn decode(&mut self, bytes: &[u8]) -> Result<Vec<Row>, DecodeError> {
+        self.decoder
+            .decode(bytes)
+            .map_err(|err| DecodeError::Text(format!("avro deserialization error: {:#}", err)))
+    }
+}
@@ -12,7 +12,7 @@
 use mz_ore::metrics::MetricsRegistry;
 use mz_ore::{metric, metrics::raw::IntCounterVec};
 
-use crate::decode::{DataDecoderInner, PreDelimitedFormat};
+use crate::decode::{DataDecoderInner, PreDelimitedFormat, WholeObjectFormat};
 
 /// Metrics specific to a single worker.
 #[derive(Clone, Debug)]
@@ -43,6 +43,10 @@ impl DecodeMetrics {
                 PreDelimitedFormat::Regex(..) => "regex",
                 PreDelimitedFormat::Protobuf(..) => "protobuf",
             },
+            DataDecoderInner::WholeObject { format, .. } => match format {
+                WholeObjectFormat::AvroOcf(..) => "avro-ocf",
+                WholeObjectFormat::Parquet(..) => "parquet",
+            },
         };
         let success_label = if success { "success" } else { "error" };
         self.events_read
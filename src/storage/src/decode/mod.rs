@@ -37,7 +37,7 @@ use timely::scheduling::SyncActivator;
 
 use mz_dataflow_types::{
     sources::{
-        encoding::{AvroEncoding, DataEncoding, RegexEncoding},
+        encoding::{AvroEncoding, AvroOcfEncoding, DataEncoding, RegexEncoding},
         IncludedColumnSource,
     },
     DecodeError, LinearOperator,
@@ -47,15 +47,17 @@ use mz_repr::Datum;
 use mz_repr::{Diff, Row, Timestamp};
 use tracing::error;
 
-use self::avro::AvroDecoderState;
+use self::avro::{AvroDecoderState, AvroOcfDecoderState};
 use self::csv::CsvDecoderState;
+use self::parquet::ParquetDecoderState;
 use self::protobuf::ProtobufDecoderState;
-use crate::source::{DecodeResult, SourceOutput};
+use crate::source::{DecodeResult, SourceOutput, UndecodedRecord};
 use metrics::DecodeMetrics;
 
 mod avro;
 mod csv;
 pub mod metrics;
+mod parquet;
 mod protobuf;
 
 /// Decode delimited CDCv2 messages.
@@ -171,6 +173,23 @@ impl PreDelimitedFormat {
     }
 }
 
+// These can only decode complete objects -- they need all of an object's
+// bytes before they can produce any of its records.
+#[derive(Debug)]
+pub(crate) enum WholeObjectFormat {
+    AvroOcf(AvroOcfDecoderState),
+    Parquet(ParquetDecoderState),
+}
+
+impl WholeObjectFormat {
+    pub fn decode(&mut self, bytes: &[u8]) -> Result<Vec<Row>, DecodeError> {
+        match self {
+            WholeObjectFormat::AvroOcf(ocf) => ocf.decode(bytes),
+            WholeObjectFormat::Parquet(parquet) => parquet.decode(bytes),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub(crate) enum DataDecoderInner {
     Avro(AvroDecoderState),
@@ -181,6 +200,13 @@ pub(crate) enum DataDecoderInner {
     Csv(CsvDecoderState),
 
     PreDelimited(PreDelimitedFormat),
+    WholeObject {
+        /// The bytes of the current object.
+        buf: Vec<u8>,
+        /// Records of the last object that have yet to be emitted.
+        pending: VecDeque<Row>,
+        format: WholeObjectFormat,
+    },
 }
 
 #[derive(Debug)]
@@ -209,6 +235,10 @@ impl DataDecoder {
                 *bytes = &[];
                 result
             }
+            DataDecoderInner::WholeObject { buf, .. } => {
+                buf.extend_from_slice(std::mem::take(bytes));
+                Ok(None)
+            }
         }
     }
 
@@ -233,10 +263,35 @@ impl DataDecoder {
                     format.decode(data)
                 }
             }
+            DataDecoderInner::WholeObject {
+                buf,
+                pending,
+                format,
+            } => {
+                buf.extend_from_slice(std::mem::take(bytes));
+                if buf.is_empty() {
+                    return Ok(None);
+                }
+                let result = format.decode(buf);
+                buf.clear();
+                pending.extend(result?);
+                Ok(pending.pop_front())
+            }
             _ => Ok(None),
         }
     }
 
+    /// Get the next record that was decoded by an earlier call to `eof`, if any.
+    ///
+    /// Only decoders for whole-object formats produce more than one record at
+    /// EOF; for all others this always returns `None`.
+    pub fn next_buffered(&mut self) -> Option<Row> {
+        match &mut self.inner {
+            DataDecoderInner::WholeObject { pending, .. } => pending.pop_front(),
+            _ => None,
+        }
+    }
+
     pub fn log_errors(&self, n: usize) {
         self.metrics.count_errors(&self.inner, n);
     }
@@ -309,6 +364,30 @@ fn get_decoder(
                 metrics,
             }
         }
+        DataEncoding::AvroOcf(AvroOcfEncoding { reader_schema }) => {
+            let state = AvroOcfDecoderState::new(&reader_schema)
+                .expect("Failed to create avro decoder, even though we validated the schema during planning.");
+            DataDecoder {
+                inner: DataDecoderInner::WholeObject {
+                    buf: vec![],
+                    pending: VecDeque::new(),
+                    format: WholeObjectFormat::AvroOcf(state),
+                },
+                metrics,
+            }
+        }
+        DataEncoding::Parquet(encoding) => {
+            let state = ParquetDecoderState::new(encoding)
+                .expect("Failed to create parquet decoder, even though we validated the schema during planning.");
+            DataDecoder {
+                inner: DataDecoderInner::WholeObject {
+                    buf: vec![],
+                    pending: VecDeque::new(),
+                    format: WholeObjectFormat::Parquet(state),
+                },
+                metrics,
+            }
+        }
         DataEncoding::Postgres => {
             unreachable!("Postgres sources should not go through the general decoding path.")
         }
@@ -388,8 +467,8 @@ where
             input.for_each(|cap, data| {
                 let mut session = output.session(&cap);
                 for SourceOutput {
-                    key,
-                    value,
+                    key: raw_key,
+                    value: raw_value,
                     position,
                     upstream_time_millis,
                     partition,
@@ -398,12 +477,17 @@ where
                 {
                     let key = key_decoder
                         .as_mut()
-                        .and_then(|decoder| try_decode(decoder, key.as_ref()));
+                        .and_then(|decoder| try_decode(decoder, raw_key.as_ref()));
 
-                    let value = try_decode(&mut value_decoder, value.as_ref());
+                    let value = try_decode(&mut value_decoder, raw_value.as_ref());
 
+                    let mut undecoded = None;
                     if matches!(&key, Some(Err(_))) || matches!(&value, Some(Err(_))) {
                         n_errors += 1;
+                        undecoded = Some(UndecodedRecord {
+                            key: raw_key.clone(),
+                            value: raw_value.clone(),
+                        });
                     } else if matches!(&value, Some(Ok(_))) {
                         n_successes += 1;
                     }
@@ -416,11 +500,12 @@ where
                         partition: partition.clone(),
                         metadata: to_metadata_row(
                             &metadata_items,
-                            partition.clone(),
+                            partition,
                             *position,
                             *upstream_time_millis,
                             headers.as_deref(),
                         ),
+                        undecoded,
                     });
                 }
             });
@@ -504,35 +589,47 @@ where
                                     data
                                 )));
                             }
+                            let undecoded = result.is_err().then(|| UndecodedRecord {
+                                key: None,
+                                value: Some(value_buf.clone()),
+                            });
                             value_buf.clear();
 
-                            match result.transpose() {
-                                None => continue,
-                                Some(value) => {
-                                    if value.is_err() {
-                                        n_errors += 1;
-                                    } else if matches!(&value, Ok(_)) {
-                                        n_successes += 1;
-                                    }
-                                    // `RangeFrom` `Iterator`'s never end
-                                    let position = n_seen.next().unwrap();
-                                    let metadata = to_metadata_row(
-                                        &metadata_items,
-                                        partition.clone(),
-                                        position,
-                                        *upstream_time_millis,
-                                        headers.as_deref(),
-                                    );
-
-                                    session.give(DecodeResult {
-                                        key: None,
-                                        value: Some(value),
-                                        position,
-                                        upstream_time_millis: *upstream_time_millis,
-                                        partition: partition.clone(),
-                                        metadata,
-                                    });
+                            // Decoders for whole-object formats decode all of
+                            // an object's records at once, and buffer all but
+                            // the first of them.
+                            let buffered =
+                                std::iter::from_fn(|| value_decoder.next_buffered().map(Ok));
+                            for value in result.transpose().into_iter().chain(buffered) {
+                                if value.is_err() {
+                                    n_errors += 1;
+                                } else if matches!(&value, Ok(_)) {
+                                    n_successes += 1;
                                 }
+                                // `RangeFrom` `Iterator`'s never end
+                                let position = n_seen.next().unwrap();
+                                let metadata = to_metadata_row(
+                                    &metadata_items,
+                                    partition,
+                                    position,
+                                    *upstream_time_millis,
+                                    headers.as_deref(),
+                                );
+
+                                let undecoded = if value.is_err() {
+                                    undecoded.clone()
+                                } else {
+                                    None
+                                };
+                                session.give(DecodeResult {
+                                    key: None,
+                                    value: Some(value),
+                                    position,
+                                    upstream_time_millis: *upstream_time_millis,
+                                    partition: partition.clone(),
+                                    metadata,
+                                    undecoded,
+                                });
                             }
                             continue;
                         }
@@ -570,8 +667,13 @@ where
                         assert!(old_value_cursor != *value_bytes_remaining || value.is_err());
 
                         let is_err = value.is_err();
+                        let mut undecoded = None;
                         if is_err {
                             n_errors += 1;
+                            undecoded = Some(UndecodedRecord {
+                                key: None,
+                                value: Some(old_value_cursor.to_vec()),
+                            });
                         } else if matches!(&value, Ok(_)) {
                             n_successes += 1;
                         }
@@ -579,7 +681,7 @@ where
                         let position = n_seen.next().unwrap();
                         let metadata = to_metadata_row(
                             &metadata_items,
-                            partition.clone(),
+                            partition,
                             position,
                             *upstream_time_millis,
                             headers.as_deref(),
@@ -593,6 +695,7 @@ where
                                 upstream_time_millis: *upstream_time_millis,
                                 partition: partition.clone(),
                                 metadata,
+                                undecoded,
                             });
                             value_buf = vec![];
                             break;
@@ -604,6 +707,7 @@ where
                                 upstream_time_millis: *upstream_time_millis,
                                 partition: partition.clone(),
                                 metadata,
+                                undecoded,
                             });
                         }
                         if is_err {
@@ -628,15 +732,20 @@ where
 
 fn to_metadata_row(
     metadata_items: &[IncludedColumnSource],
-    partition: PartitionId,
+    partition: &PartitionId,
     position: i64,
     upstream_time_millis: Option<i64>,
     headers: Option<&[(String, Option<Vec<u8>>)]>,
 ) -> Row {
     let mut row = Row::default();
     let mut packer = row.packer();
-    match partition {
-        PartitionId::Kafka(partition) => {
+    let kafka_partition = match partition {
+        PartitionId::Kafka(partition) => Some((None, *partition)),
+        PartitionId::KafkaTopic { topic, partition } => Some((Some(topic.as_str()), *partition)),
+        PartitionId::Kinesis(_) | PartitionId::None => None,
+    };
+    match kafka_partition {
+        Some((topic, partition)) => {
             for item in metadata_items.iter() {
                 match item {
                     IncludedColumnSource::Partition => packer.push(Datum::from(partition)),
@@ -656,7 +765,9 @@ fn to_metadata_row(
                             millis * 1_000_000,
                         )))
                     }
-                    IncludedColumnSource::Topic => unreachable!("Topic is not implemented yet"),
+                    IncludedColumnSource::Topic => packer.push(Datum::String(
+                        topic.expect("sources that include the topic qualify partitions by topic"),
+                    )),
                     IncludedColumnSource::Headers => {
                         packer.push_list_with(|r| {
                             // If the source asked for headers, but we didn't get any, we still
@@ -683,7 +794,7 @@ fn to_metadata_row(
                 }
             }
         }
-        PartitionId::None => {
+        None => {
             for item in metadata_items.iter() {
                 match item {
                     IncludedColumnSource::DefaultPosition => packer.push(Datum::from(position)),
@@ -0,0 +1,257 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::any::Any;
+use std::io::Cursor;
+
+use anyhow::{anyhow, bail, Context};
+use arrow2::array::{Array, BinaryArray, BooleanArray, PrimitiveArray, Utf8Array};
+use arrow2::datatypes::{DataType, TimeUnit};
+use arrow2::io::parquet::read::FileReader;
+use chrono::{NaiveDate, NaiveDateTime};
+
+use mz_dataflow_types::sources::encoding::ParquetEncoding;
+use mz_dataflow_types::DecodeError;
+use mz_interchange::avro::{parse_schema, schema_to_relationdesc};
+use mz_repr::{Datum, RelationDesc, Row, ScalarType};
+
+#[derive(Debug)]
+pub struct ParquetDecoderState {
+    desc: RelationDesc,
+    row_buf: Row,
+}
+
+impl ParquetDecoderState {
+    pub fn new(encoding: ParquetEncoding) -> Result<Self, anyhow::Error> {
+        let desc = schema_to_relationdesc(parse_schema(&encoding.schema)?)?;
+        Ok(ParquetDecoderState {
+            desc,
+            row_buf: Row::default(),
+        })
+    }
+
+    /// Decodes every row of the Parquet file `bytes`.
+    pub fn decode(&mut self, bytes: &[u8]) -> Result<Vec<Row>, DecodeError> {
+        self.decode_inner(bytes)
+            .map_err(|err| DecodeError::Text(format!("parquet deserialization error: {:#}", err)))
+    }
+
+    fn decode_inner(&mut self, bytes: &[u8]) -> Result<Vec<Row>, anyhow::Error> {
+        let reader = FileReader::try_new(Cursor::new(bytes), None, None, None, None)?;
+
+        // Columns are matched up by name. Nullable columns may be absent from
+        // the file, in which case they are filled with nulls.
+        let fields = &reader.schema().fields;
+        let mut indices = Vec::with_capacity(self.desc.arity());
+        for (name, typ) in self.desc.iter() {
+            let idx = fields.iter().position(|f| f.name == name.as_str());
+            if idx.is_none() && !typ.nullable {
+                bail!("file has no column named {}", name);
+            }
+            indices.push(idx);
+        }
+
+        let mut rows = vec![];
+        for chunk in reader {
+            let chunk = chunk?;
+            let arrays = chunk.arrays();
+            for i in 0..chunk.len() {
+                let mut packer = self.row_buf.packer();
+                for ((name, typ), idx) in self.desc.iter().zip(&indices) {
+                    let datum = match idx {
+                        None => Datum::Null,
+                        Some(idx) => datum_at(&*arrays[*idx], i, &typ.scalar_type)
+                            .with_context(|| format!("reading column {}", name))?,
+                    };
+                    if datum.is_null() && !typ.nullable {
+                        bail!("null value in non-nullable column {}", name);
+                    }
+                    packer.push(datum);
+                }
+                rows.push(self.row_buf.clone());
+            }
+        }
+        Ok(rows)
+    }
+}
+
+/// Returns the value at index `i` of `array` as a datum of type `typ`.
+fn datum_at<'a>(
+    array: &'a dyn Array,
+    i: usize,
+    typ: &ScalarType,
+) -> Result<Datum<'a>, anyhow::Error> {
+    if array.is_null(i) {
+        return Ok(Datum::Null);
+    }
+    let array_any = array.as_any();
+    let datum = match (array.data_type(), typ) {
+        (DataType::Boolean, ScalarType::Bool) => {
+            Datum::from(downcast::<BooleanArray>(array_any)?.value(i))
+        }
+        (DataType::Int32, ScalarType::Int32) => {
+            Datum::from(downcast::<PrimitiveArray<i32>>(array_any)?.value(i))
+        }
+        (DataType::Int32, ScalarType::Int64) => Datum::from(i64::from(
+            downcast::<PrimitiveArray<i32>>(array_any)?.value(i),
+        )),
+        (DataType::Int64, ScalarType::Int64) => {
+            Datum::from(downcast::<PrimitiveArray<i64>>(array_any)?.value(i))
+        }
+        (DataType::Float32, ScalarType::Float32) => {
+            Datum::from(downcast::<PrimitiveArray<f32>>(array_any)?.value(i))
+        }
+        (DataType::Float32, ScalarType::Float64) => Datum::from(f64::from(
+            downcast::<PrimitiveArray<f32>>(array_any)?.value(i),
+        )),
+        (DataType::Float64, ScalarType::Float64) => {
+            Datum::from(downcast::<PrimitiveArray<f64>>(array_any)?.value(i))
+        }
+        (DataType::Utf8, ScalarType::String) => {
+            Datum::String(downcast::<Utf8Array<i32>>(array_any)?.value(i))
+        }
+        (DataType::LargeUtf8, ScalarType::String) => {
+            Datum::String(downcast::<Utf8Array<i64>>(array_any)?.value(i))
+        }
+        (DataType::Binary, ScalarType::Bytes) => {
+            Datum::Bytes(downcast::<BinaryArray<i32>>(array_any)?.value(i))
+        }
+        (DataType::LargeBinary, ScalarType::Bytes) => {
+            Datum::Bytes(downcast::<BinaryArray<i64>>(array_any)?.value(i))
+        }
+        (DataType::Date32, ScalarType::Date) => {
+            let days = downcast::<PrimitiveArray<i32>>(array_any)?.value(i);
+            Datum::Date(
+                NaiveDate::from_ymd(1970, 1, 1)
+                    .checked_add_signed(chrono::Duration::days(days.into()))
+                    .ok_or_else(|| anyhow!("date out of range"))?,
+            )
+        }
+        (DataType::Timestamp(unit, _), ScalarType::Timestamp) => {
+            let value = downcast::<PrimitiveArray<i64>>(array_any)?.value(i);
+            Datum::Timestamp(timestamp(*unit, value)?)
+        }
+        (data_type, typ) => bail!("cannot read Parquet type {:?} as {:?}", data_type, typ),
+    };
+    Ok(datum)
+}
+
+fn downcast<T: 'static>(array: &dyn Any) -> Result<&T, anyhow::Error> {
+    array
+        .downcast_ref::<T>()
+        .ok_or_else(|| anyhow!("array does not match its data type"))
+}
+
+/// Converts a Parquet timestamp, which counts `unit`s since the Unix epoch.
+fn timestamp(unit: TimeUnit, value: i64) -> Result<NaiveDateTime, anyhow::Error> {
+    let per_second = match unit {
+        TimeUnit::Second => 1,
+        TimeUnit::Millisecond => 1_000,
+        TimeUnit::Microsecond => 1_000_000,
+        TimeUnit::Nanosecond => 1_000_000_000,
+    };
+    let secs = value.div_euclid(per_second);
+    let nanos = value.rem_euclid(per_second) * (1_000_000_000 / per_second);
+    NaiveDateTime::from_timestamp_opt(secs, u32::try_from(nanos)?)
+        .ok_or_else(|| anyhow!("timestamp out of range"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow2::chunk::Chunk;
+    use arrow2::datatypes::{Field, Schema};
+    use arrow2::io::parquet::write::{
+        CompressionOptions, Encoding, FileWriter, RowGroupIterator, Version, WriteOptions,
+    };
+
+    use super::*;
+
+    fn write_parquet(schema: Schema, columns: Vec<Arc<dyn Array>>) -> Vec<u8> {
+        let options = WriteOptions {
+            write_statistics: false,
+            compression: CompressionOptions::Uncompressed,
+            version: Version::V2,
+        };
+        let encodings = vec![Encoding::Plain; columns.len()];
+        let chunks = vec![Ok(Chunk::try_new(columns).unwrap())];
+        let row_groups =
+            RowGroupIterator::try_new(chunks.into_iter(), &schema, options, encodings).unwrap();
+        let mut buf = vec![];
+        let mut writer = FileWriter::try_new(&mut buf, schema, options).unwrap();
+        writer.start().unwrap();
+        for group in row_groups {
+            writer.write(group.unwrap()).unwrap();
+        }
+        writer.end(None).unwrap();
+        buf
+    }
+
+    #[test]
+    fn test_decode_by_name() {
+        let file = write_parquet(
+            Schema::from(vec![
+                Field::new("name", DataType::Utf8, true),
+                Field::new("id", DataType::Int32, false),
+                Field::new("ignored", DataType::Float64, false),
+            ]),
+            vec![
+                Arc::new(Utf8Array::<i32>::from(vec![Some("a"), None])) as Arc<dyn Array>,
+                Arc::new(PrimitiveArray::<i32>::from_slice([1, 2])),
+                Arc::new(PrimitiveArray::<f64>::from_slice([0.5, 1.5])),
+            ],
+        );
+
+        let mut state = ParquetDecoderState::new(ParquetEncoding {
+            schema: r#"{
+                "type": "record",
+                "name": "row",
+                "fields": [
+                    {"name": "id", "type": "long"},
+                    {"name": "name", "type": ["null", "string"]},
+                    {"name": "missing", "type": ["null", "boolean"]}
+                ]
+            }"#
+            .into(),
+        })
+        .unwrap();
+        assert_eq!(
+            state.decode(&file).unwrap(),
+            vec![
+                Row::pack([Datum::Int64(1), Datum::String("a"), Datum::Null]),
+                Row::pack([Datum::Int64(2), Datum::Null, Datum::Null]),
+            ]
+        );
+
+        // A column that is required by the schema must be present in the file.
+        let mut state = ParquetDecoderState::new(ParquetEncoding {
+            schema: r#"{
+                "type": "record",
+                "name": "row",
+                "fields": [{"name": "missing", "type": "boolean"}]
+            }"#
+            .into(),
+        })
+        .unwrap();
+        assert!(state.decode(&file).is_err());
+    }
+
+    #[test]
+    fn test_timestamp() {
+        assert_eq!(
+            timestamp(TimeUnit::Millisecond, -1).unwrap(),
+            NaiveDate::from_ymd(1969, 12, 31).and_hms_milli(23, 59, 59, 999)
+        );
+        assert_eq!(
+            timestamp(TimeUnit::Microsecond, 1_500_000).unwrap(),
+            NaiveDate::from_ymd(1970, 1, 1).and_hms_milli(0, 0, 1, 500)
+        );
+    }
+}
@@ -113,6 +113,7 @@ use timely::progress::Antichain;
 use timely::worker::Worker as TimelyWorker;
 
 use mz_dataflow_types::client::controller::storage::CollectionMetadata;
+use mz_dataflow_types::sources::{DecodeErrorPolicy, SourceConnector};
 use mz_dataflow_types::*;
 use mz_repr::GlobalId;
 
@@ -148,20 +149,50 @@ pub fn build_storage_dataflow<A: Allocate, B: StorageCapture>(
             let as_of = as_of.clone().unwrap();
             let debug_name = format!("{debug_name}-sources");
 
+            // The records diverted by the sources rendered so far, and the tokens
+            // of those sources. Sources of diverted decode errors that are
+            // imported alongside their diverting source read from its rendering.
+            // Such sources are created after, and so ordered after, the source
+            // they read from.
+            let mut diverted_collections = BTreeMap::new();
+
             // Import declared sources into the rendering context.
             for (src_id, source) in &source_imports {
                 // If `as_of` is `None`, the rendering request is invalid. We still need to satisfy it,
                 // but we will do this with an empty source.
                 let valid = storage_state.source_uppers.contains_key(src_id);
                 let ((ok, err), token) = if valid {
-                    let ((ok, err), token) = crate::render::sources::render_source(
-                        &debug_name,
-                        &as_of,
-                        source.clone(),
-                        storage_state,
-                        region,
-                        src_id.clone(),
-                    );
+                    let diverting = match &source.description.connector {
+                        SourceConnector::External {
+                            decode_errors:
+                                DecodeErrorPolicy::Emit {
+                                    source: diverting_id,
+                                },
+                            ..
+                        } => diverted_collections.get(diverting_id),
+                        _ => None,
+                    };
+                    let ((ok, err), token) = if let Some((diverted, token)) = diverting {
+                        let (ok, err) = crate::render::sources::render_diverted_decode_errors(
+                            diverted.clone(),
+                            &source.description,
+                            source.arguments.operators.clone(),
+                        );
+                        ((ok, err), Arc::clone(token))
+                    } else {
+                        let ((ok, err), diverted, token) = crate::render::sources::render_source(
+                            &debug_name,
+                            &as_of,
+                            source.clone(),
+                            storage_state,
+                            region,
+                            src_id.clone(),
+                        );
+                        if let Some(diverted) = diverted {
+                            diverted_collections.insert(*src_id, (diverted, Arc::clone(&token)));
+                        }
+                        ((ok, err), token)
+                    };
 
                     // Capture the frontier of `ok` to present as the "source upper".
                     // TODO: remove this code when storage has a better holistic take on source progress.
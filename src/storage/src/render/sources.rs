@@ -22,19 +22,22 @@ use differential_dataflow::{collection, AsCollection, Collection, Hashable};
 use serde::{Deserialize, Serialize};
 use timely::dataflow::channels::pact::Pipeline;
 use timely::dataflow::operators::unordered_input::UnorderedHandle;
-use timely::dataflow::operators::{ActivateCapability, Map, OkErr, Operator, UnorderedInput};
+use timely::dataflow::operators::{
+    ActivateCapability, Filter, Map, OkErr, Operator, UnorderedInput,
+};
 use timely::dataflow::Scope;
 
 use mz_dataflow_types::client::controller::storage::CollectionMetadata;
 use mz_dataflow_types::sources::{encoding::*, *};
 use mz_dataflow_types::*;
 use mz_expr::PartitionId;
-use mz_repr::{Diff, GlobalId, Row, RowPacker, Timestamp};
+use mz_repr::{Datum, Diff, GlobalId, RelationType, Row, RowPacker, Timestamp};
 
 use crate::decode::{render_decode, render_decode_cdcv2, render_decode_delimited};
 use crate::source::{
     self, DecodeResult, DelimitedValueSource, KafkaSourceReader, KinesisSourceReader,
-    PostgresSourceReader, PubNubSourceReader, RawSourceCreationConfig, S3SourceReader, SourceToken,
+    LoadGeneratorSourceReader, PostgresSourceReader, PubNubSourceReader, RawSourceCreationConfig,
+    S3SourceReader, SourceToken,
 };
 use crate::storage_state::LocalInput;
 use mz_timely_util::operator::{CollectionExt, StreamExt};
@@ -137,8 +140,9 @@ where
 /// encapsulated in the passed [`SourceInstanceDesc`].
 ///
 /// The first element in the returned tuple is the pair of [`Collection`]s,
-/// the second is a type-erased token that will keep the source
-/// alive as long as it is not dropped.
+/// the second holds the records that failed to decode if the source diverts
+/// them (see [`DecodeErrorPolicy::Divert`]), and the third is a type-erased
+/// token that will keep the source alive as long as it is not dropped.
 ///
 /// This function is intended to implement the recipe described here:
 /// <https://github.com/MaterializeInc/materialize/pull/12109>
@@ -158,6 +162,7 @@ pub fn render_source<G>(
     src_id: GlobalId,
 ) -> (
     (Collection<G, Row, Diff>, Collection<G, DataflowError, Diff>),
+    Option<Collection<G, Row, Diff>>,
     Arc<dyn std::any::Any + Send + Sync>,
 )
 where
@@ -215,7 +220,41 @@ where
                 capability: Rc::downgrade(&table.capability),
             });
 
-            ((table.ok_collection, table.err_collection), table.token)
+            (
+                (table.ok_collection, table.err_collection),
+                None,
+                table.token,
+            )
+        }
+
+        // Sources of diverted decode errors have no ingestion of their own. They
+        // render the ingestion of the diverting source, and produce the records
+        // that its decode operator failed to decode.
+        SourceConnector::External {
+            decode_errors: DecodeErrorPolicy::Emit { source },
+            ..
+        } => {
+            let diverting_src = storage_state
+                .source_descriptions
+                .get(&source)
+                .expect("diverting source missing")
+                .clone();
+            // Errors of the ingestion are reported by the diverting source.
+            let (_, diverted, token) = render_source(
+                dataflow_debug_name,
+                as_of_frontier,
+                SourceInstanceDesc {
+                    description: diverting_src,
+                    storage_metadata,
+                    arguments: SourceInstanceArguments { operators: None },
+                },
+                storage_state,
+                scope,
+                source,
+            );
+            let diverted = diverted.expect("diverting source did not divert decode errors");
+            let (ok, err) = render_diverted_decode_errors(diverted, &src, linear_operators);
+            ((ok, err), None, token)
         }
 
         SourceConnector::External {
@@ -225,6 +264,7 @@ where
             metadata_columns,
             ts_frequency,
             timeline: _,
+            decode_errors,
         } => {
             // TODO(benesch): this match arm is hard to follow. Refactor.
 
@@ -232,6 +272,9 @@ where
             // whose contents will be concatenated and inserted along the collection.
             let mut error_collections = Vec::<Collection<_, _, Diff>>::new();
 
+            // The records that failed to decode, if the source diverts them.
+            let mut diverted_collection = None;
+
             // Note that this `render_source` attaches a single _instance_ of a source
             // to the passed `Scope`, and this instance may be disabled if the
             // source type does not support multiple instances. `render_source`
@@ -342,6 +385,15 @@ where
                         );
                         ((SourceType::Row(ok), err), cap)
                     }
+                    ExternalSourceConnector::LoadGenerator(_) => {
+                        let ((ok, err), cap) =
+                            source::create_raw_source::<_, LoadGeneratorSourceReader>(
+                                base_source_config,
+                                &connector,
+                                storage_state.aws_external_id.clone(),
+                            );
+                        ((SourceType::Row(ok), err), cap)
+                    }
                     ExternalSourceConnector::Persist(_) => unreachable!(),
                 };
 
@@ -417,6 +469,7 @@ where
                                     upstream_time_millis: r.upstream_time_millis,
                                     partition: r.partition,
                                     metadata: Row::default(),
+                                    undecoded: None,
                                 }),
                                 None,
                             ),
@@ -425,6 +478,32 @@ where
                             needed_tokens.push(Arc::new(tok));
                         }
 
+                        // Sources that skip or divert decode errors drop the records that
+                        // failed to decode before they reach the envelope. Diverted records
+                        // are split off the output of the decode operator.
+                        let results = match decode_errors {
+                            DecodeErrorPolicy::Fail => results,
+                            DecodeErrorPolicy::Skip => results.filter(|result| !result.is_err()),
+                            DecodeErrorPolicy::Divert => {
+                                let (results, failed) = results.ok_err(|result| {
+                                    if result.is_err() {
+                                        Err(result)
+                                    } else {
+                                        Ok(result)
+                                    }
+                                });
+                                diverted_collection = Some(
+                                    render_decode_errors(failed)
+                                        .pass_through("decode-errors", 1)
+                                        .as_collection(),
+                                );
+                                results
+                            }
+                            DecodeErrorPolicy::Emit { .. } => {
+                                unreachable!("rendered from the diverting source")
+                            }
+                        };
+
                         // Render `SourceEnvelope`s. An Envelope decribes how to turn a stream
                         // of `DecodeResult`s into a _differential_ `Collection`, and is the
                         // final stage of rendering (minus some extra transformations below).
@@ -442,7 +521,7 @@ where
                                             .expect("bad tx metadata spec")
                                             .clone();
                                         // TODO(#11667): reuse the existing arrangement if it exists
-                                        let ((tx_source_ok, tx_source_err), tx_token) =
+                                        let ((tx_source_ok, tx_source_err), _, tx_token) =
                                             render_source(
                                                 dataflow_debug_name,
                                                 as_of_frontier,
@@ -546,36 +625,10 @@ where
             // At the moment this is strictly optional, but we perform it anyhow
             // to demonstrate the intended use.
             if let Some(operators) = linear_operators {
-                // Apply predicates and insert dummy values into undemanded columns.
                 let (collection2, errors) =
-                    collection
-                        .inner
-                        .flat_map_fallible("SourceLinearOperators", {
-                            // Produce an executable plan reflecting the linear operators.
-                            let source_type = src.desc.typ();
-                            let linear_op_mfp =
-                                mz_dataflow_types::plan::linear_to_mfp(operators, source_type)
-                                    .into_plan()
-                                    .unwrap_or_else(|e| panic!("{}", e));
-                            // Reusable allocation for unpacking datums.
-                            let mut datum_vec = mz_repr::DatumVec::new();
-                            let mut row_builder = Row::default();
-                            // Closure that applies the linear operators to each `input_row`.
-                            move |(input_row, time, diff)| {
-                                let arena = mz_repr::RowArena::new();
-                                let mut datums_local = datum_vec.borrow_with(&input_row);
-                                linear_op_mfp.evaluate(
-                                    &mut datums_local,
-                                    &arena,
-                                    time,
-                                    diff,
-                                    &mut row_builder,
-                                )
-                            }
-                        });
-
-                collection = collection2.as_collection();
-                error_collections.push(errors.as_collection());
+                    apply_linear_operators(collection, operators, src.desc.typ());
+                collection = collection2;
+                error_collections.push(errors);
             };
 
             // Flatten the error collections.
@@ -607,6 +660,14 @@ where
             use differential_dataflow::operators::consolidate::ConsolidateStream;
             collection = collection.consolidate_stream();
 
+            let diverted_collection = diverted_collection.map(|diverted| {
+                let as_of_frontier = as_of_frontier.clone();
+                diverted
+                    .inner
+                    .map_in_place(move |(_, time, _)| time.advance_by(as_of_frontier.borrow()))
+                    .as_collection()
+            });
+
             let source_token = Arc::new(capability);
 
             // We also need to keep track of this mapping globally to activate sources
@@ -620,11 +681,100 @@ where
             needed_tokens.push(source_token);
 
             // Return the collections and any needed tokens.
-            ((collection, err_collection), Arc::new(needed_tokens))
+            (
+                (collection, err_collection),
+                diverted_collection,
+                Arc::new(needed_tokens),
+            )
+        }
+    }
+}
+
+/// Applies the linear operators pushed down into a source to its collection,
+/// returning the resulting collection and the errors the operators produced.
+fn apply_linear_operators<G>(
+    collection: Collection<G, Row, Diff>,
+    operators: LinearOperator,
+    source_type: &RelationType,
+) -> (Collection<G, Row, Diff>, Collection<G, DataflowError, Diff>)
+where
+    G: Scope<Timestamp = Timestamp>,
+{
+    // Apply predicates and insert dummy values into undemanded columns.
+    let (collection, errors) = collection
+        .inner
+        .flat_map_fallible("SourceLinearOperators", {
+            // Produce an executable plan reflecting the linear operators.
+            let linear_op_mfp = mz_dataflow_types::plan::linear_to_mfp(operators, source_type)
+                .into_plan()
+                .unwrap_or_else(|e| panic!("{}", e));
+            // Reusable allocation for unpacking datums.
+            let mut datum_vec = mz_repr::DatumVec::new();
+            let mut row_builder = Row::default();
+            // Closure that applies the linear operators to each `input_row`.
+            move |(input_row, time, diff)| {
+                let arena = mz_repr::RowArena::new();
+                let mut datums_local = datum_vec.borrow_with(&input_row);
+                linear_op_mfp.evaluate(&mut datums_local, &arena, time, diff, &mut row_builder)
+            }
+        });
+    (collection.as_collection(), errors.as_collection())
+}
+
+/// Renders a source created with `FROM DECODE ERRORS OF` from the records that
+/// its diverting source failed to decode, as returned by [`render_source`].
+///
+/// The records are not ingested again, so the source produces exactly the
+/// records that the diverting source dropped, at the same times.
+pub fn render_diverted_decode_errors<G>(
+    diverted: Collection<G, Row, Diff>,
+    src: &SourceDesc,
+    linear_operators: Option<LinearOperator>,
+) -> (Collection<G, Row, Diff>, Collection<G, DataflowError, Diff>)
+where
+    G: Scope<Timestamp = Timestamp>,
+{
+    match linear_operators {
+        Some(operators) if !operators.is_trivial(src.desc.arity()) => {
+            apply_linear_operators(diverted, operators, src.desc.typ())
+        }
+        _ => {
+            let errors = Collection::empty(&diverted.scope());
+            (diverted, errors)
         }
     }
 }
 
+/// Converts the records that failed to decode into rows of
+/// [`DecodeErrorPolicy::error_desc`], dropping the records that decoded successfully.
+fn render_decode_errors<G>(
+    results: timely::dataflow::Stream<G, DecodeResult>,
+) -> timely::dataflow::Stream<G, Row>
+where
+    G: Scope<Timestamp = Timestamp>,
+{
+    results.flat_map(|res| {
+        let error = match (res.key, res.value) {
+            (Some(Err(err)), _) | (_, Some(Err(err))) => err,
+            _ => return None,
+        };
+        let undecoded = res.undecoded.unwrap_or_default();
+        let error = error.to_string();
+        let partition = match res.partition {
+            PartitionId::None => None,
+            partition => Some(partition.to_string()),
+        };
+        let mut row = Row::default();
+        let mut packer = row.packer();
+        packer.push(Datum::from(undecoded.key.as_deref()));
+        packer.push(Datum::from(undecoded.value.as_deref()));
+        packer.push(Datum::String(&error));
+        packer.push(Datum::from(partition.as_deref()));
+        packer.push(Datum::Int64(res.position));
+        Some(row)
+    })
+}
+
 /// After handling metadata insertion, we split streams into key/value parts for convenience
 #[derive(Debug, Clone, Hash, PartialEq, Eq, Ord, PartialOrd, Serialize, Deserialize)]
 struct KV {
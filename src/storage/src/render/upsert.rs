@@ -301,6 +301,7 @@ fn process_new_data(
         upstream_time_millis: _,
         partition: _,
         metadata,
+        undecoded: _,
     } in new_data.drain(..)
     {
         let mut time = cap.time().clone();
@@ -0,0 +1,302 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! A source that produces deterministic data, for demos and benchmarks.
+//!
+//! Each [`LoadGenerator`] is modeled as an infinite (or, for static data sets
+//! like TPC-H, finite) sequence of rows, each tagged with the tick at which it
+//! is to be emitted. The contents of the `n`th row never depend on anything but
+//! `n`, so a restarted source resumes by skipping the rows it has already
+//! emitted.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use timely::scheduling::SyncActivator;
+
+use mz_dataflow_types::sources::{
+    encoding::SourceDataEncoding, AwsExternalId, ExternalSourceConnector, LoadGenerator, MzOffset,
+    SourceCheckpoint,
+};
+use mz_expr::PartitionId;
+use mz_repr::{GlobalId, Row};
+
+use super::metrics::SourceBaseMetrics;
+use crate::source::{SourceMessage, SourceReader, SourceReaderError};
+
+mod auction;
+mod counter;
+mod tpch;
+
+/// The wall clock time that corresponds to the first tick of every generator.
+///
+/// Generators that produce timestamps derive them from this fixed point rather
+/// than from the current time, so that the generated data is reproducible.
+const EPOCH_MILLIS: i64 = 1_640_995_200_000; // 2022-01-01 00:00:00 UTC
+
+/// A sequence of rows, each tagged with the (zero-based) tick during which it
+/// is to be emitted. Ticks must be non-decreasing.
+type Rows = Box<dyn Iterator<Item = (u64, Row)>>;
+
+/// Returns the rows produced by `generator`.
+fn rows(generator: &LoadGenerator, tick_interval: Duration) -> Rows {
+    match generator {
+        LoadGenerator::Counter => counter::rows(),
+        LoadGenerator::Auction { table } => auction::rows(*table, tick_interval),
+        LoadGenerator::Tpch {
+            table,
+            scale_factor,
+        } => tpch::rows(*table, scale_factor.into_inner()),
+    }
+}
+
+/// Returns the timestamp associated with `tick`, offset by `offset` ticks.
+///
+/// The offset may be fractional, for generators that spread the rows of a tick
+/// out in time.
+fn tick_time(tick: u64, offset: f64, tick_interval: Duration) -> DateTime<Utc> {
+    let tick_millis = tick_interval.as_millis() as f64;
+    let millis = EPOCH_MILLIS + ((tick as f64 + offset) * tick_millis) as i64;
+    DateTime::from_utc(
+        NaiveDateTime::from_timestamp(millis / 1_000, (millis % 1_000) as u32 * 1_000_000),
+        Utc,
+    )
+}
+
+/// A small pseudorandom number generator.
+///
+/// The output of the generators in the `rand` crate is not guaranteed to be
+/// stable across releases, so we use SplitMix64, whose output is fully
+/// specified and cheap to seed. Generators seed an instance per row (see
+/// [`Rng::for_key`]) so that every row can be produced independently.
+struct Rng(u64);
+
+impl Rng {
+    /// Returns a generator for the row identified by `key` within the
+    /// collection identified by `stream`.
+    fn for_key(stream: u64, key: u64) -> Rng {
+        let mut rng = Rng(stream.wrapping_mul(0x9e37_79b9_7f4a_7c15) ^ key);
+        // Discard the first output, which is poorly mixed for small seeds.
+        rng.next_u64();
+        rng
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in the inclusive range `[lo, hi]`.
+    fn range(&mut self, lo: i64, hi: i64) -> i64 {
+        assert!(lo <= hi);
+        let span = (hi - lo) as u64 + 1;
+        lo + (self.next_u64() % span) as i64
+    }
+
+    /// Returns a random element of `items`.
+    fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range(0, items.len() as i64 - 1) as usize]
+    }
+
+    /// Returns a string of between `min` and `max` random words from `words`.
+    fn words(&mut self, words: &[&str], min: i64, max: i64) -> String {
+        let n = self.range(min, max);
+        (0..n)
+            .map(|_| *self.choose(words))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Produces the data described by a [`LoadGenerator`].
+pub struct LoadGeneratorSourceReader {
+    rows: Rows,
+    tick_interval: Duration,
+    /// The tick of the most recently emitted row, if any.
+    last_tick: Option<u64>,
+    /// The offset of the most recently emitted row.
+    offset: MzOffset,
+}
+
+#[async_trait(?Send)]
+impl SourceReader for LoadGeneratorSourceReader {
+    type Key = ();
+    type Value = Row;
+
+    fn new(
+        _source_name: String,
+        _source_id: GlobalId,
+        _worker_id: usize,
+        _worker_count: usize,
+        _consumer_activator: SyncActivator,
+        connector: ExternalSourceConnector,
+        _aws_external_id: AwsExternalId,
+        restored_offsets: Vec<(PartitionId, Option<MzOffset>)>,
+        _restored_checkpoints: Vec<(PartitionId, SourceCheckpoint)>,
+        _encoding: SourceDataEncoding,
+        _metrics: SourceBaseMetrics,
+    ) -> Result<Self, anyhow::Error> {
+        let connector = match connector {
+            ExternalSourceConnector::LoadGenerator(connector) => connector,
+            _ => {
+                panic!("LoadGenerator is the only legitimate ExternalSourceConnector for LoadGeneratorSourceReader")
+            }
+        };
+
+        // Restored offsets are one past the offset of the last emitted row,
+        // and offsets are 1-indexed, so the restored offset minus one is the
+        // number of rows that were already emitted.
+        let offset = restored_offsets
+            .into_iter()
+            .filter_map(|(pid, offset)| match pid {
+                PartitionId::None => offset,
+                _ => None,
+            })
+            .map(|offset| MzOffset {
+                offset: offset.offset - 1,
+            })
+            .max()
+            .unwrap_or_default();
+        let rows = rows(&connector.generator, connector.tick_interval)
+            .skip(usize::try_from(offset.offset)?);
+
+        Ok(Self {
+            rows: Box::new(rows),
+            tick_interval: connector.tick_interval,
+            // Emit the first row immediately, even if we are resuming part-way
+            // through a tick.
+            last_tick: None,
+            offset,
+        })
+    }
+
+    async fn next(
+        &mut self,
+        _timestamp_frequency: Duration,
+    ) -> Option<Result<SourceMessage<Self::Key, Self::Value>, SourceReaderError>> {
+        let (tick, row) = match self.rows.next() {
+            Some(next) => next,
+            None => {
+                // The generator has produced all of its data. Keep the source
+                // alive, so that its frontier continues to advance.
+                futures::future::pending::<()>().await;
+                unreachable!()
+            }
+        };
+
+        if let Some(last_tick) = self.last_tick {
+            if tick > last_tick {
+                let ticks = u32::try_from(tick - last_tick).unwrap_or(u32::MAX);
+                tokio::time::sleep(self.tick_interval * ticks).await;
+            }
+        }
+        self.last_tick = Some(tick);
+        self.offset = self.offset + 1;
+
+        Some(Ok(SourceMessage {
+            partition: PartitionId::None,
+            offset: self.offset,
+            upstream_time_millis: None,
+            upstream_position: None,
+            key: (),
+            value: row,
+            headers: None,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use mz_dataflow_types::sources::{AuctionTable, TpchTable};
+
+    use super::*;
+
+    fn all_generators() -> Vec<LoadGenerator> {
+        let mut generators = vec![LoadGenerator::Counter];
+        for table in AuctionTable::ALL {
+            generators.push(LoadGenerator::Auction { table: *table });
+        }
+        for table in TpchTable::ALL {
+            generators.push(LoadGenerator::Tpch {
+                table: *table,
+                scale_factor: 0.001.into(),
+            });
+        }
+        generators
+    }
+
+    #[test]
+    fn test_generators_deterministic() {
+        let tick_interval = Duration::from_millis(100);
+        for generator in all_generators() {
+            let a: Vec<_> = rows(&generator, tick_interval).take(1_000).collect();
+            let b: Vec<_> = rows(&generator, tick_interval).take(1_000).collect();
+            assert_eq!(a, b, "{:?}", generator);
+
+            // Resuming part-way through produces the remainder of the data.
+            let skip = a.len().min(10);
+            let c: Vec<_> = rows(&generator, tick_interval)
+                .skip(skip)
+                .take(a.len() - skip)
+                .collect();
+            assert_eq!(a[skip..], c[..], "{:?}", generator);
+
+            let desc = generator.desc();
+            for (_, row) in &a {
+                assert_eq!(row.unpack().len(), desc.arity(), "{:?}", generator);
+                for (datum, typ) in row.iter().zip(desc.iter_types()) {
+                    assert!(datum.is_instance_of(typ), "{:?}: {:?}", generator, datum);
+                }
+            }
+
+            let ticks: Vec<_> = a.iter().map(|(tick, _)| *tick).collect();
+            assert!(
+                ticks.windows(2).all(|w| w[0] <= w[1]),
+                "{:?}: ticks must be non-decreasing",
+                generator
+            );
+        }
+    }
+
+    #[test]
+    fn test_tpch_keys_unique() {
+        let tick_interval = Duration::from_millis(100);
+        // Tiny scale factors clamp the cardinalities of the tables, and must
+        // still respect their declared keys.
+        for scale_factor in [1e-9, 0.0001, 0.001] {
+            for table in TpchTable::ALL {
+                let generator = LoadGenerator::Tpch {
+                    table: *table,
+                    scale_factor: scale_factor.into(),
+                };
+                let desc = generator.desc();
+                for key in &desc.typ().keys {
+                    let mut seen = HashSet::new();
+                    for (_, row) in rows(&generator, tick_interval) {
+                        let datums = row.unpack();
+                        let key_row = Row::pack(key.iter().map(|c| datums[*c]));
+                        assert!(
+                            seen.insert(key_row),
+                            "{:?}: duplicate key {:?} in {:?}",
+                            generator,
+                            key,
+                            row
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
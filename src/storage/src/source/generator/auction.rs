@@ -0,0 +1,81 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! A generator for an auction house.
+//!
+//! Every tick opens one auction and places [`BIDS_PER_AUCTION`] bids on it.
+//! Auction `n` is opened in tick `n - 1`, and its bids have IDs
+//! `(n - 1) * BIDS_PER_AUCTION + 1` through `n * BIDS_PER_AUCTION`, so the
+//! `auctions` and `bids` tables can be generated independently of one another
+//! and still join up.
+
+use std::time::Duration;
+
+use mz_dataflow_types::sources::AuctionTable;
+use mz_repr::{Datum, Row};
+
+use super::{tick_time, Rng, Rows};
+
+/// The number of bids placed on each auction.
+const BIDS_PER_AUCTION: u64 = 10;
+
+/// The number of distinct users that sell and bid on items.
+const USERS: i64 = 1_000;
+
+/// The number of ticks for which each auction remains open.
+const AUCTION_TICKS: f64 = 10.0;
+
+const ITEMS: &[&str] = &[
+    "Signed Memorabilia",
+    "City Bar Crawl",
+    "Best Pizza in Town",
+    "Gift Basket",
+    "Custom Art",
+    "Vintage Guitar",
+    "Concert Tickets",
+    "Weekend Getaway",
+];
+
+const AUCTIONS_STREAM: u64 = 1;
+const BIDS_STREAM: u64 = 2;
+
+pub(super) fn rows(table: AuctionTable, tick_interval: Duration) -> Rows {
+    match table {
+        AuctionTable::Auctions => Box::new((0..).map(move |tick: u64| {
+            let id = tick + 1;
+            let mut rng = Rng::for_key(AUCTIONS_STREAM, id);
+            let row = Row::pack_slice(&[
+                Datum::Int64(id as i64),
+                Datum::Int64(rng.range(1, USERS)),
+                Datum::String(rng.choose(ITEMS)),
+                Datum::TimestampTz(tick_time(tick, AUCTION_TICKS, tick_interval)),
+            ]);
+            (tick, row)
+        })),
+        AuctionTable::Bids => Box::new((0..).flat_map(move |tick: u64| {
+            let auction_id = tick + 1;
+            let mut amount = 0;
+            (0..BIDS_PER_AUCTION).map(move |i| {
+                let id = tick * BIDS_PER_AUCTION + i + 1;
+                let mut rng = Rng::for_key(BIDS_STREAM, id);
+                // Each bid outbids the previous one.
+                amount += rng.range(1, 100) as i32;
+                let offset = (i + 1) as f64 / (BIDS_PER_AUCTION + 1) as f64;
+                let row = Row::pack_slice(&[
+                    Datum::Int64(id as i64),
+                    Datum::Int64(rng.range(1, USERS)),
+                    Datum::Int64(auction_id as i64),
+                    Datum::Int32(amount),
+                    Datum::TimestampTz(tick_time(tick, offset, tick_interval)),
+                ]);
+                (tick, row)
+            })
+        })),
+    }
+}
@@ -0,0 +1,21 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! A generator that counts up from one, once per tick.
+
+use mz_repr::{Datum, Row};
+
+use super::Rows;
+
+pub(super) fn rows() -> Rows {
+    Box::new((0..).map(|tick: u64| {
+        let counter = i64::try_from(tick + 1).expect("counter overflowed");
+        (tick, Row::pack_slice(&[Datum::Int64(counter)]))
+    }))
+}
@@ -0,0 +1,589 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! A generator for the tables of the TPC-H benchmark.
+//!
+//! The cardinalities, key relationships and value domains follow the TPC-H
+//! specification, but the values are not produced by `dbgen` and so will not
+//! match the official data sets byte for byte. Comments are drawn from a
+//! reduced vocabulary.
+//!
+//! All rows are emitted during the first tick.
+
+use chrono::NaiveDate;
+
+use mz_dataflow_types::sources::TpchTable;
+use mz_repr::adt::numeric::Numeric;
+use mz_repr::{Datum, Row};
+
+use super::{Rng, Rows};
+
+const SUPPLIERS_PER_SF: f64 = 10_000.0;
+const PARTS_PER_SF: f64 = 200_000.0;
+const CUSTOMERS_PER_SF: f64 = 150_000.0;
+const ORDERS_PER_SF: f64 = 1_500_000.0;
+const CLERKS_PER_SF: f64 = 1_000.0;
+
+/// The number of suppliers of each part.
+const SUPPLIERS_PER_PART: i64 = 4;
+
+const REGIONS: &[&str] = &["AFRICA", "AMERICA", "ASIA", "EUROPE", "MIDDLE EAST"];
+
+/// The nations, and the index of the region each belongs to.
+const NATIONS: &[(&str, i32)] = &[
+    ("ALGERIA", 0),
+    ("ARGENTINA", 1),
+    ("BRAZIL", 1),
+    ("CANADA", 1),
+    ("EGYPT", 4),
+    ("ETHIOPIA", 0),
+    ("FRANCE", 3),
+    ("GERMANY", 3),
+    ("INDIA", 2),
+    ("INDONESIA", 2),
+    ("IRAN", 4),
+    ("IRAQ", 4),
+    ("JAPAN", 2),
+    ("JORDAN", 4),
+    ("KENYA", 0),
+    ("MOROCCO", 0),
+    ("MOZAMBIQUE", 0),
+    ("PERU", 1),
+    ("CHINA", 2),
+    ("ROMANIA", 3),
+    ("SAUDI ARABIA", 4),
+    ("VIETNAM", 2),
+    ("RUSSIA", 3),
+    ("UNITED KINGDOM", 3),
+    ("UNITED STATES", 1),
+];
+
+const COLORS: &[&str] = &[
+    "almond",
+    "antique",
+    "aquamarine",
+    "azure",
+    "beige",
+    "bisque",
+    "black",
+    "blanched",
+    "blue",
+    "blush",
+    "brown",
+    "burlywood",
+    "burnished",
+    "chartreuse",
+    "chiffon",
+    "chocolate",
+    "coral",
+    "cornflower",
+    "cornsilk",
+    "cream",
+    "cyan",
+    "dark",
+    "deep",
+    "dim",
+    "dodger",
+    "drab",
+    "firebrick",
+    "floral",
+    "forest",
+    "frosted",
+    "gainsboro",
+    "ghost",
+    "goldenrod",
+    "green",
+    "grey",
+    "honeydew",
+    "hot",
+    "indian",
+    "ivory",
+    "khaki",
+    "lace",
+    "lavender",
+    "lawn",
+    "lemon",
+    "light",
+    "lime",
+    "linen",
+    "magenta",
+    "maroon",
+    "medium",
+    "metallic",
+    "midnight",
+    "mint",
+    "misty",
+    "moccasin",
+    "navajo",
+    "navy",
+    "olive",
+    "orange",
+    "orchid",
+    "pale",
+    "papaya",
+    "peach",
+    "peru",
+    "pink",
+    "plum",
+    "powder",
+    "puff",
+    "purple",
+    "red",
+    "rose",
+    "rosy",
+    "royal",
+    "saddle",
+    "salmon",
+    "sandy",
+    "seashell",
+    "sienna",
+    "sky",
+    "slate",
+    "smoke",
+    "snow",
+    "spring",
+    "steel",
+    "tan",
+    "thistle",
+    "tomato",
+    "turquoise",
+    "violet",
+    "wheat",
+    "white",
+    "yellow",
+];
+
+const TYPE_SIZES: &[&str] = &["STANDARD", "SMALL", "MEDIUM", "LARGE", "ECONOMY", "PROMO"];
+const TYPE_FINISHES: &[&str] = &["ANODIZED", "BURNISHED", "PLATED", "POLISHED", "BRUSHED"];
+const TYPE_MATERIALS: &[&str] = &["TIN", "NICKEL", "BRASS", "STEEL", "COPPER"];
+const CONTAINER_SIZES: &[&str] = &["SM", "LG", "MED", "JUMBO", "WRAP"];
+const CONTAINER_TYPES: &[&str] = &["CASE", "BOX", "BAG", "JAR", "PKG", "PACK", "CAN", "DRUM"];
+const SEGMENTS: &[&str] = &[
+    "AUTOMOBILE",
+    "BUILDING",
+    "FURNITURE",
+    "MACHINERY",
+    "HOUSEHOLD",
+];
+const PRIORITIES: &[&str] = &["1-URGENT", "2-HIGH", "3-MEDIUM", "4-NOT SPECIFIED", "5-LOW"];
+const SHIP_INSTRUCTIONS: &[&str] = &[
+    "DELIVER IN PERSON",
+    "COLLECT COD",
+    "NONE",
+    "TAKE BACK RETURN",
+];
+const SHIP_MODES: &[&str] = &["REG AIR", "AIR", "RAIL", "SHIP", "TRUCK", "MAIL", "FOB"];
+
+const COMMENT_WORDS: &[&str] = &[
+    "furiously",
+    "sly",
+    "careful",
+    "blithely",
+    "quickly",
+    "fluffily",
+    "slyly",
+    "final",
+    "ironic",
+    "regular",
+    "express",
+    "pending",
+    "special",
+    "bold",
+    "even",
+    "silent",
+    "unusual",
+    "requests",
+    "deposits",
+    "packages",
+    "accounts",
+    "instructions",
+    "theodolites",
+    "pinto",
+    "beans",
+    "foxes",
+    "ideas",
+    "dependencies",
+    "excuses",
+    "platelets",
+    "asymptotes",
+    "courts",
+    "dolphins",
+    "sleep",
+    "wake",
+    "are",
+    "cajole",
+    "haggle",
+    "nag",
+    "use",
+    "boost",
+    "affix",
+    "detect",
+    "integrate",
+    "across",
+    "about",
+    "above",
+    "after",
+    "against",
+    "along",
+];
+
+const ADDRESS_CHARS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ,";
+
+/// The date on which orders in the data set begin and end, and the date that
+/// determines whether a line item has been returned or shipped.
+const START_DATE: (i32, u32, u32) = (1992, 1, 1);
+const END_DATE: (i32, u32, u32) = (1998, 8, 2);
+const CURRENT_DATE: (i32, u32, u32) = (1995, 6, 17);
+
+// Each table draws from its own stream of random numbers. Line items are
+// generated alongside their order, from the order's stream, so that an order's
+// total price and status agree with its line items.
+const SUPPLIER_STREAM: u64 = 1;
+const PART_STREAM: u64 = 2;
+const PARTSUPP_STREAM: u64 = 3;
+const CUSTOMER_STREAM: u64 = 4;
+const ORDERS_STREAM: u64 = 5;
+
+pub(super) fn rows(table: TpchTable, scale_factor: f64) -> Rows {
+    let tpch = Tpch { scale_factor };
+    let rows: Box<dyn Iterator<Item = Row>> = match table {
+        TpchTable::Region => Box::new((0..REGIONS.len()).map(region)),
+        TpchTable::Nation => Box::new((0..NATIONS.len()).map(nation)),
+        TpchTable::Supplier => Box::new((1..=tpch.suppliers()).map(supplier)),
+        TpchTable::Part => Box::new((1..=tpch.parts()).map(part)),
+        TpchTable::PartSupp => Box::new((1..=tpch.parts()).flat_map(move |partkey| {
+            (0..SUPPLIERS_PER_PART).map(move |i| tpch.partsupp(partkey, i))
+        })),
+        TpchTable::Customer => Box::new((1..=tpch.customers()).map(customer)),
+        TpchTable::Orders => Box::new((1..=tpch.orders()).map(move |orderkey| {
+            let order = tpch.order(orderkey);
+            order.row()
+        })),
+        TpchTable::LineItem => Box::new((1..=tpch.orders()).flat_map(move |orderkey| {
+            let order = tpch.order(orderkey);
+            order.lines.into_iter().map(|line| line.row())
+        })),
+    };
+    Box::new(rows.map(|row| (0_u64, row)))
+}
+
+#[derive(Clone, Copy)]
+struct Tpch {
+    scale_factor: f64,
+}
+
+impl Tpch {
+    fn scaled(&self, per_sf: f64) -> i64 {
+        ((per_sf * self.scale_factor).ceil() as i64).max(1)
+    }
+
+    /// Returns the number of suppliers, which is never fewer than the number
+    /// of suppliers of each part.
+    fn suppliers(&self) -> i64 {
+        self.scaled(SUPPLIERS_PER_SF).max(SUPPLIERS_PER_PART)
+    }
+
+    fn parts(&self) -> i64 {
+        self.scaled(PARTS_PER_SF)
+    }
+
+    fn customers(&self) -> i64 {
+        self.scaled(CUSTOMERS_PER_SF)
+    }
+
+    fn orders(&self) -> i64 {
+        self.scaled(ORDERS_PER_SF)
+    }
+
+    fn clerks(&self) -> i64 {
+        self.scaled(CLERKS_PER_SF)
+    }
+
+    /// Returns the key of the `i`th supplier of `partkey`, as defined by the
+    /// TPC-H specification.
+    ///
+    /// At small scale factors the specification's stride between the
+    /// suppliers of a part can wrap around the suppliers and select the same
+    /// supplier twice, so it is capped to keep the suppliers of each part
+    /// distinct. The cap never applies at scale factors of 1 and above.
+    fn part_supplier(&self, partkey: i64, i: i64) -> i64 {
+        let s = self.suppliers();
+        let stride =
+            ((s / SUPPLIERS_PER_PART) + (partkey - 1) / s).min((s - 1) / (SUPPLIERS_PER_PART - 1));
+        (partkey + i * stride) % s + 1
+    }
+
+    fn partsupp(&self, partkey: i64, i: i64) -> Row {
+        let mut rng = Rng::for_key(PARTSUPP_STREAM, (partkey * SUPPLIERS_PER_PART + i) as u64);
+        Row::pack_slice(&[
+            Datum::Int64(partkey),
+            Datum::Int64(self.part_supplier(partkey, i)),
+            Datum::Int32(rng.range(1, 9_999) as i32),
+            Datum::from(decimal(rng.range(100, 100_000))),
+            Datum::String(&rng.words(COMMENT_WORDS, 5, 20)),
+        ])
+    }
+
+    fn order(&self, orderkey: i64) -> Order {
+        let mut rng = Rng::for_key(ORDERS_STREAM, orderkey as u64);
+        let start = date(START_DATE);
+        let days = (date(END_DATE) - start).num_days() - 151;
+        let orderdate = start + chrono::Duration::days(rng.range(0, days));
+        let current_date = date(CURRENT_DATE);
+
+        let lines = (1..=rng.range(1, 7))
+            .map(|linenumber| {
+                let partkey = rng.range(1, self.parts());
+                let suppkey = self.part_supplier(partkey, rng.range(0, SUPPLIERS_PER_PART - 1));
+                let quantity = rng.range(1, 50);
+                let shipdate = orderdate + chrono::Duration::days(rng.range(1, 121));
+                let commitdate = orderdate + chrono::Duration::days(rng.range(30, 90));
+                let receiptdate = shipdate + chrono::Duration::days(rng.range(1, 30));
+                let returnflag = if receiptdate <= current_date {
+                    *rng.choose(&["R", "A"])
+                } else {
+                    "N"
+                };
+                LineItem {
+                    orderkey,
+                    partkey,
+                    suppkey,
+                    linenumber: linenumber as i32,
+                    quantity,
+                    extendedprice: quantity * retail_price(partkey),
+                    discount: rng.range(0, 10),
+                    tax: rng.range(0, 8),
+                    returnflag,
+                    linestatus: if shipdate > current_date { "O" } else { "F" },
+                    shipdate,
+                    commitdate,
+                    receiptdate,
+                    shipinstruct: *rng.choose(SHIP_INSTRUCTIONS),
+                    shipmode: *rng.choose(SHIP_MODES),
+                    comment: rng.words(COMMENT_WORDS, 2, 6),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let orderstatus = if lines.iter().all(|l| l.linestatus == "F") {
+            "F"
+        } else if lines.iter().all(|l| l.linestatus == "O") {
+            "O"
+        } else {
+            "P"
+        };
+        // Prices are in cents, and discounts and taxes in hundredths, so the
+        // product carries six decimal places; round back to cents.
+        let totalprice = lines
+            .iter()
+            .map(|l| l.extendedprice * (100 + l.tax) * (100 - l.discount))
+            .sum::<i64>()
+            / 10_000;
+
+        Order {
+            orderkey,
+            custkey: rng.range(1, self.customers()),
+            orderstatus,
+            totalprice,
+            orderdate,
+            orderpriority: *rng.choose(PRIORITIES),
+            clerk: format!("Clerk#{:09}", rng.range(1, self.clerks())),
+            comment: rng.words(COMMENT_WORDS, 3, 12),
+            lines,
+        }
+    }
+}
+
+struct Order {
+    orderkey: i64,
+    custkey: i64,
+    orderstatus: &'static str,
+    /// In cents.
+    totalprice: i64,
+    orderdate: NaiveDate,
+    orderpriority: &'static str,
+    clerk: String,
+    comment: String,
+    lines: Vec<LineItem>,
+}
+
+impl Order {
+    fn row(&self) -> Row {
+        Row::pack_slice(&[
+            Datum::Int64(self.orderkey),
+            Datum::Int64(self.custkey),
+            Datum::String(self.orderstatus),
+            Datum::from(decimal(self.totalprice)),
+            Datum::Date(self.orderdate),
+            Datum::String(self.orderpriority),
+            Datum::String(&self.clerk),
+            Datum::Int32(0),
+            Datum::String(&self.comment),
+        ])
+    }
+}
+
+struct LineItem {
+    orderkey: i64,
+    partkey: i64,
+    suppkey: i64,
+    linenumber: i32,
+    quantity: i64,
+    /// In cents.
+    extendedprice: i64,
+    /// In hundredths.
+    discount: i64,
+    /// In hundredths.
+    tax: i64,
+    returnflag: &'static str,
+    linestatus: &'static str,
+    shipdate: NaiveDate,
+    commitdate: NaiveDate,
+    receiptdate: NaiveDate,
+    shipinstruct: &'static str,
+    shipmode: &'static str,
+    comment: String,
+}
+
+impl LineItem {
+    fn row(&self) -> Row {
+        Row::pack_slice(&[
+            Datum::Int64(self.orderkey),
+            Datum::Int64(self.partkey),
+            Datum::Int64(self.suppkey),
+            Datum::Int32(self.linenumber),
+            Datum::from(decimal(self.quantity * 100)),
+            Datum::from(decimal(self.extendedprice)),
+            Datum::from(decimal(self.discount)),
+            Datum::from(decimal(self.tax)),
+            Datum::String(self.returnflag),
+            Datum::String(self.linestatus),
+            Datum::Date(self.shipdate),
+            Datum::Date(self.commitdate),
+            Datum::Date(self.receiptdate),
+            Datum::String(self.shipinstruct),
+            Datum::String(self.shipmode),
+            Datum::String(&self.comment),
+        ])
+    }
+}
+
+fn region(i: usize) -> Row {
+    let mut rng = Rng::for_key(0, i as u64);
+    Row::pack_slice(&[
+        Datum::Int32(i as i32),
+        Datum::String(REGIONS[i]),
+        Datum::String(&rng.words(COMMENT_WORDS, 5, 20)),
+    ])
+}
+
+fn nation(i: usize) -> Row {
+    let mut rng = Rng::for_key(0, (REGIONS.len() + i) as u64);
+    let (name, regionkey) = NATIONS[i];
+    Row::pack_slice(&[
+        Datum::Int32(i as i32),
+        Datum::String(name),
+        Datum::Int32(regionkey),
+        Datum::String(&rng.words(COMMENT_WORDS, 5, 20)),
+    ])
+}
+
+fn supplier(suppkey: i64) -> Row {
+    let mut rng = Rng::for_key(SUPPLIER_STREAM, suppkey as u64);
+    let nationkey = rng.range(0, NATIONS.len() as i64 - 1);
+    Row::pack_slice(&[
+        Datum::Int64(suppkey),
+        Datum::String(&format!("Supplier#{:09}", suppkey)),
+        Datum::String(&address(&mut rng)),
+        Datum::Int32(nationkey as i32),
+        Datum::String(&phone(&mut rng, nationkey)),
+        Datum::from(decimal(rng.range(-99_999, 999_999))),
+        Datum::String(&rng.words(COMMENT_WORDS, 5, 15)),
+    ])
+}
+
+fn part(partkey: i64) -> Row {
+    let mut rng = Rng::for_key(PART_STREAM, partkey as u64);
+    let name = (0..5)
+        .map(|_| *rng.choose(COLORS))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mfgr = rng.range(1, 5);
+    let brand = rng.range(1, 5);
+    let typ = format!(
+        "{} {} {}",
+        rng.choose(TYPE_SIZES),
+        rng.choose(TYPE_FINISHES),
+        rng.choose(TYPE_MATERIALS)
+    );
+    let container = format!(
+        "{} {}",
+        rng.choose(CONTAINER_SIZES),
+        rng.choose(CONTAINER_TYPES)
+    );
+    Row::pack_slice(&[
+        Datum::Int64(partkey),
+        Datum::String(&name),
+        Datum::String(&format!("Manufacturer#{}", mfgr)),
+        Datum::String(&format!("Brand#{}{}", mfgr, brand)),
+        Datum::String(&typ),
+        Datum::Int32(rng.range(1, 50) as i32),
+        Datum::String(&container),
+        Datum::from(decimal(retail_price(partkey))),
+        Datum::String(&rng.words(COMMENT_WORDS, 1, 5)),
+    ])
+}
+
+fn customer(custkey: i64) -> Row {
+    let mut rng = Rng::for_key(CUSTOMER_STREAM, custkey as u64);
+    let nationkey = rng.range(0, NATIONS.len() as i64 - 1);
+    Row::pack_slice(&[
+        Datum::Int64(custkey),
+        Datum::String(&format!("Customer#{:09}", custkey)),
+        Datum::String(&address(&mut rng)),
+        Datum::Int32(nationkey as i32),
+        Datum::String(&phone(&mut rng, nationkey)),
+        Datum::from(decimal(rng.range(-99_999, 999_999))),
+        Datum::String(rng.choose(SEGMENTS)),
+        Datum::String(&rng.words(COMMENT_WORDS, 5, 20)),
+    ])
+}
+
+/// Returns the retail price of `partkey` in cents, as defined by the TPC-H
+/// specification.
+fn retail_price(partkey: i64) -> i64 {
+    90_000 + ((partkey / 10) % 20_001) + 100 * (partkey % 1_000)
+}
+
+fn address(rng: &mut Rng) -> String {
+    (0..rng.range(10, 40))
+        .map(|_| char::from(*rng.choose(ADDRESS_CHARS)))
+        .collect()
+}
+
+fn phone(rng: &mut Rng, nationkey: i64) -> String {
+    format!(
+        "{}-{}-{}-{}",
+        nationkey + 10,
+        rng.range(100, 999),
+        rng.range(100, 999),
+        rng.range(1_000, 9_999)
+    )
+}
+
+fn date((year, month, day): (i32, u32, u32)) -> NaiveDate {
+    NaiveDate::from_ymd(year, month, day)
+}
+
+/// Converts a number of hundredths into a decimal with two decimal places.
+fn decimal(hundredths: i64) -> Numeric {
+    let mut n = Numeric::from(hundredths);
+    n.set_exponent(-2);
+    n
+}
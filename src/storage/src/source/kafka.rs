@@ -19,13 +19,14 @@ use rdkafka::message::{BorrowedMessage, Headers};
 use rdkafka::statistics::Statistics;
 use rdkafka::topic_partition_list::Offset;
 use rdkafka::{ClientConfig, ClientContext, Message, TopicPartitionList};
+use regex::Regex;
 use timely::scheduling::activate::SyncActivator;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
 use mz_dataflow_types::sources::{
     encoding::SourceDataEncoding, AwsExternalId, ExternalSourceConnector, KafkaOffset,
-    KafkaSourceConnector, MzOffset,
+    KafkaSourceConnector, KafkaTopics, MzOffset, SourceCheckpoint,
 };
 use mz_expr::PartitionId;
 use mz_kafka_util::{client::MzClientContext, KafkaAddrs};
@@ -41,8 +42,11 @@ mod metrics;
 
 /// Contains all information necessary to ingest data from Kafka
 pub struct KafkaSourceReader {
-    /// Name of the topic on which this source is backed on
-    topic_name: String,
+    /// The topics on which this source is backed on
+    topics: KafkaTopics,
+    /// Whether partitions are identified as [`PartitionId::KafkaTopic`] rather than
+    /// [`PartitionId::Kafka`]
+    topic_qualified: bool,
     /// Name of the source (will have format kafka-source-id)
     source_name: String,
     /// Source global ID
@@ -55,14 +59,14 @@ pub struct KafkaSourceReader {
     worker_id: usize,
     /// Total count of workers
     worker_count: usize,
-    /// Map from partition -> most recently read offset
-    last_offsets: HashMap<i32, i64>,
-    /// Map from partition -> offset to start reading at
-    start_offsets: HashMap<i32, i64>,
+    /// Map from topic -> partition -> most recently read offset
+    last_offsets: HashMap<String, HashMap<i32, i64>>,
+    /// Map from topic -> partition -> offset to start reading at
+    start_offsets: HashMap<String, HashMap<i32, i64>>,
     /// Channel to receive Kafka statistics JSON blobs from the stats callback.
     stats_rx: crossbeam_channel::Receiver<Jsonb>,
-    /// The last partition we received
-    partition_info: Arc<Mutex<Option<Vec<i32>>>>,
+    /// The last partitions we received, by topic
+    partition_info: Arc<Mutex<Option<BTreeMap<String, Vec<i32>>>>>,
     /// A handle to the spawned metadata thread
     // Drop order is important here, we want the thread to be unparked after the `partition_info`
     // Arc has been dropped, so that the unpacked thread notices it and exits immediately
@@ -87,6 +91,7 @@ impl SourceReader for KafkaSourceReader {
         connector: ExternalSourceConnector,
         _: AwsExternalId,
         restored_offsets: Vec<(PartitionId, Option<MzOffset>)>,
+        _: Vec<(PartitionId, SourceCheckpoint)>,
         _: SourceDataEncoding,
         base_metrics: SourceBaseMetrics,
     ) -> Result<Self, anyhow::Error> {
@@ -94,11 +99,12 @@ impl SourceReader for KafkaSourceReader {
             ExternalSourceConnector::Kafka(kc) => kc,
             _ => unreachable!(),
         };
+        let topic_qualified = kc.topic_qualified_partitions();
 
         let KafkaSourceConnector {
             addrs,
             config_options,
-            topic,
+            topics,
             group_id_prefix,
             cluster_id,
             ..
@@ -120,22 +126,24 @@ impl SourceReader for KafkaSourceReader {
         let consumer = Arc::new(consumer);
 
         // Start offsets is a map from pid -> next 0-indexed offset to read from,
-        // which is equivalent to 1 + the last 0-indexed offset read.
-        let mut start_offsets: HashMap<_, _> = kc.start_offsets.into_iter().collect();
+        // which is equivalent to 1 + the last 0-indexed offset read. Start
+        // offsets can only be specified for sources that read a single topic.
+        let mut start_offsets: HashMap<String, HashMap<i32, i64>> = HashMap::new();
+        if let Some(topic) = topics.single() {
+            start_offsets.insert(topic.to_string(), kc.start_offsets.into_iter().collect());
+        }
 
         // Restored offsets are 1-indexed, so convert to 0-indexed offsets by
         // subtracting 1. The bindings in sqlite already encode 1 offset past the
         // last read offset.
         for (pid, offset) in restored_offsets {
-            let pid = match pid {
-                PartitionId::Kafka(id) => id,
-                _ => panic!("unexpected partition id type"),
-            };
+            let (topic, pid) = topic_partition(&topics, &pid);
             if let Some(offset) = offset {
-                if let Some(start_offset) = start_offsets.get_mut(&pid) {
+                let topic_offsets = start_offsets.entry(topic.to_string()).or_default();
+                if let Some(start_offset) = topic_offsets.get_mut(&pid) {
                     *start_offset = std::cmp::max(offset.offset - 1, *start_offset);
                 } else {
-                    start_offsets.insert(pid, offset.offset - 1);
+                    topic_offsets.insert(pid, offset.offset - 1);
                 }
             }
         }
@@ -143,7 +151,7 @@ impl SourceReader for KafkaSourceReader {
         let partition_info = Arc::new(Mutex::new(None));
         let metadata_thread_handle = {
             let partition_info = Arc::downgrade(&partition_info);
-            let topic = topic.clone();
+            let topics = TopicFilter::new(&topics)?;
             let consumer = Arc::clone(&consumer);
             let metadata_refresh_frequency = config_options
                 .get("topic.metadata.refresh.interval.ms")
@@ -157,7 +165,7 @@ impl SourceReader for KafkaSourceReader {
                 .name("kafka-metadata".to_string())
                 .spawn(move || {
                     while let Some(partition_info) = partition_info.upgrade() {
-                        match get_kafka_partitions(&consumer, &topic, Duration::from_secs(30)) {
+                        match get_kafka_partitions(&consumer, &topics, Duration::from_secs(30)) {
                             Ok(info) => {
                                 *partition_info.lock().unwrap() = Some(info);
                                 thread::park_timeout(metadata_refresh_frequency);
@@ -169,9 +177,13 @@ impl SourceReader for KafkaSourceReader {
                 .unwrap()
                 .unpark_on_drop()
         };
-        let partition_ids = start_offsets.keys().copied().collect();
+        let partition_ids = start_offsets
+            .iter()
+            .flat_map(|(topic, offsets)| offsets.keys().map(|pid| (topic.clone(), *pid)))
+            .collect();
         Ok(KafkaSourceReader {
-            topic_name: topic.clone(),
+            topics,
+            topic_qualified,
             source_name,
             id: source_id,
             partition_consumers: VecDeque::new(),
@@ -184,12 +196,7 @@ impl SourceReader for KafkaSourceReader {
             partition_info,
             include_headers: kc.include_headers.is_some(),
             _metadata_thread_handle: metadata_thread_handle,
-            partition_metrics: KafkaPartitionMetrics::new(
-                base_metrics,
-                partition_ids,
-                topic,
-                source_id,
-            ),
+            partition_metrics: KafkaPartitionMetrics::new(base_metrics, partition_ids, source_id),
         })
     }
 
@@ -204,8 +211,10 @@ impl SourceReader for KafkaSourceReader {
     ) -> Result<NextMessage<Self::Key, Self::Value>, SourceReaderError> {
         let partition_info = self.partition_info.lock().unwrap().take();
         if let Some(partitions) = partition_info {
-            for pid in partitions {
-                self.add_partition(PartitionId::Kafka(pid));
+            for (topic, pids) in partitions {
+                for pid in pids {
+                    self.add_partition(partition_id(self.topic_qualified, &topic, pid));
+                }
             }
         }
         let mut next_message = NextMessage::Pending;
@@ -221,10 +230,14 @@ impl SourceReader for KafkaSourceReader {
             match result {
                 Err(e) => error!(
                     "kafka error when polling consumer for source: {} topic: {} : {}",
-                    self.source_name, self.topic_name, e
+                    self.source_name, self.topics, e
                 ),
                 Ok(message) => {
-                    let source_message = construct_source_message(&message, self.include_headers);
+                    let source_message = construct_source_message(
+                        &message,
+                        self.topic_qualified,
+                        self.include_headers,
+                    );
                     next_message = self.handle_message(source_message);
                 }
             }
@@ -262,24 +275,32 @@ impl KafkaSourceReader {
         if !crate::source::responsible_for(&self.id, self.worker_id, self.worker_count, &pid) {
             return;
         }
-        let pid = match pid {
-            PartitionId::Kafka(p) => p,
-            _ => unreachable!(),
-        };
-        if self.last_offsets.contains_key(&pid) {
+        let (topic, pid) = topic_partition(&self.topics, &pid);
+        let topic = topic.to_string();
+        if self
+            .last_offsets
+            .get(&topic)
+            .map_or(false, |offsets| offsets.contains_key(&pid))
+        {
             return;
         }
 
-        let start_offset = match self.start_offsets.get(&pid) {
-            Some(offset) => *offset,
-            None => 0,
-        };
+        let start_offset = self
+            .start_offsets
+            .get(&topic)
+            .and_then(|offsets| offsets.get(&pid))
+            .copied()
+            .unwrap_or(0);
 
-        self.create_partition_queue(pid, Offset::Offset(start_offset));
+        self.create_partition_queue(&topic, pid, Offset::Offset(start_offset));
 
         // Indicate a last offset of -1 if we have not been instructed to have a specific start
         // offset for this topic.
-        let prev = self.last_offsets.insert(pid, start_offset - 1);
+        let prev = self
+            .last_offsets
+            .entry(topic)
+            .or_default()
+            .insert(pid, start_offset - 1);
 
         assert!(prev.is_none());
     }
@@ -291,25 +312,25 @@ impl KafkaSourceReader {
         self.partition_consumers.len().try_into().unwrap()
     }
 
-    /// Creates a new partition queue for `partition_id`.
-    fn create_partition_queue(&mut self, partition_id: i32, initial_offset: Offset) {
+    /// Creates a new partition queue for `partition_id` of `topic`.
+    fn create_partition_queue(&mut self, topic: &str, partition_id: i32, initial_offset: Offset) {
         info!(
             "Activating Kafka queue for {} [{}] (source {}) on worker {}",
-            self.topic_name, partition_id, self.id, self.worker_id
+            topic, partition_id, self.id, self.worker_id
         );
 
         // Collect old partition assignments
         let tpl = self.consumer.assignment().unwrap();
         // Create list from assignments
         let mut partition_list = TopicPartitionList::new();
-        for partition in tpl.elements_for_topic(&self.topic_name) {
+        for partition in tpl.elements() {
             partition_list
                 .add_partition_offset(partition.topic(), partition.partition(), partition.offset())
                 .expect("offset known to be valid");
         }
         // Add new partition
         partition_list
-            .add_partition_offset(&self.topic_name, partition_id, initial_offset)
+            .add_partition_offset(topic, partition_id, initial_offset)
             .expect("offset known to be valid");
         self.consumer
             .assign(&partition_list)
@@ -321,7 +342,7 @@ impl KafkaSourceReader {
         for pc in &mut self.partition_consumers {
             pc.partition_queue = self
                 .consumer
-                .split_partition_queue(&self.topic_name, pc.pid)
+                .split_partition_queue(&pc.topic, pc.pid)
                 .expect("partition known to be valid");
             pc.partition_queue.set_nonempty_callback({
                 let context = Arc::clone(&context);
@@ -331,20 +352,18 @@ impl KafkaSourceReader {
 
         let mut partition_queue = self
             .consumer
-            .split_partition_queue(&self.topic_name, partition_id)
+            .split_partition_queue(topic, partition_id)
             .expect("partition known to be valid");
         partition_queue.set_nonempty_callback(move || context.activate());
         self.partition_consumers.push_front(PartitionConsumer::new(
+            topic.to_string(),
             partition_id,
+            self.topic_qualified,
             partition_queue,
             self.include_headers,
         ));
         assert_eq!(
-            self.consumer
-                .assignment()
-                .unwrap()
-                .elements_for_topic(&self.topic_name)
-                .len(),
+            self.consumer.assignment().unwrap().count(),
             self.partition_consumers.len()
         );
     }
@@ -352,9 +371,9 @@ impl KafkaSourceReader {
     /// Fast-forward consumer to specified Kafka Offset. Prints a warning if failed to do so
     /// Assumption: if offset does not exist (for instance, because of compaction), will seek
     /// to the next available offset
-    fn fast_forward_consumer(&self, pid: i32, next_offset: i64) {
+    fn fast_forward_consumer(&self, topic: &str, pid: i32, next_offset: i64) {
         let res = self.consumer.seek(
-            &self.topic_name,
+            topic,
             pid,
             Offset::Offset(next_offset),
             Duration::from_secs(1),
@@ -362,22 +381,20 @@ impl KafkaSourceReader {
         match res {
             Ok(_) => {
                 let res = self.consumer.position().unwrap_or_default().to_topic_map();
-                let position = res
-                    .get(&(self.topic_name.clone(), pid))
-                    .and_then(|p| match p {
-                        Offset::Offset(o) => Some(o),
-                        _ => None,
-                    });
+                let position = res.get(&(topic.to_string(), pid)).and_then(|p| match p {
+                    Offset::Offset(o) => Some(o),
+                    _ => None,
+                });
                 if let Some(position) = position {
                     if *position != next_offset {
-                        warn!("Did not fast-forward consumer on partition PID: {} to the correct Kafka offset. Currently at offset: {} Expected offset: {}",
-                              pid, position, next_offset);
+                        warn!("Did not fast-forward consumer on topic {} partition PID: {} to the correct Kafka offset. Currently at offset: {} Expected offset: {}",
+                              topic, pid, position, next_offset);
                     } else {
-                        info!("Successfully fast-forwarded consumer on partition PID: {} to Kafka offset {}.", pid, position);
+                        info!("Successfully fast-forwarded consumer on topic {} partition PID: {} to Kafka offset {}.", topic, pid, position);
                     }
                 } else {
-                    warn!("Tried to fast-forward consumer on partition PID: {} to Kafka offset {}. Could not obtain new consumer position",
-                          pid, next_offset);
+                    warn!("Tried to fast-forward consumer on topic {} partition PID: {} to Kafka offset {}. Could not obtain new consumer position",
+                          topic, pid, next_offset);
                 }
             }
             Err(e) => error!(
@@ -392,15 +409,13 @@ impl KafkaSourceReader {
         while let Ok(stats) = self.stats_rx.try_recv() {
             match serde_json::from_str::<Statistics>(&stats.to_string()) {
                 Ok(statistics) => {
-                    let topic = statistics.topics.get(&self.topic_name);
-                    match topic {
-                        Some(topic) => {
-                            for (id, partition) in &topic.partitions {
-                                self.partition_metrics
-                                    .set_offset_max(*id, partition.hi_offset);
-                            }
+                    // Statistics are only reported for the topics that are assigned
+                    // to this consumer.
+                    for (name, topic) in &statistics.topics {
+                        for (id, partition) in &topic.partitions {
+                            self.partition_metrics
+                                .set_offset_max(name, *id, partition.hi_offset);
                         }
-                        None => error!("No stats found for topic: {}", &self.topic_name),
                     }
                 }
                 Err(e) => {
@@ -420,17 +435,17 @@ impl KafkaSourceReader {
 
         let message = match partition_queue.get_next_message() {
             Err(e) => {
-                let pid = partition_queue.pid();
                 let last_offset = self
                     .last_offsets
-                    .get(&pid)
+                    .get(&partition_queue.topic)
+                    .and_then(|offsets| offsets.get(&partition_queue.pid))
                     .expect("partition known to be installed");
 
                 error!(
                         "kafka error consuming from source: {} topic: {}: partition: {} last processed offset: {} : {}",
                         self.source_name,
-                        self.topic_name,
-                        pid,
+                        partition_queue.topic,
+                        partition_queue.pid,
                         last_offset,
                         e
                     );
@@ -450,10 +465,7 @@ impl KafkaSourceReader {
         &mut self,
         message: SourceMessage<Option<Vec<u8>>, Option<Vec<u8>>>,
     ) -> NextMessage<Option<Vec<u8>>, Option<Vec<u8>>> {
-        let partition = match message.partition {
-            PartitionId::Kafka(pid) => pid,
-            _ => unreachable!(),
-        };
+        let (topic, partition) = topic_partition(&self.topics, &message.partition);
 
         // Convert the received offset back from a 1-indexed MzOffset to the correct offset.
         let offset = message.offset.offset - 1;
@@ -468,11 +480,10 @@ impl KafkaSourceReader {
 
         // Given the explicit consumer to partition assignment, we should never receive a message
         // for a partition for which we have no metadata
-        assert!(self.last_offsets.contains_key(&partition));
-
         let last_offset_ref = self
             .last_offsets
-            .get_mut(&partition)
+            .get_mut(topic)
+            .and_then(|offsets| offsets.get_mut(&partition))
             .expect("partition known to be installed");
 
         let last_offset = *last_offset_ref;
@@ -482,13 +493,13 @@ impl KafkaSourceReader {
                              source {} (reading topic {}, partition {}) \
                              received offset {} expected offset {:?}",
                 self.source_name,
-                self.topic_name,
+                topic,
                 partition,
                 offset,
                 last_offset + 1,
             );
             // Seek to the *next* 0 indexed offset that we have not yet processed
-            self.fast_forward_consumer(partition, last_offset + 1);
+            self.fast_forward_consumer(topic, partition, last_offset + 1);
             // We explicitly should not consume the message as we have already processed it
             // However, we make sure to activate the source to make sure that we get a chance
             // to read from this consumer again (even if no new data arrives)
@@ -500,6 +511,32 @@ impl KafkaSourceReader {
     }
 }
 
+/// Returns the partition ID for `partition` of `topic`.
+fn partition_id(topic_qualified: bool, topic: &str, partition: i32) -> PartitionId {
+    if topic_qualified {
+        PartitionId::KafkaTopic {
+            topic: topic.to_string(),
+            partition,
+        }
+    } else {
+        PartitionId::Kafka(partition)
+    }
+}
+
+/// Returns the topic and partition number that `pid` refers to.
+fn topic_partition<'a>(topics: &'a KafkaTopics, pid: &'a PartitionId) -> (&'a str, i32) {
+    match pid {
+        PartitionId::Kafka(partition) => {
+            let topic = topics
+                .single()
+                .expect("unqualified partitions belong to single-topic sources");
+            (topic, *partition)
+        }
+        PartitionId::KafkaTopic { topic, partition } => (topic, *partition),
+        PartitionId::Kinesis(_) | PartitionId::None => panic!("unexpected partition id type"),
+    }
+}
+
 /// Creates a Kafka config.
 fn create_kafka_config(
     name: &str,
@@ -577,6 +614,7 @@ fn create_kafka_config(
 
 fn construct_source_message(
     msg: &BorrowedMessage<'_>,
+    topic_qualified: bool,
     include_headers: bool,
 ) -> SourceMessage<Option<Vec<u8>>, Option<Vec<u8>>> {
     let kafka_offset = KafkaOffset {
@@ -592,9 +630,10 @@ fn construct_source_message(
         _ => None,
     };
     SourceMessage {
-        partition: PartitionId::Kafka(msg.partition()),
+        partition: partition_id(topic_qualified, msg.topic(), msg.partition()),
         offset: kafka_offset.into(),
         upstream_time_millis: msg.timestamp().to_millis(),
+        upstream_position: None,
         key: msg.key().map(|k| k.to_vec()),
         value: msg.payload().map(|p| p.to_vec()),
         headers,
@@ -603,8 +642,12 @@ fn construct_source_message(
 
 /// Wrapper around a partition containing the underlying consumer
 struct PartitionConsumer {
+    /// the topic with which this consumer is associated
+    topic: String,
     /// the partition id with which this consumer is associated
     pid: i32,
+    /// Whether partitions are identified as [`PartitionId::KafkaTopic`]
+    topic_qualified: bool,
     /// The underlying Kafka partition queue
     partition_queue: PartitionQueue<GlueConsumerContext>,
     /// Whether or not to unpack and allocate headers and pass them through in the `SourceMessage`
@@ -614,12 +657,16 @@ struct PartitionConsumer {
 impl PartitionConsumer {
     /// Creates a new partition consumer from underlying Kafka consumer
     fn new(
+        topic: String,
         pid: i32,
+        topic_qualified: bool,
         partition_queue: PartitionQueue<GlueConsumerContext>,
         include_headers: bool,
     ) -> Self {
         PartitionConsumer {
+            topic,
             pid,
+            topic_qualified,
             partition_queue,
             include_headers,
         }
@@ -633,19 +680,18 @@ impl PartitionConsumer {
     ) -> Result<Option<SourceMessage<Option<Vec<u8>>, Option<Vec<u8>>>>, KafkaError> {
         match self.partition_queue.poll(Duration::from_millis(0)) {
             Some(Ok(msg)) => {
-                let result = construct_source_message(&msg, self.include_headers);
-                assert_eq!(result.partition, PartitionId::Kafka(self.pid));
+                let result =
+                    construct_source_message(&msg, self.topic_qualified, self.include_headers);
+                assert_eq!(
+                    result.partition,
+                    partition_id(self.topic_qualified, &self.topic, self.pid)
+                );
                 Ok(Some(result))
             }
             Some(Err(err)) => Err(err),
             _ => Ok(None),
         }
     }
-
-    /// Return the partition id for this PartitionConsumer
-    fn pid(&self) -> i32 {
-        self.pid
-    }
 }
 
 /// An implementation of [`ConsumerContext`] that forwards statistics to the
@@ -688,18 +734,49 @@ impl GlueConsumerContext {
 
 impl ConsumerContext for GlueConsumerContext {}
 
-/// Return the list of partition ids associated with a specific topic
+/// The topics whose partitions a metadata refresh looks up.
+enum TopicFilter {
+    Names(Vec<String>),
+    Pattern(Regex),
+}
+
+impl TopicFilter {
+    fn new(topics: &KafkaTopics) -> Result<Self, anyhow::Error> {
+        Ok(match topics {
+            KafkaTopics::Names(names) => TopicFilter::Names(names.clone()),
+            KafkaTopics::Pattern(pattern) => {
+                TopicFilter::Pattern(KafkaTopics::compile_pattern(pattern)?)
+            }
+        })
+    }
+}
+
+/// Return the list of partition ids associated with each topic that matches `topics`
 fn get_kafka_partitions(
     consumer: &BaseConsumer<GlueConsumerContext>,
-    topic: &str,
+    topics: &TopicFilter,
     timeout: Duration,
-) -> Result<Vec<i32>, anyhow::Error> {
-    let metadata = consumer.fetch_metadata(Some(topic), timeout)?;
-    Ok(metadata.topics()[0]
-        .partitions()
-        .iter()
-        .map(|x| x.id())
-        .collect())
+) -> Result<BTreeMap<String, Vec<i32>>, anyhow::Error> {
+    match topics {
+        TopicFilter::Names(names) => {
+            let mut partitions = BTreeMap::new();
+            for topic in names {
+                let metadata = consumer.fetch_metadata(Some(topic), timeout)?;
+                let ids = metadata.topics()[0]
+                    .partitions()
+                    .iter()
+                    .map(|x| x.id())
+                    .collect();
+                partitions.insert(topic.clone(), ids);
+            }
+            Ok(partitions)
+        }
+        TopicFilter::Pattern(regex) => mz_kafka_util::client::get_matching_partitions(
+            consumer.client(),
+            |topic| regex.is_match(topic),
+            timeout,
+        ),
+    }
 }
 
 #[cfg(test)]
@@ -12,41 +12,41 @@ use std::collections::HashMap;
 use prometheus::core::AtomicI64;
 use tracing::debug;
 
-use mz_ore::iter::IteratorExt;
 use mz_ore::metrics::{DeleteOnDropGauge, GaugeVecExt};
 use mz_repr::GlobalId;
 
 use crate::source::metrics::SourceBaseMetrics;
 pub(super) struct KafkaPartitionMetrics {
-    labels: Vec<String>,
+    source_id: String,
     base_metrics: SourceBaseMetrics,
-    partition_offset_map: HashMap<i32, DeleteOnDropGauge<'static, AtomicI64, Vec<String>>>,
+    partition_offset_map:
+        HashMap<(String, i32), DeleteOnDropGauge<'static, AtomicI64, Vec<String>>>,
 }
 
 impl KafkaPartitionMetrics {
     pub fn new(
         base_metrics: SourceBaseMetrics,
-        ids: Vec<i32>,
-        topic: String,
+        ids: Vec<(String, i32)>,
         source_id: GlobalId,
     ) -> Self {
         let metrics = &base_metrics.partition_specific;
+        let source_id = source_id.to_string();
         Self {
-            partition_offset_map: HashMap::from_iter(ids.iter().map(|id| {
-                let labels = &[topic.clone(), source_id.to_string(), format!("{}", id)];
+            partition_offset_map: HashMap::from_iter(ids.into_iter().map(|(topic, id)| {
+                let labels = vec![topic.clone(), source_id.clone(), format!("{}", id)];
                 (
-                    *id,
+                    (topic, id),
                     metrics
                         .partition_offset_max
-                        .get_delete_on_drop_gauge(labels.to_vec()),
+                        .get_delete_on_drop_gauge(labels),
                 )
             })),
-            labels: vec![topic.clone(), source_id.to_string()],
+            source_id,
             base_metrics,
         }
     }
 
-    pub fn set_offset_max(&mut self, id: i32, offset: i64) {
+    pub fn set_offset_max(&mut self, topic: &str, id: i32, offset: i64) {
         // Valid partition ids start at 0, librdkafka uses -1 as a sentinel for unassigned partitions
         if id < 0 {
             return;
@@ -58,18 +58,16 @@ impl KafkaPartitionMetrics {
             return;
         }
         self.partition_offset_map
-            .entry(id)
-            .or_insert_with_key(|id| {
+            .entry((topic.to_string(), id))
+            .or_insert_with_key(|(topic, id)| {
                 self.base_metrics
                     .partition_specific
                     .partition_offset_max
-                    .get_delete_on_drop_gauge(
-                        self.labels
-                            .iter()
-                            .cloned()
-                            .chain_one(format!("{}", id))
-                            .collect(),
-                    )
+                    .get_delete_on_drop_gauge(vec![
+                        topic.clone(),
+                        self.source_id.clone(),
+                        format!("{}", id),
+                    ])
             })
             .set(offset);
     }
@@ -7,11 +7,30 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use std::collections::{HashMap, HashSet, VecDeque};
+//! Kinesis source reader.
+//!
+//! Every shard of the stream is a separate partition, identified by
+//! [`PartitionId::Kinesis`]. Records are numbered per shard, and the sequence
+//! number of each record is reported as its upstream position, which is made
+//! durable as a checkpoint along with the source's timestamp bindings.
+//!
+//! On restart, a shard with a restored offset resumes reading immediately
+//! after its checkpoint. A shard without one must be replayed in its entirety,
+//! so it is read from its oldest retained record, and its records are
+//! numbered such that the checkpointed record keeps its offset. This keeps the
+//! restored timestamp bindings valid even if older records have expired from
+//! the stream in the meantime.
+//!
+//! When a stream is resharded, the parent shards are closed and new child
+//! shards are opened. Child shards are only read once all of their parents
+//! have been read to the end, which preserves the order of records with the
+//! same partition key.
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, VecDeque};
 use std::time::Duration;
 use std::time::Instant;
 
-use anyhow::anyhow;
 use aws_sdk_kinesis::error::GetRecordsError;
 use aws_sdk_kinesis::output::GetRecordsOutput;
 use aws_sdk_kinesis::types::SdkError;
@@ -22,8 +41,8 @@ use timely::scheduling::SyncActivator;
 use tracing::error;
 
 use mz_dataflow_types::sources::{
-    encoding::SourceDataEncoding, AwsExternalId, ExternalSourceConnector, KinesisSourceConnector,
-    MzOffset,
+    encoding::SourceDataEncoding, AwsExternalId, ExternalSourceConnector, MzOffset,
+    SourceCheckpoint,
 };
 use mz_dataflow_types::SourceErrorDetails;
 use mz_expr::PartitionId;
@@ -46,22 +65,141 @@ pub struct KinesisSourceReader {
     kinesis_client: KinesisClient,
     /// The name of the stream
     stream_name: String,
-    /// The set of active shards
-    shard_set: HashMap<String, ShardMetrics>,
+    /// The state of every shard we know about
+    shards: HashMap<String, ShardState>,
     /// A queue representing the next shard to read from. This is necessary
     /// to ensure that all shards are read from uniformly
-    shard_queue: VecDeque<(String, Option<String>)>,
+    shard_queue: VecDeque<String>,
     /// The time at which we last refreshed metadata
-    /// TODO(natacha): this should be moved to timestamper
     last_checked_shards: Instant,
     /// Storage for messages that have not yet been timestamped
     buffered_messages: VecDeque<SourceMessage<(), Option<Vec<u8>>>>,
-    /// Count of processed message
-    processed_message_count: i64,
+    /// Offsets before which records of a shard were ingested prior to a restart
+    restored_offsets: HashMap<String, MzOffset>,
+    /// Durable positions from which shards can resume reading after a restart
+    restored_checkpoints: HashMap<String, SourceCheckpoint>,
     /// Metrics from which per-shard metrics get created.
     base_metrics: KinesisMetrics,
 }
 
+/// How far along we are in reading a shard.
+enum ShardStatus {
+    /// The shard cannot be read until all of its parents are closed.
+    Waiting,
+    /// The shard is being read with the contained shard iterator.
+    Reading(String),
+    /// The shard was closed by a resharding operation and has been read to
+    /// the end.
+    Closed,
+}
+
+struct ShardState {
+    /// The shards this shard was split from or merged from, if any
+    parents: Vec<String>,
+    status: ShardStatus,
+    /// The offset of the last record read from the shard
+    offset: MzOffset,
+    /// The sequence number of the last record read from the shard
+    last_sequence_number: Option<String>,
+    /// Records at offsets before this one were already ingested and are skipped
+    resume_offset: MzOffset,
+    /// The checkpoint that replayed records are numbered relative to, until it
+    /// is reached
+    anchor: Option<SourceCheckpoint>,
+    /// Replayed records that cannot be numbered until the anchor is reached
+    replayed: Vec<(String, Vec<u8>)>,
+    metrics: ShardMetrics,
+}
+
+impl ShardState {
+    /// Assigns the next offset to a record read from the shard identified by
+    /// `shard_id`, and buffers it into `messages` unless it was already
+    /// ingested.
+    fn push_record(
+        &mut self,
+        shard_id: &str,
+        sequence_number: String,
+        data: Vec<u8>,
+        messages: &mut VecDeque<SourceMessage<(), Option<Vec<u8>>>>,
+    ) {
+        self.last_sequence_number = Some(sequence_number.clone());
+        if let Some(anchor) = &self.anchor {
+            let ordering = cmp_sequence_numbers(&sequence_number, &anchor.upstream_position);
+            self.replayed.push((sequence_number, data));
+            match ordering {
+                Ordering::Less => return,
+                // The anchor keeps its offset.
+                Ordering::Equal => self.offset = anchor.offset,
+                // The anchor has expired from the stream, so this is the first
+                // record after it.
+                Ordering::Greater => self.offset = anchor.offset + 1,
+            }
+            self.anchor = None;
+            self.flush_replayed(shard_id, messages);
+            return;
+        }
+
+        self.offset = self.offset + 1;
+        if self.offset < self.resume_offset {
+            return;
+        }
+        messages.push_back(SourceMessage {
+            partition: PartitionId::Kinesis(shard_id.into()),
+            offset: self.offset,
+            upstream_time_millis: None,
+            upstream_position: Some(sequence_number),
+            key: (),
+            value: Some(data),
+            headers: None,
+        });
+    }
+
+    /// Numbers all replayed records such that the last one is at the current
+    /// offset of the shard, and buffers them into `messages`.
+    fn flush_replayed(
+        &mut self,
+        shard_id: &str,
+        messages: &mut VecDeque<SourceMessage<(), Option<Vec<u8>>>>,
+    ) {
+        let count = i64::try_from(self.replayed.len()).expect("too many records");
+        let first_offset = self.offset + (1 - count);
+        for (i, (sequence_number, data)) in self.replayed.drain(..).enumerate() {
+            messages.push_back(SourceMessage {
+                partition: PartitionId::Kinesis(shard_id.into()),
+                offset: first_offset + i64::try_from(i).expect("too many records"),
+                upstream_time_millis: None,
+                upstream_position: Some(sequence_number),
+                key: (),
+                value: Some(data),
+                headers: None,
+            });
+        }
+    }
+
+    /// Stops waiting for the anchor, which is not in the stream, and numbers
+    /// all replayed records from the start of the shard.
+    fn abandon_anchor(
+        &mut self,
+        shard_id: &str,
+        messages: &mut VecDeque<SourceMessage<(), Option<Vec<u8>>>>,
+    ) {
+        if self.anchor.take().is_some() {
+            self.offset = MzOffset {
+                offset: i64::try_from(self.replayed.len()).expect("too many records"),
+            };
+            self.flush_replayed(shard_id, messages);
+        }
+    }
+}
+
+/// Compares two sequence numbers of the same shard.
+///
+/// Sequence numbers are decimal strings without leading zeros that increase
+/// over time, so longer sequence numbers are always greater.
+fn cmp_sequence_numbers(a: &str, b: &str) -> Ordering {
+    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+}
+
 struct ShardMetrics {
     millis_behind_latest: DeleteOnDropGauge<'static, AtomicI64, Vec<String>>,
 }
@@ -77,31 +215,107 @@ impl ShardMetrics {
 }
 
 impl KinesisSourceReader {
+    /// Learns about new shards of the stream and starts reading any shards
+    /// whose parents have been read to the end.
     async fn update_shard_information(&mut self) -> Result<(), anyhow::Error> {
-        let current_shards: HashSet<_> =
-            mz_kinesis_util::get_shard_ids(&self.kinesis_client, &self.stream_name)
-                .await?
+        let shards = mz_kinesis_util::list_shards(&self.kinesis_client, &self.stream_name).await?;
+        for shard in shards {
+            let shard_id = shard.shard_id.unwrap_or_else(|| "".into());
+            if self.shards.contains_key(&shard_id) {
+                continue;
+            }
+            let parents = [shard.parent_shard_id, shard.adjacent_parent_shard_id]
+                .into_iter()
+                .flatten()
                 .collect();
-        let known_shards: HashSet<_> = self.shard_set.keys().cloned().collect();
-        let new_shards = current_shards
-            .difference(&known_shards)
-            .map(|shard_id| shard_id.to_owned());
-        for shard_id in new_shards {
-            self.shard_set.insert(
-                shard_id.to_string(),
-                ShardMetrics::new(&self.base_metrics, &self.stream_name, &shard_id),
-            );
-            self.shard_queue.push_back((
-                shard_id.to_string(),
+            let checkpoint = self.restored_checkpoints.remove(&shard_id);
+            let resume_offset = self.restored_offsets.remove(&shard_id);
+            let mut state = ShardState {
+                parents,
+                status: ShardStatus::Waiting,
+                offset: MzOffset::default(),
+                last_sequence_number: None,
+                resume_offset: resume_offset.unwrap_or_default(),
+                anchor: None,
+                replayed: Vec::new(),
+                metrics: ShardMetrics::new(&self.base_metrics, &self.stream_name, &shard_id),
+            };
+            match (resume_offset, checkpoint) {
+                // Records before the restored offset are not needed again, so
+                // start reading after the checkpoint, which they cover.
+                (Some(_), Some(checkpoint)) => {
+                    state.offset = checkpoint.offset;
+                    state.last_sequence_number = Some(checkpoint.upstream_position);
+                }
+                // The shard has to be replayed from the start.
+                (None, Some(checkpoint)) => state.anchor = Some(checkpoint),
+                (_, None) => (),
+            }
+            self.shards.insert(shard_id, state);
+        }
+        self.start_ready_shards().await
+    }
+
+    /// Starts reading all waiting shards whose parents are closed.
+    ///
+    /// Parents that are no longer listed have aged out of the stream's
+    /// retention period, and so cannot hold back their children.
+    async fn start_ready_shards(&mut self) -> Result<(), anyhow::Error> {
+        loop {
+            let ready: Vec<String> = self
+                .shards
+                .iter()
+                .filter(|(_, shard)| matches!(shard.status, ShardStatus::Waiting))
+                .filter(|(_, shard)| {
+                    shard.parents.iter().all(|parent| {
+                        self.shards
+                            .get(parent)
+                            .map_or(true, |parent| matches!(parent.status, ShardStatus::Closed))
+                    })
+                })
+                .map(|(shard_id, _)| shard_id.clone())
+                .collect();
+            if ready.is_empty() {
+                return Ok(());
+            }
+            for shard_id in ready {
+                let shard_iterator = self.get_shard_iterator(&shard_id).await?;
+                let shard = self.shards.get_mut(&shard_id).expect("known to exist");
+                match shard_iterator {
+                    Some(shard_iterator) => {
+                        shard.status = ShardStatus::Reading(shard_iterator);
+                        self.shard_queue.push_back(shard_id);
+                    }
+                    None => shard.status = ShardStatus::Closed,
+                }
+            }
+        }
+    }
+
+    /// Obtains a shard iterator that starts after the last record read from
+    /// the shard, or at the oldest record of the shard if none has been read.
+    async fn get_shard_iterator(&self, shard_id: &str) -> Result<Option<String>, anyhow::Error> {
+        let shard = &self.shards[shard_id];
+        let shard_iterator = match &shard.last_sequence_number {
+            Some(sequence_number) => {
+                mz_kinesis_util::get_shard_iterator_after(
+                    &self.kinesis_client,
+                    &self.stream_name,
+                    shard_id,
+                    sequence_number,
+                )
+                .await?
+            }
+            None => {
                 mz_kinesis_util::get_shard_iterator(
                     &self.kinesis_client,
                     &self.stream_name,
-                    &shard_id,
+                    shard_id,
                 )
-                .await?,
-            ));
-        }
-        Ok(())
+                .await?
+            }
+        };
+        Ok(shard_iterator)
     }
 
     /// Obtains the next record for this shard given a shard iterator
@@ -129,7 +343,8 @@ impl SourceReader for KinesisSourceReader {
         _consumer_activator: SyncActivator,
         connector: ExternalSourceConnector,
         aws_external_id: AwsExternalId,
-        _restored_offsets: Vec<(PartitionId, Option<MzOffset>)>,
+        restored_offsets: Vec<(PartitionId, Option<MzOffset>)>,
+        restored_checkpoints: Vec<(PartitionId, SourceCheckpoint)>,
         _encoding: SourceDataEncoding,
         base_metrics: SourceBaseMetrics,
     ) -> Result<Self, anyhow::Error> {
@@ -138,26 +353,42 @@ impl SourceReader for KinesisSourceReader {
             _ => unreachable!(),
         };
 
-        let state = block_on(create_state(&base_metrics.kinesis, kc, aws_external_id));
-        match state {
-            Ok((kinesis_client, stream_name, shard_set, shard_queue)) => Ok(KinesisSourceReader {
-                kinesis_client,
-                shard_queue,
+        let restored_offsets = restored_offsets
+            .into_iter()
+            .filter_map(|(pid, offset)| match pid {
+                PartitionId::Kinesis(shard_id) => Some((shard_id, offset?)),
+                _ => None,
+            })
+            .collect();
+        let restored_checkpoints = restored_checkpoints
+            .into_iter()
+            .filter_map(|(pid, checkpoint)| match pid {
+                PartitionId::Kinesis(shard_id) => Some((shard_id, checkpoint)),
+                _ => None,
+            })
+            .collect();
+
+        block_on(async {
+            let config = kc.aws.load(aws_external_id).await;
+            let mut reader = KinesisSourceReader {
+                kinesis_client: aws_sdk_kinesis::Client::new(&config),
+                stream_name: kc.stream_name,
+                shards: HashMap::new(),
+                shard_queue: VecDeque::new(),
                 last_checked_shards: Instant::now(),
                 buffered_messages: VecDeque::new(),
-                shard_set,
-                stream_name,
-                processed_message_count: 0,
+                restored_offsets,
+                restored_checkpoints,
                 base_metrics: base_metrics.kinesis,
-            }),
-            Err(e) => Err(anyhow!("{}", e)),
-        }
+            };
+            reader.update_shard_information().await?;
+            Ok::<_, anyhow::Error>(reader)
+        })
     }
+
     fn get_next_message(
         &mut self,
     ) -> Result<NextMessage<Self::Key, Self::Value>, SourceReaderError> {
-        assert_eq!(self.shard_queue.len(), self.shard_set.len());
-
         //TODO move to timestamper
         if self.last_checked_shards.elapsed() >= KINESIS_SHARD_REFRESH_RATE {
             if let Err(e) = block_on(self.update_shard_information()) {
@@ -168,132 +399,117 @@ impl SourceReader for KinesisSourceReader {
         }
 
         if let Some(message) = self.buffered_messages.pop_front() {
-            Ok(NextMessage::Ready(message))
-        } else {
-            // Rotate through all of a stream's shards, start with a new shard on each activation.
-            if let Some((shard_id, mut shard_iterator)) = self.shard_queue.pop_front() {
-                if let Some(iterator) = &shard_iterator {
-                    let get_records_output = match block_on(self.get_records(&iterator)) {
-                        Ok(output) => {
-                            shard_iterator = output.next_shard_iterator.clone();
-                            if let Some(millis) = output.millis_behind_latest {
-                                self.shard_set
-                                    .get(&shard_id)
-                                    .unwrap()
-                                    .millis_behind_latest
-                                    .set(millis);
-                            }
-                            output
-                        }
-                        Err(SdkError::DispatchFailure(e)) => {
-                            // todo@jldlaughlin: Parse this to determine fatal/retriable?
-                            error!("{}", e);
-                            self.shard_queue.push_back((shard_id, shard_iterator));
-                            // Do not send error message as this would cause source to terminate
-                            return Ok(NextMessage::TransientDelay);
-                        }
-                        Err(SdkError::ServiceError { err, .. })
-                            if err.is_expired_iterator_exception() =>
-                        {
-                            // todo@jldlaughlin: Will need track source offsets to grab a new iterator.
-                            error!("{}", err);
-                            return Err(SourceReaderError {
-                                inner: SourceErrorDetails::Other(err.to_string()),
-                            });
-                        }
-                        Err(SdkError::ServiceError { err, .. })
-                            if err.is_provisioned_throughput_exceeded_exception() =>
-                        {
-                            self.shard_queue.push_back((shard_id, shard_iterator));
-                            // Do not send error message as this would cause source to terminate
-                            return Ok(NextMessage::Pending);
-                        }
+            return Ok(NextMessage::Ready(message));
+        }
+
+        // Rotate through all of a stream's shards, start with a new shard on each activation.
+        if let Some(shard_id) = self.shard_queue.pop_front() {
+            let shard_iterator = match &self.shards[&shard_id].status {
+                ShardStatus::Reading(shard_iterator) => shard_iterator.clone(),
+                ShardStatus::Waiting | ShardStatus::Closed => {
+                    unreachable!("only shards that are being read are queued")
+                }
+            };
+            let get_records_output = match block_on(self.get_records(&shard_iterator)) {
+                Ok(output) => output,
+                Err(SdkError::DispatchFailure(e)) => {
+                    // todo@jldlaughlin: Parse this to determine fatal/retriable?
+                    error!("{}", e);
+                    self.shard_queue.push_back(shard_id);
+                    // Do not send error message as this would cause source to terminate
+                    return Ok(NextMessage::TransientDelay);
+                }
+                Err(SdkError::ServiceError { err, .. }) if err.is_expired_iterator_exception() => {
+                    // Shard iterators expire five minutes after they are handed out. Obtain a
+                    // new one that picks up after the last record we read.
+                    let shard_iterator = match block_on(self.get_shard_iterator(&shard_id)) {
+                        Ok(shard_iterator) => shard_iterator,
                         Err(e) => {
-                            // Fatal service errors:
-                            //  - InvalidArgument
-                            //  - KMSAccessDenied, KMSDisabled, KMSInvalidState, KMSNotFound,
-                            //    KMSOptInRequired, KMSThrottling
-                            //  - ResourceNotFound
-                            //
-                            // Other fatal Rusoto errors:
-                            // - Credentials
-                            // - Validation
-                            // - ParseError
-                            // - Unknown (raw HTTP provided)
-                            // - Blocking
-                            error!("{}", e);
-                            return Err(SourceReaderError {
-                                inner: SourceErrorDetails::Other(e.to_string()),
-                            });
+                            error!("{:#?}", e);
+                            return Err(e.into());
                         }
                     };
+                    let shard = self.shards.get_mut(&shard_id).expect("known to exist");
+                    match shard_iterator {
+                        Some(shard_iterator) => {
+                            shard.status = ShardStatus::Reading(shard_iterator);
+                            self.shard_queue.push_back(shard_id);
+                        }
+                        None => shard.status = ShardStatus::Closed,
+                    }
+                    return Ok(NextMessage::TransientDelay);
+                }
+                Err(SdkError::ServiceError { err, .. })
+                    if err.is_provisioned_throughput_exceeded_exception() =>
+                {
+                    self.shard_queue.push_back(shard_id);
+                    // Do not send error message as this would cause source to terminate
+                    return Ok(NextMessage::Pending);
+                }
+                Err(e) => {
+                    // Fatal service errors:
+                    //  - InvalidArgument
+                    //  - KMSAccessDenied, KMSDisabled, KMSInvalidState, KMSNotFound,
+                    //    KMSOptInRequired, KMSThrottling
+                    //  - ResourceNotFound
+                    //
+                    // Other fatal Rusoto errors:
+                    // - Credentials
+                    // - Validation
+                    // - ParseError
+                    // - Unknown (raw HTTP provided)
+                    // - Blocking
+                    error!("{}", e);
+                    return Err(SourceReaderError {
+                        inner: SourceErrorDetails::Other(e.to_string()),
+                    });
+                }
+            };
 
-                    for record in get_records_output.records.unwrap_or_default() {
-                        let data = record
-                            .data
-                            .map(|blob| blob.into_inner())
-                            .unwrap_or_else(Vec::new);
-                        self.processed_message_count += 1;
-                        let source_message = SourceMessage {
-                            partition: PartitionId::None,
-                            offset: MzOffset {
-                                //TODO: should MzOffset be modified to be a string?
-                                offset: self.processed_message_count,
-                            },
-                            upstream_time_millis: None,
-                            key: (),
-                            value: Some(data),
-                            headers: None,
-                        };
-                        self.buffered_messages.push_back(source_message);
+            let shard = self.shards.get_mut(&shard_id).expect("known to exist");
+            if let Some(millis) = get_records_output.millis_behind_latest {
+                shard.metrics.millis_behind_latest.set(millis);
+            }
+            for record in get_records_output.records.unwrap_or_default() {
+                let data = record
+                    .data
+                    .map(|blob| blob.into_inner())
+                    .unwrap_or_else(Vec::new);
+                shard.push_record(
+                    &shard_id,
+                    record.sequence_number.unwrap_or_default(),
+                    data,
+                    &mut self.buffered_messages,
+                );
+            }
+            // Once we have caught up with the shard without finding the anchor, it is not
+            // coming. This only happens if the stream was recreated.
+            if get_records_output.millis_behind_latest == Some(0)
+                || get_records_output.next_shard_iterator.is_none()
+            {
+                shard.abandon_anchor(&shard_id, &mut self.buffered_messages);
+            }
+            match get_records_output.next_shard_iterator {
+                Some(shard_iterator) => {
+                    shard.status = ShardStatus::Reading(shard_iterator);
+                    self.shard_queue.push_back(shard_id);
+                }
+                None => {
+                    // The shard was closed by a resharding operation and we have read all of
+                    // its records, so its children can be read now.
+                    shard.status = ShardStatus::Closed;
+                    if let Err(e) = block_on(self.update_shard_information()) {
+                        error!("{:#?}", e);
+                        return Err(e.into());
                     }
-                    self.shard_queue.push_back((shard_id, shard_iterator));
+                    self.last_checked_shards = std::time::Instant::now();
                 }
             }
-            Ok(match self.buffered_messages.pop_front() {
-                Some(message) => NextMessage::Ready(message),
-                None => NextMessage::Pending,
-            })
         }
-    }
-}
 
-/// Creates the necessary data-structures for shard management
-// todo: Better error handling here! Not all errors mean we're done/can't progress.
-async fn create_state(
-    base_metrics: &KinesisMetrics,
-    c: KinesisSourceConnector,
-    aws_external_id: AwsExternalId,
-) -> Result<
-    (
-        KinesisClient,
-        String,
-        HashMap<String, ShardMetrics>,
-        VecDeque<(String, Option<String>)>,
-    ),
-    anyhow::Error,
-> {
-    let config = c.aws.load(aws_external_id).await;
-    let kinesis_client = aws_sdk_kinesis::Client::new(&config);
-
-    let shard_set = mz_kinesis_util::get_shard_ids(&kinesis_client, &c.stream_name).await?;
-    let mut shard_queue: VecDeque<(String, Option<String>)> = VecDeque::new();
-    let mut shard_map = HashMap::new();
-    for shard_id in shard_set {
-        shard_queue.push_back((
-            shard_id.clone(),
-            mz_kinesis_util::get_shard_iterator(&kinesis_client, &c.stream_name, &shard_id).await?,
-        ));
-        shard_map.insert(
-            shard_id.clone(),
-            ShardMetrics::new(base_metrics, &c.stream_name, &shard_id),
-        );
+        Ok(match self.buffered_messages.pop_front() {
+            Some(message) => NextMessage::Ready(message),
+            None => NextMessage::Pending,
+        })
     }
-
-    Ok((
-        kinesis_client,
-        c.stream_name.clone(),
-        shard_map,
-        shard_queue,
-    ))
 }
@@ -53,7 +53,9 @@ use tracing::error;
 
 use mz_avro::types::Value;
 use mz_dataflow_types::sources::encoding::SourceDataEncoding;
-use mz_dataflow_types::sources::{AwsExternalId, ExternalSourceConnector, MzOffset};
+use mz_dataflow_types::sources::{
+    AwsExternalId, ExternalSourceConnector, MzOffset, SourceCheckpoint,
+};
 use mz_dataflow_types::{DecodeError, SourceError, SourceErrorDetails};
 use mz_expr::PartitionId;
 use mz_ore::cast::CastFrom;
@@ -67,6 +69,7 @@ use crate::source::metrics::SourceBaseMetrics;
 use crate::source::timestamp::TimestampBindingRc;
 use crate::source::util::source;
 
+mod generator;
 mod kafka;
 mod kinesis;
 pub mod metrics;
@@ -78,6 +81,7 @@ pub mod util;
 
 pub mod timestamp;
 
+pub use generator::LoadGeneratorSourceReader;
 pub use kafka::KafkaSourceReader;
 pub use kinesis::KinesisSourceReader;
 pub use postgres::PostgresSourceReader;
@@ -169,6 +173,7 @@ where
         connector: ExternalSourceConnector,
         aws_external_id: AwsExternalId,
         restored_offsets: Vec<(PartitionId, Option<MzOffset>)>,
+        restored_checkpoints: Vec<(PartitionId, SourceCheckpoint)>,
         encoding: SourceDataEncoding,
         metrics: crate::source::metrics::SourceBaseMetrics,
     ) -> Result<Self, anyhow::Error> {
@@ -181,6 +186,7 @@ where
             connector,
             aws_external_id,
             restored_offsets,
+            restored_checkpoints,
             encoding,
             metrics,
         )
@@ -197,6 +203,7 @@ where
                 partition,
                 offset,
                 upstream_time_millis,
+                upstream_position,
                 headers,
             }) => Ok(NextMessage::Ready(SourceMessage {
                 key: None,
@@ -204,6 +211,7 @@ where
                 partition,
                 offset,
                 upstream_time_millis,
+                upstream_position,
                 headers,
             })),
             NextMessage::Pending => Ok(NextMessage::Pending),
@@ -230,6 +238,25 @@ pub struct DecodeResult {
     // TODO(bwm): This should probably be statically different for different streams, or we should
     // propagate whether metadata is requested into the decoder
     pub metadata: Row,
+    /// The undecoded bytes of the record, retained only if the key or value
+    /// failed to decode
+    pub undecoded: Option<UndecodedRecord>,
+}
+
+impl DecodeResult {
+    /// Reports whether the key or the value of the record failed to decode.
+    pub fn is_err(&self) -> bool {
+        matches!(self.key, Some(Err(_))) || matches!(self.value, Some(Err(_)))
+    }
+}
+
+/// The raw key and value of a record that failed to decode
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
+pub struct UndecodedRecord {
+    /// The raw key
+    pub key: Option<Vec<u8>>,
+    /// The raw value
+    pub value: Option<Vec<u8>>,
 }
 
 /// Kafka-specific information about the event
@@ -379,6 +406,9 @@ pub trait SourceReader {
     /// This function returns the source reader and optionally, any "partition" it's
     /// already reading. In practice, the partition is only non-None for static sources
     /// that either don't truly have partitions or have a fixed number of partitions.
+    ///
+    /// Readers that set [`SourceMessage::upstream_position`] receive the last durably
+    /// recorded position of each partition in `restored_checkpoints`.
     fn new(
         source_name: String,
         source_id: GlobalId,
@@ -388,6 +418,7 @@ pub trait SourceReader {
         connector: ExternalSourceConnector,
         aws_external_id: AwsExternalId,
         restored_offsets: Vec<(PartitionId, Option<MzOffset>)>,
+        restored_checkpoints: Vec<(PartitionId, SourceCheckpoint)>,
         encoding: SourceDataEncoding,
         metrics: crate::source::metrics::SourceBaseMetrics,
     ) -> Result<Self, anyhow::Error>
@@ -471,6 +502,10 @@ pub struct SourceMessage<Key, Value> {
     ///
     /// Milliseconds since the unix epoch
     pub upstream_time_millis: Option<i64>,
+    /// The position of the message in the external system, for sources whose positions cannot
+    /// be represented as an `MzOffset` (e.g. Kinesis sequence numbers). Reading can resume
+    /// from this position after a restart.
+    pub upstream_position: Option<String>,
     /// The message key
     pub key: Key,
     /// The message value
@@ -511,7 +546,8 @@ pub fn responsible_for(
     pid: &PartitionId,
 ) -> bool {
     match pid {
-        PartitionId::None => {
+        // Kinesis shards are all read by the single worker that is responsible for the source.
+        PartitionId::None | PartitionId::Kinesis(_) => {
             // All workers are responsible for reading in Kafka sources. Other sources
             // support single-threaded ingestion only. Note that in all cases we want all
             // readers of the same source or same partition to reside on the same worker,
@@ -532,6 +568,13 @@ pub fn responsible_for(
             let hash = (source_id.hashed() >> 32) + *p as u64;
             (hash % worker_count as u64) == worker_id as u64
         }
+        PartitionId::KafkaTopic { topic, partition } => {
+            // As above, but the topic is mixed into the offset so that the
+            // low-numbered partitions of many small topics do not all land
+            // on the same few workers.
+            let hash = (source_id.hashed() >> 32) + (topic.hashed() >> 32) + *partition as u64;
+            (hash % worker_count as u64) == worker_id as u64
+        }
     }
 }
 
@@ -988,6 +1031,10 @@ where
             .cloned()
             .flat_map(|(pid, offset)| Some((pid, offset?)))
             .collect();
+        let restored_checkpoints = timestamp_histories
+            .as_ref()
+            .map(|ts| ts.restored_checkpoints())
+            .unwrap_or_default();
 
         let mut source_reader = if !active {
             None
@@ -1001,6 +1048,7 @@ where
                 source_connector.clone(),
                 aws_external_id.clone(),
                 restored_offsets,
+                restored_checkpoints,
                 encoding,
                 base_metrics.clone(),
             ) {
@@ -1050,6 +1098,15 @@ where
                 match item {
                     Some(Ok(message)) => {
                         partition_cursors.insert(message.partition.clone(), message.offset + 1);
+                        if let Some(upstream_position) = &message.upstream_position {
+                            timestamp_histories.add_checkpoint(
+                                message.partition.clone(),
+                                SourceCheckpoint {
+                                    offset: message.offset,
+                                    upstream_position: upstream_position.clone(),
+                                },
+                            );
+                        }
                         handle_message::<S>(
                             message,
                             &mut bytes_read,
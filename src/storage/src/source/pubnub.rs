@@ -19,6 +19,7 @@ use tracing::info;
 
 use mz_dataflow_types::sources::{
     encoding::SourceDataEncoding, AwsExternalId, ExternalSourceConnector, MzOffset,
+    SourceCheckpoint,
 };
 use mz_expr::PartitionId;
 use mz_repr::{Datum, GlobalId, Row};
@@ -47,6 +48,7 @@ impl SourceReader for PubNubSourceReader {
         connector: ExternalSourceConnector,
         _aws_external_id: AwsExternalId,
         _restored_offsets: Vec<(PartitionId, Option<MzOffset>)>,
+        _restored_checkpoints: Vec<(PartitionId, SourceCheckpoint)>,
         _encoding: SourceDataEncoding,
         _metrics: SourceBaseMetrics,
     ) -> Result<Self, anyhow::Error> {
@@ -121,6 +123,7 @@ impl SourceReader for PubNubSourceReader {
                                 offset: msg.timetoken.t.try_into().unwrap(),
                             },
                             upstream_time_millis: None,
+                            upstream_position: None,
                             key: (),
                             value: row,
                             headers: None,
@@ -32,7 +32,7 @@ use std::convert::{From, TryInto};
 use std::default::Default;
 use std::ops::AddAssign;
 
-use async_compression::tokio::bufread::GzipDecoder;
+use async_compression::tokio::bufread::{BzDecoder, GzipDecoder, ZstdDecoder};
 use aws_sdk_s3::error::{GetObjectError, ListObjectsV2Error};
 use aws_sdk_s3::types::SdkError;
 use aws_sdk_s3::Client as S3Client;
@@ -49,7 +49,7 @@ use tracing::{debug, error, trace, warn};
 
 use mz_dataflow_types::sources::{
     encoding::SourceDataEncoding, AwsConfig, AwsExternalId, Compression, ExternalSourceConnector,
-    MzOffset, S3KeySource,
+    MzOffset, S3KeySource, SourceCheckpoint,
 };
 use mz_expr::PartitionId;
 use mz_ore::retry::{Retry, RetryReader};
@@ -672,8 +672,10 @@ async fn download_object(
         if let Some(s) = obj.content_encoding.as_deref() {
             match (s, compression) {
                 ("gzip", Compression::Gzip) => (),
+                ("bzip2" | "x-bzip2", Compression::Bzip2) => (),
+                ("zstd", Compression::Zstd) => (),
                 ("identity", Compression::None) => (),
-                ("identity" | "gzip", _) => {
+                ("identity" | "gzip" | "bzip2" | "x-bzip2" | "zstd", _) => {
                     debug!("object {} has mismatched Content-Encoding: {}", key, s)
                 }
                 _ => debug!("object {} has unrecognized Content-Encoding: {}", key, s),
@@ -704,6 +706,14 @@ async fn download_object(
             let decoder = GzipDecoder::new(reader);
             read_object_chunked(source_id, decoder, tx).await
         }
+        Compression::Bzip2 => {
+            let decoder = BzDecoder::new(reader);
+            read_object_chunked(source_id, decoder, tx).await
+        }
+        Compression::Zstd => {
+            let decoder = ZstdDecoder::new(reader);
+            read_object_chunked(source_id, decoder, tx).await
+        }
     };
 
     debug!(
@@ -780,6 +790,7 @@ impl SourceReader for S3SourceReader {
         connector: ExternalSourceConnector,
         aws_external_id: AwsExternalId,
         _restored_offsets: Vec<(PartitionId, Option<MzOffset>)>,
+        _restored_checkpoints: Vec<(PartitionId, SourceCheckpoint)>,
         _encoding: SourceDataEncoding,
         metrics: SourceBaseMetrics,
     ) -> Result<Self, anyhow::Error> {
@@ -876,6 +887,7 @@ impl SourceReader for S3SourceReader {
                     partition: PartitionId::None,
                     offset: self.offset.into(),
                     upstream_time_millis: None,
+                    upstream_position: None,
                     key: (),
                     value: record,
                     headers: None,
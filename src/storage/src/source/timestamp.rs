@@ -23,7 +23,7 @@
 //! its peers will respect.
 
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::rc::Rc;
 use std::time::Instant;
 
@@ -36,12 +36,14 @@ use timely::progress::frontier::{Antichain, AntichainRef, MutableAntichain};
 use timely::progress::{ChangeBatch, Timestamp as TimelyTimestamp};
 use tracing::debug;
 
-use mz_dataflow_types::sources::MzOffset;
+use mz_dataflow_types::sources::{MzOffset, SourceCheckpoint};
 use mz_expr::PartitionId;
 use mz_ore::now::NowFn;
 use mz_repr::Timestamp;
 
-use crate::source::{proto_source_timestamp, ProtoAssignedTimestamp, ProtoSourceTimestamp};
+use crate::source::{
+    proto_source_timestamp, ProtoAssignedTimestamp, ProtoKafkaTopicPartition, ProtoSourceTimestamp,
+};
 
 /// This struct holds state for proposed timestamps and
 /// proposed bindings from offsets to timestamps.
@@ -282,6 +284,12 @@ pub struct TimestampBindingBox {
     known_partitions: HashMap<PartitionId, Option<MzOffset>>,
     /// List of timestamp bindings per independent partition.
     partitions: HashMap<PartitionId, PartitionTimestamps>,
+    /// Upstream positions of messages read per partition, keyed by offset, for sources whose
+    /// positions cannot be represented as offsets. Positions are retained until a checkpoint
+    /// covered by reported timestamp bindings supersedes them.
+    checkpoints: HashMap<PartitionId, BTreeMap<MzOffset, String>>,
+    /// Checkpoints that we learned about from the coordinator, from which readers can resume.
+    restored_checkpoints: HashMap<PartitionId, SourceCheckpoint>,
     /// Indicates the lowest timestamp across all partitions that we retain bindings for.
     /// This frontier can be held back by other entities holding the shared
     /// `TimestampBindingRc`.
@@ -300,6 +308,8 @@ impl TimestampBindingBox {
         Self {
             known_partitions: HashMap::new(),
             partitions: HashMap::new(),
+            checkpoints: HashMap::new(),
+            restored_checkpoints: HashMap::new(),
             compaction_frontier: MutableAntichain::new_bottom(TimelyTimestamp::minimum()),
             durability_frontier: Antichain::from_elem(TimelyTimestamp::minimum()),
             proposer: TimestampProposer::new(timestamp_update_interval, now),
@@ -382,6 +392,55 @@ impl TimestampBindingBox {
         partition.add_binding(timestamp, offset);
     }
 
+    fn add_checkpoint(&mut self, partition: PartitionId, checkpoint: SourceCheckpoint) {
+        self.checkpoints
+            .entry(partition)
+            .or_default()
+            .insert(checkpoint.offset, checkpoint.upstream_position);
+    }
+
+    fn add_restored_checkpoint(&mut self, partition: PartitionId, checkpoint: SourceCheckpoint) {
+        self.restored_checkpoints.insert(partition, checkpoint);
+    }
+
+    fn take_checkpoints(
+        &mut self,
+        bindings: &[(PartitionId, Timestamp, MzOffset)],
+    ) -> Vec<(PartitionId, SourceCheckpoint)> {
+        let mut cursors: HashMap<&PartitionId, MzOffset> = HashMap::new();
+        for (pid, _, offset) in bindings {
+            let cursor = cursors.entry(pid).or_default();
+            *cursor = std::cmp::max(*cursor, *offset);
+        }
+
+        let mut ret = Vec::new();
+        for (pid, cursor) in cursors {
+            if let Some(positions) = self.checkpoints.get_mut(pid) {
+                // Binding offsets are cursors, so the latest message they cover is the last one
+                // strictly before the cursor.
+                if let Some((offset, upstream_position)) = positions.range(..cursor).next_back() {
+                    let offset = *offset;
+                    ret.push((
+                        pid.clone(),
+                        SourceCheckpoint {
+                            offset,
+                            upstream_position: upstream_position.clone(),
+                        },
+                    ));
+                    *positions = positions.split_off(&offset);
+                }
+            }
+        }
+        ret
+    }
+
+    fn restored_checkpoints(&self) -> Vec<(PartitionId, SourceCheckpoint)> {
+        self.restored_checkpoints
+            .iter()
+            .map(|(pid, checkpoint)| (pid.clone(), checkpoint.clone()))
+            .collect()
+    }
+
     fn downgrade(&self, cap: &mut Capability<Timestamp>, cursors: &HashMap<PartitionId, MzOffset>) {
         let mut ts = self.upper();
         for (pid, timestamps) in self.partitions.iter() {
@@ -527,6 +586,35 @@ impl TimestampBindingRc {
             .add_partition(partition, restored_offset);
     }
 
+    /// Record that reading `partition` can resume from `checkpoint` once all messages up to
+    /// and including its offset are durably timestamped.
+    pub fn add_checkpoint(&self, partition: PartitionId, checkpoint: SourceCheckpoint) {
+        self.wrapper
+            .borrow_mut()
+            .add_checkpoint(partition, checkpoint);
+    }
+
+    /// Tell source readers that they can resume reading `partition` from `checkpoint`.
+    pub fn add_restored_checkpoint(&self, partition: PartitionId, checkpoint: SourceCheckpoint) {
+        self.wrapper
+            .borrow_mut()
+            .add_restored_checkpoint(partition, checkpoint);
+    }
+
+    /// Returns the latest checkpoint of each partition that is covered by `bindings`, and
+    /// forgets about all earlier ones.
+    pub fn take_checkpoints(
+        &self,
+        bindings: &[(PartitionId, Timestamp, MzOffset)],
+    ) -> Vec<(PartitionId, SourceCheckpoint)> {
+        self.wrapper.borrow_mut().take_checkpoints(bindings)
+    }
+
+    /// Returns the checkpoints this source was restored with.
+    pub fn restored_checkpoints(&self) -> Vec<(PartitionId, SourceCheckpoint)> {
+        self.wrapper.borrow().restored_checkpoints()
+    }
+
     /// Get the timestamp assignment for `(partition, offset)` if it is known.
     ///
     /// This function returns the timestamp and the maximum offset for which it is
@@ -644,13 +732,40 @@ impl PartialOrd for SourceTimestamp {
                 self.offset.offset.cmp(&other.offset.offset)
             }
             (PartitionId::Kafka(a), PartitionId::Kafka(b)) => a.cmp(b),
+            (
+                PartitionId::KafkaTopic {
+                    topic: a_topic,
+                    partition: a,
+                },
+                PartitionId::KafkaTopic {
+                    topic: b_topic,
+                    partition: b,
+                },
+            ) => (a_topic, a)
+                .cmp(&(b_topic, b))
+                .then_with(|| self.offset.offset.cmp(&other.offset.offset)),
+            (PartitionId::Kinesis(a), PartitionId::Kinesis(b)) => a
+                .cmp(b)
+                .then_with(|| self.offset.offset.cmp(&other.offset.offset)),
             (PartitionId::None, PartitionId::None) => self.offset.offset.cmp(&other.offset.offset),
             // We're not using a wildcard pattern here, to make sure this fails when someone adds
             // new types of partition ID.
-            (PartitionId::None, PartitionId::Kafka(_)) => {
-                unreachable!("PartitionId types must match")
-            }
-            (PartitionId::Kafka(_), PartitionId::None) => {
+            (
+                PartitionId::None,
+                PartitionId::Kafka(_) | PartitionId::KafkaTopic { .. } | PartitionId::Kinesis(_),
+            )
+            | (
+                PartitionId::Kafka(_),
+                PartitionId::None | PartitionId::KafkaTopic { .. } | PartitionId::Kinesis(_),
+            )
+            | (
+                PartitionId::KafkaTopic { .. },
+                PartitionId::None | PartitionId::Kafka(_) | PartitionId::Kinesis(_),
+            )
+            | (
+                PartitionId::Kinesis(_),
+                PartitionId::None | PartitionId::Kafka(_) | PartitionId::KafkaTopic { .. },
+            ) => {
                 unreachable!("PartitionId types must match")
             }
         };
@@ -668,13 +783,40 @@ impl Ord for SourceTimestamp {
                 self.offset.offset.cmp(&other.offset.offset)
             }
             (PartitionId::Kafka(a), PartitionId::Kafka(b)) => a.cmp(b),
+            (
+                PartitionId::KafkaTopic {
+                    topic: a_topic,
+                    partition: a,
+                },
+                PartitionId::KafkaTopic {
+                    topic: b_topic,
+                    partition: b,
+                },
+            ) => (a_topic, a)
+                .cmp(&(b_topic, b))
+                .then_with(|| self.offset.offset.cmp(&other.offset.offset)),
+            (PartitionId::Kinesis(a), PartitionId::Kinesis(b)) => a
+                .cmp(b)
+                .then_with(|| self.offset.offset.cmp(&other.offset.offset)),
             (PartitionId::None, PartitionId::None) => self.offset.offset.cmp(&other.offset.offset),
             // We're not using a wildcard pattern here, to make sure this fails when someone adds
             // new types of partition ID.
-            (PartitionId::None, PartitionId::Kafka(_)) => {
-                unreachable!("PartitionId types must match")
-            }
-            (PartitionId::Kafka(_), PartitionId::None) => {
+            (
+                PartitionId::None,
+                PartitionId::Kafka(_) | PartitionId::KafkaTopic { .. } | PartitionId::Kinesis(_),
+            )
+            | (
+                PartitionId::Kafka(_),
+                PartitionId::None | PartitionId::KafkaTopic { .. } | PartitionId::Kinesis(_),
+            )
+            | (
+                PartitionId::KafkaTopic { .. },
+                PartitionId::None | PartitionId::Kafka(_) | PartitionId::Kinesis(_),
+            )
+            | (
+                PartitionId::Kinesis(_),
+                PartitionId::None | PartitionId::Kafka(_) | PartitionId::KafkaTopic { .. },
+            ) => {
                 unreachable!("PartitionId types must match")
             }
         };
@@ -691,6 +833,15 @@ impl From<&SourceTimestamp> for ProtoSourceTimestamp {
         ProtoSourceTimestamp {
             partition_id: Some(match &x.partition {
                 PartitionId::Kafka(x) => proto_source_timestamp::PartitionId::Kafka(*x),
+                PartitionId::KafkaTopic { topic, partition } => {
+                    proto_source_timestamp::PartitionId::KafkaTopic(ProtoKafkaTopicPartition {
+                        topic: topic.clone(),
+                        partition: *partition,
+                    })
+                }
+                PartitionId::Kinesis(shard) => {
+                    proto_source_timestamp::PartitionId::Kinesis(shard.clone())
+                }
                 PartitionId::None => proto_source_timestamp::PartitionId::None(()),
             }),
             mz_offset: x.offset.offset,
@@ -704,6 +855,11 @@ impl TryFrom<ProtoSourceTimestamp> for SourceTimestamp {
     fn try_from(x: ProtoSourceTimestamp) -> Result<Self, Self::Error> {
         let partition = match x.partition_id {
             Some(proto_source_timestamp::PartitionId::Kafka(x)) => PartitionId::Kafka(x),
+            Some(proto_source_timestamp::PartitionId::KafkaTopic(x)) => PartitionId::KafkaTopic {
+                topic: x.topic,
+                partition: x.partition,
+            },
+            Some(proto_source_timestamp::PartitionId::Kinesis(x)) => PartitionId::Kinesis(x),
             Some(proto_source_timestamp::PartitionId::None(_)) => PartitionId::None,
             None => return Err("unknown partition_id".into()),
         };
@@ -871,7 +1027,7 @@ impl TimestampBindingUpdater {
 
 #[cfg(test)]
 mod tests {
-    use mz_dataflow_types::sources::MzOffset;
+    use mz_dataflow_types::sources::{MzOffset, SourceCheckpoint};
     use mz_expr::PartitionId;
     use mz_persist_types::Codec;
 
@@ -1091,4 +1247,54 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn checkpoints_covered_by_bindings() {
+        let timestamp_histories = TimestampBindingRc::new(1000, (|| 50).into());
+        let pid = PartitionId::Kinesis("shardId-000000000000".into());
+        timestamp_histories.add_partition(pid.clone(), None);
+        for offset in 1..=5 {
+            timestamp_histories.add_checkpoint(
+                pid.clone(),
+                SourceCheckpoint {
+                    offset: MzOffset { offset },
+                    upstream_position: format!("seq-{}", offset),
+                },
+            );
+        }
+
+        // Bindings for messages with offsets below 4 only cover the checkpoint at offset 3.
+        let checkpoints = timestamp_histories.take_checkpoints(&[
+            (pid.clone(), 1000, MzOffset { offset: 2 }),
+            (pid.clone(), 2000, MzOffset { offset: 4 }),
+        ]);
+        assert_eq!(
+            checkpoints,
+            vec![(
+                pid.clone(),
+                SourceCheckpoint {
+                    offset: MzOffset { offset: 3 },
+                    upstream_position: "seq-3".into(),
+                }
+            )]
+        );
+
+        // Re-reported bindings never regress the checkpoint.
+        let checkpoints =
+            timestamp_histories.take_checkpoints(&[(pid.clone(), 1000, MzOffset { offset: 2 })]);
+        assert!(checkpoints.is_empty());
+
+        let checkpoints =
+            timestamp_histories.take_checkpoints(&[(pid.clone(), 3000, MzOffset { offset: 6 })]);
+        assert_eq!(
+            checkpoints,
+            vec![(
+                pid,
+                SourceCheckpoint {
+                    offset: MzOffset { offset: 5 },
+                    upstream_position: "seq-5".into(),
+                }
+            )]
+        );
+    }
 }
@@ -29,7 +29,7 @@ use mz_dataflow_types::client::{
     TimestampBindingFeedback,
 };
 use mz_dataflow_types::sources::AwsExternalId;
-use mz_dataflow_types::sources::{ExternalSourceConnector, SourceConnector};
+use mz_dataflow_types::sources::{DecodeErrorPolicy, ExternalSourceConnector, SourceConnector};
 use mz_expr::PartitionId;
 use mz_ore::now::NowFn;
 use mz_repr::{Diff, GlobalId, Row, Timestamp};
@@ -118,6 +118,16 @@ impl<'a, A: Allocate, B: StorageCapture> ActiveStorageState<'a, A, B> {
     /// Sets up the timestamp binding machinery if needed for this source
     fn setup_timestamp_binding_state(&mut self, source: &CreateSourceCommand<Timestamp>) {
         let ts_history = if let SourceConnector::External {
+            decode_errors: DecodeErrorPolicy::Emit { source: diverting },
+            ..
+        } = &source.desc.connector
+        {
+            debug!(
+                "Source {} is timestamped by the bindings of source {}. Ignoring",
+                source.id, diverting
+            );
+            None
+        } else if let SourceConnector::External {
             connector,
             ts_frequency,
             ..
@@ -128,15 +138,17 @@ impl<'a, A: Allocate, B: StorageCapture> ActiveStorageState<'a, A, B> {
                 self.storage_state.now.clone(),
             );
             match connector {
-                ExternalSourceConnector::Kinesis(_) | ExternalSourceConnector::S3(_) => {
+                ExternalSourceConnector::S3(_) => {
                     rt_default.add_partition(PartitionId::None, None);
                     Some(rt_default)
                 }
-                ExternalSourceConnector::PubNub(_) => {
+                ExternalSourceConnector::PubNub(_) | ExternalSourceConnector::LoadGenerator(_) => {
                     rt_default.add_partition(PartitionId::None, None);
                     Some(rt_default)
                 }
-                ExternalSourceConnector::Kafka(_) => Some(rt_default),
+                ExternalSourceConnector::Kafka(_) | ExternalSourceConnector::Kinesis(_) => {
+                    Some(rt_default)
+                }
                 ExternalSourceConnector::Postgres(_) | ExternalSourceConnector::Persist(_) => None,
             }
         } else {
@@ -176,11 +188,24 @@ impl<'a, A: Allocate, B: StorageCapture> ActiveStorageState<'a, A, B> {
                 }
             }
 
+            // Add any checkpoints that partitions can resume reading from.
+            for (pid, checkpoint) in source.checkpoints.iter().cloned() {
+                if crate::source::responsible_for(
+                    &source.id,
+                    self.timely_worker.index(),
+                    self.timely_worker.peers(),
+                    &pid,
+                ) {
+                    ts_history.add_restored_checkpoint(pid, checkpoint);
+                }
+            }
+
             self.storage_state
                 .ts_histories
                 .insert(source.id, ts_history);
         } else {
             assert!(source.ts_bindings.is_empty());
+            assert!(source.checkpoints.is_empty());
         }
     }
 
@@ -396,6 +421,7 @@ impl<'a, A: Allocate, B: StorageCapture> ActiveStorageState<'a, A, B> {
         }
         let mut changes = Vec::new();
         let mut bindings = Vec::new();
+        let mut checkpoints = Vec::new();
         let mut new_frontier = Antichain::new();
 
         // Need to go through all sources that are generating timestamp bindings, and extract their upper frontiers.
@@ -425,10 +451,14 @@ impl<'a, A: Allocate, B: StorageCapture> ActiveStorageState<'a, A, B> {
                     changes.push((*id, change_batch));
                 }
                 // Add all timestamp bindings we know about between the old and new frontier.
-                bindings.push((
-                    *id,
-                    history.get_bindings_in_range(prev_frontier.borrow(), new_frontier.borrow()),
-                ));
+                let new_bindings =
+                    history.get_bindings_in_range(prev_frontier.borrow(), new_frontier.borrow());
+                // Along with the latest checkpoints that those bindings cover.
+                let new_checkpoints = history.take_checkpoints(&new_bindings);
+                if !new_checkpoints.is_empty() {
+                    checkpoints.push((*id, new_checkpoints));
+                }
+                bindings.push((*id, new_bindings));
                 prev_frontier.clone_from(&new_frontier);
             }
         }
@@ -491,7 +521,11 @@ impl<'a, A: Allocate, B: StorageCapture> ActiveStorageState<'a, A, B> {
 
         if !changes.is_empty() || !bindings.is_empty() {
             self.send_storage_response(StorageResponse::TimestampBindings(
-                TimestampBindingFeedback { changes, bindings },
+                TimestampBindingFeedback {
+                    changes,
+                    bindings,
+                    checkpoints,
+                },
             ));
         }
         self.storage_state.last_bindings_feedback = Instant::now();
@@ -227,6 +227,45 @@ impl ColumnKnowledge {
 
                     Ok(knowledges)
                 }
+                MirRelationExpr::OuterJoin {
+                    left,
+                    right,
+                    left_key,
+                    right_key,
+                    kind,
+                } => {
+                    let left_knowledge = self.harvest(left, knowledge, knowledge_stack)?;
+                    let left_typ = left.typ();
+                    for expr in left_key.iter_mut() {
+                        optimize(expr, &left_typ, &left_knowledge[..], knowledge_stack);
+                    }
+                    let right_knowledge = self.harvest(right, knowledge, knowledge_stack)?;
+                    let right_typ = right.typ();
+                    for expr in right_key.iter_mut() {
+                        optimize(expr, &right_typ, &right_knowledge[..], knowledge_stack);
+                    }
+
+                    // Columns of an input whose counterpart rows may be padded
+                    // with nulls lose all knowledge. As with joins, error
+                    // literals are not propagated beyond the inputs.
+                    let weaken = |mut knowledge: DatumKnowledge, padded: bool| {
+                        if padded {
+                            knowledge = DatumKnowledge::default();
+                        } else if let Some((Err(_), _)) = knowledge.value {
+                            knowledge.value = None;
+                        }
+                        knowledge
+                    };
+                    Ok(left_knowledge
+                        .into_iter()
+                        .map(|k| weaken(k, kind.preserves_right()))
+                        .chain(
+                            right_knowledge
+                                .into_iter()
+                                .map(|k| weaken(k, kind.preserves_left())),
+                        )
+                        .collect())
+                }
                 MirRelationExpr::Reduce {
                     input,
                     group_key,
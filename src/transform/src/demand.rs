@@ -216,6 +216,33 @@ impl Demand {
 
                     Ok(())
                 }
+                MirRelationExpr::OuterJoin {
+                    left,
+                    right,
+                    left_key,
+                    right_key,
+                    kind: _,
+                } => {
+                    // The keys of both inputs determine which rows are matched.
+                    let left_arity = left.arity();
+                    let mut left_columns = left_key
+                        .iter()
+                        .flat_map(|e| e.support())
+                        .collect::<HashSet<_>>();
+                    let mut right_columns = right_key
+                        .iter()
+                        .flat_map(|e| e.support())
+                        .collect::<HashSet<_>>();
+                    for column in columns {
+                        if column < left_arity {
+                            left_columns.insert(column);
+                        } else {
+                            right_columns.insert(column - left_arity);
+                        }
+                    }
+                    self.action(left, left_columns, gets)?;
+                    self.action(right, right_columns, gets)
+                }
                 MirRelationExpr::Reduce {
                     input,
                     group_key,
@@ -23,7 +23,7 @@ use mz_expr::{JoinInputMapper, MapFilterProject, MirRelationExpr, MirScalarExpr,
 use mz_ore::stack::{CheckedRecursion, RecursionGuard};
 
 use self::index_map::IndexMap;
-use crate::TransformArgs;
+use crate::{CollectionStatistics, TransformArgs};
 
 /// Determines the join implementation for join operators.
 #[derive(Debug)]
@@ -121,15 +121,18 @@ impl JoinImplementation {
                 .map(|typ| typ.keys)
                 .collect::<Vec<_>>();
             let mut available_arrangements = vec![Vec::new(); inputs.len()];
+            let mut statistics = Vec::with_capacity(inputs.len());
             for index in 0..inputs.len() {
                 // We can work around mfps, as we can lift the mfps into the join execution.
                 let (mfp, input) = MapFilterProject::extract_non_errors_from_expr(&inputs[index]);
                 let (_, _, project) = mfp.as_map_filter_project();
                 // Get and ArrangeBy expressions contribute arrangements.
+                let mut input_statistics = None;
                 match input {
                     MirRelationExpr::Get { id, typ: _ } => {
                         available_arrangements[index]
                             .extend(indexes.get(*id).map(|key| key.to_vec()));
+                        input_statistics = indexes.statistics(*id);
                     }
                     MirRelationExpr::ArrangeBy { input, keys } => {
                         // We may use any presented arrangement keys.
@@ -137,6 +140,7 @@ impl JoinImplementation {
                         if let MirRelationExpr::Get { id, typ: _ } = &**input {
                             available_arrangements[index]
                                 .extend(indexes.get(*id).map(|key| key.to_vec()));
+                            input_statistics = indexes.statistics(*id);
                         }
                     }
                     MirRelationExpr::Reduce { group_key, .. } => {
@@ -165,6 +169,21 @@ impl JoinImplementation {
                         key.permute_map(&reverse_project);
                     }
                 }
+                // Treat statistics the same way. Any filters in the MFP can
+                // only reduce the number of records, so the row count remains
+                // a valid upper bound.
+                if let Some(input_statistics) = &mut input_statistics {
+                    input_statistics.distinct_keys.retain(|(key, _)| {
+                        key.iter()
+                            .all(|k| k.support().iter().all(|c| reverse_project.contains_key(c)))
+                    });
+                    for (key, _) in input_statistics.distinct_keys.iter_mut() {
+                        for k in key.iter_mut() {
+                            k.permute_map(&reverse_project);
+                        }
+                    }
+                }
+                statistics.push(input_statistics);
                 // Currently we only support using arrangements all of whose
                 // keys can be found in some equivalence.
                 // Note: because `order_input` currently only finds arrangements
@@ -181,6 +200,11 @@ impl JoinImplementation {
                 });
             }
 
+            // Statistics are only useful for comparing orders if they are
+            // available for every input.
+            let statistics = statistics.into_iter().collect::<Option<Vec<_>>>();
+            let statistics = statistics.as_deref();
+
             // Determine if we can perform delta queries with the existing arrangements.
             // We could defer the execution if we are sure we know we want one input,
             // but we could imagine wanting the best from each and then comparing the two.
@@ -189,12 +213,14 @@ impl JoinImplementation {
                 &input_mapper,
                 &available_arrangements,
                 &unique_keys,
+                statistics,
             );
             let differential_plan = differential::plan(
                 relation,
                 &input_mapper,
                 &available_arrangements,
                 &unique_keys,
+                statistics,
             );
 
             *relation = delta_query_plan
@@ -209,7 +235,7 @@ mod index_map {
 
     use mz_expr::{Id, LocalId, MirScalarExpr};
 
-    use crate::IndexOracle;
+    use crate::{CollectionStatistics, IndexOracle};
 
     /// Keeps track of local and global indexes available while descending
     /// a `MirRelationExpr`.
@@ -252,6 +278,16 @@ mod index_map {
                 ),
             }
         }
+
+        /// Returns statistics about the specified collection, if known.
+        ///
+        /// Statistics are never known for local collections.
+        pub fn statistics(&self, id: Id) -> Option<CollectionStatistics> {
+            match id {
+                Id::Global(id) => self.global.statistics(id),
+                Id::Local(_) => None,
+            }
+        }
     }
 }
 
@@ -259,6 +295,8 @@ mod delta_queries {
 
     use mz_expr::{JoinImplementation, JoinInputMapper, MirRelationExpr, MirScalarExpr};
 
+    use crate::CollectionStatistics;
+
     /// Creates a delta query plan, and any predicates that need to be lifted.
     ///
     /// The method returns `None` if it fails to find a sufficiently pleasing plan.
@@ -267,6 +305,7 @@ mod delta_queries {
         input_mapper: &JoinInputMapper,
         available: &[Vec<Vec<MirScalarExpr>>],
         unique_keys: &[Vec<Vec<usize>>],
+        statistics: Option<&[CollectionStatistics]>,
     ) -> Option<MirRelationExpr> {
         let mut new_join = join.clone();
 
@@ -286,7 +325,13 @@ mod delta_queries {
             }
 
             // Determine a viable order for each relation, or return `None` if none found.
-            let orders = super::optimize_orders(equivalences, available, unique_keys, input_mapper);
+            let orders = super::optimize_orders(
+                equivalences,
+                available,
+                unique_keys,
+                statistics,
+                input_mapper,
+            );
 
             // A viable delta query requires that, for every order,
             // there is an arrangement for every input except for
@@ -329,12 +374,15 @@ mod differential {
 
     use mz_expr::{JoinImplementation, JoinInputMapper, MirRelationExpr, MirScalarExpr};
 
+    use crate::CollectionStatistics;
+
     /// Creates a linear differential plan, and any predicates that need to be lifted.
     pub fn plan(
         join: &MirRelationExpr,
         input_mapper: &JoinInputMapper,
         available: &[Vec<Vec<MirScalarExpr>>],
         unique_keys: &[Vec<Vec<usize>>],
+        statistics: Option<&[CollectionStatistics]>,
     ) -> Option<MirRelationExpr> {
         let mut new_join = join.clone();
 
@@ -349,36 +397,44 @@ mod differential {
             // Important, we should choose something stable under re-ordering, to converge under fixed
             // point iteration; we choose to start with the first input optimizing our criteria, which
             // should remain stable even when promoted to the first position.
-            let mut orders =
-                super::optimize_orders(equivalences, available, unique_keys, input_mapper);
-
-            // For differential join, it is not as important for the starting
-            // input to have good characteristics because the other ones
-            // determine whether intermediate results blow up. Thus, we do not
-            // include the starting input when max-minning.
-            let max_min_characteristics = orders
-                .iter()
-                .flat_map(|order| order.iter().skip(1).map(|(c, _, _)| c.clone()).min())
-                .max();
-            let mut order = if let Some(max_min_characteristics) = max_min_characteristics {
+            let mut orders = super::optimize_orders(
+                equivalences,
+                available,
+                unique_keys,
+                statistics,
+                input_mapper,
+            );
+
+            let order = if let Some(statistics) = statistics {
+                // With statistics, choose the order with the lowest estimated
+                // cost, preferring earlier orders in the case of ties.
                 orders
                     .into_iter()
-                    .find(|o| {
+                    .min_by_key(|o| estimated_cost(o, statistics))?
+            } else {
+                // For differential join, it is not as important for the starting
+                // input to have good characteristics because the other ones
+                // determine whether intermediate results blow up. Thus, we do not
+                // include the starting input when max-minning.
+                let max_min_characteristics = orders
+                    .iter()
+                    .flat_map(|order| order.iter().skip(1).map(|(c, _, _)| c.clone()).min())
+                    .max();
+                if let Some(max_min_characteristics) = max_min_characteristics {
+                    orders.into_iter().find(|o| {
                         o.iter().skip(1).map(|(c, _, _)| c).min().unwrap()
                             == &max_min_characteristics
                     })?
-                    .into_iter()
-                    .map(|(_c, k, r)| (r, k))
-                    .collect::<Vec<_>>()
-            } else {
-                // if max_min_characteristics is None, then there must only be
-                // one input and thus only one order in orders
-                orders
-                    .remove(0)
-                    .into_iter()
-                    .map(|(_c, k, r)| (r, k))
-                    .collect::<Vec<_>>()
+                } else {
+                    // if max_min_characteristics is None, then there must only be
+                    // one input and thus only one order in orders
+                    orders.remove(0)
+                }
             };
+            let mut order = order
+                .into_iter()
+                .map(|(_c, k, r)| (r, k))
+                .collect::<Vec<_>>();
 
             let (start, start_keys) = &order[0];
             let start = *start;
@@ -412,6 +468,33 @@ mod differential {
             panic!("differential::plan call on non-join expression.")
         }
     }
+
+    /// Estimates the cost of a linear differential join that follows `order`.
+    ///
+    /// The cost is the number of records the join must maintain in new
+    /// arrangements: one for each input after the first that does not use
+    /// an existing arrangement, and one for each intermediate result other
+    /// than the final one. Input sizes come from update counts, which may
+    /// overestimate the number of records, so the cost is only used to
+    /// compare orders.
+    fn estimated_cost(
+        order: &[(super::Characteristics, Vec<MirScalarExpr>, usize)],
+        statistics: &[CollectionStatistics],
+    ) -> u64 {
+        let mut cost = 0u64;
+        // The fanout of the starting input is its number of records.
+        let mut records = order[0].0.fanout.0;
+        for (position, (characteristics, _, input)) in order.iter().enumerate().skip(1) {
+            if !characteristics.arranged {
+                cost = cost.saturating_add(statistics[*input].rows);
+            }
+            records = records.saturating_mul(characteristics.fanout.0);
+            if position + 1 < order.len() {
+                cost = cost.saturating_add(records);
+            }
+        }
+        cost
+    }
 }
 
 /// Modify `inputs` to ensure specified arrangements are available.
@@ -531,9 +614,16 @@ fn optimize_orders(
     equivalences: &[Vec<MirScalarExpr>],
     available: &[Vec<Vec<MirScalarExpr>>],
     unique_keys: &[Vec<Vec<usize>>],
+    statistics: Option<&[CollectionStatistics]>,
     input_mapper: &JoinInputMapper,
 ) -> Vec<Vec<(Characteristics, Vec<MirScalarExpr>, usize)>> {
-    let mut orderer = Orderer::new(equivalences, available, unique_keys, input_mapper);
+    let mut orderer = Orderer::new(
+        equivalences,
+        available,
+        unique_keys,
+        statistics,
+        input_mapper,
+    );
     (0..available.len())
         .map(move |i| orderer.optimize_order_for(i))
         .collect::<Vec<_>>()
@@ -546,10 +636,16 @@ fn optimize_orders(
 /// that concerns us greatly. Additionally the candidate may be unarranged, and we would
 /// prefer candidates that do not require additional memory. Finally, we prefer lower id
 /// collections in the interest of consistent tie-breaking.
+///
+/// When statistics are available for every input, the candidate also carries an estimate
+/// of the number of its records that match each record looked up by the key, which is a
+/// more direct measure of inflation than the key length.
 #[derive(Eq, PartialEq, Ord, PartialOrd, Debug, Clone)]
 pub struct Characteristics {
     // An excellent indication that record count will not increase.
     unique_key: bool,
+    // The estimated number of matching records per lookup, or zero without statistics.
+    fanout: std::cmp::Reverse<u64>,
     // A weaker signal that record count will not increase.
     key_length: usize,
     // Indicates that there will be no additional in-memory footprint.
@@ -559,9 +655,10 @@ pub struct Characteristics {
 }
 
 impl Characteristics {
-    fn new(unique_key: bool, key_length: usize, arranged: bool, input: usize) -> Self {
+    fn new(unique_key: bool, fanout: u64, key_length: usize, arranged: bool, input: usize) -> Self {
         Self {
             unique_key,
+            fanout: std::cmp::Reverse(fanout),
             key_length,
             arranged,
             input: std::cmp::Reverse(input),
@@ -574,6 +671,7 @@ struct Orderer<'a> {
     equivalences: &'a [Vec<MirScalarExpr>],
     arrangements: &'a [Vec<Vec<MirScalarExpr>>],
     unique_keys: &'a [Vec<Vec<usize>>],
+    statistics: Option<&'a [CollectionStatistics]>,
     input_mapper: &'a JoinInputMapper,
     reverse_equivalences: Vec<Vec<(usize, usize)>>,
     unique_arrangement: Vec<Vec<bool>>,
@@ -591,6 +689,7 @@ impl<'a> Orderer<'a> {
         equivalences: &'a [Vec<MirScalarExpr>],
         arrangements: &'a [Vec<Vec<MirScalarExpr>>],
         unique_keys: &'a [Vec<Vec<usize>>],
+        statistics: Option<&'a [CollectionStatistics]>,
         input_mapper: &'a JoinInputMapper,
     ) -> Self {
         let inputs = arrangements.len();
@@ -625,6 +724,7 @@ impl<'a> Orderer<'a> {
             equivalences,
             arrangements,
             unique_keys,
+            statistics,
             input_mapper,
             reverse_equivalences,
             unique_arrangement,
@@ -655,19 +755,20 @@ impl<'a> Orderer<'a> {
         // Introduce cross joins as a possibility.
         for input in 0..self.inputs {
             let is_unique = self.unique_keys[input].iter().any(|cols| cols.is_empty());
+            let fanout = self.fanout(input, &[], is_unique);
             if let Some(pos) = self.arrangements[input]
                 .iter()
                 .position(|key| key.is_empty())
             {
                 self.arrangement_active[input].push(pos);
                 self.priority_queue.push((
-                    Characteristics::new(is_unique, 0, true, input),
+                    Characteristics::new(is_unique, fanout, 0, true, input),
                     vec![],
                     input,
                 ));
             } else {
                 self.priority_queue.push((
-                    Characteristics::new(is_unique, 0, false, input),
+                    Characteristics::new(is_unique, fanout, 0, false, input),
                     vec![],
                     input,
                 ));
@@ -692,7 +793,13 @@ impl<'a> Orderer<'a> {
 
         // calculate characteristics of an arrangement, if any on the starting input
         // by default, there is no arrangement on the starting input
-        let mut start_tuple = (Characteristics::new(false, 0, false, start), vec![], start);
+        // the fanout of the starting input is the number of records it contains
+        let fanout = self.fanout(start, &[], false);
+        let mut start_tuple = (
+            Characteristics::new(false, fanout, 0, false, start),
+            vec![],
+            start,
+        );
         // use an arrangement if there exists one that lines up with the keys of
         // the second input
         if let Some((_, key, second)) = self.order.get(0) {
@@ -714,7 +821,13 @@ impl<'a> Orderer<'a> {
                 {
                     let is_unique = self.unique_arrangement[start][pos];
                     start_tuple = (
-                        Characteristics::new(is_unique, candidate_start_key.len(), true, start),
+                        Characteristics::new(
+                            is_unique,
+                            fanout,
+                            candidate_start_key.len(),
+                            true,
+                            start,
+                        ),
                         candidate_start_key,
                         start,
                     );
@@ -781,9 +894,11 @@ impl<'a> Orderer<'a> {
                                             self.arrangement_active[rel].push(pos);
                                             // TODO: This could be pre-computed, as it is independent of the order.
                                             let is_unique = self.unique_arrangement[rel][pos];
+                                            let fanout = self.fanout(rel, keys, is_unique);
                                             self.priority_queue.push((
                                                 Characteristics::new(
                                                     is_unique,
+                                                    fanout,
                                                     keys.len(),
                                                     true,
                                                     rel,
@@ -799,9 +914,11 @@ impl<'a> Orderer<'a> {
                                         self.bound[rel].contains(&MirScalarExpr::Column(*c))
                                     })
                                 });
+                                let fanout = self.fanout(rel, &self.bound[rel], is_unique);
                                 self.priority_queue.push((
                                     Characteristics::new(
                                         is_unique,
+                                        fanout,
                                         self.bound[rel].len(),
                                         false,
                                         rel,
@@ -816,4 +933,30 @@ impl<'a> Orderer<'a> {
             }
         }
     }
+
+    /// Estimates the number of records of `input` that match each record
+    /// looked up by `key`, or returns zero if statistics are not available.
+    ///
+    /// The estimate uses the most selective known distinct key count among
+    /// those keys whose expressions are all contained in `key`.
+    fn fanout(&self, input: usize, key: &[MirScalarExpr], is_unique: bool) -> u64 {
+        let statistics = match self.statistics {
+            Some(statistics) => &statistics[input],
+            None => return 0,
+        };
+        if is_unique {
+            return 1;
+        }
+        statistics
+            .distinct_keys
+            .iter()
+            .filter(|(distinct_key, _)| distinct_key.iter().all(|k| key.contains(k)))
+            .map(|(_, distinct)| {
+                let distinct = std::cmp::max(*distinct, 1);
+                statistics.rows.saturating_add(distinct - 1) / distinct
+            })
+            .min()
+            .unwrap_or(statistics.rows)
+            .max(1)
+    }
 }
@@ -120,6 +120,28 @@ pub trait IndexOracle: fmt::Debug {
     // better somehow? Making the entire optimizer generic over this iterator
     // type doesn't presently seem worthwhile.
     fn indexes_on(&self, id: GlobalId) -> Box<dyn Iterator<Item = &[MirScalarExpr]> + '_>;
+
+    /// Returns statistics about the contents of the identified collection.
+    ///
+    /// Statistics are estimates, and may be stale. Returns `None` if no
+    /// statistics are known for the collection, in which case optimizations
+    /// fall back to decisions based on the structure of the query alone.
+    fn statistics(&self, _id: GlobalId) -> Option<CollectionStatistics> {
+        None
+    }
+}
+
+/// Estimated statistics about the contents of a collection.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CollectionStatistics {
+    /// The number of records in the collection.
+    ///
+    /// This may overestimate the number of records, for example if it is
+    /// derived from unconsolidated updates.
+    pub rows: u64,
+    /// The number of distinct values of each listed key, where each key is
+    /// described by its list of key expressions.
+    pub distinct_keys: Vec<(Vec<MirScalarExpr>, u64)>,
 }
 
 /// An [`IndexOracle`] that knows about no indexes.
@@ -621,6 +621,18 @@ impl LiteralLifting {
                     }
                     Ok(literals)
                 }
+                MirRelationExpr::OuterJoin { left, right, .. } => {
+                    // Literals cannot be lifted out of an outer join, as
+                    // the rows it pads with nulls do not contain them.
+                    // Instead, apply the literals of each input to it.
+                    for input in [left, right] {
+                        let literals = self.action(input, gets)?;
+                        if !literals.is_empty() {
+                            **input = input.take_dangerous().map(literals);
+                        }
+                    }
+                    Ok(Vec::new())
+                }
                 MirRelationExpr::Negate { input } => {
                     // Literals can just be lifted out of negate.
                     self.action(input, gets)
@@ -98,6 +98,12 @@ impl MonotonicFlag {
                     }
                     monotonic
                 }
+                MirRelationExpr::OuterJoin { left, right, .. } => {
+                    // Null-extended rows are retracted once a match appears.
+                    self.apply(left, sources, locals)?;
+                    self.apply(right, sources, locals)?;
+                    false
+                }
                 MirRelationExpr::Constant { rows: Ok(rows), .. } => {
                     rows.iter().all(|(_, diff)| diff > &0)
                 }
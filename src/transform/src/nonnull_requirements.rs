@@ -202,6 +202,21 @@ impl NonNullRequirements {
                     }
                     Ok(())
                 }
+                MirRelationExpr::OuterJoin { left, right, .. } => {
+                    // Rows padded with nulls never satisfy the requirements
+                    // on the columns of the padded input. Removing rows from
+                    // an input can only produce more such rows, and so the
+                    // requirements can be pushed to both inputs.
+                    let left_arity = left.arity();
+                    let (left_columns, right_columns): (HashSet<_>, HashSet<_>) =
+                        columns.into_iter().partition(|c| *c < left_arity);
+                    self.action(left, left_columns, gets)?;
+                    self.action(
+                        right,
+                        right_columns.into_iter().map(|c| c - left_arity).collect(),
+                        gets,
+                    )
+                }
                 MirRelationExpr::Reduce {
                     input,
                     group_key,
@@ -435,6 +435,45 @@ impl PredicatePushdown {
                             *relation = inner.take_dangerous().filter(predicates).negate();
                             self.action(relation, get_predicates)?;
                         }
+                        MirRelationExpr::OuterJoin {
+                            left, right, kind, ..
+                        } => {
+                            // Predicates over the columns of an input whose rows are
+                            // never padded with nulls can be pushed to that input,
+                            // as each of its rows produces at least one output row.
+                            let left_arity = left.arity();
+                            let right_permutation = (0..left_arity + right.arity())
+                                .map(|c| c.saturating_sub(left_arity))
+                                .collect::<Vec<_>>();
+                            let mut left_pushdown = Vec::new();
+                            let mut right_pushdown = Vec::new();
+                            predicates.retain(|predicate| {
+                                let support = predicate.support();
+                                if !kind.preserves_right()
+                                    && support.iter().all(|c| *c < left_arity)
+                                {
+                                    left_pushdown.push(predicate.clone());
+                                    false
+                                } else if !kind.preserves_left()
+                                    && support.iter().all(|c| *c >= left_arity)
+                                {
+                                    let mut predicate = predicate.clone();
+                                    predicate.permute(&right_permutation);
+                                    right_pushdown.push(predicate);
+                                    false
+                                } else {
+                                    true
+                                }
+                            });
+                            if !left_pushdown.is_empty() {
+                                **left = left.take_dangerous().filter(left_pushdown);
+                            }
+                            if !right_pushdown.is_empty() {
+                                **right = right.take_dangerous().filter(right_pushdown);
+                            }
+                            self.action(left, get_predicates)?;
+                            self.action(right, get_predicates)?;
+                        }
                         x => {
                             x.try_visit_mut_children(|e| self.action(e, get_predicates))?;
                         }
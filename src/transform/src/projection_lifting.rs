@@ -205,6 +205,43 @@ impl ProjectionLifting {
                     }
                     Ok(())
                 }
+                MirRelationExpr::OuterJoin {
+                    left,
+                    right,
+                    left_key,
+                    right_key,
+                    kind: _,
+                } => {
+                    self.action(left, gets)?;
+                    self.action(right, gets)?;
+
+                    // Track the location of the projected columns in the un-projected join.
+                    let mut projection = Vec::new();
+                    let mut temp_arity = 0;
+
+                    for (join_input, key) in [(left, left_key), (right, right_key)] {
+                        if let MirRelationExpr::Project { input, outputs } = &mut **join_input {
+                            for expr in key.iter_mut() {
+                                expr.permute(outputs);
+                            }
+                            for output in outputs.iter() {
+                                projection.push(temp_arity + *output);
+                            }
+                            temp_arity += input.arity();
+                            **join_input = input.take_dangerous();
+                        } else {
+                            let arity = join_input.arity();
+                            projection.extend(temp_arity..(temp_arity + arity));
+                            temp_arity += arity;
+                        }
+                    }
+
+                    if projection.len() != temp_arity || (0..temp_arity).any(|i| projection[i] != i)
+                    {
+                        *relation = relation.take_dangerous().project(projection);
+                    }
+                    Ok(())
+                }
                 MirRelationExpr::Reduce {
                     input,
                     group_key,
@@ -145,6 +145,40 @@ impl ProjectionPushdown {
 
                 columns_to_pushdown.into_iter().collect()
             }
+            MirRelationExpr::OuterJoin {
+                left,
+                right,
+                left_key,
+                right_key,
+                kind: _,
+            } => {
+                let left_arity = left.arity();
+
+                // The keys impose internal demand for columns of each input.
+                let mut left_columns = BTreeSet::new();
+                let mut right_columns = BTreeSet::new();
+                for c in desired_projection.iter() {
+                    if *c < left_arity {
+                        left_columns.insert(*c);
+                    } else {
+                        right_columns.insert(*c - left_arity);
+                    }
+                }
+                left_columns.extend(left_key.iter().flat_map(|e| e.support()));
+                right_columns.extend(right_key.iter().flat_map(|e| e.support()));
+                let left_columns = left_columns.into_iter().collect::<Vec<_>>();
+                let right_columns = right_columns.into_iter().collect::<Vec<_>>();
+
+                self.action(left, &left_columns, gets);
+                self.action(right, &right_columns, gets);
+                reverse_permute(left_key.iter_mut(), left_columns.iter());
+                reverse_permute(right_key.iter_mut(), right_columns.iter());
+
+                left_columns
+                    .into_iter()
+                    .chain(right_columns.into_iter().map(|c| c + left_arity))
+                    .collect()
+            }
             MirRelationExpr::FlatMap { input, func, exprs } => {
                 let inner_arity = input.arity();
                 // A FlatMap which returns zero rows acts like a filter
@@ -387,6 +387,24 @@ impl FoldConstants {
                     *relation = input.take_dangerous();
                 }
             }
+            MirRelationExpr::OuterJoin {
+                left, right, kind, ..
+            } => {
+                if let Some(e) = [&**left, &**right].into_iter().find_map(|i| match i {
+                    MirRelationExpr::Constant { rows: Err(e), .. } => Some(e),
+                    _ => None,
+                }) {
+                    *relation = MirRelationExpr::Constant {
+                        rows: Err(e.clone()),
+                        typ: relation_type.clone(),
+                    };
+                } else if (left.is_empty() && !kind.preserves_right())
+                    || (right.is_empty() && !kind.preserves_left())
+                    || (left.is_empty() && right.is_empty())
+                {
+                    relation.take_safely();
+                }
+            }
         }
 
         // This transformation maintains the invariant that all constant nodes
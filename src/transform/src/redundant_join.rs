@@ -355,6 +355,14 @@ impl RedundantJoin {
                 }
 
                 MirRelationExpr::ArrangeBy { input, .. } => self.action(input, lets),
+
+                MirRelationExpr::OuterJoin { left, right, .. } => {
+                    // Outer joins invent null values, and we do not track
+                    // the provenance of their columns.
+                    self.action(left, lets)?;
+                    self.action(right, lets)?;
+                    Ok(Vec::new())
+                }
             }
         })
     }
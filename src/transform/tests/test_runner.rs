@@ -20,16 +20,17 @@ mod tests {
     use std::fmt::Write;
 
     use anyhow::{anyhow, Error};
+    use mz_expr::MirScalarExpr;
     use mz_expr::{Id, MirRelationExpr};
     use mz_expr_test_util::{
-        build_rel, generate_explanation, json_to_spec, MirRelationExprDeserializeContext,
-        TestCatalog, RTI,
+        build_rel, build_scalar, generate_explanation, json_to_spec,
+        MirRelationExprDeserializeContext, TestCatalog, RTI,
     };
     use mz_lowertest::{deserialize, tokenize};
     use mz_ore::str::separated;
     use mz_repr::GlobalId;
     use mz_transform::dataflow::{optimize_dataflow_demand_inner, optimize_dataflow_filters_inner};
-    use mz_transform::{EmptyIndexOracle, Optimizer, Transform, TransformArgs};
+    use mz_transform::{CollectionStatistics, IndexOracle, Optimizer, Transform, TransformArgs};
     use proc_macro2::TokenTree;
 
     // Global options
@@ -56,6 +57,64 @@ mod tests {
                 .collect::<Vec<_>>();
     }
 
+    /// An [`IndexOracle`] that knows about no indexes, but reports the
+    /// statistics supplied by `stats` directives.
+    #[derive(Debug)]
+    struct TestIndexOracle<'a> {
+        cat: &'a TestCatalog,
+        statistics: &'a HashMap<String, CollectionStatistics>,
+    }
+
+    impl IndexOracle for TestIndexOracle<'_> {
+        fn indexes_on(&self, _: GlobalId) -> Box<dyn Iterator<Item = &[MirScalarExpr]> + '_> {
+            Box::new(std::iter::empty())
+        }
+
+        fn statistics(&self, id: GlobalId) -> Option<CollectionStatistics> {
+            let name = self.cat.get_source_name(&id)?;
+            self.statistics.get(name).cloned()
+        }
+    }
+
+    /// Parses statistics of the form `<name> <rows> [<key>=<distinct> ...]`,
+    /// one collection per line, where `<key>` is a comma-separated list of
+    /// scalar expressions.
+    fn parse_statistics(
+        s: &str,
+        statistics: &mut HashMap<String, CollectionStatistics>,
+    ) -> Result<(), Error> {
+        for line in s.lines() {
+            let mut words = line.split_whitespace();
+            let name = match words.next() {
+                Some(name) => name,
+                None => continue,
+            };
+            let rows = words
+                .next()
+                .ok_or_else(|| anyhow!("missing row count for {}", name))?
+                .parse()?;
+            let mut distinct_keys = Vec::new();
+            for word in words {
+                let (key, distinct) = word
+                    .split_once('=')
+                    .ok_or_else(|| anyhow!("expected <key>=<distinct>, got {}", word))?;
+                let key = key
+                    .split(',')
+                    .map(|k| build_scalar(k).map_err(|e| anyhow!(e)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                distinct_keys.push((key, distinct.parse()?));
+            }
+            statistics.insert(
+                name.to_string(),
+                CollectionStatistics {
+                    rows,
+                    distinct_keys,
+                },
+            );
+        }
+        Ok(())
+    }
+
     #[derive(Debug, Copy, Clone, PartialEq, Eq)]
     enum FormatType<'a> {
         Explain(Option<&'a Vec<String>>),
@@ -119,6 +178,7 @@ mod tests {
     fn run_single_view_testcase(
         s: &str,
         cat: &TestCatalog,
+        indexes: &dyn IndexOracle,
         args: &HashMap<String, Vec<String>>,
         test_type: TestType,
     ) -> Result<String, Error> {
@@ -129,7 +189,7 @@ mod tests {
                 &mut rel,
                 TransformArgs {
                     id_gen: &mut id_gen,
-                    indexes: &EmptyIndexOracle,
+                    indexes,
                 },
             )?;
         }
@@ -143,7 +203,7 @@ mod tests {
                         &mut rel,
                         TransformArgs {
                             id_gen: &mut id_gen,
-                            indexes: &EmptyIndexOracle,
+                            indexes,
                         },
                     )?;
                 }
@@ -167,7 +227,7 @@ mod tests {
                             &mut rel,
                             TransformArgs {
                                 id_gen: &mut id_gen,
-                                indexes: &EmptyIndexOracle,
+                                indexes,
                             },
                         )?;
 
@@ -238,6 +298,9 @@ mod tests {
             })),
             "FlatMapToMap" => Ok(Box::new(mz_transform::fusion::flatmap_to_map::FlatMapToMap)),
             "JoinFusion" => Ok(Box::new(mz_transform::fusion::join::Join)),
+            "JoinImplementation" => Ok(Box::new(
+                mz_transform::join_implementation::JoinImplementation::default(),
+            )),
             "LiteralLifting" => Ok(Box::new(
                 mz_transform::map_lifting::LiteralLifting::default(),
             )),
@@ -420,15 +483,29 @@ mod tests {
     fn run() {
         datadriven::walk("tests/testdata", |f| {
             let mut catalog = TestCatalog::default();
+            let mut statistics = HashMap::new();
             f.run(move |s| -> String {
+                let indexes = TestIndexOracle {
+                    cat: &catalog,
+                    statistics: &statistics,
+                };
                 match s.directive.as_str() {
                     "cat" => match catalog.handle_test_command(&s.input) {
                         Ok(()) => String::from("ok\n"),
                         Err(err) => format!("error: {}\n", err),
                     },
+                    "stats" => match parse_statistics(&s.input, &mut statistics) {
+                        Ok(()) => String::from("ok\n"),
+                        Err(err) => format!("error: {}\n", err),
+                    },
                     "build" => {
-                        match run_single_view_testcase(&s.input, &catalog, &s.args, TestType::Build)
-                        {
+                        match run_single_view_testcase(
+                            &s.input,
+                            &catalog,
+                            &indexes,
+                            &s.args,
+                            TestType::Build,
+                        ) {
                             // Generally, explanations for fully optimized queries
                             // are not allowed to have whitespace at the end;
                             // however, a partially optimized query can.
@@ -444,14 +521,25 @@ mod tests {
                         }
                     }
                     "opt" => {
-                        match run_single_view_testcase(&s.input, &catalog, &s.args, TestType::Opt) {
+                        match run_single_view_testcase(
+                            &s.input,
+                            &catalog,
+                            &indexes,
+                            &s.args,
+                            TestType::Opt,
+                        ) {
                             Ok(msg) => msg,
                             Err(err) => format!("error: {}\n", err),
                         }
                     }
                     "steps" => {
-                        match run_single_view_testcase(&s.input, &catalog, &s.args, TestType::Steps)
-                        {
+                        match run_single_view_testcase(
+                            &s.input,
+                            &catalog,
+                            &indexes,
+                            &s.args,
+                            TestType::Steps,
+                        ) {
                             Ok(msg) => msg,
                             Err(err) => format!("error: {}\n", err),
                         }
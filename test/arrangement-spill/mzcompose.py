@@ -0,0 +1,34 @@
+# Copyright Materialize, Inc. and contributors. All rights reserved.
+#
+# Use of this software is governed by the Business Source License
+# included in the LICENSE file at the root of this repository.
+#
+# As of the Change Date specified in that file, in accordance with
+# the Business Source License, use of this software will be governed
+# by the Apache License, Version 2.0.
+
+import json
+
+from materialize.mzcompose import Composition
+from materialize.mzcompose.services import Materialized, Testdrive
+
+# The `spill` size holds only a small fraction of the test's arrangements in
+# memory, so that most of their batches are spilled to disk.
+REPLICA_SIZES = {
+    "1": {"scale": 1, "workers": 1},
+    "spill": {"scale": 1, "workers": 1, "arrangement_memory_budget": "1MiB"},
+}
+
+SERVICES = [
+    Materialized(
+        environment_extra=[f"MZ_CLUSTER_REPLICA_SIZES={json.dumps(REPLICA_SIZES)}"]
+    ),
+    Testdrive(),
+]
+
+
+def workflow_default(c: Composition) -> None:
+    """Run joins whose arrangements exceed the replica's memory budget."""
+    c.up("materialized")
+    c.wait_for_materialized()
+    c.run("testdrive", "*.td")
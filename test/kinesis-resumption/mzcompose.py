@@ -0,0 +1,37 @@
+# Copyright Materialize, Inc. and contributors. All rights reserved.
+#
+# Use of this software is governed by the Business Source License
+# included in the LICENSE file at the root of this repository.
+#
+# As of the Change Date specified in that file, in accordance with
+# the Business Source License, use of this software will be governed
+# by the Apache License, Version 2.0.
+
+import random
+
+from materialize.mzcompose import Composition
+from materialize.mzcompose.services import Localstack, Materialized, Testdrive
+
+SERVICES = [
+    Localstack(),
+    Materialized(),
+    Testdrive(no_reset=True, default_timeout="120s"),
+]
+
+#
+# Test that Kinesis sources recover every record exactly once after a restart,
+# including across a resharding of the stream.
+#
+def workflow_default(c: Composition) -> None:
+    c.start_and_wait_for_tcp(services=["localstack", "materialized"])
+    c.wait_for_materialized()
+
+    seed = random.getrandbits(16)
+    c.run("testdrive", f"--seed={seed}", "setup.td")
+
+    c.kill("materialized")
+    c.run("testdrive", f"--seed={seed}", "during.td")
+
+    c.up("materialized")
+    c.wait_for_materialized()
+    c.run("testdrive", f"--seed={seed}", "verify.td")
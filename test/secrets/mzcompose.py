@@ -16,6 +16,13 @@ SERVICES = [
 ]
 
 
+def checksum(c: Composition, path: str) -> str:
+    """Returns the SHA-256 checksum of the files matching `path`."""
+    return c.exec(
+        "materialized", "bash", "-c", f"cat {path} | sha256sum", capture=True
+    ).stdout
+
+
 def workflow_default(c: Composition) -> None:
     c.start_and_wait_for_tcp(services=["materialized"])
     c.wait_for_materialized("materialized")
@@ -28,13 +35,28 @@ def workflow_default(c: Composition) -> None:
         "[[ `stat -c \"%a\" /share/mzdata/secrets` == '700' ]] && exit 0 || exit 1",
     )
 
+    # ensure that the master key has restricted permissions
+    c.exec(
+        "materialized",
+        "bash",
+        "-c",
+        "[[ `stat -c \"%a\" /share/mzdata/secrets.key` == '600' ]] && exit 0 || exit 1",
+    )
+
     c.sql("CREATE SECRET secret AS 's3cret'")
-    # Check that the contents of the secret have made it to the storage
+    # Check that exactly one secret has made it to the storage, and that its
+    # contents are encrypted
+    c.exec(
+        "materialized",
+        "bash",
+        "-c",
+        "[[ `ls -A /share/mzdata/secrets | wc -l` == '1' ]] && exit 0 || exit 1",
+    )
     c.exec(
         "materialized",
         "bash",
         "-c",
-        "[[ `cat /share/mzdata/secrets/*` == 's3cret' ]] && exit 0 || exit 1",
+        "grep -q s3cret /share/mzdata/secrets/* && exit 1 || exit 0",
     )
 
     # Check that the file permissions are restrictive
@@ -46,12 +68,15 @@ def workflow_default(c: Composition) -> None:
     )
 
     # Check that alter secret gets reflected on disk
+    before = checksum(c, "/share/mzdata/secrets/*")
     c.sql("ALTER SECRET secret AS 'tops3cret'")
+    after = checksum(c, "/share/mzdata/secrets/*")
+    assert before != after
     c.exec(
         "materialized",
         "bash",
         "-c",
-        "[[ `cat /share/mzdata/secrets/*` == 'tops3cret' ]] && exit 0 || exit 1",
+        "grep -q tops3cret /share/mzdata/secrets/* && exit 1 || exit 0",
     )
 
     # check that replacing the file did not change permissions
@@ -64,14 +89,19 @@ def workflow_default(c: Composition) -> None:
 
     # Rename should not change the contents on disk
     c.sql("ALTER SECRET secret RENAME TO renamed_secret")
+    renamed = checksum(c, "/share/mzdata/secrets/*")
+    assert renamed == after
 
-    # Check that the contents of the secret have made it to the storage
-    c.exec(
-        "materialized",
-        "bash",
-        "-c",
-        "[[ `cat /share/mzdata/secrets/*` == 'tops3cret' ]] && exit 0 || exit 1",
-    )
+    # Rotating the master key should replace the key and re-encrypt the secret
+    key = checksum(c, "/share/mzdata/secrets.key")
+    c.kill("materialized")
+    with c.override(Materialized(options="--secrets-rotate-master-key")):
+        c.up("materialized")
+        c.wait_for_materialized("materialized")
+    rotated_key = checksum(c, "/share/mzdata/secrets.key")
+    assert rotated_key != key
+    rotated = checksum(c, "/share/mzdata/secrets/*")
+    assert rotated != renamed
 
     c.sql("DROP SECRET renamed_secret")
     # Check that the file has been deleted from the storage